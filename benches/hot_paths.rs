@@ -0,0 +1,100 @@
+//! Benchmarks for the per-transaction hot path: parsing and the price and
+//! state math applied to every trade. Run with
+//! `cargo bench --features bench --bench hot_paths`; arguments after `--`
+//! go to criterion (a name filter, `--save-baseline`, `--baseline`).
+
+use std::hint::black_box;
+use chrono::Utc;
+use criterion::{criterion_group, criterion_main, Criterion};
+use pumpfun_indexer::helius::parser::{self, PumpEvent};
+use pumpfun_indexer::processor::{calculator, momentum::TradeWindow, state};
+use solana_transaction_status::EncodedTransactionWithStatusMeta;
+
+/// Name, transaction and the number of events it holds.
+const FIXTURES: &[(&str, &str, usize)] = &[
+    ("buy", include_str!("fixtures/buy.json"), 1),
+    ("create_and_buy", include_str!("fixtures/create_and_buy.json"), 2),
+    ("unrelated", include_str!("fixtures/unrelated.json"), 0),
+];
+
+fn fixture(raw: &str) -> EncodedTransactionWithStatusMeta {
+    serde_json::from_str(raw).expect("fixture isn't a transaction")
+}
+
+fn bench_parser(c: &mut Criterion) {
+    for (name, raw, events) in FIXTURES {
+        let transaction = fixture(raw);
+        let parsed = parser::parse_transaction("bench", &transaction).expect("fixture doesn't parse");
+        assert_eq!(parsed.len(), *events, "fixture {} decodes to the wrong events", name);
+        c.bench_function(&format!("parse_transaction/{}", name), |b| {
+            b.iter(|| parser::parse_transaction("bench", &transaction).expect("fixture doesn't parse"))
+        });
+    }
+
+    let transaction = fixture(FIXTURES[0].1);
+    c.bench_function("invokes_indexed_program", |b| b.iter(|| parser::invokes_indexed_program(&transaction)));
+}
+
+fn bench_calculator(c: &mut Criterion) {
+    c.bench_function("calculate_price_sol", |b| {
+        b.iter(|| calculator::calculate_price_sol(black_box(31_000_000_000), black_box(1_038_387_096_774_194), 6))
+    });
+    c.bench_function("calculate_trade_impact", |b| {
+        b.iter(|| {
+            calculator::calculate_trade_impact(
+                black_box(1_000_000_000),
+                black_box(34_612_903_225_806),
+                true,
+                black_box(31_000_000_000),
+                black_box(1_038_387_096_774_194),
+                6,
+            )
+        })
+    });
+}
+
+fn bench_state(c: &mut Criterion) {
+    let now = Utc::now().timestamp();
+    let mut window = TradeWindow::default();
+    // One trade per bucket across the whole 15 minute window.
+    for seconds_ago in (0..900).step_by(10) {
+        window.record(now - seconds_ago, seconds_ago % 20 == 0, 500_000_000);
+    }
+    c.bench_function("trade_window/record", |b| {
+        b.iter(|| {
+            let mut window = window.clone();
+            window.record(black_box(now), true, 1_000_000_000);
+            window
+        })
+    });
+    c.bench_function("trade_window/momentum", |b| b.iter(|| window.momentum(black_box(now))));
+
+    let Some(PumpEvent::Trade(trade)) = parser::parse_transaction("bench", &fixture(FIXTURES[0].1))
+        .expect("fixture doesn't parse")
+        .into_iter()
+        .next()
+    else {
+        panic!("buy fixture has no trade");
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread().build().expect("runtime");
+    let state_map = state::create_state_map();
+    runtime.block_on(state::init_token_state(
+        &state_map,
+        trade.mint.clone(),
+        "Bench Token".to_string(),
+        "BENCH".to_string(),
+        trade.creator.clone(),
+        30_000_000_000,
+        1_073_000_000_000_000,
+        793_100_000_000_000,
+        1_000_000_000_000_000,
+        6,
+        150.0,
+    ));
+    c.bench_function("state/apply_trade", |b| {
+        b.iter(|| runtime.block_on(state::apply_trade(&state_map, &trade, 1, 150.0)))
+    });
+}
+
+criterion_group!(benches, bench_parser, bench_calculator, bench_state);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // The gRPC service glue is generated from Rust definitions, so building
+    // doesn't need `protoc`. Messages are hand-written in `src/api/grpc.rs`
+    // and must match `proto/pump/v1/pump.proto`.
+    #[cfg(feature = "grpc")]
+    grpc_service();
+}
+
+#[cfg(feature = "grpc")]
+fn grpc_service() {
+    use tonic_build::manual::{Builder, Method, Service};
+
+    let codec = "tonic_prost::ProstCodec";
+    let service = Service::builder()
+        .name("PumpIndexer")
+        .package("pump.v1")
+        .method(
+            Method::builder()
+                .name("stream_trades")
+                .route_name("StreamTrades")
+                .input_type("crate::api::grpc::proto::StreamTradesRequest")
+                .output_type("crate::api::grpc::proto::Trade")
+                .codec_path(codec)
+                .server_streaming()
+                .build(),
+        )
+        .method(
+            Method::builder()
+                .name("stream_new_tokens")
+                .route_name("StreamNewTokens")
+                .input_type("crate::api::grpc::proto::StreamNewTokensRequest")
+                .output_type("crate::api::grpc::proto::NewToken")
+                .codec_path(codec)
+                .server_streaming()
+                .build(),
+        )
+        .method(
+            Method::builder()
+                .name("get_token")
+                .route_name("GetToken")
+                .input_type("crate::api::grpc::proto::GetTokenRequest")
+                .output_type("crate::api::grpc::proto::Token")
+                .codec_path(codec)
+                .build(),
+        )
+        .build();
+
+    Builder::new().compile(&[service]);
+}
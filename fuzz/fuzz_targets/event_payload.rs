@@ -0,0 +1,58 @@
+//! Every parser's `decode` on arbitrary payloads, including ones shorter
+//! than a discriminator. PumpSwap events are given a transaction that swaps
+//! on the pool they name, so decoding gets past the pool lookup.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pumpfun_indexer::helius::parser::{ParseContext, PARSERS};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::EncodedTransactionWithStatusMeta;
+
+const PUMP_AMM_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+/// Offset of `pool` in a PumpSwap event: discriminator plus 14 u64 fields.
+const SWAP_POOL_OFFSET: usize = 8 + 14 * 8;
+const POOL: [u8; 32] = [7; 32];
+
+fuzz_target!(|data: &[u8]| {
+    let pool = Pubkey::new_from_array(POOL).to_string();
+    let transaction: EncodedTransactionWithStatusMeta = serde_json::from_value(serde_json::json!({
+        "transaction": {
+            "signatures": ["fuzz"],
+            "message": {
+                "header": { "numRequiredSignatures": 1, "numReadonlySignedAccounts": 0, "numReadonlyUnsignedAccounts": 0 },
+                "accountKeys": [pool, "user", "config", "mint", WSOL_MINT, PUMP_AMM_PROGRAM_ID],
+                "recentBlockhash": "11111111111111111111111111111111",
+                "instructions": [],
+            },
+        },
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            // Swap instructions are read from the inner instructions of raw messages.
+            "innerInstructions": [{
+                "index": 0,
+                "instructions": [{ "programIdIndex": 5, "accounts": [0, 1, 2, 3, 4], "data": "" }],
+            }],
+        },
+    }))
+    .unwrap();
+    let context = ParseContext { signature: "fuzz", transaction: &transaction };
+
+    for parser in PARSERS {
+        parser.decode(data, &context);
+
+        for discriminator in parser.discriminators() {
+            let mut payload = discriminator.to_vec();
+            payload.extend_from_slice(data);
+            if parser.program_id() == PUMP_AMM_PROGRAM_ID && payload.len() >= SWAP_POOL_OFFSET + 32 {
+                payload[SWAP_POOL_OFFSET..SWAP_POOL_OFFSET + 32].copy_from_slice(&POOL);
+            }
+            parser.decode(&payload, &context);
+        }
+    }
+});
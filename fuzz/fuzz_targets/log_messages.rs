@@ -0,0 +1,31 @@
+//! Arbitrary log lines, one per input line: the invoke-stack attribution
+//! and base58/base64 sniffing that run before any event is decoded.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pumpfun_indexer::helius::parser;
+use solana_transaction_status::EncodedTransactionWithStatusMeta;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let logs: Vec<&str> = text.lines().collect();
+    let transaction: EncodedTransactionWithStatusMeta = serde_json::from_value(serde_json::json!({
+        "transaction": ["", "base64"],
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "logMessages": logs,
+        },
+    }))
+    .unwrap();
+
+    parser::invokes_indexed_program(&transaction);
+    let _ = parser::parse_transaction("fuzz", &transaction);
+    parser::parse_creator_fee_claims(&transaction);
+});
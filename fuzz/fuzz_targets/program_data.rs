@@ -0,0 +1,46 @@
+//! Arbitrary bytes logged as `Program data:` by each indexed program, as a
+//! buggy or hostile program version could, through the full parse path.
+
+#![no_main]
+
+use base64::Engine;
+use libfuzzer_sys::fuzz_target;
+use pumpfun_indexer::helius::parser::{self, PARSERS};
+use solana_transaction_status::EncodedTransactionWithStatusMeta;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, rest)) = data.split_first() else {
+        return;
+    };
+    // Random bytes almost never start with a known discriminator, so the
+    // first byte picks one to put in front of the rest.
+    let discriminators: Vec<[u8; 8]> = PARSERS.iter().flat_map(|p| p.discriminators()).copied().collect();
+    let mut payload = discriminators[selector as usize % discriminators.len()].to_vec();
+    payload.extend_from_slice(rest);
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&payload);
+
+    let logs: Vec<String> = PARSERS
+        .iter()
+        .flat_map(|p| [
+            format!("Program {} invoke [1]", p.program_id()),
+            format!("Program data: {}", encoded),
+            format!("Program {} success", p.program_id()),
+        ])
+        .collect();
+    let transaction: EncodedTransactionWithStatusMeta = serde_json::from_value(serde_json::json!({
+        "transaction": ["", "base64"],
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "logMessages": logs,
+        },
+    }))
+    .unwrap();
+
+    let _ = parser::parse_transaction("fuzz", &transaction);
+    parser::contains_create_event(&transaction);
+    parser::parse_creator_fee_claims(&transaction);
+});
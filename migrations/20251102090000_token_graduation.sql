@@ -0,0 +1,13 @@
+-- Graduation tracking for completed bonding curves
+ALTER TABLE tokens ADD COLUMN IF NOT EXISTS graduated_at TIMESTAMPTZ;
+ALTER TABLE tokens ADD COLUMN IF NOT EXISTS amm_pool VARCHAR(44);
+
+-- Best-effort graduation time for tokens completed before this column existed
+UPDATE tokens t
+SET graduated_at = COALESCE(
+    (SELECT MAX(timestamp) FROM trades WHERE token_mint = t.mint_address),
+    t.updated_at
+)
+WHERE t.complete = TRUE AND t.graduated_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_tokens_graduated_at ON tokens(graduated_at DESC) WHERE complete = TRUE;
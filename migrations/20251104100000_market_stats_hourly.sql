@@ -0,0 +1,12 @@
+-- Hourly market-wide aggregates, maintained by the background aggregator
+CREATE TABLE IF NOT EXISTS market_stats_hourly (
+    bucket TIMESTAMPTZ PRIMARY KEY,
+    tokens_created BIGINT NOT NULL DEFAULT 0,
+    trades BIGINT NOT NULL DEFAULT 0,
+    volume_lamports NUMERIC(30, 0) NOT NULL DEFAULT 0,
+    graduations BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- The aggregator scans trades by time across all tokens
+CREATE INDEX IF NOT EXISTS idx_trades_timestamp ON trades(timestamp DESC);
@@ -0,0 +1,13 @@
+-- Objects uploaded by the archiver; also its cursor for what's left to archive
+CREATE TABLE IF NOT EXISTS archive_manifest (
+    id BIGSERIAL PRIMARY KEY,
+    kind VARCHAR(20) NOT NULL,              -- 'transactions' (hourly NDJSON) or 'trades' (daily Parquet)
+    object_key TEXT NOT NULL UNIQUE,
+    period_start TIMESTAMPTZ NOT NULL,
+    period_end TIMESTAMPTZ NOT NULL,
+    row_count BIGINT NOT NULL,
+    bytes BIGINT NOT NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_archive_manifest_kind_period ON archive_manifest(kind, period_end DESC);
@@ -0,0 +1,5 @@
+-- Composite indexes behind the is_buy / wallet / min_sol filters on
+-- GET /api/tokens/{mint}/trades; time ranges use idx_trades_token_timestamp
+CREATE INDEX IF NOT EXISTS idx_trades_token_side_timestamp ON trades(token_mint, is_buy, timestamp DESC);
+CREATE INDEX IF NOT EXISTS idx_trades_token_wallet_timestamp ON trades(token_mint, user_wallet, timestamp DESC);
+CREATE INDEX IF NOT EXISTS idx_trades_token_sol_amount ON trades(token_mint, sol_amount DESC);
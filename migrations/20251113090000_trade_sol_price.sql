@@ -0,0 +1,3 @@
+-- SOL/USD price when the trade was indexed, so USD values don't drift with
+-- the current price. NULL for trades loaded by the backfill binary.
+ALTER TABLE trades ADD COLUMN IF NOT EXISTS sol_price_usd DECIMAL(12, 4);
@@ -0,0 +1,17 @@
+-- USD volume and distinct traders per bucket, plus a daily table: unique
+-- wallets can't be summed from hourly buckets
+ALTER TABLE market_stats_hourly
+    ADD COLUMN IF NOT EXISTS volume_usd NUMERIC(20, 2) NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS unique_wallets BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS market_stats_daily (
+    bucket TIMESTAMPTZ PRIMARY KEY,
+    tokens_created BIGINT NOT NULL DEFAULT 0,
+    trades BIGINT NOT NULL DEFAULT 0,
+    volume_lamports NUMERIC(30, 0) NOT NULL DEFAULT 0,
+    volume_usd NUMERIC(20, 2) NOT NULL DEFAULT 0,
+    graduations BIGINT NOT NULL DEFAULT 0,
+    unique_wallets BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
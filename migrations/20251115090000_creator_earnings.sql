@@ -0,0 +1,22 @@
+-- Creator fee withdrawals (CollectCreatorFeeEvent); not tied to a token
+CREATE TABLE IF NOT EXISTS creator_fee_claims (
+    signature VARCHAR(88) NOT NULL,
+    event_index SMALLINT NOT NULL,
+    creator VARCHAR(44) NOT NULL,
+    lamports BIGINT NOT NULL,
+    claimed_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_creator_fee_claims_creator ON creator_fee_claims(creator, claimed_at DESC);
+
+-- Creator fees accrued per token per UTC day, maintained by the background aggregator
+CREATE TABLE IF NOT EXISTS creator_earnings_daily (
+    creator VARCHAR(44) NOT NULL,
+    token_mint VARCHAR(44) NOT NULL,
+    day TIMESTAMPTZ NOT NULL,
+    accrued_lamports NUMERIC(30, 0) NOT NULL DEFAULT 0,
+    trades BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    PRIMARY KEY (creator, day, token_mint)
+);
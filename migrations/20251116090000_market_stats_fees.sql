@@ -0,0 +1,8 @@
+-- Protocol and creator fees per bucket, for GET /api/stats/fees
+ALTER TABLE market_stats_hourly
+    ADD COLUMN IF NOT EXISTS protocol_fee_lamports NUMERIC(30, 0) NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS creator_fee_lamports NUMERIC(30, 0) NOT NULL DEFAULT 0;
+
+ALTER TABLE market_stats_daily
+    ADD COLUMN IF NOT EXISTS protocol_fee_lamports NUMERIC(30, 0) NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS creator_fee_lamports NUMERIC(30, 0) NOT NULL DEFAULT 0;
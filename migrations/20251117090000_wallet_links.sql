@@ -0,0 +1,23 @@
+-- Relationships between wallets, for sybil/rug analysis:
+--   early_buyer: source created token_mint, target bought within its first seconds
+--   same_slot:   both bought token_mint in one slot of its early window (source < target)
+--   funded:      source signed a token_mint transaction that moved SOL to target
+CREATE TABLE IF NOT EXISTS wallet_links (
+    source_wallet VARCHAR(44) NOT NULL,
+    target_wallet VARCHAR(44) NOT NULL,
+    relation VARCHAR(20) NOT NULL,
+    token_mint VARCHAR(44) NOT NULL,
+    occurrences BIGINT NOT NULL DEFAULT 0,
+    lamports NUMERIC(30, 0) NOT NULL DEFAULT 0,
+    first_seen TIMESTAMPTZ NOT NULL,
+    last_seen TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (source_wallet, target_wallet, relation, token_mint)
+);
+
+CREATE INDEX IF NOT EXISTS idx_wallet_links_target ON wallet_links(target_wallet);
+
+-- Trades are linked exactly once: everything before processed_until is done
+CREATE TABLE IF NOT EXISTS wallet_link_cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    processed_until TIMESTAMPTZ NOT NULL
+);
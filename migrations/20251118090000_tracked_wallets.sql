@@ -0,0 +1,6 @@
+-- Wallets whose trades are re-published on `pump:tracked`, managed via /api/admin/tracked-wallets
+CREATE TABLE IF NOT EXISTS tracked_wallets (
+    wallet VARCHAR(44) PRIMARY KEY,
+    label TEXT,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
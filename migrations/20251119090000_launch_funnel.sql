@@ -0,0 +1,38 @@
+-- Furthest each token got along its bonding curve, maintained by the funnel
+-- aggregator. Progress here is real SOL deposited out of 85 SOL, so every
+-- launch starts at 0%; placeholder tokens (no CreateEvent seen) are skipped.
+CREATE TABLE IF NOT EXISTS token_funnel (
+    mint_address VARCHAR(44) PRIMARY KEY,
+    creator_wallet VARCHAR(44) NOT NULL,
+    cohort_day TIMESTAMPTZ NOT NULL,
+    max_progress DOUBLE PRECISION NOT NULL DEFAULT 0,
+    reached_10_at TIMESTAMPTZ,
+    reached_50_at TIMESTAMPTZ,
+    graduated_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_funnel_updated ON token_funnel(updated_at);
+
+-- Launch cohorts per UTC creation day, overall and per creator
+CREATE TABLE IF NOT EXISTS funnel_daily (
+    day TIMESTAMPTZ PRIMARY KEY,
+    created BIGINT NOT NULL DEFAULT 0,
+    reached_10 BIGINT NOT NULL DEFAULT 0,
+    reached_50 BIGINT NOT NULL DEFAULT 0,
+    graduated BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS funnel_creator_daily (
+    creator_wallet VARCHAR(44) NOT NULL,
+    day TIMESTAMPTZ NOT NULL,
+    created BIGINT NOT NULL DEFAULT 0,
+    reached_10 BIGINT NOT NULL DEFAULT 0,
+    reached_50 BIGINT NOT NULL DEFAULT 0,
+    graduated BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    PRIMARY KEY (creator_wallet, day)
+);
+
+CREATE INDEX IF NOT EXISTS idx_funnel_creator_daily_day ON funnel_creator_daily(day);
@@ -0,0 +1,23 @@
+-- Spot price before and after each trade, its price impact and the
+-- execution price's slippage from the pre-trade price
+ALTER TABLE trades
+    ADD COLUMN IF NOT EXISTS price_before_sol DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS price_after_sol DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS price_impact_pct DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS slippage_pct DOUBLE PRECISION;
+
+-- Trade events carry post-trade reserves; undo the trade for the price before
+UPDATE trades t
+SET price_before_sol = p.before,
+    price_after_sol = p.after,
+    price_impact_pct = (p.after - p.before) / NULLIF(p.before, 0) * 100,
+    slippage_pct = ABS(p.execution - p.before) / NULLIF(p.before, 0) * 100
+FROM (
+    SELECT signature,
+           (virtual_sol_reserves - CASE WHEN is_buy THEN sol_amount ELSE -sol_amount END)::FLOAT8 / 1e9
+               / NULLIF((virtual_token_reserves + CASE WHEN is_buy THEN token_amount ELSE -token_amount END)::FLOAT8 / 1e6, 0) AS before,
+           virtual_sol_reserves::FLOAT8 / 1e9 / NULLIF(virtual_token_reserves::FLOAT8 / 1e6, 0) AS after,
+           sol_amount::FLOAT8 / 1e9 / NULLIF(token_amount::FLOAT8 / 1e6, 0) AS execution
+    FROM trades
+) p
+WHERE p.signature = t.signature AND t.price_impact_pct IS NULL;
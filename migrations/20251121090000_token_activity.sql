@@ -0,0 +1,33 @@
+-- Trade activity kept on the token row by the processor, so activity
+-- listings don't scan trades
+ALTER TABLE tokens
+    ADD COLUMN IF NOT EXISTS last_trade_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS trade_count BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS unique_traders BIGINT NOT NULL DEFAULT 0;
+
+-- Every wallet that traded each token; an insert here is a new unique trader
+CREATE TABLE IF NOT EXISTS token_traders (
+    token_mint VARCHAR(44) NOT NULL,
+    user_wallet VARCHAR(44) NOT NULL,
+    first_trade_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (token_mint, user_wallet)
+);
+
+INSERT INTO token_traders (token_mint, user_wallet, first_trade_at)
+SELECT token_mint, user_wallet, MIN(timestamp)
+FROM trades
+GROUP BY token_mint, user_wallet
+ON CONFLICT DO NOTHING;
+
+UPDATE tokens t
+SET last_trade_at = a.last_trade_at,
+    trade_count = a.trade_count,
+    unique_traders = a.unique_traders
+FROM (
+    SELECT token_mint, MAX(timestamp) AS last_trade_at, COUNT(*) AS trade_count, COUNT(DISTINCT user_wallet) AS unique_traders
+    FROM trades
+    GROUP BY token_mint
+) a
+WHERE a.token_mint = t.mint_address;
+
+CREATE INDEX IF NOT EXISTS idx_tokens_last_trade_at ON tokens(last_trade_at DESC NULLS LAST);
@@ -0,0 +1,14 @@
+-- King of the hill reigns; the row with dethroned_at NULL is the current king
+CREATE TABLE IF NOT EXISTS koth_reigns (
+    id BIGSERIAL PRIMARY KEY,
+    mint_address VARCHAR(44) NOT NULL REFERENCES tokens(mint_address),
+    crowned_at TIMESTAMPTZ NOT NULL,
+    market_cap_sol DOUBLE PRECISION NOT NULL,
+    market_cap_usd DOUBLE PRECISION NOT NULL,
+    dethroned_at TIMESTAMPTZ,
+    -- 'overtaken', 'graduated' or 'fell'
+    dethroned_by VARCHAR(16)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_koth_reigns_current ON koth_reigns ((TRUE)) WHERE dethroned_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_koth_reigns_crowned_at ON koth_reigns(crowned_at DESC);
@@ -0,0 +1,9 @@
+-- Ledger of events the processor has applied, consulted before applying one
+-- so a replayed or doubly ingested transaction can't count twice
+CREATE TABLE IF NOT EXISTS processed_events (
+    signature VARCHAR(88) NOT NULL,
+    event_index SMALLINT NOT NULL,
+    kind VARCHAR(16) NOT NULL,
+    processed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (signature, event_index)
+);
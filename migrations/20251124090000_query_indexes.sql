@@ -0,0 +1,6 @@
+-- Indexes behind the wallet pages and the graduation / market cap listings.
+-- The rest of the common patterns are covered by the initial schema
+-- (idx_trades_token_timestamp, idx_tokens_creator, idx_tokens_market_cap);
+-- database::missing_indexes checks for all of them at startup.
+CREATE INDEX IF NOT EXISTS idx_trades_user_timestamp ON trades(user_wallet, timestamp DESC);
+CREATE INDEX IF NOT EXISTS idx_tokens_complete_progress ON tokens(complete, bonding_curve_progress DESC);
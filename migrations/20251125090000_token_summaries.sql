@@ -0,0 +1,45 @@
+-- Precomputed rows for GET /api/tokens, refreshed by the token_summaries
+-- background task so listings don't aggregate trades per request.
+-- Holders are wallets with a positive net balance from their trades.
+CREATE MATERIALIZED VIEW IF NOT EXISTS token_summaries AS
+SELECT t.mint_address,
+       t.name,
+       t.symbol,
+       t.uri,
+       t.creator_wallet,
+       t.market_cap_usd,
+       t.bonding_curve_progress,
+       t.complete,
+       t.created_at,
+       t.last_trade_at,
+       t.trade_count,
+       t.unique_traders,
+       COALESCE(v.volume_24h_sol, 0)::FLOAT8 AS volume_24h_sol,
+       COALESCE(v.volume_24h_usd, 0)::FLOAT8 AS volume_24h_usd,
+       COALESCE(h.holder_count, 0) AS holder_count
+FROM tokens t
+LEFT JOIN (
+    SELECT token_mint,
+           SUM(sol_amount) / 1e9 AS volume_24h_sol,
+           SUM(sol_amount / 1e9 * sol_price_usd) AS volume_24h_usd
+    FROM trades
+    WHERE timestamp >= NOW() - INTERVAL '24 hours'
+    GROUP BY token_mint
+) v ON v.token_mint = t.mint_address
+LEFT JOIN (
+    SELECT token_mint, COUNT(*) AS holder_count
+    FROM (
+        SELECT token_mint, user_wallet
+        FROM trades
+        GROUP BY token_mint, user_wallet
+        HAVING SUM(CASE WHEN is_buy THEN token_amount ELSE -token_amount END) > 0
+    ) balances
+    GROUP BY token_mint
+) h ON h.token_mint = t.mint_address;
+
+-- Required by REFRESH ... CONCURRENTLY
+CREATE UNIQUE INDEX IF NOT EXISTS idx_token_summaries_mint ON token_summaries(mint_address);
+CREATE INDEX IF NOT EXISTS idx_token_summaries_created_at ON token_summaries(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_token_summaries_market_cap ON token_summaries(market_cap_usd DESC NULLS LAST);
+CREATE INDEX IF NOT EXISTS idx_token_summaries_last_trade_at ON token_summaries(last_trade_at DESC NULLS LAST);
+CREATE INDEX IF NOT EXISTS idx_token_summaries_volume ON token_summaries(volume_24h_usd DESC);
@@ -0,0 +1,11 @@
+-- Holder count and top-10 concentration of each active token, recorded by
+-- the holder_snapshots background task. Holders are wallets with a positive
+-- net balance from their trades; top10_pct is the share of total supply the
+-- ten largest of them hold, NULL when the supply is unknown.
+CREATE TABLE IF NOT EXISTS holder_snapshots (
+    token_mint VARCHAR(44) NOT NULL,
+    taken_at TIMESTAMPTZ NOT NULL,
+    holder_count BIGINT NOT NULL,
+    top10_pct DOUBLE PRECISION,
+    PRIMARY KEY (token_mint, taken_at)
+);
@@ -0,0 +1,21 @@
+-- Lifecycle events per token, written by the processor and served as
+-- GET /api/tokens/{mint}/timeline
+CREATE TABLE IF NOT EXISTS token_events (
+    id BIGSERIAL PRIMARY KEY,
+    token_mint VARCHAR(44) NOT NULL,
+    kind VARCHAR(24) NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    signature VARCHAR(88),
+    details JSONB NOT NULL DEFAULT '{}'
+);
+
+-- Milestones happen once per token; ath and creator_sell can repeat
+CREATE UNIQUE INDEX IF NOT EXISTS idx_token_events_once ON token_events(token_mint, kind)
+    WHERE kind IN ('created', 'first_trade', 'progress_25', 'progress_50', 'progress_75', 'graduated');
+CREATE INDEX IF NOT EXISTS idx_token_events_token ON token_events(token_mint, occurred_at);
+
+-- Price of the last ATH put on the timeline, which new highs must beat by
+-- a step before they are recorded
+ALTER TABLE tokens
+    ADD COLUMN IF NOT EXISTS ath_price_sol DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS ath_at TIMESTAMPTZ;
@@ -0,0 +1,9 @@
+-- Creators flagged by operators via /api/admin/creator-lists: a blacklisted
+-- creator's tokens are flagged and left out of trending, a watchlisted
+-- creator's launches trigger notifications
+CREATE TABLE IF NOT EXISTS creator_lists (
+    creator VARCHAR(44) PRIMARY KEY,
+    list VARCHAR(16) NOT NULL CHECK (list IN ('blacklist', 'watchlist')),
+    reason TEXT,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
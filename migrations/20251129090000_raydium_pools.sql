@@ -0,0 +1,12 @@
+-- Raydium AMM v4 pools of tokens that graduated before PumpSwap existed,
+-- resolved and priced by the raydium_prices background task. pool_address
+-- is NULL when no SOL pool was found as of checked_at.
+CREATE TABLE IF NOT EXISTS raydium_pools (
+    token_mint VARCHAR(44) PRIMARY KEY,
+    pool_address VARCHAR(44),
+    price_sol DOUBLE PRECISION,
+    checked_at TIMESTAMPTZ NOT NULL,
+    priced_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_raydium_pools_priced_at ON raydium_pools(priced_at NULLS FIRST) WHERE pool_address IS NOT NULL;
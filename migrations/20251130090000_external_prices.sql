@@ -0,0 +1,6 @@
+-- Jupiter's USD price for graduated tokens, fetched by the external_prices
+-- background task as a cross-check. price_divergence_pct is how far the
+-- indexer's own price was from it at external_price_at.
+ALTER TABLE tokens ADD COLUMN IF NOT EXISTS external_price_usd NUMERIC;
+ALTER TABLE tokens ADD COLUMN IF NOT EXISTS external_price_at TIMESTAMPTZ;
+ALTER TABLE tokens ADD COLUMN IF NOT EXISTS price_divergence_pct DOUBLE PRECISION;
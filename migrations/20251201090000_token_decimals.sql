@@ -0,0 +1,3 @@
+-- Mint decimals, used to convert reserves and supply to whole tokens.
+-- Every pump.fun mint has 6.
+ALTER TABLE tokens ADD COLUMN decimals SMALLINT NOT NULL DEFAULT 6;
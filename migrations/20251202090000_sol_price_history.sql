@@ -0,0 +1,7 @@
+-- Historical SOL/USD prices the backfill binary prices old trades with:
+-- hourly for recent ranges, daily further back. Cached here so reruns over
+-- the same range don't refetch them.
+CREATE TABLE IF NOT EXISTS sol_price_history (
+    timestamp TIMESTAMPTZ PRIMARY KEY,
+    price_usd DOUBLE PRECISION NOT NULL
+);
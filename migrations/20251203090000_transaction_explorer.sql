@@ -0,0 +1,4 @@
+-- Lookups for the transaction explorer: a wallet's transactions by any
+-- account they touch, and a signature's timeline events.
+CREATE INDEX IF NOT EXISTS idx_transactions_accounts ON transactions USING GIN (accounts_involved);
+CREATE INDEX IF NOT EXISTS idx_token_events_signature ON token_events(signature) WHERE signature IS NOT NULL;
@@ -0,0 +1,17 @@
+-- Events whose processing failed, kept with their payload so the sweeper
+-- can retry them instead of losing them.
+CREATE TABLE IF NOT EXISTS dead_letter_events (
+    signature VARCHAR(88) NOT NULL,
+    event_index SMALLINT NOT NULL,
+    kind VARCHAR(16) NOT NULL,
+    slot BIGINT NOT NULL,
+    payload JSONB NOT NULL,
+    error TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 1,
+    first_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    next_retry_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dead_letter_events_next_retry ON dead_letter_events(next_retry_at);
@@ -0,0 +1,19 @@
+-- One row per indexer or backfill run, for auditing uptime and what each
+-- run ingested. `ended_at` stays NULL for a run that is still going or was
+-- killed; `last_seen_at` tells the two apart.
+CREATE TABLE IF NOT EXISTS indexer_sessions (
+    id BIGSERIAL PRIMARY KEY,
+    version VARCHAR(32) NOT NULL,
+    mode VARCHAR(32) NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at TIMESTAMPTZ,
+    start_slot BIGINT,
+    end_slot BIGINT,
+    transactions_processed BIGINT NOT NULL DEFAULT 0,
+    tokens_created BIGINT NOT NULL DEFAULT 0,
+    trades_processed BIGINT NOT NULL DEFAULT 0,
+    tokens_graduated BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_indexer_sessions_started ON indexer_sessions(started_at DESC);
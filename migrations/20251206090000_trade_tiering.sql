@@ -0,0 +1,21 @@
+-- Trades older than ARCHIVE_HOT_DAYS live only in the archived Parquet
+-- files; these keep what the candle and history endpoints need from them
+ALTER TABLE archive_manifest ADD COLUMN IF NOT EXISTS pruned_at TIMESTAMPTZ;  -- when the period's rows were removed from Postgres
+
+-- One-minute candles of the removed trades, as curve reserves so prices
+-- go through the same calculator as live ones
+CREATE TABLE IF NOT EXISTS cold_trade_candles (
+    token_mint VARCHAR(44) NOT NULL,
+    bucket TIMESTAMPTZ NOT NULL,
+    open_sol BIGINT NOT NULL,
+    open_tokens BIGINT NOT NULL,
+    high_sol BIGINT NOT NULL,
+    high_tokens BIGINT NOT NULL,
+    low_sol BIGINT NOT NULL,
+    low_tokens BIGINT NOT NULL,
+    close_sol BIGINT NOT NULL,
+    close_tokens BIGINT NOT NULL,
+    volume_lamports BIGINT NOT NULL,
+    trades INTEGER NOT NULL,
+    PRIMARY KEY (token_mint, bucket)
+);
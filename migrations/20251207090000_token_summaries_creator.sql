@@ -0,0 +1,2 @@
+-- GET /api/tokens?creator= pages a creator's launches newest first
+CREATE INDEX IF NOT EXISTS idx_token_summaries_creator ON token_summaries(creator_wallet, created_at DESC);
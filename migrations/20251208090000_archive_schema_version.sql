@@ -0,0 +1,3 @@
+-- Which layout an archived object was written with; trade days are only
+-- tiered out of Postgres once their Parquet file holds every column
+ALTER TABLE archive_manifest ADD COLUMN IF NOT EXISTS schema_version INTEGER NOT NULL DEFAULT 1;
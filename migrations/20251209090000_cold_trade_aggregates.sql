@@ -0,0 +1,51 @@
+-- What the fee, slippage, creator and holder endpoints need from trades
+-- tiered out of Postgres, written by the tiering task with the candles
+
+-- One-minute sums per token and side
+CREATE TABLE IF NOT EXISTS cold_trade_sides (
+    token_mint VARCHAR(44) NOT NULL,
+    bucket TIMESTAMPTZ NOT NULL,
+    is_buy BOOLEAN NOT NULL,
+    trades INTEGER NOT NULL,
+    volume_lamports BIGINT NOT NULL,
+    fee_lamports BIGINT NOT NULL,
+    creator_fee_lamports BIGINT NOT NULL,
+    fees_usd FLOAT8,                           -- NULL when none of the trades had a SOL price
+    slippage_trades INTEGER NOT NULL,          -- trades with slippage_pct set; the sums below cover only these
+    slippage_volume_lamports BIGINT NOT NULL,
+    price_impact_pct_sum FLOAT8 NOT NULL,
+    slippage_pct_sum FLOAT8 NOT NULL,
+    slippage_pct_volume_sum FLOAT8 NOT NULL,   -- SUM(slippage_pct * sol_amount)
+    max_slippage_pct FLOAT8,
+    PRIMARY KEY (token_mint, bucket, is_buy)
+);
+
+-- One-minute protocol fees per fee recipient
+CREATE TABLE IF NOT EXISTS cold_fee_recipients (
+    fee_recipient VARCHAR(44) NOT NULL,
+    bucket TIMESTAMPTZ NOT NULL,
+    fee_lamports BIGINT NOT NULL,
+    trades INTEGER NOT NULL,
+    PRIMARY KEY (fee_recipient, bucket)
+);
+
+-- All-time creator fees per creator and token
+CREATE TABLE IF NOT EXISTS cold_creator_fees (
+    creator VARCHAR(44) NOT NULL,
+    token_mint VARCHAR(44) NOT NULL,
+    creator_fee_lamports BIGINT NOT NULL,
+    trades BIGINT NOT NULL,
+    PRIMARY KEY (creator, token_mint)
+);
+
+-- All-time net token balance and sells per wallet and token
+CREATE TABLE IF NOT EXISTS cold_wallet_balances (
+    token_mint VARCHAR(44) NOT NULL,
+    user_wallet VARCHAR(44) NOT NULL,
+    balance NUMERIC NOT NULL,                  -- tokens bought minus tokens sold
+    trades BIGINT NOT NULL,
+    sells BIGINT NOT NULL,
+    sold_lamports NUMERIC NOT NULL,
+    first_sell_at TIMESTAMPTZ,
+    PRIMARY KEY (token_mint, user_wallet)
+);
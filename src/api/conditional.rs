@@ -0,0 +1,82 @@
+//! `ETag` / `If-None-Match` for endpoints that clients poll, so an
+//! unchanged response costs a 304 instead of the full body.
+
+use axum::{
+  body::{Body, Bytes},
+  extract::Request,
+  http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+  middleware::Next,
+  response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// Responses bigger than this are passed through without an ETag.
+const MAX_BUFFERED_BYTES: usize = 4 * 1024 * 1024;
+
+/// Tags successful GET responses with a weak ETag derived from the body and
+/// answers 304 when it matches the request's `If-None-Match`. Weak because
+/// the compression layer may re-encode the body.
+pub async fn etag(request: Request, next: Next) -> Response {
+  if request.method() != Method::GET && request.method() != Method::HEAD {
+    return next.run(request).await;
+  }
+  let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+  let response = next.run(request).await;
+  if response.status() != StatusCode::OK {
+    return response;
+  }
+
+  let (mut parts, body) = response.into_parts();
+  let bytes = match axum::body::to_bytes(body, MAX_BUFFERED_BYTES).await {
+    Ok(bytes) => bytes,
+    Err(e) => {
+      tracing::warn!("Failed to buffer response for ETag: {}", e);
+      return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+  };
+
+  let tag = compute_etag(&bytes);
+  if if_none_match.is_some_and(|value| matches(&value, &tag)) {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ETAG, tag);
+    if let Some(cache_control) = parts.headers.get(header::CACHE_CONTROL) {
+      headers.insert(header::CACHE_CONTROL, cache_control.clone());
+    }
+    return (StatusCode::NOT_MODIFIED, headers).into_response();
+  }
+
+  parts.headers.insert(header::ETAG, tag);
+  Response::from_parts(parts, Body::from(bytes))
+}
+
+fn compute_etag(body: &Bytes) -> HeaderValue {
+  let digest = Sha256::digest(body);
+  let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+  HeaderValue::from_str(&format!("W/\"{}\"", hex)).expect("hex is a valid header value")
+}
+
+/// Weak comparison (RFC 9110 §13.1.2) against a comma-separated list.
+fn matches(if_none_match: &HeaderValue, tag: &HeaderValue) -> bool {
+  let Ok(value) = if_none_match.to_str() else { return false };
+  let opaque = |t: &str| t.trim().trim_start_matches("W/").to_string();
+  let tag = opaque(tag.to_str().unwrap_or_default());
+  value.split(',').any(|candidate| candidate.trim() == "*" || opaque(candidate) == tag)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_etag_matching() {
+    let tag = compute_etag(&Bytes::from_static(b"{\"mint\":\"abc\"}"));
+    assert_eq!(tag, compute_etag(&Bytes::from_static(b"{\"mint\":\"abc\"}")));
+    assert_ne!(tag, compute_etag(&Bytes::from_static(b"{\"mint\":\"abd\"}")));
+
+    let strong = HeaderValue::from_str(tag.to_str().unwrap().trim_start_matches("W/")).unwrap();
+    assert!(matches(&strong, &tag));
+    assert!(matches(&HeaderValue::from_static("\"other\", *"), &tag));
+    assert!(!matches(&HeaderValue::from_static("\"other\""), &tag));
+  }
+}
@@ -0,0 +1,164 @@
+use axum::{
+  http::StatusCode,
+  response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Every API failure, rendered as
+/// `{ "error": { "code", "message", "details" } }`.
+///
+/// Database and internal errors are logged in full and answered with a
+/// generic message, so nothing about the schema leaks to clients.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+  #[error("{0}")]
+  BadRequest(String),
+  /// A request body or settings update with one or more problems, listed in
+  /// `details`.
+  #[error("{message}")]
+  Validation { message: String, errors: Vec<String> },
+  #[error("{0}")]
+  Unauthorized(String),
+  #[error("{0}")]
+  NotFound(String),
+  #[error("{0}")]
+  RateLimited(String),
+  #[error("{0}")]
+  Unavailable(String),
+  /// Trades the request needs were tiered out of Postgres; `details` lists
+  /// the archived files holding them.
+  #[error("{message}")]
+  Archived { message: String, objects: Vec<String> },
+  #[error("Database error")]
+  Database(#[from] sqlx::Error),
+  #[error("Internal error")]
+  Internal(#[from] anyhow::Error),
+}
+
+impl ApiError {
+  pub fn bad_request(message: impl Into<String>) -> Self {
+    ApiError::BadRequest(message.into())
+  }
+
+  pub fn not_found(message: impl Into<String>) -> Self {
+    ApiError::NotFound(message.into())
+  }
+
+  pub fn archived(message: impl Into<String>, objects: Vec<String>) -> Self {
+    ApiError::Archived { message: message.into(), objects }
+  }
+
+  pub fn status(&self) -> StatusCode {
+    match self {
+      ApiError::BadRequest(_) | ApiError::Validation { .. } => StatusCode::BAD_REQUEST,
+      ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+      ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+      ApiError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+      ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+      ApiError::Archived { .. } => StatusCode::GONE,
+      ApiError::Database(_) | ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+  }
+
+  /// Stable, machine-readable identifier for the kind of failure.
+  pub fn code(&self) -> &'static str {
+    match self {
+      ApiError::BadRequest(_) => "bad_request",
+      ApiError::Validation { .. } => "validation_failed",
+      ApiError::Unauthorized(_) => "unauthorized",
+      ApiError::NotFound(_) => "not_found",
+      ApiError::RateLimited(_) => "rate_limited",
+      ApiError::Unavailable(_) => "unavailable",
+      ApiError::Archived { .. } => "range_archived",
+      ApiError::Database(_) => "database_error",
+      ApiError::Internal(_) => "internal_error",
+    }
+  }
+
+  fn details(&self) -> Option<Value> {
+    match self {
+      ApiError::Validation { errors, .. } => Some(Value::from(errors.clone())),
+      ApiError::Archived { objects, .. } => Some(Value::from(objects.clone())),
+      _ => None,
+    }
+  }
+}
+
+impl From<crate::error::IndexerError> for ApiError {
+  fn from(e: crate::error::IndexerError) -> Self {
+    match e {
+      crate::error::IndexerError::Db(e) => ApiError::Database(e),
+      other => ApiError::Internal(other.into()),
+    }
+  }
+}
+
+/// Body of every error response.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorResponse {
+  pub error: ErrorBody,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorBody {
+  /// e.g. `bad_request`, `not_found`, `rate_limited`, `range_archived`, `database_error`
+  pub code: String,
+  pub message: String,
+  #[schema(value_type = Option<Object>)]
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub details: Option<Value>,
+}
+
+impl IntoResponse for ApiError {
+  fn into_response(self) -> Response {
+    match &self {
+      ApiError::Database(e) => tracing::error!("Database error: {}", e),
+      ApiError::Internal(e) => tracing::error!("Internal error: {:#}", e),
+      _ => {}
+    }
+
+    let body = ErrorResponse {
+      error: ErrorBody {
+        code: self.code().to_string(),
+        message: self.to_string(),
+        details: self.details(),
+      },
+    };
+    (self.status(), Json(body)).into_response()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn test_error_body() {
+    let response = ApiError::Validation {
+      message: "Invalid settings".to_string(),
+      errors: vec!["rate_limit.read_per_minute must be at least 1".to_string()],
+    }
+    .into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["code"], "validation_failed");
+    assert_eq!(body["error"]["message"], "Invalid settings");
+    assert_eq!(body["error"]["details"][0], "rate_limit.read_per_minute must be at least 1");
+
+    let response = ApiError::Database(sqlx::Error::RowNotFound).into_response();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["message"], "Database error");
+
+    let response = ApiError::archived("Trades archived", vec!["trades/date=2025-11-03/trades.parquet".to_string()]).into_response();
+    assert_eq!(response.status(), StatusCode::GONE);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["code"], "range_archived");
+    assert_eq!(body["error"]["details"][0], "trades/date=2025-11-03/trades.parquet");
+  }
+}
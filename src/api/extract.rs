@@ -0,0 +1,108 @@
+//! Drop-in replacements for axum's `Query`, `Path` and `Json` extractors
+//! whose rejections are [`ApiError`]s, so malformed requests get the same
+//! JSON error body as every other failure, plus [`Address`] for routes
+//! keyed by a mint or wallet and [`TxSignature`] for those keyed by a
+//! transaction.
+
+use axum::{
+  extract::{FromRequest, FromRequestParts, Request},
+  http::request::Parts,
+  response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use super::error::ApiError;
+use super::params::{parse_pubkey, parse_signature};
+
+pub struct Query<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for Query<T>
+where
+  T: DeserializeOwned,
+  S: Send + Sync,
+{
+  type Rejection = ApiError;
+
+  async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, ApiError> {
+    axum::extract::Query::from_request_parts(parts, state)
+      .await
+      .map(|axum::extract::Query(value)| Query(value))
+      .map_err(|e| ApiError::BadRequest(e.body_text()))
+  }
+}
+
+pub struct Path<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for Path<T>
+where
+  T: DeserializeOwned + Send,
+  S: Send + Sync,
+{
+  type Rejection = ApiError;
+
+  async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, ApiError> {
+    axum::extract::Path::from_request_parts(parts, state)
+      .await
+      .map(|axum::extract::Path(value)| Path(value))
+      .map_err(|e| ApiError::BadRequest(e.body_text()))
+  }
+}
+
+/// A route's single `{mint}` or `{wallet}` segment, checked to be a base58
+/// pubkey before it reaches a SQL bind or a Redis channel name.
+pub struct Address(pub String);
+
+impl<S> FromRequestParts<S> for Address
+where
+  S: Send + Sync,
+{
+  type Rejection = ApiError;
+
+  async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, ApiError> {
+    let Path(value) = Path::<String>::from_request_parts(parts, state).await?;
+    parse_pubkey(&value)
+      .map(Address)
+      .ok_or_else(|| ApiError::bad_request("Invalid address: expected a base58 pubkey"))
+  }
+}
+
+/// A route's single `{signature}` segment, checked to be a base58
+/// transaction signature.
+pub struct TxSignature(pub String);
+
+impl<S> FromRequestParts<S> for TxSignature
+where
+  S: Send + Sync,
+{
+  type Rejection = ApiError;
+
+  async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, ApiError> {
+    let Path(value) = Path::<String>::from_request_parts(parts, state).await?;
+    parse_signature(&value)
+      .map(TxSignature)
+      .ok_or_else(|| ApiError::bad_request("Invalid signature: expected a base58 transaction signature"))
+  }
+}
+
+/// Also usable as a response, exactly like `axum::Json`.
+pub struct Json<T>(pub T);
+
+impl<S, T> FromRequest<S> for Json<T>
+where
+  T: DeserializeOwned,
+  S: Send + Sync,
+{
+  type Rejection = ApiError;
+
+  async fn from_request(request: Request, state: &S) -> Result<Self, ApiError> {
+    axum::Json::from_request(request, state)
+      .await
+      .map(|axum::Json(value)| Json(value))
+      .map_err(|e| ApiError::BadRequest(e.body_text()))
+  }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+  fn into_response(self) -> Response {
+    axum::Json(self.0).into_response()
+  }
+}
@@ -0,0 +1,98 @@
+//! Data freshness attached to every read response as a `meta` object, so
+//! clients can tell live data from data that is behind the chain.
+
+use axum::{
+  body::Body,
+  extract::{Request, State},
+  http::{header, StatusCode},
+  middleware::Next,
+  response::{IntoResponse, Response},
+};
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::sync::atomic::Ordering;
+use crate::api::AppState;
+use crate::dto::{AsOf, ResponseMeta};
+use crate::processor::metrics::Metrics;
+
+/// Block-time lag past which responses are flagged `stale`. Matches the
+/// readiness check's degraded threshold.
+pub const MAX_PROCESSING_LAG_SECS: i64 = 120;
+
+/// Bodies bigger than this are passed through without `meta`.
+const MAX_BUFFERED_BYTES: usize = 8 * 1024 * 1024;
+
+impl ResponseMeta {
+  pub fn from_metrics(metrics: &Metrics, paused: bool, now: DateTime<Utc>) -> Self {
+    let seen = metrics.last_seen_slot.load(Ordering::Relaxed);
+    let processed = metrics.last_processed_slot.load(Ordering::Relaxed);
+    let block_time = metrics
+      .last_processed_block_time()
+      .and_then(|ts| Utc.timestamp_opt(ts, 0).single());
+    let lag_seconds = block_time.map(|t| (now - t).num_seconds().max(0));
+
+    Self {
+      server_time: now,
+      as_of: AsOf { slot: processed, block_time },
+      lag_seconds,
+      lag_slots: seen.saturating_sub(processed),
+      stale: paused || lag_seconds.is_none_or(|lag| lag > MAX_PROCESSING_LAG_SECS),
+    }
+  }
+}
+
+/// Adds `meta` to successful JSON object responses. Runs outside the ETag
+/// layer, so a changing `meta` alone doesn't invalidate cached bodies.
+pub async fn attach(State(state): State<AppState>, request: Request, next: Next) -> Response {
+  let response = next.run(request).await;
+  let is_json = response
+    .headers()
+    .get(header::CONTENT_TYPE)
+    .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+  if response.status() != StatusCode::OK || !is_json {
+    return response;
+  }
+
+  let (mut parts, body) = response.into_parts();
+  let bytes = match axum::body::to_bytes(body, MAX_BUFFERED_BYTES).await {
+    Ok(bytes) => bytes,
+    Err(e) => {
+      tracing::warn!("Failed to buffer response for meta: {}", e);
+      return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+  };
+
+  let Ok(Value::Object(mut object)) = serde_json::from_slice::<Value>(&bytes) else {
+    return Response::from_parts(parts, Body::from(bytes));
+  };
+  let meta = ResponseMeta::from_metrics(&state.metrics, state.ingestion.is_paused(), Utc::now());
+  object.insert("meta".to_string(), serde_json::to_value(meta).unwrap_or(Value::Null));
+
+  let body = serde_json::to_vec(&object).unwrap_or_else(|_| bytes.to_vec());
+  parts.headers.remove(header::CONTENT_LENGTH);
+  Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_meta_staleness() {
+    let metrics = Metrics::new();
+    let now = Utc::now();
+    assert!(ResponseMeta::from_metrics(&metrics, false, now).stale);
+
+    metrics.record_seen_slot(105);
+    metrics.record_processed_slot(100, Some(now.timestamp() - 5));
+    let meta = ResponseMeta::from_metrics(&metrics, false, now);
+    assert!(!meta.stale);
+    assert_eq!(meta.as_of.slot, 100);
+    assert_eq!(meta.lag_slots, 5);
+    assert_eq!(meta.lag_seconds, Some(5));
+    assert!(ResponseMeta::from_metrics(&metrics, true, now).stale);
+
+    let later = now + chrono::Duration::seconds(MAX_PROCESSING_LAG_SECS + 10);
+    assert!(ResponseMeta::from_metrics(&metrics, false, later).stale);
+  }
+}
@@ -0,0 +1,724 @@
+//! GraphQL view over the indexed data, served at `/graphql` (POST for
+//! queries, GET for the GraphiQL explorer).
+//!
+//! List fields cost their page size times the cost of each item, so the
+//! complexity cap bounds how many rows a request can fan out to. Nested
+//! `trades` and `holders` are batched through DataLoaders: a page of tokens
+//! loads them in one query instead of one per token.
+//!
+//! Trades tiered out of Postgres are counted in `totalCount` from their
+//! one-minute sums but not listed: pages hold the trades still in Postgres,
+//! and `archivedObjects` names the Parquet files with the rest.
+
+use std::collections::HashMap;
+
+use async_graphql::{
+  ComplexObject, Context, EmptyMutation, EmptySubscription, Enum, Object, Result,
+  Schema, SimpleObject,
+  dataloader::{DataLoader, Loader},
+  http::GraphiQLSource,
+};
+use axum::{
+  Router,
+  extract::State,
+  response::{Html, IntoResponse, Json},
+  routing::get,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+
+use crate::api::params::{parse_interval, window_start};
+use crate::database::tiering;
+use crate::processor::calculator;
+
+pub type PumpSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+const MAX_PAGE_SIZE: i64 = 100;
+const MAX_CANDLES: i64 = 2000;
+const MAX_DEPTH: usize = 8;
+/// Enough for a full page of tokens with a few nested trades each, or one
+/// token's full candle history.
+const MAX_COMPLEXITY: usize = 20_000;
+
+pub fn build_schema(db: PgPool) -> PumpSchema {
+  Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+    .data(DataLoader::new(TradeLoader(db.clone()), tokio::spawn))
+    .data(DataLoader::new(HolderLoader(db.clone()), tokio::spawn))
+    .data(db)
+    .limit_depth(MAX_DEPTH)
+    .limit_complexity(MAX_COMPLEXITY)
+    .finish()
+}
+
+pub fn create_graphql_routes<S>(schema: PumpSchema) -> Router<S> {
+  Router::new()
+    .route("/graphql", get(graphiql).post(graphql_handler))
+    .with_state(schema)
+}
+
+async fn graphql_handler(
+  State(schema): State<PumpSchema>,
+  Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+  Json(schema.execute(request).await)
+}
+
+async fn graphiql() -> impl IntoResponse {
+  Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+fn page(first: i64) -> i64 {
+  first.clamp(0, MAX_PAGE_SIZE)
+}
+
+/// Complexity of a list of `first` items costing `child` each.
+fn page_cost(first: i64, child: usize) -> usize {
+  page(first) as usize * child
+}
+
+fn db_error(e: impl std::fmt::Display) -> async_graphql::Error {
+  tracing::error!("Database error: {}", e);
+  async_graphql::Error::new("Database error")
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum TokenOrder {
+  CreatedAt,
+  MarketCap,
+}
+
+#[derive(SimpleObject, FromRow)]
+#[graphql(complex)]
+pub struct Token {
+  pub mint_address: String,
+  pub name: String,
+  pub symbol: String,
+  pub uri: String,
+  pub creator_wallet: String,
+  pub market_cap_usd: Option<f64>,
+  pub bonding_curve_progress: Option<f64>,
+  pub complete: bool,
+  pub created_at: DateTime<Utc>,
+  pub graduated_at: Option<DateTime<Utc>>,
+  pub decimals: i16,
+  #[graphql(skip)]
+  pub token_total_supply: Option<i64>,
+}
+
+const TOKEN_COLUMNS: &str =
+  "mint_address, name, symbol, uri, creator_wallet,
+   market_cap_usd::FLOAT8 AS market_cap_usd,
+   bonding_curve_progress::FLOAT8 AS bonding_curve_progress,
+   COALESCE(complete, false) AS complete, created_at, graduated_at, decimals, token_total_supply";
+
+#[derive(SimpleObject)]
+pub struct TokenConnection {
+  pub nodes: Vec<Token>,
+  pub total_count: i64,
+}
+
+#[derive(Clone, SimpleObject, FromRow)]
+#[graphql(complex)]
+pub struct Trade {
+  pub signature: String,
+  pub token_mint: String,
+  pub user_wallet: String,
+  pub is_buy: bool,
+  pub sol_amount: i64,
+  pub token_amount: i64,
+  pub timestamp: DateTime<Utc>,
+  #[graphql(skip)]
+  pub virtual_sol_reserves: i64,
+  #[graphql(skip)]
+  pub virtual_token_reserves: i64,
+  #[graphql(skip)]
+  pub token_decimals: i16,
+}
+
+const TRADE_COLUMNS: &str =
+  "signature, token_mint, user_wallet, is_buy, sol_amount, token_amount, timestamp,
+   virtual_sol_reserves, virtual_token_reserves,
+   COALESCE((SELECT decimals FROM tokens WHERE mint_address = token_mint), 6)::SMALLINT AS token_decimals";
+
+#[derive(Clone, Default, SimpleObject)]
+pub struct TradeConnection {
+  pub nodes: Vec<Trade>,
+  pub total_count: i64,
+  /// Parquet files of trades counted in `totalCount` but tiered out of
+  /// Postgres, so not in `nodes`.
+  pub archived_objects: Vec<String>,
+}
+
+/// OHLC in SOL per token, taken from the curve reserves after each trade.
+#[derive(SimpleObject)]
+pub struct Candle {
+  pub timestamp: DateTime<Utc>,
+  pub open: f64,
+  pub high: f64,
+  pub low: f64,
+  pub close: f64,
+  pub volume_sol: f64,
+  pub trades: i64,
+}
+
+#[derive(FromRow)]
+struct CandleRow {
+  bucket: DateTime<Utc>,
+  open_sol: i64,
+  open_tokens: i64,
+  high_sol: i64,
+  high_tokens: i64,
+  low_sol: i64,
+  low_tokens: i64,
+  close_sol: i64,
+  close_tokens: i64,
+  volume_lamports: i64,
+  trades: i64,
+}
+
+/// A wallet's balance as implied by its bonding curve trades; transfers and
+/// post-graduation activity are not reflected.
+#[derive(Clone, SimpleObject, FromRow)]
+pub struct Holder {
+  pub wallet: String,
+  pub balance: i64,
+}
+
+#[derive(FromRow)]
+struct HolderRow {
+  token_mint: String,
+  #[sqlx(flatten)]
+  holder: Holder,
+}
+
+#[derive(SimpleObject, FromRow)]
+#[graphql(complex)]
+pub struct Creator {
+  pub wallet: String,
+  pub tokens_launched: i64,
+  pub tokens_graduated: i64,
+}
+
+async fn fetch_token(db: &PgPool, mint: &str) -> Result<Option<Token>> {
+  sqlx::query_as::<_, Token>(&format!(
+    "SELECT {} FROM tokens WHERE mint_address = $1", TOKEN_COLUMNS
+  ))
+  .bind(mint)
+  .fetch_optional(db)
+  .await
+  .map_err(db_error)
+}
+
+async fn fetch_trades(
+  db: &PgPool,
+  mint: Option<&str>,
+  wallet: Option<&str>,
+  first: i64,
+  offset: i64,
+) -> Result<TradeConnection> {
+  let filter = "($1::VARCHAR IS NULL OR token_mint = $1)
+                AND ($2::VARCHAR IS NULL OR user_wallet = $2)";
+
+  let nodes = sqlx::query_as::<_, Trade>(&format!(
+    "SELECT {} FROM trades WHERE {} ORDER BY timestamp DESC LIMIT $3 OFFSET $4",
+    TRADE_COLUMNS, filter
+  ))
+  .bind(mint)
+  .bind(wallet)
+  .bind(page(first))
+  .bind(offset.max(0))
+  .fetch_all(db)
+  .await
+  .map_err(db_error)?;
+
+  let total: (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM trades WHERE {}", filter))
+    .bind(mint)
+    .bind(wallet)
+    .fetch_one(db)
+    .await
+    .map_err(db_error)?;
+
+  let cold = match wallet {
+    Some(wallet) => tiering::count_cold_wallet_trades(db, mint, wallet).await,
+    None => tiering::count_cold_trades(db, mint, None, None, None).await,
+  }
+  .map_err(db_error)?;
+  let archived_objects = if cold > 0 {
+    tiering::fetch_cold_objects(db, mint, None, None).await.map_err(db_error)?
+  } else {
+    Vec::new()
+  };
+
+  Ok(TradeConnection { nodes, total_count: total.0 + cold, archived_objects })
+}
+
+async fn fetch_tokens(
+  db: &PgPool,
+  creator: Option<&str>,
+  complete: Option<bool>,
+  order_by: TokenOrder,
+  first: i64,
+  offset: i64,
+) -> Result<TokenConnection> {
+  let filter = "($1::VARCHAR IS NULL OR creator_wallet = $1)
+                AND ($2::BOOLEAN IS NULL OR complete = $2)";
+  let order = match order_by {
+    TokenOrder::CreatedAt => "created_at DESC",
+    TokenOrder::MarketCap => "market_cap_usd DESC NULLS LAST",
+  };
+
+  let nodes = sqlx::query_as::<_, Token>(&format!(
+    "SELECT {} FROM tokens WHERE {} ORDER BY {} LIMIT $3 OFFSET $4",
+    TOKEN_COLUMNS, filter, order
+  ))
+  .bind(creator)
+  .bind(complete)
+  .bind(page(first))
+  .bind(offset.max(0))
+  .fetch_all(db)
+  .await
+  .map_err(db_error)?;
+
+  let total: (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM tokens WHERE {}", filter))
+    .bind(creator)
+    .bind(complete)
+    .fetch_one(db)
+    .await
+    .map_err(db_error)?;
+
+  Ok(TokenConnection { nodes, total_count: total.0 })
+}
+
+async fn fetch_creator(db: &PgPool, wallet: &str) -> Result<Creator> {
+  sqlx::query_as::<_, Creator>(
+    "SELECT $1::VARCHAR AS wallet,
+            COUNT(*) AS tokens_launched,
+            COUNT(*) FILTER (WHERE complete) AS tokens_graduated
+     FROM tokens
+     WHERE creator_wallet = $1"
+  )
+  .bind(wallet)
+  .fetch_one(db)
+  .await
+  .map_err(db_error)
+}
+
+/// `Token.trades` for one token. Tokens resolved together usually share the
+/// other arguments, and each distinct set costs one query.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct TradePage {
+  mint: String,
+  wallet: Option<String>,
+  first: i64,
+  offset: i64,
+}
+
+pub struct TradeLoader(PgPool);
+
+impl Loader<TradePage> for TradeLoader {
+  type Value = TradeConnection;
+  type Error = async_graphql::Error;
+
+  async fn load(&self, keys: &[TradePage]) -> Result<HashMap<TradePage, TradeConnection>> {
+    let mut groups: HashMap<(Option<&str>, i64, i64), Vec<&str>> = HashMap::new();
+    for key in keys {
+      groups.entry((key.wallet.as_deref(), key.first, key.offset)).or_default().push(&key.mint);
+    }
+
+    let mut pages = HashMap::new();
+    for ((wallet, first, offset), mints) in groups {
+      let nodes = sqlx::query_as::<_, Trade>(&format!(
+        "SELECT page.*
+         FROM UNNEST($1::VARCHAR[]) AS m(mint)
+         CROSS JOIN LATERAL (
+             SELECT {} FROM trades
+             WHERE token_mint = m.mint AND ($2::VARCHAR IS NULL OR user_wallet = $2)
+             ORDER BY timestamp DESC
+             LIMIT $3 OFFSET $4
+         ) page",
+        TRADE_COLUMNS
+      ))
+      .bind(&mints)
+      .bind(wallet)
+      .bind(first)
+      .bind(offset)
+      .fetch_all(&self.0)
+      .await
+      .map_err(db_error)?;
+
+      let totals: HashMap<String, i64> = sqlx::query_as(
+        "SELECT token_mint, COUNT(*) FROM trades
+         WHERE token_mint = ANY($1) AND ($2::VARCHAR IS NULL OR user_wallet = $2)
+         GROUP BY token_mint"
+      )
+      .bind(&mints)
+      .bind(wallet)
+      .fetch_all(&self.0)
+      .await
+      .map_err(db_error)?
+      .into_iter()
+      .collect();
+
+      let cold: HashMap<String, i64> = sqlx::query_as(
+        "SELECT token_mint, SUM(trades)::BIGINT FROM cold_wallet_balances
+         WHERE token_mint = ANY($1) AND ($2::VARCHAR IS NULL OR user_wallet = $2)
+         GROUP BY token_mint
+         HAVING SUM(trades) > 0"
+      )
+      .bind(&mints)
+      .bind(wallet)
+      .fetch_all(&self.0)
+      .await
+      .map_err(db_error)?
+      .into_iter()
+      .collect();
+
+      let mut connections: HashMap<&str, TradeConnection> = HashMap::new();
+      for trade in nodes {
+        if let Some(&mint) = mints.iter().find(|&&mint| mint == trade.token_mint) {
+          connections.entry(mint).or_default().nodes.push(trade);
+        }
+      }
+      for mint in mints {
+        let mut connection = connections.remove(mint).unwrap_or_default();
+        let hot = totals.get(mint).copied().unwrap_or(0);
+        connection.total_count = hot + cold.get(mint).copied().unwrap_or(0);
+        if cold.contains_key(mint) {
+          connection.archived_objects =
+            tiering::fetch_cold_objects(&self.0, Some(mint), None, None).await.map_err(db_error)?;
+        }
+        let key = TradePage { mint: mint.to_string(), wallet: wallet.map(String::from), first, offset };
+        pages.insert(key, connection);
+      }
+    }
+    Ok(pages)
+  }
+}
+
+/// `Token.holders` for one token.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct HolderPage {
+  mint: String,
+  first: i64,
+}
+
+pub struct HolderLoader(PgPool);
+
+impl Loader<HolderPage> for HolderLoader {
+  type Value = Vec<Holder>;
+  type Error = async_graphql::Error;
+
+  async fn load(&self, keys: &[HolderPage]) -> Result<HashMap<HolderPage, Vec<Holder>>> {
+    let mut groups: HashMap<i64, Vec<&str>> = HashMap::new();
+    for key in keys {
+      groups.entry(key.first).or_default().push(&key.mint);
+    }
+
+    let mut pages: HashMap<HolderPage, Vec<Holder>> = HashMap::new();
+    for (first, mints) in groups {
+      // Balances of trades tiered out of Postgres are kept per wallet in
+      // cold_wallet_balances, so they're added to the hot trades.
+      let rows = sqlx::query_as::<_, HolderRow>(
+        "SELECT m.mint AS token_mint, top.wallet, top.balance
+         FROM UNNEST($1::VARCHAR[]) AS m(mint)
+         CROSS JOIN LATERAL (
+             SELECT wallet, SUM(amount)::BIGINT AS balance
+             FROM (
+                 SELECT user_wallet AS wallet,
+                        CASE WHEN is_buy THEN token_amount ELSE -token_amount END::NUMERIC AS amount
+                 FROM trades
+                 WHERE token_mint = m.mint
+                 UNION ALL
+                 SELECT user_wallet, balance
+                 FROM cold_wallet_balances
+                 WHERE token_mint = m.mint
+             ) amounts
+             GROUP BY wallet
+             HAVING SUM(amount) > 0
+             ORDER BY balance DESC
+             LIMIT $2
+         ) top
+         ORDER BY m.mint, top.balance DESC"
+      )
+      .bind(&mints)
+      .bind(first)
+      .fetch_all(&self.0)
+      .await
+      .map_err(db_error)?;
+
+      for mint in &mints {
+        pages.insert(HolderPage { mint: mint.to_string(), first }, Vec::new());
+      }
+      for row in rows {
+        if let Some(holders) = pages.get_mut(&HolderPage { mint: row.token_mint, first }) {
+          holders.push(row.holder);
+        }
+      }
+    }
+    Ok(pages)
+  }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+  async fn token(&self, ctx: &Context<'_>, mint: String) -> Result<Option<Token>> {
+    fetch_token(ctx.data::<PgPool>()?, &mint).await
+  }
+
+  #[graphql(complexity = "page_cost(first, child_complexity)")]
+  async fn tokens(
+    &self,
+    ctx: &Context<'_>,
+    creator: Option<String>,
+    complete: Option<bool>,
+    #[graphql(default_with = "TokenOrder::CreatedAt")] order_by: TokenOrder,
+    #[graphql(default = 20)] first: i64,
+    #[graphql(default)] offset: i64,
+  ) -> Result<TokenConnection> {
+    let db = ctx.data::<PgPool>()?;
+    fetch_tokens(db, creator.as_deref(), complete, order_by, first, offset).await
+  }
+
+  #[graphql(complexity = "page_cost(first, child_complexity)")]
+  async fn trades(
+    &self,
+    ctx: &Context<'_>,
+    mint: Option<String>,
+    wallet: Option<String>,
+    #[graphql(default = 20)] first: i64,
+    #[graphql(default)] offset: i64,
+  ) -> Result<TradeConnection> {
+    let db = ctx.data::<PgPool>()?;
+    fetch_trades(db, mint.as_deref(), wallet.as_deref(), first, offset).await
+  }
+
+  async fn creator(&self, ctx: &Context<'_>, wallet: String) -> Result<Creator> {
+    fetch_creator(ctx.data::<PgPool>()?, &wallet).await
+  }
+}
+
+#[ComplexObject]
+impl Token {
+  #[graphql(complexity = "page_cost(first, child_complexity)")]
+  async fn trades(
+    &self,
+    ctx: &Context<'_>,
+    wallet: Option<String>,
+    #[graphql(default = 20)] first: i64,
+    #[graphql(default)] offset: i64,
+  ) -> Result<TradeConnection> {
+    let key = TradePage { mint: self.mint_address.clone(), wallet, first: page(first), offset: offset.max(0) };
+    Ok(ctx.data::<DataLoader<TradeLoader>>()?.load_one(key).await?.unwrap_or_default())
+  }
+
+  async fn creator(&self, ctx: &Context<'_>) -> Result<Creator> {
+    fetch_creator(ctx.data::<PgPool>()?, &self.creator_wallet).await
+  }
+
+  /// Largest holders by net bought-minus-sold tokens.
+  #[graphql(complexity = "page_cost(first, child_complexity)")]
+  async fn holders(
+    &self,
+    ctx: &Context<'_>,
+    #[graphql(default = 20)] first: i64,
+  ) -> Result<Vec<Holder>> {
+    let key = HolderPage { mint: self.mint_address.clone(), first: page(first) };
+    Ok(ctx.data::<DataLoader<HolderLoader>>()?.load_one(key).await?.unwrap_or_default())
+  }
+
+  #[graphql(complexity = "limit.clamp(1, MAX_CANDLES) as usize * child_complexity")]
+  async fn candles(
+    &self,
+    ctx: &Context<'_>,
+    #[graphql(default_with = "\"5m\".to_string()")] interval: String,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    #[graphql(default = 288)] limit: i64,
+  ) -> Result<Vec<Candle>> {
+    let interval = parse_interval(&interval)
+      .ok_or("interval must look like 1m, 5m, 1h or 1d")?;
+    let limit = limit.clamp(1, MAX_CANDLES);
+    let to = to.unwrap_or_else(Utc::now);
+    let from = match from {
+      Some(from) => from,
+      None => window_start(to, interval, limit as i32).ok_or("interval too large")?,
+    };
+
+    // Prices are compared as reserve ratios; highs/lows pick the trade whose
+    // ratio was extreme so the pair can go through the shared calculator.
+    // Trades tiered out of Postgres come in as their one-minute candles.
+    let rows = sqlx::query_as::<_, CandleRow>(
+      "WITH points AS (
+           SELECT timestamp AS at,
+                  virtual_sol_reserves AS open_sol, virtual_token_reserves AS open_tokens,
+                  virtual_sol_reserves AS high_sol, virtual_token_reserves AS high_tokens,
+                  virtual_sol_reserves AS low_sol, virtual_token_reserves AS low_tokens,
+                  virtual_sol_reserves AS close_sol, virtual_token_reserves AS close_tokens,
+                  sol_amount AS volume_lamports, 1 AS trades
+           FROM trades
+           WHERE token_mint = $1 AND timestamp >= $3 AND timestamp < $4
+           UNION ALL
+           SELECT bucket, open_sol, open_tokens, high_sol, high_tokens, low_sol, low_tokens,
+                  close_sol, close_tokens, volume_lamports, trades
+           FROM cold_trade_candles
+           WHERE token_mint = $1 AND bucket >= $3 AND bucket < $4
+       )
+       SELECT date_bin($2 * INTERVAL '1 second', at, TIMESTAMPTZ '2000-01-01') AS bucket,
+              (array_agg(open_sol ORDER BY at ASC))[1] AS open_sol,
+              (array_agg(open_tokens ORDER BY at ASC))[1] AS open_tokens,
+              (array_agg(high_sol ORDER BY high_sol::FLOAT8 / NULLIF(high_tokens, 0) DESC NULLS LAST))[1] AS high_sol,
+              (array_agg(high_tokens ORDER BY high_sol::FLOAT8 / NULLIF(high_tokens, 0) DESC NULLS LAST))[1] AS high_tokens,
+              (array_agg(low_sol ORDER BY low_sol::FLOAT8 / NULLIF(low_tokens, 0) ASC NULLS LAST))[1] AS low_sol,
+              (array_agg(low_tokens ORDER BY low_sol::FLOAT8 / NULLIF(low_tokens, 0) ASC NULLS LAST))[1] AS low_tokens,
+              (array_agg(close_sol ORDER BY at DESC))[1] AS close_sol,
+              (array_agg(close_tokens ORDER BY at DESC))[1] AS close_tokens,
+              SUM(volume_lamports)::BIGINT AS volume_lamports,
+              SUM(trades)::BIGINT AS trades
+       FROM points
+       GROUP BY 1
+       ORDER BY 1
+       LIMIT $5"
+    )
+    .bind(&self.mint_address)
+    .bind(interval.num_seconds() as f64)
+    .bind(from)
+    .bind(to)
+    .bind(limit)
+    .fetch_all(ctx.data::<PgPool>()?)
+    .await
+    .map_err(db_error)?;
+
+    let price = |sol: i64, tokens: i64| calculator::calculate_price_sol(sol as u64, tokens as u64, self.decimals as u8);
+
+    Ok(rows.into_iter().map(|r| Candle {
+      timestamp: r.bucket,
+      open: price(r.open_sol, r.open_tokens),
+      high: price(r.high_sol, r.high_tokens),
+      low: price(r.low_sol, r.low_tokens),
+      close: price(r.close_sol, r.close_tokens),
+      volume_sol: calculator::to_ui_amount(r.volume_lamports as u64, calculator::SOL_DECIMALS),
+      trades: r.trades,
+    }).collect())
+  }
+
+  /// Market cap in SOL at the latest trade, or at the close of the latest
+  /// minute tiered out of Postgres once all its trades are.
+  async fn market_cap_sol(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+    let last: Option<(i64, i64)> = sqlx::query_as(
+      "SELECT virtual_sol_reserves, virtual_token_reserves
+       FROM (
+           SELECT timestamp AS at, virtual_sol_reserves, virtual_token_reserves
+           FROM trades WHERE token_mint = $1
+           UNION ALL
+           SELECT bucket, close_sol, close_tokens
+           FROM cold_trade_candles WHERE token_mint = $1
+       ) points
+       ORDER BY at DESC LIMIT 1"
+    )
+    .bind(&self.mint_address)
+    .fetch_optional(ctx.data::<PgPool>()?)
+    .await
+    .map_err(db_error)?;
+
+    Ok(last.map(|(sol, tokens)| {
+      let price = calculator::calculate_price_sol(sol as u64, tokens as u64, self.decimals as u8);
+      calculator::calculate_market_cap_sol(price, self.token_total_supply.unwrap_or(0) as u64, self.decimals as u8)
+    }))
+  }
+}
+
+#[ComplexObject]
+impl Trade {
+  /// Curve price in SOL per token right after this trade.
+  async fn price_sol(&self) -> f64 {
+    calculator::calculate_price_sol(self.virtual_sol_reserves as u64, self.virtual_token_reserves as u64, self.token_decimals as u8)
+  }
+
+  async fn token(&self, ctx: &Context<'_>) -> Result<Option<Token>> {
+    fetch_token(ctx.data::<PgPool>()?, &self.token_mint).await
+  }
+}
+
+#[ComplexObject]
+impl Creator {
+  #[graphql(complexity = "page_cost(first, child_complexity)")]
+  async fn tokens(
+    &self,
+    ctx: &Context<'_>,
+    #[graphql(default_with = "TokenOrder::CreatedAt")] order_by: TokenOrder,
+    #[graphql(default = 20)] first: i64,
+    #[graphql(default)] offset: i64,
+  ) -> Result<TokenConnection> {
+    let db = ctx.data::<PgPool>()?;
+    fetch_tokens(db, Some(&self.wallet), None, order_by, first, offset).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_schema_exposes_root_fields() {
+    let sdl = Schema::build(QueryRoot, EmptyMutation, EmptySubscription).finish().sdl();
+    for field in ["token(", "tokens(", "trades(", "creator(", "candles(", "holders("] {
+      assert!(sdl.contains(field), "missing {}", field);
+    }
+  }
+
+  #[sqlx::test(migrations = "./migrations")]
+  async fn test_nested_lists_are_batched_and_priced(db: PgPool) {
+    for (mint, created) in [("mintA", 2), ("mintB", 1)] {
+      sqlx::query(
+        "INSERT INTO tokens (mint_address, name, symbol, uri, bonding_curve_address, creator_wallet, created_at)
+         VALUES ($1, 'Name', 'SYM', 'uri', 'curve', 'creator', NOW() - make_interval(hours => $2))"
+      )
+      .bind(mint)
+      .bind(created)
+      .execute(&db)
+      .await
+      .unwrap();
+    }
+    for (i, (mint, wallet, is_buy)) in [("mintA", "alice", true), ("mintA", "bob", true), ("mintA", "bob", false), ("mintB", "carol", true)]
+      .into_iter()
+      .enumerate()
+    {
+      sqlx::query(
+        "INSERT INTO trades (signature, token_mint, sol_amount, token_amount, is_buy, user_wallet, timestamp,
+                             virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                             fee_recipient, fee_basis_points, fee, creator, creator_fee_basis_points, creator_fee,
+                             track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume,
+                             last_update_timestamp, ix_name)
+         VALUES ($1, $2, 1000000000, 100, $4, $3, NOW() - make_interval(mins => $5), 1, 1, 1, 1, 'feeRecipient', 0, 0,
+                 'creator', 0, 0, FALSE, 0, 0, 0, NOW(), 'buy')"
+      )
+      .bind(format!("sig{}", i))
+      .bind(mint)
+      .bind(wallet)
+      .bind(is_buy)
+      .bind(10 - i as i32)
+      .execute(&db)
+      .await
+      .unwrap();
+    }
+    let schema = build_schema(db);
+
+    let response = schema
+      .execute("{ tokens { nodes { mintAddress trades(first: 2) { totalCount nodes { signature } } holders { wallet } } } }")
+      .await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    let data = response.data.into_json().unwrap();
+    let (b, a) = (&data["tokens"]["nodes"][0], &data["tokens"]["nodes"][1]);
+    assert_eq!(a["trades"]["totalCount"], 3);
+    assert_eq!(a["trades"]["nodes"], serde_json::json!([{ "signature": "sig2" }, { "signature": "sig1" }]));
+    assert_eq!(a["holders"], serde_json::json!([{ "wallet": "alice" }]));
+    assert_eq!(b["trades"]["totalCount"], 1);
+
+    let fan_out = schema
+      .execute("{ tokens(first: 100) { nodes { trades(first: 100) { nodes { signature } } holders { wallet } } } }")
+      .await;
+    assert!(fan_out.errors[0].message.contains("complex"), "{:?}", fan_out.errors);
+  }
+}
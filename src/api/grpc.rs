@@ -0,0 +1,300 @@
+//! gRPC streaming API for machine consumers, served on `GRPC_PORT` next to
+//! the HTTP server. The schema lives in `proto/pump/v1/pump.proto`; live data
+//! comes from the same shared channels as the WebSocket routes.
+
+#[cfg(feature = "grpc")]
+pub mod proto {
+  //! Messages written to match `pump.proto` field for field; the service
+  //! traits and client are generated by `build.rs`.
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct StreamTradesRequest {
+    #[prost(string, optional, tag = "1")]
+    pub mint: Option<String>,
+    #[prost(double, optional, tag = "2")]
+    pub min_sol: Option<f64>,
+    #[prost(bool, tag = "3")]
+    pub buys_only: bool,
+    #[prost(double, optional, tag = "4")]
+    pub min_market_cap_usd: Option<f64>,
+    #[prost(double, optional, tag = "5")]
+    pub max_market_cap_usd: Option<f64>,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct Trade {
+    #[prost(string, tag = "1")]
+    pub signature: String,
+    #[prost(string, tag = "2")]
+    pub mint: String,
+    #[prost(bool, tag = "3")]
+    pub is_buy: bool,
+    #[prost(uint64, tag = "4")]
+    pub sol_amount: u64,
+    #[prost(uint64, tag = "5")]
+    pub token_amount: u64,
+    #[prost(string, tag = "6")]
+    pub user_wallet: String,
+    #[prost(int64, tag = "7")]
+    pub timestamp: i64,
+    #[prost(double, tag = "8")]
+    pub market_cap_usd: f64,
+    #[prost(double, tag = "9")]
+    pub price_sol: f64,
+    #[prost(uint64, optional, tag = "10")]
+    pub seq: Option<u64>,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct StreamNewTokensRequest {}
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct NewToken {
+    #[prost(string, tag = "1")]
+    pub mint: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub symbol: String,
+    #[prost(string, tag = "4")]
+    pub creator: String,
+    #[prost(int64, tag = "5")]
+    pub timestamp: i64,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct GetTokenRequest {
+    #[prost(string, tag = "1")]
+    pub mint: String,
+  }
+
+  #[derive(Clone, PartialEq, prost::Message)]
+  pub struct Token {
+    #[prost(string, tag = "1")]
+    pub mint: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub symbol: String,
+    #[prost(string, tag = "4")]
+    pub creator: String,
+    #[prost(double, optional, tag = "5")]
+    pub price_sol: Option<f64>,
+    #[prost(double, optional, tag = "6")]
+    pub market_cap_usd: Option<f64>,
+    #[prost(double, optional, tag = "7")]
+    pub bonding_curve_progress: Option<f64>,
+    #[prost(bool, tag = "8")]
+    pub complete: bool,
+  }
+
+  include!(concat!(env!("OUT_DIR"), "/pump.v1.PumpIndexer.rs"));
+}
+
+#[cfg(feature = "grpc")]
+mod server {
+  use anyhow::{Context, Result};
+  use futures::future::{BoxFuture, FutureExt};
+  use futures::stream::{BoxStream, StreamExt};
+  use std::future::ready;
+  use tonic::transport::server::TcpIncoming;
+  use tonic::{Request, Response, Status};
+  use tracing::info;
+  use crate::api::AppState;
+  use crate::api::routes::ws_protocol::TradeFilter;
+  use crate::api::params::parse_pubkey;
+  use crate::dto::{NewTokenMessage, TradeMessage};
+  use super::proto::{self, pump_indexer_server::{PumpIndexer, PumpIndexerServer}};
+
+  impl From<TradeMessage> for proto::Trade {
+    fn from(trade: TradeMessage) -> Self {
+      Self {
+        signature: trade.signature,
+        mint: trade.mint,
+        is_buy: trade.is_buy,
+        sol_amount: trade.sol_amount,
+        token_amount: trade.token_amount,
+        user_wallet: trade.user_wallet,
+        timestamp: trade.timestamp.timestamp(),
+        market_cap_usd: trade.market_cap_usd,
+        price_sol: trade.price_sol,
+        seq: trade.seq,
+      }
+    }
+  }
+
+  impl From<NewTokenMessage> for proto::NewToken {
+    fn from(token: NewTokenMessage) -> Self {
+      Self {
+        mint: token.mint,
+        name: token.name,
+        symbol: token.symbol,
+        creator: token.creator,
+        timestamp: token.timestamp,
+      }
+    }
+  }
+
+  struct PumpService {
+    state: AppState,
+  }
+
+  #[tonic::async_trait]
+  impl PumpIndexer for PumpService {
+    type StreamTradesStream = BoxStream<'static, Result<proto::Trade, Status>>;
+    type StreamNewTokensStream = BoxStream<'static, Result<proto::NewToken, Status>>;
+
+    async fn stream_trades(
+      &self,
+      request: Request<proto::StreamTradesRequest>,
+    ) -> Result<Response<Self::StreamTradesStream>, Status> {
+      let request = request.into_inner();
+      let filter = TradeFilter {
+        min_sol: request.min_sol,
+        buys_only: request.buys_only,
+        min_market_cap_usd: request.min_market_cap_usd,
+        max_market_cap_usd: request.max_market_cap_usd,
+      };
+      filter.validate().map_err(Status::invalid_argument)?;
+
+      let mint = match request.mint.filter(|mint| !mint.is_empty()) {
+        Some(mint) => Some(parse_pubkey(&mint).ok_or_else(|| Status::invalid_argument("mint must be a base58 pubkey"))?),
+        None => None,
+      };
+      let channel = match mint {
+        Some(mint) => format!("pump:trades:{}", mint),
+        None => "pump:trades".to_string(),
+      };
+      info!("📡 New gRPC trade stream: {}", channel);
+
+      let trades = self.state.pubsub.subscribe_stream(&channel).filter_map(move |payload| {
+        let trade = serde_json::from_str::<TradeMessage>(&payload)
+          .ok()
+          .filter(|trade| filter.matches(trade))
+          .map(|trade| Ok(proto::Trade::from(trade)));
+        ready(trade)
+      });
+
+      Ok(Response::new(trades.boxed()))
+    }
+
+    async fn stream_new_tokens(
+      &self,
+      _request: Request<proto::StreamNewTokensRequest>,
+    ) -> Result<Response<Self::StreamNewTokensStream>, Status> {
+      info!("📡 New gRPC token stream");
+
+      let tokens = self.state.pubsub.subscribe_stream("pump:tokens:new").filter_map(|payload| {
+        let token = serde_json::from_str::<NewTokenMessage>(&payload)
+          .ok()
+          .map(|token| Ok(proto::NewToken::from(token)));
+        ready(token)
+      });
+
+      Ok(Response::new(tokens.boxed()))
+    }
+
+    async fn get_token(
+      &self,
+      request: Request<proto::GetTokenRequest>,
+    ) -> Result<Response<proto::Token>, Status> {
+      let mint = request.into_inner().mint;
+
+      if let Some(token) = self.state.token_state.read().await.get(&mint) {
+        return Ok(Response::new(proto::Token {
+          mint: token.mint.clone(),
+          name: token.name.clone(),
+          symbol: token.symbol.clone(),
+          creator: token.creator.clone(),
+          price_sol: Some(token.current_price_sol),
+          market_cap_usd: Some(token.market_cap_usd),
+          bonding_curve_progress: Some(token.bonding_curve_progress),
+          complete: token.complete,
+        }));
+      }
+
+      let row = sqlx::query_as::<_, (String, String, String, String, Option<f64>, Option<f64>, bool)>(
+        "SELECT mint_address, name, symbol, creator_wallet,
+                market_cap_usd::float8, bonding_curve_progress::float8, complete
+         FROM tokens
+         WHERE mint_address = $1"
+      )
+      .bind(&mint)
+      .fetch_optional(&self.state.db)
+      .await
+      .map_err(|e| {
+        tracing::error!("Database error: {}", e);
+        Status::internal("Database error")
+      })?;
+
+      let Some((mint, name, symbol, creator, market_cap_usd, bonding_curve_progress, complete)) = row else {
+        return Err(Status::not_found("Token not found"));
+      };
+
+      Ok(Response::new(proto::Token {
+        mint,
+        name,
+        symbol,
+        creator,
+        price_sol: None,
+        market_cap_usd,
+        bonding_curve_progress,
+        complete,
+      }))
+    }
+  }
+
+  /// Binds the port up front so a taken port fails startup; the returned
+  /// future serves until it errors.
+  pub async fn bind(state: AppState, port: u16) -> Result<BoxFuture<'static, Result<()>>> {
+    let addr = format!("0.0.0.0:{}", port).parse().context("Invalid gRPC address")?;
+    let incoming = TcpIncoming::bind(addr).with_context(|| format!("Failed to bind gRPC server to {}", addr))?;
+    info!("🛰️  Starting gRPC server on {}", addr);
+
+    let server = tonic::transport::Server::builder()
+      .add_service(PumpIndexerServer::new(PumpService { state }))
+      .serve_with_incoming(incoming);
+    Ok(async move { server.await.context("gRPC server failed") }.boxed())
+  }
+}
+
+#[cfg(not(feature = "grpc"))]
+mod server {
+  use anyhow::{bail, Result};
+  use futures::future::BoxFuture;
+  use crate::api::AppState;
+
+  pub async fn bind(_state: AppState, _port: u16) -> Result<BoxFuture<'static, Result<()>>> {
+    bail!("GRPC_PORT is set but the indexer was built without the `grpc` feature")
+  }
+}
+
+pub use server::bind;
+
+#[cfg(all(test, feature = "grpc"))]
+mod tests {
+  use super::proto;
+  use crate::dto::TradeMessage;
+  use prost::Message;
+
+  #[test]
+  fn test_trade_roundtrip() {
+    let trade = proto::Trade::from(TradeMessage {
+      signature: "sig".to_string(),
+      mint: "Mint".to_string(),
+      is_buy: true,
+      sol_amount: 1_500_000_000,
+      token_amount: 42,
+      user_wallet: "User".to_string(),
+      timestamp: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+      market_cap_usd: 12_000.0,
+      price_sol: 0.000001,
+      seq: None,
+    });
+
+    assert_eq!(trade.timestamp, 1_700_000_000);
+    let decoded = proto::Trade::decode(trade.encode_to_vec().as_slice()).unwrap();
+    assert_eq!(decoded, trade);
+    assert_eq!(decoded.seq, None);
+  }
+}
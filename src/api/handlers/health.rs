@@ -1,9 +1,68 @@
 use axum::{
+  extract::State,
   http::StatusCode,
   response::Json,
 };
+use serde::Serialize;
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use crate::api::AppState;
+use crate::api::freshness::MAX_PROCESSING_LAG_SECS;
+use crate::background::supervisor::{TaskState, TaskStatus};
 
+/// Each dependency check gives up after this long and reports a failure.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+/// No frame from Helius for this long means the subscription is stuck.
+const LISTENER_STALE_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+  Ok,
+  Degraded,
+  Fail,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+  status: CheckStatus,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  latency_ms: Option<u128>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  detail: Option<String>,
+}
+
+impl Check {
+  fn ok(latency_ms: u128) -> Self {
+    Self { status: CheckStatus::Ok, latency_ms: Some(latency_ms), detail: None }
+  }
+
+  fn with_status(status: CheckStatus, detail: String) -> Self {
+    Self { status, latency_ms: None, detail: Some(detail) }
+  }
+}
+
+async fn timed<F, E>(check: F) -> Check
+where
+  F: Future<Output = Result<(), E>>,
+  E: std::fmt::Display,
+{
+  let started = Instant::now();
+  match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+    Ok(Ok(())) => Check::ok(started.elapsed().as_millis()),
+    Ok(Err(e)) => Check::with_status(CheckStatus::Fail, e.to_string()),
+    Err(_) => Check::with_status(CheckStatus::Fail, "timed out".to_string()),
+  }
+}
+
+#[utoipa::path(
+  get,
+  path = "/health",
+  tag = "health",
+  responses((status = 200, description = "Service is up"))
+)]
 pub async fn health_check() -> (StatusCode, Json<Value>) {
   (
     StatusCode::OK,
@@ -13,4 +72,134 @@ pub async fn health_check() -> (StatusCode, Json<Value>) {
       "timestamp": chrono::Utc::now(),
     }))
   )
-}
\ No newline at end of file
+}
+
+/// Liveness only says the process is serving requests; dependency outages
+/// shouldn't get the pod restarted.
+#[utoipa::path(
+  get,
+  path = "/health/live",
+  tag = "health",
+  responses((status = 200, description = "Process is alive"))
+)]
+pub async fn liveness() -> (StatusCode, Json<Value>) {
+  (StatusCode::OK, Json(json!({ "status": "ok" })))
+}
+
+#[utoipa::path(
+  get,
+  path = "/health/ready",
+  tag = "health",
+  responses(
+    (status = 200, description = "All dependencies healthy (possibly degraded)"),
+    (status = 503, description = "Postgres, Redis or the Helius listener is down"),
+  )
+)]
+pub async fn readiness(State(state): State<AppState>) -> (StatusCode, Json<Value>) {
+  let postgres = timed(async {
+    sqlx::query("SELECT 1").execute(&state.db).await.map(|_| ())
+  }).await;
+
+  let redis = match state.redis.clone() {
+    Some(mut redis) => timed(async move { redis.ping().await }).await,
+    None => Check::with_status(CheckStatus::Ok, "not used (EVENT_TRANSPORT=postgres)".to_string()),
+  };
+
+  let listener = listener_check(&state);
+  let lag = lag_check(&state).await;
+  let tasks = state.supervisor.statuses();
+  let background = tasks_check(&tasks);
+  let ingestion = if state.ingestion.is_paused() {
+    Check::with_status(CheckStatus::Degraded, "paused via admin API".to_string())
+  } else {
+    Check::with_status(CheckStatus::Ok, "running".to_string())
+  };
+
+  let checks = [&postgres, &redis, &listener, &lag, &background, &ingestion];
+  let overall = if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+    CheckStatus::Fail
+  } else if checks.iter().any(|c| c.status == CheckStatus::Degraded) {
+    CheckStatus::Degraded
+  } else {
+    CheckStatus::Ok
+  };
+
+  let code = match overall {
+    CheckStatus::Fail => StatusCode::SERVICE_UNAVAILABLE,
+    _ => StatusCode::OK,
+  };
+
+  (
+    code,
+    Json(json!({
+      "status": overall,
+      "timestamp": chrono::Utc::now(),
+      "checks": {
+        "postgres": postgres,
+        "redis": redis,
+        "helius_listener": listener,
+        "processing_lag": lag,
+        "background_tasks": background,
+        "ingestion": ingestion,
+      },
+      "tasks": tasks,
+    }))
+  )
+}
+
+fn listener_check(state: &AppState) -> Check {
+  if !state.listener.is_subscribed() {
+    return Check::with_status(CheckStatus::Fail, "not subscribed".to_string());
+  }
+
+  match state.listener.last_message_at() {
+    Some(ts) => {
+      let silent_for = chrono::Utc::now().timestamp() - ts;
+      if silent_for > LISTENER_STALE_SECS {
+        Check::with_status(CheckStatus::Fail, format!("no messages for {}s", silent_for))
+      } else {
+        Check::with_status(CheckStatus::Ok, format!("last message {}s ago", silent_for))
+      }
+    }
+    None => Check::with_status(CheckStatus::Ok, "subscribed, no messages yet".to_string()),
+  }
+}
+
+/// A task waiting to be restarted degrades the service; the listener's own
+/// check already fails readiness when ingestion stops.
+fn tasks_check(tasks: &BTreeMap<&'static str, TaskStatus>) -> Check {
+  let restarting: Vec<String> = tasks
+    .iter()
+    .filter(|(_, status)| status.state == TaskState::Restarting)
+    .map(|(name, status)| format!("{} ({})", name, status.last_error.as_deref().unwrap_or("stopped")))
+    .collect();
+
+  if restarting.is_empty() {
+    Check::with_status(CheckStatus::Ok, format!("{} running", tasks.len()))
+  } else {
+    Check::with_status(CheckStatus::Degraded, format!("restarting: {}", restarting.join(", ")))
+  }
+}
+
+/// Lag is measured from the newest stored transaction's block time, so it
+/// covers the whole listener -> fetch -> save pipeline.
+async fn lag_check(state: &AppState) -> Check {
+  let latest = tokio::time::timeout(
+    CHECK_TIMEOUT,
+    sqlx::query_as::<_, (Option<chrono::DateTime<chrono::Utc>>,)>(
+      "SELECT MAX(block_time) FROM transactions"
+    )
+    .fetch_one(&state.db),
+  ).await;
+
+  match latest {
+    Ok(Ok((Some(block_time),))) => {
+      let lag = (chrono::Utc::now() - block_time).num_seconds();
+      let status = if lag > MAX_PROCESSING_LAG_SECS { CheckStatus::Degraded } else { CheckStatus::Ok };
+      Check::with_status(status, format!("{}s behind chain", lag))
+    }
+    Ok(Ok((None,))) => Check::with_status(CheckStatus::Degraded, "no transactions indexed yet".to_string()),
+    Ok(Err(e)) => Check::with_status(CheckStatus::Degraded, e.to_string()),
+    Err(_) => Check::with_status(CheckStatus::Degraded, "timed out".to_string()),
+  }
+}
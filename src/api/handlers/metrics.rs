@@ -0,0 +1,48 @@
+use axum::{
+  extract::State,
+  http::header,
+  response::IntoResponse,
+};
+use std::collections::BTreeMap;
+use crate::api::AppState;
+
+pub async fn prometheus_metrics(State(state): State<AppState>) -> impl IntoResponse {
+  let mut body = state.metrics.render_prometheus();
+  body.push_str(&state.supervisor.render_prometheus());
+
+  body.push_str("# HELP pump_tokens_in_state Tokens tracked in live state\n");
+  body.push_str("# TYPE pump_tokens_in_state gauge\n");
+  body.push_str(&format!("pump_tokens_in_state {}\n", state.token_state.read().await.len()));
+
+  body.push_str("# HELP pump_sol_price_usd SOL price used for USD conversions\n");
+  body.push_str("# TYPE pump_sol_price_usd gauge\n");
+  body.push_str(&format!("pump_sol_price_usd {}\n", *state.sol_price.read().await));
+
+  body.push_str("# HELP pump_db_pool_connections Open Postgres connections\n");
+  body.push_str("# TYPE pump_db_pool_connections gauge\n");
+  body.push_str(&format!("pump_db_pool_connections {}\n", state.db.size()));
+
+  // Per-mint trade channels are summed into one series to bound cardinality.
+  let mut subscribers: BTreeMap<&str, usize> = BTreeMap::new();
+  let counts = state.pubsub.subscriber_counts();
+  for (channel, count) in &counts {
+    let label = match channel.as_str() {
+      "pump:trades" | "pump:tokens:new" | "pump:completions" | "pump:tracked" | "pump:koth" => channel.as_str(),
+      _ if channel.starts_with("pump:trades:") => "pump:trades:{mint}",
+      _ => "other",
+    };
+    *subscribers.entry(label).or_default() += count;
+  }
+
+  body.push_str("# HELP pump_pubsub_channels Redis channels with a shared subscriber\n");
+  body.push_str("# TYPE pump_pubsub_channels gauge\n");
+  body.push_str(&format!("pump_pubsub_channels {}\n", counts.len()));
+
+  body.push_str("# HELP pump_channel_subscribers WebSocket subscriptions and SSE clients per channel\n");
+  body.push_str("# TYPE pump_channel_subscribers gauge\n");
+  for (channel, count) in subscribers {
+    body.push_str(&format!("pump_channel_subscribers{{channel=\"{}\"}} {}\n", channel, count));
+  }
+
+  ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
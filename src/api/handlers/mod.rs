@@ -1 +1,2 @@
-pub mod health;
\ No newline at end of file
+pub mod health;
+pub mod metrics;
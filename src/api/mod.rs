@@ -1,36 +1,163 @@
 pub mod routes;
 pub mod handlers;
+pub mod conditional;
+pub mod error;
+pub mod extract;
+pub mod freshness;
+pub mod params;
+pub mod openapi;
+pub mod graphql;
+pub mod grpc;
+pub mod rate_limit;
 
 use axum::{
   Router,
+  middleware,
   routing::get,
 };
-use tower_http::cors::{CorsLayer, Any};
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+use tower::ServiceBuilder;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::TraceLayer;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 use sqlx::PgPool;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use anyhow::Context;
+use futures::future::{BoxFuture, FutureExt};
+use tracing::info;
+use crate::config::{ApiConfig, CorsConfig};
 use crate::processor::state::TokenStateMap;
-use crate::storage::RedisClient;
+use crate::storage::{PubSubHub, RedisClient};
 
 
 #[derive(Clone)]
 pub struct AppState {
   pub db: PgPool,
-  pub redis: RedisClient,
+  /// Absent when `EVENT_TRANSPORT=postgres`; caches are then skipped.
+  pub redis: Option<RedisClient>,
+  /// Shared live-event subscriptions fanned out to WebSocket and SSE clients.
+  pub pubsub: Arc<PubSubHub>,
   pub token_state: TokenStateMap,
   pub sol_price: Arc<tokio::sync::RwLock<f64>>,
+  pub rate_limiter: Arc<rate_limit::RateLimiter>,
+  pub listener: Arc<crate::helius::ListenerStatus>,
+  pub metrics: crate::processor::metrics::Metrics,
+  pub ws_limits: crate::config::WsLimits,
+  /// Settings changeable at runtime via `/api/admin/config` or SIGHUP.
+  pub settings: crate::config::SharedSettings,
+  /// Required by the `/api/admin` routes, which are disabled when unset.
+  pub admin_token: Option<Arc<str>>,
+  /// Background tasks, reported by `/health/ready` and `/metrics`.
+  pub supervisor: crate::background::Supervisor,
+  /// Helius RPC, used by admin resync, metadata and backfill routes.
+  pub rpc: Arc<solana_client::nonblocking::rpc_client::RpcClient>,
+  pub ingestion: crate::processor::control::IngestionControl,
+  /// Wallets whose trades are re-published on `pump:tracked`.
+  pub tracked_wallets: crate::processor::tracked::TrackedWallets,
+  /// Creators flagged by operators, checked when listing tokens.
+  pub creator_lists: crate::processor::creator_lists::CreatorLists,
+  pub koth: crate::processor::koth::Koth,
+  /// Latest trades, serving the first pages of trade listings.
+  pub recent_trades: crate::processor::recent_trades::RecentTrades,
+  /// The installed log filter, adjustable via `/api/admin/log-filter`.
+  pub log_filter: crate::logging::LogFilter,
 }
 
-pub fn create_router(state: AppState) -> Router {
+pub fn create_router(state: AppState, cors: &CorsConfig) -> Router {
   Router::new()
     .route("/health", get(handlers::health::health_check))
+    .route("/health/live", get(handlers::health::liveness))
+    .route("/health/ready", get(handlers::health::readiness))
+    .route("/metrics", get(handlers::metrics::prometheus_metrics))
 
-    .nest("/api", routes::create_api_routes())
+    .nest("/api", routes::create_api_routes(&state))
 
+    .route("/ws", get(routes::websocket::unified_websocket))
     .nest("/ws", routes::create_ws_routes())
 
-    .layer(CorsLayer::new().allow_origin(Any))
-    .layer(TraceLayer::new_for_http())
+    .merge(graphql::create_graphql_routes(graphql::build_schema(state.db.clone())))
+
+    .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", openapi::ApiDoc::openapi()))
+
+    .layer(middleware::from_fn_with_state(state.clone(), rate_limit::enforce))
+    .layer(cors_layer(cors))
+    // The default predicate skips small bodies, images and SSE streams.
+    .layer(CompressionLayer::new().gzip(true).br(true))
+    .layer(
+      ServiceBuilder::new()
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+        .layer(TraceLayer::new_for_http().make_span_with(request_span))
+        .layer(PropagateRequestIdLayer::x_request_id())
+    )
 
     .with_state(state)
-}
\ No newline at end of file
+}
+
+fn cors_layer(config: &CorsConfig) -> CorsLayer {
+  let origin = if config.any_origin {
+    AllowOrigin::any()
+  } else {
+    AllowOrigin::list(config.allowed_origins.clone())
+  };
+  let headers = if config.any_header {
+    AllowHeaders::any()
+  } else {
+    AllowHeaders::list(config.allowed_headers.clone())
+  };
+  let methods = if config.any_method {
+    AllowMethods::any()
+  } else {
+    AllowMethods::list(config.allowed_methods.clone())
+  };
+
+  CorsLayer::new().allow_origin(origin).allow_headers(headers).allow_methods(methods)
+}
+
+/// Serves the router on `API_BIND:API_PORT`, over HTTPS when TLS is
+/// configured.
+/// Binds the listener and loads TLS up front, so a bad address or
+/// certificate fails startup; the returned future serves until it errors.
+pub async fn bind(router: Router, config: &ApiConfig) -> anyhow::Result<BoxFuture<'static, std::io::Result<()>>> {
+  let addr = SocketAddr::new(config.bind, config.port);
+  let service = router.into_make_service_with_connect_info::<SocketAddr>();
+  let listener = tokio::net::TcpListener::bind(addr)
+    .await
+    .with_context(|| format!("Failed to bind API server to {}", addr))?;
+
+  match &config.tls {
+    Some(tls) => {
+      // Several rustls providers can end up linked in; pick one explicitly.
+      let _ = rustls::crypto::ring::default_provider().install_default();
+      let rustls = axum_server::tls_rustls::RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+        .await
+        .with_context(|| format!("Failed to load TLS certificate {}", tls.cert_path.display()))?;
+
+      info!("🔒 Starting API server on https://{}", addr);
+      Ok(axum_server::from_tcp_rustls(listener.into_std()?, rustls).serve(service).boxed())
+    }
+    None => {
+      info!("🌐 Starting API server on http://{}", addr);
+      Ok(async move { axum::serve(listener, service).await }.boxed())
+    }
+  }
+}
+
+/// Tags every log line emitted while serving a request with its ID, which
+/// is also echoed back to the client in `x-request-id`.
+fn request_span(request: &axum::http::Request<axum::body::Body>) -> tracing::Span {
+  let request_id = request
+    .headers()
+    .get("x-request-id")
+    .and_then(|v| v.to_str().ok())
+    .unwrap_or("-");
+
+  tracing::info_span!(
+    "request",
+    method = %request.method(),
+    uri = %request.uri(),
+    request_id = %request_id,
+  )
+}
@@ -0,0 +1,197 @@
+//! OpenAPI description of the REST API, served at `/api/openapi.json` with a
+//! Swagger UI at `/api/docs`.
+//!
+//! Handlers return the types their `responses` name, wrapped in
+//! `dto::WithMeta` for the `meta` that `api::freshness` attaches, so the
+//! spec is generated from the bodies actually served. Shapes shared with
+//! clients live in `crate::dto`.
+
+use utoipa::OpenApi;
+
+use crate::api::error::{ErrorBody, ErrorResponse};
+use crate::api::handlers;
+use crate::api::routes::{admin, creators, export, history, koth, leaderboards, state, stats, stream, timeline, tokens, trades, transactions, wallets};
+use crate::background::leaderboards::{
+  CreatorLeaderboardEntry, TokenLeaderboardEntry, TraderLeaderboardEntry,
+};
+use crate::background::token_backfill::TokenBackfillRequest;
+use crate::config::RuntimeSettings;
+use crate::database::model::{CreatorListEntry, IndexerSession, KothReign, TrackedWallet};
+use crate::dto::{AsOf, Pagination, ResponseMeta, TokenDetail, TokenResponse, TradeResponse};
+use crate::processor::creator_lists::CreatorList;
+use crate::processor::koth::King;
+use crate::processor::momentum::Momentum;
+use crate::processor::state::TokenState;
+
+#[derive(OpenApi)]
+#[openapi(
+  info(
+    title = "Pump.fun Indexer API",
+    description = "Tokens, trades and analytics indexed from the pump.fun bonding curve program.",
+  ),
+  paths(
+    handlers::health::health_check,
+    handlers::health::liveness,
+    handlers::health::readiness,
+    tokens::list_tokens,
+    tokens::list_graduated_tokens,
+    tokens::get_token,
+    koth::get_koth,
+    trades::get_recent_trades,
+    trades::get_token_trades,
+    trades::get_token_slippage,
+    history::get_token_history,
+    history::get_holder_history,
+    timeline::get_token_timeline,
+    state::get_token_state,
+    state::get_token_state_at,
+    state::get_state_snapshot,
+    creators::get_creator_tokens,
+    creators::get_creator_earnings,
+    wallets::get_related_wallets,
+    transactions::get_wallet_transactions,
+    transactions::get_transaction,
+    stats::get_stats,
+    stats::get_timeseries,
+    stats::get_fees,
+    stats::get_funnel,
+    stats::get_sessions,
+    leaderboards::token_leaderboard,
+    leaderboards::trader_leaderboard,
+    leaderboards::creator_leaderboard,
+    stream::stream_all_trades,
+    stream::stream_token_trades,
+    export::export_trades,
+    admin::get_runtime_config,
+    admin::put_runtime_config,
+    admin::get_log_filter,
+    admin::put_log_filter,
+    admin::get_snapshot,
+    admin::resync_token,
+    admin::refresh_token_state,
+    admin::evict_token_state,
+    admin::refresh_token_metadata,
+    admin::start_backfill,
+    admin::get_ingestion,
+    admin::pause_ingestion,
+    admin::resume_ingestion,
+    admin::list_tracked_wallets,
+    admin::track_wallet,
+    admin::untrack_wallet,
+    admin::list_creator_lists,
+    admin::list_creator,
+    admin::unlist_creator,
+  ),
+  components(schemas(
+    Pagination,
+    TokenResponse,
+    TokenDetail,
+    tokens::GraduatedTokenResponse,
+    TradeResponse,
+    trades::SlippageStats,
+    history::HistoryPoint,
+    history::HolderSnapshot,
+    timeline::TimelineEvent,
+    creators::CreatorTokenResponse,
+    creators::EarningsPoint,
+    creators::TokenEarnings,
+    wallets::RelatedWallet,
+    transactions::TransactionDetail,
+    transactions::TransactionEvent,
+    stats::StatsRates,
+    stats::ActivityRates,
+    stats::TimeseriesPoint,
+    stats::FeesPoint,
+    stats::FeeRecipientTotal,
+    stats::TokenFeeTotal,
+    stats::FunnelCohort,
+    stats::FunnelRates,
+    TokenState,
+    state::HistoricalState,
+    Momentum,
+    King,
+    KothReign,
+    IndexerSession,
+    TokenLeaderboardEntry,
+    TraderLeaderboardEntry,
+    CreatorLeaderboardEntry,
+    RuntimeSettings,
+    TokenBackfillRequest,
+    TrackedWallet,
+    admin::LogFilterBody,
+    admin::TrackWalletRequest,
+    CreatorListEntry,
+    CreatorList,
+    admin::ListCreatorRequest,
+    ErrorResponse,
+    ErrorBody,
+    ResponseMeta,
+    AsOf,
+  )),
+  tags(
+    (name = "health"),
+    (name = "tokens", description = "Token listings and per-token data"),
+    (name = "trades"),
+    (name = "state", description = "Live in-memory bonding curve state"),
+    (name = "creators"),
+    (name = "wallets", description = "Links between wallets for sybil and rug analysis"),
+    (name = "transactions", description = "Stored transactions with the events parsed from them"),
+    (name = "stats", description = "Market-wide counters and time series"),
+    (name = "leaderboards", description = "Rankings refreshed every 30s"),
+    (name = "stream", description = "Server-Sent Events alternative to the WebSocket API"),
+    (name = "admin", description = "Runtime settings; requires `Authorization: Bearer <ADMIN_TOKEN>`"),
+  )
+)]
+pub struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_spec_lists_all_routes() {
+    let spec = ApiDoc::openapi();
+    for path in [
+      "/health",
+      "/health/ready",
+      "/api/tokens",
+      "/api/tokens/graduated",
+      "/api/tokens/{mint}",
+      "/api/koth",
+      "/api/trades/recent",
+      "/api/tokens/{mint}/trades",
+      "/api/tokens/{mint}/slippage",
+      "/api/tokens/{mint}/history",
+      "/api/tokens/{mint}/holders/history",
+      "/api/tokens/{mint}/timeline",
+      "/api/tokens/{mint}/state",
+      "/api/tokens/{mint}/at",
+      "/api/state/snapshot",
+      "/api/creators/{wallet}",
+      "/api/creators/{wallet}/earnings",
+      "/api/wallets/{wallet}/related",
+      "/api/wallets/{wallet}/transactions",
+      "/api/transactions/{signature}",
+      "/api/stats",
+      "/api/stats/timeseries",
+      "/api/stats/fees",
+      "/api/stats/funnel",
+      "/api/stats/sessions",
+      "/api/leaderboards/tokens",
+      "/api/leaderboards/traders",
+      "/api/leaderboards/creators",
+      "/api/stream/trades",
+      "/api/stream/trades/{mint}",
+      "/api/export/trades",
+    ] {
+      assert!(spec.paths.paths.contains_key(path), "missing {}", path);
+    }
+
+    // Bodies are described with the `meta` the freshness layer adds
+    let koth = serde_json::to_value(&spec.paths.paths["/api/koth"]).unwrap();
+    assert_eq!(
+      koth["get"]["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+      "#/components/schemas/WithMeta_KothResponse"
+    );
+  }
+}
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Duration, Utc};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::str::FromStr;
+
+/// Parses interval strings such as `5m`, `1h` or `1d` used by the
+/// time-series endpoints. Amounts too large for a `Duration` are rejected.
+pub fn parse_interval(s: &str) -> Option<Duration> {
+  let s = s.trim();
+  if s.len() < 2 {
+    return None;
+  }
+
+  let (amount, unit) = s.split_at(s.len() - 1);
+  let amount: i64 = amount.parse().ok().filter(|n| *n > 0)?;
+
+  match unit {
+    "m" => Duration::try_minutes(amount),
+    "h" => Duration::try_hours(amount),
+    "d" => Duration::try_days(amount),
+    _ => None,
+  }
+}
+
+/// Start of a window of `points` intervals ending at `to`, or `None` when
+/// it falls outside the range timestamps can represent.
+pub fn window_start(to: DateTime<Utc>, interval: Duration, points: i32) -> Option<DateTime<Utc>> {
+  to.checked_sub_signed(interval.checked_mul(points)?)
+}
+
+/// Parses a base58 account address, returning its canonical form.
+pub fn parse_pubkey(s: &str) -> Option<String> {
+  Pubkey::from_str(s.trim()).ok().map(|key| key.to_string())
+}
+
+/// Parses a base58 transaction signature, returning its canonical form.
+pub fn parse_signature(s: &str) -> Option<String> {
+  Signature::from_str(s.trim()).ok().map(|sig| sig.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_interval() {
+    assert_eq!(parse_interval("5m"), Some(Duration::minutes(5)));
+    assert_eq!(parse_interval("1h"), Some(Duration::hours(1)));
+    assert_eq!(parse_interval("7d"), Some(Duration::days(7)));
+    assert_eq!(parse_interval("0h"), None);
+    assert_eq!(parse_interval("h"), None);
+    assert_eq!(parse_interval("10s"), None);
+    assert_eq!(parse_interval("200000000000d"), None);
+    assert_eq!(parse_interval("9223372036854775807m"), None);
+  }
+
+  #[test]
+  fn test_window_start_out_of_range() {
+    let to = Utc::now();
+    assert_eq!(window_start(to, Duration::hours(1), 2), Some(to - Duration::hours(2)));
+
+    let huge = parse_interval("100000000d").unwrap();
+    assert_eq!(window_start(to, huge, 1), None);
+    assert_eq!(window_start(to, Duration::days(10_000_000), 288), None);
+  }
+
+  #[test]
+  fn test_parse_pubkey_and_signature() {
+    let program = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+    assert_eq!(parse_pubkey(&format!(" {} ", program)).as_deref(), Some(program));
+    assert_eq!(parse_pubkey("mint"), None);
+    assert_eq!(parse_pubkey("0OIl0OIl0OIl0OIl0OIl0OIl0OIl0OIl0OIl0OIl0OI"), None);
+
+    let signature = Signature::from([7u8; 64]).to_string();
+    assert_eq!(parse_signature(&signature), Some(signature));
+    assert_eq!(parse_signature(program), None);
+  }
+}
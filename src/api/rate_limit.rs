@@ -0,0 +1,257 @@
+//! Per-client rate limiting for the HTTP API.
+//!
+//! Clients are identified by their `x-api-key` header when it is one of the
+//! configured `api_keys`, otherwise by IP. Each client gets a fixed
+//! one-minute window per route class, so a burst of cheap reads doesn't eat
+//! into the budget for aggregations or stream connects.
+
+use axum::{
+  extract::{ConnectInfo, Request, State},
+  http::{HeaderMap, HeaderValue},
+  middleware::Next,
+  response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::config::SharedSettings;
+
+const WINDOW: Duration = Duration::from_secs(60);
+/// Expired windows are swept once the table grows past this many clients.
+const SWEEP_THRESHOLD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteClass {
+  Read,
+  Heavy,
+  Stream,
+}
+
+impl RouteClass {
+  pub fn for_path(path: &str) -> Self {
+    if path.starts_with("/ws") || path.starts_with("/api/stream") {
+      Self::Stream
+    } else if path.starts_with("/graphql")
+      || path.starts_with("/api/stats/timeseries")
+      || path.starts_with("/api/stats/fees")
+      || path.starts_with("/api/stats/funnel")
+      || path.starts_with("/api/leaderboards")
+      || path.starts_with("/api/creators")
+      || path.starts_with("/api/wallets")
+      || path.starts_with("/api/export")
+      || path.starts_with("/api/state/snapshot")
+      || path.ends_with("/history")
+      || path.ends_with("/slippage")
+      || path.ends_with("/at")
+    {
+      Self::Heavy
+    } else {
+      Self::Read
+    }
+  }
+
+  fn as_str(&self) -> &'static str {
+    match self {
+      Self::Read => "read",
+      Self::Heavy => "heavy",
+      Self::Stream => "stream",
+    }
+  }
+}
+
+struct Window {
+  started: Instant,
+  count: u32,
+}
+
+pub enum Decision {
+  Allowed { limit: u32, remaining: u32, reset: Duration },
+  Limited { limit: u32, reset: Duration },
+}
+
+/// Limits are read from the runtime settings on every request, so changes
+/// apply immediately; windows already open keep their counts.
+pub struct RateLimiter {
+  settings: SharedSettings,
+  windows: Mutex<HashMap<(String, RouteClass), Window>>,
+}
+
+impl RateLimiter {
+  pub fn new(settings: SharedSettings) -> Self {
+    Self { settings, windows: Mutex::new(HashMap::new()) }
+  }
+
+  fn limit_for(&self, class: RouteClass) -> u32 {
+    let config = &self.settings.read().rate_limit;
+    match class {
+      RouteClass::Read => config.read_per_minute,
+      RouteClass::Heavy => config.heavy_per_minute,
+      RouteClass::Stream => config.stream_per_minute,
+    }
+  }
+
+  pub fn check(&self, client: &str, class: RouteClass, now: Instant) -> Decision {
+    let limit = self.limit_for(class);
+    let mut windows = self.windows.lock().unwrap();
+
+    if windows.len() > SWEEP_THRESHOLD {
+      windows.retain(|_, w| now.duration_since(w.started) < WINDOW);
+    }
+
+    let window = windows
+      .entry((client.to_string(), class))
+      .or_insert(Window { started: now, count: 0 });
+
+    if now.duration_since(window.started) >= WINDOW {
+      window.started = now;
+      window.count = 0;
+    }
+
+    let reset = WINDOW.saturating_sub(now.duration_since(window.started));
+
+    if window.count >= limit {
+      return Decision::Limited { limit, reset };
+    }
+
+    window.count += 1;
+    Decision::Allowed { limit, remaining: limit - window.count, reset }
+  }
+}
+
+/// Unknown keys are ignored, so a client can't dodge its IP's limits (or
+/// grow the window table) by sending a fresh key with every request. Behind
+/// a proxy the last `X-Forwarded-For` entry is used: the proxy appends the
+/// address it saw, while anything before it came from the client.
+fn client_key(request: &Request, trust_proxy: bool, api_keys: &[String]) -> String {
+  let headers = request.headers();
+
+  if let Some(key) = headers.get("x-api-key").and_then(|v| v.to_str().ok())
+    && api_keys.iter().any(|known| known == key)
+  {
+    return format!("key:{}", key);
+  }
+
+  if trust_proxy
+    && let Some(ip) = headers
+      .get("x-forwarded-for")
+      .and_then(|v| v.to_str().ok())
+      .and_then(|v| v.rsplit(',').next())
+  {
+    return format!("ip:{}", ip.trim());
+  }
+
+  request
+    .extensions()
+    .get::<ConnectInfo<SocketAddr>>()
+    .map(|ConnectInfo(addr)| format!("ip:{}", addr.ip()))
+    .unwrap_or_else(|| "ip:unknown".to_string())
+}
+
+fn set_headers(headers: &mut HeaderMap, limit: u32, remaining: u32, reset: Duration) {
+  headers.insert("x-ratelimit-limit", HeaderValue::from(limit));
+  headers.insert("x-ratelimit-remaining", HeaderValue::from(remaining));
+  headers.insert("x-ratelimit-reset", HeaderValue::from(reset.as_secs().max(1)));
+}
+
+pub async fn enforce(State(state): State<AppState>, request: Request, next: Next) -> Response {
+  let limiter = &state.rate_limiter;
+  let path = request.uri().path();
+  let (enabled, client) = {
+    let config = &limiter.settings.read().rate_limit;
+    (config.enabled, client_key(&request, config.trust_proxy, &config.api_keys))
+  };
+  if !enabled || path.starts_with("/health") || path == "/metrics" {
+    return next.run(request).await;
+  }
+
+  let class = RouteClass::for_path(request.uri().path());
+
+  match limiter.check(&client, class, Instant::now()) {
+    Decision::Allowed { limit, remaining, reset } => {
+      let mut response = next.run(request).await;
+      set_headers(response.headers_mut(), limit, remaining, reset);
+      response
+    }
+    Decision::Limited { limit, reset } => {
+      tracing::debug!("Rate limited {} on {} routes", client, class.as_str());
+
+      let mut response =
+        ApiError::RateLimited(format!("Rate limit exceeded for {} requests", class.as_str())).into_response();
+      set_headers(response.headers_mut(), limit, 0, reset);
+      response.headers_mut().insert("retry-after", HeaderValue::from(reset.as_secs().max(1)));
+      response
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::{NotifyRules, RateLimitConfig, RuntimeSettings};
+
+  #[test]
+  fn test_window_limits_and_resets() {
+    let limiter = RateLimiter::new(SharedSettings::new(RuntimeSettings {
+      rate_limit: RateLimitConfig {
+        enabled: true,
+        trust_proxy: false,
+        read_per_minute: 2,
+        heavy_per_minute: 1,
+        stream_per_minute: 1,
+        api_keys: Vec::new(),
+      },
+      notify: NotifyRules::default(),
+    }));
+    let start = Instant::now();
+
+    assert!(matches!(limiter.check("a", RouteClass::Read, start), Decision::Allowed { remaining: 1, .. }));
+    assert!(matches!(limiter.check("a", RouteClass::Read, start), Decision::Allowed { remaining: 0, .. }));
+    assert!(matches!(limiter.check("a", RouteClass::Read, start), Decision::Limited { .. }));
+
+    // Classes and clients are counted separately
+    assert!(matches!(limiter.check("a", RouteClass::Heavy, start), Decision::Allowed { .. }));
+    assert!(matches!(limiter.check("b", RouteClass::Read, start), Decision::Allowed { .. }));
+
+    let later = start + WINDOW;
+    assert!(matches!(limiter.check("a", RouteClass::Read, later), Decision::Allowed { remaining: 1, .. }));
+  }
+
+  #[test]
+  fn test_only_configured_keys_get_their_own_bucket() {
+    let request = |key: &str| {
+      let mut request = Request::builder().header("x-api-key", key).body(axum::body::Body::empty()).unwrap();
+      request.extensions_mut().insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+      request
+    };
+    let api_keys = vec!["partner".to_string()];
+
+    assert_eq!(client_key(&request("partner"), false, &api_keys), "key:partner");
+    assert_eq!(client_key(&request("made-up"), false, &api_keys), "ip:10.0.0.1");
+  }
+
+  #[test]
+  fn test_forwarded_for_uses_the_proxy_appended_entry() {
+    let request = Request::builder()
+      .header("x-forwarded-for", "1.2.3.4, 203.0.113.7")
+      .body(axum::body::Body::empty())
+      .unwrap();
+
+    assert_eq!(client_key(&request, true, &[]), "ip:203.0.113.7");
+  }
+
+  #[test]
+  fn test_route_classes() {
+    assert_eq!(RouteClass::for_path("/api/tokens"), RouteClass::Read);
+    assert_eq!(RouteClass::for_path("/api/tokens/abc/history"), RouteClass::Heavy);
+    assert_eq!(RouteClass::for_path("/api/tokens/abc/at"), RouteClass::Heavy);
+    assert_eq!(RouteClass::for_path("/api/stats/funnel"), RouteClass::Heavy);
+    assert_eq!(RouteClass::for_path("/graphql"), RouteClass::Heavy);
+    assert_eq!(RouteClass::for_path("/api/state/snapshot"), RouteClass::Heavy);
+    assert_eq!(RouteClass::for_path("/ws/trades"), RouteClass::Stream);
+    assert_eq!(RouteClass::for_path("/api/stream/trades/abc"), RouteClass::Stream);
+  }
+}
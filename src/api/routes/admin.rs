@@ -0,0 +1,530 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{error, info, warn};
+use crate::api::AppState;
+use crate::api::error::{ApiError, ErrorResponse};
+use crate::api::extract::{Address, Json};
+use crate::background::token_backfill::{self, TokenBackfillRequest};
+use crate::config::RuntimeSettings;
+use crate::database::{self, model::{CreatorListEntry, TrackedWallet}};
+use crate::processor::creator_lists::CreatorList;
+use crate::helius::accounts;
+use crate::processor::snapshot::{self, StateSnapshot};
+use crate::processor::state;
+use crate::storage::response_cache::{self, CacheScope};
+
+/// Admin routes answer 404 unless `ADMIN_TOKEN` is configured, and 401
+/// unless the request carries it as a bearer token.
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let Some(expected) = state.admin_token.as_deref() else {
+        return Err(ApiError::not_found("Not found"));
+    };
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or_default();
+
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        return Err(ApiError::Unauthorized("Invalid admin token".to_string()));
+    }
+    Ok(())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/config",
+    tag = "admin",
+    responses(
+        (status = 200, body = RuntimeSettings),
+        (status = 401, description = "Missing or wrong admin token"),
+    )
+)]
+pub async fn get_runtime_config(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<RuntimeSettings>, ApiError> {
+    authorize(&state, &headers)?;
+    Ok(Json(state.settings.read().clone()))
+}
+
+/// Replaces the runtime settings wholesale; fetch them first and send back
+/// the edited copy.
+#[utoipa::path(
+    put,
+    path = "/api/admin/config",
+    tag = "admin",
+    responses(
+        (status = 200, body = RuntimeSettings, description = "Settings applied"),
+        (status = 400, body = ErrorResponse, description = "Invalid settings; every problem is listed in `details`"),
+        (status = 401, description = "Missing or wrong admin token"),
+    )
+)]
+pub async fn put_runtime_config(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(settings): Json<RuntimeSettings>,
+) -> Result<Json<RuntimeSettings>, ApiError> {
+    authorize(&state, &headers)?;
+
+    if let Err(errors) = state.settings.replace(settings.clone()) {
+        return Err(ApiError::Validation { message: "Invalid settings".to_string(), errors });
+    }
+    info!("🔧 Runtime settings updated via admin API");
+
+    Ok(Json(settings))
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct LogFilterBody {
+    /// `RUST_LOG`-style directives, e.g. `info,pumpfun_indexer::helius::parser=debug`
+    pub filter: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/log-filter",
+    tag = "admin",
+    responses(
+        (status = 200, body = LogFilterBody),
+        (status = 401, description = "Missing or wrong admin token"),
+    )
+)]
+pub async fn get_log_filter(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<LogFilterBody>, ApiError> {
+    authorize(&state, &headers)?;
+    Ok(Json(LogFilterBody { filter: state.log_filter.current() }))
+}
+
+/// Changes log levels without a restart. The change lasts until the next
+/// restart; set `logging.filter` to keep it.
+#[utoipa::path(
+    put,
+    path = "/api/admin/log-filter",
+    tag = "admin",
+    request_body = LogFilterBody,
+    responses(
+        (status = 200, body = LogFilterBody, description = "Filter applied"),
+        (status = 400, body = ErrorResponse, description = "Directives don't parse"),
+        (status = 401, description = "Missing or wrong admin token"),
+    )
+)]
+pub async fn put_log_filter(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<LogFilterBody>,
+) -> Result<Json<LogFilterBody>, ApiError> {
+    authorize(&state, &headers)?;
+
+    state.log_filter
+        .set(&body.filter)
+        .map_err(|e| ApiError::bad_request(format!("Invalid log filter: {}", e)))?;
+    info!("🔧 Log filter set to {} via admin API", body.filter);
+
+    Ok(Json(body))
+}
+
+/// Every token held in memory, with the momentum windows and slot positions
+/// the public endpoints leave out. Save it and pass it to `--restore` to
+/// restart warm.
+#[utoipa::path(
+    get,
+    path = "/api/admin/snapshot",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Full in-memory state, readable by `--restore`"),
+        (status = 401, description = "Missing or wrong admin token"),
+    )
+)]
+pub async fn get_snapshot(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<StateSnapshot>, ApiError> {
+    authorize(&state, &headers)?;
+    Ok(Json(snapshot::take(&state.token_state, &state.metrics).await))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/tokens/{mint}/resync",
+    tag = "admin",
+    params(("mint" = String, Path, description = "Token mint address")),
+    responses(
+        (status = 200, description = "Reserves reloaded from the bonding curve account; returns the new live state"),
+        (status = 404, body = ErrorResponse, description = "Token not stored"),
+    )
+)]
+pub async fn resync_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Address(mint): Address,
+) -> Result<Json<Value>, ApiError> {
+    authorize(&state, &headers)?;
+
+    let curve = accounts::fetch_bonding_curve(&state.rpc, &mint).await?;
+    let curve_address = accounts::bonding_curve_address(&mint)?.to_string();
+    let snapshot = database::apply_bonding_curve(&state.db, &mint, &curve_address, &curve)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Token not found"))?;
+    response_cache::invalidate_key(state.redis.as_ref(), CacheScope::TokenDetail, &mint).await;
+
+    let sol_price = *state.sol_price.read().await;
+    let token_state = state::restore_token_state(&state.token_state, &mint, snapshot, sol_price).await;
+    info!("🔧 Resynced {} from chain via admin API", mint);
+
+    Ok(Json(json!({ "mint": mint, "state": token_state })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/tokens/{mint}/state/refresh",
+    tag = "admin",
+    params(("mint" = String, Path, description = "Token mint address")),
+    responses(
+        (status = 200, description = "Live state rebuilt from the database"),
+        (status = 404, body = ErrorResponse, description = "Token not stored"),
+    )
+)]
+pub async fn refresh_token_state(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Address(mint): Address,
+) -> Result<Json<Value>, ApiError> {
+    authorize(&state, &headers)?;
+
+    let snapshot = database::fetch_token_snapshot(&state.db, &mint)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Token not found"))?;
+    let sol_price = *state.sol_price.read().await;
+    let token_state = state::restore_token_state(&state.token_state, &mint, snapshot, sol_price).await;
+
+    Ok(Json(json!({ "mint": mint, "state": token_state })))
+}
+
+/// The token is re-added to live state by its next event.
+#[utoipa::path(
+    delete,
+    path = "/api/admin/tokens/{mint}/state",
+    tag = "admin",
+    params(("mint" = String, Path, description = "Token mint address")),
+    responses(
+        (status = 204, description = "Evicted from live state"),
+        (status = 404, body = ErrorResponse, description = "Token not in live state"),
+    )
+)]
+pub async fn evict_token_state(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Address(mint): Address,
+) -> Result<StatusCode, ApiError> {
+    authorize(&state, &headers)?;
+
+    if !state::remove_token_state(&state.token_state, &mint).await {
+        return Err(ApiError::not_found("Token not in live state"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Re-reads name, symbol and URI from the mint's Metaplex metadata and
+/// decimals from the mint account, e.g. for placeholder tokens created when
+/// a trade arrived before its launch.
+#[utoipa::path(
+    post,
+    path = "/api/admin/tokens/{mint}/metadata",
+    tag = "admin",
+    params(("mint" = String, Path, description = "Token mint address")),
+    responses(
+        (status = 200, description = "Metadata refreshed"),
+        (status = 404, body = ErrorResponse, description = "Token not stored"),
+    )
+)]
+pub async fn refresh_token_metadata(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Address(mint): Address,
+) -> Result<Json<Value>, ApiError> {
+    authorize(&state, &headers)?;
+
+    let metadata = accounts::fetch_token_metadata(&state.rpc, &mint).await?;
+    if !database::update_token_metadata(&state.db, &mint, &metadata).await? {
+        return Err(ApiError::not_found("Token not found"));
+    }
+    response_cache::invalidate_key(state.redis.as_ref(), CacheScope::TokenDetail, &mint).await;
+
+    if state::get_token_state(&state.token_state, &mint).await.is_some()
+        && let Some(snapshot) = database::fetch_token_snapshot(&state.db, &mint).await?
+    {
+        let sol_price = *state.sol_price.read().await;
+        state::restore_token_state(&state.token_state, &mint, snapshot, sol_price).await;
+    }
+
+    Ok(Json(json!({
+        "mint": mint,
+        "name": metadata.name,
+        "symbol": metadata.symbol,
+        "uri": metadata.uri,
+        "decimals": metadata.decimals,
+    })))
+}
+
+/// Starts replaying a token's recent transactions in the background;
+/// progress and the final counts are logged.
+#[utoipa::path(
+    post,
+    path = "/api/admin/backfill",
+    tag = "admin",
+    request_body = TokenBackfillRequest,
+    responses(
+        (status = 202, description = "Backfill started"),
+        (status = 400, body = ErrorResponse, description = "Invalid mint or limit"),
+    )
+)]
+pub async fn start_backfill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<TokenBackfillRequest>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    authorize(&state, &headers)?;
+
+    accounts::bonding_curve_address(&request.mint).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    if request.limit == 0 || request.limit > 10_000 {
+        return Err(ApiError::bad_request("limit must be between 1 and 10000"));
+    }
+
+    let body = json!({ "mint": request.mint, "limit": request.limit, "status": "started" });
+    tokio::spawn(async move {
+        let sol_price = *state.sol_price.read().await;
+        if let Err(e) = token_backfill::backfill_token(
+            &state.rpc,
+            &state.db,
+            state.redis.as_ref(),
+            &state.token_state,
+            sol_price,
+            &state.metrics,
+            &request,
+        ).await {
+            error!("Backfill for {} failed: {}", request.mint, e);
+        }
+        state.recent_trades.forget(&request.mint).await;
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(body)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/ingestion",
+    tag = "admin",
+    responses((status = 200, description = "Whether event processing is paused"))
+)]
+pub async fn get_ingestion(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    authorize(&state, &headers)?;
+    Ok(Json(json!({ "paused": state.ingestion.is_paused() })))
+}
+
+/// Holds event processing; the listener keeps queueing transactions, which
+/// are processed on resume.
+#[utoipa::path(
+    post,
+    path = "/api/admin/ingestion/pause",
+    tag = "admin",
+    responses((status = 200, description = "Processing paused"))
+)]
+pub async fn pause_ingestion(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    authorize(&state, &headers)?;
+    if state.ingestion.set_paused(true) {
+        warn!("⏸️ Ingestion paused via admin API");
+    }
+    Ok(Json(json!({ "paused": true })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/ingestion/resume",
+    tag = "admin",
+    responses((status = 200, description = "Processing resumed"))
+)]
+pub async fn resume_ingestion(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    authorize(&state, &headers)?;
+    if state.ingestion.set_paused(false) {
+        info!("▶️ Ingestion resumed via admin API");
+    }
+    Ok(Json(json!({ "paused": false })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/tracked-wallets",
+    tag = "admin",
+    responses((status = 200, body = Vec<TrackedWallet>, description = "Tracked wallets, oldest first"))
+)]
+pub async fn list_tracked_wallets(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<TrackedWallet>>, ApiError> {
+    authorize(&state, &headers)?;
+    Ok(Json(state.tracked_wallets.list().await))
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct TrackWalletRequest {
+    /// Shown alongside the wallet's trades on `pump:tracked`.
+    pub label: Option<String>,
+}
+
+/// Starts publishing the wallet's trades on `pump:tracked`; re-tracking a
+/// wallet replaces its label.
+#[utoipa::path(
+    put,
+    path = "/api/admin/tracked-wallets/{wallet}",
+    tag = "admin",
+    params(("wallet" = String, Path, description = "Wallet address")),
+    request_body = TrackWalletRequest,
+    responses(
+        (status = 200, body = TrackedWallet, description = "Wallet tracked"),
+        (status = 400, body = ErrorResponse, description = "Invalid wallet address"),
+    )
+)]
+pub async fn track_wallet(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Address(wallet): Address,
+    Json(request): Json<TrackWalletRequest>,
+) -> Result<Json<TrackedWallet>, ApiError> {
+    authorize(&state, &headers)?;
+
+    let tracked = database::save_tracked_wallet(&state.db, &wallet, request.label.as_deref()).await?;
+    state.tracked_wallets.insert(tracked.clone()).await;
+    info!("👀 Tracking wallet {} via admin API", wallet);
+
+    Ok(Json(tracked))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/tracked-wallets/{wallet}",
+    tag = "admin",
+    params(("wallet" = String, Path, description = "Wallet address")),
+    responses(
+        (status = 204, description = "Wallet no longer tracked"),
+        (status = 404, body = ErrorResponse, description = "Wallet not tracked"),
+    )
+)]
+pub async fn untrack_wallet(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Address(wallet): Address,
+) -> Result<StatusCode, ApiError> {
+    authorize(&state, &headers)?;
+
+    let deleted = database::delete_tracked_wallet(&state.db, &wallet).await?;
+    if !state.tracked_wallets.remove(&wallet).await && !deleted {
+        return Err(ApiError::not_found("Wallet not tracked"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/creator-lists",
+    tag = "admin",
+    responses((status = 200, body = Vec<CreatorListEntry>, description = "Listed creators, oldest first"))
+)]
+pub async fn list_creator_lists(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<CreatorListEntry>>, ApiError> {
+    authorize(&state, &headers)?;
+    Ok(Json(state.creator_lists.entries().await))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct ListCreatorRequest {
+    pub list: CreatorList,
+    /// Why the creator was listed, for other operators.
+    pub reason: Option<String>,
+}
+
+/// Puts a creator on the blacklist or watchlist; listing it again moves it
+/// between them and replaces the reason.
+#[utoipa::path(
+    put,
+    path = "/api/admin/creator-lists/{wallet}",
+    tag = "admin",
+    params(("wallet" = String, Path, description = "Creator wallet address")),
+    request_body = ListCreatorRequest,
+    responses(
+        (status = 200, body = CreatorListEntry, description = "Creator listed"),
+        (status = 400, body = ErrorResponse, description = "Invalid wallet address"),
+    )
+)]
+pub async fn list_creator(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Address(wallet): Address,
+    Json(request): Json<ListCreatorRequest>,
+) -> Result<Json<CreatorListEntry>, ApiError> {
+    authorize(&state, &headers)?;
+
+    let entry = database::save_creator_list_entry(&state.db, &wallet, request.list.as_str(), request.reason.as_deref()).await?;
+    state.creator_lists.insert(entry.clone()).await;
+    info!("📝 Creator {} put on the {} via admin API", wallet, request.list.as_str());
+
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/creator-lists/{wallet}",
+    tag = "admin",
+    params(("wallet" = String, Path, description = "Creator wallet address")),
+    responses(
+        (status = 204, description = "Creator no longer listed"),
+        (status = 404, body = ErrorResponse, description = "Creator not listed"),
+    )
+)]
+pub async fn unlist_creator(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Address(wallet): Address,
+) -> Result<StatusCode, ApiError> {
+    authorize(&state, &headers)?;
+
+    let deleted = database::delete_creator_list_entry(&state.db, &wallet).await?;
+    if !state.creator_lists.remove(&wallet).await && !deleted {
+        return Err(ApiError::not_found("Creator not listed"));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+}
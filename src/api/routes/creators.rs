@@ -1,45 +1,431 @@
 use axum::{
-    extract::{State, Path},
-    http::StatusCode,
+    extract::State,
     response::Json,
 };
-use serde::Serialize;
-use serde_json::{json, Value};
+use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::{Address, Query};
+use crate::api::params::parse_interval;
+use crate::dto::{Pagination, WithMeta};
 
-#[derive(Debug, Serialize, FromRow)]
+/// A creator selling within this window after launch counts as a fast dump.
+const FAST_DUMP_WINDOW_SECS: f64 = 300.0;
+
+#[derive(Deserialize, IntoParams)]
+pub struct CreatorQuery {
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+    /// `created_at` (default), `market_cap` or `name`
+    #[serde(default)]
+    sort: String,
+    /// `desc` (default) or `asc`
+    #[serde(default)]
+    order: String,
+}
+
+fn default_limit() -> i64 { 50 }
+
+/// `ORDER BY` clause for the token list; `mint_address` breaks ties so
+/// pages don't overlap.
+fn order_by(sort: &str, order: &str) -> Option<String> {
+    let column = match sort {
+        "" | "created_at" => "created_at",
+        "market_cap" => "market_cap_usd",
+        "name" => "name",
+        _ => return None,
+    };
+    let direction = match order {
+        "" | "desc" => "DESC NULLS LAST",
+        "asc" => "ASC NULLS LAST",
+        _ => return None,
+    };
+    Some(format!("{} {}, mint_address", column, direction))
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
 pub struct CreatorTokenResponse {
-    pub mint_address: String,       
+    pub mint_address: String,
     pub name: String,
     pub symbol: String,
+    #[schema(value_type = Option<String>)]
     pub market_cap_usd: Option<bigdecimal::BigDecimal>,
     pub complete: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+async fn fetch_creator_tokens(
+    db: &sqlx::PgPool,
+    wallet: &str,
+    order_by: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<CreatorTokenResponse>, sqlx::Error> {
+    sqlx::query_as::<_, CreatorTokenResponse>(&format!(
+        "SELECT mint_address, name, symbol, market_cap_usd, COALESCE(complete, FALSE) AS complete, created_at
+         FROM tokens
+         WHERE creator_wallet = $1
+         ORDER BY {}
+         LIMIT $2 OFFSET $3",
+        order_by
+    ))
+    .bind(wallet)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await
+}
+
+#[derive(Debug, FromRow)]
+struct CreatorTokenCounts {
+    tokens_launched: i64,
+    tokens_graduated: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct CreatorSellStats {
+    sell_count: i64,
+    sold_lamports: i64,
+    tokens_sold: i64,
+    fast_dumps: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatorStats {
+    pub tokens_launched: i64,
+    pub tokens_graduated: i64,
+    /// Percentage of launches that completed the bonding curve
+    pub graduation_rate: f64,
+    pub total_creator_fees_lamports: i64,
+    pub total_creator_fees_sol: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatorRugIndicators {
+    pub creator_sell_count: i64,
+    pub creator_sold_sol: f64,
+    pub tokens_sold_by_creator: i64,
+    /// Tokens the creator sold within five minutes of launch
+    pub fast_dumps: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatorAnalyticsResponse {
+    pub creator: String,
+    pub stats: CreatorStats,
+    pub rug_indicators: CreatorRugIndicators,
+    pub tokens: Vec<CreatorTokenResponse>,
+    pub pagination: Pagination,
+}
+
+/// Fees and rug indicators are summed over the creator's trades, adding
+/// those tiered out of Postgres from their per-token sums.
+#[utoipa::path(
+    get,
+    path = "/api/creators/{wallet}",
+    tag = "creators",
+    params(("wallet" = String, Path, description = "Creator wallet address"), CreatorQuery),
+    responses(
+        (status = 200, body = WithMeta<CreatorAnalyticsResponse>),
+    )
+)]
 pub async fn get_creator_tokens(
     State(state): State<AppState>,
-    Path(wallet): Path<String>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+    Address(wallet): Address,
+    Query(query): Query<CreatorQuery>,
+) -> Result<Json<CreatorAnalyticsResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 100);
+    let offset = query.offset.max(0);
+    let order_by = order_by(&query.sort, &query.order)
+        .ok_or_else(|| ApiError::bad_request("sort must be created_at, market_cap or name and order asc or desc"))?;
+
+    let tokens = fetch_creator_tokens(&state.db, &wallet, &order_by, limit, offset).await?;
 
-    let tokens = sqlx::query_as::<_, CreatorTokenResponse>(
-        "SELECT mint_address, name, symbol, market_cap_usd, complete, created_at
+    let counts = sqlx::query_as::<_, CreatorTokenCounts>(
+        "SELECT COUNT(*) AS tokens_launched,
+                COUNT(*) FILTER (WHERE complete) AS tokens_graduated
          FROM tokens
-         WHERE creator_wallet = $1
-         ORDER BY created_at DESC"
+         WHERE creator_wallet = $1"
+    )
+    .bind(&wallet)
+    .fetch_one(&state.db)
+    .await?;
+
+    let fees: (i64,) = sqlx::query_as(
+        "SELECT (COALESCE((SELECT SUM(creator_fee) FROM trades WHERE creator = $1), 0)
+                 + COALESCE((SELECT SUM(creator_fee_lamports) FROM cold_creator_fees WHERE creator = $1), 0))::BIGINT"
+    )
+    .bind(&wallet)
+    .fetch_one(&state.db)
+    .await?;
+
+    // Sells by the creator on their own launches are the strongest rug signal
+    // available from trade data alone.
+    let sells = sqlx::query_as::<_, CreatorSellStats>(
+        "SELECT COALESCE(SUM(s.sells), 0)::BIGINT AS sell_count,
+                COALESCE(SUM(s.sold_lamports), 0)::BIGINT AS sold_lamports,
+                COUNT(DISTINCT s.token_mint) AS tokens_sold,
+                COUNT(DISTINCT s.token_mint) FILTER (
+                    WHERE EXTRACT(EPOCH FROM (s.first_sell_at - t.created_at)) <= $2
+                ) AS fast_dumps
+         FROM (
+             SELECT token_mint, 1::BIGINT AS sells, sol_amount::NUMERIC AS sold_lamports, timestamp AS first_sell_at
+             FROM trades
+             WHERE user_wallet = $1 AND NOT is_buy
+             UNION ALL
+             SELECT token_mint, sells, sold_lamports, first_sell_at
+             FROM cold_wallet_balances
+             WHERE user_wallet = $1 AND sells > 0
+         ) s
+         JOIN tokens t ON t.mint_address = s.token_mint
+         WHERE t.creator_wallet = $1"
+    )
+    .bind(&wallet)
+    .bind(FAST_DUMP_WINDOW_SECS)
+    .fetch_one(&state.db)
+    .await?;
+
+    let graduation_rate = if counts.tokens_launched > 0 {
+        counts.tokens_graduated as f64 / counts.tokens_launched as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    Ok(Json(CreatorAnalyticsResponse {
+        creator: wallet,
+        stats: CreatorStats {
+            tokens_launched: counts.tokens_launched,
+            tokens_graduated: counts.tokens_graduated,
+            graduation_rate,
+            total_creator_fees_lamports: fees.0,
+            total_creator_fees_sol: fees.0 as f64 / 1_000_000_000.0,
+        },
+        rug_indicators: CreatorRugIndicators {
+            creator_sell_count: sells.sell_count,
+            creator_sold_sol: sells.sold_lamports as f64 / 1_000_000_000.0,
+            tokens_sold_by_creator: sells.tokens_sold,
+            fast_dumps: sells.fast_dumps,
+        },
+        tokens,
+        pagination: Pagination { total: counts.tokens_launched, limit, offset },
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct EarningsQuery {
+    /// Whole days, e.g. `1d` (default), `7d`
+    #[serde(default = "default_earnings_interval")]
+    interval: String,
+    /// Defaults to 30 days before `to`
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+fn default_earnings_interval() -> String { "1d".to_string() }
+
+const DEFAULT_EARNINGS_DAYS: i64 = 30;
+/// Tokens listed in the per-token breakdown.
+const TOP_EARNING_TOKENS: i64 = 20;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EarningsPoint {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub accrued_sol: f64,
+    pub claimed_sol: f64,
+    /// Running totals from the start of the range
+    pub cumulative_accrued_sol: f64,
+    pub cumulative_claimed_sol: f64,
+}
+
+#[derive(Debug, FromRow)]
+struct EarningsBucket {
+    timestamp: chrono::DateTime<chrono::Utc>,
+    accrued_sol: f64,
+    claimed_sol: f64,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct TokenEarnings {
+    pub mint_address: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub accrued_sol: f64,
+    pub trades: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatorEarningsTotals {
+    pub accrued_sol: f64,
+    pub claimed_sol: f64,
+    pub unclaimed_sol: f64,
+}
+
+/// All-time `totals`; `tokens` and `points` cover `from`..`to`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatorEarningsResponse {
+    pub creator: String,
+    pub interval: String,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    pub totals: CreatorEarningsTotals,
+    pub tokens: Vec<TokenEarnings>,
+    pub points: Vec<EarningsPoint>,
+}
+
+/// Accrued fees come from the daily rollup of trades, claimed fees from
+/// `CollectCreatorFeeEvent`s. Claims made before indexing started can push
+/// `claimed` above `accrued`, so `unclaimed` is floored at zero.
+#[utoipa::path(
+    get,
+    path = "/api/creators/{wallet}/earnings",
+    tag = "creators",
+    params(("wallet" = String, Path, description = "Creator wallet address"), EarningsQuery),
+    responses(
+        (status = 200, body = WithMeta<CreatorEarningsResponse>),
+        (status = 400, description = "Invalid interval or range"),
+    )
+)]
+pub async fn get_creator_earnings(
+    State(state): State<AppState>,
+    Address(wallet): Address,
+    Query(query): Query<EarningsQuery>,
+) -> Result<Json<CreatorEarningsResponse>, ApiError> {
+    let interval = parse_interval(&query.interval)
+        .filter(|d| d.num_seconds() % 86_400 == 0)
+        .ok_or_else(|| ApiError::bad_request("interval must be a whole number of days, e.g. 1d, 7d"))?;
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::days(DEFAULT_EARNINGS_DAYS));
+    if from >= to {
+        return Err(ApiError::bad_request("from must be before to"));
+    }
+
+    let totals: (f64, f64) = sqlx::query_as(
+        "SELECT COALESCE((SELECT SUM(accrued_lamports) FROM creator_earnings_daily WHERE creator = $1), 0)::FLOAT8 / 1e9,
+                COALESCE((SELECT SUM(lamports) FROM creator_fee_claims WHERE creator = $1), 0)::FLOAT8 / 1e9"
+    )
+    .bind(&wallet)
+    .fetch_one(&state.db)
+    .await?;
+
+    let buckets = sqlx::query_as::<_, EarningsBucket>(
+        "WITH accrued AS (
+             SELECT date_bin($2 * INTERVAL '1 second', day, TIMESTAMPTZ '2000-01-01') AS bucket,
+                    SUM(accrued_lamports)::FLOAT8 / 1e9 AS sol
+             FROM creator_earnings_daily
+             WHERE creator = $1 AND day >= date_trunc('day', $3::TIMESTAMPTZ) AND day < $4
+             GROUP BY 1
+         ),
+         claimed AS (
+             SELECT date_bin($2 * INTERVAL '1 second', claimed_at, TIMESTAMPTZ '2000-01-01') AS bucket,
+                    SUM(lamports)::FLOAT8 / 1e9 AS sol
+             FROM creator_fee_claims
+             WHERE creator = $1 AND claimed_at >= date_trunc('day', $3::TIMESTAMPTZ) AND claimed_at < $4
+             GROUP BY 1
+         )
+         SELECT COALESCE(accrued.bucket, claimed.bucket) AS timestamp,
+                COALESCE(accrued.sol, 0) AS accrued_sol,
+                COALESCE(claimed.sol, 0) AS claimed_sol
+         FROM accrued
+         FULL JOIN claimed ON claimed.bucket = accrued.bucket
+         ORDER BY 1"
     )
     .bind(&wallet)
+    .bind(interval.num_seconds() as f64)
+    .bind(from)
+    .bind(to)
     .fetch_all(&state.db)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database error: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
-    })?;
-    
-    Ok(Json(json!({
-        "creator": wallet,
-        "tokens": tokens,
-        "total": tokens.len(),
-    })))
-}
\ No newline at end of file
+    .await?;
+
+    let tokens = sqlx::query_as::<_, TokenEarnings>(
+        "SELECT e.token_mint AS mint_address, t.name, t.symbol,
+                SUM(e.accrued_lamports)::FLOAT8 / 1e9 AS accrued_sol,
+                SUM(e.trades)::BIGINT AS trades
+         FROM creator_earnings_daily e
+         LEFT JOIN tokens t ON t.mint_address = e.token_mint
+         WHERE e.creator = $1 AND e.day >= date_trunc('day', $2::TIMESTAMPTZ) AND e.day < $3
+         GROUP BY e.token_mint, t.name, t.symbol
+         ORDER BY accrued_sol DESC
+         LIMIT $4"
+    )
+    .bind(&wallet)
+    .bind(from)
+    .bind(to)
+    .bind(TOP_EARNING_TOKENS)
+    .fetch_all(&state.db)
+    .await?;
+
+    let (accrued_sol, claimed_sol) = totals;
+    Ok(Json(CreatorEarningsResponse {
+        creator: wallet,
+        interval: query.interval,
+        from,
+        to,
+        totals: CreatorEarningsTotals {
+            accrued_sol,
+            claimed_sol,
+            unclaimed_sol: (accrued_sol - claimed_sol).max(0.0),
+        },
+        tokens,
+        points: with_running_totals(buckets),
+    }))
+}
+
+fn with_running_totals(buckets: Vec<EarningsBucket>) -> Vec<EarningsPoint> {
+    let (mut accrued, mut claimed) = (0.0, 0.0);
+    buckets
+        .into_iter()
+        .map(|bucket| {
+            accrued += bucket.accrued_sol;
+            claimed += bucket.claimed_sol;
+            EarningsPoint {
+                timestamp: bucket.timestamp,
+                accrued_sol: bucket.accrued_sol,
+                claimed_sol: bucket.claimed_sol,
+                cumulative_accrued_sol: accrued,
+                cumulative_claimed_sol: claimed,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs against a fresh database created from `DATABASE_URL` with the
+    /// migrations applied.
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_creator_tokens_sorting_and_pagination(db: sqlx::PgPool) {
+        for (mint, name, market_cap, age_hours) in [("mintA", "Alpha", 5000, 3), ("mintB", "Bravo", 9000, 2), ("mintC", "Charlie", 1000, 1)] {
+            sqlx::query(
+                "INSERT INTO tokens (mint_address, name, symbol, uri, bonding_curve_address, creator_wallet, market_cap_usd, created_at)
+                 VALUES ($1, $2, 'SYM', 'uri', 'curve', 'creator1', $3, NOW() - make_interval(hours => $4))"
+            )
+            .bind(mint)
+            .bind(name)
+            .bind(bigdecimal::BigDecimal::from(market_cap))
+            .bind(age_hours)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+
+        let mints = |tokens: Vec<CreatorTokenResponse>| tokens.into_iter().map(|t| t.mint_address).collect::<Vec<_>>();
+
+        let newest = fetch_creator_tokens(&db, "creator1", &order_by("", "").unwrap(), 2, 0).await.unwrap();
+        assert_eq!(mints(newest), ["mintC", "mintB"]);
+        let second_page = fetch_creator_tokens(&db, "creator1", &order_by("", "").unwrap(), 2, 2).await.unwrap();
+        assert_eq!(mints(second_page), ["mintA"]);
+
+        let by_cap = fetch_creator_tokens(&db, "creator1", &order_by("market_cap", "asc").unwrap(), 10, 0).await.unwrap();
+        assert_eq!(mints(by_cap), ["mintC", "mintA", "mintB"]);
+
+        assert!(fetch_creator_tokens(&db, "nobody", &order_by("name", "").unwrap(), 10, 0).await.unwrap().is_empty());
+        assert!(order_by("volume", "").is_none());
+        assert!(order_by("name", "sideways").is_none());
+    }
+}
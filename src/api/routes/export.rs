@@ -0,0 +1,294 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::stream;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Postgres, Transaction};
+use tracing::{error, info};
+use utoipa::IntoParams;
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::Query;
+use crate::api::params::parse_pubkey;
+use crate::database::tiering;
+
+/// Rows pulled from the cursor per chunk written to the client.
+const FETCH_SIZE: i64 = 1_000;
+/// Range exported when `from` is omitted.
+const DEFAULT_EXPORT_HOURS: i64 = 24;
+
+const CSV_HEADER: &str = "signature,token_mint,user_wallet,is_buy,sol_amount,token_amount,timestamp,\
+virtual_sol_reserves,virtual_token_reserves,real_sol_reserves,real_token_reserves,fee,creator_fee,\
+price_sol,price_usd,sol_price_usd\n";
+
+#[derive(Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// Limit the export to one token
+    mint: Option<String>,
+    /// Start of the range (default 24 hours before `to`)
+    from: Option<DateTime<Utc>>,
+    /// End of the range, exclusive (default now)
+    to: Option<DateTime<Utc>>,
+    /// `ndjson` (default) or `csv`
+    #[serde(default)]
+    format: String,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Ndjson,
+    Csv,
+}
+
+/// Lamport and raw token amounts, like the rest of the API; prices are per
+/// whole token.
+#[derive(Debug, Serialize, FromRow)]
+struct ExportedTrade {
+    signature: String,
+    token_mint: String,
+    user_wallet: String,
+    is_buy: bool,
+    sol_amount: i64,
+    token_amount: i64,
+    timestamp: DateTime<Utc>,
+    virtual_sol_reserves: i64,
+    virtual_token_reserves: i64,
+    real_sol_reserves: i64,
+    real_token_reserves: i64,
+    fee: i64,
+    creator_fee: i64,
+    price_sol: Option<f64>,
+    price_usd: Option<f64>,
+    sol_price_usd: Option<f64>,
+}
+
+impl ExportedTrade {
+    /// Every text column is base58, so nothing needs quoting.
+    fn write_csv(&self, out: &mut String) {
+        let opt = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_default();
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            self.signature,
+            self.token_mint,
+            self.user_wallet,
+            self.is_buy,
+            self.sol_amount,
+            self.token_amount,
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+            self.real_sol_reserves,
+            self.real_token_reserves,
+            self.fee,
+            self.creator_fee,
+            opt(self.price_sol),
+            opt(self.price_usd),
+            opt(self.sol_price_usd),
+        ));
+    }
+}
+
+/// Streams trades oldest first from a server-side cursor, one chunk per
+/// `FETCH`, so neither side holds the whole range in memory. A failure
+/// mid-export aborts the response rather than ending it cleanly, so a
+/// truncated download is never mistaken for a complete one. Trades tiered
+/// out of Postgres are left out and their Parquet files named in
+/// `x-archived-objects`; a range holding only those is answered with 410.
+#[utoipa::path(
+    get,
+    path = "/api/export/trades",
+    tag = "trades",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "One trade per line", content_type = "application/x-ndjson"),
+        (status = 200, description = "Header row, then one trade per line", content_type = "text/csv"),
+        (status = 400, description = "Invalid format or range"),
+        (status = 410, description = "Range only holds archived trades"),
+    )
+)]
+pub async fn export_trades(
+    State(state): State<AppState>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let format = match query.format.as_str() {
+        "" | "ndjson" => Format::Ndjson,
+        "csv" => Format::Csv,
+        _ => return Err(ApiError::bad_request("format must be ndjson or csv")),
+    };
+    let mint = query.mint.as_deref()
+        .map(|mint| parse_pubkey(mint).ok_or_else(|| ApiError::bad_request("mint must be a base58 pubkey")))
+        .transpose()?;
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::hours(DEFAULT_EXPORT_HOURS));
+    if from >= to {
+        return Err(ApiError::bad_request("from must be before to"));
+    }
+
+    // Tiered-out trades are already exported, as the archive's Parquet files.
+    let archived = tiering::fetch_cold_objects(&state.db, mint.as_deref(), Some(from), Some(to)).await?;
+    if !archived.is_empty() {
+        let hot: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM trades
+                 WHERE ($1::VARCHAR IS NULL OR token_mint = $1) AND timestamp >= $2 AND timestamp < $3
+             )"
+        )
+        .bind(mint.as_deref())
+        .bind(from)
+        .bind(to)
+        .fetch_one(&state.db)
+        .await?;
+        if !hot {
+            return Err(ApiError::archived(
+                "Trades in this range were archived; download the listed files instead",
+                archived,
+            ));
+        }
+    }
+
+    // Declared up front so a bad query fails with a status code instead of
+    // an empty body.
+    let tx = open_cursor(&state.db, mint.as_deref(), from, to).await?;
+
+    info!("📦 Exporting trades {} to {} (mint: {:?})", from, to, mint);
+
+    let header = match format {
+        Format::Csv => Some(Ok(Bytes::from_static(CSV_HEADER.as_bytes()))),
+        Format::Ndjson => None,
+    };
+    let rows = stream::try_unfold(Some(tx), move |tx| fetch_chunk(tx, format));
+    let body = Body::from_stream(futures::StreamExt::chain(stream::iter(header), rows));
+
+    let (content_type, extension) = match format {
+        Format::Ndjson => ("application/x-ndjson", "ndjson"),
+        Format::Csv => ("text/csv", "csv"),
+    };
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"trades.{}\"", extension)),
+        ],
+        body,
+    ).into_response();
+    if !archived.is_empty()
+        && let Ok(value) = HeaderValue::from_str(&archived.join(","))
+    {
+        response.headers_mut().insert("x-archived-objects", value);
+    }
+    Ok(response)
+}
+
+type Cursor = Option<Transaction<'static, Postgres>>;
+
+async fn open_cursor(
+    db: &sqlx::PgPool,
+    mint: Option<&str>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> sqlx::Result<Transaction<'static, Postgres>> {
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        "DECLARE export_trades NO SCROLL CURSOR FOR
+         SELECT signature, token_mint, user_wallet, is_buy, sol_amount, token_amount, timestamp,
+                virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                fee, creator_fee, price_after_sol AS price_sol, price_usd::FLOAT8 AS price_usd,
+                sol_price_usd::FLOAT8 AS sol_price_usd
+         FROM trades
+         WHERE ($1::VARCHAR IS NULL OR token_mint = $1)
+           AND timestamp >= $2 AND timestamp < $3
+         ORDER BY timestamp, signature"
+    )
+    .bind(mint)
+    .bind(from)
+    .bind(to)
+    .execute(&mut *tx)
+    .await?;
+
+    Ok(tx)
+}
+
+/// Fetches and encodes the next chunk; `None` once the cursor is drained.
+async fn fetch_chunk(tx: Cursor, format: Format) -> Result<Option<(Bytes, Cursor)>, std::io::Error> {
+    let Some(mut tx) = tx else {
+        return Ok(None);
+    };
+
+    let rows = sqlx::query_as::<_, ExportedTrade>(&format!("FETCH {} FROM export_trades", FETCH_SIZE))
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Trade export failed: {}", e);
+            std::io::Error::other(e)
+        })?;
+
+    let mut chunk = String::new();
+    for trade in &rows {
+        match format {
+            Format::Ndjson => {
+                chunk.push_str(&serde_json::to_string(trade)?);
+                chunk.push('\n');
+            }
+            Format::Csv => trade.write_csv(&mut chunk),
+        }
+    }
+
+    if (rows.len() as i64) < FETCH_SIZE {
+        // Read-only, so there is nothing to commit; dropping rolls back.
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        return Ok(Some((Bytes::from(chunk), None)));
+    }
+    Ok(Some((Bytes::from(chunk), Some(tx))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_export_cursor(pool: sqlx::PgPool) {
+        sqlx::query(
+            "INSERT INTO tokens (mint_address, name, symbol, uri, creator_wallet, bonding_curve_address, created_at)
+             VALUES ('mint', 'mint', 'mint', '', 'creator', 'curve', NOW())"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let at: DateTime<Utc> = "2025-11-20T12:00:00Z".parse().unwrap();
+        for (signature, offset) in [("a", 0), ("b", 60), ("late", 7200)] {
+            sqlx::query(
+                "INSERT INTO trades (signature, token_mint, sol_amount, token_amount, is_buy, user_wallet, timestamp,
+                                     virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                                     fee_recipient, fee_basis_points, fee, creator, creator_fee_basis_points, creator_fee,
+                                     track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume,
+                                     last_update_timestamp, ix_name, price_after_sol)
+                 VALUES ($1, 'mint', 1000000000, 35000000000, TRUE, 'wallet', $2, 31000000000, 1038000000000000,
+                         1000000000, 758000000000000, 'feeRecipient', 95, 9500000, 'creator', 5, 500000,
+                         FALSE, 0, 0, 0, $2, 'buy', 0.0000000298)"
+            )
+            .bind(signature)
+            .bind(at + chrono::Duration::seconds(offset))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let tx = open_cursor(&pool, Some("mint"), at, at + chrono::Duration::hours(1)).await.unwrap();
+        let (chunk, rest) = fetch_chunk(Some(tx), Format::Csv).await.unwrap().unwrap();
+        let rows = std::str::from_utf8(&chunk).unwrap();
+
+        assert!(rest.is_none(), "a short chunk drains the cursor");
+        assert_eq!(
+            rows.lines().next().unwrap(),
+            "a,mint,wallet,true,1000000000,35000000000,2025-11-20T12:00:00Z,31000000000,1038000000000000,\
+             1000000000,758000000000000,9500000,500000,0.0000000298,,"
+        );
+        assert_eq!(rows.lines().count(), 2);
+        assert!(rows.lines().all(|row| row.split(',').count() == CSV_HEADER.split(',').count()));
+    }
+}
@@ -0,0 +1,245 @@
+use axum::{
+    extract::State,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::{Address, Query};
+use crate::api::params::{parse_interval, window_start};
+use crate::dto::WithMeta;
+use crate::processor::calculator;
+
+#[derive(Deserialize, IntoParams)]
+pub struct HistoryQuery {
+    /// `price` (default), `market_cap` or `progress`
+    #[serde(default = "default_metric")]
+    metric: String,
+    /// e.g. `1m`, `5m` (default), `1h`
+    #[serde(default = "default_interval")]
+    interval: String,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+fn default_metric() -> String { "price".to_string() }
+fn default_interval() -> String { "5m".to_string() }
+
+/// Points returned when the caller doesn't pass `from` (one day at 5m).
+const DEFAULT_POINTS: i32 = 288;
+const MAX_POINTS: i64 = 2000;
+
+#[derive(Debug, Clone, Copy)]
+enum HistoryMetric {
+    Price,
+    MarketCap,
+    Progress,
+}
+
+#[derive(Debug, FromRow)]
+struct BucketRow {
+    bucket: chrono::DateTime<chrono::Utc>,
+    virtual_sol_reserves: i64,
+    virtual_token_reserves: i64,
+    trades: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HistoryPoint {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub value: f64,
+    pub trades: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenHistoryResponse {
+    pub mint: String,
+    pub metric: String,
+    /// `sol` or `percent`
+    pub unit: String,
+    pub interval: String,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    pub points: Vec<HistoryPoint>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tokens/{mint}/history",
+    tag = "tokens",
+    params(("mint" = String, Path, description = "Token mint address"), HistoryQuery),
+    responses(
+        (status = 200, body = WithMeta<TokenHistoryResponse>),
+        (status = 400, description = "Unknown metric, bad interval or range too large"),
+        (status = 404, description = "Token not found"),
+    )
+)]
+pub async fn get_token_history(
+    State(state): State<AppState>,
+    Address(mint): Address,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<TokenHistoryResponse>, ApiError> {
+    let (metric, unit) = match query.metric.as_str() {
+        "price" => (HistoryMetric::Price, "sol"),
+        "market_cap" => (HistoryMetric::MarketCap, "sol"),
+        "progress" => (HistoryMetric::Progress, "percent"),
+        _ => return Err(ApiError::bad_request("metric must be one of price, market_cap, progress")),
+    };
+
+    let interval = parse_interval(&query.interval)
+        .ok_or_else(|| ApiError::bad_request("interval must look like 1m, 5m, 1h or 1d"))?;
+
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = match query.from {
+        Some(from) => from,
+        None => window_start(to, interval, DEFAULT_POINTS).ok_or_else(|| ApiError::bad_request("interval too large"))?,
+    };
+
+    if (to - from).num_seconds() / interval.num_seconds() > MAX_POINTS {
+        return Err(ApiError::bad_request(format!("range too large for interval (max {} points)", MAX_POINTS)));
+    }
+
+    let supply: Option<(Option<i64>, i16)> = sqlx::query_as(
+        "SELECT token_total_supply, decimals FROM tokens WHERE mint_address = $1"
+    )
+    .bind(&mint)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let (total_supply, decimals) = match supply {
+        Some((supply, decimals)) => (supply.unwrap_or(0) as u64, decimals as u8),
+        None => return Err(ApiError::not_found("Token not found")),
+    };
+
+    // Each bucket takes the reserves left by its last trade (the close).
+    // Minutes whose trades were tiered out of Postgres count through their
+    // cold candle; hot and cold minutes never overlap.
+    let rows = sqlx::query_as::<_, BucketRow>(
+        "WITH points AS (
+             SELECT timestamp AS at, virtual_sol_reserves, virtual_token_reserves, 1 AS trades
+             FROM trades
+             WHERE token_mint = $1 AND timestamp >= $3 AND timestamp < $4
+             UNION ALL
+             SELECT bucket, close_sol, close_tokens, trades
+             FROM cold_trade_candles
+             WHERE token_mint = $1 AND bucket >= $3 AND bucket < $4
+         )
+         SELECT date_bin($2 * INTERVAL '1 second', at, TIMESTAMPTZ '2000-01-01') AS bucket,
+                (array_agg(virtual_sol_reserves ORDER BY at DESC))[1] AS virtual_sol_reserves,
+                (array_agg(virtual_token_reserves ORDER BY at DESC))[1] AS virtual_token_reserves,
+                SUM(trades)::BIGINT AS trades
+         FROM points
+         GROUP BY 1
+         ORDER BY 1"
+    )
+    .bind(&mint)
+    .bind(interval.num_seconds() as f64)
+    .bind(from)
+    .bind(to)
+    .fetch_all(&state.db)
+    .await?;
+
+    let points: Vec<HistoryPoint> = rows.into_iter()
+        .map(|row| {
+            let virtual_sol = row.virtual_sol_reserves as u64;
+            let price = calculator::calculate_price_sol(virtual_sol, row.virtual_token_reserves as u64, decimals);
+
+            let value = match metric {
+                HistoryMetric::Price => price,
+                HistoryMetric::MarketCap => calculator::calculate_market_cap_sol(price, total_supply, decimals),
+                HistoryMetric::Progress => calculator::calculate_bonding_curve_progress(virtual_sol),
+            };
+
+            HistoryPoint { timestamp: row.bucket, value, trades: row.trades }
+        })
+        .collect();
+
+    Ok(Json(TokenHistoryResponse {
+        mint,
+        metric: query.metric,
+        unit: unit.to_string(),
+        interval: query.interval,
+        from,
+        to,
+        points,
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct HolderHistoryQuery {
+    /// Defaults to one day before `to`
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Defaults to now
+    to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct HolderSnapshot {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub holder_count: i64,
+    /// Percent of total supply held by the ten largest holders
+    pub top10_pct: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HolderHistoryResponse {
+    pub mint: String,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    /// Oldest first
+    pub snapshots: Vec<HolderSnapshot>,
+}
+
+/// Snapshots are taken every 5 minutes while a token trades; a longer range
+/// returns its latest `MAX_POINTS`.
+#[utoipa::path(
+    get,
+    path = "/api/tokens/{mint}/holders/history",
+    tag = "tokens",
+    params(("mint" = String, Path, description = "Token mint address"), HolderHistoryQuery),
+    responses(
+        (status = 200, body = WithMeta<HolderHistoryResponse>),
+        (status = 400, description = "from is after to"),
+        (status = 404, description = "Token not found"),
+    )
+)]
+pub async fn get_holder_history(
+    State(state): State<AppState>,
+    Address(mint): Address,
+    Query(query): Query<HolderHistoryQuery>,
+) -> Result<Json<HolderHistoryResponse>, ApiError> {
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::days(1));
+    if from > to {
+        return Err(ApiError::bad_request("from must be before to"));
+    }
+
+    let exists: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM tokens WHERE mint_address = $1")
+        .bind(&mint)
+        .fetch_optional(&state.db)
+        .await?;
+    if exists.is_none() {
+        return Err(ApiError::not_found("Token not found"));
+    }
+
+    let snapshots = sqlx::query_as::<_, HolderSnapshot>(
+        "SELECT * FROM (
+             SELECT taken_at AS timestamp, holder_count, top10_pct
+             FROM holder_snapshots
+             WHERE token_mint = $1 AND taken_at >= $2 AND taken_at < $3
+             ORDER BY taken_at DESC
+             LIMIT $4
+         ) latest
+         ORDER BY timestamp"
+    )
+    .bind(&mint)
+    .bind(from)
+    .bind(to)
+    .bind(MAX_POINTS)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(HolderHistoryResponse { mint, from, to, snapshots }))
+}
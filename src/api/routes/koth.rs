@@ -0,0 +1,53 @@
+use axum::{
+    extract::State,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::Query;
+use crate::database;
+use crate::database::model::KothReign;
+use crate::dto::WithMeta;
+use crate::processor::koth::{King, KOTH_THRESHOLD_SOL};
+
+#[derive(Deserialize, IntoParams)]
+pub struct KothQuery {
+    /// Past reigns to include (default 20, max 100)
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 { 20 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KothResponse {
+    /// Null while no live token is above the threshold
+    pub king: Option<King>,
+    pub threshold_sol: f64,
+    pub history: Vec<KothReign>,
+}
+
+/// The current king comes from the processor's live tracking; `history`
+/// lists reigns newest first, starting with the current one.
+#[utoipa::path(
+    get,
+    path = "/api/koth",
+    tag = "tokens",
+    params(KothQuery),
+    responses((status = 200, body = WithMeta<KothResponse>))
+)]
+pub async fn get_koth(
+    State(state): State<AppState>,
+    Query(query): Query<KothQuery>,
+) -> Result<Json<KothResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 100);
+    let history = database::fetch_koth_reigns(&state.db, limit).await?;
+
+    Ok(Json(KothResponse {
+        king: state.koth.current().await,
+        threshold_sol: KOTH_THRESHOLD_SOL,
+        history,
+    }))
+}
@@ -0,0 +1,139 @@
+use axum::{
+    extract::State,
+    response::Json,
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use utoipa::{IntoParams, ToSchema};
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::Query;
+use crate::background::leaderboards::{
+    self, CreatorLeaderboardEntry, Leaderboard, TokenLeaderboardEntry, TokenSort, TraderLeaderboardEntry, TraderSort,
+    LEADERBOARD_TTL_SECS, CREATOR_LEADERBOARD_KEY,
+};
+use crate::dto::WithMeta;
+
+#[derive(Deserialize, IntoParams)]
+pub struct LeaderboardQuery {
+    /// Entries to return (max 100)
+    #[serde(default = "default_limit")]
+    limit: usize,
+    /// Board-specific ordering, see each endpoint
+    #[serde(default)]
+    sort: String,
+}
+
+fn default_limit() -> usize { 25 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeaderboardResponse<T> {
+    pub leaderboard: String,
+    pub sort: String,
+    pub entries: Vec<T>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Reads a board from Redis, computing and caching it on a miss so the
+/// endpoint still works before the background job's first run.
+async fn cached_or_compute<T, F, Fut>(
+    state: &AppState,
+    key: &str,
+    compute: F,
+) -> Result<Leaderboard<T>, ApiError>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<Leaderboard<T>>>,
+{
+    let mut redis = state.redis.clone();
+
+    if let Some(redis) = &mut redis {
+        match redis.get::<Leaderboard<T>>(key).await {
+            Ok(Some(board)) => return Ok(board),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Leaderboard cache read failed ({}): {}", key, e),
+        }
+    }
+
+    let board = compute().await?;
+
+    if let Some(redis) = &mut redis
+        && let Err(e) = redis.set(key, &board, Some(LEADERBOARD_TTL_SECS)).await
+    {
+        tracing::warn!("Leaderboard cache write failed ({}): {}", key, e);
+    }
+
+    Ok(board)
+}
+
+fn respond<T>(name: &str, sort: &str, board: Leaderboard<T>, limit: usize) -> Json<LeaderboardResponse<T>> {
+    Json(LeaderboardResponse {
+        leaderboard: name.to_string(),
+        sort: sort.to_string(),
+        entries: board.entries.into_iter().take(limit).collect(),
+        updated_at: board.updated_at,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/leaderboards/tokens",
+    tag = "leaderboards",
+    params(LeaderboardQuery),
+    responses((status = 200, body = WithMeta<LeaderboardResponse<TokenLeaderboardEntry>>))
+)]
+pub async fn token_leaderboard(
+    State(state): State<AppState>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<Json<LeaderboardResponse<TokenLeaderboardEntry>>, ApiError> {
+    let sort = TokenSort::parse(&query.sort)
+        .ok_or_else(|| ApiError::bad_request("sort must be volume_24h or market_cap"))?;
+
+    let db = state.db.clone();
+    let board = cached_or_compute(&state, &sort.cache_key(), || async move {
+        leaderboards::compute_token_leaderboard(&db, sort).await
+    }).await?;
+
+    Ok(respond("tokens", sort.as_str(), board, query.limit))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/leaderboards/traders",
+    tag = "leaderboards",
+    params(LeaderboardQuery),
+    responses((status = 200, body = WithMeta<LeaderboardResponse<TraderLeaderboardEntry>>))
+)]
+pub async fn trader_leaderboard(
+    State(state): State<AppState>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<Json<LeaderboardResponse<TraderLeaderboardEntry>>, ApiError> {
+    let sort = TraderSort::parse(&query.sort)
+        .ok_or_else(|| ApiError::bad_request("sort must be volume_24h or pnl_24h"))?;
+
+    let db = state.db.clone();
+    let board = cached_or_compute(&state, &sort.cache_key(), || async move {
+        leaderboards::compute_trader_leaderboard(&db, sort).await
+    }).await?;
+
+    Ok(respond("traders", sort.as_str(), board, query.limit))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/leaderboards/creators",
+    tag = "leaderboards",
+    params(LeaderboardQuery),
+    responses((status = 200, body = WithMeta<LeaderboardResponse<CreatorLeaderboardEntry>>))
+)]
+pub async fn creator_leaderboard(
+    State(state): State<AppState>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<Json<LeaderboardResponse<CreatorLeaderboardEntry>>, ApiError> {
+    let db = state.db.clone();
+    let board = cached_or_compute(&state, CREATOR_LEADERBOARD_KEY, || async move {
+        leaderboards::compute_creator_leaderboard(&db).await
+    }).await?;
+
+    Ok(respond("creators", "graduations", board, query.limit))
+}
@@ -1,27 +1,87 @@
+pub mod admin;
 pub mod tokens;
 pub mod trades;
 pub mod creators;
+pub mod export;
 pub mod stats;
+pub mod leaderboards;
+pub mod history;
+pub mod koth;
+pub mod state;
+pub mod timeline;
+pub mod stream;
+pub mod wallets;
+pub mod transactions;
 pub mod websocket;
+pub mod ws_protocol;
+pub mod ws_session;
 
 use axum::{
     Router,
-    routing::get,
+    middleware,
+    routing::{delete, get, post, put},
 };
-use crate::api::AppState;
+use crate::api::{conditional, freshness, AppState};
 
-pub fn create_api_routes() -> Router<AppState> {
+/// `route_layer` only wraps the routes added before it, so read endpoints
+/// get `meta` while streams and admin routes don't.
+pub fn create_api_routes(state: &AppState) -> Router<AppState> {
     Router::new()
         
         .route("/tokens", get(tokens::list_tokens))
-        .route("/tokens/{mint}", get(tokens::get_token))
+        .route("/tokens/graduated", get(tokens::list_graduated_tokens))
+        .route("/koth", get(koth::get_koth))
+        .route("/tokens/{mint}", get(tokens::get_token).layer(middleware::from_fn(conditional::etag)))
         
+        .route("/trades/recent", get(trades::get_recent_trades))
         .route("/tokens/{mint}/trades", get(trades::get_token_trades))
+        .route("/tokens/{mint}/slippage", get(trades::get_token_slippage))
+        .route("/tokens/{mint}/history", get(history::get_token_history).layer(middleware::from_fn(conditional::etag)))
+        .route("/tokens/{mint}/holders/history", get(history::get_holder_history))
+        .route("/tokens/{mint}/timeline", get(timeline::get_token_timeline))
+        .route("/tokens/{mint}/state", get(state::get_token_state))
+        .route("/tokens/{mint}/at", get(state::get_token_state_at))
+
+        .route("/state/snapshot", get(state::get_state_snapshot))
         
         .route("/creators/{wallet}", get(creators::get_creator_tokens))
+        .route("/creators/{wallet}/earnings", get(creators::get_creator_earnings))
+
+        .route("/wallets/{wallet}/related", get(wallets::get_related_wallets))
+        .route("/wallets/{wallet}/transactions", get(transactions::get_wallet_transactions))
+        .route("/transactions/{signature}", get(transactions::get_transaction))
         
 
         .route("/stats", get(stats::get_stats))
+        .route("/stats/timeseries", get(stats::get_timeseries))
+        .route("/stats/fees", get(stats::get_fees))
+        .route("/stats/funnel", get(stats::get_funnel))
+        .route("/stats/sessions", get(stats::get_sessions))
+
+        .route("/leaderboards/tokens", get(leaderboards::token_leaderboard))
+        .route("/leaderboards/traders", get(leaderboards::trader_leaderboard))
+        .route("/leaderboards/creators", get(leaderboards::creator_leaderboard))
+        .route_layer(middleware::from_fn_with_state(state.clone(), freshness::attach))
+
+        .route("/stream/trades", get(stream::stream_all_trades))
+        .route("/stream/trades/{mint}", get(stream::stream_token_trades))
+        .route("/export/trades", get(export::export_trades))
+
+        .route("/admin/config", get(admin::get_runtime_config).put(admin::put_runtime_config))
+        .route("/admin/log-filter", get(admin::get_log_filter).put(admin::put_log_filter))
+        .route("/admin/snapshot", get(admin::get_snapshot))
+        .route("/admin/tokens/{mint}/resync", post(admin::resync_token))
+        .route("/admin/tokens/{mint}/state", delete(admin::evict_token_state))
+        .route("/admin/tokens/{mint}/state/refresh", post(admin::refresh_token_state))
+        .route("/admin/tokens/{mint}/metadata", post(admin::refresh_token_metadata))
+        .route("/admin/backfill", post(admin::start_backfill))
+        .route("/admin/ingestion", get(admin::get_ingestion))
+        .route("/admin/ingestion/pause", post(admin::pause_ingestion))
+        .route("/admin/ingestion/resume", post(admin::resume_ingestion))
+        .route("/admin/tracked-wallets", get(admin::list_tracked_wallets))
+        .route("/admin/tracked-wallets/{wallet}", put(admin::track_wallet).delete(admin::untrack_wallet))
+        .route("/admin/creator-lists", get(admin::list_creator_lists))
+        .route("/admin/creator-lists/{wallet}", put(admin::list_creator).delete(admin::unlist_creator))
 }
 
 
@@ -30,4 +90,8 @@ pub fn create_ws_routes() -> Router<AppState> {
 
         .route("/trades", get(websocket::trades_websocket))
         .route("/trades/{mint}", get(websocket::token_trades_websocket))
+        .route("/tokens/new", get(websocket::new_tokens_websocket))
+        .route("/graduations", get(websocket::graduations_websocket))
+        .route("/tracked", get(websocket::tracked_websocket))
+        .route("/koth", get(websocket::koth_websocket))
 }
\ No newline at end of file
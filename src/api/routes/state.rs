@@ -0,0 +1,408 @@
+use axum::{
+    extract::State,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::{Address, Query};
+use crate::database::tiering;
+use crate::dto::WithMeta;
+use crate::processor::{calculator, state};
+use crate::processor::state::TokenState;
+
+#[derive(Deserialize, IntoParams)]
+pub struct SnapshotQuery {
+    /// Include graduated tokens, whose curve state no longer changes.
+    #[serde(default)]
+    include_complete: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenStateResponse {
+    pub state: TokenState,
+    pub sol_price_usd: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StateSnapshotResponse {
+    pub count: usize,
+    pub sol_price_usd: f64,
+    pub as_of: DateTime<Utc>,
+    pub tokens: Vec<TokenState>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tokens/{mint}/state",
+    tag = "state",
+    params(("mint" = String, Path, description = "Token mint address")),
+    responses(
+        (status = 200, body = WithMeta<TokenStateResponse>),
+        (status = 404, description = "Token not in live state"),
+    )
+)]
+pub async fn get_token_state(
+    State(state): State<AppState>,
+    Address(mint): Address,
+) -> Result<Json<TokenStateResponse>, ApiError> {
+    match state::get_token_state(&state.token_state, &mint).await {
+        Some(token_state) => Ok(Json(TokenStateResponse {
+            state: token_state,
+            sol_price_usd: *state.sol_price.read().await,
+        })),
+        None => Err(ApiError::not_found("Token not in live state")),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/state/snapshot",
+    tag = "state",
+    params(SnapshotQuery),
+    responses((status = 200, body = WithMeta<StateSnapshotResponse>))
+)]
+pub async fn get_state_snapshot(
+    State(state): State<AppState>,
+    Query(query): Query<SnapshotQuery>,
+) -> Json<StateSnapshotResponse> {
+    let mut tokens: Vec<_> = state::get_all_tokens(&state.token_state).await
+        .into_iter()
+        .filter(|t| query.include_complete || !t.complete)
+        .collect();
+
+    tokens.sort_by_key(|t| std::cmp::Reverse(t.last_updated));
+
+    Json(StateSnapshotResponse {
+        count: tokens.len(),
+        sol_price_usd: *state.sol_price.read().await,
+        as_of: Utc::now(),
+        tokens,
+    })
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct StateAtQuery {
+    /// RFC 3339 time to reconstruct the curve at
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct TokenAtCreation {
+    name: String,
+    symbol: String,
+    created_at: DateTime<Utc>,
+    graduated_at: Option<DateTime<Utc>>,
+    token_total_supply: Option<i64>,
+    decimals: i16,
+}
+
+/// Curve reserves recorded by a trade, a tiered-out minute or the create
+/// event.
+#[derive(FromRow)]
+struct CurveSnapshot {
+    signature: Option<String>,
+    /// Set for the close of a minute of trades moved to cold storage.
+    #[sqlx(skip)]
+    archived: bool,
+    at: DateTime<Utc>,
+    virtual_sol_reserves: i64,
+    virtual_token_reserves: i64,
+    real_sol_reserves: i64,
+    real_token_reserves: i64,
+    sol_price_usd: Option<f64>,
+}
+
+/// A token's bonding curve as of a past time, priced the same way as live
+/// state.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HistoricalState {
+    pub mint: String,
+    pub name: String,
+    pub symbol: String,
+    /// `trade`, `archive` (the close of a minute of trades moved to cold
+    /// storage) or `creation`
+    pub source: &'static str,
+    /// The trade the reserves come from
+    pub signature: Option<String>,
+    /// When the snapshot was recorded, at or before the requested time
+    pub snapshot_at: DateTime<Utc>,
+    pub virtual_sol_reserves: i64,
+    pub virtual_token_reserves: i64,
+    pub real_sol_reserves: i64,
+    pub real_token_reserves: i64,
+    pub price_sol: f64,
+    pub market_cap_sol: f64,
+    /// Priced at the SOL/USD rate stored with the trade; null without one
+    pub market_cap_usd: Option<f64>,
+    pub bonding_curve_progress: f64,
+    pub complete: bool,
+}
+
+impl HistoricalState {
+    fn build(mint: String, token: TokenAtCreation, snapshot: CurveSnapshot, timestamp: DateTime<Utc>) -> Self {
+        let price_sol = calculator::calculate_price_sol(
+            snapshot.virtual_sol_reserves as u64,
+            snapshot.virtual_token_reserves as u64,
+            token.decimals as u8,
+        );
+        let total_supply = token.token_total_supply.unwrap_or(0) as u64;
+        let market_cap_sol = calculator::calculate_market_cap_sol(price_sol, total_supply, token.decimals as u8);
+        let complete = token.graduated_at.is_some_and(|at| at <= timestamp);
+
+        Self {
+            mint,
+            name: token.name,
+            symbol: token.symbol,
+            source: match (&snapshot.signature, snapshot.archived) {
+                (Some(_), _) => "trade",
+                (None, true) => "archive",
+                (None, false) => "creation",
+            },
+            signature: snapshot.signature,
+            snapshot_at: snapshot.at,
+            virtual_sol_reserves: snapshot.virtual_sol_reserves,
+            virtual_token_reserves: snapshot.virtual_token_reserves,
+            real_sol_reserves: snapshot.real_sol_reserves,
+            real_token_reserves: snapshot.real_token_reserves,
+            price_sol,
+            market_cap_sol,
+            market_cap_usd: snapshot.sol_price_usd.map(|usd| market_cap_sol * usd),
+            bonding_curve_progress: if complete {
+                100.0
+            } else {
+                calculator::calculate_bonding_curve_progress(snapshot.virtual_sol_reserves as u64)
+            },
+            complete,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenStateAtResponse {
+    pub timestamp: DateTime<Utc>,
+    pub state: HistoricalState,
+}
+
+/// Rebuilds the curve from the last trade at or before `timestamp`, or
+/// from the create event's reserves if the token had not traded yet. Trades only
+/// carry a block time, so trades in the same second as `timestamp` count as
+/// before it, and ties within a second are broken by signature. Trades
+/// tiered out of Postgres count through their one-minute candles: the last
+/// one starting at or before `timestamp` stands in for its closing trade,
+/// with real reserves derived from the launch reserves.
+#[utoipa::path(
+    get,
+    path = "/api/tokens/{mint}/at",
+    tag = "state",
+    params(("mint" = String, Path, description = "Token mint address"), StateAtQuery),
+    responses(
+        (status = 200, body = WithMeta<TokenStateAtResponse>),
+        (status = 404, description = "Token unknown, not yet created at that time, or without recorded launch reserves"),
+        (status = 410, description = "Archived trades without recorded launch reserves to rebuild the curve from"),
+    )
+)]
+pub async fn get_token_state_at(
+    State(state): State<AppState>,
+    Address(mint): Address,
+    Query(query): Query<StateAtQuery>,
+) -> Result<Json<TokenStateAtResponse>, ApiError> {
+    let token = sqlx::query_as::<_, TokenAtCreation>(
+        "SELECT name, symbol, created_at, graduated_at, token_total_supply, decimals
+         FROM tokens
+         WHERE mint_address = $1"
+    )
+    .bind(&mint)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Token not found"))?;
+
+    let last_trade = sqlx::query_as::<_, CurveSnapshot>(
+        "SELECT signature, timestamp AS at, virtual_sol_reserves, virtual_token_reserves,
+                real_sol_reserves, real_token_reserves, sol_price_usd::FLOAT8 AS sol_price_usd
+         FROM trades
+         WHERE token_mint = $1 AND timestamp <= $2
+         ORDER BY timestamp DESC, signature DESC
+         LIMIT 1"
+    )
+    .bind(&mint)
+    .bind(query.timestamp)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let cold = tiering::fetch_cold_close(&state.db, &mint, query.timestamp)
+        .await?
+        .filter(|close| last_trade.as_ref().is_none_or(|trade| close.bucket > trade.at));
+
+    let snapshot = match (last_trade, cold) {
+        (_, Some(close)) => fetch_archived_snapshot(&state.db, &mint, close).await?,
+        (Some(trade), None) => trade,
+        (None, None) if token.created_at <= query.timestamp => fetch_creation_snapshot(&state.db, &mint)
+            .await?
+            .ok_or_else(|| ApiError::not_found("No launch reserves recorded for this token"))?,
+        (None, None) => return Err(ApiError::not_found("Token had not been created at that time")),
+    };
+
+    Ok(Json(TokenStateAtResponse {
+        timestamp: query.timestamp,
+        state: HistoricalState::build(mint, token, snapshot, query.timestamp),
+    }))
+}
+
+/// The curve after a tiered-out minute. Candles keep only virtual reserves;
+/// the real ones move with them from launch, so they are derived from the
+/// launch reserves, and the SOL price comes from the price history.
+async fn fetch_archived_snapshot(
+    db: &sqlx::PgPool,
+    mint: &str,
+    close: tiering::ColdClose,
+) -> Result<CurveSnapshot, ApiError> {
+    let Some(launch) = fetch_creation_snapshot(db, mint).await? else {
+        return Err(ApiError::archived(
+            "Trades up to this time were archived and no launch reserves are recorded to rebuild the curve from",
+            tiering::fetch_cold_periods_at(db, &[close.bucket])
+                .await?
+                .into_iter()
+                .map(|period| period.object_key)
+                .collect(),
+        ));
+    };
+    let sol_price_usd: Option<f64> = sqlx::query_scalar(
+        "SELECT price_usd FROM sol_price_history WHERE timestamp <= $1 ORDER BY timestamp DESC LIMIT 1"
+    )
+    .bind(close.bucket)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(CurveSnapshot {
+        signature: None,
+        archived: true,
+        at: close.bucket,
+        virtual_sol_reserves: close.close_sol,
+        virtual_token_reserves: close.close_tokens,
+        real_sol_reserves: (close.close_sol - launch.virtual_sol_reserves).max(0),
+        real_token_reserves: (launch.real_token_reserves - (launch.virtual_token_reserves - close.close_tokens)).max(0),
+        sol_price_usd,
+    })
+}
+
+/// The launch reserves kept with the `created` timeline entry. The `tokens`
+/// row can't stand in for them: a curve resync overwrites its reserves.
+async fn fetch_creation_snapshot(db: &sqlx::PgPool, mint: &str) -> Result<Option<CurveSnapshot>, ApiError> {
+    let snapshot = sqlx::query_as::<_, CurveSnapshot>(
+        "SELECT NULL::VARCHAR AS signature, occurred_at AS at,
+                (details->>'virtual_sol_reserves')::BIGINT AS virtual_sol_reserves,
+                (details->>'virtual_token_reserves')::BIGINT AS virtual_token_reserves,
+                0::BIGINT AS real_sol_reserves,
+                (details->>'real_token_reserves')::BIGINT AS real_token_reserves,
+                NULL::FLOAT8 AS sol_price_usd
+         FROM token_events
+         WHERE token_mint = $1 AND kind = 'created'
+           AND details ?& ARRAY['virtual_sol_reserves', 'virtual_token_reserves', 'real_token_reserves']"
+    )
+    .bind(mint)
+    .fetch_optional(db)
+    .await?;
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_historical_state() {
+        let created: DateTime<Utc> = "2025-11-20T12:00:00Z".parse().unwrap();
+        let token = || TokenAtCreation {
+            name: "Token".into(),
+            symbol: "TKN".into(),
+            created_at: created,
+            graduated_at: Some(created + chrono::Duration::hours(2)),
+            token_total_supply: Some(1_000_000_000_000_000),
+            decimals: 6,
+        };
+        let snapshot = || CurveSnapshot {
+            signature: Some("sig".into()),
+            archived: false,
+            at: created + chrono::Duration::minutes(30),
+            virtual_sol_reserves: 60_000_000_000,
+            virtual_token_reserves: 536_500_000_000_000,
+            real_sol_reserves: 30_000_000_000,
+            real_token_reserves: 256_600_000_000_000,
+            sol_price_usd: Some(150.0),
+        };
+
+        let before = HistoricalState::build("mint".into(), token(), snapshot(), created + chrono::Duration::hours(1));
+        assert_eq!(before.source, "trade");
+        assert!(!before.complete);
+        // Twice the SOL over half the tokens: four times the launch price.
+        assert!((before.price_sol - 4.0 * 30.0 / 1_073_000_000.0).abs() < 1e-15);
+        assert!((before.market_cap_sol - before.price_sol * 1_000_000_000.0).abs() < 1e-6);
+        assert_eq!(before.market_cap_usd, Some(before.market_cap_sol * 150.0));
+        assert!(before.bonding_curve_progress < 100.0);
+
+        let after = HistoricalState::build("mint".into(), token(), snapshot(), created + chrono::Duration::hours(3));
+        assert!(after.complete);
+        assert_eq!(after.bonding_curve_progress, 100.0);
+    }
+
+    /// Runs against a fresh database created from `DATABASE_URL` with the
+    /// migrations applied.
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_creation_snapshot_comes_from_create_event(db: sqlx::PgPool) {
+        sqlx::query(
+            "INSERT INTO token_events (token_mint, kind, occurred_at, signature, details)
+             VALUES ('mintA', 'created', NOW(), 'sigA', $1), ('mintB', 'created', NOW(), 'sigB', '{\"name\": \"Old\"}')"
+        )
+        .bind(serde_json::json!({
+            "virtual_sol_reserves": 30_000_000_000u64,
+            "virtual_token_reserves": 1_073_000_000_000_000u64,
+            "real_token_reserves": 793_100_000_000_000u64,
+        }))
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let snapshot = fetch_creation_snapshot(&db, "mintA").await.unwrap().unwrap();
+        assert_eq!(snapshot.signature, None);
+        assert_eq!(snapshot.virtual_sol_reserves, 30_000_000_000);
+        assert_eq!(snapshot.virtual_token_reserves, 1_073_000_000_000_000);
+        assert_eq!(snapshot.real_sol_reserves, 0);
+        assert_eq!(snapshot.real_token_reserves, 793_100_000_000_000);
+
+        // Entries written before the reserves were kept can't be used.
+        assert!(fetch_creation_snapshot(&db, "mintB").await.unwrap().is_none());
+        assert!(fetch_creation_snapshot(&db, "mintC").await.unwrap().is_none());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_archived_snapshot_derives_real_reserves(db: sqlx::PgPool) {
+        sqlx::query(
+            "INSERT INTO token_events (token_mint, kind, occurred_at, signature, details)
+             VALUES ('mintA', 'created', NOW(), 'sigA', $1)"
+        )
+        .bind(serde_json::json!({
+            "virtual_sol_reserves": 30_000_000_000u64,
+            "virtual_token_reserves": 1_073_000_000_000_000u64,
+            "real_token_reserves": 793_100_000_000_000u64,
+        }))
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let close = || tiering::ColdClose {
+            bucket: "2025-11-20T12:00:00Z".parse().unwrap(),
+            close_sol: 60_000_000_000,
+            close_tokens: 536_500_000_000_000,
+        };
+        let snapshot = fetch_archived_snapshot(&db, "mintA", close()).await.unwrap();
+        assert!(snapshot.archived);
+        assert_eq!(snapshot.real_sol_reserves, 30_000_000_000);
+        assert_eq!(snapshot.real_token_reserves, 256_600_000_000_000);
+
+        assert!(matches!(fetch_archived_snapshot(&db, "mintB", close()).await, Err(ApiError::Archived { .. })));
+    }
+}
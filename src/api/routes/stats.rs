@@ -1,14 +1,50 @@
 use axum::{
     extract::State,
-    http::StatusCode,
     response::Json,
 };
-use serde_json::{json, Value};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::Query;
+use crate::api::params::{parse_interval, window_start};
+use crate::background::market_stats::Granularity;
+use crate::database;
+use crate::database::model::IndexerSession;
+use crate::dto::WithMeta;
+use crate::storage::response_cache::{self, CacheScope};
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StatsResponse {
+    pub total_transactions: i64,
+    pub total_tokens_created: i64,
+    pub total_trades: i64,
+    pub total_volume_sol: f64,
+    pub total_volume_usd: f64,
+    pub sol_price_usd: f64,
+    pub last_processed_slot: i64,
+    pub last_updated: Option<chrono::DateTime<chrono::Utc>>,
+    pub rates: StatsRates,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/stats",
+    tag = "stats",
+    responses((status = 200, body = WithMeta<StatsResponse>))
+)]
 pub async fn get_stats(
     State(state): State<AppState>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+) -> Result<Json<StatsResponse>, ApiError> {
+    let body = response_cache::get_or_compute(state.redis.as_ref(), &state.metrics, CacheScope::Stats, "global", || {
+        compute_stats(&state)
+    }).await?;
+
+    Ok(Json(body))
+}
+
+async fn compute_stats(state: &AppState) -> Result<StatsResponse, ApiError> {
     let stats = sqlx::query!(
         "SELECT 
             total_transactions, 
@@ -21,38 +57,603 @@ pub async fn get_stats(
          WHERE id = 1"
     )
     .fetch_optional(&state.db)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database error: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
-    })?;
+    .await?;
     
     let sol_price = *state.sol_price.read().await;
+    let rates = activity_rates(&state.db).await?;
     
     if let Some(stats) = stats {
         let volume_sol = stats.total_volume_sol
             .map(|v| v.to_string().parse::<f64>().unwrap_or(0.0))
             .unwrap_or(0.0);
         
-        Ok(Json(json!({
-            "total_transactions": stats.total_transactions,
-            "total_tokens_created": stats.total_tokens_created,
-            "total_trades": stats.total_trades,
-            "total_volume_sol": volume_sol,
-            "total_volume_usd": volume_sol * sol_price,
-            "sol_price_usd": sol_price,
-            "last_processed_slot": stats.last_processed_slot,
-            "last_updated": stats.last_updated,
-        })))
+        Ok(StatsResponse {
+            total_transactions: stats.total_transactions.unwrap_or(0),
+            total_tokens_created: stats.total_tokens_created.unwrap_or(0),
+            total_trades: stats.total_trades.unwrap_or(0),
+            total_volume_sol: volume_sol,
+            total_volume_usd: volume_sol * sol_price,
+            sol_price_usd: sol_price,
+            last_processed_slot: stats.last_processed_slot.unwrap_or(0),
+            last_updated: stats.last_updated,
+            rates,
+        })
     } else {
-        Ok(Json(json!({
-            "total_transactions": 0,
-            "total_tokens_created": 0,
-            "total_trades": 0,
-            "total_volume_sol": 0.0,
-            "total_volume_usd": 0.0,
-            "sol_price_usd": sol_price,
-            "last_processed_slot": 0,
-        })))
-    }
-}
\ No newline at end of file
+        Ok(StatsResponse {
+            total_transactions: 0,
+            total_tokens_created: 0,
+            total_trades: 0,
+            total_volume_sol: 0.0,
+            total_volume_usd: 0.0,
+            sol_price_usd: sol_price,
+            last_processed_slot: 0,
+            last_updated: None,
+            rates,
+        })
+    }
+}
+
+/// Market activity per unit of time over a window of complete hours.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ActivityRates {
+    pub trades_per_min: f64,
+    pub tokens_per_hour: f64,
+    pub volume_sol_per_hour: f64,
+    pub volume_usd_per_hour: f64,
+    pub graduations_per_hour: f64,
+}
+
+/// Rates over the last complete hour and the last 24 complete hours, so a
+/// half-aggregated current hour doesn't drag them down.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct StatsRates {
+    pub last_1h: ActivityRates,
+    pub last_24h: ActivityRates,
+}
+
+#[derive(Debug, Default, FromRow)]
+struct WindowTotals {
+    trades: f64,
+    tokens_created: f64,
+    volume_sol: f64,
+    volume_usd: f64,
+    graduations: f64,
+}
+
+impl WindowTotals {
+    fn rates(&self, hours: f64) -> ActivityRates {
+        ActivityRates {
+            trades_per_min: self.trades / (hours * 60.0),
+            tokens_per_hour: self.tokens_created / hours,
+            volume_sol_per_hour: self.volume_sol / hours,
+            volume_usd_per_hour: self.volume_usd / hours,
+            graduations_per_hour: self.graduations / hours,
+        }
+    }
+}
+
+async fn activity_rates(pool: &sqlx::PgPool) -> Result<StatsRates, ApiError> {
+    let window = |hours: i32| sqlx::query_as::<_, WindowTotals>(
+        "SELECT COALESCE(SUM(trades), 0)::FLOAT8 AS trades,
+                COALESCE(SUM(tokens_created), 0)::FLOAT8 AS tokens_created,
+                COALESCE(SUM(volume_lamports) / 1e9, 0)::FLOAT8 AS volume_sol,
+                COALESCE(SUM(volume_usd), 0)::FLOAT8 AS volume_usd,
+                COALESCE(SUM(graduations), 0)::FLOAT8 AS graduations
+         FROM market_stats_hourly
+         WHERE bucket >= date_trunc('hour', NOW()) - $1 * INTERVAL '1 hour'
+           AND bucket < date_trunc('hour', NOW())"
+    )
+    .bind(hours)
+    .fetch_one(pool);
+
+    Ok(StatsRates {
+        last_1h: window(1).await?.rates(1.0),
+        last_24h: window(24).await?.rates(24.0),
+    })
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct TimeseriesQuery {
+    /// `tokens_created`, `trades`, `volume`, `volume_usd`, `graduations`,
+    /// `unique_wallets` or `protocol_fees`
+    metric: String,
+    /// Whole hours, e.g. `1h`, `6h`, `1d`; `unique_wallets` needs `1h` or `1d`
+    #[serde(default = "default_interval")]
+    interval: String,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+fn default_interval() -> String { "1h".to_string() }
+
+/// Points returned when the caller doesn't pass `from`.
+const DEFAULT_POINTS: i32 = 48;
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct TimeseriesPoint {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimeseriesResponse {
+    pub metric: String,
+    pub interval: String,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    pub points: Vec<TimeseriesPoint>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/stats/timeseries",
+    tag = "stats",
+    params(TimeseriesQuery),
+    responses(
+        (status = 200, body = WithMeta<TimeseriesResponse>),
+        (status = 400, description = "Unknown metric or interval"),
+    )
+)]
+pub async fn get_timeseries(
+    State(state): State<AppState>,
+    Query(query): Query<TimeseriesQuery>,
+) -> Result<Json<TimeseriesResponse>, ApiError> {
+    let value_expr = match query.metric.as_str() {
+        "tokens_created" => "SUM(tokens_created)::FLOAT8",
+        "trades" => "SUM(trades)::FLOAT8",
+        "volume" => "(SUM(volume_lamports) / 1e9)::FLOAT8",
+        "volume_usd" => "SUM(volume_usd)::FLOAT8",
+        "graduations" => "SUM(graduations)::FLOAT8",
+        "unique_wallets" => "SUM(unique_wallets)::FLOAT8",
+        "protocol_fees" => "(SUM(protocol_fee_lamports) / 1e9)::FLOAT8",
+        _ => return Err(ApiError::bad_request(
+            "metric must be one of tokens_created, trades, volume, volume_usd, graduations, unique_wallets, protocol_fees",
+        )),
+    };
+
+    // Coarser buckets are sums of whole hours or days, read from whichever
+    // table divides the interval.
+    let interval = parse_interval(&query.interval)
+        .filter(|d| d.num_seconds() % 3600 == 0)
+        .ok_or_else(|| ApiError::bad_request("interval must be a whole number of hours, e.g. 1h, 6h, 1d"))?;
+    let granularity = if interval.num_seconds() % 86_400 == 0 { Granularity::Day } else { Granularity::Hour };
+
+    // Distinct counts don't add up across buckets.
+    if query.metric == "unique_wallets" && !matches!(interval.num_seconds(), 3600 | 86_400) {
+        return Err(ApiError::bad_request("unique_wallets is only available at 1h or 1d intervals"));
+    }
+
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = match query.from {
+        Some(from) => from,
+        None => window_start(to, interval, DEFAULT_POINTS).ok_or_else(|| ApiError::bad_request("interval too large"))?,
+    };
+
+    let sql = format!(
+        "SELECT date_bin($1 * INTERVAL '1 second', bucket, TIMESTAMPTZ '2000-01-01') AS timestamp,
+                {} AS value
+         FROM {}
+         WHERE bucket >= $2 AND bucket < $3
+         GROUP BY 1
+         ORDER BY 1",
+        value_expr,
+        granularity.table()
+    );
+
+    let points = sqlx::query_as::<_, TimeseriesPoint>(&sql)
+        .bind(interval.num_seconds() as f64)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&state.db)
+        .await?;
+
+    Ok(Json(TimeseriesResponse { metric: query.metric, interval: query.interval, from, to, points }))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct FeesQuery {
+    /// Whole hours, e.g. `1h`, `1d` (default)
+    #[serde(default = "default_fees_interval")]
+    interval: String,
+    /// Defaults to 7 days before `to`
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+fn default_fees_interval() -> String { "1d".to_string() }
+
+const DEFAULT_FEES_DAYS: i64 = 7;
+/// The recipient and token breakdowns scan raw trades, so the range is capped.
+const MAX_FEES_RANGE_DAYS: i64 = 31;
+const TOP_FEE_TOKENS: i64 = 20;
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct FeesPoint {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub protocol_fees_sol: f64,
+    pub creator_fees_sol: f64,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct FeeTotals {
+    pub trades: i64,
+    pub protocol_fees_sol: f64,
+    pub protocol_fees_usd: Option<f64>,
+    pub creator_fees_sol: f64,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct FeeRecipientTotal {
+    pub fee_recipient: String,
+    pub fees_sol: f64,
+    pub trades: i64,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct TokenFeeTotal {
+    pub mint_address: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub fees_sol: f64,
+    pub trades: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeesResponse {
+    pub interval: String,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    pub totals: FeeTotals,
+    pub points: Vec<FeesPoint>,
+    /// Every fee recipient, highest first
+    pub recipients: Vec<FeeRecipientTotal>,
+    pub tokens: Vec<TokenFeeTotal>,
+}
+
+/// Protocol fees (`fee`, paid to `fee_recipient`) alongside creator fees.
+/// USD totals only include trades stored with a SOL price. Trades tiered
+/// out of Postgres are added from their one-minute sums, a minute straddling
+/// either bound counting whole.
+#[utoipa::path(
+    get,
+    path = "/api/stats/fees",
+    tag = "stats",
+    params(FeesQuery),
+    responses(
+        (status = 200, body = WithMeta<FeesResponse>),
+        (status = 400, description = "Invalid interval or range"),
+    )
+)]
+pub async fn get_fees(
+    State(state): State<AppState>,
+    Query(query): Query<FeesQuery>,
+) -> Result<Json<FeesResponse>, ApiError> {
+    let interval = parse_interval(&query.interval)
+        .filter(|d| d.num_seconds() % 3600 == 0)
+        .ok_or_else(|| ApiError::bad_request("interval must be a whole number of hours, e.g. 1h, 6h, 1d"))?;
+    let granularity = if interval.num_seconds() % 86_400 == 0 { Granularity::Day } else { Granularity::Hour };
+
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::days(DEFAULT_FEES_DAYS));
+    if from >= to || to - from > chrono::Duration::days(MAX_FEES_RANGE_DAYS) {
+        return Err(ApiError::bad_request(format!(
+            "from must be before to and the range at most {} days",
+            MAX_FEES_RANGE_DAYS
+        )));
+    }
+
+    let totals = sqlx::query_as::<_, FeeTotals>(
+        "SELECT COALESCE(SUM(trades), 0)::BIGINT AS trades,
+                COALESCE(SUM(fee), 0)::FLOAT8 / 1e9 AS protocol_fees_sol,
+                SUM(fees_usd) AS protocol_fees_usd,
+                COALESCE(SUM(creator_fee), 0)::FLOAT8 / 1e9 AS creator_fees_sol
+         FROM (
+             SELECT 1::BIGINT AS trades, fee::NUMERIC AS fee, (fee / 1e9 * sol_price_usd)::FLOAT8 AS fees_usd,
+                    creator_fee::NUMERIC AS creator_fee
+             FROM trades
+             WHERE timestamp >= $1 AND timestamp < $2
+             UNION ALL
+             SELECT trades, fee_lamports, fees_usd, creator_fee_lamports
+             FROM cold_trade_sides
+             WHERE bucket + INTERVAL '1 minute' > $1 AND bucket < $2
+         ) fees"
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_one(&state.db)
+    .await?;
+
+    let points = sqlx::query_as::<_, FeesPoint>(&format!(
+        "SELECT date_bin($1 * INTERVAL '1 second', bucket, TIMESTAMPTZ '2000-01-01') AS timestamp,
+                (SUM(protocol_fee_lamports) / 1e9)::FLOAT8 AS protocol_fees_sol,
+                (SUM(creator_fee_lamports) / 1e9)::FLOAT8 AS creator_fees_sol
+         FROM {}
+         WHERE bucket >= $2 AND bucket < $3
+         GROUP BY 1
+         ORDER BY 1",
+        granularity.table()
+    ))
+    .bind(interval.num_seconds() as f64)
+    .bind(from)
+    .bind(to)
+    .fetch_all(&state.db)
+    .await?;
+
+    let recipients = sqlx::query_as::<_, FeeRecipientTotal>(
+        "SELECT fee_recipient, SUM(fee)::FLOAT8 / 1e9 AS fees_sol, SUM(trades)::BIGINT AS trades
+         FROM (
+             SELECT fee_recipient, fee::NUMERIC AS fee, 1::BIGINT AS trades
+             FROM trades
+             WHERE timestamp >= $1 AND timestamp < $2
+             UNION ALL
+             SELECT fee_recipient, fee_lamports, trades
+             FROM cold_fee_recipients
+             WHERE bucket + INTERVAL '1 minute' > $1 AND bucket < $2
+         ) fees
+         GROUP BY fee_recipient
+         ORDER BY fees_sol DESC"
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(&state.db)
+    .await?;
+
+    let tokens = sqlx::query_as::<_, TokenFeeTotal>(
+        "SELECT f.token_mint AS mint_address, t.name, t.symbol,
+                SUM(f.fee)::FLOAT8 / 1e9 AS fees_sol, SUM(f.trades)::BIGINT AS trades
+         FROM (
+             SELECT token_mint, fee::NUMERIC AS fee, 1::BIGINT AS trades
+             FROM trades
+             WHERE timestamp >= $1 AND timestamp < $2
+             UNION ALL
+             SELECT token_mint, fee_lamports, trades
+             FROM cold_trade_sides
+             WHERE bucket + INTERVAL '1 minute' > $1 AND bucket < $2
+         ) f
+         LEFT JOIN tokens t ON t.mint_address = f.token_mint
+         GROUP BY f.token_mint, t.name, t.symbol
+         ORDER BY fees_sol DESC
+         LIMIT $3"
+    )
+    .bind(from)
+    .bind(to)
+    .bind(TOP_FEE_TOKENS)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(FeesResponse { interval: query.interval, from, to, totals, points, recipients, tokens }))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct FunnelQuery {
+    /// `day` (default) or `creator`
+    #[serde(default = "default_funnel_group")]
+    group: String,
+    /// Restricts day cohorts to one creator's launches
+    creator: Option<String>,
+    /// Defaults to 30 days before `to`; cohorts are whole UTC days
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+    /// Creators returned with `group=creator`, most launches first (default 50, max 500)
+    limit: Option<i64>,
+}
+
+fn default_funnel_group() -> String { "day".to_string() }
+
+const DEFAULT_FUNNEL_DAYS: i64 = 30;
+const MAX_FUNNEL_RANGE_DAYS: i64 = 366;
+
+/// Share of `created` launches that reached each stage, in percent.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct FunnelRates {
+    pub reached_10: f64,
+    pub reached_50: f64,
+    pub graduated: f64,
+}
+
+/// Launches in a cohort and how many got past each stage of the bonding
+/// curve (10% and 50% of the 85 SOL target, then graduation).
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct FunnelCohort {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+    pub created: i64,
+    pub reached_10: i64,
+    pub reached_50: i64,
+    pub graduated: i64,
+    #[sqlx(skip)]
+    pub rates: FunnelRates,
+}
+
+impl FunnelCohort {
+    fn with_rates(mut self) -> Self {
+        let pct = |n: i64| if self.created > 0 { n as f64 * 100.0 / self.created as f64 } else { 0.0 };
+        self.rates = FunnelRates {
+            reached_10: pct(self.reached_10),
+            reached_50: pct(self.reached_50),
+            graduated: pct(self.graduated),
+        };
+        self
+    }
+}
+
+/// `cohorts` are days (`group=day`) or creators (`group=creator`); `totals`
+/// covers every launch in the range.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FunnelResponse {
+    pub group: String,
+    pub creator: Option<String>,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    pub totals: FunnelCohort,
+    pub cohorts: Vec<FunnelCohort>,
+}
+
+/// Launch funnel by creation-day cohort, or per creator over the range.
+/// Cohorts keep filling in as their tokens progress; refreshed every 5 minutes.
+#[utoipa::path(
+    get,
+    path = "/api/stats/funnel",
+    tag = "stats",
+    params(FunnelQuery),
+    responses(
+        (status = 200, body = WithMeta<FunnelResponse>),
+        (status = 400, description = "Invalid group, range or limit"),
+    )
+)]
+pub async fn get_funnel(
+    State(state): State<AppState>,
+    Query(query): Query<FunnelQuery>,
+) -> Result<Json<FunnelResponse>, ApiError> {
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::days(DEFAULT_FUNNEL_DAYS));
+    if from >= to || to - from > chrono::Duration::days(MAX_FUNNEL_RANGE_DAYS) {
+        return Err(ApiError::bad_request(format!(
+            "from must be before to and the range at most {} days",
+            MAX_FUNNEL_RANGE_DAYS
+        )));
+    }
+    let limit = query.limit.unwrap_or(50);
+    if !(1..=500).contains(&limit) {
+        return Err(ApiError::bad_request("limit must be between 1 and 500"));
+    }
+
+    let cohorts = match (query.group.as_str(), &query.creator) {
+        ("day", None) => sqlx::query_as::<_, FunnelCohort>(
+            "SELECT day, NULL::VARCHAR AS creator, created, reached_10, reached_50, graduated
+             FROM funnel_daily
+             WHERE day >= date_trunc('day', $1::TIMESTAMPTZ) AND day < $2
+             ORDER BY day"
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&state.db)
+        .await?,
+        ("day", Some(creator)) => sqlx::query_as::<_, FunnelCohort>(
+            "SELECT day, creator_wallet AS creator, created, reached_10, reached_50, graduated
+             FROM funnel_creator_daily
+             WHERE creator_wallet = $3 AND day >= date_trunc('day', $1::TIMESTAMPTZ) AND day < $2
+             ORDER BY day"
+        )
+        .bind(from)
+        .bind(to)
+        .bind(creator)
+        .fetch_all(&state.db)
+        .await?,
+        ("creator", None) => sqlx::query_as::<_, FunnelCohort>(
+            "SELECT NULL::TIMESTAMPTZ AS day, creator_wallet AS creator,
+                    SUM(created)::BIGINT AS created, SUM(reached_10)::BIGINT AS reached_10,
+                    SUM(reached_50)::BIGINT AS reached_50, SUM(graduated)::BIGINT AS graduated
+             FROM funnel_creator_daily
+             WHERE day >= date_trunc('day', $1::TIMESTAMPTZ) AND day < $2
+             GROUP BY creator_wallet
+             ORDER BY created DESC, graduated DESC, creator_wallet
+             LIMIT $3"
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&state.db)
+        .await?,
+        ("creator", Some(_)) => return Err(ApiError::bad_request("creator only applies to group=day")),
+        _ => return Err(ApiError::bad_request("group must be day or creator")),
+    };
+
+    let (table, creator_filter) = match query.creator {
+        Some(_) => ("funnel_creator_daily", "AND creator_wallet = $3"),
+        None => ("funnel_daily", "AND $3::VARCHAR IS NULL"),
+    };
+    let totals = sqlx::query_as::<_, FunnelCohort>(&format!(
+        "SELECT NULL::TIMESTAMPTZ AS day, NULL::VARCHAR AS creator,
+                COALESCE(SUM(created), 0)::BIGINT AS created, COALESCE(SUM(reached_10), 0)::BIGINT AS reached_10,
+                COALESCE(SUM(reached_50), 0)::BIGINT AS reached_50, COALESCE(SUM(graduated), 0)::BIGINT AS graduated
+         FROM {}
+         WHERE day >= date_trunc('day', $1::TIMESTAMPTZ) AND day < $2 {}",
+        table, creator_filter
+    ))
+    .bind(from)
+    .bind(to)
+    .bind(&query.creator)
+    .fetch_one(&state.db)
+    .await?;
+
+    let cohorts: Vec<_> = cohorts.into_iter().map(FunnelCohort::with_rates).collect();
+    Ok(Json(FunnelResponse {
+        group: query.group,
+        creator: query.creator,
+        from,
+        to,
+        totals: totals.with_rates(),
+        cohorts,
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SessionsQuery {
+    /// Sessions returned, newest first (default 20, max 200)
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionsResponse {
+    /// Newest first
+    pub sessions: Vec<IndexerSession>,
+}
+
+/// Recent indexer and backfill runs with their uptime and what they
+/// processed. Live counts are refreshed every minute.
+#[utoipa::path(
+    get,
+    path = "/api/stats/sessions",
+    tag = "stats",
+    params(SessionsQuery),
+    responses(
+        (status = 200, body = WithMeta<SessionsResponse>),
+        (status = 400, description = "Invalid limit"),
+    )
+)]
+pub async fn get_sessions(
+    State(state): State<AppState>,
+    Query(query): Query<SessionsQuery>,
+) -> Result<Json<SessionsResponse>, ApiError> {
+    let limit = query.limit.unwrap_or(20);
+    if !(1..=200).contains(&limit) {
+        return Err(ApiError::bad_request("limit must be between 1 and 200"));
+    }
+
+    let sessions = database::fetch_sessions(&state.db, limit).await?;
+    Ok(Json(SessionsResponse { sessions }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_funnel_rates() {
+        let cohort = FunnelCohort {
+            day: None,
+            creator: Some("alice".to_string()),
+            created: 8,
+            reached_10: 4,
+            reached_50: 2,
+            graduated: 1,
+            rates: FunnelRates::default(),
+        }
+        .with_rates();
+        assert_eq!((cohort.rates.reached_10, cohort.rates.reached_50, cohort.rates.graduated), (50.0, 25.0, 12.5));
+
+        let empty = FunnelCohort { created: 0, ..cohort }.with_rates();
+        assert_eq!(empty.rates.graduated, 0.0);
+    }
+
+    #[test]
+    fn test_activity_rates() {
+        let totals = WindowTotals { trades: 2880.0, tokens_created: 48.0, volume_sol: 240.0, volume_usd: 36_000.0, graduations: 2.0 };
+        let rates = totals.rates(24.0);
+        assert_eq!((rates.trades_per_min, rates.tokens_per_hour, rates.volume_sol_per_hour), (2.0, 2.0, 10.0));
+        assert_eq!(rates.volume_usd_per_hour, 1500.0);
+    }
+}
@@ -0,0 +1,79 @@
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream, StreamExt};
+use std::convert::Infallible;
+use tracing::info;
+use crate::api::AppState;
+use crate::api::extract::Address;
+
+fn trade_events(
+    state: &AppState,
+    channel: &str,
+    welcome: serde_json::Value,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>> + use<>> {
+    let client = state.metrics.track_sse_client();
+    let trades = state.pubsub.subscribe_stream(channel);
+
+    let connected = Event::default().event("connected").data(welcome.to_string());
+    // The guard rides along with the stream so the client stays counted
+    // until axum drops it on disconnect.
+    let events = stream::once(async move { Ok(connected) })
+        .chain(trades.map(move |payload| {
+            let _ = &client;
+            Ok(Event::default().event("trade").data(payload.as_ref()))
+        }));
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/stream/trades",
+    tag = "stream",
+    responses(
+        (status = 200, description = "`trade` events, one JSON trade per event", content_type = "text/event-stream"),
+    )
+)]
+pub async fn stream_all_trades(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    info!("📡 New SSE client connected: All trades");
+
+    trade_events(
+        &state,
+        "pump:trades",
+        serde_json::json!({
+            "type": "connected",
+            "channel": "pump:trades",
+            "message": "Connected to all trades stream"
+        }),
+    )
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/stream/trades/{mint}",
+    tag = "stream",
+    params(("mint" = String, Path, description = "Token mint address")),
+    responses(
+        (status = 200, description = "`trade` events for one token", content_type = "text/event-stream"),
+    )
+)]
+pub async fn stream_token_trades(
+    State(state): State<AppState>,
+    Address(mint): Address,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    info!("📡 New SSE client connected: Token {}", mint);
+
+    let channel = format!("pump:trades:{}", mint);
+    let welcome = serde_json::json!({
+        "type": "connected",
+        "channel": channel,
+        "mint": mint,
+        "message": format!("Connected to token trades stream for {}", mint)
+    });
+
+    trade_events(&state, &channel, welcome)
+}
@@ -0,0 +1,89 @@
+use axum::{
+    extract::State,
+    response::Json,
+};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::Address;
+use crate::dto::WithMeta;
+
+/// Events returned, oldest first.
+const MAX_EVENTS: i64 = 1000;
+
+#[derive(Debug, FromRow)]
+struct TimelineRow {
+    kind: String,
+    occurred_at: chrono::DateTime<chrono::Utc>,
+    signature: Option<String>,
+    details: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TimelineEvent {
+    /// `created`, `first_trade`, `progress_25`, `progress_50`,
+    /// `progress_75`, `ath`, `creator_sell` or `graduated`
+    pub kind: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub signature: Option<String>,
+    /// Kind-specific fields, e.g. `price_sol` for `ath`
+    #[schema(value_type = Object)]
+    pub details: Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenTimelineResponse {
+    pub mint: String,
+    /// Oldest first
+    pub events: Vec<TimelineEvent>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tokens/{mint}/timeline",
+    tag = "tokens",
+    params(("mint" = String, Path, description = "Token mint address")),
+    responses(
+        (status = 200, body = WithMeta<TokenTimelineResponse>),
+        (status = 404, description = "Token not found"),
+    )
+)]
+pub async fn get_token_timeline(
+    State(state): State<AppState>,
+    Address(mint): Address,
+) -> Result<Json<TokenTimelineResponse>, ApiError> {
+    let exists: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM tokens WHERE mint_address = $1")
+        .bind(&mint)
+        .fetch_optional(&state.db)
+        .await?;
+    if exists.is_none() {
+        return Err(ApiError::not_found("Token not found"));
+    }
+
+    let rows = sqlx::query_as::<_, TimelineRow>(
+        "SELECT kind, occurred_at, signature, details::TEXT AS details
+         FROM token_events
+         WHERE token_mint = $1
+         ORDER BY occurred_at, id
+         LIMIT $2"
+    )
+    .bind(&mint)
+    .bind(MAX_EVENTS)
+    .fetch_all(&state.db)
+    .await?;
+
+    let events: Vec<TimelineEvent> = rows
+        .into_iter()
+        .map(|row| TimelineEvent {
+            kind: row.kind,
+            timestamp: row.occurred_at,
+            signature: row.signature,
+            details: serde_json::from_str(&row.details).unwrap_or(Value::Null),
+        })
+        .collect();
+
+    Ok(Json(TokenTimelineResponse { mint, events }))
+}
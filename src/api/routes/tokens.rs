@@ -1,55 +1,210 @@
 use axum::{
-    extract::{State, Path, Query},
-    http::StatusCode,
+    extract::State,
     response::Json,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
 use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::{Address, Query};
+use crate::api::params::{parse_interval, parse_pubkey, window_start};
+use crate::dto::{Pagination, TokenDetail, TokenListResponse, TokenResponse, WithMeta};
+use crate::processor::creator_lists::CreatorList;
+use crate::processor::momentum::Momentum;
+use crate::processor::state::{self, TokenState};
+use crate::storage::response_cache::{self, CacheScope};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct ListTokensQuery {
+    /// Page size (max 100)
     #[serde(default = "default_limit")]
     limit: i64,
     #[serde(default)]
     offset: i64,
+    /// `market_cap`, `created_at` (default), `last_trade`, `volume` (24h USD),
+    /// or from live state `trades_per_minute` or `buy_pressure` (net buys
+    /// over 5 minutes)
     #[serde(default)]
     sort: String,
+    /// Only tokens traded within this window, e.g. `5m` or `1h`
+    active_within: Option<String>,
+    /// Only tokens launched by this wallet
+    creator: Option<String>,
 }
 
 fn default_limit() -> i64 { 50 }
 
-#[derive(Debug, Serialize, FromRow)]
-pub struct TokenResponse {
-    pub mint_address: String,          
-    pub name: String,
-    pub symbol: String,
-    pub uri: String,
-    pub creator_wallet: String,        
-    pub market_cap_usd: Option<bigdecimal::BigDecimal>,
-    pub bonding_curve_progress: Option<bigdecimal::BigDecimal>,
-    pub complete: bool,
-    pub created_at: chrono::DateTime<chrono::Utc>,
-}
-
+#[utoipa::path(
+    get,
+    path = "/api/tokens",
+    tag = "tokens",
+    params(ListTokensQuery),
+    responses((status = 200, body = WithMeta<TokenListResponse>))
+)]
 pub async fn list_tokens(
     State(state): State<AppState>,
     Query(query): Query<ListTokensQuery>,
-) -> Result<Json<Value>, (StatusCode, String)> {
-    let limit = query.limit.min(100); 
-    let offset = query.offset;
+) -> Result<Json<TokenListResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 100);
+    let offset = query.offset.max(0);
+    let creator = query
+        .creator
+        .as_deref()
+        .map(|s| parse_pubkey(s).ok_or_else(|| ApiError::bad_request("creator must be a base58 pubkey")))
+        .transpose()?;
     
-    let order_by = match query.sort.as_str() {
-        "market_cap" => "market_cap_usd DESC NULLS LAST",
-        _ => "created_at DESC",
+    let momentum_key: Option<fn(&Momentum) -> f64> = match query.sort.as_str() {
+        "trades_per_minute" => Some(|m| m.trades_per_minute),
+        "buy_pressure" => Some(|m| m.net_buy_sol_5m),
+        _ => None,
     };
-    
-    
+    if let Some(key) = momentum_key {
+        let mut body = fetch_tokens_by_momentum(&state, key, creator.as_deref(), limit, offset).await?;
+        flag_creators(&state, &mut body.tokens).await;
+        return Ok(Json(body));
+    }
+
+    let (sort, order_by) = match query.sort.as_str() {
+        "market_cap" => ("market_cap", "market_cap_usd DESC NULLS LAST"),
+        "last_trade" => ("last_trade", "last_trade_at DESC NULLS LAST"),
+        "volume" => ("volume", "volume_24h_usd DESC"),
+        _ => ("created_at", "created_at DESC"),
+    };
+    let active_within = query
+        .active_within
+        .as_deref()
+        .map(|s| {
+            parse_interval(s)
+                .filter(|d| window_start(chrono::Utc::now(), *d, 1).is_some())
+                .ok_or_else(|| ApiError::bad_request("active_within must look like 5m, 1h or 1d"))
+        })
+        .transpose()?;
+
+    let cache_key = format!(
+        "list:{}:{}:{}:{}:{}",
+        sort,
+        limit,
+        offset,
+        active_within.map_or(0, |d| d.num_minutes()),
+        creator.as_deref().unwrap_or(""),
+    );
+    let mut body = response_cache::get_or_compute(state.redis.as_ref(), &state.metrics, CacheScope::Tokens, &cache_key, || {
+        fetch_token_list(&state.db, order_by, active_within, creator.as_deref(), limit, offset)
+    }).await?;
+
+    // Momentum changes every second, so it is attached after the cache.
+    for token in &mut body.tokens {
+        if let Some(live) = state::get_token_state(&state.token_state, &token.mint_address).await {
+            token.momentum = Some(live.momentum);
+        }
+    }
+    flag_creators(&state, &mut body.tokens).await;
+
+    Ok(Json(body))
+}
+
+/// A token in a response, which can be flagged with the operator list its
+/// creator is on.
+trait Listable {
+    fn creator(&self) -> &str;
+    fn creator_list(&mut self) -> &mut Option<CreatorList>;
+}
+
+impl Listable for TokenResponse {
+    fn creator(&self) -> &str { &self.creator_wallet }
+    fn creator_list(&mut self) -> &mut Option<CreatorList> { &mut self.creator_list }
+}
+
+impl Listable for TokenDetail {
+    fn creator(&self) -> &str { &self.creator }
+    fn creator_list(&mut self) -> &mut Option<CreatorList> { &mut self.creator_list }
+}
+
+impl Listable for GraduatedTokenResponse {
+    fn creator(&self) -> &str { &self.creator_wallet }
+    fn creator_list(&mut self) -> &mut Option<CreatorList> { &mut self.creator_list }
+}
+
+/// Marks the tokens whose creator is on an operator list. Done after
+/// caching so list changes show up at once.
+async fn flag_creators<T: Listable>(state: &AppState, tokens: &mut [T]) {
+    let lists = state.creator_lists.all().await;
+    if lists.is_empty() {
+        return;
+    }
+
+    for token in tokens {
+        if let Some(&list) = lists.get(token.creator()) {
+            *token.creator_list() = Some(list);
+        }
+    }
+}
+
+/// Ranks the tokens in live state by a momentum metric, highest first.
+/// Tokens outside live state have no momentum and are not listed, and
+/// neither are those of blacklisted creators.
+async fn fetch_tokens_by_momentum(
+    state: &AppState,
+    key: fn(&Momentum) -> f64,
+    creator: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<TokenListResponse, ApiError> {
+    let blacklisted = state.creator_lists.all().await;
+    let mut live = state::get_all_tokens(&state.token_state).await;
+    live.retain(|t| {
+        blacklisted.get(&t.creator) != Some(&CreatorList::Blacklist) && creator.is_none_or(|c| t.creator == c)
+    });
+    live.sort_by(|a, b| key(&b.momentum).total_cmp(&key(&a.momentum)).then_with(|| a.mint.cmp(&b.mint)));
+    let total = live.len() as i64;
+    let page: Vec<TokenState> = live.into_iter().skip(offset.max(0) as usize).take(limit.max(0) as usize).collect();
+
+    let mints: Vec<&str> = page.iter().map(|t| t.mint.as_str()).collect();
+    let mut rows = sqlx::query_as::<_, TokenResponse>(
+        "SELECT mint_address, name, symbol, uri, creator_wallet,
+                market_cap_usd, bonding_curve_progress, complete, created_at,
+                last_trade_at, trade_count, unique_traders
+         FROM tokens
+         WHERE mint_address = ANY($1)"
+    )
+    .bind(&mints)
+    .fetch_all(&state.db)
+    .await?;
+
+    let tokens: Vec<TokenResponse> = page
+        .into_iter()
+        .filter_map(|live| {
+            let at = rows.iter().position(|row| row.mint_address == live.mint)?;
+            let mut row = rows.swap_remove(at);
+            row.momentum = Some(live.momentum);
+            Some(row)
+        })
+        .collect();
+
+    Ok(TokenListResponse { tokens, pagination: Pagination { total, limit, offset } })
+}
+
+/// Reads the `token_summaries` materialized view, so listings lag new
+/// tokens and trades by up to one refresh. `active_within` is answered from
+/// the denormalized `last_trade_at`, so it never has to scan trades.
+async fn fetch_token_list(
+    db: &sqlx::PgPool,
+    order_by: &str,
+    active_within: Option<chrono::Duration>,
+    creator: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<TokenListResponse, ApiError> {
+    let active_since = active_within.map(|d| chrono::Utc::now() - d);
     let sql = format!(
         "SELECT mint_address, name, symbol, uri, creator_wallet, 
-                market_cap_usd, bonding_curve_progress, complete, created_at
-         FROM tokens
+                market_cap_usd, bonding_curve_progress, complete, created_at,
+                last_trade_at, trade_count, unique_traders,
+                volume_24h_sol, volume_24h_usd, holder_count
+         FROM token_summaries
+         WHERE ($3::TIMESTAMPTZ IS NULL OR last_trade_at >= $3)
+           AND ($4::VARCHAR IS NULL OR creator_wallet = $4)
          ORDER BY {}
          LIMIT $1 OFFSET $2",
         order_by
@@ -58,81 +213,220 @@ pub async fn list_tokens(
     let tokens = sqlx::query_as::<_, TokenResponse>(&sql)
         .bind(limit)
         .bind(offset)
-        .fetch_all(&state.db)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database error: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
-        })?;
+        .bind(active_since)
+        .bind(creator)
+        .fetch_all(db)
+        .await?;
     
-    let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tokens")
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database error: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
-        })?;
+    let total: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM token_summaries
+         WHERE ($1::TIMESTAMPTZ IS NULL OR last_trade_at >= $1)
+           AND ($2::VARCHAR IS NULL OR creator_wallet = $2)"
+    )
+    .bind(active_since)
+    .bind(creator)
+    .fetch_one(db)
+    .await?;
     
-    Ok(Json(json!({
-        "tokens": tokens,
-        "pagination": {
-            "total": total.0,
-            "limit": limit,
-            "offset": offset,
-        }
-    })))
+    Ok(TokenListResponse { tokens, pagination: Pagination { total: total.0, limit, offset } })
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tokens/{mint}",
+    tag = "tokens",
+    params(("mint" = String, Path, description = "Token mint address")),
+    responses(
+        (status = 200, body = WithMeta<TokenDetail>),
+        (status = 404, description = "Token not found"),
+    )
+)]
 pub async fn get_token(
     State(state): State<AppState>,
-    Path(mint): Path<String>,
-) -> Result<Json<Value>, (StatusCode, String)> {
+    Address(mint): Address,
+) -> Result<Json<TokenDetail>, ApiError> {
     
-    let state_map = state.token_state.read().await;
-    if let Some(token_state) = state_map.get(&mint) {
-        return Ok(Json(json!({
-            "mint_address": token_state.mint,
-            "name": token_state.name,
-            "symbol": token_state.symbol,
-            "creator": token_state.creator,
-            "current_price_sol": token_state.current_price_sol,
-            "market_cap_sol": token_state.market_cap_sol,
-            "market_cap_usd": token_state.market_cap_usd,
-            "bonding_curve_progress": token_state.bonding_curve_progress,
-            "complete": token_state.complete,
-            "last_updated": token_state.last_updated,
-            "source": "in_memory",
-        })));
+    if let Some(token_state) = state::get_token_state(&state.token_state, &mint).await {
+        let mut token = TokenDetail::from(token_state);
+        flag_creators(&state, std::slice::from_mut(&mut token)).await;
+        return Ok(Json(token));
     }
-    drop(state_map);
-    
-    
+
+    // Tokens outside live state are mostly graduated ones, which can stay
+    // popular long after their curve stops trading.
+    let mut token = response_cache::get_or_compute(state.redis.as_ref(), &state.metrics, CacheScope::TokenDetail, &mint, || {
+        fetch_token_detail(&state.db, &mint)
+    }).await?;
+    flag_creators(&state, std::slice::from_mut(&mut token)).await;
+
+    Ok(Json(token))
+}
+
+async fn fetch_token_detail(db: &sqlx::PgPool, mint: &str) -> Result<TokenDetail, ApiError> {
     let token = sqlx::query_as::<_, TokenResponse>(
         "SELECT mint_address, name, symbol, uri, creator_wallet, 
-                market_cap_usd, bonding_curve_progress, complete, created_at
+                market_cap_usd, bonding_curve_progress, complete, created_at,
+                last_trade_at, trade_count, unique_traders
          FROM tokens
          WHERE mint_address = $1"
     )
-    .bind(&mint)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database error: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
-    })?;
+    .bind(mint)
+    .fetch_optional(db)
+    .await?;
     
     match token {
-        Some(t) => Ok(Json(json!({
-            "mint_address": t.mint_address,
-            "name": t.name,
-            "symbol": t.symbol,
-            "creator": t.creator_wallet,
-            "market_cap_usd": t.market_cap_usd,
-            "bonding_curve_progress": t.bonding_curve_progress,
-            "complete": t.complete,
-            "created_at": t.created_at,
-            "source": "database",
-        }))),
-        None => Err((StatusCode::NOT_FOUND, "Token not found".to_string())),
+        Some(t) => Ok(TokenDetail::from(t)),
+        None => Err(ApiError::not_found("Token not found")),
     }
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct GraduatedQuery {
+    /// Only tokens graduated at or after this time
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct GraduatedTokenResponse {
+    pub mint_address: String,
+    pub name: String,
+    pub symbol: String,
+    pub creator_wallet: String,
+    pub bonding_curve_address: String,
+    pub graduated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(value_type = Option<String>)]
+    pub market_cap_usd: Option<bigdecimal::BigDecimal>,
+    /// Destination AMM pool; null until migration instructions are parsed.
+    pub amm_pool: Option<String>,
+    /// From the last trade, or the close of the last minute tiered out of
+    /// Postgres once that trade is.
+    pub final_virtual_sol_reserves: Option<i64>,
+    pub final_virtual_token_reserves: Option<i64>,
+    /// Null once the last trade was tiered out, as candles don't keep them.
+    pub final_real_sol_reserves: Option<i64>,
+    pub final_real_token_reserves: Option<i64>,
+    pub trade_count: i64,
+    /// Jupiter's price as of the last cross-check
+    #[schema(value_type = Option<String>)]
+    pub external_price_usd: Option<bigdecimal::BigDecimal>,
+    /// How far our price was from Jupiter's, in percent
+    pub price_divergence_pct: Option<f64>,
+    /// Set when the creator is on an operator list
+    #[sqlx(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator_list: Option<CreatorList>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GraduatedTokenListResponse {
+    pub tokens: Vec<GraduatedTokenResponse>,
+    pub pagination: Pagination,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tokens/graduated",
+    tag = "tokens",
+    params(GraduatedQuery),
+    responses((status = 200, body = WithMeta<GraduatedTokenListResponse>))
+)]
+pub async fn list_graduated_tokens(
+    State(state): State<AppState>,
+    Query(query): Query<GraduatedQuery>,
+) -> Result<Json<GraduatedTokenListResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 100);
+    let offset = query.offset.max(0);
+
+    // Final curve stats come from the last trade before graduation, since the
+    // tokens row only holds reserves as of creation; its cold candle stands
+    // in once the trade has been tiered out.
+    let tokens = sqlx::query_as::<_, GraduatedTokenResponse>(
+        "SELECT t.mint_address, t.name, t.symbol, t.creator_wallet, t.bonding_curve_address,
+                t.graduated_at, t.created_at, t.market_cap_usd, t.amm_pool,
+                last.virtual_sol_reserves AS final_virtual_sol_reserves,
+                last.virtual_token_reserves AS final_virtual_token_reserves,
+                last.real_sol_reserves AS final_real_sol_reserves,
+                last.real_token_reserves AS final_real_token_reserves,
+                t.trade_count, t.external_price_usd, t.price_divergence_pct
+         FROM tokens t
+         LEFT JOIN LATERAL (
+             SELECT virtual_sol_reserves, virtual_token_reserves,
+                    real_sol_reserves, real_token_reserves
+             FROM (
+                 SELECT timestamp AS at, virtual_sol_reserves, virtual_token_reserves,
+                        real_sol_reserves, real_token_reserves
+                 FROM trades
+                 WHERE token_mint = t.mint_address
+                 UNION ALL
+                 SELECT bucket, close_sol, close_tokens, NULL, NULL
+                 FROM cold_trade_candles
+                 WHERE token_mint = t.mint_address
+             ) points
+             ORDER BY at DESC
+             LIMIT 1
+         ) last ON TRUE
+         WHERE t.complete = TRUE
+           AND ($1::TIMESTAMPTZ IS NULL OR t.graduated_at >= $1)
+         ORDER BY t.graduated_at DESC NULLS LAST
+         LIMIT $2 OFFSET $3"
+    )
+    .bind(query.since)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?;
+
+    let total: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM tokens
+         WHERE complete = TRUE
+           AND ($1::TIMESTAMPTZ IS NULL OR graduated_at >= $1)"
+    )
+    .bind(query.since)
+    .fetch_one(&state.db)
+    .await?;
+
+    let mut body = GraduatedTokenListResponse { tokens, pagination: Pagination { total: total.0, limit, offset } };
+    flag_creators(&state, &mut body.tokens).await;
+
+    Ok(Json(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs against a fresh database created from `DATABASE_URL` with the
+    /// migrations applied.
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_token_list_filters_by_creator(db: sqlx::PgPool) {
+        for (mint, creator, age_hours) in [("mintA", "creator1", 3), ("mintB", "creator2", 2), ("mintC", "creator1", 1)] {
+            sqlx::query(
+                "INSERT INTO tokens (mint_address, name, symbol, uri, bonding_curve_address, creator_wallet, created_at)
+                 VALUES ($1, 'Name', 'SYM', 'uri', 'curve', $2, NOW() - make_interval(hours => $3))"
+            )
+            .bind(mint)
+            .bind(creator)
+            .bind(age_hours)
+            .execute(&db)
+            .await
+            .unwrap();
+        }
+        sqlx::query("REFRESH MATERIALIZED VIEW token_summaries").execute(&db).await.unwrap();
+
+        let mints = |body: &TokenListResponse| body.tokens.iter().map(|t| t.mint_address.clone()).collect::<Vec<_>>();
+
+        let first_page = fetch_token_list(&db, "created_at DESC", None, Some("creator1"), 1, 0).await.unwrap();
+        assert_eq!(mints(&first_page), ["mintC"]);
+        assert_eq!(first_page.pagination.total, 2);
+        let second_page = fetch_token_list(&db, "created_at DESC", None, Some("creator1"), 1, 1).await.unwrap();
+        assert_eq!(mints(&second_page), ["mintA"]);
+
+        let everyone = fetch_token_list(&db, "created_at DESC", None, None, 10, 0).await.unwrap();
+        assert_eq!(everyone.pagination.total, 3);
+    }
+}
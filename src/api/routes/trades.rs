@@ -1,76 +1,374 @@
 use axum::{
-    extract::{State, Path, Query},
-    http::StatusCode,
+    extract::State,
     response::Json,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
 use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::{Address, Query};
+use crate::api::params::parse_pubkey;
+use crate::dto::{Pagination, TradeListResponse, TradeResponse, WithMeta};
+use crate::database::tiering;
+use crate::processor::recent_trades::RecentTrade;
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct TradesQuery {
     #[serde(default = "default_limit")]
     limit: i64,
     #[serde(default)]
     offset: i64,
+    /// `true` for buys only, `false` for sells only
+    is_buy: Option<bool>,
+    /// Minimum trade size in SOL
+    min_sol: Option<f64>,
+    /// Only trades by this wallet
+    wallet: Option<String>,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Unset filters match every row, so one statement serves all combinations.
+const TRADE_FILTER: &str = "token_mint = $1
+           AND ($2::BOOLEAN IS NULL OR is_buy = $2)
+           AND ($3::BIGINT IS NULL OR sol_amount >= $3)
+           AND ($4::VARCHAR IS NULL OR user_wallet = $4)
+           AND ($5::TIMESTAMPTZ IS NULL OR timestamp >= $5)
+           AND ($6::TIMESTAMPTZ IS NULL OR timestamp < $6)";
+
 fn default_limit() -> i64 { 50 }
 
-#[derive(Debug, Serialize, FromRow)]
-pub struct TradeResponse {
-    pub signature: String,
-    pub token_mint: String,           
-    pub user_wallet: String,          
-    pub is_buy: bool,
-    pub sol_amount: i64,
-    pub token_amount: i64,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
+/// `TradeResponse` columns, selected from `trades`.
+const TRADE_COLUMNS: &str = "signature, token_mint, user_wallet, is_buy, sol_amount, token_amount, timestamp,
+                COALESCE(virtual_sol_reserves::FLOAT8 / 1e9 / NULLIF(virtual_token_reserves::FLOAT8 / 10 ^ COALESCE((SELECT decimals FROM tokens WHERE mint_address = token_mint), 6), 0), 0) AS price_sol,
+                price_usd::FLOAT8 AS price_usd,
+                sol_amount::FLOAT8 / 1e9 * sol_price_usd::FLOAT8 AS value_usd,
+                price_before_sol, price_impact_pct, slippage_pct,
+                virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves";
+
+impl From<RecentTrade> for TradeResponse {
+    fn from(trade: RecentTrade) -> Self {
+        Self {
+            signature: trade.signature,
+            token_mint: trade.token_mint,
+            user_wallet: trade.user_wallet,
+            is_buy: trade.is_buy,
+            sol_amount: trade.sol_amount,
+            token_amount: trade.token_amount,
+            timestamp: trade.timestamp,
+            price_sol: trade.price_sol,
+            price_usd: trade.price_usd,
+            value_usd: trade.value_usd,
+            price_before_sol: trade.price_before_sol,
+            price_impact_pct: trade.price_impact_pct,
+            slippage_pct: trade.slippage_pct,
+            virtual_sol_reserves: trade.virtual_sol_reserves,
+            virtual_token_reserves: trade.virtual_token_reserves,
+            real_sol_reserves: trade.real_sol_reserves,
+            real_token_reserves: trade.real_token_reserves,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct RecentTradesQuery {
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecentTradesResponse {
+    pub trades: Vec<TradeResponse>,
+    pub limit: i64,
+    pub offset: i64,
+    /// Archive objects (Parquet) holding the trades tiered out of Postgres,
+    /// set when the page runs past the trades still in it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<Vec<String>>,
+}
+
+/// The latest trades across all tokens, newest first. Served from memory
+/// while the page is within the recent-trades buffer; a page running past
+/// the trades still in Postgres is cut short and names the archived files
+/// holding the rest.
+#[utoipa::path(
+    get,
+    path = "/api/trades/recent",
+    tag = "trades",
+    params(RecentTradesQuery),
+    responses(
+        (status = 200, body = WithMeta<RecentTradesResponse>),
+    )
+)]
+pub async fn get_recent_trades(
+    State(state): State<AppState>,
+    Query(query): Query<RecentTradesQuery>,
+) -> Result<Json<RecentTradesResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 100);
+    let offset = query.offset.max(0);
+
+    let trades: Vec<TradeResponse> = match state.recent_trades.latest(offset as usize, limit as usize).await {
+        Some(trades) => trades.into_iter().map(TradeResponse::from).collect(),
+        None => {
+            sqlx::query_as(&format!(
+                "SELECT {} FROM trades ORDER BY timestamp DESC, signature DESC LIMIT $1 OFFSET $2",
+                TRADE_COLUMNS
+            ))
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&state.db)
+            .await?
+        }
+    };
+
+    let archived = if trades.len() < limit as usize {
+        Some(tiering::fetch_cold_objects(&state.db, None, None, None).await?).filter(|objects| !objects.is_empty())
+    } else {
+        None
+    };
+
+    Ok(Json(RecentTradesResponse { trades, limit, offset, archived }))
 }
 
+/// A token's trades, newest first. Trades tiered to cold storage are
+/// counted in `pagination.total` from their one-minute sums and their
+/// Parquet files named under `archived`; pages list the trades still in
+/// Postgres, so one past them comes back empty.
+#[utoipa::path(
+    get,
+    path = "/api/tokens/{mint}/trades",
+    tag = "trades",
+    params(("mint" = String, Path, description = "Token mint address"), TradesQuery),
+    responses(
+        (status = 200, body = WithMeta<TradeListResponse>),
+    )
+)]
 pub async fn get_token_trades(
     State(state): State<AppState>,
-    Path(mint): Path<String>,
+    Address(mint): Address,
     Query(query): Query<TradesQuery>,
-) -> Result<Json<Value>, (StatusCode, String)> {
-    let limit = query.limit.min(100);
-    let offset = query.offset;
-    
-    let trades = sqlx::query_as::<_, TradeResponse>(
-        "SELECT signature, token_mint, user_wallet, is_buy, sol_amount, token_amount, timestamp
+) -> Result<Json<TradeListResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 100);
+    let offset = query.offset.max(0);
+    let wallet = query
+        .wallet
+        .as_deref()
+        .map(|s| parse_pubkey(s).ok_or_else(|| ApiError::bad_request("wallet must be a base58 pubkey")))
+        .transpose()?;
+
+    let min_lamports = match query.min_sol {
+        Some(sol) if !sol.is_finite() || sol < 0.0 => {
+            return Err(ApiError::bad_request("min_sol must be a non-negative number"));
+        }
+        sol => sol.map(|sol| (sol * 1e9) as i64),
+    };
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from >= to
+    {
+        return Err(ApiError::bad_request("from must be before to"));
+    }
+
+    // Unfiltered early pages come from the recent-trades buffer.
+    let unfiltered = unfiltered_by_trade(&query, min_lamports, wallet.as_deref())
+        && query.from.is_none()
+        && query.to.is_none();
+    if unfiltered
+        && let Some(page) = state.recent_trades.latest_for(&mint, offset as usize, limit as usize).await
+    {
+        let total = match page.total {
+            Some(total) => total,
+            None => sqlx::query_scalar("SELECT trade_count FROM tokens WHERE mint_address = $1")
+                .bind(&mint)
+                .fetch_optional(&state.db)
+                .await?
+                .unwrap_or(0),
+        };
+        let trades: Vec<TradeResponse> = page.trades.into_iter().map(TradeResponse::from).collect();
+        return Ok(Json(TradeListResponse { trades, pagination: Pagination { total, limit, offset }, archived: None }));
+    }
+
+    let archived = tiering::fetch_cold_objects(&state.db, Some(&mint), query.from, query.to).await?;
+
+    let trades = sqlx::query_as::<_, TradeResponse>(&format!(
+        "SELECT {}
          FROM trades
-         WHERE token_mint = $1
-         ORDER BY timestamp DESC
-         LIMIT $2 OFFSET $3"
-    )
+         WHERE {}
+         ORDER BY timestamp DESC, signature DESC
+         LIMIT $7 OFFSET $8",
+        TRADE_COLUMNS,
+        TRADE_FILTER
+    ))
     .bind(&mint)
+    .bind(query.is_buy)
+    .bind(min_lamports)
+    .bind(wallet.as_deref())
+    .bind(query.from)
+    .bind(query.to)
     .bind(limit)
     .bind(offset)
     .fetch_all(&state.db)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database error: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
-    })?;
-    
-    let total: (i64,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM trades WHERE token_mint = $1"
+    .await?;
+
+    let hot: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM trades WHERE {}", TRADE_FILTER))
+        .bind(&mint)
+        .bind(query.is_buy)
+        .bind(min_lamports)
+        .bind(wallet.as_deref())
+        .bind(query.from)
+        .bind(query.to)
+        .fetch_one(&state.db)
+        .await?;
+
+    if archived.is_empty() {
+        return Ok(Json(TradeListResponse {
+            trades,
+            pagination: Pagination { total: hot, limit, offset },
+            archived: None,
+        }));
+    }
+
+    // The one-minute sums count tiered trades by side and time, and per
+    // wallet over all time; a `min_sol` filter can't be counted from either.
+    let cold = match (min_lamports, wallet.as_deref()) {
+        (None, None) => tiering::count_cold_trades(&state.db, Some(&mint), query.is_buy, query.from, query.to).await?,
+        (None, Some(wallet)) if query.is_buy.is_none() && query.from.is_none() && query.to.is_none() => {
+            tiering::count_cold_wallet_trades(&state.db, Some(&mint), wallet).await?
+        }
+        _ => 0,
+    };
+
+    Ok(Json(TradeListResponse {
+        trades,
+        pagination: Pagination { total: hot + cold, limit, offset },
+        archived: Some(archived),
+    }))
+}
+
+/// Whether the only filter, if any, is the time range.
+fn unfiltered_by_trade(query: &TradesQuery, min_lamports: Option<i64>, wallet: Option<&str>) -> bool {
+    query.is_buy.is_none() && min_lamports.is_none() && wallet.is_none()
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SlippageQuery {
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Price impact and slippage of one side of a token's trades, in percent.
+#[derive(Debug, Default, Serialize, FromRow, ToSchema)]
+pub struct SlippageStats {
+    pub trades: i64,
+    /// Of `trades`, those tiered out of Postgres. They count in every field
+    /// but the median and p95, which only their Parquet files can give.
+    pub archived_trades: i64,
+    pub avg_price_impact_pct: Option<f64>,
+    pub avg_slippage_pct: Option<f64>,
+    /// Weighted by `sol_amount`
+    pub volume_weighted_slippage_pct: Option<f64>,
+    pub median_slippage_pct: Option<f64>,
+    pub p95_slippage_pct: Option<f64>,
+    pub max_slippage_pct: Option<f64>,
+}
+
+#[derive(Debug, FromRow)]
+struct SideStats {
+    is_buy: bool,
+    #[sqlx(flatten)]
+    stats: SlippageStats,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SlippageResponse {
+    pub mint: String,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub buys: SlippageStats,
+    pub sells: SlippageStats,
+}
+
+/// Aggregate price impact and slippage for a token's buys and sells, over
+/// every stored trade unless `from`/`to` narrow it. Trades tiered out of
+/// Postgres are added from their one-minute sums.
+#[utoipa::path(
+    get,
+    path = "/api/tokens/{mint}/slippage",
+    tag = "trades",
+    params(("mint" = String, Path, description = "Token mint address"), SlippageQuery),
+    responses(
+        (status = 200, body = WithMeta<SlippageResponse>),
+        (status = 400, description = "Invalid range"),
+    )
+)]
+pub async fn get_token_slippage(
+    State(state): State<AppState>,
+    Address(mint): Address,
+    Query(query): Query<SlippageQuery>,
+) -> Result<Json<SlippageResponse>, ApiError> {
+    if let (Some(from), Some(to)) = (query.from, query.to)
+        && from >= to
+    {
+        return Err(ApiError::bad_request("from must be before to"));
+    }
+
+    let sides = sqlx::query_as::<_, SideStats>(
+        "WITH hot AS (
+             SELECT is_buy,
+                    COUNT(*) AS trades,
+                    SUM(price_impact_pct) AS impact,
+                    SUM(slippage_pct) AS slippage,
+                    SUM(slippage_pct * sol_amount) AS weighted,
+                    SUM(sol_amount)::FLOAT8 AS volume,
+                    percentile_cont(0.5) WITHIN GROUP (ORDER BY slippage_pct) AS median,
+                    percentile_cont(0.95) WITHIN GROUP (ORDER BY slippage_pct) AS p95,
+                    MAX(slippage_pct) AS max
+             FROM trades
+             WHERE token_mint = $1
+               AND slippage_pct IS NOT NULL
+               AND ($2::TIMESTAMPTZ IS NULL OR timestamp >= $2)
+               AND ($3::TIMESTAMPTZ IS NULL OR timestamp < $3)
+             GROUP BY is_buy
+         ), cold AS (
+             SELECT is_buy,
+                    SUM(slippage_trades)::BIGINT AS trades,
+                    SUM(price_impact_pct_sum) AS impact,
+                    SUM(slippage_pct_sum) AS slippage,
+                    SUM(slippage_pct_volume_sum) AS weighted,
+                    SUM(slippage_volume_lamports)::FLOAT8 AS volume,
+                    MAX(max_slippage_pct) AS max
+             FROM cold_trade_sides
+             WHERE token_mint = $1
+               AND ($2::TIMESTAMPTZ IS NULL OR bucket + INTERVAL '1 minute' > $2)
+               AND ($3::TIMESTAMPTZ IS NULL OR bucket < $3)
+             GROUP BY is_buy
+             HAVING SUM(slippage_trades) > 0
+         )
+         SELECT COALESCE(h.is_buy, c.is_buy) AS is_buy,
+                COALESCE(h.trades, 0) + COALESCE(c.trades, 0) AS trades,
+                COALESCE(c.trades, 0) AS archived_trades,
+                (COALESCE(h.impact, 0) + COALESCE(c.impact, 0)) / (COALESCE(h.trades, 0) + COALESCE(c.trades, 0)) AS avg_price_impact_pct,
+                (COALESCE(h.slippage, 0) + COALESCE(c.slippage, 0)) / (COALESCE(h.trades, 0) + COALESCE(c.trades, 0)) AS avg_slippage_pct,
+                (COALESCE(h.weighted, 0) + COALESCE(c.weighted, 0))
+                    / NULLIF(COALESCE(h.volume, 0) + COALESCE(c.volume, 0), 0) AS volume_weighted_slippage_pct,
+                h.median AS median_slippage_pct,
+                h.p95 AS p95_slippage_pct,
+                GREATEST(h.max, c.max) AS max_slippage_pct
+         FROM hot h
+         FULL JOIN cold c ON c.is_buy = h.is_buy"
     )
     .bind(&mint)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|e| {
-        tracing::error!("Database error: {}", e);
-        (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
-    })?;
-    
-    Ok(Json(json!({
-        "trades": trades,
-        "pagination": {
-            "total": total.0,
-            "limit": limit,
-            "offset": offset,
-        }
-    })))
-}
\ No newline at end of file
+    .bind(query.from)
+    .bind(query.to)
+    .fetch_all(&state.db)
+    .await?;
+
+    let (mut buys, mut sells) = (SlippageStats::default(), SlippageStats::default());
+    for side in sides {
+        if side.is_buy { buys = side.stats } else { sells = side.stats }
+    }
+
+    Ok(Json(SlippageResponse { mint, from: query.from, to: query.to, buys, sells }))
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use axum::{
+    extract::State,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use utoipa::{IntoParams, ToSchema};
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::{Address, Query, TxSignature};
+use crate::database::tiering;
+use crate::dto::WithMeta;
+
+#[derive(Deserialize, IntoParams)]
+pub struct WalletTransactionsQuery {
+    /// Max transactions (default 50, max 200)
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+fn default_limit() -> i64 { 50 }
+
+#[derive(Debug, FromRow)]
+struct TransactionRow {
+    signature: String,
+    slot: i64,
+    block_time: chrono::DateTime<chrono::Utc>,
+    fee: i64,
+    success: bool,
+    signer: String,
+    instruction_count: i32,
+    compute_units_consumed: Option<i64>,
+    error_message: Option<String>,
+    accounts_involved: Option<Vec<String>>,
+}
+
+#[derive(Debug, FromRow)]
+struct EventRow {
+    signature: String,
+    kind: String,
+    token_mint: Option<String>,
+    details: String,
+}
+
+/// An event the indexer parsed out of the transaction.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransactionEvent {
+    /// `trade`, `created`, `graduated` or `creator_fee_claim`
+    pub kind: String,
+    /// Null for creator fee claims, which cover all of a creator's tokens
+    pub token_mint: Option<String>,
+    /// Kind-specific fields, e.g. `is_buy` and `sol_amount` for trades
+    #[schema(value_type = Object)]
+    pub details: Value,
+}
+
+/// A stored transaction with the events parsed from it.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransactionDetail {
+    pub signature: String,
+    pub slot: i64,
+    pub block_time: chrono::DateTime<chrono::Utc>,
+    /// Lamports
+    pub fee: i64,
+    pub success: bool,
+    pub signer: String,
+    pub instruction_count: i32,
+    pub compute_units_consumed: Option<i64>,
+    pub error_message: Option<String>,
+    pub accounts: Vec<String>,
+    /// Without its trades when they were tiered out of Postgres, see
+    /// `trades_archived_in`
+    pub events: Vec<TransactionEvent>,
+    /// Archive object (Parquet) holding the trades of this transaction, set
+    /// when its day was tiered out of Postgres
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trades_archived_in: Option<String>,
+}
+
+const TRANSACTION_COLUMNS: &str = "signature, slot, block_time, fee, success, signer, instruction_count,
+    compute_units_consumed, error_message, accounts_involved";
+
+/// Trades come from `trades`, creations and graduations from the token
+/// timeline and fee claims from `creator_fee_claims`.
+async fn fetch_events(pool: &PgPool, signatures: &[String]) -> Result<HashMap<String, Vec<TransactionEvent>>, ApiError> {
+    let rows = sqlx::query_as::<_, EventRow>(
+        "SELECT signature, 'trade' AS kind, token_mint,
+                json_build_object(
+                    'user_wallet', user_wallet,
+                    'is_buy', is_buy,
+                    'sol_amount', sol_amount,
+                    'token_amount', token_amount,
+                    'price_sol', price_after_sol,
+                    'price_usd', price_usd,
+                    'ix_name', ix_name
+                )::TEXT AS details,
+                0 AS position
+         FROM trades
+         WHERE signature = ANY($1)
+         UNION ALL
+         SELECT signature, kind, token_mint, details::TEXT, 1
+         FROM token_events
+         WHERE signature = ANY($1) AND kind IN ('created', 'graduated')
+         UNION ALL
+         SELECT signature, 'creator_fee_claim', NULL,
+                json_build_object('creator', creator, 'lamports', lamports)::TEXT, 2
+         FROM creator_fee_claims
+         WHERE signature = ANY($1)
+         ORDER BY position"
+    )
+    .bind(signatures)
+    .fetch_all(pool)
+    .await?;
+
+    let mut events: HashMap<String, Vec<TransactionEvent>> = HashMap::new();
+    for row in rows {
+        events.entry(row.signature).or_default().push(TransactionEvent {
+            kind: row.kind,
+            token_mint: row.token_mint,
+            details: serde_json::from_str(&row.details).unwrap_or(Value::Null),
+        });
+    }
+    Ok(events)
+}
+
+/// Transactions from days whose trades were tiered out of Postgres keep
+/// their other events and name the file holding their trades.
+async fn with_events(pool: &PgPool, rows: Vec<TransactionRow>) -> Result<Vec<TransactionDetail>, ApiError> {
+    let times: Vec<chrono::DateTime<chrono::Utc>> = rows.iter().map(|row| row.block_time).collect();
+    let archived = tiering::fetch_cold_periods_at(pool, &times).await?;
+
+    let signatures: Vec<String> = rows.iter().map(|row| row.signature.clone()).collect();
+    let mut events = fetch_events(pool, &signatures).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| TransactionDetail {
+            events: events.remove(&row.signature).unwrap_or_default(),
+            trades_archived_in: archived
+                .iter()
+                .find(|period| row.block_time >= period.period_start && row.block_time < period.period_end)
+                .map(|period| period.object_key.clone()),
+            signature: row.signature,
+            slot: row.slot,
+            block_time: row.block_time,
+            fee: row.fee,
+            success: row.success,
+            signer: row.signer,
+            instruction_count: row.instruction_count,
+            compute_units_consumed: row.compute_units_consumed,
+            error_message: row.error_message,
+            accounts: row.accounts_involved.unwrap_or_default(),
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransactionResponse {
+    pub transaction: TransactionDetail,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WalletTransactionsResponse {
+    pub wallet: String,
+    pub transactions: Vec<TransactionDetail>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/transactions/{signature}",
+    tag = "transactions",
+    params(("signature" = String, Path, description = "Transaction signature")),
+    responses(
+        (status = 200, body = WithMeta<TransactionResponse>),
+        (status = 400, description = "Invalid signature"),
+        (status = 404, description = "Transaction not indexed"),
+    )
+)]
+pub async fn get_transaction(
+    State(state): State<AppState>,
+    TxSignature(signature): TxSignature,
+) -> Result<Json<TransactionResponse>, ApiError> {
+    let row = sqlx::query_as::<_, TransactionRow>(&format!(
+        "SELECT {} FROM transactions WHERE signature = $1",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(&signature)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Transaction not found"))?;
+
+    let transaction = with_events(&state.db, vec![row]).await?.remove(0);
+    Ok(Json(TransactionResponse { transaction }))
+}
+
+/// Transactions that touched the wallet as signer or any other account,
+/// newest first.
+#[utoipa::path(
+    get,
+    path = "/api/wallets/{wallet}/transactions",
+    tag = "wallets",
+    params(("wallet" = String, Path, description = "Wallet address"), WalletTransactionsQuery),
+    responses(
+        (status = 200, body = WithMeta<WalletTransactionsResponse>),
+        (status = 400, description = "Invalid address"),
+    )
+)]
+pub async fn get_wallet_transactions(
+    State(state): State<AppState>,
+    Address(wallet): Address,
+    Query(query): Query<WalletTransactionsQuery>,
+) -> Result<Json<WalletTransactionsResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 200);
+    let offset = query.offset.max(0);
+
+    let rows = sqlx::query_as::<_, TransactionRow>(&format!(
+        "SELECT {} FROM transactions
+         WHERE accounts_involved @> ARRAY[$1]::TEXT[]
+         ORDER BY block_time DESC, signature
+         LIMIT $2 OFFSET $3",
+        TRANSACTION_COLUMNS
+    ))
+    .bind(&wallet)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await?;
+
+    let transactions = with_events(&state.db, rows).await?;
+    Ok(Json(WalletTransactionsResponse { wallet, transactions, limit, offset }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_fetch_events(pool: PgPool) {
+        sqlx::query(
+            "INSERT INTO creator_fee_claims (signature, event_index, creator, lamports, claimed_at)
+             VALUES ('sig1', 0, 'alice', 5000, NOW())"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let events = fetch_events(&pool, &["sig1".to_string(), "sig2".to_string()]).await.unwrap();
+        assert!(!events.contains_key("sig2"));
+
+        let claim = &events["sig1"][0];
+        assert_eq!((claim.kind.as_str(), claim.token_mint.as_deref()), ("creator_fee_claim", None));
+        assert_eq!(claim.details["lamports"], 5000);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_with_events_flags_tiered_trades(pool: PgPool) {
+        sqlx::query(
+            "INSERT INTO archive_manifest (kind, object_key, period_start, period_end, row_count, bytes, pruned_at)
+             VALUES ('trades', 'trades/date=2025-11-03/trades.parquet', '2025-11-03', '2025-11-04', 1, 100, NOW())"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let row = |signature: &str, block_time: &str| TransactionRow {
+            signature: signature.to_string(),
+            slot: 1,
+            block_time: block_time.parse().unwrap(),
+            fee: 5000,
+            success: true,
+            signer: "alice".to_string(),
+            instruction_count: 1,
+            compute_units_consumed: None,
+            error_message: None,
+            accounts_involved: None,
+        };
+        let details = with_events(&pool, vec![row("sig1", "2025-11-03T12:00:00Z"), row("sig2", "2025-11-04T12:00:00Z")])
+            .await
+            .unwrap();
+        assert_eq!(details[0].trades_archived_in.as_deref(), Some("trades/date=2025-11-03/trades.parquet"));
+        assert_eq!(details[1].trades_archived_in, None);
+    }
+}
@@ -0,0 +1,127 @@
+use axum::{
+    extract::State,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::extract::{Address, Query};
+use crate::dto::WithMeta;
+
+#[derive(Deserialize, IntoParams)]
+pub struct RelatedQuery {
+    /// Max related wallets (default 50, max 200)
+    #[serde(default = "default_limit")]
+    limit: i64,
+}
+
+fn default_limit() -> i64 { 50 }
+
+/// Wallets in the cluster listing.
+const MAX_CLUSTER_WALLETS: i64 = 200;
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct RelatedWallet {
+    pub wallet: String,
+    /// `early_buyer`, `same_slot` or `funded`
+    pub relation: String,
+    /// `outgoing` when the queried wallet is the creator/funder, `incoming`
+    /// when it's the buyer/recipient; `same_slot` links are always
+    /// `outgoing` from the lexicographically smaller wallet.
+    pub direction: String,
+    pub tokens: i64,
+    pub occurrences: i64,
+    pub lamports: i64,
+    pub first_seen: chrono::DateTime<chrono::Utc>,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+    /// Up to five of the tokens the link was seen on
+    pub sample_mints: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WalletCluster {
+    pub size: usize,
+    /// True when the cluster was cut off at 200 wallets
+    pub truncated: bool,
+    pub wallets: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RelatedWalletsResponse {
+    pub wallet: String,
+    pub related: Vec<RelatedWallet>,
+    pub cluster: WalletCluster,
+}
+
+/// Direct links come from the wallet linker (see `wallet_links`). The
+/// cluster follows `same_slot` and `funded` links two hops out; early buying
+/// alone isn't evidence of common control, so it isn't followed.
+#[utoipa::path(
+    get,
+    path = "/api/wallets/{wallet}/related",
+    tag = "wallets",
+    params(("wallet" = String, Path, description = "Wallet address"), RelatedQuery),
+    responses((status = 200, body = WithMeta<RelatedWalletsResponse>))
+)]
+pub async fn get_related_wallets(
+    State(state): State<AppState>,
+    Address(wallet): Address,
+    Query(query): Query<RelatedQuery>,
+) -> Result<Json<RelatedWalletsResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 200);
+
+    let related = sqlx::query_as::<_, RelatedWallet>(
+        "SELECT CASE WHEN source_wallet = $1 THEN target_wallet ELSE source_wallet END AS wallet,
+                relation,
+                CASE WHEN source_wallet = $1 THEN 'outgoing' ELSE 'incoming' END AS direction,
+                COUNT(DISTINCT token_mint) AS tokens,
+                SUM(occurrences)::BIGINT AS occurrences,
+                SUM(lamports)::BIGINT AS lamports,
+                MIN(first_seen) AS first_seen,
+                MAX(last_seen) AS last_seen,
+                (array_agg(DISTINCT token_mint))[1:5] AS sample_mints
+         FROM wallet_links
+         WHERE source_wallet = $1 OR target_wallet = $1
+         GROUP BY 1, 2, 3
+         ORDER BY occurrences DESC, last_seen DESC
+         LIMIT $2"
+    )
+    .bind(&wallet)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await?;
+
+    let cluster: Vec<(String,)> = sqlx::query_as(
+        "WITH hop1 AS (
+             SELECT DISTINCT CASE WHEN source_wallet = $1 THEN target_wallet ELSE source_wallet END AS wallet
+             FROM wallet_links
+             WHERE (source_wallet = $1 OR target_wallet = $1) AND relation <> 'early_buyer'
+         ),
+         hop2 AS (
+             SELECT DISTINCT CASE WHEN l.source_wallet = h.wallet THEN l.target_wallet ELSE l.source_wallet END AS wallet
+             FROM wallet_links l
+             JOIN hop1 h ON l.source_wallet = h.wallet OR l.target_wallet = h.wallet
+             WHERE l.relation <> 'early_buyer'
+         )
+         SELECT wallet FROM (SELECT wallet FROM hop1 UNION SELECT wallet FROM hop2) c
+         WHERE wallet <> $1
+         ORDER BY wallet
+         LIMIT $2"
+    )
+    .bind(&wallet)
+    .bind(MAX_CLUSTER_WALLETS)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(Json(RelatedWalletsResponse {
+        wallet,
+        related,
+        cluster: WalletCluster {
+            size: cluster.len(),
+            truncated: cluster.len() as i64 == MAX_CLUSTER_WALLETS,
+            wallets: cluster.into_iter().map(|(w,)| w).collect(),
+        },
+    }))
+}
@@ -1,118 +1,296 @@
 use axum::{
     extract::{
         ws::{WebSocket, WebSocketUpgrade, Message},
-        State, Path,
+        State,
     },
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use futures::{sink::SinkExt, stream::StreamExt};
-use tokio::sync::broadcast;
-use tracing::{info, error, debug};
+use futures::{
+    future::{AbortHandle, Abortable},
+    stream::{BoxStream, SelectAll, StreamExt},
+};
+use std::collections::{hash_map::Entry, HashMap};
+use std::future::Future;
+use std::sync::Arc;
+use tracing::{info, error, warn};
 use crate::api::AppState;
-use redis::AsyncCommands;
+use crate::api::error::ApiError;
+use crate::api::extract::{Address, Query};
+use crate::storage::replay_buffer;
+use serde::Deserialize;
+use super::ws_protocol::{Channel, ClientMessage, Subscription, TradeFilter, WsMessage};
+use super::ws_session::{Heartbeat, Outbox};
+
 
+/// Upgrades the connection unless `WS_MAX_CONNECTIONS` clients are already
+/// connected. The client stays counted until `handler` returns.
+fn upgrade<F, Fut>(ws: WebSocketUpgrade, state: AppState, handler: F) -> Response
+where
+    F: FnOnce(WebSocket, AppState) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let Some(client) = state.metrics.try_track_ws_client(state.ws_limits.max_connections) else {
+        warn!("Rejecting WebSocket client: connection limit ({}) reached", state.ws_limits.max_connections);
+        return ApiError::Unavailable("Too many WebSocket connections".to_string()).into_response();
+    };
+
+    ws.on_upgrade(move |socket| async move {
+        let _client = client;
+        handler(socket, state).await;
+    })
+}
+
+/// Query params for the legacy trade routes.
+#[derive(Debug, Default, Deserialize)]
+pub struct ReplayParams {
+    pub since_seq: Option<u64>,
+}
 
 pub async fn trades_websocket(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
+    Query(filter): Query<TradeFilter>,
+    Query(replay): Query<ReplayParams>,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_all_trades_socket(socket, state))
+    if let Err(e) = filter.validate() {
+        return ApiError::BadRequest(e).into_response();
+    }
+    let subscription = Subscription { channel: Channel::Trades, mint: None, filter, since_seq: replay.since_seq };
+    upgrade(ws, state, move |socket, state| handle_all_trades_socket(socket, state, subscription))
 }
 
 pub async fn token_trades_websocket(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
-    Path(mint): Path<String>,
+    Address(mint): Address,
+    Query(filter): Query<TradeFilter>,
+    Query(replay): Query<ReplayParams>,
 ) -> Response {
-    ws.on_upgrade(move |socket| handle_token_trades_socket(socket, state, mint))
+    if let Err(e) = filter.validate() {
+        return ApiError::BadRequest(e).into_response();
+    }
+    let subscription = Subscription { channel: Channel::Trades, mint: Some(mint), filter, since_seq: replay.since_seq };
+    upgrade(ws, state, move |socket, state| handle_token_trades_socket(socket, state, subscription))
 }
 
-async fn handle_all_trades_socket(socket: WebSocket, _state: AppState) {
-    let (mut sender, mut receiver) = socket.split();
-    
+async fn handle_all_trades_socket(socket: WebSocket, state: AppState, subscription: Subscription) {
     info!("🔌 New WebSocket client connected: All trades");
-    
-
-    let (tx, mut rx) = broadcast::channel::<String>(100);
-    
-    let redis_url = std::env::var("REDIS_URL")
-        .unwrap_or_else(|_| "redis://localhost:6379".to_string());
-    
-    tokio::spawn(async move {
-    
-        let client = match redis::Client::open(redis_url.as_str()) {
-            Ok(c) => c,
-            Err(e) => {
-                error!("Failed to create Redis client: {}", e);
-                return;
-            }
+
+    let welcome = WsMessage::Connected {
+        message: "Connected to all trades stream".to_string(),
+        channel: Some(Channel::Trades),
+        mint: None,
+    };
+
+    forward_channel(socket, &state, welcome, subscription).await;
+
+    info!("🔌 WebSocket client disconnected: All trades");
+}
+
+async fn handle_token_trades_socket(socket: WebSocket, state: AppState, subscription: Subscription) {
+    let mint = subscription.mint.clone().unwrap_or_default();
+    info!("🔌 New WebSocket client connected: Token {}", mint);
+
+    let welcome = WsMessage::Connected {
+        message: format!("Connected to token trades stream for {}", mint),
+        channel: Some(Channel::Trades),
+        mint: Some(mint.clone()),
+    };
+
+    forward_channel(socket, &state, welcome, subscription).await;
+
+    info!("🔌 WebSocket client disconnected: Token {}", mint);
+}
+
+pub async fn new_tokens_websocket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+) -> Response {
+    upgrade(ws, state, |socket, state| async move {
+        info!("🔌 New WebSocket client connected: New tokens");
+
+        let welcome = WsMessage::Connected {
+            message: "Connected to new tokens stream".to_string(),
+            channel: Some(Channel::Tokens),
+            mint: None,
         };
-        
-  
-        let mut pubsub = match client.get_async_pubsub().await {
-            Ok(ps) => ps,
-            Err(e) => {
-                error!("Failed to get pubsub connection: {}", e);
-                return;
-            }
+        let subscription = Subscription { channel: Channel::Tokens, mint: None, filter: TradeFilter::default(), since_seq: None };
+        forward_channel(socket, &state, welcome, subscription).await;
+
+        info!("🔌 WebSocket client disconnected: New tokens");
+    })
+}
+
+pub async fn graduations_websocket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+) -> Response {
+    upgrade(ws, state, |socket, state| async move {
+        info!("🔌 New WebSocket client connected: Graduations");
+
+        let welcome = WsMessage::Connected {
+            message: "Connected to graduations stream".to_string(),
+            channel: Some(Channel::Graduations),
+            mint: None,
         };
-        
-  
-        if let Err(e) = pubsub.subscribe("pump:trades").await {
-            error!("Failed to subscribe to Redis: {}", e);
-            return;
-        }
-        
-        info!("✅ Subscribed to Redis channel: pump:trades");
-        
-  
-        let mut stream = pubsub.on_message();
-        
-        loop {
-            match stream.next().await {
-                Some(msg) => {
-                    if let Ok(payload) = msg.get_payload::<String>() {
-                        let _ = tx.send(payload);
-                    }
-                }
-                None => {
-                    error!("Redis pubsub stream ended");
-                    break;
-                }
-            }
+        let subscription = Subscription { channel: Channel::Graduations, mint: None, filter: TradeFilter::default(), since_seq: None };
+        forward_channel(socket, &state, welcome, subscription).await;
+
+        info!("🔌 WebSocket client disconnected: Graduations");
+    })
+}
+
+pub async fn tracked_websocket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+) -> Response {
+    upgrade(ws, state, |socket, state| async move {
+        info!("🔌 New WebSocket client connected: Tracked wallets");
+
+        let welcome = WsMessage::Connected {
+            message: "Connected to tracked wallets stream".to_string(),
+            channel: Some(Channel::Tracked),
+            mint: None,
+        };
+        let subscription = Subscription { channel: Channel::Tracked, mint: None, filter: TradeFilter::default(), since_seq: None };
+        forward_channel(socket, &state, welcome, subscription).await;
+
+        info!("🔌 WebSocket client disconnected: Tracked wallets");
+    })
+}
+
+pub async fn koth_websocket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+) -> Response {
+    upgrade(ws, state, |socket, state| async move {
+        info!("🔌 New WebSocket client connected: King of the hill");
+
+        let welcome = WsMessage::Connected {
+            message: "Connected to king of the hill stream".to_string(),
+            channel: Some(Channel::Koth),
+            mint: None,
+        };
+        let subscription = Subscription { channel: Channel::Koth, mint: None, filter: TradeFilter::default(), since_seq: None };
+        forward_channel(socket, &state, welcome, subscription).await;
+
+        info!("🔌 WebSocket client disconnected: King of the hill");
+    })
+}
+
+/// Buffered trades a resuming subscription missed, as raw payloads alongside
+/// their decoded messages.
+struct Replay {
+    trades: Vec<(String, WsMessage)>,
+    /// Highest sequence number read from the buffer; live trades at or below
+    /// it were already covered by the replay.
+    through: u64,
+    gap: Option<WsMessage>,
+}
+
+/// Reads the replay buffer for `subscription`. Redis failures are logged and
+/// the client simply starts from live.
+async fn replay(state: &AppState, subscription: &Subscription) -> Replay {
+    // The buffer lives in Redis; without it every client starts from live.
+    let (Some(since_seq), Some(redis)) = (subscription.since_seq, &state.redis) else {
+        return Replay { trades: Vec::new(), through: 0, gap: None };
+    };
+
+    let mut redis = redis.clone();
+    let entries = match replay_buffer::since(&mut redis, since_seq).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("Replay failed: {}", e);
+            return Replay { trades: Vec::new(), through: 0, gap: None };
         }
+    };
+
+    let gap = replay_buffer::has_gap(since_seq, &entries).then(|| WsMessage::ReplayGap {
+        since_seq,
+        first_seq: entries[0].0,
     });
-    
-    let welcome = serde_json::json!({
-        "type": "connected",
-        "channel": "pump:trades",
-        "message": "Connected to all trades stream"
-    });
-    
-    if sender.send(Message::Text(welcome.to_string().into())).await.is_err() {
+    let through = entries.last().map_or(since_seq, |(seq, _)| *seq);
+    let trades = entries
+        .into_iter()
+        .filter_map(|(_, payload)| {
+            let message = subscription.decode(&payload).ok()?;
+            subscription.includes(&message).then_some((payload, message))
+        })
+        .collect();
+
+    Replay { trades, through, gap }
+}
+
+/// Whether a live message was already sent during replay.
+fn already_replayed(message: &WsMessage, through: u64) -> bool {
+    message.seq().is_some_and(|seq| seq <= through)
+}
+
+/// Relays payloads from one shared Redis channel until the client leaves.
+/// Data frames keep the published shape the legacy routes always sent;
+/// control frames use `WsMessage`.
+async fn forward_channel(
+    socket: WebSocket,
+    state: &AppState,
+    welcome: WsMessage,
+    subscription: Subscription,
+) {
+    let (sender, mut receiver) = socket.split();
+    let mut outbox = Outbox::spawn(sender, &state.metrics);
+    let mut heartbeat = Heartbeat::new();
+
+    // Attach to the live channel before reading the buffer so nothing
+    // published in between is lost; duplicates are skipped by seq.
+    let mut live = std::pin::pin!(state.pubsub.subscribe_stream(&subscription.redis_channel()));
+
+    if !outbox.send(&welcome) {
         return;
     }
-    
+
+    let replay = replay(state, &subscription).await;
+    if let Some(gap) = &replay.gap
+        && !outbox.send(gap)
+    {
+        return;
+    }
+    for (payload, _) in replay.trades {
+        if !outbox.push(Message::Text(payload.into())) {
+            return;
+        }
+    }
+
     loop {
         tokio::select! {
-    
-            msg = rx.recv() => {
-                match msg {
-                    Ok(trade_json) => {
-                        if sender.send(Message::Text(trade_json.into())).await.is_err() {
-                            debug!("Client disconnected");
-                            break;
-                        }
+            msg = live.next() => {
+                let Some(payload) = msg else { break };
+
+                if !subscription.filter.is_empty() || replay.through > 0 {
+                    let forward = subscription
+                        .decode(&payload)
+                        .is_ok_and(|message| subscription.includes(&message) && !already_replayed(&message, replay.through));
+                    if !forward {
+                        continue;
                     }
-                    Err(_) => break,
+                }
+
+                if !outbox.push(Message::Text(payload.as_ref().into())) {
+                    break;
                 }
             }
-            
+
+            _ = heartbeat.tick() => {
+                if !heartbeat.ping(&mut outbox) {
+                    break;
+                }
+            }
+
             msg = receiver.next() => {
+                heartbeat.saw_activity();
                 match msg {
                     Some(Ok(Message::Close(_))) => break,
                     Some(Ok(Message::Ping(ping))) => {
-                        if sender.send(Message::Pong(ping)).await.is_err() {
+                        let queued = outbox.push(Message::Pong(ping));
+                        if !queued {
                             break;
                         }
                     }
@@ -126,94 +304,156 @@ async fn handle_all_trades_socket(socket: WebSocket, _state: AppState) {
             }
         }
     }
-    
-    info!("🔌 WebSocket client disconnected: All trades");
 }
 
-async fn handle_token_trades_socket(socket: WebSocket, _state: AppState, mint: String) {
-    let (mut sender, mut receiver) = socket.split();
-    
-    info!("🔌 New WebSocket client connected: Token {}", mint);
-    
-
-    let (tx, mut rx) = broadcast::channel::<String>(100);
-    let channel = format!("pump:trades:{}", mint);
-    
-    let redis_url = std::env::var("REDIS_URL")
-        .unwrap_or_else(|_| "redis://localhost:6379".to_string());
-    
-    let channel_clone = channel.clone();
-    tokio::spawn(async move {
-        let client = match redis::Client::open(redis_url.as_str()) {
-            Ok(c) => c,
-            Err(e) => {
-                error!("Failed to create Redis client: {}", e);
-                return;
+pub async fn unified_websocket(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+) -> Response {
+    upgrade(ws, state, handle_unified_socket)
+}
+
+/// A subscription's shared stream, tagged with its Redis channel so the
+/// payloads can be matched back to the subscription.
+type TaggedStream = Abortable<BoxStream<'static, (String, Arc<str>)>>;
+
+struct ActiveSubscription {
+    subscription: Subscription,
+    handle: AbortHandle,
+    /// Live trades up to this seq were already delivered by replay.
+    replayed_through: u64,
+}
+
+/// Applies one client op to the connection's subscriptions and returns the
+/// messages to send back: the reply, followed by any replayed trades.
+async fn handle_client_message(
+    text: &str,
+    state: &AppState,
+    streams: &mut SelectAll<TaggedStream>,
+    subscriptions: &mut HashMap<String, ActiveSubscription>,
+) -> Vec<WsMessage> {
+    let message = match serde_json::from_str::<ClientMessage>(text) {
+        Ok(message) => message,
+        Err(e) => return vec![WsMessage::error(format!("Invalid message: {}", e))],
+    };
+
+    match message {
+        ClientMessage::Subscribe(subscription) => {
+            if let Err(e) = subscription.validate() {
+                return vec![WsMessage::error(e)];
             }
-        };
-        
-        let mut pubsub = match client.get_async_pubsub().await {
-            Ok(ps) => ps,
-            Err(e) => {
-                error!("Failed to get pubsub connection: {}", e);
-                return;
+
+            // Subscribing again to the same channel just replaces its filter.
+            let channel = subscription.redis_channel();
+            let max = state.ws_limits.max_subscriptions_per_connection;
+            if !subscriptions.contains_key(&channel) && subscriptions.len() >= max {
+                return vec![WsMessage::error(format!("Subscription limit ({}) reached", max))];
             }
-        };
-        
-        if let Err(e) = pubsub.subscribe(&channel_clone).await {
-            error!("Failed to subscribe to Redis: {}", e);
-            return;
-        }
-        
-        info!("✅ Subscribed to Redis channel: {}", channel_clone);
-        
-        let mut stream = pubsub.on_message();
-        
-        loop {
-            match stream.next().await {
-                Some(msg) => {
-                    if let Ok(payload) = msg.get_payload::<String>() {
-                        let _ = tx.send(payload);
-                    }
+
+            let active = match subscriptions.entry(channel.clone()) {
+                Entry::Occupied(entry) => {
+                    let active = entry.into_mut();
+                    active.subscription = subscription.clone();
+                    active
                 }
-                None => {
-                    error!("Redis pubsub stream ended");
-                    break;
+                Entry::Vacant(entry) => {
+                    let (handle, registration) = AbortHandle::new_pair();
+                    let tagged = state
+                        .pubsub
+                        .subscribe_stream(&channel)
+                        .map(move |payload| (channel.clone(), payload))
+                        .boxed();
+                    streams.push(Abortable::new(tagged, registration));
+                    entry.insert(ActiveSubscription {
+                        subscription: subscription.clone(),
+                        handle,
+                        replayed_through: 0,
+                    })
                 }
+            };
+
+            let replay = replay(state, &subscription).await;
+            active.replayed_through = active.replayed_through.max(replay.through);
+
+            let mut replies = vec![WsMessage::Subscribed(subscription.clone())];
+            replies.extend(replay.gap);
+            replies.extend(replay.trades.into_iter().map(|(_, message)| message));
+            replies
+        }
+        ClientMessage::Unsubscribe(subscription) => {
+            // Aborting ends the stream, which drops its broadcast receiver.
+            match subscriptions.remove(&subscription.redis_channel()) {
+                Some(active) => active.handle.abort(),
+                None => return vec![WsMessage::error("Not subscribed")],
             }
+
+            vec![WsMessage::Unsubscribed(subscription)]
         }
-    });
-    
-    let welcome = serde_json::json!({
-        "type": "connected",
-        "channel": channel,
-        "mint": mint,
-        "message": format!("Connected to token trades stream for {}", mint)
-    });
-    
-    if sender.send(Message::Text(welcome.to_string().into())).await.is_err() {
+        ClientMessage::Ping => vec![WsMessage::heartbeat()],
+    }
+}
+
+async fn handle_unified_socket(socket: WebSocket, state: AppState) {
+    let (sender, mut receiver) = socket.split();
+    let mut outbox = Outbox::spawn(sender, &state.metrics);
+    let mut heartbeat = Heartbeat::new();
+
+    info!("🔌 New WebSocket client connected: /ws");
+
+    let mut streams: SelectAll<TaggedStream> = SelectAll::new();
+    let mut subscriptions: HashMap<String, ActiveSubscription> = HashMap::new();
+
+    let welcome = WsMessage::Connected {
+        message: "Send {\"op\":\"subscribe\",\"channel\":\"trades\"} to start streaming".to_string(),
+        channel: None,
+        mint: None,
+    };
+    if !outbox.send(&welcome) {
         return;
     }
-    
+
     loop {
         tokio::select! {
-            msg = rx.recv() => {
-                match msg {
-                    Ok(trade_json) => {
-                        if sender.send(Message::Text(trade_json.into())).await.is_err() {
-                            debug!("Client disconnected");
-                            break;
-                        }
+            Some((channel, payload)) = streams.next(), if !streams.is_empty() => {
+                let Some(active) = subscriptions.get(&channel) else {
+                    continue;
+                };
+
+                let message = match active.subscription.decode(&payload) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        error!("Dropping malformed {} payload: {}", channel, e);
+                        continue;
                     }
-                    Err(_) => break,
+                };
+                if !active.subscription.includes(&message) || already_replayed(&message, active.replayed_through) {
+                    continue;
+                }
+
+                if !outbox.send(&message) {
+                    break;
                 }
             }
-            
+
+            _ = heartbeat.tick() => {
+                if !heartbeat.ping(&mut outbox) {
+                    break;
+                }
+            }
+
             msg = receiver.next() => {
+                heartbeat.saw_activity();
                 match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        let replies = handle_client_message(&text, &state, &mut streams, &mut subscriptions).await;
+                        if !replies.iter().all(|reply| outbox.send(reply)) {
+                            break;
+                        }
+                    }
                     Some(Ok(Message::Close(_))) => break,
                     Some(Ok(Message::Ping(ping))) => {
-                        if sender.send(Message::Pong(ping)).await.is_err() {
+                        let queued = outbox.push(Message::Pong(ping));
+                        if !queued {
                             break;
                         }
                     }
@@ -227,6 +467,10 @@ async fn handle_token_trades_socket(socket: WebSocket, _state: AppState, mint: S
             }
         }
     }
-    
-    info!("🔌 WebSocket client disconnected: Token {}", mint);
-}
\ No newline at end of file
+
+    info!(
+        "🔌 WebSocket client disconnected: /ws ({} subscriptions, {} messages dropped)",
+        subscriptions.len(),
+        outbox.dropped()
+    );
+}
@@ -0,0 +1,297 @@
+use serde::{Deserialize, Serialize};
+use crate::dto::{GraduationMessage, NewTokenMessage, TradeMessage};
+use crate::processor::koth::KothMessage;
+use crate::processor::tracked::TrackedTradeMessage;
+use crate::api::params::parse_pubkey;
+
+/// Messages a client sends over `/ws`, tagged by `op`:
+/// `{"op":"subscribe","channel":"trades","mint":"..."}`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe(Subscription),
+    Unsubscribe(Subscription),
+    Ping,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Trades,
+    /// Newly created tokens (`pump:tokens:new`).
+    Tokens,
+    /// Bonding curve completions (`pump:completions`).
+    Graduations,
+    /// Trades by wallets on the admin-managed tracked list (`pump:tracked`).
+    Tracked,
+    /// King of the hill changes (`pump:koth`).
+    Koth,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub channel: Channel,
+    /// Narrows the channel to a single token; omit for every token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mint: Option<String>,
+    #[serde(default, skip_serializing_if = "TradeFilter::is_empty")]
+    pub filter: TradeFilter,
+    /// Replays buffered trades after this sequence number before going live.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since_seq: Option<u64>,
+}
+
+/// Server-side trade filter. Every set field must match for a trade to be
+/// forwarded; an empty filter forwards everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeFilter {
+    /// Minimum trade size in SOL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_sol: Option<f64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub buys_only: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_market_cap_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_market_cap_usd: Option<f64>,
+}
+
+impl TradeFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let (Some(min), Some(max)) = (self.min_market_cap_usd, self.max_market_cap_usd)
+            && min > max
+        {
+            return Err("min_market_cap_usd must not exceed max_market_cap_usd".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, trade: &TradeMessage) -> bool {
+        if self.buys_only && !trade.is_buy {
+            return false;
+        }
+
+        if self.min_sol.is_some_and(|min_sol| (trade.sol_amount as f64 / 1_000_000_000.0) < min_sol) {
+            return false;
+        }
+
+        if self.min_market_cap_usd.is_some_and(|min| trade.market_cap_usd < min)
+            || self.max_market_cap_usd.is_some_and(|max| trade.market_cap_usd > max)
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+impl Subscription {
+    pub fn redis_channel(&self) -> String {
+        match (&self.channel, &self.mint) {
+            (Channel::Trades, None) => "pump:trades".to_string(),
+            (Channel::Trades, Some(mint)) => format!("pump:trades:{}", mint),
+            (Channel::Tokens, _) => "pump:tokens:new".to_string(),
+            (Channel::Graduations, _) => "pump:completions".to_string(),
+            (Channel::Tracked, _) => "pump:tracked".to_string(),
+            (Channel::Koth, _) => "pump:koth".to_string(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.channel != Channel::Trades {
+            if self.mint.is_some() {
+                return Err("mint is only supported on the trades channel".to_string());
+            }
+            if !self.filter.is_empty() {
+                return Err("filter is only supported on the trades channel".to_string());
+            }
+            if self.since_seq.is_some() {
+                return Err("since_seq is only supported on the trades channel".to_string());
+            }
+        }
+
+        if let Some(mint) = &self.mint
+            && parse_pubkey(mint).is_none()
+        {
+            return Err("mint must be a base58 pubkey".to_string());
+        }
+
+        self.filter.validate()
+    }
+
+    /// Decodes a payload published on this subscription's channel.
+    pub fn decode(&self, payload: &str) -> serde_json::Result<WsMessage> {
+        Ok(match self.channel {
+            Channel::Trades => WsMessage::Trade {
+                channel: self.channel,
+                mint: self.mint.clone(),
+                data: serde_json::from_str(payload)?,
+            },
+            Channel::Tokens => WsMessage::NewToken { data: serde_json::from_str(payload)? },
+            Channel::Graduations => WsMessage::Graduation { data: serde_json::from_str(payload)? },
+            Channel::Tracked => WsMessage::TrackedTrade { data: serde_json::from_str(payload)? },
+            Channel::Koth => WsMessage::Koth { data: serde_json::from_str(payload)? },
+        })
+    }
+
+    /// Whether a decoded message should reach this subscriber. Checks the
+    /// mint too, since replayed trades come from the shared buffer rather
+    /// than the mint's own channel.
+    pub fn includes(&self, message: &WsMessage) -> bool {
+        match message {
+            WsMessage::Trade { data, .. } => {
+                self.mint.as_ref().is_none_or(|mint| *mint == data.mint) && self.filter.matches(data)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Bumped on any breaking change to `WsMessage`.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Messages the server sends, tagged by `type`. Always serialized through
+/// `versioned()` so every frame also carries the protocol `version`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsMessage {
+    Connected {
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        channel: Option<Channel>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mint: Option<String>,
+    },
+    Subscribed(Subscription),
+    Unsubscribed(Subscription),
+    Trade {
+        channel: Channel,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mint: Option<String>,
+        data: TradeMessage,
+    },
+    NewToken { data: NewTokenMessage },
+    Graduation { data: GraduationMessage },
+    TrackedTrade { data: TrackedTradeMessage },
+    Koth { data: KothMessage },
+    /// Sent on every heartbeat tick and in reply to a client `ping`, for
+    /// clients that cannot see WebSocket ping frames.
+    Heartbeat { timestamp: i64 },
+    /// Sent before a replay that starts after `since_seq + 1` because older
+    /// trades already left the buffer.
+    ReplayGap { since_seq: u64, first_seq: u64 },
+    Error { message: String },
+}
+
+#[derive(Serialize)]
+pub struct Versioned<'a> {
+    version: u32,
+    #[serde(flatten)]
+    message: &'a WsMessage,
+}
+
+impl WsMessage {
+    pub fn error(message: impl Into<String>) -> Self {
+        WsMessage::Error { message: message.into() }
+    }
+
+    pub fn heartbeat() -> Self {
+        WsMessage::Heartbeat { timestamp: chrono::Utc::now().timestamp() }
+    }
+
+    pub fn versioned(&self) -> Versioned<'_> {
+        Versioned { version: PROTOCOL_VERSION, message: self }
+    }
+
+    pub fn seq(&self) -> Option<u64> {
+        match self {
+            WsMessage::Trade { data, .. } => data.seq,
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(is_buy: bool, sol_amount: u64, market_cap_usd: f64) -> TradeMessage {
+        TradeMessage {
+            signature: "sig".to_string(),
+            mint: "Mint111".to_string(),
+            is_buy,
+            sol_amount,
+            token_amount: 1,
+            user_wallet: "wallet".to_string(),
+            timestamp: chrono::DateTime::from_timestamp(0, 0).unwrap(),
+            market_cap_usd,
+            price_sol: 0.0,
+            seq: Some(7),
+        }
+    }
+
+    #[test]
+    fn test_client_message_parsing() {
+        let msg: ClientMessage = serde_json::from_str(
+            r#"{"op":"subscribe","channel":"trades","mint":"Mint111"}"#
+        ).unwrap();
+        match msg {
+            ClientMessage::Subscribe(sub) => assert_eq!(sub.redis_channel(), "pump:trades:Mint111"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(serde_json::from_str::<ClientMessage>(r#"{"op":"subscribe","channel":"nope"}"#).is_err());
+    }
+
+    #[test]
+    fn test_trade_message_shape() {
+        let msg = WsMessage::Trade {
+            channel: Channel::Trades,
+            mint: None,
+            data: trade(true, 1, 0.0),
+        };
+        let value = serde_json::to_value(msg.versioned()).unwrap();
+
+        assert_eq!(value["version"], PROTOCOL_VERSION);
+        assert_eq!(value["type"], "trade");
+        assert_eq!(value["channel"], "trades");
+        assert_eq!(value["data"]["signature"], "sig");
+        assert_eq!(msg.seq(), Some(7));
+    }
+
+    #[test]
+    fn test_trade_filter() {
+        let sub: Subscription = serde_json::from_str(
+            r#"{"channel":"trades","filter":{"min_sol":1.0,"buys_only":true,"max_market_cap_usd":50000}}"#
+        ).unwrap();
+        let filter = sub.filter;
+
+        assert!(filter.matches(&trade(true, 2_000_000_000, 10000.0)));
+        assert!(!filter.matches(&trade(true, 500_000_000, 10000.0)));
+        assert!(!filter.matches(&trade(false, 2_000_000_000, 10000.0)));
+        assert!(!filter.matches(&trade(true, 2_000_000_000, 90000.0)));
+        assert!(TradeFilter::default().matches(&trade(false, 1, 0.0)));
+    }
+
+    #[test]
+    fn test_token_channels() {
+        let sub: Subscription = serde_json::from_str(r#"{"channel":"graduations"}"#).unwrap();
+        assert_eq!(sub.redis_channel(), "pump:completions");
+
+        let msg = sub.decode(r#"{"mint":"M","user":"U","timestamp":1}"#).unwrap();
+        assert_eq!(
+            serde_json::to_value(msg.versioned()).unwrap(),
+            serde_json::json!({"version": 1, "type": "graduation", "data": {"mint": "M", "user": "U", "timestamp": 1}})
+        );
+
+        let scoped: Subscription = serde_json::from_str(r#"{"channel":"tokens","mint":"M"}"#).unwrap();
+        assert!(scoped.validate().is_err());
+
+        let tracked: Subscription = serde_json::from_str(r#"{"channel":"tracked"}"#).unwrap();
+        assert_eq!(tracked.redis_channel(), "pump:tracked");
+    }
+}
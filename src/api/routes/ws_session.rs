@@ -0,0 +1,167 @@
+use axum::extract::ws::{Message, WebSocket};
+use futures::{sink::SinkExt, stream::SplitSink};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::time::{interval, timeout, Duration, Instant, Interval, MissedTickBehavior};
+use std::sync::atomic::Ordering;
+use tracing::{debug, error, warn};
+use crate::processor::metrics::Metrics;
+use super::ws_protocol::WsMessage;
+
+/// How often the server pings an otherwise quiet client.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(20);
+/// Clients that send nothing (not even a pong) for this long are disconnected.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// A single socket write taking longer than this closes the connection.
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+/// Messages queued per client before new ones start being dropped.
+const OUTBOX_CAPACITY: usize = 256;
+/// How long a client's queue may stay full before it is disconnected.
+const SLOW_CLIENT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Per-connection send queue drained by a dedicated writer task, so a slow
+/// socket never blocks the loop reading from the shared channels.
+///
+/// When the queue is full new messages are dropped; a client that stays
+/// backed up for `SLOW_CLIENT_TIMEOUT` is disconnected.
+pub struct Outbox {
+    tx: mpsc::Sender<Message>,
+    metrics: Metrics,
+    backed_up_since: Option<Instant>,
+    dropped: u64,
+}
+
+impl Outbox {
+    pub fn spawn(sink: SplitSink<WebSocket, Message>, metrics: &Metrics) -> Self {
+        let (tx, rx) = mpsc::channel(OUTBOX_CAPACITY);
+        tokio::spawn(write_loop(sink, rx, metrics.clone()));
+        Self::new(tx, metrics.clone())
+    }
+
+    fn new(tx: mpsc::Sender<Message>, metrics: Metrics) -> Self {
+        Self { tx, metrics, backed_up_since: None, dropped: 0 }
+    }
+
+    /// Queues a message without waiting. Returns false when the connection
+    /// should be closed.
+    pub fn push(&mut self, message: Message) -> bool {
+        match self.tx.try_send(message) {
+            Ok(()) => {
+                self.backed_up_since = None;
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                self.dropped += 1;
+                self.metrics.ws_messages_dropped.fetch_add(1, Ordering::Relaxed);
+                let since = *self.backed_up_since.get_or_insert_with(Instant::now);
+                if since.elapsed() > SLOW_CLIENT_TIMEOUT {
+                    warn!("Disconnecting slow WebSocket client ({} messages dropped)", self.dropped);
+                    return false;
+                }
+                true
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+
+    pub fn send(&mut self, message: &WsMessage) -> bool {
+        match serde_json::to_string(&message.versioned()) {
+            Ok(json) => self.push(Message::Text(json.into())),
+            Err(e) => {
+                error!("Failed to serialize WebSocket message: {}", e);
+                true
+            }
+        }
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+async fn write_loop(
+    mut sink: SplitSink<WebSocket, Message>,
+    mut rx: mpsc::Receiver<Message>,
+    metrics: Metrics,
+) {
+    while let Some(message) = rx.recv().await {
+        match timeout(SEND_TIMEOUT, sink.send(message)).await {
+            Ok(Ok(())) => {
+                metrics.ws_messages_sent.fetch_add(1, Ordering::Relaxed);
+            }
+            Ok(Err(e)) => {
+                debug!("Client disconnected: {}", e);
+                break;
+            }
+            Err(_) => {
+                warn!("WebSocket send timed out, closing connection");
+                break;
+            }
+        }
+    }
+
+    let _ = sink.close().await;
+}
+
+/// Tracks client liveness: pings on an interval and reports the client idle
+/// once nothing has been received for `IDLE_TIMEOUT`.
+pub struct Heartbeat {
+    interval: Interval,
+    last_seen: Instant,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Heartbeat {
+    pub fn new() -> Self {
+        let mut interval = interval(HEARTBEAT_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        interval.reset();
+        Self { interval, last_seen: Instant::now() }
+    }
+
+    pub async fn tick(&mut self) {
+        self.interval.tick().await;
+    }
+
+    pub fn saw_activity(&mut self) {
+        self.last_seen = Instant::now();
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.last_seen.elapsed() > IDLE_TIMEOUT
+    }
+
+    /// Runs after a tick: pings the client and sends a `heartbeat` message,
+    /// or returns false if it has gone idle.
+    pub fn ping(&self, outbox: &mut Outbox) -> bool {
+        if self.is_idle() {
+            debug!("Closing idle WebSocket client");
+            return false;
+        }
+        outbox.push(Message::Ping(Default::default())) && outbox.send(&WsMessage::heartbeat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outbox_drops_when_full_and_stops_when_closed() {
+        let (tx, rx) = mpsc::channel(1);
+        let metrics = Metrics::new();
+        let mut outbox = Outbox::new(tx, metrics.clone());
+
+        assert!(outbox.push(Message::Text("a".into())));
+        assert!(outbox.push(Message::Text("b".into())));
+        assert_eq!(outbox.dropped(), 1);
+        assert_eq!(metrics.ws_messages_dropped.load(Ordering::Relaxed), 1);
+
+        drop(rx);
+        assert!(!outbox.push(Message::Text("c".into())));
+    }
+}
@@ -0,0 +1,528 @@
+#[cfg(feature = "archive")]
+mod uploader {
+    use anyhow::{bail, Context, Result};
+    use bigdecimal::{num_bigint::BigInt, BigDecimal, ToPrimitive};
+    use chrono::{DateTime, Duration as ChronoDuration, Utc};
+    use flate2::{write::GzEncoder, Compression as GzLevel};
+    use futures::TryStreamExt;
+    use object_store::{aws::AmazonS3Builder, gcp::GoogleCloudStorageBuilder, path::Path, ObjectStore, PutPayload};
+    use parquet::basic::{Compression, ZstdLevel};
+    use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DataType, DoubleType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+    use parquet::schema::parser::parse_message_type;
+    use serde::Serialize;
+    use sqlx::PgPool;
+    use std::io::Write;
+    use std::sync::Arc;
+    use tokio::time::{interval, Duration};
+    use tracing::{error, info};
+    use crate::config::ArchiveConfig;
+    use crate::database::tiering;
+
+    /// Upper bound on periods uploaded per tick, so catching up on a long
+    /// backlog doesn't hold one tick for hours.
+    const MAX_PERIODS_PER_TICK: usize = 24;
+    /// How long after a period ends before it is archived, to let in-flight
+    /// writes for it land first.
+    const SETTLE_DELAY_MINUTES: i64 = 5;
+    const TRADES_ROW_GROUP_SIZE: usize = 100_000;
+
+    const TRADES_SCHEMA: &str = "
+        message trade {
+            REQUIRED BYTE_ARRAY signature (UTF8);
+            REQUIRED BYTE_ARRAY token_mint (UTF8);
+            REQUIRED INT64 sol_amount;
+            REQUIRED INT64 token_amount;
+            REQUIRED BOOLEAN is_buy;
+            REQUIRED BYTE_ARRAY user_wallet (UTF8);
+            REQUIRED INT64 timestamp (TIMESTAMP(MICROS, true));
+            REQUIRED INT64 virtual_sol_reserves;
+            REQUIRED INT64 virtual_token_reserves;
+            REQUIRED INT64 fee;
+            REQUIRED INT64 creator_fee;
+            REQUIRED INT64 real_sol_reserves;
+            REQUIRED INT64 real_token_reserves;
+            REQUIRED BYTE_ARRAY fee_recipient (UTF8);
+            REQUIRED INT64 fee_basis_points;
+            REQUIRED BYTE_ARRAY creator (UTF8);
+            REQUIRED INT64 creator_fee_basis_points;
+            REQUIRED BOOLEAN track_volume;
+            REQUIRED INT64 total_unclaimed_tokens;
+            REQUIRED INT64 total_claimed_tokens;
+            REQUIRED INT64 current_sol_volume;
+            REQUIRED INT64 last_update_timestamp (TIMESTAMP(MICROS, true));
+            REQUIRED BYTE_ARRAY ix_name (UTF8);
+            OPTIONAL BYTE_ARRAY price_usd (DECIMAL(20,10));
+            OPTIONAL INT64 sol_price_usd (DECIMAL(12,4));
+            OPTIONAL DOUBLE price_before_sol;
+            OPTIONAL DOUBLE price_after_sol;
+            OPTIONAL DOUBLE price_impact_pct;
+            OPTIONAL DOUBLE slippage_pct;
+        }
+    ";
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Dataset {
+        /// Raw `transactions` rows, one gzipped NDJSON object per hour.
+        Transactions,
+        /// `trades` rows, one Parquet file per UTC day.
+        Trades,
+    }
+
+    impl Dataset {
+        fn kind(self) -> &'static str {
+            match self {
+                Dataset::Transactions => "transactions",
+                Dataset::Trades => "trades",
+            }
+        }
+
+        fn table_and_column(self) -> (&'static str, &'static str) {
+            match self {
+                Dataset::Transactions => ("transactions", "block_time"),
+                Dataset::Trades => ("trades", "timestamp"),
+            }
+        }
+
+        fn unit(self) -> &'static str {
+            match self {
+                Dataset::Transactions => "hour",
+                Dataset::Trades => "day",
+            }
+        }
+
+        fn period(self) -> ChronoDuration {
+            match self {
+                Dataset::Transactions => ChronoDuration::hours(1),
+                Dataset::Trades => ChronoDuration::days(1),
+            }
+        }
+
+        /// Written to the manifest so tiering can tell files holding every
+        /// `trades` column from ones written before they all were.
+        fn schema_version(self) -> i32 {
+            match self {
+                Dataset::Transactions => 1,
+                Dataset::Trades => tiering::TRADES_ARCHIVE_VERSION,
+            }
+        }
+
+        fn object_name(self, start: DateTime<Utc>) -> String {
+            match self {
+                Dataset::Transactions => format!("transactions/{}.ndjson.gz", start.format("%Y/%m/%d/%H")),
+                Dataset::Trades => format!("trades/date={}/trades.parquet", start.format("%Y-%m-%d")),
+            }
+        }
+    }
+
+    #[derive(sqlx::FromRow, Serialize)]
+    struct ArchivedTransaction {
+        signature: String,
+        slot: i64,
+        block_time: DateTime<Utc>,
+        fee: i64,
+        success: bool,
+        signer: String,
+        instruction_count: i32,
+        log_messages_count: i32,
+        has_program_data: Option<bool>,
+        accounts_involved: Option<Vec<String>>,
+        pre_balances: Option<Vec<i64>>,
+        post_balances: Option<Vec<i64>>,
+        compute_units_consumed: Option<i64>,
+        error_message: Option<String>,
+    }
+
+    #[derive(sqlx::FromRow)]
+    struct ArchivedTrade {
+        signature: String,
+        token_mint: String,
+        sol_amount: i64,
+        token_amount: i64,
+        is_buy: bool,
+        user_wallet: String,
+        timestamp: DateTime<Utc>,
+        virtual_sol_reserves: i64,
+        virtual_token_reserves: i64,
+        fee: i64,
+        creator_fee: i64,
+        real_sol_reserves: i64,
+        real_token_reserves: i64,
+        fee_recipient: String,
+        fee_basis_points: i64,
+        creator: String,
+        creator_fee_basis_points: i64,
+        track_volume: bool,
+        total_unclaimed_tokens: i64,
+        total_claimed_tokens: i64,
+        current_sol_volume: i64,
+        last_update_timestamp: DateTime<Utc>,
+        ix_name: String,
+        price_usd: Option<BigDecimal>,
+        sol_price_usd: Option<BigDecimal>,
+        price_before_sol: Option<f64>,
+        price_after_sol: Option<f64>,
+        price_impact_pct: Option<f64>,
+        slippage_pct: Option<f64>,
+    }
+
+    /// Uploads completed hours of raw transactions and completed days of
+    /// trades to S3/GCS, recording each object in `archive_manifest`.
+    ///
+    /// The manifest doubles as the cursor: each tick resumes after the last
+    /// archived period, skipping periods with no rows. Trade days archived
+    /// before every column was written are uploaded again first, while their
+    /// rows are still in Postgres.
+    pub struct Archiver {
+        store: Arc<dyn ObjectStore>,
+        prefix: String,
+        url: String,
+        interval_minutes: u64,
+    }
+
+    impl Archiver {
+        pub fn new(config: &ArchiveConfig) -> Result<Self> {
+            let (scheme, bucket, prefix) = split_url(&config.url)?;
+            let store: Arc<dyn ObjectStore> = match scheme {
+                "s3" => Arc::new(
+                    AmazonS3Builder::from_env()
+                        .with_bucket_name(bucket)
+                        .build()
+                        .context("Failed to configure S3 archive store")?,
+                ),
+                "gs" => Arc::new(
+                    GoogleCloudStorageBuilder::from_env()
+                        .with_bucket_name(bucket)
+                        .build()
+                        .context("Failed to configure GCS archive store")?,
+                ),
+                other => bail!("Unsupported archive scheme `{}`", other),
+            };
+
+            Ok(Self {
+                store,
+                prefix: prefix.to_string(),
+                url: config.url.clone(),
+                interval_minutes: config.interval_minutes,
+            })
+        }
+
+        pub async fn run(&self, pool: PgPool) {
+            let mut interval = interval(Duration::from_secs(self.interval_minutes * 60));
+
+            info!("🗄️ Starting archiver to {} ({} min interval)", self.url, self.interval_minutes);
+
+            loop {
+                interval.tick().await;
+
+                for dataset in [Dataset::Transactions, Dataset::Trades] {
+                    if let Err(e) = self.archive_pending(&pool, dataset).await {
+                        error!("Failed to archive {}: {:#}", dataset.kind(), e);
+                    }
+                }
+            }
+        }
+
+        async fn archive_pending(&self, pool: &PgPool, dataset: Dataset) -> Result<()> {
+            for _ in 0..MAX_PERIODS_PER_TICK {
+                let start = match stale_period(pool, dataset).await? {
+                    Some(start) => start,
+                    None => match next_period(pool, dataset).await? {
+                        Some(start) => start,
+                        None => return Ok(()),
+                    },
+                };
+                let end = start + dataset.period();
+                if end + ChronoDuration::minutes(SETTLE_DELAY_MINUTES) > Utc::now() {
+                    return Ok(());
+                }
+
+                let (body, rows) = match dataset {
+                    Dataset::Transactions => transactions_ndjson(pool, start, end).await?,
+                    Dataset::Trades => trades_parquet(pool, start, end).await?,
+                };
+                let bytes = body.len();
+                let key = object_key(&self.prefix, &dataset.object_name(start));
+
+                self.store
+                    .put(&Path::from(key.as_str()), PutPayload::from(body))
+                    .await
+                    .with_context(|| format!("Failed to upload {}", key))?;
+                record_upload(pool, dataset, &key, start, end, rows, bytes).await?;
+
+                info!("🗄️ Archived {} {} ({} bytes) to {}", rows, dataset.kind(), bytes, key);
+            }
+            Ok(())
+        }
+    }
+
+    /// Start of the first period after the last archived one that has rows.
+    async fn next_period(pool: &PgPool, dataset: Dataset) -> Result<Option<DateTime<Utc>>> {
+        let (table, column) = dataset.table_and_column();
+        let sql = format!(
+            "SELECT date_trunc('{unit}', MIN({column}) AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+             FROM {table}
+             WHERE {column} >= COALESCE(
+                 (SELECT MAX(period_end) FROM archive_manifest WHERE kind = $1),
+                 '-infinity'
+             )",
+            unit = dataset.unit(),
+        );
+        let row: (Option<DateTime<Utc>>,) = sqlx::query_as(&sql)
+            .bind(dataset.kind())
+            .fetch_one(pool)
+            .await?;
+
+        Ok(row.0)
+    }
+
+    /// Start of the oldest period archived by an older schema whose rows
+    /// are still in Postgres; uploading it again overwrites the object so
+    /// tiering may remove the rows.
+    async fn stale_period(pool: &PgPool, dataset: Dataset) -> Result<Option<DateTime<Utc>>> {
+        let start = sqlx::query_scalar(
+            "SELECT period_start FROM archive_manifest
+             WHERE kind = $1 AND pruned_at IS NULL AND schema_version < $2
+             ORDER BY period_start
+             LIMIT 1"
+        )
+        .bind(dataset.kind())
+        .bind(dataset.schema_version())
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(start)
+    }
+
+    async fn transactions_ndjson(pool: &PgPool, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<(Vec<u8>, u64)> {
+        let mut rows = sqlx::query_as::<_, ArchivedTransaction>(
+            "SELECT signature, slot, block_time, fee, success, signer, instruction_count,
+                    log_messages_count, has_program_data, accounts_involved, pre_balances,
+                    post_balances, compute_units_consumed, error_message
+             FROM transactions
+             WHERE block_time >= $1 AND block_time < $2
+             ORDER BY block_time, signature"
+        )
+        .bind(start)
+        .bind(end)
+        .fetch(pool);
+
+        let mut encoder = GzEncoder::new(Vec::new(), GzLevel::default());
+        let mut count = 0;
+        while let Some(tx) = rows.try_next().await? {
+            serde_json::to_writer(&mut encoder, &tx)?;
+            encoder.write_all(b"\n")?;
+            count += 1;
+        }
+
+        Ok((encoder.finish()?, count))
+    }
+
+    async fn trades_parquet(pool: &PgPool, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<(Vec<u8>, u64)> {
+        let mut chunks = sqlx::query_as::<_, ArchivedTrade>(
+            "SELECT signature, token_mint, sol_amount, token_amount, is_buy, user_wallet, timestamp,
+                    virtual_sol_reserves, virtual_token_reserves, fee, creator_fee,
+                    real_sol_reserves, real_token_reserves, fee_recipient, fee_basis_points,
+                    creator, creator_fee_basis_points, track_volume, total_unclaimed_tokens,
+                    total_claimed_tokens, current_sol_volume, last_update_timestamp, ix_name,
+                    price_usd, sol_price_usd, price_before_sol, price_after_sol,
+                    price_impact_pct, slippage_pct
+             FROM trades
+             WHERE timestamp >= $1 AND timestamp < $2
+             ORDER BY timestamp, signature"
+        )
+        .bind(start)
+        .bind(end)
+        .fetch(pool)
+        .try_chunks(TRADES_ROW_GROUP_SIZE);
+
+        let schema = Arc::new(parse_message_type(TRADES_SCHEMA)?);
+        let props = Arc::new(
+            WriterProperties::builder()
+                .set_compression(Compression::ZSTD(ZstdLevel::default()))
+                .build(),
+        );
+        let mut writer = SerializedFileWriter::new(Vec::new(), schema, props)?;
+
+        let mut count = 0;
+        while let Some(trades) = chunks.try_next().await.map_err(|e| e.1)? {
+            write_trades(&mut writer, &trades)?;
+            count += trades.len() as u64;
+        }
+
+        Ok((writer.into_inner()?, count))
+    }
+
+    /// Writes one row group; columns must follow `TRADES_SCHEMA` order.
+    fn write_trades(writer: &mut SerializedFileWriter<Vec<u8>>, trades: &[ArchivedTrade]) -> Result<()> {
+        let strings = |f: fn(&ArchivedTrade) -> &str| trades.iter().map(|t| ByteArray::from(f(t))).collect::<Vec<_>>();
+        let ints = |f: fn(&ArchivedTrade) -> i64| trades.iter().map(f).collect::<Vec<_>>();
+        let bools = |f: fn(&ArchivedTrade) -> bool| trades.iter().map(f).collect::<Vec<_>>();
+        let floats = |f: fn(&ArchivedTrade) -> Option<f64>| trades.iter().map(f).collect::<Vec<_>>();
+
+        let mut group = writer.next_row_group()?;
+        write_column::<ByteArrayType>(&mut group, &strings(|t| t.signature.as_str()))?;
+        write_column::<ByteArrayType>(&mut group, &strings(|t| t.token_mint.as_str()))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.sol_amount))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.token_amount))?;
+        write_column::<BoolType>(&mut group, &bools(|t| t.is_buy))?;
+        write_column::<ByteArrayType>(&mut group, &strings(|t| t.user_wallet.as_str()))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.timestamp.timestamp_micros()))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.virtual_sol_reserves))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.virtual_token_reserves))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.fee))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.creator_fee))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.real_sol_reserves))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.real_token_reserves))?;
+        write_column::<ByteArrayType>(&mut group, &strings(|t| t.fee_recipient.as_str()))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.fee_basis_points))?;
+        write_column::<ByteArrayType>(&mut group, &strings(|t| t.creator.as_str()))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.creator_fee_basis_points))?;
+        write_column::<BoolType>(&mut group, &bools(|t| t.track_volume))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.total_unclaimed_tokens))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.total_claimed_tokens))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.current_sol_volume))?;
+        write_column::<Int64Type>(&mut group, &ints(|t| t.last_update_timestamp.timestamp_micros()))?;
+        write_column::<ByteArrayType>(&mut group, &strings(|t| t.ix_name.as_str()))?;
+        write_optional_column::<ByteArrayType>(
+            &mut group,
+            &trades
+                .iter()
+                .map(|t| t.price_usd.as_ref().map(|p| ByteArray::from(unscaled(p, 10).to_signed_bytes_be())))
+                .collect::<Vec<_>>(),
+        )?;
+        write_optional_column::<Int64Type>(
+            &mut group,
+            &trades
+                .iter()
+                .map(|t| t.sol_price_usd.as_ref().and_then(|p| unscaled(p, 4).to_i64()))
+                .collect::<Vec<_>>(),
+        )?;
+        write_optional_column::<DoubleType>(&mut group, &floats(|t| t.price_before_sol))?;
+        write_optional_column::<DoubleType>(&mut group, &floats(|t| t.price_after_sol))?;
+        write_optional_column::<DoubleType>(&mut group, &floats(|t| t.price_impact_pct))?;
+        write_optional_column::<DoubleType>(&mut group, &floats(|t| t.slippage_pct))?;
+        group.close()?;
+
+        Ok(())
+    }
+
+    fn write_column<T: DataType>(group: &mut SerializedRowGroupWriter<'_, Vec<u8>>, values: &[T::T]) -> Result<()> {
+        let mut column = group.next_column()?.context("Parquet schema is missing a column")?;
+        column.typed::<T>().write_batch(values, None, None)?;
+        column.close()?;
+        Ok(())
+    }
+
+    /// Nulls are left out of the values and marked by definition levels.
+    fn write_optional_column<T: DataType>(
+        group: &mut SerializedRowGroupWriter<'_, Vec<u8>>,
+        values: &[Option<T::T>],
+    ) -> Result<()> {
+        let present: Vec<T::T> = values.iter().flatten().cloned().collect();
+        let levels: Vec<i16> = values.iter().map(|v| i16::from(v.is_some())).collect();
+
+        let mut column = group.next_column()?.context("Parquet schema is missing a column")?;
+        column.typed::<T>().write_batch(&present, Some(&levels), None)?;
+        column.close()?;
+        Ok(())
+    }
+
+    /// The unscaled integer Parquet stores for a DECIMAL with `scale`.
+    fn unscaled(value: &BigDecimal, scale: i64) -> BigInt {
+        value.with_scale(scale).into_bigint_and_exponent().0
+    }
+
+    async fn record_upload(
+        pool: &PgPool,
+        dataset: Dataset,
+        key: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        rows: u64,
+        bytes: usize,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO archive_manifest (kind, object_key, period_start, period_end, row_count, bytes, schema_version)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (object_key) DO UPDATE SET
+                 row_count = EXCLUDED.row_count,
+                 bytes = EXCLUDED.bytes,
+                 schema_version = EXCLUDED.schema_version,
+                 archived_at = NOW()"
+        )
+        .bind(dataset.kind())
+        .bind(key)
+        .bind(start)
+        .bind(end)
+        .bind(rows as i64)
+        .bind(bytes as i64)
+        .bind(dataset.schema_version())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Splits `s3://bucket/some/prefix` into `("s3", "bucket", "some/prefix")`.
+    fn split_url(url: &str) -> Result<(&str, &str, &str)> {
+        let (scheme, rest) = url.split_once("://").context("ARCHIVE_URL must look like s3://bucket/prefix")?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            bail!("ARCHIVE_URL is missing a bucket name");
+        }
+        Ok((scheme, bucket, prefix.trim_matches('/')))
+    }
+
+    fn object_key(prefix: &str, name: &str) -> String {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use chrono::TimeZone;
+
+        #[test]
+        fn test_object_keys() {
+            let (scheme, bucket, prefix) = split_url("s3://pump-archive/indexer/").unwrap();
+            assert_eq!((scheme, bucket, prefix), ("s3", "pump-archive", "indexer"));
+
+            let start = Utc.with_ymd_and_hms(2025, 11, 3, 7, 0, 0).unwrap();
+            assert_eq!(
+                object_key(prefix, &Dataset::Transactions.object_name(start)),
+                "indexer/transactions/2025/11/03/07.ndjson.gz"
+            );
+            assert_eq!(
+                object_key("", &Dataset::Trades.object_name(start)),
+                "trades/date=2025-11-03/trades.parquet"
+            );
+            assert!(split_url("gs://").is_err());
+        }
+    }
+}
+
+#[cfg(not(feature = "archive"))]
+mod uploader {
+    use anyhow::{bail, Result};
+    use sqlx::PgPool;
+    use crate::config::ArchiveConfig;
+
+    /// Stand-in when the crate is built without the `archive` feature.
+    pub struct Archiver;
+
+    impl Archiver {
+        pub fn new(_config: &ArchiveConfig) -> Result<Self> {
+            bail!("ARCHIVE_URL is set but the indexer was built without the `archive` feature")
+        }
+
+        pub async fn run(&self, _pool: PgPool) {}
+    }
+}
+
+pub use uploader::Archiver;
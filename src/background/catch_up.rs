@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, warn};
+use anyhow::Result;
+use crate::database;
+use crate::helius::parser::PARSERS;
+use crate::processor::{metrics::Metrics, state::TokenStateMap};
+use super::token_backfill::replay_transaction;
+
+const CHECKPOINT_INTERVAL_SECS: u64 = 15;
+/// `getSignaturesForAddress` page size limit.
+const PAGE_SIZE: usize = 1000;
+
+/// Successful transactions of `program` after `after_slot`, newest first,
+/// and whether there were more than `limit`.
+async fn signatures_since(
+    rpc: &RpcClient,
+    program: &Pubkey,
+    after_slot: u64,
+    limit: usize,
+) -> Result<(Vec<(u64, String)>, bool)> {
+    let mut signatures = Vec::new();
+    let mut before = None;
+
+    loop {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(PAGE_SIZE),
+            commitment: Some(CommitmentConfig::confirmed()),
+        };
+        let page = rpc.get_signatures_for_address_with_config(program, config).await?;
+        let Some(last) = page.last() else { return Ok((signatures, false)) };
+        before = Signature::from_str(&last.signature).ok();
+        let exhausted = page.len() < PAGE_SIZE;
+
+        for entry in page {
+            if entry.slot <= after_slot {
+                return Ok((signatures, false));
+            }
+            if signatures.len() == limit {
+                return Ok((signatures, true));
+            }
+            if entry.err.is_none() {
+                signatures.push((entry.slot, entry.signature));
+            }
+        }
+        if exhausted {
+            return Ok((signatures, false));
+        }
+    }
+}
+
+/// Replays every indexed program's transactions since the saved checkpoint,
+/// oldest first, up to `limit` per program. The live listener runs
+/// meanwhile; the event ledger keeps the overlap from counting twice.
+/// Fails if any transaction couldn't be replayed.
+async fn catch_up(
+    pool: &PgPool,
+    rpc: &RpcClient,
+    state_map: &TokenStateMap,
+    sol_price: &RwLock<f64>,
+    metrics: &Metrics,
+    limit: usize,
+) -> Result<()> {
+    let checkpoint = database::fetch_checkpoint_slot(pool).await?;
+    if checkpoint == 0 {
+        info!("⏩ No checkpoint saved yet, skipping catch-up");
+        return Ok(());
+    }
+
+    let mut pending = Vec::new();
+    for parser in PARSERS {
+        let program = Pubkey::from_str(parser.program_id())?;
+        let (signatures, truncated) = signatures_since(rpc, &program, checkpoint, limit).await?;
+        if truncated {
+            warn!(
+                "⚠️  More than {} {} transactions since slot {}; older ones are left to a manual backfill",
+                limit, parser.name(), checkpoint
+            );
+        }
+        pending.extend(signatures);
+    }
+
+    // A transaction can touch several programs.
+    let mut seen = HashSet::new();
+    pending.retain(|(_, signature)| seen.insert(signature.clone()));
+    pending.sort_by_key(|(slot, _)| *slot);
+
+    info!("⏩ Catching up on {} transactions since slot {}", pending.len(), checkpoint);
+
+    let (mut events, mut failed) = (0, 0);
+    for (_, signature) in &pending {
+        let sol_price_usd = *sol_price.read().await;
+        match replay_transaction(rpc, pool, None, state_map, sol_price_usd, metrics, signature).await {
+            Ok(count) => events += count,
+            Err(e) => {
+                warn!("⚠️  Catch-up of {} failed: {}", signature, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} transactions since slot {} failed to replay", failed, pending.len(), checkpoint);
+    }
+    info!("✅ Caught up: {} transactions, {} events", pending.len(), events);
+    Ok(())
+}
+
+/// Fills the gap left by downtime, then keeps saving the last processed
+/// slot as the next start's checkpoint. The checkpoint only moves once the
+/// catch-up succeeded for every transaction; otherwise this returns the
+/// error and the supervisor retries it from the same point.
+pub async fn start_catch_up(
+    pool: PgPool,
+    rpc: Arc<RpcClient>,
+    state_map: TokenStateMap,
+    sol_price: Arc<RwLock<f64>>,
+    metrics: Metrics,
+    limit: usize,
+) -> Result<()> {
+    if limit > 0 {
+        catch_up(&pool, &rpc, &state_map, &sol_price, &metrics, limit).await?;
+    }
+
+    let mut interval = interval(Duration::from_secs(CHECKPOINT_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+
+        let slot = metrics.last_processed_slot.load(Ordering::Relaxed);
+        if slot > 0
+            && let Err(e) = database::save_checkpoint_slot(&pool, slot).await
+        {
+            error!("Failed to save the checkpoint slot: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_checkpoint_slot(pool: PgPool) {
+        assert_eq!(database::fetch_checkpoint_slot(&pool).await.unwrap(), 0);
+
+        database::save_checkpoint_slot(&pool, 300).await.unwrap();
+        // Never moves back.
+        database::save_checkpoint_slot(&pool, 200).await.unwrap();
+        assert_eq!(database::fetch_checkpoint_slot(&pool).await.unwrap(), 300);
+    }
+}
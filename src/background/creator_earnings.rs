@@ -0,0 +1,71 @@
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, debug};
+use chrono::{DateTime, Utc};
+use anyhow::Result;
+
+const AGGREGATION_INTERVAL_SECS: u64 = 300;
+/// Hours re-aggregated on every tick, to pick up late-arriving trades.
+const REFRESH_WINDOW_HOURS: i64 = 2;
+
+/// Recomputes each creator's accrued fees per token for every UTC day from
+/// `since` on.
+pub async fn aggregate_creator_earnings(pool: &PgPool, since: DateTime<Utc>) -> Result<u64> {
+    let result = sqlx::query(
+        "INSERT INTO creator_earnings_daily (creator, token_mint, day, accrued_lamports, trades, updated_at)
+         SELECT creator, token_mint, date_trunc('day', timestamp), SUM(creator_fee), COUNT(*), NOW()
+         FROM trades
+         WHERE timestamp >= date_trunc('day', $1::TIMESTAMPTZ)
+           AND creator_fee > 0
+         GROUP BY 1, 2, 3
+         ON CONFLICT (creator, day, token_mint) DO UPDATE SET
+             accrued_lamports = EXCLUDED.accrued_lamports,
+             trades = EXCLUDED.trades,
+             updated_at = NOW()"
+    )
+    .bind(since)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// The last rolled-up day, or the first trade when the table is empty.
+async fn initial_since(pool: &PgPool) -> Result<DateTime<Utc>> {
+    let row: (Option<DateTime<Utc>>,) = sqlx::query_as(
+        "SELECT COALESCE(
+             (SELECT MAX(day) FROM creator_earnings_daily),
+             (SELECT MIN(timestamp) FROM trades)
+         )"
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.0.unwrap_or_else(Utc::now))
+}
+
+pub async fn start_creator_earnings_aggregator(pool: PgPool) {
+    let mut interval = interval(Duration::from_secs(AGGREGATION_INTERVAL_SECS));
+
+    info!("💸 Starting creator earnings aggregator ({}s interval)", AGGREGATION_INTERVAL_SECS);
+
+    let mut since = match initial_since(&pool).await {
+        Ok(since) => since,
+        Err(e) => {
+            error!("Failed to determine creator earnings starting point: {}", e);
+            Utc::now()
+        }
+    };
+
+    loop {
+        interval.tick().await;
+
+        match aggregate_creator_earnings(&pool, since).await {
+            Ok(rows) => {
+                debug!("💸 Rolled up {} creator earnings rows since {}", rows, since);
+                since = Utc::now() - chrono::Duration::hours(REFRESH_WINDOW_HOURS);
+            }
+            Err(e) => error!("Failed to aggregate creator earnings: {}", e),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, warn};
+use anyhow::Result;
+use crate::database;
+use crate::helius::parser::PumpEvent;
+use crate::processor::{self, metrics::Metrics, state::TokenStateMap};
+
+const SWEEP_INTERVAL_SECS: u64 = 60;
+const EVENTS_PER_SWEEP: i64 = 200;
+/// Events that failed this often are left in the table for an operator.
+const MAX_ATTEMPTS: i32 = 10;
+
+/// Removes a handled dead letter. A failure only means it comes up again
+/// on a later sweep, so it doesn't stop this one.
+async fn forget(pool: &PgPool, signature: &str, index: i16) {
+    if let Err(e) = database::delete_dead_letter(pool, signature, index).await {
+        error!("Failed to delete dead letter {}#{}: {}", signature, index, e);
+    }
+}
+
+/// Retries due dead letters. Recovered events are stored and applied to
+/// live state, but not published to the event sinks: by now they are
+/// minutes old and consumers would see them out of order.
+async fn sweep(
+    pool: &PgPool,
+    state_map: &TokenStateMap,
+    sol_price: &RwLock<f64>,
+    metrics: &Metrics,
+) -> Result<(usize, usize)> {
+    let due = database::fetch_due_dead_letters(pool, MAX_ATTEMPTS, EVENTS_PER_SWEEP).await?;
+    let sol_price_usd = *sol_price.read().await;
+    let (mut recovered, mut failed) = (0, 0);
+
+    for letter in due {
+        let index = letter.event_index as usize;
+        let event = match PumpEvent::from_parts(&letter.kind, &letter.payload) {
+            Ok(event) => event,
+            Err(e) => {
+                warn!("⚠️  Dropping unreadable dead letter {}#{}: {}", letter.signature, index, e);
+                forget(pool, &letter.signature, letter.event_index).await;
+                continue;
+            }
+        };
+
+        match processor::process_event(
+            pool,
+            &letter.signature,
+            index,
+            &event,
+            letter.slot as u64,
+            None,
+            state_map,
+            None,
+            sol_price_usd,
+            metrics,
+        ).await {
+            Ok(_) => {
+                forget(pool, &letter.signature, letter.event_index).await;
+                recovered += 1;
+            }
+            Err(e) => {
+                processor::dead_letter(pool, &letter.signature, index, &event, letter.slot as u64, &e, metrics).await;
+                failed += 1;
+            }
+        }
+    }
+
+    Ok((recovered, failed))
+}
+
+pub async fn start_dead_letter_sweeper(
+    pool: PgPool,
+    state_map: TokenStateMap,
+    sol_price: Arc<RwLock<f64>>,
+    metrics: Metrics,
+) {
+    let mut interval = interval(Duration::from_secs(SWEEP_INTERVAL_SECS));
+
+    info!("📮 Starting dead-letter sweeper ({}s interval)", SWEEP_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+
+        match sweep(&pool, &state_map, &sol_price, &metrics).await {
+            Ok((0, 0)) => {}
+            Ok((recovered, failed)) => info!("📮 Retried dead letters: {} recovered, {} failed again", recovered, failed),
+            Err(e) => error!("Failed to sweep dead letters: {}", e),
+        }
+
+        match database::count_dead_letters(&pool).await {
+            Ok(count) => metrics.dead_letter_events.store(count, Ordering::Relaxed),
+            Err(e) => error!("Failed to count dead letters: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::model::CompleteEvent;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_sweep_recovers_event(pool: PgPool) {
+        let event = PumpEvent::Complete(CompleteEvent {
+            user: "alice".to_string(),
+            mint: "mint".to_string(),
+            bonding_curve: "curve".to_string(),
+            timestamp: 1_700_000_000,
+        });
+        let payload = serde_json::to_string(&event).unwrap();
+        database::save_dead_letter(&pool, "sig", 0, event.kind(), 1, &payload, "pool timed out").await.unwrap();
+        sqlx::query("UPDATE dead_letter_events SET next_retry_at = NOW()").execute(&pool).await.unwrap();
+
+        let state_map = TokenStateMap::default();
+        let sol_price = RwLock::new(150.0);
+        let (recovered, failed) = sweep(&pool, &state_map, &sol_price, &Metrics::new()).await.unwrap();
+
+        assert_eq!((recovered, failed), (1, 0));
+        assert_eq!(database::count_dead_letters(&pool).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use serde::Deserialize;
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, debug, warn};
+use anyhow::Result;
+use crate::processor::{metrics::Metrics, state::TokenStateMap};
+
+const JUPITER_PRICE_URL: &str = "https://lite-api.jup.ag/price/v3";
+const REFRESH_INTERVAL_SECS: u64 = 900;
+/// Jupiter's limit on ids per request.
+const BATCH_SIZE: usize = 50;
+/// Tokens checked per pass, least recently checked first.
+const TOKENS_PER_RUN: i64 = 500;
+/// Divergence (percent of Jupiter's price) flagged as a data-quality problem.
+pub const DIVERGENCE_THRESHOLD_PCT: f64 = 25.0;
+
+#[derive(Debug, Deserialize)]
+struct JupiterPrice {
+    #[serde(rename = "usdPrice")]
+    usd_price: f64,
+}
+
+/// How far `internal` is from `external`, as a percentage of `external`.
+pub fn divergence_pct(internal: f64, external: f64) -> Option<f64> {
+    (external > 0.0 && internal.is_finite()).then(|| (internal - external).abs() / external * 100.0)
+}
+
+async fn fetch_jupiter_prices(client: &reqwest::Client, mints: &[String]) -> Result<HashMap<String, f64>> {
+    // Mints Jupiter can't price are left out of the response or null.
+    let prices: HashMap<String, Option<JupiterPrice>> = client
+        .get(JUPITER_PRICE_URL)
+        .query(&[("ids", mints.join(","))])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(prices
+        .into_iter()
+        .filter_map(|(mint, price)| Some((mint, price?.usd_price)))
+        .collect())
+}
+
+/// Fetches Jupiter prices for the least recently checked graduated tokens
+/// and stores them with the divergence from our own price: live state if
+/// the token is held, otherwise its last trade. Returns how many tokens
+/// were checked and how many of those diverged.
+async fn check_prices(
+    pool: &PgPool,
+    client: &reqwest::Client,
+    state_map: &TokenStateMap,
+    sol_price_usd: f64,
+) -> Result<(usize, usize)> {
+    let tokens: Vec<(String, Option<f64>)> = sqlx::query_as(
+        "SELECT t.mint_address,
+                (SELECT price_usd FROM trades WHERE token_mint = t.mint_address ORDER BY timestamp DESC LIMIT 1)::FLOAT8
+         FROM tokens t
+         WHERE t.complete
+         ORDER BY t.external_price_at NULLS FIRST
+         LIMIT $1"
+    )
+    .bind(TOKENS_PER_RUN)
+    .fetch_all(pool)
+    .await?;
+
+    let (mut checked, mut diverged) = (0, 0);
+    for batch in tokens.chunks(BATCH_SIZE) {
+        let mints: Vec<String> = batch.iter().map(|(mint, _)| mint.clone()).collect();
+        let prices = fetch_jupiter_prices(client, &mints).await?;
+
+        for (mint, last_trade_price) in batch {
+            let live_price = state_map
+                .read()
+                .await
+                .get(mint)
+                .map(|s| s.current_price_sol * sol_price_usd);
+            let internal = live_price.or(*last_trade_price);
+            let external = prices.get(mint).copied();
+            let divergence = internal.zip(external).and_then(|(i, e)| divergence_pct(i, e));
+
+            if let Some(pct) = divergence.filter(|pct| *pct > DIVERGENCE_THRESHOLD_PCT) {
+                warn!("⚠️  Price of {} is {:.1}% off Jupiter's (${:.10} vs ${:.10})", mint, pct, internal.unwrap_or_default(), external.unwrap_or_default());
+                diverged += 1;
+            }
+
+            sqlx::query(
+                "UPDATE tokens
+                 SET external_price_usd = $2::NUMERIC, external_price_at = NOW(), price_divergence_pct = $3
+                 WHERE mint_address = $1"
+            )
+            .bind(mint)
+            .bind(external)
+            .bind(divergence)
+            .execute(pool)
+            .await?;
+            checked += 1;
+        }
+    }
+
+    Ok((checked, diverged))
+}
+
+pub async fn start_external_price_checker(
+    pool: PgPool,
+    state_map: TokenStateMap,
+    sol_price: Arc<RwLock<f64>>,
+    metrics: Metrics,
+) {
+    let mut interval = interval(Duration::from_secs(REFRESH_INTERVAL_SECS));
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .expect("reqwest client");
+
+    info!("🪐 Starting Jupiter price cross-check ({}s interval)", REFRESH_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+
+        let sol_price_usd = *sol_price.read().await;
+        match check_prices(&pool, &client, &state_map, sol_price_usd).await {
+            Ok((checked, diverged)) => {
+                metrics.diverging_prices.store(diverged as i64, Ordering::Relaxed);
+                debug!("🪐 Checked {} prices against Jupiter, {} diverged", checked, diverged);
+            }
+            Err(e) => error!("Failed to cross-check prices with Jupiter: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_divergence_pct() {
+        assert_eq!(divergence_pct(1.5, 1.0), Some(50.0));
+        assert_eq!(divergence_pct(0.5, 1.0), Some(50.0));
+        assert_eq!(divergence_pct(1.0, 0.0), None);
+        assert_eq!(divergence_pct(f64::NAN, 1.0), None);
+    }
+}
@@ -0,0 +1,235 @@
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, debug};
+use chrono::{DateTime, Utc};
+use anyhow::Result;
+
+const AGGREGATION_INTERVAL_SECS: u64 = 300;
+/// Minutes of activity re-examined on every tick, to pick up late trades.
+const REFRESH_WINDOW_MINUTES: i64 = 30;
+/// Real SOL in the curve at which a token graduates.
+const GRADUATION_LAMPORTS: i64 = 85_000_000_000;
+/// Funnel stages below graduation, as percent of `GRADUATION_LAMPORTS`.
+pub const STAGES: [i64; 2] = [10, 50];
+/// Creator recorded for tokens first seen through a trade.
+const PLACEHOLDER_CREATOR: &str = "11111111111111111111111111111111";
+
+/// Refreshes the milestones of every token created, traded or graduated
+/// since `since`, then rebuilds the day and creator cohorts those tokens
+/// belong to. Returns how many tokens were updated. Milestones only move
+/// forward, as trades tiered out of Postgres no longer count towards them.
+pub async fn aggregate(pool: &PgPool, since: DateTime<Utc>) -> Result<u64> {
+    let stamp = Utc::now();
+    let mut tx = pool.begin().await?;
+
+    let tokens = sqlx::query(
+        "WITH touched AS (
+             SELECT mint_address FROM tokens WHERE created_at >= $1 OR graduated_at >= $1
+             UNION
+             SELECT token_mint FROM trades WHERE timestamp >= $1
+         )
+         INSERT INTO token_funnel (mint_address, creator_wallet, cohort_day, max_progress,
+                                   reached_10_at, reached_50_at, graduated_at, updated_at)
+         SELECT t.mint_address, t.creator_wallet, date_trunc('day', t.created_at),
+                CASE WHEN t.graduated_at IS NOT NULL THEN 100
+                     ELSE LEAST(COALESCE(tr.max_real, 0)::FLOAT8 / $3 * 100, 100) END,
+                COALESCE(tr.at_10, t.graduated_at),
+                COALESCE(tr.at_50, t.graduated_at),
+                t.graduated_at,
+                $2
+         FROM touched
+         JOIN tokens t USING (mint_address)
+         LEFT JOIN LATERAL (
+             SELECT MAX(real_sol_reserves) AS max_real,
+                    MIN(timestamp) FILTER (WHERE real_sol_reserves * 100 >= $3 * $4) AS at_10,
+                    MIN(timestamp) FILTER (WHERE real_sol_reserves * 100 >= $3 * $5) AS at_50
+             FROM trades
+             WHERE token_mint = t.mint_address
+         ) tr ON TRUE
+         WHERE t.creator_wallet <> $6
+         ON CONFLICT (mint_address) DO UPDATE SET
+             max_progress = GREATEST(token_funnel.max_progress, EXCLUDED.max_progress),
+             reached_10_at = LEAST(token_funnel.reached_10_at, EXCLUDED.reached_10_at),
+             reached_50_at = LEAST(token_funnel.reached_50_at, EXCLUDED.reached_50_at),
+             graduated_at = EXCLUDED.graduated_at,
+             updated_at = EXCLUDED.updated_at"
+    )
+    .bind(since)
+    .bind(stamp)
+    .bind(GRADUATION_LAMPORTS)
+    .bind(STAGES[0])
+    .bind(STAGES[1])
+    .bind(PLACEHOLDER_CREATOR)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query(
+        "INSERT INTO funnel_daily (day, created, reached_10, reached_50, graduated, updated_at)
+         SELECT cohort_day, COUNT(*), COUNT(reached_10_at), COUNT(reached_50_at), COUNT(graduated_at), NOW()
+         FROM token_funnel
+         WHERE cohort_day IN (SELECT cohort_day FROM token_funnel WHERE updated_at = $1)
+         GROUP BY 1
+         ON CONFLICT (day) DO UPDATE SET
+             created = EXCLUDED.created,
+             reached_10 = EXCLUDED.reached_10,
+             reached_50 = EXCLUDED.reached_50,
+             graduated = EXCLUDED.graduated,
+             updated_at = NOW()"
+    )
+    .bind(stamp)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO funnel_creator_daily (creator_wallet, day, created, reached_10, reached_50, graduated, updated_at)
+         SELECT creator_wallet, cohort_day, COUNT(*), COUNT(reached_10_at), COUNT(reached_50_at), COUNT(graduated_at), NOW()
+         FROM token_funnel
+         WHERE (creator_wallet, cohort_day) IN (
+             SELECT creator_wallet, cohort_day FROM token_funnel WHERE updated_at = $1
+         )
+         GROUP BY 1, 2
+         ON CONFLICT (creator_wallet, day) DO UPDATE SET
+             created = EXCLUDED.created,
+             reached_10 = EXCLUDED.reached_10,
+             reached_50 = EXCLUDED.reached_50,
+             graduated = EXCLUDED.graduated,
+             updated_at = NOW()"
+    )
+    .bind(stamp)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(tokens)
+}
+
+/// The last funnel refresh, or the first token ever indexed when the table
+/// is empty.
+async fn initial_since(pool: &PgPool) -> Result<DateTime<Utc>> {
+    let row: (Option<DateTime<Utc>>,) = sqlx::query_as(
+        "SELECT COALESCE(
+             (SELECT MAX(updated_at) FROM token_funnel),
+             (SELECT MIN(created_at) FROM tokens)
+         )"
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.0.unwrap_or_else(Utc::now))
+}
+
+pub async fn start_funnel_aggregator(pool: PgPool) {
+    let mut interval = interval(Duration::from_secs(AGGREGATION_INTERVAL_SECS));
+
+    info!("🪜 Starting launch funnel aggregator ({}s interval)", AGGREGATION_INTERVAL_SECS);
+
+    let mut since = match initial_since(&pool).await {
+        Ok(since) => since,
+        Err(e) => {
+            error!("Failed to determine funnel starting point: {}", e);
+            Utc::now()
+        }
+    };
+
+    loop {
+        interval.tick().await;
+
+        let started = Utc::now();
+        match aggregate(&pool, since).await {
+            Ok(tokens) => {
+                debug!("🪜 Refreshed funnel for {} tokens active since {}", tokens, since);
+                since = started - chrono::Duration::minutes(REFRESH_WINDOW_MINUTES);
+            }
+            Err(e) => error!("Failed to aggregate launch funnel: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn insert_token(pool: &PgPool, mint: &str, creator: &str, created_at: DateTime<Utc>, graduated: bool) {
+        sqlx::query(
+            "INSERT INTO tokens (mint_address, name, symbol, uri, creator_wallet, bonding_curve_address,
+                                 created_at, complete, graduated_at)
+             VALUES ($1, $1, $1, '', $2, $1, $3, $4, CASE WHEN $4 THEN $3 + INTERVAL '1 hour' END)"
+        )
+        .bind(mint)
+        .bind(creator)
+        .bind(created_at)
+        .bind(graduated)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn insert_trade(pool: &PgPool, mint: &str, real_sol: i64, at: DateTime<Utc>) {
+        sqlx::query(
+            "INSERT INTO trades (signature, token_mint, sol_amount, token_amount, is_buy, user_wallet, timestamp,
+                                 virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                                 fee_recipient, fee_basis_points, fee, creator, creator_fee_basis_points, creator_fee,
+                                 track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume,
+                                 last_update_timestamp, ix_name)
+             VALUES ($1 || $2, $1, 1, 1, TRUE, 'buyer', $3, 1, 1, $2, 1, 'feeRecipient', 0, 0, 'creator', 0, 0,
+                     FALSE, 0, 0, 0, $3, 'buy')"
+        )
+        .bind(mint)
+        .bind(real_sol)
+        .bind(at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_funnel_cohorts(pool: PgPool) {
+        let day = "2025-11-10T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let at = day + chrono::Duration::hours(3);
+        insert_token(&pool, "stalled", "alice", at, false).await;
+        insert_token(&pool, "halfway", "alice", at, false).await;
+        insert_token(&pool, "graduated", "bob", at, true).await;
+        insert_token(&pool, "placeholder", PLACEHOLDER_CREATOR, at, false).await;
+        insert_token(&pool, "tomorrow", "bob", at + chrono::Duration::days(1), false).await;
+
+        insert_trade(&pool, "stalled", 5_000_000_000, at).await;
+        insert_trade(&pool, "halfway", 9_000_000_000, at).await;
+        insert_trade(&pool, "halfway", 50_000_000_000, at + chrono::Duration::minutes(5)).await;
+        // Sold back down; the token still reached 50%.
+        insert_trade(&pool, "halfway", 1_000_000_000, at + chrono::Duration::minutes(9)).await;
+
+        assert_eq!(aggregate(&pool, day).await.unwrap(), 4);
+
+        let daily: Vec<(DateTime<Utc>, i64, i64, i64, i64)> = sqlx::query_as(
+            "SELECT day, created, reached_10, reached_50, graduated FROM funnel_daily ORDER BY day"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(daily, vec![(day, 3, 2, 2, 1), (day + chrono::Duration::days(1), 1, 0, 0, 0)]);
+
+        let creators: Vec<(String, i64, i64, i64)> = sqlx::query_as(
+            "SELECT creator_wallet, created, reached_50, graduated FROM funnel_creator_daily WHERE day = $1 ORDER BY 1"
+        )
+        .bind(day)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(creators, vec![("alice".into(), 2, 1, 0), ("bob".into(), 1, 1, 1)]);
+
+        // Tiering removes the trades that reached 50%; the milestone stays.
+        sqlx::query("DELETE FROM trades WHERE token_mint = 'halfway' AND real_sol_reserves > 1000000000")
+            .execute(&pool)
+            .await
+            .unwrap();
+        aggregate(&pool, day).await.unwrap();
+        let halfway: (f64, bool) = sqlx::query_as(
+            "SELECT max_progress, reached_50_at IS NOT NULL FROM token_funnel WHERE mint_address = 'halfway'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(halfway.0 > 50.0 && halfway.1);
+    }
+}
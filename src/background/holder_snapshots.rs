@@ -0,0 +1,156 @@
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, debug};
+use chrono::{DateTime, Utc};
+use anyhow::Result;
+
+const SNAPSHOT_INTERVAL_SECS: u64 = 300;
+
+/// Records the holder count and top-10 concentration of every token traded
+/// since `since`, stamped `taken_at`. Returns how many tokens were
+/// snapshotted. Balances from trades tiered out of Postgres come from
+/// `cold_wallet_balances`.
+pub async fn snapshot(pool: &PgPool, since: DateTime<Utc>, taken_at: DateTime<Utc>) -> Result<u64> {
+    let tokens = sqlx::query(
+        "WITH active AS (
+             SELECT mint_address, token_total_supply FROM tokens t
+             WHERE last_trade_at >= $1
+         ),
+         balances AS (
+             SELECT token_mint, SUM(balance) AS balance
+             FROM (
+                 SELECT t.token_mint, t.user_wallet, CASE WHEN t.is_buy THEN t.token_amount ELSE -t.token_amount END AS balance
+                 FROM trades t
+                 JOIN active a ON a.mint_address = t.token_mint
+                 UNION ALL
+                 SELECT c.token_mint, c.user_wallet, c.balance
+                 FROM cold_wallet_balances c
+                 JOIN active a ON a.mint_address = c.token_mint
+             ) b
+             GROUP BY token_mint, user_wallet
+         ),
+         ranked AS (
+             SELECT token_mint, balance, ROW_NUMBER() OVER (PARTITION BY token_mint ORDER BY balance DESC) AS rank
+             FROM balances
+             WHERE balance > 0
+         )
+         INSERT INTO holder_snapshots (token_mint, taken_at, holder_count, top10_pct)
+         SELECT a.mint_address,
+                $2,
+                COUNT(r.token_mint),
+                CASE WHEN a.token_total_supply > 0
+                     THEN COALESCE(SUM(r.balance) FILTER (WHERE r.rank <= 10), 0)::FLOAT8 / a.token_total_supply * 100
+                END
+         FROM active a
+         LEFT JOIN ranked r ON r.token_mint = a.mint_address
+         GROUP BY a.mint_address, a.token_total_supply
+         ON CONFLICT (token_mint, taken_at) DO NOTHING"
+    )
+    .bind(since)
+    .bind(taken_at)
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    Ok(tokens)
+}
+
+pub async fn start_holder_snapshotter(pool: PgPool) {
+    let mut interval = interval(Duration::from_secs(SNAPSHOT_INTERVAL_SECS));
+
+    info!("👥 Starting holder snapshots ({}s interval)", SNAPSHOT_INTERVAL_SECS);
+
+    let mut since = Utc::now() - chrono::Duration::seconds(SNAPSHOT_INTERVAL_SECS as i64);
+
+    loop {
+        interval.tick().await;
+
+        let taken_at = Utc::now();
+        match snapshot(&pool, since, taken_at).await {
+            Ok(tokens) => {
+                debug!("👥 Snapshotted holders of {} tokens traded since {}", tokens, since);
+                since = taken_at;
+            }
+            Err(e) => error!("Failed to snapshot holders: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn insert_trade(pool: &PgPool, signature: &str, wallet: &str, is_buy: bool, tokens: i64, at: DateTime<Utc>) {
+        sqlx::query(
+            "INSERT INTO trades (signature, token_mint, sol_amount, token_amount, is_buy, user_wallet, timestamp,
+                                 virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                                 fee_recipient, fee_basis_points, fee, creator, creator_fee_basis_points, creator_fee,
+                                 track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume,
+                                 last_update_timestamp, ix_name)
+             VALUES ($1, 'mint', 1, $4, $3, $2, $5, 1, 1, 1, 1, 'feeRecipient', 0, 0, 'creator', 0, 0,
+                     FALSE, 0, 0, 0, $5, 'buy')"
+        )
+        .bind(signature)
+        .bind(wallet)
+        .bind(is_buy)
+        .bind(tokens)
+        .bind(at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_snapshot_holders(pool: PgPool) {
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO tokens (mint_address, name, symbol, uri, creator_wallet, bonding_curve_address, created_at,
+                                 token_total_supply, last_trade_at)
+             VALUES ('mint', 'mint', 'mint', '', 'creator', 'curve', $1, 1000, $1)"
+        )
+        .bind(now)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        for i in 0..12 {
+            insert_trade(&pool, &format!("buy{}", i), &format!("wallet{}", i), true, 10 + i, now).await;
+        }
+        // Sold out, so no longer a holder.
+        insert_trade(&pool, "sell", "wallet11", false, 21, now).await;
+
+        assert_eq!(snapshot(&pool, now - chrono::Duration::minutes(5), now).await.unwrap(), 1);
+        // Nothing traded since.
+        assert_eq!(snapshot(&pool, now + chrono::Duration::seconds(1), now).await.unwrap(), 0);
+
+        let row: (i64, Option<f64>) = sqlx::query_as(
+            "SELECT holder_count, top10_pct FROM holder_snapshots WHERE token_mint = 'mint'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        // wallets 1..=10 hold 11 + ... + 20 = 155 of 1000.
+        assert_eq!(row, (11, Some(15.5)));
+
+        // Earlier trades tiered out of Postgres: wallet0 sold its 10 there,
+        // and wallet11 bought 100 that it still holds.
+        sqlx::query(
+            "INSERT INTO cold_wallet_balances (token_mint, user_wallet, balance, trades, sells, sold_lamports)
+             VALUES ('mint', 'wallet0', -10, 1, 1, 1), ('mint', 'wallet11', 100, 1, 0, 0)"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let later = now + chrono::Duration::seconds(1);
+        assert_eq!(snapshot(&pool, now - chrono::Duration::minutes(5), later).await.unwrap(), 1);
+        let row: (i64, Option<f64>) = sqlx::query_as(
+            "SELECT holder_count, top10_pct FROM holder_snapshots WHERE token_mint = 'mint' AND taken_at = $1"
+        )
+        .bind(later)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        // wallet11's 100, then wallets 10 down to 2: 100 + 20 + ... + 12 = 244.
+        assert_eq!(row, (11, Some(24.4)));
+    }
+}
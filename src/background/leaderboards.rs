@@ -0,0 +1,239 @@
+use sqlx::{FromRow, PgPool};
+use serde::{Deserialize, Serialize};
+use tokio::time::{interval, Duration};
+use tracing::{info, error, debug};
+use chrono::{DateTime, Utc};
+use anyhow::Result;
+use utoipa::ToSchema;
+use crate::storage::RedisClient;
+
+/// How many entries each board keeps; API callers can only ask for fewer.
+pub const LEADERBOARD_SIZE: i64 = 100;
+pub const LEADERBOARD_TTL_SECS: usize = 60;
+const REFRESH_INTERVAL_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Leaderboard<T> {
+    pub entries: Vec<T>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TokenLeaderboardEntry {
+    pub mint_address: String,
+    pub name: String,
+    pub symbol: String,
+    #[schema(value_type = Option<String>)]
+    pub market_cap_usd: Option<bigdecimal::BigDecimal>,
+    pub complete: bool,
+    pub volume_24h_sol: f64,
+    pub trades_24h: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TraderLeaderboardEntry {
+    pub wallet: String,
+    pub volume_24h_sol: f64,
+    /// Realized SOL flow over the window: sells received minus buys paid.
+    pub pnl_24h_sol: f64,
+    pub trades_24h: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CreatorLeaderboardEntry {
+    pub creator_wallet: String,
+    pub tokens_graduated: i64,
+    pub tokens_launched: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TokenSort {
+    Volume24h,
+    MarketCap,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum TraderSort {
+    Volume24h,
+    Pnl24h,
+}
+
+impl TokenSort {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "" | "volume_24h" => Some(Self::Volume24h),
+            "market_cap" => Some(Self::MarketCap),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Volume24h => "volume_24h",
+            Self::MarketCap => "market_cap",
+        }
+    }
+
+    pub fn cache_key(&self) -> String {
+        format!("pump:leaderboard:tokens:{}", self.as_str())
+    }
+}
+
+impl TraderSort {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "" | "volume_24h" => Some(Self::Volume24h),
+            "pnl_24h" => Some(Self::Pnl24h),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Volume24h => "volume_24h",
+            Self::Pnl24h => "pnl_24h",
+        }
+    }
+
+    pub fn cache_key(&self) -> String {
+        format!("pump:leaderboard:traders:{}", self.as_str())
+    }
+}
+
+pub const CREATOR_LEADERBOARD_KEY: &str = "pump:leaderboard:creators:graduations";
+
+pub async fn compute_token_leaderboard(
+    pool: &PgPool,
+    sort: TokenSort,
+) -> Result<Leaderboard<TokenLeaderboardEntry>> {
+    let sql = match sort {
+        TokenSort::Volume24h => {
+            "SELECT t.mint_address, t.name, t.symbol, t.market_cap_usd, t.complete,
+                    v.volume_24h_sol, v.trades_24h
+             FROM (
+                 SELECT token_mint,
+                        (SUM(sol_amount) / 1e9)::FLOAT8 AS volume_24h_sol,
+                        COUNT(*) AS trades_24h
+                 FROM trades
+                 WHERE timestamp > NOW() - INTERVAL '24 hours'
+                   AND token_mint NOT IN (
+                       SELECT mint_address FROM tokens
+                       WHERE creator_wallet IN (SELECT creator FROM creator_lists WHERE list = 'blacklist')
+                   )
+                 GROUP BY token_mint
+                 ORDER BY SUM(sol_amount) DESC
+                 LIMIT $1
+             ) v
+             JOIN tokens t ON t.mint_address = v.token_mint
+             ORDER BY v.volume_24h_sol DESC"
+        }
+        TokenSort::MarketCap => {
+            "SELECT t.mint_address, t.name, t.symbol, t.market_cap_usd, t.complete,
+                    COALESCE(v.volume_24h_sol, 0) AS volume_24h_sol,
+                    COALESCE(v.trades_24h, 0) AS trades_24h
+             FROM (
+                 SELECT * FROM tokens
+                 WHERE creator_wallet NOT IN (SELECT creator FROM creator_lists WHERE list = 'blacklist')
+                 ORDER BY market_cap_usd DESC NULLS LAST
+                 LIMIT $1
+             ) t
+             LEFT JOIN LATERAL (
+                 SELECT (SUM(sol_amount) / 1e9)::FLOAT8 AS volume_24h_sol,
+                        COUNT(*) AS trades_24h
+                 FROM trades
+                 WHERE token_mint = t.mint_address
+                   AND timestamp > NOW() - INTERVAL '24 hours'
+             ) v ON TRUE
+             ORDER BY t.market_cap_usd DESC NULLS LAST"
+        }
+    };
+
+    let entries = sqlx::query_as::<_, TokenLeaderboardEntry>(sql)
+        .bind(LEADERBOARD_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(Leaderboard { entries, updated_at: Utc::now() })
+}
+
+pub async fn compute_trader_leaderboard(
+    pool: &PgPool,
+    sort: TraderSort,
+) -> Result<Leaderboard<TraderLeaderboardEntry>> {
+    let order_by = match sort {
+        TraderSort::Volume24h => "volume_24h_sol DESC",
+        TraderSort::Pnl24h => "pnl_24h_sol DESC",
+    };
+
+    let sql = format!(
+        "SELECT user_wallet AS wallet,
+                (SUM(sol_amount) / 1e9)::FLOAT8 AS volume_24h_sol,
+                (SUM(CASE WHEN is_buy THEN -sol_amount ELSE sol_amount END) / 1e9)::FLOAT8 AS pnl_24h_sol,
+                COUNT(*) AS trades_24h
+         FROM trades
+         WHERE timestamp > NOW() - INTERVAL '24 hours'
+         GROUP BY user_wallet
+         ORDER BY {}
+         LIMIT $1",
+        order_by
+    );
+
+    let entries = sqlx::query_as::<_, TraderLeaderboardEntry>(&sql)
+        .bind(LEADERBOARD_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(Leaderboard { entries, updated_at: Utc::now() })
+}
+
+pub async fn compute_creator_leaderboard(
+    pool: &PgPool,
+) -> Result<Leaderboard<CreatorLeaderboardEntry>> {
+    let entries = sqlx::query_as::<_, CreatorLeaderboardEntry>(
+        "SELECT creator_wallet,
+                COUNT(*) FILTER (WHERE complete) AS tokens_graduated,
+                COUNT(*) AS tokens_launched
+         FROM tokens
+         GROUP BY creator_wallet
+         HAVING COUNT(*) FILTER (WHERE complete) > 0
+         ORDER BY tokens_graduated DESC, tokens_launched ASC
+         LIMIT $1"
+    )
+    .bind(LEADERBOARD_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Leaderboard { entries, updated_at: Utc::now() })
+}
+
+async fn refresh_all(pool: &PgPool, redis: &mut RedisClient) -> Result<()> {
+    for sort in [TokenSort::Volume24h, TokenSort::MarketCap] {
+        let board = compute_token_leaderboard(pool, sort).await?;
+        redis.set(&sort.cache_key(), &board, Some(LEADERBOARD_TTL_SECS)).await?;
+    }
+
+    for sort in [TraderSort::Volume24h, TraderSort::Pnl24h] {
+        let board = compute_trader_leaderboard(pool, sort).await?;
+        redis.set(&sort.cache_key(), &board, Some(LEADERBOARD_TTL_SECS)).await?;
+    }
+
+    let board = compute_creator_leaderboard(pool).await?;
+    redis.set(CREATOR_LEADERBOARD_KEY, &board, Some(LEADERBOARD_TTL_SECS)).await?;
+
+    Ok(())
+}
+
+pub async fn start_leaderboard_updater(pool: PgPool, mut redis: RedisClient) {
+    let mut interval = interval(Duration::from_secs(REFRESH_INTERVAL_SECS));
+
+    info!("🏆 Starting leaderboard updater ({}s interval)", REFRESH_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+
+        match refresh_all(&pool, &mut redis).await {
+            Ok(()) => debug!("🏆 Leaderboards refreshed"),
+            Err(e) => error!("Failed to refresh leaderboards: {}", e),
+        }
+    }
+}
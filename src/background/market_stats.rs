@@ -0,0 +1,213 @@
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, debug};
+use chrono::{DateTime, Utc};
+use anyhow::Result;
+
+const AGGREGATION_INTERVAL_SECS: u64 = 60;
+/// Hours re-aggregated on every tick, to pick up late-arriving events.
+const REFRESH_WINDOW_HOURS: i64 = 2;
+
+/// Bucket size of a market stats table. Days are UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Hour,
+    Day,
+}
+
+impl Granularity {
+    pub const ALL: [Granularity; 2] = [Granularity::Hour, Granularity::Day];
+
+    pub fn table(self) -> &'static str {
+        match self {
+            Granularity::Hour => "market_stats_hourly",
+            Granularity::Day => "market_stats_daily",
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Granularity::Hour => "hour",
+            Granularity::Day => "day",
+        }
+    }
+}
+
+/// Recomputes every bucket from `since` up to the current one. USD volume
+/// only counts trades stored with a SOL price.
+pub async fn aggregate(pool: &PgPool, granularity: Granularity, since: DateTime<Utc>) -> Result<u64> {
+    let sql = format!(
+        "WITH created AS (
+             SELECT date_trunc('{unit}', created_at) AS bucket, COUNT(*) AS c
+             FROM tokens
+             WHERE created_at >= date_trunc('{unit}', $1::TIMESTAMPTZ)
+             GROUP BY 1
+         ),
+         traded AS (
+             SELECT date_trunc('{unit}', timestamp) AS bucket,
+                    COUNT(*) AS c,
+                    SUM(sol_amount) AS volume,
+                    SUM(sol_amount / 1e9 * sol_price_usd) AS volume_usd,
+                    SUM(fee) AS protocol_fees,
+                    SUM(creator_fee) AS creator_fees,
+                    COUNT(DISTINCT user_wallet) AS wallets
+             FROM trades
+             WHERE timestamp >= date_trunc('{unit}', $1::TIMESTAMPTZ)
+             GROUP BY 1
+         ),
+         graduated AS (
+             SELECT date_trunc('{unit}', graduated_at) AS bucket, COUNT(*) AS c
+             FROM tokens
+             WHERE graduated_at >= date_trunc('{unit}', $1::TIMESTAMPTZ)
+             GROUP BY 1
+         )
+         INSERT INTO {table} (bucket, tokens_created, trades, volume_lamports, volume_usd, graduations, unique_wallets,
+                              protocol_fee_lamports, creator_fee_lamports, updated_at)
+         SELECT b.bucket,
+                COALESCE(created.c, 0),
+                COALESCE(traded.c, 0),
+                COALESCE(traded.volume, 0),
+                COALESCE(traded.volume_usd, 0),
+                COALESCE(graduated.c, 0),
+                COALESCE(traded.wallets, 0),
+                COALESCE(traded.protocol_fees, 0),
+                COALESCE(traded.creator_fees, 0),
+                NOW()
+         FROM generate_series(
+             date_trunc('{unit}', $1::TIMESTAMPTZ),
+             date_trunc('{unit}', NOW()),
+             INTERVAL '1 {unit}'
+         ) AS b(bucket)
+         LEFT JOIN created ON created.bucket = b.bucket
+         LEFT JOIN traded ON traded.bucket = b.bucket
+         LEFT JOIN graduated ON graduated.bucket = b.bucket
+         ON CONFLICT (bucket) DO UPDATE SET
+             tokens_created = EXCLUDED.tokens_created,
+             trades = EXCLUDED.trades,
+             volume_lamports = EXCLUDED.volume_lamports,
+             volume_usd = EXCLUDED.volume_usd,
+             graduations = EXCLUDED.graduations,
+             unique_wallets = EXCLUDED.unique_wallets,
+             protocol_fee_lamports = EXCLUDED.protocol_fee_lamports,
+             creator_fee_lamports = EXCLUDED.creator_fee_lamports,
+             updated_at = NOW()",
+        unit = granularity.unit(),
+        table = granularity.table(),
+    );
+
+    let result = sqlx::query(&sql)
+        .bind(since)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Where to resume aggregation on startup: the last stored bucket, or the
+/// first token ever indexed when the table is empty.
+async fn initial_since(pool: &PgPool, granularity: Granularity) -> Result<DateTime<Utc>> {
+    let row: (Option<DateTime<Utc>>,) = sqlx::query_as(&format!(
+        "SELECT COALESCE(
+             (SELECT MAX(bucket) FROM {}),
+             (SELECT MIN(created_at) FROM tokens)
+         )",
+        granularity.table()
+    ))
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.0.unwrap_or_else(Utc::now))
+}
+
+pub async fn start_market_stats_aggregator(pool: PgPool) {
+    let mut interval = interval(Duration::from_secs(AGGREGATION_INTERVAL_SECS));
+
+    info!("📈 Starting market stats aggregator ({}s interval)", AGGREGATION_INTERVAL_SECS);
+
+    let mut since = Vec::new();
+    for granularity in Granularity::ALL {
+        let start = match initial_since(&pool, granularity).await {
+            Ok(start) => start,
+            Err(e) => {
+                error!("Failed to determine {} starting point: {}", granularity.table(), e);
+                Utc::now()
+            }
+        };
+        since.push((granularity, start));
+    }
+
+    loop {
+        interval.tick().await;
+
+        for (granularity, since) in &mut since {
+            match aggregate(&pool, *granularity, *since).await {
+                Ok(buckets) => {
+                    debug!("📈 Aggregated {} {} buckets since {}", buckets, granularity.unit(), since);
+                    *since = Utc::now() - chrono::Duration::hours(REFRESH_WINDOW_HOURS);
+                }
+                Err(e) => error!("Failed to aggregate {}: {}", granularity.table(), e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_unique_wallets_per_granularity(pool: PgPool) {
+        let day = Utc::now() - chrono::Duration::days(1);
+        let day = day.date_naive().and_hms_opt(10, 0, 0).unwrap().and_utc();
+
+        sqlx::query(
+            "INSERT INTO tokens (mint_address, name, symbol, uri, bonding_curve_address, creator_wallet, created_at)
+             VALUES ('mint1', 'Token', 'TKN', 'uri', 'curve', 'creator', $1)"
+        )
+        .bind(day)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // The same wallet trades in two different hours of the same day.
+        for (signature, wallet, hour, sol_price) in [("sig1", "w1", 0, Some(100)), ("sig2", "w2", 0, None), ("sig3", "w1", 2, Some(200))] {
+            sqlx::query(
+                "INSERT INTO trades (signature, token_mint, sol_amount, token_amount, is_buy, user_wallet, timestamp,
+                                     virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                                     fee_recipient, fee_basis_points, fee, creator, creator_fee_basis_points, creator_fee,
+                                     track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume,
+                                     last_update_timestamp, ix_name, sol_price_usd)
+                 VALUES ($1, 'mint1', 1000000000, 1, TRUE, $2, $3, 1, 1, 1, 1, 'fee', 0, 0, 'creator', 0, 0,
+                         FALSE, 0, 0, 0, $3, 'buy', $4)"
+            )
+            .bind(signature)
+            .bind(wallet)
+            .bind(day + chrono::Duration::hours(hour))
+            .bind(sol_price.map(bigdecimal::BigDecimal::from))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        for granularity in Granularity::ALL {
+            aggregate(&pool, granularity, day).await.unwrap();
+        }
+
+        let hourly: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT trades, unique_wallets FROM market_stats_hourly WHERE trades > 0 ORDER BY bucket"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(hourly, [(2, 2), (1, 1)]);
+
+        let daily: (i64, i64, i64, f64) = sqlx::query_as(
+            "SELECT tokens_created, trades, unique_wallets, volume_usd::FLOAT8 FROM market_stats_daily WHERE bucket = $1"
+        )
+        .bind(day.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(daily, (1, 3, 2, 300.0));
+    }
+}
@@ -1,5 +1,40 @@
 pub mod sol_price;
 pub mod state_backup;
+pub mod leaderboards;
+pub mod market_stats;
+pub mod creator_earnings;
+pub mod funnel;
+pub mod wallet_links;
+pub mod archiver;
+pub mod supervisor;
+pub mod token_backfill;
+pub mod token_summaries;
+pub mod holder_snapshots;
+pub mod raydium_prices;
+pub mod external_prices;
+pub mod catch_up;
+pub mod dead_letter;
+pub mod sessions;
+pub mod spill;
+pub mod tiering;
+pub mod runtime_monitor;
 
 pub use sol_price::start_sol_price_updater;
-pub use state_backup::start_state_backup;
\ No newline at end of file
+pub use state_backup::start_state_backup;
+pub use leaderboards::start_leaderboard_updater;
+pub use market_stats::start_market_stats_aggregator;
+pub use creator_earnings::start_creator_earnings_aggregator;
+pub use funnel::start_funnel_aggregator;
+pub use wallet_links::start_wallet_linker;
+pub use token_summaries::start_token_summary_refresher;
+pub use holder_snapshots::start_holder_snapshotter;
+pub use raydium_prices::start_raydium_pricer;
+pub use external_prices::start_external_price_checker;
+pub use catch_up::start_catch_up;
+pub use dead_letter::start_dead_letter_sweeper;
+pub use sessions::start_session_heartbeat;
+pub use spill::start_spill_drainer;
+pub use tiering::start_trade_tiering;
+pub use runtime_monitor::start_runtime_monitor;
+pub use archiver::Archiver;
+pub use supervisor::Supervisor;
\ No newline at end of file
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+use sqlx::PgPool;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::RwLock;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, debug, warn};
+use chrono::{DateTime, TimeZone, Utc};
+use anyhow::Result;
+use std::str::FromStr;
+use crate::database;
+use crate::helius::raydium;
+use crate::processor::{self, state::{self, TokenStateMap}};
+
+const REFRESH_INTERVAL_SECS: u64 = 600;
+/// Pool lookups scan the whole Raydium program, so only a few run per pass.
+const LOOKUPS_PER_RUN: i64 = 20;
+/// Pools repriced per pass, least recently priced first.
+const PRICES_PER_RUN: i64 = 200;
+/// How long a token without a Raydium pool waits before it's looked up again.
+const RETRY_MISSING_HOURS: i64 = 24;
+/// PumpSwap launched on 2025-03-20; tokens that graduated before then
+/// migrated to Raydium.
+const PUMPSWAP_LAUNCH: i64 = 1_742_428_800;
+
+/// Graduates from before PumpSwap whose pool hasn't been found yet, or was
+/// missing last time but is due another lookup.
+pub async fn tokens_to_resolve(pool: &PgPool, now: DateTime<Utc>) -> Result<Vec<String>> {
+    let mints = sqlx::query_scalar(
+        "SELECT t.mint_address
+         FROM tokens t
+         LEFT JOIN raydium_pools r ON r.token_mint = t.mint_address
+         WHERE t.complete AND t.graduated_at < $1
+           AND (r.token_mint IS NULL OR (r.pool_address IS NULL AND r.checked_at < $2))
+         ORDER BY t.graduated_at DESC
+         LIMIT $3"
+    )
+    .bind(Utc.timestamp_opt(PUMPSWAP_LAUNCH, 0).unwrap())
+    .bind(now - chrono::Duration::hours(RETRY_MISSING_HOURS))
+    .bind(LOOKUPS_PER_RUN)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(mints)
+}
+
+async fn resolve_pools(pool: &PgPool, rpc: &RpcClient) -> Result<usize> {
+    let mut found = 0;
+    for mint in tokens_to_resolve(pool, Utc::now()).await? {
+        let address = match raydium::find_pool(rpc, &mint).await {
+            Ok(address) => address,
+            Err(e) => {
+                warn!("⚠️  {}", e);
+                continue;
+            }
+        };
+        found += address.is_some() as usize;
+
+        sqlx::query(
+            "INSERT INTO raydium_pools (token_mint, pool_address, checked_at)
+             VALUES ($1, $2, NOW())
+             ON CONFLICT (token_mint) DO UPDATE
+             SET pool_address = EXCLUDED.pool_address, checked_at = EXCLUDED.checked_at"
+        )
+        .bind(&mint)
+        .bind(address.map(|a| a.to_string()))
+        .execute(pool)
+        .await?;
+    }
+    Ok(found)
+}
+
+/// Reads the reserves of the least recently priced pools and applies them
+/// to the tokens' live state and stored market cap. Returns how many were
+/// priced.
+async fn reprice(pool: &PgPool, rpc: &RpcClient, state_map: &TokenStateMap, sol_price_usd: f64) -> Result<usize> {
+    let pools: Vec<(String, String)> = sqlx::query_as(
+        "SELECT token_mint, pool_address
+         FROM raydium_pools
+         WHERE pool_address IS NOT NULL
+         ORDER BY priced_at NULLS FIRST
+         LIMIT $1"
+    )
+    .bind(PRICES_PER_RUN)
+    .fetch_all(pool)
+    .await?;
+
+    let mut priced = 0;
+    for (mint, address) in pools {
+        let reserves = match raydium::fetch_reserves(rpc, &Pubkey::from_str(&address)?).await {
+            Ok(reserves) => reserves,
+            Err(e) => {
+                warn!("⚠️  Could not price {} from Raydium: {}", mint, e);
+                continue;
+            }
+        };
+
+        if !processor::load_graduated_state(pool, state_map, &mint, sol_price_usd).await? {
+            continue;
+        }
+        let Some(token) = state::update_token_state(
+            state_map,
+            &mint,
+            reserves.quote,
+            reserves.base,
+            reserves.quote,
+            reserves.base,
+            sol_price_usd,
+        ).await else {
+            continue;
+        };
+
+        database::update_token_metrics(pool, &mint, token.market_cap_usd, token.bonding_curve_progress).await?;
+        sqlx::query("UPDATE raydium_pools SET price_sol = $2, priced_at = NOW() WHERE token_mint = $1")
+            .bind(&mint)
+            .bind(token.current_price_sol)
+            .execute(pool)
+            .await?;
+        priced += 1;
+    }
+    Ok(priced)
+}
+
+pub async fn start_raydium_pricer(
+    pool: PgPool,
+    rpc: Arc<RpcClient>,
+    state_map: TokenStateMap,
+    sol_price: Arc<RwLock<f64>>,
+) {
+    let mut interval = interval(Duration::from_secs(REFRESH_INTERVAL_SECS));
+
+    info!("🌊 Starting Raydium pricer for pre-PumpSwap graduates ({}s interval)", REFRESH_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+
+        match resolve_pools(&pool, &rpc).await {
+            Ok(found) => debug!("🌊 Found {} Raydium pools", found),
+            Err(e) => error!("Failed to resolve Raydium pools: {}", e),
+        }
+
+        let sol_price_usd = *sol_price.read().await;
+        match reprice(&pool, &rpc, &state_map, sol_price_usd).await {
+            Ok(priced) => debug!("🌊 Priced {} tokens from Raydium", priced),
+            Err(e) => error!("Failed to price tokens from Raydium: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_tokens_to_resolve(pool: PgPool) {
+        let before_pumpswap = Utc.timestamp_opt(PUMPSWAP_LAUNCH - 86_400, 0).unwrap();
+        for (mint, graduated_at) in [("legacy", before_pumpswap), ("missing", before_pumpswap), ("recent", Utc::now())] {
+            sqlx::query(
+                "INSERT INTO tokens (mint_address, name, symbol, uri, creator_wallet, bonding_curve_address, created_at,
+                                     complete, graduated_at)
+                 VALUES ($1, $1, $1, '', 'creator', 'curve', $2, TRUE, $2)"
+            )
+            .bind(mint)
+            .bind(graduated_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query("INSERT INTO raydium_pools (token_mint, pool_address, checked_at) VALUES ('missing', NULL, NOW())")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(tokens_to_resolve(&pool, Utc::now()).await.unwrap(), ["legacy"]);
+        // A missed lookup is retried once it's old enough.
+        let later = Utc::now() + chrono::Duration::hours(RETRY_MISSING_HOURS + 1);
+        assert_eq!(tokens_to_resolve(&pool, later).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt::Write;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::runtime::{Handle, RuntimeMetrics};
+use tokio::time::interval;
+use tracing::{info, warn};
+use crate::processor::metrics::{write_metric, Metrics};
+
+const SAMPLE_INTERVAL_SECS: u64 = 5;
+
+/// What a worker had done as of one sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WorkerSample {
+    park_unpark_count: u64,
+    busy: Duration,
+}
+
+fn sample(runtime: &RuntimeMetrics) -> Vec<WorkerSample> {
+    (0..runtime.num_workers())
+        .map(|worker| WorkerSample {
+            park_unpark_count: runtime.worker_park_unpark_count(worker),
+            busy: runtime.worker_total_busy_duration(worker),
+        })
+        .collect()
+}
+
+/// Workers that were running (an even park/unpark count) at both samples
+/// without parking or reporting any busy time in between. Workers publish
+/// their counters between polls, so one stuck like this has spent the whole
+/// interval inside a single poll: blocking I/O, a lock or a hot loop.
+fn blocked_workers(previous: &[WorkerSample], current: &[WorkerSample]) -> usize {
+    previous
+        .iter()
+        .zip(current)
+        .filter(|(before, now)| now.park_unpark_count % 2 == 0 && before == now)
+        .count()
+}
+
+pub async fn start_runtime_monitor(metrics: Metrics) {
+    let runtime = Handle::current().metrics();
+    let mut interval = interval(Duration::from_secs(SAMPLE_INTERVAL_SECS));
+    let mut previous = sample(&runtime);
+
+    info!("🩺 Starting runtime monitor ({} workers, {}s interval)", runtime.num_workers(), SAMPLE_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+
+        let current = sample(&runtime);
+        let blocked = blocked_workers(&previous, &current);
+        let was_blocked = metrics.runtime_blocked_workers.swap(blocked as i64, Ordering::Relaxed);
+        if blocked > 0 && was_blocked == 0 {
+            warn!(
+                "🐌 {} of {} runtime workers stuck in one poll for {}s+ ({} tasks queued globally)",
+                blocked, runtime.num_workers(), SAMPLE_INTERVAL_SECS, runtime.global_queue_depth()
+            );
+        } else if blocked == 0 && was_blocked > 0 {
+            info!("✅ Runtime workers making progress again");
+        }
+        previous = current;
+    }
+}
+
+/// Appends the tokio runtime's own counters. Poll times and blocking pool
+/// sizes are only available in builds with `--cfg tokio_unstable`.
+pub fn render(out: &mut String) {
+    let Ok(handle) = Handle::try_current() else {
+        return;
+    };
+    let runtime = handle.metrics();
+    let workers = runtime.num_workers();
+
+    write_metric(out, "pump_tokio_workers", "Runtime worker threads", "gauge", workers);
+    write_metric(out, "pump_tokio_alive_tasks", "Tasks spawned and not yet finished", "gauge", runtime.num_alive_tasks());
+    write_metric(out, "pump_tokio_global_queue_depth", "Tasks waiting in the runtime's global queue", "gauge", runtime.global_queue_depth());
+
+    let _ = writeln!(out, "# HELP pump_tokio_worker_busy_seconds_total Time each worker spent running tasks");
+    let _ = writeln!(out, "# TYPE pump_tokio_worker_busy_seconds_total counter");
+    for worker in 0..workers {
+        let _ = writeln!(out, "pump_tokio_worker_busy_seconds_total{{worker=\"{}\"}} {}", worker, runtime.worker_total_busy_duration(worker).as_secs_f64());
+    }
+    let _ = writeln!(out, "# HELP pump_tokio_worker_parks_total Times each worker ran out of work and parked");
+    let _ = writeln!(out, "# TYPE pump_tokio_worker_parks_total counter");
+    for worker in 0..workers {
+        let _ = writeln!(out, "pump_tokio_worker_parks_total{{worker=\"{}\"}} {}", worker, runtime.worker_park_count(worker));
+    }
+
+    #[cfg(tokio_unstable)]
+    {
+        write_metric(out, "pump_tokio_blocking_threads", "Threads in the blocking pool", "gauge", runtime.num_blocking_threads());
+        write_metric(out, "pump_tokio_blocking_queue_depth", "Tasks waiting for a blocking pool thread", "gauge", runtime.blocking_queue_depth());
+        let _ = writeln!(out, "# HELP pump_tokio_worker_mean_poll_seconds Moving average of each worker's poll time");
+        let _ = writeln!(out, "# TYPE pump_tokio_worker_mean_poll_seconds gauge");
+        for worker in 0..workers {
+            let _ = writeln!(out, "pump_tokio_worker_mean_poll_seconds{{worker=\"{}\"}} {}", worker, runtime.worker_mean_poll_time(worker).as_secs_f64());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocked_workers() {
+        let at = |park_unpark_count, busy_ms| WorkerSample { park_unpark_count, busy: Duration::from_millis(busy_ms) };
+        let previous = [at(4, 100), at(4, 100), at(5, 100), at(6, 100)];
+        // Stuck; parked all along; still parked; ran and reported busy time.
+        let current = [at(4, 100), at(6, 250), at(5, 100), at(6, 400)];
+
+        assert_eq!(blocked_workers(&previous, &current), 1);
+    }
+}
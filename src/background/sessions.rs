@@ -0,0 +1,66 @@
+use std::sync::atomic::Ordering;
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{info, error};
+use crate::database::{self, model::SessionCounts};
+use crate::processor::metrics::Metrics;
+
+const HEARTBEAT_INTERVAL_SECS: u64 = 60;
+
+/// What the live indexer has processed since it started.
+pub fn counts(metrics: &Metrics) -> SessionCounts {
+    SessionCounts {
+        transactions: metrics.transactions_processed.load(Ordering::Relaxed),
+        tokens_created: metrics.tokens_created.load(Ordering::Relaxed),
+        trades: metrics.trades_processed.load(Ordering::Relaxed),
+        tokens_graduated: metrics.tokens_graduated.load(Ordering::Relaxed),
+    }
+}
+
+/// The latest processed slot, or `None` before the first transaction.
+pub fn end_slot(metrics: &Metrics) -> Option<u64> {
+    match metrics.last_processed_slot.load(Ordering::Relaxed) {
+        0 => None,
+        slot => Some(slot),
+    }
+}
+
+/// Keeps the session's counts current, so a run that is killed rather than
+/// shut down still shows what it did and roughly when it stopped.
+pub async fn start_session_heartbeat(pool: PgPool, session_id: i64, metrics: Metrics) {
+    let mut interval = interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+
+    info!("🫀 Recording session #{} ({}s heartbeat)", session_id, HEARTBEAT_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = database::update_session(&pool, session_id, end_slot(&metrics), &counts(&metrics), false).await {
+            error!("Failed to update session #{}: {}", session_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_session_lifecycle(pool: PgPool) {
+        let metrics = Metrics::new();
+        let id = database::start_session(&pool, "live", Some(100)).await.unwrap();
+        metrics.record_processed_slot(150, None);
+        metrics.increment_trades_processed();
+
+        database::update_session(&pool, id, end_slot(&metrics), &counts(&metrics), false).await.unwrap();
+        let running = database::fetch_sessions(&pool, 10).await.unwrap().remove(0);
+        assert_eq!((running.status.as_str(), running.end_slot, running.trades_processed), ("running", Some(150), 1));
+
+        sqlx::query("UPDATE indexer_sessions SET last_seen_at = NOW() - INTERVAL '1 hour'").execute(&pool).await.unwrap();
+        assert_eq!(database::fetch_sessions(&pool, 10).await.unwrap()[0].status, "interrupted");
+
+        database::update_session(&pool, id, None, &counts(&metrics), true).await.unwrap();
+        let stopped = database::fetch_sessions(&pool, 10).await.unwrap().remove(0);
+        assert_eq!((stopped.status.as_str(), stopped.end_slot, stopped.transactions_processed), ("stopped", Some(150), 1));
+    }
+}
@@ -1,31 +1,11 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
 use tokio::time::{interval, Duration};
-use tracing::{info, error, warn};
+use tracing::{info, warn};
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
-struct PythResponse {
-    #[serde(default)]
-    data: Vec<PriceData>,
-}
-
-#[derive(Debug, Deserialize)]
-struct PriceData {
-    id: String,
-    price: PriceInfo,
-}
-
-#[derive(Debug, Deserialize)]
-struct PriceInfo {
-    price: String,
-    #[serde(rename = "conf")]
-    confidence: String,
-    expo: i32,
-    #[serde(rename = "publish_time")]
-    publish_time: i64,
-}
-
 async fn fetch_sol_price_pyth() -> Result<f64, Box<dyn std::error::Error + Send + Sync>> {
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(10))
@@ -54,7 +34,6 @@ async fn fetch_sol_price_pyth() -> Result<f64, Box<dyn std::error::Error + Send
     
     #[derive(Deserialize)]
     struct ParsedData {
-        id: String,
         price: ParsedPrice,
     }
     
@@ -108,6 +87,55 @@ async fn fetch_sol_price_coingecko(api_key: Option<String>) -> Result<f64, Box<d
     Ok(data.solana.usd)
 }
 
+/// CoinGecko returns hourly points for ranges up to 90 days and daily ones
+/// beyond, so longer ranges are fetched in 90-day windows.
+const HISTORY_WINDOW_SECS: i64 = 90 * 86_400;
+
+/// Historical SOL/USD prices between two Unix timestamps from CoinGecko's
+/// range API, oldest first. The free tier only serves the last 365 days.
+pub async fn fetch_sol_price_history(
+    api_key: Option<&str>,
+    from: i64,
+    to: i64,
+) -> Result<Vec<(DateTime<Utc>, f64)>, Box<dyn std::error::Error + Send + Sync>> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    
+    #[derive(Deserialize)]
+    struct MarketChart {
+        /// `[unix_ms, price]` pairs
+        prices: Vec<(f64, f64)>,
+    }
+    
+    let mut points = BTreeMap::new();
+    let mut window_start = from;
+    while window_start < to {
+        let window_end = (window_start + HISTORY_WINDOW_SECS).min(to);
+        
+        let mut request = client
+            .get("https://api.coingecko.com/api/v3/coins/solana/market_chart/range")
+            .query(&[("vs_currency", "usd".to_string()), ("from", window_start.to_string()), ("to", window_end.to_string())])
+            .header("User-Agent", "Mozilla/5.0")
+            .header("Accept", "application/json");
+        
+        if let Some(key) = api_key {
+            request = request.header("x-cg-demo-api-key", key);
+        }
+        
+        let chart: MarketChart = request.send().await?.error_for_status()?.json().await?;
+        for (ms, price) in chart.prices {
+            if let Some(at) = DateTime::from_timestamp_millis(ms as i64) {
+                points.insert(at, price);
+            }
+        }
+        
+        window_start = window_end;
+    }
+    
+    Ok(points.into_iter().collect())
+}
+
 async fn fetch_sol_price(api_key: Option<String>) -> f64 {
     match fetch_sol_price_pyth().await {
         Ok(price) => {
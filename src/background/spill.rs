@@ -0,0 +1,145 @@
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use sqlx::PgPool;
+use tokio::sync::RwLock;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, warn};
+use anyhow::Result;
+use crate::processor::{self, metrics::Metrics, spill::SpillQueue, state::TokenStateMap};
+
+const DRAIN_INTERVAL_SECS: u64 = 5;
+const EVENTS_PER_BATCH: usize = 500;
+
+/// Applies spilled events oldest first until the queue is empty or the
+/// database fails again, returning how many were applied. Events that fail
+/// for other reasons are dead-lettered rather than blocking the rest.
+/// Like dead-letter retries, applied events aren't published to the sinks.
+async fn drain(
+    pool: &PgPool,
+    queue: &SpillQueue,
+    state_map: &TokenStateMap,
+    sol_price: &RwLock<f64>,
+    metrics: &Metrics,
+) -> Result<usize> {
+    let mut applied = 0;
+
+    loop {
+        let batch = queue.peek(EVENTS_PER_BATCH).await?;
+        if batch.is_empty() {
+            return Ok(applied);
+        }
+
+        let sol_price_usd = *sol_price.read().await;
+        let mut done = 0;
+        let mut outage = false;
+
+        for entry in &batch {
+            let (entry, event) = match entry {
+                Ok(entry) => match entry.event() {
+                    Ok(event) => (entry, event),
+                    Err(e) => {
+                        warn!("⚠️  Dropping unreadable spilled {} event {}#{}: {}", entry.kind, entry.signature, entry.index, e);
+                        done += 1;
+                        continue;
+                    }
+                },
+                Err(e) => {
+                    warn!("⚠️  Dropping unreadable spill line: {}", e);
+                    done += 1;
+                    continue;
+                }
+            };
+
+            match processor::process_event(
+                pool,
+                &entry.signature,
+                entry.index,
+                &event,
+                entry.slot,
+                None,
+                state_map,
+                None,
+                sol_price_usd,
+                metrics,
+            ).await {
+                Ok(_) => applied += 1,
+                Err(e) if e.is_retryable() => {
+                    warn!("Database still unavailable, {} events left spilled: {}", queue.len() - done, e);
+                    outage = true;
+                    break;
+                }
+                Err(e) => {
+                    processor::dead_letter(pool, &entry.signature, entry.index, &event, entry.slot, &e, metrics).await;
+                }
+            }
+            done += 1;
+        }
+
+        queue.remove_front(done).await?;
+        metrics.spill_queue_events.store(queue.len() as i64, Ordering::Relaxed);
+        if outage {
+            return Ok(applied);
+        }
+    }
+}
+
+pub async fn start_spill_drainer(
+    pool: PgPool,
+    queue: SpillQueue,
+    state_map: TokenStateMap,
+    sol_price: Arc<RwLock<f64>>,
+    metrics: Metrics,
+) {
+    let mut interval = interval(Duration::from_secs(DRAIN_INTERVAL_SECS));
+
+    info!("💾 Starting spill drainer ({}s interval, {} events queued)", DRAIN_INTERVAL_SECS, queue.len());
+    metrics.spill_queue_events.store(queue.len() as i64, Ordering::Relaxed);
+
+    loop {
+        interval.tick().await;
+        if queue.is_empty() {
+            continue;
+        }
+
+        match drain(&pool, &queue, &state_map, &sol_price, &metrics).await {
+            Ok(0) => {}
+            Ok(applied) => info!("💾 Applied {} spilled events, {} left", applied, queue.len()),
+            Err(e) => error!("Failed to drain spilled events: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::model::CompleteEvent;
+    use crate::helius::parser::PumpEvent;
+    use crate::processor::spill::SpilledEvent;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_drain_applies_spilled_events(pool: PgPool) {
+        let dir = std::env::temp_dir().join(format!("pump-spill-drain-{}", std::process::id()));
+        let queue = SpillQueue::open(&dir).await.unwrap();
+        let event = PumpEvent::Complete(CompleteEvent {
+            user: "alice".to_string(),
+            mint: "mint".to_string(),
+            bonding_curve: "curve".to_string(),
+            timestamp: 1_700_000_000,
+        });
+        queue.push(&SpilledEvent::new("sig", 0, 1, &event).unwrap()).await.unwrap();
+
+        let state_map = TokenStateMap::default();
+        let sol_price = RwLock::new(150.0);
+        let applied = drain(&pool, &queue, &state_map, &sol_price, &Metrics::new()).await.unwrap();
+
+        assert_eq!(applied, 1);
+        assert!(queue.is_empty());
+        let claimed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM processed_events WHERE signature = 'sig'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(claimed, 1);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
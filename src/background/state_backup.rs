@@ -28,10 +28,10 @@ pub async fn start_state_backup(pool: PgPool, state_map: TokenStateMap) {
         for state in states {
             let result = sqlx::query(
                 "UPDATE tokens 
-                 SET market_cap_sol = $1, bonding_curve_progress = $2, last_updated = NOW()
-                 WHERE mint = $3"
+                 SET market_cap_usd = $1::NUMERIC, bonding_curve_progress = $2::NUMERIC, updated_at = NOW()
+                 WHERE mint_address = $3"
             )
-            .bind(state.market_cap_sol)
+            .bind(state.market_cap_usd)
             .bind(state.bonding_curve_progress)
             .bind(&state.mint)
             .execute(&pool)
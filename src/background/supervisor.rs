@@ -0,0 +1,229 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio::time::Instant;
+use tracing::{error, info};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A run lasting at least this long resets the backoff.
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    Running,
+    /// Waiting out the backoff before the next start.
+    Restarting,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub state: TaskState,
+    pub restarts: u64,
+    pub last_error: Option<String>,
+    /// When the task entered its current state.
+    pub since: DateTime<Utc>,
+}
+
+/// What a supervised task returns. Long-running services never return, so
+/// any return, successful or not, is treated as the task stopping.
+pub trait TaskOutcome {
+    fn into_result(self) -> anyhow::Result<()>;
+}
+
+impl TaskOutcome for () {
+    fn into_result(self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+impl<E: Into<anyhow::Error>> TaskOutcome for Result<(), E> {
+    fn into_result(self) -> anyhow::Result<()> {
+        self.map_err(Into::into)
+    }
+}
+
+/// Owns the indexer's long-running tasks. Each one is restarted with
+/// exponential backoff whenever it returns, fails or panics, and its
+/// status is exposed for `/health/ready` and `/metrics`.
+#[derive(Clone)]
+pub struct Supervisor {
+    tasks: Arc<Mutex<BTreeMap<&'static str, TaskStatus>>>,
+    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    initial_backoff: Duration,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self {
+            tasks: Arc::new(Mutex::new(BTreeMap::new())),
+            handles: Arc::new(Mutex::new(Vec::new())),
+            initial_backoff: INITIAL_BACKOFF,
+        }
+    }
+
+    /// Runs `make_task()` under supervision, calling it again for every
+    /// restart.
+    pub fn spawn<F, Fut>(&self, name: &'static str, mut make_task: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future + Send + 'static,
+        Fut::Output: TaskOutcome + Send,
+    {
+        let supervisor = self.clone();
+        let handle = tokio::spawn(async move {
+            let mut backoff = supervisor.initial_backoff;
+            loop {
+                supervisor.set_state(name, TaskState::Running, None);
+                let started = Instant::now();
+
+                // A separate task so a panic is caught as a JoinError; the
+                // guard stops it if the supervisor itself is aborted.
+                let run = tokio::spawn(make_task());
+                let _guard = AbortOnDrop(run.abort_handle());
+                let reason = match run.await {
+                    Ok(outcome) => match outcome.into_result() {
+                        Ok(()) => "exited".to_string(),
+                        Err(e) => format!("{:#}", e),
+                    },
+                    Err(e) if e.is_panic() => format!("panicked: {}", panic_message(e.into_panic())),
+                    Err(e) => e.to_string(),
+                };
+
+                if started.elapsed() >= HEALTHY_RUN {
+                    backoff = supervisor.initial_backoff;
+                }
+                error!("💥 Background task {} stopped ({}), restarting in {:?}", name, reason, backoff);
+                supervisor.set_state(name, TaskState::Restarting, Some(reason));
+
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        });
+
+        self.handles.lock().unwrap().push(handle);
+        info!("🧭 Supervising background task: {}", name);
+    }
+
+    fn set_state(&self, name: &'static str, state: TaskState, error: Option<String>) {
+        let mut tasks = self.tasks.lock().unwrap();
+        let status = tasks.entry(name).or_insert(TaskStatus {
+            state,
+            restarts: 0,
+            last_error: None,
+            since: Utc::now(),
+        });
+        if status.state == TaskState::Restarting && state == TaskState::Running {
+            status.restarts += 1;
+        }
+        status.state = state;
+        status.since = Utc::now();
+        if error.is_some() {
+            status.last_error = error;
+        }
+    }
+
+    pub fn statuses(&self) -> BTreeMap<&'static str, TaskStatus> {
+        self.tasks.lock().unwrap().clone()
+    }
+
+    pub fn render_prometheus(&self) -> String {
+        let tasks = self.statuses();
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# HELP pump_task_up 1 while a supervised background task is running");
+        let _ = writeln!(out, "# TYPE pump_task_up gauge");
+        for (name, status) in &tasks {
+            let up = u8::from(status.state == TaskState::Running);
+            let _ = writeln!(out, "pump_task_up{{task=\"{}\"}} {}", name, up);
+        }
+
+        let _ = writeln!(out, "# HELP pump_task_restarts_total Restarts of supervised background tasks");
+        let _ = writeln!(out, "# TYPE pump_task_restarts_total counter");
+        for (name, status) in &tasks {
+            let _ = writeln!(out, "pump_task_restarts_total{{task=\"{}\"}} {}", name, status.restarts);
+        }
+
+        out
+    }
+
+    /// Stops every supervised task.
+    pub fn shutdown(&self) {
+        for handle in self.handles.lock().unwrap().drain(..) {
+            handle.abort();
+        }
+    }
+}
+
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_restarts_failed_and_panicked_tasks() {
+        let supervisor = Supervisor { initial_backoff: Duration::from_millis(10), ..Supervisor::new() };
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let counter = runs.clone();
+        supervisor.spawn("flaky", move || {
+            let counter = counter.clone();
+            async move {
+                match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 => anyhow::bail!("first run fails"),
+                    1 => panic!("second run panics"),
+                    _ => std::future::pending::<anyhow::Result<()>>().await,
+                }
+            }
+        });
+
+        // Poll rather than sleep a fixed time: capturing the panic's
+        // backtrace can take longer than the backoffs themselves.
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while runs.load(Ordering::SeqCst) < 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("task was not started three times");
+
+        let status = &supervisor.statuses()["flaky"];
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(status.state, TaskState::Running);
+        assert_eq!(status.restarts, 2);
+        assert_eq!(status.last_error.as_deref(), Some("panicked: second run panics"));
+
+        supervisor.shutdown();
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, warn};
+use anyhow::Result;
+use crate::database::tiering;
+
+const TIERING_INTERVAL_SECS: u64 = 3600;
+
+/// Moves archived days of trades older than `hot_days` out of Postgres,
+/// leaving one-minute candles behind. Days the archiver hasn't uploaded
+/// yet, or uploaded without every column, stay put, so nothing leaves
+/// Postgres before it is in Parquet.
+async fn tier(pool: &PgPool, hot_days: u32) -> Result<(usize, u64)> {
+    let before = Utc::now() - ChronoDuration::days(hot_days as i64);
+    let (mut days, mut trades) = (0, 0);
+
+    for period in tiering::fetch_prunable_periods(pool, before).await? {
+        match tiering::move_to_cold(pool, &period).await? {
+            Some(removed) => {
+                days += 1;
+                trades += removed;
+            }
+            None => warn!(
+                "⚠️  Keeping trades for {} in Postgres: they changed since {} was archived",
+                period.period_start.date_naive(),
+                period.object_key
+            ),
+        }
+    }
+
+    Ok((days, trades))
+}
+
+pub async fn start_trade_tiering(pool: PgPool, hot_days: u32) {
+    let mut interval = interval(Duration::from_secs(TIERING_INTERVAL_SECS));
+
+    info!("🧊 Starting trade tiering (keeping {} days in Postgres, {}s interval)", hot_days, TIERING_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+
+        match tier(&pool, hot_days).await {
+            Ok((0, _)) => {}
+            Ok((days, trades)) => info!("🧊 Moved {} trades from {} archived days to cold storage", trades, days),
+            Err(e) => error!("Failed to tier trades: {}", e),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use sqlx::PgPool;
+use std::str::FromStr;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use crate::database;
+use crate::error::{IndexerError, Result};
+use crate::helius::{self, accounts, RawTransaction};
+use crate::processor::{self, metrics::Metrics, state::TokenStateMap};
+use crate::storage::RedisClient;
+
+/// `getSignaturesForAddress` page size limit.
+const PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct TokenBackfillRequest {
+    pub mint: String,
+    /// Most recent transactions to replay (default 1000).
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+fn default_limit() -> usize { 1000 }
+
+#[derive(Debug, Default, Serialize)]
+pub struct TokenBackfillReport {
+    pub transactions: usize,
+    pub events: usize,
+    pub failed: usize,
+}
+
+/// Replays a token's most recent bonding curve transactions through the
+/// normal processing path, oldest first so the live state ends on the
+/// latest trade. Events are stored but not published to sinks, since they
+/// are not live.
+pub async fn backfill_token(
+    rpc: &RpcClient,
+    pool: &PgPool,
+    redis: Option<&RedisClient>,
+    state_map: &TokenStateMap,
+    sol_price_usd: f64,
+    metrics: &Metrics,
+    request: &TokenBackfillRequest,
+) -> Result<TokenBackfillReport> {
+    let curve = accounts::bonding_curve_address(&request.mint)?;
+    let mut signatures = Vec::new();
+    let mut before = None;
+
+    while signatures.len() < request.limit {
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(PAGE_SIZE.min(request.limit - signatures.len())),
+            commitment: Some(CommitmentConfig::confirmed()),
+        };
+        let page = rpc
+            .get_signatures_for_address_with_config(&curve, config)
+            .await
+            .map_err(|e| IndexerError::Rpc(format!("Failed to list signatures for {}: {}", curve, e)))?;
+        let Some(last) = page.last() else { break };
+        before = Signature::from_str(&last.signature).ok();
+        let exhausted = page.len() < PAGE_SIZE;
+
+        signatures.extend(page.into_iter().filter(|s| s.err.is_none()).map(|s| s.signature));
+        if exhausted {
+            break;
+        }
+    }
+
+    info!("🔁 Backfilling {} transactions for {}", signatures.len(), request.mint);
+
+    let mut report = TokenBackfillReport::default();
+    for signature in signatures.iter().rev() {
+        match replay_transaction(rpc, pool, redis, state_map, sol_price_usd, metrics, signature).await {
+            Ok(events) => {
+                report.transactions += 1;
+                report.events += events;
+            }
+            Err(e) => {
+                warn!("⚠️ Backfill of {} failed: {}", signature, e);
+                report.failed += 1;
+            }
+        }
+    }
+
+    info!(
+        "✅ Backfill for {} done: {} transactions, {} events, {} failed",
+        request.mint, report.transactions, report.events, report.failed
+    );
+    Ok(report)
+}
+
+/// Fetches one transaction and runs it through the normal processing path,
+/// returning how many events it had.
+pub(crate) async fn replay_transaction(
+    rpc: &RpcClient,
+    pool: &PgPool,
+    redis: Option<&RedisClient>,
+    state_map: &TokenStateMap,
+    sol_price_usd: f64,
+    metrics: &Metrics,
+    signature: &str,
+) -> Result<usize> {
+    let sig = Signature::from_str(signature)
+        .map_err(|e| IndexerError::Parse(format!("invalid signature {}: {}", signature, e)))?;
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::JsonParsed),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let response = rpc
+        .get_transaction_with_config(&sig, config)
+        .await
+        .map_err(|e| IndexerError::Rpc(format!("Failed to fetch {}: {}", signature, e)))?;
+
+    let raw_tx = RawTransaction {
+        signature: signature.to_string(),
+        slot: response.slot,
+        block_time: response.block_time,
+        seq: 0,
+        transaction: response.transaction,
+    };
+    let saved = match raw_tx.to_general_transaction() {
+        Ok(tx) => database::save_general_transaction(pool, &tx).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = saved {
+        warn!("⚠️ Failed to save transaction {}: {}", signature, e);
+    }
+    processor::process_creator_fee_claims(pool, signature, &raw_tx.transaction).await?;
+
+    let events = helius::parser::parse_transaction(signature, &raw_tx.transaction)?;
+    for (index, event) in events.iter().enumerate() {
+        processor::process_event(pool, signature, index, event, raw_tx.slot, redis, state_map, None, sol_price_usd, metrics).await?;
+    }
+    Ok(events.len())
+}
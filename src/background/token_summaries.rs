@@ -0,0 +1,85 @@
+use sqlx::PgPool;
+use tokio::time::{interval, Duration, Instant};
+use tracing::{info, error, debug};
+use anyhow::Result;
+
+/// How stale `/api/tokens` listings may get, including new tokens showing up.
+const REFRESH_INTERVAL_SECS: u64 = 60;
+
+/// Rebuilds `token_summaries` without blocking readers of the old contents.
+pub async fn refresh(pool: &PgPool) -> Result<()> {
+    sqlx::query("REFRESH MATERIALIZED VIEW CONCURRENTLY token_summaries")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn start_token_summary_refresher(pool: PgPool) {
+    let mut interval = interval(Duration::from_secs(REFRESH_INTERVAL_SECS));
+
+    info!("📋 Starting token summary refresher ({}s interval)", REFRESH_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+
+        let started = Instant::now();
+        match refresh(&pool).await {
+            Ok(()) => debug!("📋 Refreshed token summaries in {:?}", started.elapsed()),
+            Err(e) => error!("Failed to refresh token summaries: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    async fn insert_trade(pool: &PgPool, signature: &str, wallet: &str, is_buy: bool, tokens: i64, at: DateTime<Utc>) {
+        sqlx::query(
+            "INSERT INTO trades (signature, token_mint, sol_amount, token_amount, is_buy, user_wallet, timestamp,
+                                 virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                                 fee_recipient, fee_basis_points, fee, creator, creator_fee_basis_points, creator_fee,
+                                 track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume,
+                                 last_update_timestamp, ix_name, sol_price_usd)
+             VALUES ($1, 'mint', 2000000000, $4, $3, $2, $5, 1, 1, 1, 1, 'feeRecipient', 0, 0, 'creator', 0, 0,
+                     FALSE, 0, 0, 0, $5, 'buy', 100)"
+        )
+        .bind(signature)
+        .bind(wallet)
+        .bind(is_buy)
+        .bind(tokens)
+        .bind(at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_refresh_summaries(pool: PgPool) {
+        sqlx::query(
+            "INSERT INTO tokens (mint_address, name, symbol, uri, creator_wallet, bonding_curve_address, created_at)
+             VALUES ('mint', 'mint', 'mint', '', 'creator', 'curve', NOW())"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let now = Utc::now();
+        insert_trade(&pool, "a", "alice", true, 100, now).await;
+        insert_trade(&pool, "b", "bob", true, 50, now).await;
+        // Bob sold out, and this old buy falls outside the 24h volume.
+        insert_trade(&pool, "c", "bob", false, 50, now).await;
+        insert_trade(&pool, "d", "carol", true, 10, now - chrono::Duration::days(2)).await;
+
+        refresh(&pool).await.unwrap();
+
+        let row: (f64, f64, i64) = sqlx::query_as(
+            "SELECT volume_24h_sol, volume_24h_usd, holder_count FROM token_summaries WHERE mint_address = 'mint'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(row, (6.0, 600.0, 2));
+    }
+}
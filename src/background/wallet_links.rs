@@ -0,0 +1,250 @@
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{info, error, debug};
+use chrono::{DateTime, Utc};
+use anyhow::Result;
+
+const LINK_INTERVAL_SECS: u64 = 60;
+/// Trades younger than this are left for the next tick, so transactions
+/// still being fetched aren't skipped.
+const SETTLE_DELAY_SECS: i64 = 120;
+/// Buys this soon after a launch link the buyer to the creator.
+pub const EARLY_BUYER_WINDOW_SECS: i64 = 30;
+
+/// Links every trade in `[from, until)` and every launch whose early-buyer
+/// window closed in that range, then advances the cursor, all in one
+/// transaction so nothing is counted twice.
+pub async fn link_wallets(pool: &PgPool, from: DateTime<Utc>, until: DateTime<Utc>) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let mut linked = 0;
+
+    linked += sqlx::query(
+        "INSERT INTO wallet_links (source_wallet, target_wallet, relation, token_mint, occurrences, lamports, first_seen, last_seen)
+         SELECT t.creator_wallet, tr.user_wallet, 'early_buyer', t.mint_address,
+                COUNT(*), SUM(tr.sol_amount), MIN(tr.timestamp), MAX(tr.timestamp)
+         FROM tokens t
+         JOIN trades tr ON tr.token_mint = t.mint_address
+         WHERE t.created_at >= $1 - make_interval(secs => $3)
+           AND t.created_at < $2 - make_interval(secs => $3)
+           AND tr.timestamp < t.created_at + make_interval(secs => $3)
+           AND tr.is_buy
+           AND tr.user_wallet <> t.creator_wallet
+         GROUP BY 1, 2, 4
+         ON CONFLICT (source_wallet, target_wallet, relation, token_mint) DO UPDATE SET
+             occurrences = EXCLUDED.occurrences,
+             lamports = EXCLUDED.lamports,
+             first_seen = EXCLUDED.first_seen,
+             last_seen = EXCLUDED.last_seen"
+    )
+    .bind(from)
+    .bind(until)
+    .bind(EARLY_BUYER_WINDOW_SECS as f64)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    // Buys landing in the same slot of a launch's early window usually come
+    // from one bundle.
+    linked += sqlx::query(
+        "WITH early AS (
+             SELECT tr.user_wallet, tr.token_mint, tr.sol_amount, tr.timestamp, x.slot
+             FROM tokens t
+             JOIN trades tr ON tr.token_mint = t.mint_address
+             JOIN transactions x ON x.signature = tr.signature
+             WHERE t.created_at >= $1 - make_interval(secs => $3)
+               AND t.created_at < $2 - make_interval(secs => $3)
+               AND tr.timestamp < t.created_at + make_interval(secs => $3)
+               AND tr.is_buy
+         )
+         INSERT INTO wallet_links (source_wallet, target_wallet, relation, token_mint, occurrences, lamports, first_seen, last_seen)
+         SELECT a.user_wallet, b.user_wallet, 'same_slot', a.token_mint,
+                COUNT(*), SUM(a.sol_amount + b.sol_amount), MIN(a.timestamp), MAX(a.timestamp)
+         FROM early a
+         JOIN early b ON b.token_mint = a.token_mint AND b.slot = a.slot AND b.user_wallet > a.user_wallet
+         GROUP BY 1, 2, 4
+         ON CONFLICT (source_wallet, target_wallet, relation, token_mint) DO UPDATE SET
+             occurrences = EXCLUDED.occurrences,
+             lamports = EXCLUDED.lamports,
+             first_seen = EXCLUDED.first_seen,
+             last_seen = EXCLUDED.last_seen"
+    )
+    .bind(from)
+    .bind(until)
+    .bind(EARLY_BUYER_WINDOW_SECS as f64)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    // Balance increases of accounts other than the signer, the curve and
+    // the fee recipient are SOL the signer sent. Only accounts seen trading
+    // count, which keeps PDAs such as creator vaults out.
+    linked += sqlx::query(
+        "WITH txs AS (
+             SELECT DISTINCT ON (tr.signature)
+                    tr.signature, tr.token_mint, tr.timestamp, tr.fee_recipient, t.bonding_curve_address,
+                    x.signer, x.accounts_involved, x.pre_balances, x.post_balances
+             FROM trades tr
+             JOIN transactions x ON x.signature = tr.signature
+             LEFT JOIN tokens t ON t.mint_address = tr.token_mint
+             WHERE tr.timestamp >= $1 AND tr.timestamp < $2
+         ),
+         credited AS (
+             SELECT txs.signer, a.account, txs.token_mint, txs.timestamp,
+                    txs.post_balances[a.idx] - txs.pre_balances[a.idx] AS delta,
+                    txs.fee_recipient, txs.bonding_curve_address
+             FROM txs, unnest(txs.accounts_involved) WITH ORDINALITY AS a(account, idx)
+         )
+         INSERT INTO wallet_links (source_wallet, target_wallet, relation, token_mint, occurrences, lamports, first_seen, last_seen)
+         SELECT signer, account, 'funded', token_mint, COUNT(*), SUM(delta), MIN(timestamp), MAX(timestamp)
+         FROM credited c
+         WHERE c.delta > 0
+           AND c.account <> c.signer
+           AND c.account <> c.fee_recipient
+           AND c.account IS DISTINCT FROM c.bonding_curve_address
+           AND EXISTS (SELECT 1 FROM trades u WHERE u.user_wallet = c.account)
+         GROUP BY 1, 2, 4
+         ON CONFLICT (source_wallet, target_wallet, relation, token_mint) DO UPDATE SET
+             occurrences = wallet_links.occurrences + EXCLUDED.occurrences,
+             lamports = wallet_links.lamports + EXCLUDED.lamports,
+             first_seen = LEAST(wallet_links.first_seen, EXCLUDED.first_seen),
+             last_seen = GREATEST(wallet_links.last_seen, EXCLUDED.last_seen)"
+    )
+    .bind(from)
+    .bind(until)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    sqlx::query(
+        "INSERT INTO wallet_link_cursor (id, processed_until) VALUES (1, $1)
+         ON CONFLICT (id) DO UPDATE SET processed_until = EXCLUDED.processed_until"
+    )
+    .bind(until)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(linked)
+}
+
+/// The stored cursor, or the first trade when linking has never run.
+async fn initial_cursor(pool: &PgPool) -> Result<DateTime<Utc>> {
+    let row: (Option<DateTime<Utc>>,) = sqlx::query_as(
+        "SELECT COALESCE(
+             (SELECT processed_until FROM wallet_link_cursor WHERE id = 1),
+             (SELECT MIN(timestamp) FROM trades)
+         )"
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.0.unwrap_or_else(Utc::now))
+}
+
+pub async fn start_wallet_linker(pool: PgPool) -> Result<()> {
+    let mut interval = interval(Duration::from_secs(LINK_INTERVAL_SECS));
+
+    info!("🕸️ Starting wallet linker ({}s interval)", LINK_INTERVAL_SECS);
+
+    // Failing here lets the supervisor retry rather than relink from scratch.
+    let mut cursor = initial_cursor(&pool).await?;
+
+    loop {
+        interval.tick().await;
+
+        let until = Utc::now() - chrono::Duration::seconds(SETTLE_DELAY_SECS);
+        if until <= cursor {
+            continue;
+        }
+
+        match link_wallets(&pool, cursor, until).await {
+            Ok(links) => {
+                debug!("🕸️ Updated {} wallet links for {} .. {}", links, cursor, until);
+                cursor = until;
+            }
+            Err(e) => error!("Failed to link wallets: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn insert_trade(pool: &PgPool, signature: &str, wallet: &str, at: DateTime<Utc>) {
+        sqlx::query(
+            "INSERT INTO trades (signature, token_mint, sol_amount, token_amount, is_buy, user_wallet, timestamp,
+                                 virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                                 fee_recipient, fee_basis_points, fee, creator, creator_fee_basis_points, creator_fee,
+                                 track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume,
+                                 last_update_timestamp, ix_name)
+             VALUES ($1 || $2, 'mint1', 1000, 1, TRUE, $2, $3, 1, 1, 1, 1, 'feeRecipient', 0, 0, 'creator', 0, 0,
+                     FALSE, 0, 0, 0, $3, 'buy')"
+        )
+        .bind(signature)
+        .bind(wallet)
+        .bind(at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_link_wallets(pool: PgPool) {
+        let launch = Utc::now() - chrono::Duration::hours(1);
+        sqlx::query(
+            "INSERT INTO tokens (mint_address, name, symbol, uri, bonding_curve_address, creator_wallet, created_at)
+             VALUES ('mint1', 'Token', 'TKN', 'uri', 'curve', 'creator', $1)"
+        )
+        .bind(launch)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // sniper1 and sniper2 buy in the same slot right after launch;
+        // later, sniper1 sends SOL to sniper3 inside a trade.
+        let bundle = launch + chrono::Duration::seconds(2);
+        let late = launch + chrono::Duration::minutes(5);
+        for (signature, wallet, at, slot, accounts, post) in [
+            ("bundle:", "sniper1", bundle, 7, vec!["sniper1", "curve"], vec![90i64, 10]),
+            ("bundle:", "sniper2", bundle, 7, vec!["sniper2", "curve"], vec![90, 10]),
+            ("late:", "sniper3", late, 9, vec!["sniper3", "curve"], vec![90, 10]),
+            ("transfer:", "sniper1", late, 9, vec!["sniper1", "curve", "feeRecipient", "sniper3"], vec![10, 40, 10, 40]),
+        ] {
+            insert_trade(&pool, signature, wallet, at).await;
+            let mut pre = vec![0i64; accounts.len()];
+            pre[0] = 100;
+            sqlx::query(
+                "INSERT INTO transactions (signature, slot, block_time, fee, success, signer, instruction_count,
+                                           log_messages_count, has_program_data, accounts_involved, pre_balances, post_balances)
+                 VALUES ($1 || $2, $3, $4, 5000, TRUE, $2, 0, 0, TRUE, $5, $6, $7)"
+            )
+            .bind(signature)
+            .bind(wallet)
+            .bind(slot)
+            .bind(at)
+            .bind(&accounts)
+            .bind(&pre)
+            .bind(&post)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        link_wallets(&pool, launch - chrono::Duration::minutes(1), launch + chrono::Duration::minutes(10)).await.unwrap();
+
+        let mut links: Vec<(String, String, String, i64)> = sqlx::query_as(
+            "SELECT source_wallet, target_wallet, relation, lamports::BIGINT FROM wallet_links"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        links.sort();
+        assert_eq!(links, [
+            ("creator".into(), "sniper1".into(), "early_buyer".into(), 1000),
+            ("creator".into(), "sniper2".into(), "early_buyer".into(), 1000),
+            ("sniper1".into(), "sniper2".into(), "same_slot".into(), 2000),
+            ("sniper1".into(), "sniper3".into(), "funded".into(), 40),
+        ]);
+    }
+}
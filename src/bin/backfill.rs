@@ -1,14 +1,19 @@
 use anyhow::{Result, Context};
 use clap::Parser;
 use solana_client::rpc_client::RpcClient;
-use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_config::{RpcBlockConfig, RpcTransactionConfig};
 use solana_sdk::{pubkey::Pubkey, signature::Signature, commitment_config::CommitmentConfig};
-use solana_transaction_status::UiTransactionEncoding;
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
 use std::str::FromStr;
 use std::time::Duration;
 use tracing::{info, warn, error};
-use sqlx::postgres::PgPoolOptions;
-use chrono::TimeZone;
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use chrono::{DateTime, TimeZone, Utc};
+use pumpfun_indexer::background::sol_price::fetch_sol_price_history;
+use pumpfun_indexer::database::{self, bulk::{BulkLoader, BulkReport, SolPriceHistory}, model::SessionCounts};
+use pumpfun_indexer::ingest;
+use pumpfun_indexer::processor::calculator::DEFAULT_TOKEN_DECIMALS;
+use pumpfun_indexer::helius::parser::{self, PumpEvent};
 
 const PUMP_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 
@@ -36,6 +41,19 @@ struct Args {
     
     #[arg(long, default_value = "10")]
     concurrency: usize,
+    
+    /// Events buffered before they're written with COPY; 0 stores each
+    /// event as it's parsed, the way the live indexer does
+    #[arg(long, default_value = "5000")]
+    flush_size: usize,
+    
+    /// Walk whole blocks forward from this slot instead of paging signatures
+    #[arg(long)]
+    start_slot: Option<u64>,
+    
+    /// Last slot to walk in block mode (default: the current slot)
+    #[arg(long)]
+    end_slot: Option<u64>,
 }
 
 #[tokio::main]
@@ -60,6 +78,7 @@ async fn main() -> Result<()> {
     info!("🚀 Starting Pump.fun Backfill Tool");
     info!("   Batch size: {}", args.batch_size);
     info!("   Concurrency: {}", args.concurrency);
+    info!("   Flush size: {}", args.flush_size);
     
     if args.tokens_only {
         info!("   📍 MODE: PHASE 1 - TOKENS ONLY");
@@ -84,6 +103,7 @@ async fn main() -> Result<()> {
         .context("DATABASE_URL must be set")?;
     let helius_api_key = std::env::var("HELIUS_API_KEY")
         .context("HELIUS_API_KEY must be set")?;
+    let coingecko_api_key = std::env::var("COINGECKO_API_KEY").ok();
     
     info!("📊 Connecting to database...");
     let pool = PgPoolOptions::new()
@@ -93,6 +113,25 @@ async fn main() -> Result<()> {
         .context("Failed to connect to database")?;
     
     info!("✅ Database connected");
+
+    let mode = if args.tokens_only {
+        "backfill_tokens"
+    } else if args.trades_only {
+        "backfill_trades"
+    } else {
+        "backfill"
+    };
+    let session = database::start_session(&pool, mode, args.start_slot).await?;
+    let heartbeat_pool = pool.clone();
+    let heartbeat = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = database::touch_session(&heartbeat_pool, session).await {
+                warn!("⚠️  Failed to update session #{}: {}", session, e);
+            }
+        }
+    });
     
   
     let rpc_url = format!("https://mainnet.helius-rpc.com/?api-key={}", helius_api_key);
@@ -102,8 +141,8 @@ async fn main() -> Result<()> {
     
     let pump_pubkey = Pubkey::from_str(PUMP_PROGRAM)?;
     
-    let mut before_sig = if let Some(sig_str) = args.before {
-        Some(Signature::from_str(&sig_str)?)
+    let mut before_sig = if let Some(sig_str) = &args.before {
+        Some(Signature::from_str(sig_str)?)
     } else {
         None
     };
@@ -111,18 +150,24 @@ async fn main() -> Result<()> {
   
     let mut total_processed = 0;
     let mut total_events = 0;
-    let mut total_tokens = 0;
-    let mut total_trades = 0;
-    let mut total_completions = 0;
+    let mut totals = BulkReport::default();
     let mut batch_count = 0;
     let mut skipped_txs = 0;
-    let mut foreign_key_errors = 0; // Track trades without tokens
+    let mut loader = BulkLoader::new(pool.clone(), args.flush_size);
     
     let start_time = std::time::Instant::now();
     
-    info!("🔍 Starting signature fetch...");
+    if let Some(start_slot) = args.start_slot {
+        let walked = backfill_blocks(&client, &args, start_slot, &mut loader, &mut totals, coingecko_api_key.as_deref()).await?;
+        total_processed = walked.processed;
+        skipped_txs = walked.skipped;
+        total_events = walked.events;
+    } else {
+        info!("🔍 Starting signature fetch...");
+    }
     
-    loop {
+    // Block mode has already done the work; otherwise page signatures.
+    while args.start_slot.is_none() {
         batch_count += 1;
         let batch_start = std::time::Instant::now();
         
@@ -158,7 +203,7 @@ async fn main() -> Result<()> {
         for (chunk_idx, chunk) in sigs.chunks(chunk_size).enumerate() {
             info!("   Processing chunk {}/{} ({} sigs)...", 
                   chunk_idx + 1, 
-                  (sigs.len() + chunk_size - 1) / chunk_size,
+                  sigs.len().div_ceil(chunk_size),
                   chunk.len());
             
             for sig_info in chunk {
@@ -201,96 +246,37 @@ async fn main() -> Result<()> {
                         if !events.is_empty() {
                             total_events += events.len();
                             
-                            for event in events {
-                                match event {
-                                    pumpfun_indexer::helius::parser::PumpEvent::Create(create) => {
-                
-                                        if args.trades_only {
-                                            continue;
-                                        }
-                                        
-                                        if let Err(e) = save_create_event(&pool, &create).await {
-                                            if !e.to_string().contains("duplicate key") {
-                                                error!("❌ Failed to save CREATE: {}", e);
-                                            }
-                                        } else {
-                                            total_tokens += 1;
-                                            if total_tokens % 50 == 0 {
-                                                info!("      ✨ {} tokens created so far", total_tokens);
-                                            }
-                                        }
-                                    }
-                                    pumpfun_indexer::helius::parser::PumpEvent::Trade(trade) => {
-                                        
-                                        if args.tokens_only {
-                                            continue; 
-                                        }
-                                        
-                                        if let Err(e) = save_trade_event(&pool, &trade).await {
-                                            let err_str = e.to_string();
-                                            
-                                          
-                                            if err_str.contains("foreign key") || err_str.contains("violates") {
-                                                foreign_key_errors += 1;
-                                                if foreign_key_errors % 100 == 1 {
-                                                    warn!("⚠️  {} trades skipped (token not found in DB)", foreign_key_errors);
-                                                }
-                                            } else if !err_str.contains("duplicate key") {
-                                                error!("❌ Failed to save TRADE: {}", e);
-                                            }
-                                        } else {
-                                            total_trades += 1;
-                                            if total_trades % 1000 == 0 {
-                                                info!("      💰 {} trades saved so far", total_trades);
-                                            }
-                                        }
-                                    }
-                                    pumpfun_indexer::helius::parser::PumpEvent::Complete(complete) => {
-                                        
-                                        if args.tokens_only {
-                                            continue; 
-                                        }
-                                        
-                                        if let Err(e) = mark_complete(&pool, &complete.mint).await {
-                                            if !e.to_string().contains("duplicate key") {
-                                                error!("❌ Failed to mark COMPLETE: {}", e);
-                                            }
-                                        } else {
-                                            total_completions += 1;
-                                            if total_completions % 10 == 0 {
-                                                info!("      🎓 {} tokens graduated so far", total_completions);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
+                            store_events(&args, &sig_info.signature, sig_info.slot, &mut loader, &mut totals, coingecko_api_key.as_deref(), events).await;
                         }
                     }
                     Err(e) => {
-                        let err_str = e.to_string();
-                        if !err_str.contains("base58") && !err_str.contains("base64") {
-                            warn!("⚠️  Failed to parse TX {}: {}", &sig_info.signature[..8], e);
-                        }
+                        warn!("⚠️  Failed to parse TX {}: {}", &sig_info.signature[..8], e);
                     }
                 }
                 
                 total_processed += 1;
                 
-                if let Some(max) = args.max_txs {
-                    if total_processed >= max {
-                        info!("✅ Reached max transactions limit");
-                        break;
-                    }
+                if loader.is_full() {
+                    flush(&mut loader, &mut totals, coingecko_api_key.as_deref()).await;
                 }
-            }
-            
-            if let Some(max) = args.max_txs {
-                if total_processed >= max {
+                
+                if let Some(max) = args.max_txs
+                    && total_processed >= max
+                {
+                    info!("✅ Reached max transactions limit");
                     break;
                 }
             }
+            
+            if let Some(max) = args.max_txs
+                && total_processed >= max
+            {
+                break;
+            }
         }
         
+        flush(&mut loader, &mut totals, coingecko_api_key.as_deref()).await;
+        
         // Batch summary
         let batch_elapsed = batch_start.elapsed();
         let total_elapsed = start_time.elapsed();
@@ -299,18 +285,18 @@ async fn main() -> Result<()> {
         info!("📊 Batch #{} complete ({:.1}s):", batch_count, batch_elapsed.as_secs_f64());
         info!("   Processed: {}/{} TXs", total_processed, total_processed + skipped_txs);
         info!("   Events: {} ({} tokens, {} trades, {} completions)", 
-              total_events, total_tokens, total_trades, total_completions);
+              total_events, totals.tokens, totals.trades, totals.completions);
         
-        if args.trades_only && foreign_key_errors > 0 {
-            info!("   Foreign key errors: {} (run --tokens-only first)", foreign_key_errors);
+        if args.trades_only && totals.trades_without_token > 0 {
+            info!("   Foreign key errors: {} (run --tokens-only first)", totals.trades_without_token);
         }
         
         info!("   Speed: {:.2} TX/sec | Elapsed: {:?}", tx_per_sec, total_elapsed);
         
-        if let Some(max) = args.max_txs {
-            if total_processed >= max {
-                break;
-            }
+        if let Some(max) = args.max_txs
+            && total_processed >= max
+        {
+            break;
         }
         
         before_sig = Some(Signature::from_str(&sigs.last().unwrap().signature)?);
@@ -320,6 +306,10 @@ async fn main() -> Result<()> {
         }
     }
     
+    // Events a failed flush kept buffered get one last try.
+    flush(&mut loader, &mut totals, coingecko_api_key.as_deref()).await;
+    totals.dropped += loader.buffered() as u64;
+    
     let total_time = start_time.elapsed();
     let avg_speed = total_processed as f64 / total_time.as_secs_f64();
     
@@ -330,12 +320,15 @@ async fn main() -> Result<()> {
     info!("   Transactions processed: {}", total_processed);
     info!("   Transactions skipped: {}", skipped_txs);
     info!("   Total events: {}", total_events);
-    info!("   ├─ Tokens created: {}", total_tokens);
-    info!("   ├─ Trades: {}", total_trades);
-    info!("   └─ Completions: {}", total_completions);
+    info!("   ├─ Tokens created: {}", totals.tokens);
+    info!("   ├─ Trades: {}", totals.trades);
+    info!("   ├─ Completions: {}", totals.completions);
+    info!("   ├─ Already processed: {}", totals.already_processed);
+    info!("   ├─ Dead-lettered: {}", totals.dead_lettered);
+    info!("   └─ Dropped: {}", totals.dropped);
     
-    if args.trades_only && foreign_key_errors > 0 {
-        warn!("   ⚠️  Foreign key errors: {} trades skipped (tokens not in DB)", foreign_key_errors);
+    if args.trades_only && totals.trades_without_token > 0 {
+        warn!("   ⚠️  Foreign key errors: {} trades skipped (tokens not in DB)", totals.trades_without_token);
         warn!("   Run PHASE 1 (--tokens-only) first to fix this!");
     }
     
@@ -343,101 +336,245 @@ async fn main() -> Result<()> {
     info!("   Average speed: {:.2} TX/sec", avg_speed);
     info!("════════════════════════════════════");
     
+    heartbeat.abort();
+    let counts = SessionCounts {
+        transactions: total_processed as u64,
+        tokens_created: totals.tokens,
+        trades: totals.trades,
+        tokens_graduated: totals.completions,
+    };
+    database::update_session(&pool, session, args.end_slot, &counts, true).await?;
+    
+    if totals.dropped > 0 {
+        anyhow::bail!("{} events could not be stored or dead-lettered; rerun the range to fill them in", totals.dropped);
+    }
     Ok(())
 }
 
-async fn save_create_event(
-    pool: &sqlx::PgPool,
-    event: &pumpfun_indexer::database::model::CreateEvent,
-) -> Result<()> {
-    sqlx::query(
-        "INSERT INTO tokens (
-            mint_address, name, symbol, uri, creator_wallet, bonding_curve_address,
-            virtual_sol_reserves, virtual_token_reserves, real_token_reserves,
-            token_total_supply, complete, created_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-        ON CONFLICT (mint_address) DO UPDATE SET
-            name = EXCLUDED.name,
-            symbol = EXCLUDED.symbol,
-            uri = EXCLUDED.uri,
-            creator_wallet = EXCLUDED.creator_wallet,
-            bonding_curve_address = EXCLUDED.bonding_curve_address,
-            virtual_sol_reserves = EXCLUDED.virtual_sol_reserves,
-            virtual_token_reserves = EXCLUDED.virtual_token_reserves,
-            real_token_reserves = EXCLUDED.real_token_reserves,
-            token_total_supply = EXCLUDED.token_total_supply,
-            updated_at = NOW()
-        "
-    )
-    .bind(&event.mint)
-    .bind(&event.name)
-    .bind(&event.symbol)
-    .bind(&event.uri)
-    .bind(&event.creator)
-    .bind(&event.bonding_curve)
-    .bind(event.virtual_sol_reserves as i64)
-    .bind(event.virtual_token_reserves as i64)
-    .bind(event.real_token_reserves as i64)
-    .bind(event.token_total_supply as i64)
-    .bind(false)
-    .bind(chrono::Utc.timestamp_opt(event.timestamp, 0).unwrap())
-    .execute(pool)
-    .await?;
+/// Buffers the events for the next flush, or with `--flush-size 0` stores
+/// each one right away through the live indexer's write path.
+async fn store_events(
+    args: &Args,
+    signature: &str,
+    slot: u64,
+    loader: &mut BulkLoader,
+    totals: &mut BulkReport,
+    coingecko_api_key: Option<&str>,
+    events: Vec<PumpEvent>,
+) {
+    for (index, event) in events.into_iter().enumerate() {
+        let wanted = match &event {
+            PumpEvent::Create(_) => !args.trades_only,
+            PumpEvent::Trade(_) | PumpEvent::Complete(_) => !args.tokens_only,
+        };
+        if !wanted {
+            continue;
+        }
+        
+        if args.flush_size > 0 {
+            loader.push(signature, index, slot, event);
+            continue;
+        }
+        
+        let mut sol_price_usd = None;
+        if let PumpEvent::Trade(trade) = &event {
+            let pool = loader.pool().clone();
+            cover_sol_prices(&pool, coingecko_api_key, loader.sol_prices(), trade.timestamp, trade.timestamp).await;
+            sol_price_usd = loader.sol_prices().price_at(trade.timestamp);
+        }
+        
+        // Errors are logged by `persist_event`.
+        match ingest::persist_event(loader.pool(), signature, index, &event, DEFAULT_TOKEN_DECIMALS, sol_price_usd).await {
+            Ok(true) => match event {
+                PumpEvent::Create(_) => totals.tokens += 1,
+                PumpEvent::Trade(_) => totals.trades += 1,
+                PumpEvent::Complete(_) => totals.completions += 1,
+            },
+            Ok(false) => totals.already_processed += 1,
+            Err(_) => totals.dropped += 1,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Walked {
+    processed: usize,
+    skipped: usize,
+    events: usize,
+}
+
+/// Block mode: lists confirmed blocks `batch_size` slots at a time with
+/// `getBlocksWithLimit`, fetches each with `getBlock` and parses the
+/// transactions that invoked an indexed program. One call per block instead
+/// of one per transaction.
+async fn backfill_blocks(
+    client: &RpcClient,
+    args: &Args,
+    start_slot: u64,
+    loader: &mut BulkLoader,
+    totals: &mut BulkReport,
+    coingecko_api_key: Option<&str>,
+) -> Result<Walked> {
+    let commitment = CommitmentConfig::confirmed();
+    let end_slot = match args.end_slot {
+        Some(slot) => slot,
+        None => client.get_slot_with_commitment(commitment)?,
+    };
+    let block_config = RpcBlockConfig {
+        encoding: Some(UiTransactionEncoding::JsonParsed),
+        transaction_details: Some(TransactionDetails::Full),
+        rewards: Some(false),
+        commitment: Some(commitment),
+        max_supported_transaction_version: Some(0),
+    };
     
-    Ok(())
+    info!("🧱 Walking blocks {}..={}", start_slot, end_slot);
+    
+    let mut walked = Walked::default();
+    let mut slot = start_slot;
+    
+    while slot <= end_slot {
+        let limit = args.batch_size.min((end_slot - slot + 1) as usize);
+        let slots = match client.get_blocks_with_limit_and_commitment(slot, limit, commitment) {
+            Ok(slots) => slots,
+            Err(e) => {
+                error!("❌ Failed to list blocks from slot {}: {}", slot, e);
+                warn!("   Retrying in 5 seconds...");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+        let Some(&last_slot) = slots.last() else {
+            info!("✅ No more blocks before slot {}", end_slot);
+            break;
+        };
+        
+        for block_slot in slots.into_iter().filter(|s| *s <= end_slot) {
+            let block = match client.get_block_with_config(block_slot, block_config) {
+                Ok(block) => block,
+                Err(e) => {
+                    warn!("⚠️  Failed to fetch block {}: {}", block_slot, e);
+                    continue;
+                }
+            };
+            
+            for tx in block.transactions.unwrap_or_default() {
+                if !parser::invokes_indexed_program(&tx) {
+                    continue;
+                }
+                if tx.meta.as_ref().is_some_and(|meta| meta.err.is_some()) {
+                    walked.skipped += 1;
+                    continue;
+                }
+                let Some(signature) = parser::transaction_signature(&tx).map(str::to_string) else {
+                    continue;
+                };
+                
+                match parser::parse_transaction(&signature, &tx) {
+                    Ok(events) => {
+                        walked.events += events.len();
+                        store_events(args, &signature, block_slot, loader, totals, coingecko_api_key, events).await;
+                    }
+                    Err(e) => warn!("⚠️  Failed to parse TX {}: {}", &signature[..8], e),
+                }
+                walked.processed += 1;
+                
+                if loader.is_full() {
+                    flush(loader, totals, coingecko_api_key).await;
+                }
+                
+                if let Some(max) = args.max_txs
+                    && walked.processed >= max
+                {
+                    info!("✅ Reached max transactions limit");
+                    flush(loader, totals, coingecko_api_key).await;
+                    return Ok(walked);
+                }
+            }
+        }
+        
+        flush(loader, totals, coingecko_api_key).await;
+        info!("📊 Slots {}..={}: {} TXs, {} events ({} tokens, {} trades, {} completions)",
+              slot, last_slot.min(end_slot), walked.processed, walked.events,
+              totals.tokens, totals.trades, totals.completions);
+        
+        slot = last_slot + 1;
+        
+        if args.delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(args.delay_ms)).await;
+        }
+    }
+    
+    Ok(walked)
 }
 
-async fn save_trade_event(
-    pool: &sqlx::PgPool,
-    event: &pumpfun_indexer::database::model::TradeEventData,
-) -> Result<()> {
-    sqlx::query(
-        "INSERT INTO trades (
-            signature, token_mint, user_wallet, is_buy,
-            sol_amount, token_amount, timestamp,
-            virtual_sol_reserves, virtual_token_reserves,
-            real_sol_reserves, real_token_reserves,
-            fee_recipient, fee_basis_points, fee,
-            creator, creator_fee_basis_points, creator_fee,
-            track_volume, total_unclaimed_tokens, total_claimed_tokens,
-            current_sol_volume, last_update_timestamp, ix_name
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)
-        ON CONFLICT (signature) DO NOTHING"
-    )
-    .bind(&event.signature)
-    .bind(&event.mint)
-    .bind(&event.user)
-    .bind(event.is_buy)
-    .bind(event.sol_amount as i64)
-    .bind(event.token_amount as i64)
-    .bind(chrono::Utc.timestamp_opt(event.timestamp, 0).unwrap())
-    .bind(event.virtual_sol_reserves as i64)
-    .bind(event.virtual_token_reserves as i64)
-    .bind(event.real_sol_reserves as i64)
-    .bind(event.real_token_reserves as i64)
-    .bind(&event.fee_recipient)
-    .bind(event.fee_basis_points as i64)
-    .bind(event.fee as i64)
-    .bind(&event.creator)
-    .bind(event.creator_fee_basis_points as i64)
-    .bind(event.creator_fee as i64)
-    .bind(event.track_volume)
-    .bind(event.total_unclaimed_tokens as i64)
-    .bind(event.total_claimed_tokens as i64)
-    .bind(event.current_sol_volume as i64)
-    .bind(chrono::Utc.timestamp_opt(event.last_update_timestamp, 0).unwrap())
-    .bind(&event.ix_name)
-    .execute(pool)
-    .await?;
+/// Makes sure the loader has SOL prices around the buffered trades: from
+/// `sol_price_history` if they were fetched before, otherwise from CoinGecko,
+/// saving them for next time.
+async fn cover_sol_prices(
+    pool: &PgPool,
+    coingecko_api_key: Option<&str>,
+    sol_prices: &mut SolPriceHistory,
+    from: i64,
+    to: i64,
+) {
+    if sol_prices.covers(from, to) {
+        return;
+    }
     
-    Ok(())
+    // Pad by a day so trades at either edge still find a price.
+    let (padded_from, padded_to) = (from - 86_400, (to + 86_400).min(Utc::now().timestamp()));
+    let (from_at, to_at) = (unix_time(padded_from), unix_time(padded_to));
+    match database::load_sol_price_history(pool, from_at, to_at).await {
+        Ok(points) => sol_prices.extend(points),
+        Err(e) => warn!("⚠️  Failed to load SOL price history: {}", e),
+    }
+    if sol_prices.covers(from, to) {
+        return;
+    }
+    
+    info!("💰 Fetching SOL prices {} → {} from CoinGecko", from_at, to_at);
+    match fetch_sol_price_history(coingecko_api_key, padded_from, padded_to).await {
+        Ok(points) => {
+            if let Err(e) = database::save_sol_price_history(pool, &points).await {
+                warn!("⚠️  Failed to save SOL price history: {}", e);
+            }
+            sol_prices.extend(points);
+        }
+        Err(e) => warn!("⚠️  Failed to fetch SOL price history, USD values left empty: {}", e),
+    }
 }
 
-async fn mark_complete(pool: &sqlx::PgPool, mint: &str) -> Result<()> {
-    sqlx::query("UPDATE tokens SET complete = true WHERE mint_address = $1")
-        .bind(mint)
-        .execute(pool)
-        .await?;
+fn unix_time(seconds: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(seconds, 0).single().unwrap_or_default()
+}
+
+/// Writes the buffered events. A flush that fails keeps them buffered for
+/// the next one; whatever is still buffered at the end counts as dropped.
+async fn flush(loader: &mut BulkLoader, totals: &mut BulkReport, coingecko_api_key: Option<&str>) {
+    if let Some((from, to)) = loader.trade_time_range() {
+        let pool = loader.pool().clone();
+        cover_sol_prices(&pool, coingecko_api_key, loader.sol_prices(), from, to).await;
+    }
     
-    Ok(())
-}
\ No newline at end of file
+    let buffered = loader.buffered();
+    match loader.flush().await {
+        Ok(report) => {
+            totals.tokens += report.tokens;
+            totals.trades += report.trades;
+            totals.trades_without_token += report.trades_without_token;
+            totals.completions += report.completions;
+            totals.already_processed += report.already_processed;
+            totals.dead_lettered += report.dead_lettered;
+            totals.dropped += report.dropped;
+            if buffered > 0 {
+                info!("      💾 Flushed {} events ({} tokens, {} trades, {} completions)",
+                      buffered, report.tokens, report.trades, report.completions);
+            }
+            if report.trades_without_token > 0 {
+                warn!("⚠️  {} trades skipped (token not found in DB)", report.trades_without_token);
+            }
+        }
+        Err(e) => error!("❌ Failed to flush {} events, keeping them for the next flush: {}", buffered, e),
+    }
+}
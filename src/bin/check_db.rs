@@ -12,7 +12,16 @@ async fn main() -> Result<()> {
         .await?;
     
     println!("🔍 Checking database contents...\n");
-    
+
+    let drift = pumpfun_indexer::database::schema::check(&pool).await?;
+    if drift.is_empty() {
+        println!("✅ Schema matches this build");
+    } else {
+        for problem in &drift {
+            println!("❌ Schema drift: {}", problem);
+        }
+    }
+
     let tx_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM transactions")
         .fetch_one(&pool)
         .await?;
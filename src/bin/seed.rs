@@ -0,0 +1,124 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use clap::Parser;
+use sqlx::postgres::PgPoolOptions;
+use tracing::{info, warn};
+use pumpfun_indexer::background::{creator_earnings, funnel, holder_snapshots, market_stats, token_summaries};
+use pumpfun_indexer::database::{self, bulk::{BulkLoader, BulkReport}};
+use pumpfun_indexer::helius::mock::MockMarket;
+use pumpfun_indexer::helius::parser;
+
+#[derive(Parser, Debug)]
+#[command(name = "seed")]
+#[command(about = "Fill a demo database with synthetic pump.fun tokens and trades", long_about = None)]
+struct Args {
+    /// Roughly how many tokens to launch
+    #[arg(long, default_value = "300")]
+    tokens: usize,
+
+    /// Average trades per token; a few tokens get most of them
+    #[arg(long, default_value = "150")]
+    trades_per_token: usize,
+
+    /// Days of history, ending now
+    #[arg(long, default_value = "7")]
+    days: i64,
+
+    /// The same seed generates the same market
+    #[arg(long, default_value = "1")]
+    seed: u64,
+
+    #[arg(long, default_value = "5000")]
+    flush_size: usize,
+
+    /// Seed even though the database already has tokens
+    #[arg(long)]
+    force: bool,
+}
+
+/// A made-up SOL/USD price drifting around $150, so trades get USD values.
+fn sol_price_at(timestamp: i64) -> f64 {
+    let hours = timestamp as f64 / 3_600.0;
+    150.0 + 15.0 * (hours / 19.0).sin() + 4.0 * (hours / 3.7).sin()
+}
+
+fn unix_time(seconds: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(seconds, 0).single().unwrap_or_default()
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_target(false)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info,pumpfun_indexer::helius::parser=warn"))
+        )
+        .init();
+
+    let args = Args::parse();
+    if args.tokens == 0 || args.trades_per_token == 0 || args.days <= 0 {
+        bail!("--tokens, --trades-per-token and --days must be positive");
+    }
+
+    dotenv::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be set")?;
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .context("Failed to connect to database")?;
+
+    let (existing,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tokens").fetch_one(&pool).await?;
+    if existing > 0 && !args.force {
+        bail!("the database already has {} tokens; seed an empty one, or pass --force to add to it", existing);
+    }
+
+    let now = Utc::now();
+    let start = now - Duration::days(args.days);
+    let transactions = args.tokens * args.trades_per_token;
+    let spacing = (now - start).num_milliseconds() as f64 / transactions as f64;
+    info!("🌱 Seeding ~{} tokens over {} days ({} transactions, seed {})", args.tokens, args.days, transactions, args.seed);
+
+    let sol_prices: Vec<_> = (start.timestamp()..=now.timestamp())
+        .step_by(3_600)
+        .map(|timestamp| (unix_time(timestamp), sol_price_at(timestamp)))
+        .collect();
+    database::save_sol_price_history(&pool, &sol_prices).await?;
+
+    let mut market = MockMarket::new(args.seed).with_create_chance(1.0 / args.trades_per_token as f64);
+    let mut loader = BulkLoader::new(pool.clone(), args.flush_size);
+    loader.sol_prices().extend(sol_prices);
+    let mut totals = BulkReport::default();
+
+    for i in 0..transactions {
+        let timestamp = start.timestamp() + (i as f64 * spacing / 1_000.0) as i64;
+        let (signature, transaction) = market.next(timestamp);
+        for (index, event) in parser::parse_transaction(&signature, &transaction)?.into_iter().enumerate() {
+            loader.push(&signature, index, i as u64, event);
+        }
+
+        if loader.is_full() || i + 1 == transactions {
+            let report = loader.flush().await?;
+            totals.tokens += report.tokens;
+            totals.trades += report.trades;
+            totals.completions += report.completions;
+            info!("   💾 {}/{} transactions ({} tokens, {} trades so far)", i + 1, transactions, totals.tokens, totals.trades);
+        }
+    }
+
+    // The rollups the indexer's background tasks would have built up.
+    info!("📊 Building aggregates...");
+    for granularity in market_stats::Granularity::ALL {
+        market_stats::aggregate(&pool, granularity, start).await?;
+    }
+    creator_earnings::aggregate_creator_earnings(&pool, start).await?;
+    funnel::aggregate(&pool, start).await?;
+    holder_snapshots::snapshot(&pool, start, now).await?;
+    if let Err(e) = token_summaries::refresh(&pool).await {
+        warn!("⚠️  Failed to refresh token summaries, the indexer will on its next tick: {}", e);
+    }
+
+    info!("✅ Seeded {} tokens, {} trades and {} graduations", totals.tokens, totals.trades, totals.completions);
+    Ok(())
+}
@@ -0,0 +1,228 @@
+//! Typed async client for the indexer's REST and WebSocket API (`client`
+//! feature). Responses decode into the `crate::dto` types the server builds
+//! them from, so a field change on one side breaks the build on the other.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), pumpfun_indexer::client::ClientError> {
+//! use futures::StreamExt;
+//! use pumpfun_indexer::client::{IndexerClient, TokenQuery};
+//!
+//! let client = IndexerClient::new("http://localhost:8080");
+//! let newest = client.tokens(&TokenQuery::default()).await?;
+//! let mut trades = client.subscribe_trades(None, Default::default()).await?;
+//! while let Some(trade) = trades.next().await {
+//!     println!("{} {}", newest.body.tokens.len(), trade?.signature);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use futures::{future, SinkExt, Stream, StreamExt};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, http::HeaderValue, Message};
+use tracing::warn;
+use crate::api::error::ErrorResponse;
+use crate::dto::{TokenDetail, TokenListResponse, TradeListResponse, TradeMessage, WithMeta};
+use crate::api::routes::ws_protocol::{Channel, ClientMessage, Subscription, TradeFilter, WsMessage};
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+    /// A non-2xx response, with the server's error code and message.
+    #[error("{status} {code}: {message}")]
+    Api { status: u16, code: String, message: String },
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tungstenite::Error),
+    /// An `error` frame on the WebSocket, e.g. a rejected subscription.
+    #[error("Server error: {0}")]
+    Server(String),
+    #[error("Decode error: {0}")]
+    Decode(#[from] serde_json::Error),
+}
+
+/// Query for [`IndexerClient::tokens`]; unset fields use the server's defaults.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TokenQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+    /// `market_cap`, `created_at`, `last_trade`, `volume`, `trades_per_minute`
+    /// or `buy_pressure`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// e.g. `5m` or `1h`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_within: Option<String>,
+    /// Only tokens launched by this wallet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+}
+
+/// Query for [`IndexerClient::trades`]; unset fields match every trade.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TradeQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_buy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_sol: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wallet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexerClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl IndexerClient {
+    /// `base_url` is where the API is served, e.g. `http://localhost:8080`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+        }
+    }
+
+    /// Sent as `x-api-key`; keys in the server's `api_keys` get their own
+    /// rate limits.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// `GET /api/tokens`
+    pub async fn tokens(&self, query: &TokenQuery) -> Result<WithMeta<TokenListResponse>, ClientError> {
+        self.get("/api/tokens", query).await
+    }
+
+    /// `GET /api/tokens/{mint}`
+    pub async fn token(&self, mint: &str) -> Result<WithMeta<TokenDetail>, ClientError> {
+        self.get(&format!("/api/tokens/{}", mint), &()).await
+    }
+
+    /// `GET /api/tokens/{mint}/trades`
+    pub async fn trades(&self, mint: &str, query: &TradeQuery) -> Result<WithMeta<TradeListResponse>, ClientError> {
+        self.get(&format!("/api/tokens/{}/trades", mint), query).await
+    }
+
+    /// Subscribes to trades on `/ws`, of one token or of all of them, and
+    /// yields them as they arrive. The stream ends when the server closes
+    /// the connection; resubscribing after a reconnect is up to the caller.
+    pub async fn subscribe_trades(
+        &self,
+        mint: Option<&str>,
+        filter: TradeFilter,
+    ) -> Result<impl Stream<Item = Result<TradeMessage, ClientError>> + Unpin + use<>, ClientError> {
+        let mut request = self.ws_url("/ws").into_client_request()?;
+        if let Some(key) = &self.api_key
+            && let Ok(value) = HeaderValue::from_str(key)
+        {
+            request.headers_mut().insert(API_KEY_HEADER, value);
+        }
+        let (mut socket, _) = tokio_tungstenite::connect_async(request).await?;
+
+        let subscribe = ClientMessage::Subscribe(Subscription {
+            channel: Channel::Trades,
+            mint: mint.map(String::from),
+            filter,
+            since_seq: None,
+        });
+        socket.send(Message::text(serde_json::to_string(&subscribe)?)).await?;
+
+        Ok(socket.filter_map(|frame| {
+            future::ready(match frame {
+                Ok(Message::Text(text)) => decode_trade_frame(&text).transpose(),
+                Ok(_) => None,
+                Err(e) => Some(Err(e.into())),
+            })
+        }))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str, query: &impl Serialize) -> Result<T, ClientError> {
+        let mut request = self.http.get(format!("{}{}", self.base_url, path)).query(query);
+        if let Some(key) = &self.api_key {
+            request = request.header(API_KEY_HEADER, key);
+        }
+        let response = request.send().await?;
+
+        let status = response.status();
+        let body = response.bytes().await?;
+        if !status.is_success() {
+            let (code, message) = match serde_json::from_slice::<ErrorResponse>(&body) {
+                Ok(error) => (error.error.code, error.error.message),
+                Err(_) => (String::new(), String::from_utf8_lossy(&body).into_owned()),
+            };
+            return Err(ClientError::Api { status: status.as_u16(), code, message });
+        }
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    fn ws_url(&self, path: &str) -> String {
+        let base = match self.base_url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{}", rest),
+            Some((_, rest)) => format!("ws://{}", rest),
+            None => format!("ws://{}", self.base_url),
+        };
+        format!("{}{}", base, path)
+    }
+}
+
+/// The trade in a server frame, if it holds one. Acknowledgements and
+/// heartbeats are skipped; `error` frames become errors.
+fn decode_trade_frame(text: &str) -> Result<Option<TradeMessage>, ClientError> {
+    match serde_json::from_str(text)? {
+        WsMessage::Trade { data, .. } => Ok(Some(data)),
+        WsMessage::Error { message } => Err(ClientError::Server(message)),
+        WsMessage::ReplayGap { since_seq, first_seq } => {
+            warn!("Trades {}..{} were no longer buffered and were skipped", since_seq + 1, first_seq);
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_server_frames() {
+        let trade = TradeMessage {
+            signature: "sig".to_string(),
+            mint: "mint".to_string(),
+            is_buy: true,
+            sol_amount: 1_000_000_000,
+            token_amount: 35_000_000_000_000,
+            user_wallet: "alice".to_string(),
+            timestamp: chrono::Utc::now(),
+            market_cap_usd: 5_000.0,
+            price_sol: 0.000_000_03,
+            seq: Some(7),
+        };
+        let encode = |message: WsMessage| serde_json::to_string(&message.versioned()).unwrap();
+        let frame = encode(WsMessage::Trade { channel: Channel::Trades, mint: None, data: trade });
+        let heartbeat = encode(WsMessage::Heartbeat { timestamp: 0 });
+        let error = encode(WsMessage::error("mint must be a base58 pubkey"));
+
+        assert_eq!(decode_trade_frame(&frame).unwrap().unwrap().seq, Some(7));
+        assert!(decode_trade_frame(&heartbeat).unwrap().is_none());
+        assert!(matches!(decode_trade_frame(&error), Err(ClientError::Server(_))));
+        assert_eq!(IndexerClient::new("https://api.example.com/").ws_url("/ws"), "wss://api.example.com/ws");
+    }
+}
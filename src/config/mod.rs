@@ -1,36 +1,724 @@
-use anyhow::{Result, Context};
+mod runtime;
+mod settings;
+
+pub use runtime::{reload_on_sighup, RuntimeSettings, SharedSettings};
+pub use settings::Settings;
+
+use anyhow::Result;
+use axum::http::{HeaderName, HeaderValue, Method};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::env;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Command-line options shared by the binaries that load a [`Config`].
+#[derive(Debug, Default, Clone, Parser)]
+pub struct ConfigArgs {
+    /// TOML or YAML config file (also `CONFIG_FILE`). Environment variables
+    /// override it.
+    #[arg(long, short)]
+    pub config: Option<PathBuf>,
+
+    /// Override one setting by its file key, e.g. `--set api.port=9090`.
+    /// Repeatable; wins over the environment and the file.
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+
+    /// Parse live traffic and report what would be stored, without writing
+    /// to the database or publishing events (also `DRY_RUN=true`).
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Start from a state snapshot taken with `GET /api/admin/snapshot`
+    /// instead of an empty token map; tokens that traded since it was taken
+    /// are reloaded from the database.
+    #[arg(long, value_name = "FILE")]
+    pub restore: Option<PathBuf>,
+
+    /// Where transactions come from: `helius` or `mock` (also
+    /// `INGEST_SOURCE`). `mock` synthesizes pump.fun activity so the stack
+    /// runs without a Helius key.
+    #[arg(long, value_name = "SOURCE")]
+    pub source: Option<String>,
+}
 
 #[derive(Debug, Clone)]
 pub struct Config {
-    pub database_url: String,
+    /// Observe-only mode: see [`ConfigArgs::dry_run`].
+    pub dry_run: bool,
+    pub rpc: RpcConfig,
+    pub source: IngestSource,
+    pub database: DatabaseConfig,
+    pub redis: RedisConfig,
+    pub api: ApiConfig,
+    pub analytics: AnalyticsConfig,
+    /// Set when `KAFKA_BROKERS` is configured.
+    pub kafka: Option<KafkaConfig>,
+    /// Set when `NATS_URL` is configured.
+    pub nats: Option<NatsConfig>,
+    /// Set when `WEBHOOK_URL` is configured.
+    pub webhook: Option<WebhookConfig>,
+    /// Set when a Discord webhook or Telegram chat is configured.
+    pub notifier: Option<NotifierConfig>,
+    pub sinks: Vec<SinkKind>,
+    /// How live events reach the API's WebSocket/SSE clients.
+    pub transport: EventTransport,
+    /// Set when `ARCHIVE_URL` is configured.
+    pub archive: Option<ArchiveConfig>,
+    /// Most transactions per program replayed at startup to cover the time
+    /// the indexer was down; 0 disables the catch-up.
+    pub catch_up_limit: usize,
+    /// Log directives (`logging.filter`, or `RUST_LOG`); see
+    /// [`crate::logging::DEFAULT_FILTER`].
+    pub log_filter: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcConfig {
+    /// Empty with the mock source, which doesn't need one.
     pub helius_api_key: String,
-    pub redis_url: String,
-    pub api_port: u16,
-    pub coingecko_api_key: Option<String>, // 🔥 NEW: Optional API key
+}
+
+/// Selected by `--source` or `INGEST_SOURCE`.
+#[derive(Debug, Clone)]
+pub enum IngestSource {
+    /// Helius logs subscription (the default).
+    Helius,
+    /// Synthetic transactions for local development.
+    Mock(MockConfig),
+}
+
+#[derive(Debug, Clone)]
+pub struct MockConfig {
+    pub transactions_per_second: f64,
+    /// Directory of transaction JSON files replayed once before
+    /// synthesizing, e.g. `benches/fixtures`.
+    pub replay_dir: Option<PathBuf>,
+}
+
+impl IngestSource {
+    pub fn from_settings(s: &mut Settings, flag: Option<&str>) -> Self {
+        let configured = s.raw("ingest.source", "INGEST_SOURCE");
+        let mock = MockConfig {
+            transactions_per_second: s.get("ingest.mock.transactions_per_second", "MOCK_TRANSACTIONS_PER_SECOND", 5.0),
+            replay_dir: s.optional("ingest.mock.replay_dir", "MOCK_REPLAY_DIR"),
+        };
+        if mock.transactions_per_second <= 0.0 {
+            s.error(format!(
+                "ingest.mock.transactions_per_second (MOCK_TRANSACTIONS_PER_SECOND) must be positive, got {}",
+                mock.transactions_per_second
+            ));
+        }
+
+        match flag.or(configured.as_deref()) {
+            Some("helius") | None => IngestSource::Helius,
+            Some("mock") => IngestSource::Mock(mock),
+            Some(other) => {
+                s.error(format!("ingest.source (--source, INGEST_SOURCE) must be `helius` or `mock`, got `{}`", other));
+                IngestSource::Helius
+            }
+        }
+    }
+
+    pub fn is_mock(&self) -> bool {
+        matches!(self, IngestSource::Mock(_))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    pub url: String,
+    /// Database calls at least this slow are logged; 0 disables the log.
+    pub slow_query_ms: u64,
+    /// Fraction of raw transactions written to `transactions`, from 0 (none)
+    /// to 1 (all). Events are processed either way.
+    pub transaction_sample_rate: f64,
+    /// Where events are queued on disk while the database is unreachable.
+    pub spill_dir: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RedisConfig {
+    pub url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiConfig {
+    /// Address the HTTP server listens on; `0.0.0.0` unless `API_BIND` is set.
+    pub bind: IpAddr,
+    pub port: u16,
+    /// gRPC API port; the server only starts when `GRPC_PORT` is set.
+    pub grpc_port: Option<u16>,
+    pub rate_limit: RateLimitConfig,
+    pub ws: WsLimits,
+    /// Bearer token for `/api/admin`; the admin routes are disabled without it.
+    pub admin_token: Option<String>,
+    pub cors: CorsConfig,
+    /// Serve HTTPS directly when both `TLS_CERT_PATH` and `TLS_KEY_PATH` are set.
+    pub tls: Option<TlsConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnalyticsConfig {
+    /// CoinGecko Pro key for SOL/USD; the free tier is used without it.
+    pub coingecko_api_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Pretty,
+    /// One JSON object per line, for log shippers.
+    Json,
+}
+
+impl LogFormat {
+    /// Read separately from `Config` because logging starts before the rest
+    /// of the configuration is validated.
+    pub fn from_env() -> Self {
+        dotenv::dotenv().ok();
+
+        match env::var("LOG_FORMAT").as_deref() {
+            Ok("json") => LogFormat::Json,
+            _ => LogFormat::Pretty,
+        }
+    }
+}
+
+/// Sentry error reporting, enabled by `SENTRY_DSN`.
+#[derive(Debug, Clone)]
+pub struct SentryConfig {
+    pub dsn: String,
+    pub environment: String,
+}
+
+impl SentryConfig {
+    /// Read separately from `Config` so panics during startup are reported too.
+    pub fn from_env() -> Option<Self> {
+        dotenv::dotenv().ok();
+
+        let dsn = env::var("SENTRY_DSN").ok().filter(|dsn| !dsn.is_empty())?;
+        Some(SentryConfig {
+            dsn,
+            environment: env::var("SENTRY_ENVIRONMENT").unwrap_or_else(|_| "production".to_string()),
+        })
+    }
+}
+
+/// Requests allowed per client per minute, by route class.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    /// Key clients by the last `X-Forwarded-For` entry, the one appended by
+    /// the proxy in front; only safe behind exactly one proxy.
+    pub trust_proxy: bool,
+    pub read_per_minute: u32,
+    pub heavy_per_minute: u32,
+    pub stream_per_minute: u32,
+    /// `x-api-key` values that get their own limits; any other key is
+    /// ignored and the client is limited by IP.
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+}
+
+impl RateLimitConfig {
+    pub fn from_settings(s: &mut Settings) -> Self {
+        RateLimitConfig {
+            enabled: s.get("api.rate_limit.enabled", "RATE_LIMIT_ENABLED", true),
+            trust_proxy: s.get("api.rate_limit.trust_proxy", "RATE_LIMIT_TRUST_PROXY", false),
+            read_per_minute: s.get("api.rate_limit.read_per_minute", "RATE_LIMIT_READ_PER_MIN", 300),
+            heavy_per_minute: s.get("api.rate_limit.heavy_per_minute", "RATE_LIMIT_HEAVY_PER_MIN", 30),
+            stream_per_minute: s.get("api.rate_limit.stream_per_minute", "RATE_LIMIT_STREAM_PER_MIN", 10),
+            api_keys: s.list("api.rate_limit.api_keys", "RATE_LIMIT_API_KEYS"),
+        }
+    }
+}
+
+/// Caps protecting the server from WebSocket clients.
+#[derive(Debug, Clone, Copy)]
+pub struct WsLimits {
+    pub max_connections: i64,
+    pub max_subscriptions_per_connection: usize,
+}
+
+impl WsLimits {
+    pub fn from_settings(s: &mut Settings) -> Self {
+        WsLimits {
+            max_connections: s.get("api.ws.max_connections", "WS_MAX_CONNECTIONS", 10000),
+            max_subscriptions_per_connection: s.get(
+                "api.ws.max_subscriptions_per_connection",
+                "WS_MAX_SUBSCRIPTIONS_PER_CONNECTION",
+                50,
+            ),
+        }
+    }
+}
+
+/// Cross-origin access to the API. `*` allows any origin, header or method;
+/// unset headers and methods allow only CORS-safelisted ones.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<HeaderValue>,
+    pub allowed_headers: Vec<HeaderName>,
+    pub allowed_methods: Vec<Method>,
+    pub any_origin: bool,
+    pub any_header: bool,
+    pub any_method: bool,
+}
+
+impl CorsConfig {
+    pub fn from_settings(s: &mut Settings) -> Self {
+        let mut origins = s.list("api.cors.allowed_origins", "CORS_ALLOWED_ORIGINS");
+        if origins.is_empty() {
+            origins.push("*".to_string());
+        }
+        let headers = s.list("api.cors.allowed_headers", "CORS_ALLOWED_HEADERS");
+        let methods = s.list("api.cors.allowed_methods", "CORS_ALLOWED_METHODS");
+
+        CorsConfig {
+            any_origin: origins.iter().any(|o| o == "*"),
+            any_header: headers.iter().any(|h| h == "*"),
+            any_method: methods.iter().any(|m| m == "*"),
+            allowed_origins: parse_cors_values(s, "api.cors.allowed_origins", &origins),
+            allowed_headers: parse_cors_values(s, "api.cors.allowed_headers", &headers),
+            allowed_methods: parse_cors_values(s, "api.cors.allowed_methods", &methods),
+        }
+    }
+}
+
+fn parse_cors_values<T: FromStr>(s: &mut Settings, key: &str, values: &[String]) -> Vec<T> {
+    values
+        .iter()
+        .filter(|value| *value != "*")
+        .filter_map(|value| match value.parse() {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                s.error(format!("{}: invalid value `{}`", key, value));
+                None
+            }
+        })
+        .collect()
+}
+
+/// PEM certificate chain and private key for serving HTTPS.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    pub fn from_settings(s: &mut Settings) -> Option<Self> {
+        let cert_path = s.raw("api.tls.cert_path", "TLS_CERT_PATH");
+        let key_path = s.raw("api.tls.key_path", "TLS_KEY_PATH");
+
+        match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => Some(TlsConfig {
+                cert_path: cert_path.into(),
+                key_path: key_path.into(),
+            }),
+            (None, None) => None,
+            _ => {
+                s.error("api.tls: TLS_CERT_PATH and TLS_KEY_PATH must be set together");
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KafkaConfig {
+    pub brokers: String,
+    pub client_id: String,
+    pub creates_topic: String,
+    pub trades_topic: String,
+    pub graduations_topic: String,
+}
+
+impl KafkaConfig {
+    pub fn from_settings(s: &mut Settings) -> Option<Self> {
+        let brokers = s.raw("kafka.brokers", "KAFKA_BROKERS");
+        let client_id = s.get("kafka.client_id", "KAFKA_CLIENT_ID", "pumpfun-indexer".to_string());
+        let creates_topic = s.get("kafka.creates_topic", "KAFKA_TOPIC_CREATES", "pump.creates".to_string());
+        let trades_topic = s.get("kafka.trades_topic", "KAFKA_TOPIC_TRADES", "pump.trades".to_string());
+        let graduations_topic = s.get("kafka.graduations_topic", "KAFKA_TOPIC_GRADUATIONS", "pump.graduations".to_string());
+
+        Some(KafkaConfig {
+            brokers: brokers?,
+            client_id,
+            creates_topic,
+            trades_topic,
+            graduations_topic,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NatsConfig {
+    pub url: String,
+    /// JetStream stream created (if missing) to hold the events.
+    pub stream: String,
+    pub subject_prefix: String,
+    pub max_age_hours: u64,
+}
+
+impl NatsConfig {
+    pub fn from_settings(s: &mut Settings) -> Option<Self> {
+        let url = s.raw("nats.url", "NATS_URL");
+        let stream = s.get("nats.stream", "NATS_STREAM", "PUMP".to_string());
+        let subject_prefix = s.get("nats.subject_prefix", "NATS_SUBJECT_PREFIX", "pump".to_string());
+        let max_age_hours = s.get("nats.max_age_hours", "NATS_MAX_AGE_HOURS", 168);
+
+        Some(NatsConfig {
+            url: url?,
+            stream,
+            subject_prefix,
+            max_age_hours,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamsConfig {
+    /// Approximate number of entries kept per stream.
+    pub maxlen: usize,
+    /// Prefix of the consumer groups the API reads through. Each replica
+    /// adds its host name, so every replica receives every event and
+    /// resumes its own group after a restart.
+    pub consumer_group: String,
+}
+
+/// Selected by `EVENT_TRANSPORT`.
+#[derive(Debug, Clone)]
+pub enum EventTransport {
+    /// Redis pub/sub (the default).
+    PubSub,
+    /// Redis Streams read through a consumer group.
+    Streams(StreamsConfig),
+    /// Postgres `LISTEN/NOTIFY`; Redis is not used at all.
+    Postgres,
+}
+
+impl EventTransport {
+    pub fn from_settings(s: &mut Settings) -> Self {
+        let transport = s.raw("events.transport", "EVENT_TRANSPORT");
+        let streams = StreamsConfig {
+            maxlen: s.get("events.stream_maxlen", "STREAM_MAXLEN", 100_000),
+            consumer_group: s.get("events.stream_consumer_group", "STREAM_CONSUMER_GROUP", "pump-api".to_string()),
+        };
+
+        match transport.as_deref() {
+            Some("pubsub") | None => EventTransport::PubSub,
+            Some("streams") => EventTransport::Streams(streams),
+            Some("postgres") => EventTransport::Postgres,
+            Some(other) => {
+                s.error(format!(
+                    "events.transport (EVENT_TRANSPORT) must be `pubsub`, `streams` or `postgres`, got `{}`",
+                    other
+                ));
+                EventTransport::PubSub
+            }
+        }
+    }
+
+    pub fn uses_redis(&self) -> bool {
+        !matches!(self, EventTransport::Postgres)
+    }
+
+    pub fn streams(&self) -> Option<&StreamsConfig> {
+        match self {
+            EventTransport::Streams(config) => Some(config),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveConfig {
+    /// `s3://bucket/prefix` or `gs://bucket/prefix`. Credentials come from
+    /// the usual AWS_* / GOOGLE_* environment variables.
+    pub url: String,
+    pub interval_minutes: u64,
+    /// Days of trades kept in Postgres once archived; older ones are
+    /// served from `cold_trade_candles` and the Parquet files. 0 keeps all.
+    pub hot_days: u32,
+}
+
+impl ArchiveConfig {
+    pub fn from_settings(s: &mut Settings) -> Option<Self> {
+        let url = s.raw("archive.url", "ARCHIVE_URL");
+        let interval_minutes = s.get("archive.interval_minutes", "ARCHIVE_INTERVAL_MINUTES", 60);
+        let hot_days = s.get("archive.hot_days", "ARCHIVE_HOT_DAYS", 0);
+
+        let url = url?;
+        if !url.starts_with("s3://") && !url.starts_with("gs://") {
+            s.error(format!("archive.url (ARCHIVE_URL) must start with s3:// or gs://, got `{}`", url));
+        }
+        if interval_minutes == 0 {
+            s.error("archive.interval_minutes (ARCHIVE_INTERVAL_MINUTES) must be positive, got 0");
+        }
+
+        Some(ArchiveConfig { url, interval_minutes, hot_days })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Sent as `Authorization: Bearer ...` when set.
+    pub bearer_token: Option<String>,
+}
+
+impl WebhookConfig {
+    pub fn from_settings(s: &mut Settings) -> Option<Self> {
+        let url = s.raw("webhook.url", "WEBHOOK_URL");
+        let bearer_token = s.raw("webhook.token", "WEBHOOK_TOKEN");
+
+        Some(WebhookConfig { url: url?, bearer_token })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NotifierConfig {
+    pub discord_webhooks: Vec<String>,
+    pub telegram: Option<TelegramConfig>,
+    pub rules: NotifyRules,
+}
+
+#[derive(Debug, Clone)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_ids: Vec<String>,
+}
+
+/// Which events produce an alert.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NotifyRules {
+    /// Creator wallets whose new tokens are announced, besides those on the
+    /// watchlist.
+    pub tracked_creators: HashSet<String>,
+    /// Buys of at least this many SOL are announced.
+    pub whale_buy_sol: Option<f64>,
+    pub graduations: bool,
+}
+
+impl NotifierConfig {
+    pub fn from_settings(s: &mut Settings) -> Option<Self> {
+        let discord_webhooks = s.list("notify.discord_webhooks", "NOTIFY_DISCORD_WEBHOOKS");
+        let bot_token = s.raw("notify.telegram_bot_token", "NOTIFY_TELEGRAM_BOT_TOKEN");
+        let chat_ids = s.list("notify.telegram_chat_ids", "NOTIFY_TELEGRAM_CHAT_IDS");
+        let whale_buy_sol = match s.raw("notify.whale_buy_sol", "NOTIFY_WHALE_BUY_SOL").as_deref() {
+            Some("" | "off") => None,
+            Some(_) => s.optional("notify.whale_buy_sol", "NOTIFY_WHALE_BUY_SOL"),
+            None => Some(25.0),
+        };
+        let rules = NotifyRules {
+            tracked_creators: s.list("notify.tracked_creators", "NOTIFY_TRACKED_CREATORS").into_iter().collect(),
+            whale_buy_sol,
+            graduations: s.get("notify.graduations", "NOTIFY_GRADUATIONS", true),
+        };
+
+        let telegram = bot_token.map(|bot_token| {
+            if chat_ids.is_empty() {
+                s.error("notify.telegram_bot_token is set but notify.telegram_chat_ids (NOTIFY_TELEGRAM_CHAT_IDS) is empty");
+            }
+            TelegramConfig { bot_token, chat_ids }
+        });
+
+        if discord_webhooks.is_empty() && telegram.is_none() {
+            return None;
+        }
+
+        Some(NotifierConfig { discord_webhooks, telegram, rules })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkKind {
+    Redis,
+    /// `pg_notify` on the indexer's database.
+    Postgres,
+    Kafka,
+    Nats,
+    Webhook,
+    Notifier,
+    /// Discards events; for running the indexer without any delivery.
+    Noop,
+}
+
+impl std::str::FromStr for SinkKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "redis" => Ok(SinkKind::Redis),
+            "postgres" | "pg_notify" => Ok(SinkKind::Postgres),
+            "kafka" => Ok(SinkKind::Kafka),
+            "nats" => Ok(SinkKind::Nats),
+            "webhook" => Ok(SinkKind::Webhook),
+            "notifier" => Ok(SinkKind::Notifier),
+            "noop" | "none" => Ok(SinkKind::Noop),
+            other => anyhow::bail!("Unknown event sink: {}", other),
+        }
+    }
+}
+
+impl SinkKind {
+    /// The setting that must be present for this sink to be enabled.
+    fn required_setting(self) -> Option<&'static str> {
+        match self {
+            SinkKind::Kafka => Some("KAFKA_BROKERS"),
+            SinkKind::Nats => Some("NATS_URL"),
+            SinkKind::Webhook => Some("WEBHOOK_URL"),
+            SinkKind::Notifier => Some("NOTIFY_DISCORD_WEBHOOKS or NOTIFY_TELEGRAM_BOT_TOKEN"),
+            SinkKind::Redis => Some("EVENT_TRANSPORT=pubsub or streams"),
+            SinkKind::Postgres | SinkKind::Noop => None,
+        }
+    }
+}
+
+/// Sinks named in `EVENT_SINKS` (comma-separated). When unset, every sink
+/// with its settings present is enabled, led by the live transport's.
+fn sinks_from_settings(s: &mut Settings, configured: &[SinkKind]) -> Vec<SinkKind> {
+    let Some(list) = s.raw("events.sinks", "EVENT_SINKS") else {
+        return configured.to_vec();
+    };
+    parse_sinks(&list, configured).unwrap_or_else(|e| {
+        s.error(format!("events.sinks: {}", e));
+        Vec::new()
+    })
+}
+
+fn parse_sinks(list: &str, configured: &[SinkKind]) -> Result<Vec<SinkKind>> {
+    let mut sinks = Vec::new();
+    for name in list.split(',').filter(|name| !name.trim().is_empty()) {
+        let sink: SinkKind = name.parse()?;
+        if let Some(setting) = sink.required_setting()
+            && !configured.contains(&sink)
+        {
+            anyhow::bail!("EVENT_SINKS includes {} but {} is not set", name.trim(), setting);
+        }
+        if !sinks.contains(&sink) {
+            sinks.push(sink);
+        }
+    }
+    Ok(sinks)
 }
 
 impl Config {
-    pub fn from_env() -> Result<Self> {
+    /// Loads the config file named by `--config` or `CONFIG_FILE` (if any),
+    /// applies environment variables and `--set` overrides on top, and
+    /// validates the result, listing every problem found.
+    pub fn load(args: &ConfigArgs) -> Result<Self> {
         dotenv::dotenv().ok();
 
+        let file = args.config.clone().or_else(|| env::var_os("CONFIG_FILE").map(PathBuf::from));
+        let mut s = Settings::load(file.as_deref(), &args.overrides)?;
+
+        let dry_run = args.dry_run || s.get("dry_run", "DRY_RUN", false);
+        let source = IngestSource::from_settings(&mut s, args.source.as_deref());
+        let rpc = RpcConfig {
+            helius_api_key: if source.is_mock() {
+                s.get("rpc.helius_api_key", "HELIUS_API_KEY", String::new())
+            } else {
+                s.required("rpc.helius_api_key", "HELIUS_API_KEY")
+            },
+        };
+        let database = DatabaseConfig {
+            url: s.required("database.url", "DATABASE_URL"),
+            slow_query_ms: s.get("database.slow_query_ms", "DB_SLOW_QUERY_MS", 250),
+            transaction_sample_rate: s.get("database.transaction_sample_rate", "DB_TRANSACTION_SAMPLE_RATE", 1.0),
+            spill_dir: s.get("database.spill_dir", "DB_SPILL_DIR", "spill".to_string()),
+        };
+        if !(0.0..=1.0).contains(&database.transaction_sample_rate) {
+            s.error(format!(
+                "database.transaction_sample_rate (DB_TRANSACTION_SAMPLE_RATE) must be between 0 and 1, got {}",
+                database.transaction_sample_rate
+            ));
+        }
+        let redis = RedisConfig {
+            url: s.get("redis.url", "REDIS_URL", "redis://localhost:6379".to_string()),
+        };
+        let api = ApiConfig {
+            bind: s.get("api.bind", "API_BIND", IpAddr::from([0, 0, 0, 0])),
+            port: s.get("api.port", "API_PORT", 8080),
+            grpc_port: s.optional("api.grpc_port", "GRPC_PORT"),
+            rate_limit: RateLimitConfig::from_settings(&mut s),
+            ws: WsLimits::from_settings(&mut s),
+            admin_token: s.raw("api.admin_token", "ADMIN_TOKEN").filter(|token| !token.is_empty()),
+            cors: CorsConfig::from_settings(&mut s),
+            tls: TlsConfig::from_settings(&mut s),
+        };
+        let analytics = AnalyticsConfig {
+            coingecko_api_key: s.raw("analytics.coingecko_api_key", "COINGECKO_API_KEY"),
+        };
+
+        let kafka = KafkaConfig::from_settings(&mut s);
+        let nats = NatsConfig::from_settings(&mut s);
+        let webhook = WebhookConfig::from_settings(&mut s);
+        let notifier = NotifierConfig::from_settings(&mut s);
+        let transport = EventTransport::from_settings(&mut s);
+        let archive = ArchiveConfig::from_settings(&mut s);
+        let catch_up_limit = s.get("catch_up.limit", "CATCH_UP_LIMIT", 10_000);
+        let log_filter = s.get("logging.filter", "RUST_LOG", crate::logging::DEFAULT_FILTER.to_string());
+        if let Err(e) = crate::logging::parse(&log_filter) {
+            s.error(format!("logging.filter (RUST_LOG) is invalid: {}", e));
+        }
+
+        let live_sink = if transport.uses_redis() { SinkKind::Redis } else { SinkKind::Postgres };
+        let configured: Vec<SinkKind> = [
+            (true, live_sink),
+            (kafka.is_some(), SinkKind::Kafka),
+            (nats.is_some(), SinkKind::Nats),
+            (webhook.is_some(), SinkKind::Webhook),
+            (notifier.is_some(), SinkKind::Notifier),
+        ]
+        .into_iter()
+        .filter_map(|(present, sink)| present.then_some(sink))
+        .collect();
+        let sinks = sinks_from_settings(&mut s, &configured);
+
+        s.finish()?;
+
         Ok(Config {
-            database_url: env::var("DATABASE_URL")
-                .context("DATABASE_URL must be set")?,
-            
-            helius_api_key: env::var("HELIUS_API_KEY")
-                .context("HELIUS_API_KEY must be set")?,
-            
-            redis_url: env::var("REDIS_URL")
-                .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
-            
-            api_port: env::var("API_PORT")
-                .unwrap_or_else(|_| "8080".to_string())
-                .parse()
-                .context("API_PORT must be a valid number")?,
-            
-            // 🔥 NEW: Load CoinGecko API key (optional)
-            coingecko_api_key: env::var("COINGECKO_API_KEY").ok(),
+            dry_run,
+            rpc,
+            source,
+            database,
+            redis,
+            api,
+            analytics,
+            kafka,
+            nats,
+            webhook,
+            notifier,
+            sinks,
+            transport,
+            archive,
+            catch_up_limit,
+            log_filter,
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sinks() {
+        assert_eq!(
+            parse_sinks("redis, webhook,redis", &[SinkKind::Redis, SinkKind::Webhook]).unwrap(),
+            vec![SinkKind::Redis, SinkKind::Webhook]
+        );
+        assert_eq!(parse_sinks("noop", &[]).unwrap(), vec![SinkKind::Noop]);
+        assert!(parse_sinks("kafka", &[SinkKind::Nats]).is_err());
+        assert!(parse_sinks("redis", &[SinkKind::Postgres]).is_err());
+        assert_eq!(parse_sinks("pg_notify", &[SinkKind::Redis]).unwrap(), vec![SinkKind::Postgres]);
+        assert!(parse_sinks("carrier-pigeon", &[]).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use tracing::{error, info};
+use super::{Config, ConfigArgs, NotifyRules, RateLimitConfig};
+
+/// The part of the configuration that can change while the indexer runs,
+/// through `PUT /api/admin/config` or a SIGHUP-triggered file reload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeSettings {
+    pub rate_limit: RateLimitConfig,
+    /// Notifier rules, including the whale-buy threshold.
+    pub notify: NotifyRules,
+}
+
+impl RuntimeSettings {
+    pub fn from_config(config: &Config) -> Self {
+        RuntimeSettings {
+            rate_limit: config.api.rate_limit.clone(),
+            notify: config.notifier.as_ref().map(|n| n.rules.clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Every problem with the settings, empty when they can be applied.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, value) in [
+            ("rate_limit.read_per_minute", self.rate_limit.read_per_minute),
+            ("rate_limit.heavy_per_minute", self.rate_limit.heavy_per_minute),
+            ("rate_limit.stream_per_minute", self.rate_limit.stream_per_minute),
+        ] {
+            if value == 0 {
+                errors.push(format!("{} must be at least 1", name));
+            }
+        }
+        if let Some(threshold) = self.notify.whale_buy_sol
+            && !(threshold.is_finite() && threshold > 0.0)
+        {
+            errors.push("notify.whale_buy_sol must be a positive number or null".to_string());
+        }
+        errors
+    }
+}
+
+/// Shared handle to the current [`RuntimeSettings`]; readers see an update
+/// on their next access.
+#[derive(Debug, Clone)]
+pub struct SharedSettings(Arc<RwLock<RuntimeSettings>>);
+
+impl SharedSettings {
+    pub fn new(settings: RuntimeSettings) -> Self {
+        Self(Arc::new(RwLock::new(settings)))
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, RuntimeSettings> {
+        self.0.read().unwrap()
+    }
+
+    /// Replaces the settings if they validate, returning the problems
+    /// otherwise.
+    pub fn replace(&self, settings: RuntimeSettings) -> Result<(), Vec<String>> {
+        let errors = settings.validate();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        *self.0.write().unwrap() = settings;
+        Ok(())
+    }
+}
+
+/// Re-reads the config file (plus environment and `--set`) on every SIGHUP
+/// and applies its runtime settings. Everything else in the file needs a
+/// restart to take effect.
+#[cfg(unix)]
+pub async fn reload_on_sighup(args: ConfigArgs, settings: SharedSettings) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(e) => {
+            error!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+
+    while hangups.recv().await.is_some() {
+        info!("🔄 SIGHUP received, reloading runtime settings");
+
+        let reloaded = match Config::load(&args) {
+            Ok(config) => RuntimeSettings::from_config(&config),
+            Err(e) => {
+                error!("Config reload failed, keeping current settings: {:#}", e);
+                continue;
+            }
+        };
+        if *settings.read() == reloaded {
+            info!("Runtime settings unchanged");
+            continue;
+        }
+        match settings.replace(reloaded) {
+            Ok(()) => info!("✅ Runtime settings reloaded"),
+            Err(errors) => error!("Config reload rejected: {}", errors.join("; ")),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn reload_on_sighup(_args: ConfigArgs, _settings: SharedSettings) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_validates() {
+        let settings = SharedSettings::new(RuntimeSettings {
+            rate_limit: RateLimitConfig {
+                enabled: true,
+                trust_proxy: false,
+                read_per_minute: 300,
+                heavy_per_minute: 30,
+                stream_per_minute: 10,
+                api_keys: Vec::new(),
+            },
+            notify: NotifyRules::default(),
+        });
+
+        let mut update = settings.read().clone();
+        update.rate_limit.read_per_minute = 0;
+        update.notify.whale_buy_sol = Some(-1.0);
+        assert_eq!(settings.replace(update).unwrap_err().len(), 2);
+        assert_eq!(settings.read().rate_limit.read_per_minute, 300);
+
+        let mut update = settings.read().clone();
+        update.notify.whale_buy_sol = Some(50.0);
+        settings.replace(update).unwrap();
+        assert_eq!(settings.read().notify.whale_buy_sol, Some(50.0));
+    }
+}
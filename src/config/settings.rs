@@ -0,0 +1,240 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Where a setting's value came from, for error messages.
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    File,
+    Env(&'static str),
+    Cli,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::File => write!(f, "config file"),
+            Source::Env(var) => write!(f, "{}", var),
+            Source::Cli => write!(f, "--set"),
+        }
+    }
+}
+
+/// Reads an environment variable; swapped out in tests.
+type EnvLookup = Box<dyn Fn(&str) -> Option<String>>;
+
+/// Settings layered from a TOML/YAML file, the environment and `--set`
+/// overrides, in increasing precedence.
+///
+/// Every setting has a dotted key (`api.port`, as written in the file or
+/// `--set`) and an environment variable (`API_PORT`). Problems are collected
+/// rather than returned, so [`Settings::finish`] can report all of them at
+/// once.
+pub struct Settings {
+    file: HashMap<String, String>,
+    overrides: HashMap<String, String>,
+    env: EnvLookup,
+    known: HashSet<String>,
+    errors: Vec<String>,
+}
+
+impl Settings {
+    pub fn load(file: Option<&Path>, overrides: &[String]) -> Result<Self> {
+        let file = match file {
+            Some(path) => read_file(path)?,
+            None => HashMap::new(),
+        };
+        Ok(Self::from_parts(file, overrides, |var| env::var(var).ok()))
+    }
+
+    fn from_parts(
+        file: HashMap<String, String>,
+        overrides: &[String],
+        env: impl Fn(&str) -> Option<String> + 'static,
+    ) -> Self {
+        let mut settings = Settings {
+            file,
+            overrides: HashMap::new(),
+            env: Box::new(env),
+            known: HashSet::new(),
+            errors: Vec::new(),
+        };
+        for entry in overrides {
+            match entry.split_once('=') {
+                Some((key, value)) => {
+                    settings.overrides.insert(key.trim().to_string(), value.to_string());
+                }
+                None => settings.error(format!("--set {}: expected KEY=VALUE", entry)),
+            }
+        }
+        settings
+    }
+
+    fn lookup(&mut self, key: &str, var: &'static str) -> Option<(String, Source)> {
+        self.known.insert(key.to_string());
+
+        if let Some(value) = self.overrides.get(key) {
+            return Some((value.clone(), Source::Cli));
+        }
+        if let Some(value) = (self.env)(var) {
+            return Some((value, Source::Env(var)));
+        }
+        self.file.get(key).map(|value| (value.clone(), Source::File))
+    }
+
+    /// The value as written, if set anywhere.
+    pub fn raw(&mut self, key: &str, var: &'static str) -> Option<String> {
+        self.lookup(key, var).map(|(value, _)| value)
+    }
+
+    pub fn optional<T: FromStr>(&mut self, key: &str, var: &'static str) -> Option<T> {
+        let (value, source) = self.lookup(key, var)?;
+        match value.trim().parse() {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                self.error(format!("{}: invalid value `{}` (from {})", key, value, source));
+                None
+            }
+        }
+    }
+
+    pub fn get<T: FromStr>(&mut self, key: &str, var: &'static str, default: T) -> T {
+        self.optional(key, var).unwrap_or(default)
+    }
+
+    /// Records an error when unset; the empty string is returned so loading
+    /// can carry on and report everything else too.
+    pub fn required(&mut self, key: &str, var: &'static str) -> String {
+        match self.raw(key, var) {
+            Some(value) if !value.is_empty() => value,
+            _ => {
+                self.error(format!("{} ({}) must be set", key, var));
+                String::new()
+            }
+        }
+    }
+
+    /// Comma-separated values (or a list in the file), with blanks skipped.
+    pub fn list(&mut self, key: &str, var: &'static str) -> Vec<String> {
+        self.raw(key, var)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.errors.push(message.into());
+    }
+
+    /// Fails with every problem found, including file or `--set` keys that
+    /// no setting reads (usually typos).
+    pub fn finish(mut self) -> Result<()> {
+        let mut unknown: Vec<String> = self
+            .file
+            .keys()
+            .map(|key| (key, Source::File))
+            .chain(self.overrides.keys().map(|key| (key, Source::Cli)))
+            .filter(|(key, _)| !self.known.contains(*key))
+            .map(|(key, source)| format!("{}: unknown setting (from {})", key, source))
+            .collect();
+        unknown.sort();
+        self.errors.extend(unknown);
+
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+
+        let listed: Vec<String> = self.errors.iter().map(|e| format!("  - {}", e)).collect();
+        anyhow::bail!("Invalid configuration:\n{}", listed.join("\n"))
+    }
+}
+
+/// Parses the file by extension (`.yaml`/`.yml`, otherwise TOML) into
+/// dotted keys.
+fn read_file(path: &Path) -> Result<HashMap<String, String>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let value: Value = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml" | "yml") => serde_yaml::from_str(&text)
+            .with_context(|| format!("Failed to parse {}", path.display()))?,
+        _ => toml::from_str(&text)
+            .with_context(|| format!("Failed to parse {}", path.display()))?,
+    };
+
+    let mut values = HashMap::new();
+    flatten("", &value, &mut values);
+    Ok(values)
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&key, value, out);
+            }
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(scalar).collect();
+            out.insert(prefix.to_string(), items.join(","));
+        }
+        Value::Null => {}
+        other => {
+            out.insert(prefix.to_string(), scalar(other));
+        }
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layering_and_errors() {
+        let file: Value = toml::from_str(
+            r#"
+            [api]
+            port = 8080
+            grpc_port = "not-a-port"
+
+            [notify]
+            discord_webhooks = ["https://a", "https://b"]
+
+            [databse]
+            url = "postgres://typo"
+            "#,
+        )
+        .unwrap();
+        let mut values = HashMap::new();
+        flatten("", &file, &mut values);
+
+        let mut settings = Settings::from_parts(values, &["api.port=9090".to_string()], |var| {
+            (var == "REDIS_URL").then(|| "redis://env".to_string())
+        });
+
+        assert_eq!(settings.get("api.port", "API_PORT", 0u16), 9090);
+        assert_eq!(settings.get("redis.url", "REDIS_URL", String::new()), "redis://env");
+        assert_eq!(settings.list("notify.discord_webhooks", "NOTIFY_DISCORD_WEBHOOKS"), vec!["https://a", "https://b"]);
+        assert_eq!(settings.optional::<u16>("api.grpc_port", "GRPC_PORT"), None);
+        assert_eq!(settings.required("database.url", "DATABASE_URL"), "");
+
+        let message = settings.finish().unwrap_err().to_string();
+        assert!(message.contains("api.grpc_port: invalid value `not-a-port` (from config file)"));
+        assert!(message.contains("database.url (DATABASE_URL) must be set"));
+        assert!(message.contains("databse.url: unknown setting (from config file)"));
+    }
+}
@@ -0,0 +1,779 @@
+use super::model::{CompleteEvent, CreateEvent, TradeEventData};
+use super::to_bigint;
+use crate::error::{IndexerError, Result};
+use crate::helius::parser::PumpEvent;
+use crate::ingest;
+use crate::processor::calculator::{calculate_trade_impact, DEFAULT_TOKEN_DECIMALS};
+use crate::processor::spill::retry_transient;
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::{PgConnection, PgPool};
+use std::collections::{BTreeMap, HashSet};
+use tracing::{error, warn};
+
+const COPY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+/// Seconds between the Unix epoch and Postgres' epoch (2000-01-01).
+const PG_EPOCH_OFFSET_SECS: i64 = 946_684_800;
+
+const TOKEN_COLUMNS: &str = "mint_address, name, symbol, uri, creator_wallet, bonding_curve_address,
+    virtual_sol_reserves, virtual_token_reserves, real_token_reserves,
+    token_total_supply, created_at";
+
+const TRADE_COLUMNS: &str = "signature, token_mint, user_wallet, is_buy,
+    sol_amount, token_amount, timestamp,
+    virtual_sol_reserves, virtual_token_reserves,
+    real_sol_reserves, real_token_reserves,
+    fee_recipient, fee_basis_points, fee,
+    creator, creator_fee_basis_points, creator_fee,
+    track_volume, total_unclaimed_tokens, total_claimed_tokens,
+    current_sol_volume, last_update_timestamp, ix_name,
+    price_usd, sol_price_usd, price_before_sol, price_after_sol,
+    price_impact_pct, slippage_pct";
+
+/// How far from a trade the nearest SOL price may be. Older history is daily.
+const MAX_SOL_PRICE_GAP_SECS: i64 = 25 * 3600;
+
+/// Writes rows in Postgres' binary COPY format.
+pub struct CopyEncoder {
+    buf: Vec<u8>,
+    rows: usize,
+}
+
+impl Default for CopyEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CopyEncoder {
+    pub fn new() -> Self {
+        let mut buf = COPY_SIGNATURE.to_vec();
+        buf.extend_from_slice(&0i32.to_be_bytes()); // flags
+        buf.extend_from_slice(&0i32.to_be_bytes()); // header extension length
+        Self { buf, rows: 0 }
+    }
+
+    /// Starts a row; exactly `fields` values must follow.
+    pub fn row(&mut self, fields: i16) -> &mut Self {
+        self.buf.extend_from_slice(&fields.to_be_bytes());
+        self.rows += 1;
+        self
+    }
+
+    fn field(&mut self, bytes: &[u8]) -> &mut Self {
+        self.buf.extend_from_slice(&(bytes.len() as i32).to_be_bytes());
+        self.buf.extend_from_slice(bytes);
+        self
+    }
+
+    pub fn text(&mut self, value: &str) -> &mut Self {
+        self.field(value.as_bytes())
+    }
+
+    pub fn bigint(&mut self, value: i64) -> &mut Self {
+        self.field(&value.to_be_bytes())
+    }
+
+    pub fn bool(&mut self, value: bool) -> &mut Self {
+        self.field(&[value as u8])
+    }
+
+    pub fn float8(&mut self, value: Option<f64>) -> &mut Self {
+        match value {
+            Some(value) => self.field(&value.to_be_bytes()),
+            None => self.null(),
+        }
+    }
+
+    pub fn null(&mut self) -> &mut Self {
+        self.buf.extend_from_slice(&(-1i32).to_be_bytes());
+        self
+    }
+
+    pub fn timestamptz(&mut self, value: DateTime<Utc>) -> &mut Self {
+        let micros = (value.timestamp() - PG_EPOCH_OFFSET_SECS) * 1_000_000
+            + i64::from(value.timestamp_subsec_micros());
+        self.field(&micros.to_be_bytes())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.buf.extend_from_slice(&(-1i16).to_be_bytes());
+        self.buf
+    }
+}
+
+fn unix_time(seconds: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(seconds, 0).single().unwrap_or_default()
+}
+
+/// SOL/USD price points by Unix timestamp, for pricing trades long after
+/// the fact.
+#[derive(Debug, Default, Clone)]
+pub struct SolPriceHistory {
+    points: BTreeMap<i64, f64>,
+}
+
+impl SolPriceHistory {
+    pub fn extend(&mut self, points: impl IntoIterator<Item = (DateTime<Utc>, f64)>) {
+        self.points.extend(points.into_iter().map(|(at, price)| (at.timestamp(), price)));
+    }
+
+    /// The latest price at or before `timestamp`, else the first one after it,
+    /// as long as it's within `MAX_SOL_PRICE_GAP_SECS`.
+    pub fn price_at(&self, timestamp: i64) -> Option<f64> {
+        let before = self.points.range(..=timestamp).next_back();
+        let after = self.points.range(timestamp..).next();
+        before
+            .filter(|(at, _)| timestamp - **at <= MAX_SOL_PRICE_GAP_SECS)
+            .or(after.filter(|(at, _)| **at - timestamp <= MAX_SOL_PRICE_GAP_SECS))
+            .map(|(_, price)| *price)
+    }
+
+    pub fn covers(&self, from: i64, to: i64) -> bool {
+        self.price_at(from).is_some() && self.price_at(to).is_some()
+    }
+}
+
+/// What a flush wrote.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BulkReport {
+    pub tokens: u64,
+    pub trades: u64,
+    /// Trades whose token isn't in `tokens`, left out instead of failing the batch
+    pub trades_without_token: u64,
+    pub completions: u64,
+    /// Events already in the `processed_events` ledger, left out
+    pub already_processed: u64,
+    /// Events that couldn't be stored, kept in `dead_letter_events` instead
+    pub dead_lettered: u64,
+    /// Events that couldn't be stored or dead-lettered
+    pub dropped: u64,
+}
+
+/// An event with its `processed_events` key: the transaction signature and
+/// the event's position in it, plus the slot for dead-lettering it.
+struct Keyed<T> {
+    signature: String,
+    index: i16,
+    slot: u64,
+    event: T,
+}
+
+impl<T> Keyed<T> {
+    fn new(signature: &str, index: usize, slot: u64, event: T) -> Self {
+        Self { signature: signature.to_string(), index: index as i16, slot, event }
+    }
+}
+
+/// Buffers parsed events and writes them with `COPY ... FROM STDIN (FORMAT binary)`
+/// into staging tables, merged into the real ones with the same conflict handling
+/// as the per-row writes. Events are claimed in the `processed_events` ledger in
+/// the same transaction, like `ingest::persist_event` does, so events the live
+/// indexer already applied are skipped and it skips the ones loaded here.
+pub struct BulkLoader {
+    pool: PgPool,
+    flush_size: usize,
+    creates: Vec<Keyed<CreateEvent>>,
+    trades: Vec<Keyed<TradeEventData>>,
+    completions: Vec<Keyed<CompleteEvent>>,
+    /// Events with a value out of BIGINT range, with the reason
+    rejected: Vec<(Keyed<PumpEvent>, String)>,
+    sol_prices: SolPriceHistory,
+}
+
+impl BulkLoader {
+    pub fn new(pool: PgPool, flush_size: usize) -> Self {
+        Self {
+            pool,
+            flush_size: flush_size.max(1),
+            creates: Vec::new(),
+            trades: Vec::new(),
+            completions: Vec::new(),
+            rejected: Vec::new(),
+            sol_prices: SolPriceHistory::default(),
+        }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Prices trades are valued at in USD. Trades with no price nearby get
+    /// NULL USD values.
+    pub fn sol_prices(&mut self) -> &mut SolPriceHistory {
+        &mut self.sol_prices
+    }
+
+    /// Oldest and newest timestamp of the buffered trades.
+    pub fn trade_time_range(&self) -> Option<(i64, i64)> {
+        let timestamps = self.trades.iter().map(|t| t.event.timestamp);
+        Some((timestamps.clone().min()?, timestamps.max()?))
+    }
+
+    /// Buffers an event for the next flush. One with a value that doesn't fit
+    /// its BIGINT column would fail the whole COPY, so it is set aside and
+    /// dead-lettered by the flush instead.
+    pub fn push(&mut self, signature: &str, index: usize, slot: u64, event: PumpEvent) {
+        if let Err(e) = check_range(&event) {
+            self.rejected.push((Keyed::new(signature, index, slot, event), e.to_string()));
+            return;
+        }
+        match event {
+            PumpEvent::Create(create) => self.creates.push(Keyed::new(signature, index, slot, create)),
+            PumpEvent::Trade(trade) => self.trades.push(Keyed::new(signature, index, slot, trade)),
+            PumpEvent::Complete(complete) => self.completions.push(Keyed::new(signature, index, slot, complete)),
+        }
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.creates.len() + self.trades.len() + self.completions.len() + self.rejected.len()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.buffered() >= self.flush_size
+    }
+
+    /// Writes everything buffered in one transaction, retrying transient
+    /// failures. If the batch keeps failing for another reason, each event is
+    /// stored on its own through `ingest::persist_event` and the ones that
+    /// still fail are dead-lettered. The buffers are only emptied once that's
+    /// done; on error every event is still buffered for the next flush.
+    pub async fn flush(&mut self) -> Result<BulkReport> {
+        let mut report = BulkReport::default();
+        if self.buffered() == 0 {
+            return Ok(report);
+        }
+
+        if !(self.creates.is_empty() && self.trades.is_empty() && self.completions.is_empty()) {
+            match retry_transient(|| self.write_batch()).await {
+                Ok(written) => report = written,
+                Err(e) if e.is_retryable() => return Err(e),
+                Err(e) => {
+                    warn!("⚠️  Bulk write failed, storing {} events one by one: {}", self.buffered(), e);
+                    self.write_each(&mut report).await;
+                }
+            }
+            self.creates.clear();
+            self.trades.clear();
+            self.completions.clear();
+        }
+
+        for (keyed, failure) in std::mem::take(&mut self.rejected) {
+            self.dead_letter(&keyed, &failure, &mut report).await;
+        }
+
+        Ok(report)
+    }
+
+    /// Tokens go first so trades of tokens created in the same batch find them.
+    async fn write_batch(&self) -> Result<BulkReport> {
+        let mut report = BulkReport::default();
+        let mut tx = self.pool.begin().await?;
+        let creates = claim_events(&mut tx, "create", &self.creates, |e| &e.mint, false).await?;
+        report.already_processed += creates.already_processed;
+        if !creates.events.is_empty() {
+            report.tokens = copy_tokens(&mut tx, &creates.events).await?;
+        }
+        let trades = claim_events(&mut tx, "trade", &self.trades, |e| &e.mint, true).await?;
+        report.already_processed += trades.already_processed;
+        report.trades_without_token = trades.without_token;
+        if !trades.events.is_empty() {
+            report.trades = copy_trades(&mut tx, &trades.events, &self.sol_prices).await?;
+        }
+        let completions = claim_events(&mut tx, "complete", &self.completions, |e| &e.mint, true).await?;
+        report.already_processed += completions.already_processed;
+        if !completions.events.is_empty() {
+            report.completions = mark_completions(&mut tx, &completions.events).await?;
+        }
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    /// The live indexer's write path, one event at a time, in the order a
+    /// batch writes them.
+    async fn write_each(&self, report: &mut BulkReport) {
+        let creates = self.creates.iter().map(|k| (k, PumpEvent::Create(k.event.clone())));
+        let trades = self.trades.iter().map(|k| (k, PumpEvent::Trade(k.event.clone())));
+        let completions = self.completions.iter().map(|k| (k, PumpEvent::Complete(k.event.clone())));
+        let events = creates.map(|(k, e)| (&k.signature, k.index, k.slot, e))
+            .chain(trades.map(|(k, e)| (&k.signature, k.index, k.slot, e)))
+            .chain(completions.map(|(k, e)| (&k.signature, k.index, k.slot, e)));
+
+        for (signature, index, slot, event) in events {
+            let sol_price_usd = match &event {
+                PumpEvent::Trade(trade) => self.sol_prices.price_at(trade.timestamp),
+                _ => None,
+            };
+            let keyed = Keyed::new(signature, index as usize, slot, event);
+            let stored = ingest::persist_event(
+                &self.pool, signature, index as usize, &keyed.event, DEFAULT_TOKEN_DECIMALS, sol_price_usd,
+            ).await;
+            match stored {
+                Ok(true) => match keyed.event {
+                    PumpEvent::Create(_) => report.tokens += 1,
+                    PumpEvent::Trade(_) => report.trades += 1,
+                    PumpEvent::Complete(_) => report.completions += 1,
+                },
+                Ok(false) => report.already_processed += 1,
+                Err(e) => self.dead_letter(&keyed, &e.to_string(), report).await,
+            }
+        }
+    }
+
+    async fn dead_letter(&self, keyed: &Keyed<PumpEvent>, failure: &str, report: &mut BulkReport) {
+        let event = &keyed.event;
+        let saved = match serde_json::to_string(event) {
+            Ok(payload) => super::save_dead_letter(
+                &self.pool, &keyed.signature, keyed.index as usize, event.kind(), keyed.slot, &payload, failure,
+            ).await,
+            Err(e) => Err(IndexerError::Parse(e.to_string())),
+        };
+        match saved {
+            Ok(()) => {
+                report.dead_lettered += 1;
+                warn!("📮 Dead-lettered {} event {}#{}: {}", event.kind(), keyed.signature, keyed.index, failure);
+            }
+            Err(e) => {
+                report.dropped += 1;
+                error!("Failed to dead-letter {} event {}#{} ({}): {}", event.kind(), keyed.signature, keyed.index, failure, e);
+            }
+        }
+    }
+}
+
+/// Fails on the first value that doesn't fit its BIGINT column.
+fn check_range(event: &PumpEvent) -> Result<()> {
+    let values: &[(u64, &str)] = match event {
+        PumpEvent::Create(e) => &[
+            (e.virtual_sol_reserves, "virtual_sol_reserves"),
+            (e.virtual_token_reserves, "virtual_token_reserves"),
+            (e.real_token_reserves, "real_token_reserves"),
+            (e.token_total_supply, "token_total_supply"),
+        ],
+        PumpEvent::Trade(e) => &[
+            (e.sol_amount, "sol_amount"),
+            (e.token_amount, "token_amount"),
+            (e.virtual_sol_reserves, "virtual_sol_reserves"),
+            (e.virtual_token_reserves, "virtual_token_reserves"),
+            (e.real_sol_reserves, "real_sol_reserves"),
+            (e.real_token_reserves, "real_token_reserves"),
+            (e.fee_basis_points, "fee_basis_points"),
+            (e.fee, "fee"),
+            (e.creator_fee_basis_points, "creator_fee_basis_points"),
+            (e.creator_fee, "creator_fee"),
+            (e.total_unclaimed_tokens, "total_unclaimed_tokens"),
+            (e.total_claimed_tokens, "total_claimed_tokens"),
+            (e.current_sol_volume, "current_sol_volume"),
+        ],
+        PumpEvent::Complete(_) => &[],
+    };
+    for (value, column) in values {
+        to_bigint(*value, column)?;
+    }
+    Ok(())
+}
+
+struct Claimed<T> {
+    events: Vec<T>,
+    without_token: u64,
+    already_processed: u64,
+}
+
+/// Claims the events in the ledger and returns those claimed now, keeping
+/// the first of any repeated in the batch. With `require_token`, events
+/// whose token isn't in `tokens` are neither claimed nor returned, so a
+/// later run can still store them.
+async fn claim_events<'a, T>(
+    conn: &mut PgConnection,
+    kind: &str,
+    events: &'a [Keyed<T>],
+    mint: impl Fn(&T) -> &str,
+    require_token: bool,
+) -> Result<Claimed<&'a T>> {
+    if events.is_empty() {
+        return Ok(Claimed { events: Vec::new(), without_token: 0, already_processed: 0 });
+    }
+    let signatures: Vec<&str> = events.iter().map(|e| e.signature.as_str()).collect();
+    let indexes: Vec<i16> = events.iter().map(|e| e.index).collect();
+    let mints: Vec<&str> = events.iter().map(|e| mint(&e.event)).collect();
+
+    let mut without_token = 0;
+    if require_token {
+        (without_token,) = sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(*) FROM UNNEST($1::TEXT[]) AS u(mint)
+             WHERE NOT EXISTS (SELECT 1 FROM tokens WHERE mint_address = u.mint)"
+        )
+        .bind(&mints)
+        .fetch_one(&mut *conn)
+        .await?;
+    }
+
+    let claimed: Vec<(String, i16)> = sqlx::query_as(
+        "INSERT INTO processed_events (signature, event_index, kind)
+         SELECT u.signature, u.event_index, $4
+         FROM UNNEST($1::TEXT[], $2::SMALLINT[], $3::TEXT[]) AS u(signature, event_index, mint)
+         WHERE NOT $5 OR EXISTS (SELECT 1 FROM tokens WHERE mint_address = u.mint)
+         ON CONFLICT (signature, event_index) DO NOTHING
+         RETURNING signature, event_index"
+    )
+    .bind(&signatures)
+    .bind(&indexes)
+    .bind(&mints)
+    .bind(kind)
+    .bind(require_token)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let buffered = events.len() as u64;
+    let mut claimed: HashSet<(String, i16)> = claimed.into_iter().collect();
+    let events: Vec<&T> = events
+        .iter()
+        .filter(|e| claimed.remove(&(e.signature.clone(), e.index)))
+        .map(|e| &e.event)
+        .collect();
+
+    let without_token = without_token as u64;
+    Ok(Claimed {
+        already_processed: buffered - events.len() as u64 - without_token,
+        events,
+        without_token,
+    })
+}
+
+async fn copy_into(conn: &mut PgConnection, table: &str, columns: &str, data: Vec<u8>) -> Result<()> {
+    let mut copy = conn
+        .copy_in_raw(&format!("COPY {} ({}) FROM STDIN (FORMAT binary)", table, columns))
+        .await?;
+    copy.send(data).await?;
+    copy.finish().await?;
+    Ok(())
+}
+
+async fn copy_tokens(conn: &mut PgConnection, events: &[&CreateEvent]) -> Result<u64> {
+    let mut encoder = CopyEncoder::new();
+    for event in events {
+        encoder
+            .row(11)
+            .text(&event.mint)
+            .text(&event.name)
+            .text(&event.symbol)
+            .text(&event.uri)
+            .text(&event.creator)
+            .text(&event.bonding_curve)
+            .bigint(to_bigint(event.virtual_sol_reserves, "virtual_sol_reserves")?)
+            .bigint(to_bigint(event.virtual_token_reserves, "virtual_token_reserves")?)
+            .bigint(to_bigint(event.real_token_reserves, "real_token_reserves")?)
+            .bigint(to_bigint(event.token_total_supply, "token_total_supply")?)
+            .timestamptz(unix_time(event.timestamp));
+    }
+
+    sqlx::query("CREATE TEMP TABLE staging_tokens (LIKE tokens INCLUDING DEFAULTS) ON COMMIT DROP")
+        .execute(&mut *conn)
+        .await?;
+    copy_into(conn, "staging_tokens", TOKEN_COLUMNS, encoder.finish()).await?;
+
+    // A mint can only be updated once per statement, so keep its latest create.
+    let written = sqlx::query(&format!(
+        "INSERT INTO tokens ({columns})
+         SELECT DISTINCT ON (mint_address) {columns}
+         FROM staging_tokens
+         ORDER BY mint_address, created_at DESC
+         ON CONFLICT (mint_address) DO UPDATE SET
+            name = EXCLUDED.name,
+            symbol = EXCLUDED.symbol,
+            uri = EXCLUDED.uri,
+            creator_wallet = EXCLUDED.creator_wallet,
+            bonding_curve_address = EXCLUDED.bonding_curve_address,
+            virtual_sol_reserves = EXCLUDED.virtual_sol_reserves,
+            virtual_token_reserves = EXCLUDED.virtual_token_reserves,
+            real_token_reserves = EXCLUDED.real_token_reserves,
+            token_total_supply = EXCLUDED.token_total_supply,
+            updated_at = NOW()",
+        columns = TOKEN_COLUMNS
+    ))
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    Ok(written)
+}
+
+/// Returns how many were inserted. Duplicate signatures are dropped
+/// silently, as in `save_trade`; trades of unknown tokens were already left
+/// out when claiming them. Tokens whose newest trade is in the batch get
+/// their market cap from it.
+async fn copy_trades(
+    conn: &mut PgConnection,
+    events: &[&TradeEventData],
+    sol_prices: &SolPriceHistory,
+) -> Result<u64> {
+    let mut encoder = CopyEncoder::new();
+    for event in events {
+        let impact = calculate_trade_impact(
+            event.sol_amount,
+            event.token_amount,
+            event.is_buy,
+            event.virtual_sol_reserves,
+            event.virtual_token_reserves,
+            DEFAULT_TOKEN_DECIMALS,
+        );
+        let sol_price_usd = sol_prices.price_at(event.timestamp);
+
+        encoder
+            .row(29)
+            .text(&event.signature)
+            .text(&event.mint)
+            .text(&event.user)
+            .bool(event.is_buy)
+            .bigint(to_bigint(event.sol_amount, "sol_amount")?)
+            .bigint(to_bigint(event.token_amount, "token_amount")?)
+            .timestamptz(unix_time(event.timestamp))
+            .bigint(to_bigint(event.virtual_sol_reserves, "virtual_sol_reserves")?)
+            .bigint(to_bigint(event.virtual_token_reserves, "virtual_token_reserves")?)
+            .bigint(to_bigint(event.real_sol_reserves, "real_sol_reserves")?)
+            .bigint(to_bigint(event.real_token_reserves, "real_token_reserves")?)
+            .text(&event.fee_recipient)
+            .bigint(to_bigint(event.fee_basis_points, "fee_basis_points")?)
+            .bigint(to_bigint(event.fee, "fee")?)
+            .text(&event.creator)
+            .bigint(to_bigint(event.creator_fee_basis_points, "creator_fee_basis_points")?)
+            .bigint(to_bigint(event.creator_fee, "creator_fee")?)
+            .bool(event.track_volume)
+            .bigint(to_bigint(event.total_unclaimed_tokens, "total_unclaimed_tokens")?)
+            .bigint(to_bigint(event.total_claimed_tokens, "total_claimed_tokens")?)
+            .bigint(to_bigint(event.current_sol_volume, "current_sol_volume")?)
+            .timestamptz(unix_time(event.last_update_timestamp))
+            .text(&event.ix_name)
+            .float8(sol_price_usd.map(|sol| impact.price_after_sol * sol))
+            .float8(sol_price_usd)
+            .float8(Some(impact.price_before_sol))
+            .float8(Some(impact.price_after_sol))
+            .float8(Some(impact.price_impact_pct))
+            .float8(Some(impact.slippage_pct));
+    }
+
+    // NUMERIC's binary format isn't worth encoding; stage the USD columns as
+    // FLOAT8 and let the insert cast them.
+    sqlx::query("CREATE TEMP TABLE staging_trades (LIKE trades INCLUDING DEFAULTS) ON COMMIT DROP")
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("ALTER TABLE staging_trades ALTER price_usd TYPE FLOAT8, ALTER sol_price_usd TYPE FLOAT8")
+        .execute(&mut *conn)
+        .await?;
+    copy_into(conn, "staging_trades", TRADE_COLUMNS, encoder.finish()).await?;
+
+    // Set-based equivalent of `record_trade_activity` for the rows actually inserted.
+    let (inserted,): (i64,) = sqlx::query_as(&format!(
+        "WITH inserted AS (
+            INSERT INTO trades ({columns})
+            SELECT {columns} FROM staging_trades s
+            WHERE EXISTS (SELECT 1 FROM tokens WHERE mint_address = s.token_mint)
+            ON CONFLICT (signature) DO NOTHING
+            RETURNING token_mint, user_wallet, timestamp, price_usd
+         ),
+         new_traders AS (
+            INSERT INTO token_traders (token_mint, user_wallet, first_trade_at)
+            SELECT token_mint, user_wallet, MIN(timestamp) FROM inserted
+            GROUP BY token_mint, user_wallet
+            ON CONFLICT DO NOTHING
+            RETURNING token_mint
+         ),
+         bumped AS (
+            UPDATE tokens t
+            SET trade_count = t.trade_count + c.trades,
+                last_trade_at = GREATEST(t.last_trade_at, c.last_trade_at),
+                unique_traders = t.unique_traders + COALESCE(n.traders, 0),
+                market_cap_usd = CASE
+                    WHEN c.latest_price_usd IS NOT NULL
+                         AND (t.last_trade_at IS NULL OR c.last_trade_at >= t.last_trade_at)
+                    THEN c.latest_price_usd * t.token_total_supply / POWER(10::NUMERIC, t.decimals)
+                    ELSE t.market_cap_usd
+                END
+            FROM (SELECT token_mint, COUNT(*) AS trades, MAX(timestamp) AS last_trade_at,
+                         (ARRAY_AGG(price_usd ORDER BY timestamp DESC))[1] AS latest_price_usd
+                  FROM inserted GROUP BY token_mint) c
+            LEFT JOIN (SELECT token_mint, COUNT(*) AS traders
+                       FROM new_traders GROUP BY token_mint) n USING (token_mint)
+            WHERE t.mint_address = c.token_mint
+            RETURNING 1
+         )
+         SELECT COUNT(*) FROM inserted",
+        columns = TRADE_COLUMNS
+    ))
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(inserted as u64)
+}
+
+async fn mark_completions(conn: &mut PgConnection, completions: &[&CompleteEvent]) -> Result<u64> {
+    let mints: Vec<&str> = completions.iter().map(|e| e.mint.as_str()).collect();
+    let timestamps: Vec<DateTime<Utc>> = completions.iter().map(|e| unix_time(e.timestamp)).collect();
+
+    let updated = sqlx::query(
+        "UPDATE tokens t
+         SET complete = true, graduated_at = COALESCE(t.graduated_at, c.graduated_at)
+         FROM (SELECT mint, MIN(graduated_at) AS graduated_at
+               FROM UNNEST($1::TEXT[], $2::TIMESTAMPTZ[]) AS u(mint, graduated_at)
+               GROUP BY mint) c
+         WHERE t.mint_address = c.mint"
+    )
+    .bind(&mints)
+    .bind(&timestamps)
+    .execute(conn)
+    .await?
+    .rows_affected();
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(mint: &str) -> CreateEvent {
+        CreateEvent {
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            uri: String::new(),
+            mint: mint.to_string(),
+            bonding_curve: "curve".to_string(),
+            user: "creator".to_string(),
+            creator: "creator".to_string(),
+            timestamp: 1_700_000_000,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+        }
+    }
+
+    fn trade(signature: &str, mint: &str, user: &str) -> TradeEventData {
+        TradeEventData {
+            mint: mint.to_string(),
+            sol_amount: 1_000_000_000,
+            token_amount: 35_000_000_000_000,
+            is_buy: true,
+            user: user.to_string(),
+            timestamp: 1_700_000_100,
+            virtual_sol_reserves: 31_000_000_000,
+            virtual_token_reserves: 1_038_000_000_000_000,
+            real_sol_reserves: 1_000_000_000,
+            real_token_reserves: 758_100_000_000_000,
+            fee_recipient: "fees".to_string(),
+            fee_basis_points: 95,
+            fee: 9_500_000,
+            creator: "creator".to_string(),
+            creator_fee_basis_points: 5,
+            creator_fee: 500_000,
+            track_volume: true,
+            total_unclaimed_tokens: 0,
+            total_claimed_tokens: 0,
+            current_sol_volume: 0,
+            last_update_timestamp: 1_700_000_100,
+            ix_name: "buy".to_string(),
+            signature: signature.to_string(),
+        }
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_bulk_flush(pool: PgPool) {
+        let mut loader = BulkLoader::new(pool.clone(), 10);
+        loader.sol_prices().extend([(unix_time(1_700_000_000), 100.0)]);
+        loader.push("sig0", 0, 1, PumpEvent::Create(create("mint1")));
+        loader.push("sig1", 0, 1, PumpEvent::Trade(trade("sig1", "mint1", "alice")));
+        loader.push("sig2", 0, 1, PumpEvent::Trade(trade("sig2", "mint1", "alice")));
+        loader.push("sig2", 0, 1, PumpEvent::Trade(trade("sig2", "mint1", "alice")));
+        loader.push("sig3", 0, 1, PumpEvent::Trade(trade("sig3", "unknown", "bob")));
+        loader.push("sig4", 1, 1, PumpEvent::Complete(CompleteEvent {
+            user: "alice".to_string(),
+            mint: "mint1".to_string(),
+            bonding_curve: "curve".to_string(),
+            timestamp: 1_700_000_200,
+        }));
+
+        let report = loader.flush().await.unwrap();
+        assert_eq!(
+            report,
+            BulkReport { tokens: 1, trades: 2, trades_without_token: 1, completions: 1, already_processed: 1, ..BulkReport::default() }
+        );
+        assert_eq!(loader.buffered(), 0);
+
+        // Everything stored is in the ledger, so a second pass over the
+        // range changes nothing; the trade of an unknown token isn't.
+        loader.push("sig0", 0, 1, PumpEvent::Create(create("mint1")));
+        loader.push("sig1", 0, 1, PumpEvent::Trade(trade("sig1", "mint1", "alice")));
+        loader.push("sig3", 0, 1, PumpEvent::Trade(trade("sig3", "unknown", "bob")));
+        let report = loader.flush().await.unwrap();
+        assert_eq!(
+            report,
+            BulkReport { tokens: 0, trades: 0, trades_without_token: 1, completions: 0, already_processed: 2, ..BulkReport::default() }
+        );
+        let (ledgered,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM processed_events")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(ledgered, 4);
+
+        let (trade_count, unique_traders, complete): (i64, i64, bool) = sqlx::query_as(
+            "SELECT trade_count, unique_traders, complete FROM tokens WHERE mint_address = 'mint1'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((trade_count, unique_traders, complete), (2, 1, true));
+
+        let (sol_price, market_cap): (f64, f64) = sqlx::query_as(
+            "SELECT tr.sol_price_usd::FLOAT8, t.market_cap_usd::FLOAT8
+             FROM trades tr JOIN tokens t ON t.mint_address = tr.token_mint
+             WHERE tr.signature = 'sig1'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(sol_price, 100.0);
+        assert!(market_cap > 0.0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_bulk_flush_falls_back_to_single_events(pool: PgPool) {
+        let mut loader = BulkLoader::new(pool.clone(), 10);
+        loader.push("sig0", 0, 1, PumpEvent::Create(create("mint1")));
+        loader.flush().await.unwrap();
+
+        // A NUL byte fails the whole COPY; the rest are then stored one by one.
+        let mut bad = create("mint2");
+        bad.name = "bad\0name".to_string();
+        loader.push("sig1", 0, 2, PumpEvent::Create(bad));
+        loader.push("sig2", 0, 3, PumpEvent::Create(create("mint3")));
+        loader.push("sig3", 0, 4, PumpEvent::Trade(trade("sig3", "mint1", "alice")));
+        let report = loader.flush().await.unwrap();
+        assert_eq!((report.tokens, report.trades), (1, 1));
+        assert_eq!(report.dead_lettered + report.dropped, 1);
+        assert_eq!(loader.buffered(), 0);
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_bulk_flush_sets_aside_out_of_range_events(pool: PgPool) {
+        let mut loader = BulkLoader::new(pool.clone(), 10);
+        loader.push("sig0", 0, 1, PumpEvent::Create(create("mint1")));
+        let mut huge = trade("sig1", "mint1", "alice");
+        huge.sol_amount = u64::MAX;
+        loader.push("sig1", 0, 2, PumpEvent::Trade(huge));
+        loader.push("sig2", 0, 3, PumpEvent::Trade(trade("sig2", "mint1", "bob")));
+
+        // Dead-lettered before the COPY, which goes ahead without it.
+        let report = loader.flush().await.unwrap();
+        assert_eq!((report.tokens, report.trades, report.dead_lettered), (1, 1, 1));
+        assert_eq!(loader.buffered(), 0);
+        let (signature, slot): (String, i64) = sqlx::query_as("SELECT signature, slot FROM dead_letter_events")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((signature.as_str(), slot), ("sig1", 2));
+    }
+}
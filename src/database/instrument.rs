@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use crate::error::Result;
+use crate::processor::metrics::Histogram;
+
+/// Upper bounds (seconds) for the per-operation query latency histograms.
+const QUERY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Queries at least this slow are logged; 0 turns the log off.
+static SLOW_QUERY_MS: AtomicU64 = AtomicU64::new(250);
+
+/// Kept process-wide rather than on `Metrics`, since the `database::*`
+/// functions only receive a pool.
+static OPERATIONS: LazyLock<RwLock<BTreeMap<&'static str, Arc<OperationStats>>>> =
+    LazyLock::new(|| RwLock::new(BTreeMap::new()));
+
+struct OperationStats {
+    latency: Histogram,
+    errors: AtomicU64,
+}
+
+pub fn set_slow_query_threshold(threshold: Duration) {
+    SLOW_QUERY_MS.store(threshold.as_millis() as u64, Ordering::Relaxed);
+}
+
+/// Times one `database::*` call under `op`, counting it as an error when it
+/// fails. `binds` summarizes the call's arguments and is only evaluated when
+/// the call is slow enough to log.
+pub async fn observe<T, F>(op: &'static str, binds: impl FnOnce() -> String, query: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    let started = Instant::now();
+    let result = query.await;
+    let elapsed = started.elapsed();
+
+    let stats = stats_for(op);
+    stats.latency.observe(elapsed);
+    if result.is_err() {
+        stats.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let threshold = SLOW_QUERY_MS.load(Ordering::Relaxed);
+    if threshold > 0 && elapsed >= Duration::from_millis(threshold) {
+        warn!("🐢 Slow query {} took {:?} ({})", op, elapsed, binds());
+    }
+
+    result
+}
+
+fn stats_for(op: &'static str) -> Arc<OperationStats> {
+    if let Some(stats) = OPERATIONS.read().unwrap().get(op) {
+        return stats.clone();
+    }
+    OPERATIONS
+        .write()
+        .unwrap()
+        .entry(op)
+        .or_insert_with(|| Arc::new(OperationStats { latency: Histogram::new(QUERY_BUCKETS), errors: AtomicU64::new(0) }))
+        .clone()
+}
+
+/// Appends the per-operation metrics in the Prometheus text format.
+pub fn render(out: &mut String) {
+    let operations = OPERATIONS.read().unwrap();
+
+    let _ = writeln!(out, "# HELP pump_db_query_duration_seconds Time spent in each database operation");
+    let _ = writeln!(out, "# TYPE pump_db_query_duration_seconds histogram");
+    for (op, stats) in operations.iter() {
+        stats.latency.render(out, "pump_db_query_duration_seconds", &format!("op=\"{}\"", op));
+    }
+
+    let _ = writeln!(out, "# HELP pump_db_query_errors_total Failed database operations");
+    let _ = writeln!(out, "# TYPE pump_db_query_errors_total counter");
+    for (op, stats) in operations.iter() {
+        let _ = writeln!(out, "pump_db_query_errors_total{{op=\"{}\"}} {}", op, stats.errors.load(Ordering::Relaxed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::IndexerError;
+
+    #[tokio::test]
+    async fn test_observe_counts_latency_and_errors() {
+        observe("test_op", String::new, async { Ok(()) }).await.unwrap();
+        let failed: Result<()> = observe("test_op", String::new, async {
+            Err(IndexerError::Parse("boom".to_string()))
+        }).await;
+        assert!(failed.is_err());
+
+        let mut out = String::new();
+        render(&mut out);
+        assert!(out.contains("pump_db_query_duration_seconds_count{op=\"test_op\"} 2"));
+        assert!(out.contains("pump_db_query_errors_total{op=\"test_op\"} 1"));
+    }
+}
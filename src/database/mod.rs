@@ -1,9 +1,15 @@
 pub mod model;
-use model::{CreateEvent, TradeEventData, GeneralTransaction};
-use anyhow::Result;
-use sqlx::{postgres::PgPoolOptions, PgPool};
+pub mod instrument;
+pub mod bulk;
+pub mod schema;
+pub mod tiering;
+use model::{TradeEventData, GeneralTransaction, TokenSnapshot, CreatorFeeClaim, TrackedWallet, PositionTotals, KothReign, CreatorListEntry, IndexerSession, SessionCounts};
+use crate::helius::accounts::{BondingCurveAccount, TokenMetadata};
+use crate::error::{IndexerError, Result};
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool};
 use tracing::info;
 use chrono::{DateTime, Utc, TimeZone};
+use sha2::{Digest, Sha256};
 
 pub async fn create_pool(database_url: &str) -> Result<PgPool> {
     info!("Connecting to database...");
@@ -20,145 +26,269 @@ pub async fn create_pool(database_url: &str) -> Result<PgPool> {
 }
 
 pub async fn test_connection(pool: &PgPool) -> Result<()> {
-    let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tokens")
-        .fetch_one(pool)
+    instrument::observe("test_connection", String::new, async {
+        let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tokens")
+            .fetch_one(pool)
+            .await?;
+        info!("Database has {} tokens", row.0);
+        Ok(())
+    }).await
+}
+
+/// Indexes the API's common queries rely on, as (table, index) pairs.
+pub const EXPECTED_INDEXES: &[(&str, &str)] = &[
+    ("trades", "idx_trades_token_timestamp"),
+    ("trades", "idx_trades_user_timestamp"),
+    ("tokens", "idx_tokens_creator"),
+    ("tokens", "idx_tokens_market_cap"),
+    ("tokens", "idx_tokens_complete_progress"),
+];
+
+/// The `EXPECTED_INDEXES` not present in the database, e.g. because a
+/// migration was never applied.
+pub async fn missing_indexes(pool: &PgPool) -> Result<Vec<(&'static str, &'static str)>> {
+    instrument::observe("missing_indexes", String::new, async {
+        let present: Vec<(String,)> = sqlx::query_as(
+            "SELECT indexname::TEXT FROM pg_indexes WHERE schemaname = current_schema()"
+        )
+        .fetch_all(pool)
         .await?;
-    info!("Database has {} tokens", row.0);
-    Ok(())
+
+        Ok(EXPECTED_INDEXES
+            .iter()
+            .filter(|(_, index)| !present.iter().any(|(name,)| name == index))
+            .copied()
+            .collect())
+    }).await
 }
 
 pub async fn save_token_creation(
-    pool: &PgPool,
+    conn: &mut PgConnection,
     event: &crate::database::model::CreateEvent,
 ) -> Result<()> {
-    sqlx::query!(
-        r#"
-        INSERT INTO tokens (
-            mint_address,
-            name,
-            symbol,
-            uri,
-            bonding_curve_address,  -- 🔥 CHANGED from bonding_curve
-            creator_wallet,
-            virtual_token_reserves,
-            virtual_sol_reserves,
-            real_token_reserves,
-            token_total_supply,
-            created_at
-        )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-        ON CONFLICT (mint_address) DO UPDATE SET
-            name = EXCLUDED.name,
-            symbol = EXCLUDED.symbol,
-            uri = EXCLUDED.uri,
-            bonding_curve_address = EXCLUDED.bonding_curve_address,
-            creator_wallet = EXCLUDED.creator_wallet,
-            virtual_token_reserves = EXCLUDED.virtual_token_reserves,
-            virtual_sol_reserves = EXCLUDED.virtual_sol_reserves,
-            real_token_reserves = EXCLUDED.real_token_reserves,
-            token_total_supply = EXCLUDED.token_total_supply,
-            updated_at = NOW()
-        "#,
-        event.mint,
-        event.name,
-        event.symbol,
-        event.uri,
-        event.bonding_curve,  // This is the value
-        event.creator,
-        event.virtual_token_reserves as i64,
-        event.virtual_sol_reserves as i64,
-        event.real_token_reserves as i64,
-        event.token_total_supply as i64,
-        chrono::Utc.timestamp_opt(event.timestamp, 0).unwrap(),
-    )
-    .execute(pool)
-    .await?;
+    instrument::observe("save_token_creation", || format!("mint={}", event.mint), async {
+        sqlx::query!(
+            r#"
+            INSERT INTO tokens (
+                mint_address,
+                name,
+                symbol,
+                uri,
+                bonding_curve_address,  -- 🔥 CHANGED from bonding_curve
+                creator_wallet,
+                virtual_token_reserves,
+                virtual_sol_reserves,
+                real_token_reserves,
+                token_total_supply,
+                created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (mint_address) DO UPDATE SET
+                name = EXCLUDED.name,
+                symbol = EXCLUDED.symbol,
+                uri = EXCLUDED.uri,
+                bonding_curve_address = EXCLUDED.bonding_curve_address,
+                creator_wallet = EXCLUDED.creator_wallet,
+                virtual_token_reserves = EXCLUDED.virtual_token_reserves,
+                virtual_sol_reserves = EXCLUDED.virtual_sol_reserves,
+                real_token_reserves = EXCLUDED.real_token_reserves,
+                token_total_supply = EXCLUDED.token_total_supply,
+                updated_at = NOW()
+            "#,
+            event.mint,
+            event.name,
+            event.symbol,
+            event.uri,
+            event.bonding_curve,  // This is the value
+            event.creator,
+            to_bigint(event.virtual_token_reserves, "virtual_token_reserves")?,
+            to_bigint(event.virtual_sol_reserves, "virtual_sol_reserves")?,
+            to_bigint(event.real_token_reserves, "real_token_reserves")?,
+            to_bigint(event.token_total_supply, "token_total_supply")?,
+            chrono::Utc.timestamp_opt(event.timestamp, 0).unwrap(),
+        )
+        .execute(conn)
+        .await?;
 
-    Ok(())
+        Ok(())
+    }).await
 }
 
-pub async fn save_trade(pool: &PgPool, event: &TradeEventData) -> Result<()> {
-    let timestamp = Utc.timestamp_opt(event.timestamp, 0)
-        .single()
-        .unwrap_or_else(|| Utc::now());
+/// Bumps the token's denormalized trade counters for a newly stored trade.
+/// Callers run this in the same transaction as the trade insert, and only
+/// when the insert added a row.
+pub async fn record_trade_activity(
+    conn: &mut PgConnection,
+    mint: &str,
+    wallet: &str,
+    timestamp: DateTime<Utc>,
+) -> Result<()> {
+    instrument::observe("record_trade_activity", || format!("mint={} wallet={}", mint, wallet), async {
+        sqlx::query!(
+            r#"
+            WITH new_trader AS (
+                INSERT INTO token_traders (token_mint, user_wallet, first_trade_at)
+                VALUES ($1, $2, $3)
+                ON CONFLICT DO NOTHING
+                RETURNING 1
+            )
+            UPDATE tokens
+            SET trade_count = trade_count + 1,
+                last_trade_at = GREATEST(last_trade_at, $3),
+                unique_traders = unique_traders + (SELECT COUNT(*) FROM new_trader)
+            WHERE mint_address = $1
+            "#,
+            mint,
+            wallet,
+            timestamp
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }).await
+}
+
+/// `token_decimals` are the traded mint's, for the stored prices. Without a
+/// SOL price the USD columns are left NULL. Callers run this in a
+/// transaction, so the trade and its counters land together.
+pub async fn save_trade(conn: &mut PgConnection, event: &TradeEventData, token_decimals: u8, sol_price_usd: Option<f64>) -> Result<()> {
+    instrument::observe("save_trade", || format!("signature={} mint={}", event.signature, event.mint), async {
+        let timestamp = Utc.timestamp_opt(event.timestamp, 0)
+            .single()
+            .unwrap_or_else(Utc::now);
     
-    let last_update = Utc.timestamp_opt(event.last_update_timestamp, 0)
-        .single()
-        .unwrap_or_else(|| Utc::now());
-
-    sqlx::query!(
-        r#"
-        INSERT INTO trades (
-            signature,
-            token_mint,
-            sol_amount,
-            token_amount,
-            is_buy,
-            user_wallet,
+        let last_update = Utc.timestamp_opt(event.last_update_timestamp, 0)
+            .single()
+            .unwrap_or_else(Utc::now);
+
+        let impact = crate::processor::calculator::calculate_trade_impact(
+            event.sol_amount,
+            event.token_amount,
+            event.is_buy,
+            event.virtual_sol_reserves,
+            event.virtual_token_reserves,
+            token_decimals,
+        );
+        let price_usd = sol_price_usd.map(|sol| to_decimal(impact.price_after_sol * sol)).transpose()?;
+        let sol_price = sol_price_usd.map(to_decimal).transpose()?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO trades (
+                signature,
+                token_mint,
+                sol_amount,
+                token_amount,
+                is_buy,
+                user_wallet,
+                timestamp,
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                real_sol_reserves,
+                real_token_reserves,
+                fee_recipient,
+                fee_basis_points,
+                fee,
+                creator,
+                creator_fee_basis_points,
+                creator_fee,
+                track_volume,
+                total_unclaimed_tokens,
+                total_claimed_tokens,
+                current_sol_volume,
+                last_update_timestamp,
+                ix_name,
+                price_usd,
+                sol_price_usd,
+                price_before_sol,
+                price_after_sol,
+                price_impact_pct,
+                slippage_pct
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25,
+                    $26, $27, $28, $29)
+            ON CONFLICT (signature) DO NOTHING
+            "#,
+            event.signature,
+            event.mint,
+            to_bigint(event.sol_amount, "sol_amount")?,
+            to_bigint(event.token_amount, "token_amount")?,
+            event.is_buy,
+            event.user,
             timestamp,
-            virtual_sol_reserves,
-            virtual_token_reserves,
-            real_sol_reserves,
-            real_token_reserves,
-            fee_recipient,
-            fee_basis_points,
-            fee,
-            creator,
-            creator_fee_basis_points,
-            creator_fee,
-            track_volume,
-            total_unclaimed_tokens,
-            total_claimed_tokens,
-            current_sol_volume,
-            last_update_timestamp,
-            ix_name
-        )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)
-        ON CONFLICT (signature) DO NOTHING
-        "#,
-        event.signature,
-        event.mint,
-        event.sol_amount as i64,
-        event.token_amount as i64,
-        event.is_buy,
-        event.user,
-        timestamp,
-        event.virtual_sol_reserves as i64,
-        event.virtual_token_reserves as i64,
-        event.real_sol_reserves as i64,
-        event.real_token_reserves as i64,
-        event.fee_recipient,
-        event.fee_basis_points as i64,
-        event.fee as i64,
-        event.creator,
-        event.creator_fee_basis_points as i64,
-        event.creator_fee as i64,
-        event.track_volume,
-        event.total_unclaimed_tokens as i64,
-        event.total_claimed_tokens as i64,
-        event.current_sol_volume as i64,
-        last_update,
-        event.ix_name
-    )
-    .execute(pool)
-    .await?;
+            to_bigint(event.virtual_sol_reserves, "virtual_sol_reserves")?,
+            to_bigint(event.virtual_token_reserves, "virtual_token_reserves")?,
+            to_bigint(event.real_sol_reserves, "real_sol_reserves")?,
+            to_bigint(event.real_token_reserves, "real_token_reserves")?,
+            event.fee_recipient,
+            to_bigint(event.fee_basis_points, "fee_basis_points")?,
+            to_bigint(event.fee, "fee")?,
+            event.creator,
+            to_bigint(event.creator_fee_basis_points, "creator_fee_basis_points")?,
+            to_bigint(event.creator_fee, "creator_fee")?,
+            event.track_volume,
+            to_bigint(event.total_unclaimed_tokens, "total_unclaimed_tokens")?,
+            to_bigint(event.total_claimed_tokens, "total_claimed_tokens")?,
+            to_bigint(event.current_sol_volume, "current_sol_volume")?,
+            last_update,
+            event.ix_name,
+            price_usd,
+            sol_price,
+            impact.price_before_sol,
+            impact.price_after_sol,
+            impact.price_impact_pct,
+            impact.slippage_pct
+        )
+        .execute(&mut *conn)
+        .await?
+        .rows_affected() > 0;
 
-    Ok(())
+        // Only new trades count, so replays leave the totals alone.
+        if inserted {
+            record_trade_activity(conn, &event.mint, &event.user, timestamp).await?;
+        }
+
+        Ok(())
+    }).await
 }
 
-pub async fn mark_token_complete(pool: &PgPool, mint: &str) -> Result<()> {
-    sqlx::query!(
-        r#"
-        UPDATE tokens 
-        SET complete = true 
-        WHERE mint_address = $1
-        "#,
-        mint
-    )
-    .execute(pool)
-    .await?;
+pub async fn mark_token_complete(conn: &mut PgConnection, mint: &str, timestamp: i64) -> Result<()> {
+    instrument::observe("mark_token_complete", || format!("mint={}", mint), async {
+        let graduated_at = Utc.timestamp_opt(timestamp, 0)
+            .single()
+            .unwrap_or_else(Utc::now);
 
-    Ok(())
+        sqlx::query!(
+            r#"
+            UPDATE tokens 
+            SET complete = true,
+                graduated_at = COALESCE(graduated_at, $2)
+            WHERE mint_address = $1
+            "#,
+            mint,
+            graduated_at
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }).await
+}
+
+/// Converts an on-chain `u64` for a BIGINT column, failing instead of
+/// wrapping to a negative number when it doesn't fit.
+pub fn to_bigint(value: u64, column: &str) -> Result<i64> {
+    i64::try_from(value)
+        .map_err(|_| IndexerError::Parse(format!("{} {} does not fit in BIGINT", column, value)))
+}
+
+/// NaN and infinities have no NUMERIC equivalent.
+fn to_decimal(value: f64) -> Result<bigdecimal::BigDecimal> {
+    value
+        .to_string()
+        .parse()
+        .map_err(|e| IndexerError::Parse(format!("invalid decimal {}: {}", value, e)))
 }
 
 pub async fn update_token_metrics(
@@ -167,62 +297,79 @@ pub async fn update_token_metrics(
     market_cap: f64,
     bonding_curve_progress: f64,
 ) -> Result<()> {
-    use bigdecimal::BigDecimal;
-    use std::str::FromStr;
-
-    let market_cap_bd = BigDecimal::from_str(&market_cap.to_string())?;
-    let progress_bd = BigDecimal::from_str(&bonding_curve_progress.to_string())?;
-
-    sqlx::query!(
-        r#"
-        UPDATE tokens 
-        SET 
-            market_cap_usd = $2,
-            bonding_curve_progress = $3,
-            updated_at = NOW()
-        WHERE mint_address = $1
-        "#,
-        mint,
-        market_cap_bd,
-        progress_bd
-    )
-    .execute(pool)
-    .await?;
+    instrument::observe("update_token_metrics", || format!("mint={}", mint), async {
+        let market_cap_bd = to_decimal(market_cap)?;
+        let progress_bd = to_decimal(bonding_curve_progress)?;
 
-    Ok(())
+        sqlx::query!(
+            r#"
+            UPDATE tokens 
+            SET 
+                market_cap_usd = $2,
+                bonding_curve_progress = $3,
+                updated_at = NOW()
+            WHERE mint_address = $1
+            "#,
+            mint,
+            market_cap_bd,
+            progress_bd
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }).await
+}
+
+/// Whether a transaction is written to `transactions` when keeping `rate` of
+/// them. Decided by the signature, so a replayed transaction gets the same
+/// answer as the first time. SHA-256 rather than `DefaultHasher`, whose
+/// output may change between Rust releases.
+pub fn sample_transaction(signature: &str, rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 {
+        return false;
+    }
+    let digest = Sha256::digest(signature.as_bytes());
+    let hash = u64::from_be_bytes(digest[..8].try_into().expect("SHA-256 digests are 32 bytes"));
+    (hash as f64 / u64::MAX as f64) < rate
 }
 
 pub async fn save_general_transaction(pool: &PgPool, tx: &GeneralTransaction) -> Result<()> {
-    sqlx::query!(
-        r#"
-        INSERT INTO transactions (
-            signature, slot, block_time, fee, success, signer,
-            instruction_count, log_messages_count, has_program_data,
-            accounts_involved, pre_balances, post_balances,
-            compute_units_consumed, error_message
-        )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
-        ON CONFLICT (signature) DO NOTHING
-        "#,
-        tx.signature,
-        tx.slot as i64,
-        tx.block_time,
-        tx.fee as i64,
-        tx.success,
-        tx.signer,
-        tx.instruction_count,
-        tx.log_messages_count,
-        tx.has_program_data,
-        &tx.accounts_involved,
-        &tx.pre_balances,
-        &tx.post_balances,
-        tx.compute_units_consumed,
-        tx.error_message
-    )
-    .execute(pool)
-    .await?;
+    instrument::observe("save_general_transaction", || format!("signature={} slot={}", tx.signature, tx.slot), async {
+        sqlx::query!(
+            r#"
+            INSERT INTO transactions (
+                signature, slot, block_time, fee, success, signer,
+                instruction_count, log_messages_count, has_program_data,
+                accounts_involved, pre_balances, post_balances,
+                compute_units_consumed, error_message
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT (signature) DO NOTHING
+            "#,
+            tx.signature,
+            to_bigint(tx.slot, "slot")?,
+            tx.block_time,
+            to_bigint(tx.fee, "fee")?,
+            tx.success,
+            tx.signer,
+            tx.instruction_count,
+            tx.log_messages_count,
+            tx.has_program_data,
+            &tx.accounts_involved,
+            &tx.pre_balances,
+            &tx.post_balances,
+            tx.compute_units_consumed,
+            tx.error_message
+        )
+        .execute(pool)
+        .await?;
 
-    Ok(())
+        Ok(())
+    }).await
 }
 
 pub async fn update_stats(
@@ -232,61 +379,754 @@ pub async fn update_stats(
     trades_delta: i64,
     volume_delta: f64,
 ) -> Result<()> {
-    use bigdecimal::BigDecimal;
-    use std::str::FromStr;
-
-    let volume_bd = BigDecimal::from_str(&volume_delta.to_string())?;
-
-    sqlx::query!(
-        r#"
-        UPDATE indexer_stats 
-        SET 
-            total_transactions = total_transactions + 1,
-            total_tokens_created = total_tokens_created + $1,
-            total_trades = total_trades + $2,
-            total_volume_sol = total_volume_sol + $3,
-            last_processed_slot = GREATEST(last_processed_slot, $4),
-            last_updated = NOW()
-        WHERE id = 1
-        "#,
-        tokens_delta,
-        trades_delta,
-        volume_bd,
-        slot as i64
-    )
-    .execute(pool)
-    .await?;
+    instrument::observe("update_stats", || format!("slot={}", slot), async {
+        let volume_bd = to_decimal(volume_delta)?;
 
-    Ok(())
+        sqlx::query!(
+            r#"
+            UPDATE indexer_stats 
+            SET 
+                total_transactions = total_transactions + 1,
+                total_tokens_created = total_tokens_created + $1,
+                total_trades = total_trades + $2,
+                total_volume_sol = total_volume_sol + $3,
+                last_processed_slot = GREATEST(last_processed_slot, $4),
+                last_updated = NOW()
+            WHERE id = 1
+            "#,
+            tokens_delta,
+            trades_delta,
+            volume_bd,
+            to_bigint(slot, "slot")?
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }).await
 }
 
 pub async fn get_stats(pool: &PgPool) -> Result<model::IndexerStats> {
-    use bigdecimal::BigDecimal;
-    
-    let stats = sqlx::query!(
-        r#"
-        SELECT 
-            id,
-            COALESCE(total_transactions, 0) as "total_transactions!",
-            COALESCE(total_tokens_created, 0) as "total_tokens_created!",
-            COALESCE(total_trades, 0) as "total_trades!",
-            COALESCE(total_volume_sol, 0) as "total_volume_sol!",
-            COALESCE(last_processed_slot, 0) as "last_processed_slot!",
-            last_updated
-        FROM indexer_stats 
-        WHERE id = 1
-        "#
+    instrument::observe("get_stats", String::new, async {
+        let stats = sqlx::query!(
+            r#"
+            SELECT 
+                id,
+                COALESCE(total_transactions, 0) as "total_transactions!",
+                COALESCE(total_tokens_created, 0) as "total_tokens_created!",
+                COALESCE(total_trades, 0) as "total_trades!",
+                COALESCE(total_volume_sol, 0) as "total_volume_sol!",
+                COALESCE(last_processed_slot, 0) as "last_processed_slot!",
+                last_updated
+            FROM indexer_stats 
+            WHERE id = 1
+            "#
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(model::IndexerStats {
+            id: stats.id,
+            total_transactions: stats.total_transactions,
+            total_tokens_created: stats.total_tokens_created,
+            total_trades: stats.total_trades,
+            total_volume_sol: stats.total_volume_sol,
+            last_processed_slot: stats.last_processed_slot,
+            last_updated: stats.last_updated.unwrap_or_else(Utc::now),
+        })
+    }).await
+}
+
+/// Tokens created or traded after `since`.
+pub async fn fetch_mints_active_since(pool: &PgPool, since: DateTime<Utc>) -> Result<Vec<String>> {
+    instrument::observe("fetch_mints_active_since", || format!("since={}", since), async {
+        let mints = sqlx::query_scalar(
+            "SELECT mint_address FROM tokens WHERE last_trade_at > $1 OR created_at > $1"
+        )
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+        Ok(mints)
+    }).await
+}
+
+/// Reserves come from the token's latest trade, or the close of its latest
+/// minute tiered out of Postgres (real reserves derived from the launch
+/// reserves), falling back to the values recorded at creation.
+pub async fn fetch_token_snapshot(pool: &PgPool, mint: &str) -> Result<Option<TokenSnapshot>> {
+    instrument::observe("fetch_token_snapshot", || format!("mint={}", mint), async {
+        let snapshot = sqlx::query_as::<_, TokenSnapshot>(
+            "SELECT t.name, t.symbol, t.creator_wallet,
+                    COALESCE(last.virtual_sol_reserves, t.virtual_sol_reserves, 0) AS virtual_sol_reserves,
+                    COALESCE(last.virtual_token_reserves, t.virtual_token_reserves, 0) AS virtual_token_reserves,
+                    COALESCE(last.real_sol_reserves, 0) AS real_sol_reserves,
+                    COALESCE(last.real_token_reserves, t.real_token_reserves, 0) AS real_token_reserves,
+                    COALESCE(t.token_total_supply, 0) AS token_total_supply,
+                    t.decimals,
+                    COALESCE(t.complete, FALSE) AS complete
+             FROM tokens t
+             LEFT JOIN LATERAL (
+                 SELECT virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves
+                 FROM (
+                     SELECT timestamp AS at, virtual_sol_reserves, virtual_token_reserves,
+                            real_sol_reserves, real_token_reserves
+                     FROM trades
+                     WHERE token_mint = t.mint_address
+                     UNION ALL
+                     SELECT c.bucket, c.close_sol, c.close_tokens,
+                            GREATEST(c.close_sol - (e.details->>'virtual_sol_reserves')::BIGINT, 0),
+                            GREATEST((e.details->>'real_token_reserves')::BIGINT
+                                     - ((e.details->>'virtual_token_reserves')::BIGINT - c.close_tokens), 0)
+                     FROM cold_trade_candles c
+                     LEFT JOIN token_events e ON e.token_mint = c.token_mint AND e.kind = 'created'
+                     WHERE c.token_mint = t.mint_address
+                 ) points
+                 ORDER BY at DESC
+                 LIMIT 1
+             ) last ON TRUE
+             WHERE t.mint_address = $1"
+        )
+        .bind(mint)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(snapshot)
+    }).await
+}
+
+/// Overwrites a token's reserves with the bonding curve account read from
+/// chain. Returns `None` if the token is not stored.
+pub async fn apply_bonding_curve(
+    pool: &PgPool,
+    mint: &str,
+    curve_address: &str,
+    curve: &BondingCurveAccount,
+) -> Result<Option<TokenSnapshot>> {
+    instrument::observe("apply_bonding_curve", || format!("mint={}", mint), async {
+        let snapshot = sqlx::query_as::<_, TokenSnapshot>(
+            "UPDATE tokens
+             SET virtual_token_reserves = $2,
+                 virtual_sol_reserves = $3,
+                 real_token_reserves = $4,
+                 token_total_supply = $5,
+                 complete = COALESCE(complete, FALSE) OR $6,
+                 graduated_at = CASE WHEN $6 THEN COALESCE(graduated_at, NOW()) ELSE graduated_at END,
+                 bonding_curve_address = $7,
+                 creator_wallet = COALESCE($8, creator_wallet),
+                 updated_at = NOW()
+             WHERE mint_address = $1
+             RETURNING name, symbol, creator_wallet,
+                       virtual_sol_reserves, virtual_token_reserves,
+                       $9::BIGINT AS real_sol_reserves, real_token_reserves,
+                       token_total_supply, decimals, complete"
+        )
+        .bind(mint)
+        .bind(to_bigint(curve.virtual_token_reserves, "virtual_token_reserves")?)
+        .bind(to_bigint(curve.virtual_sol_reserves, "virtual_sol_reserves")?)
+        .bind(to_bigint(curve.real_token_reserves, "real_token_reserves")?)
+        .bind(to_bigint(curve.token_total_supply, "token_total_supply")?)
+        .bind(curve.complete)
+        .bind(curve_address)
+        .bind(curve.creator.as_deref())
+        .bind(to_bigint(curve.real_sol_reserves, "real_sol_reserves")?)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(snapshot)
+    }).await
+}
+
+/// Returns whether the token exists.
+pub async fn update_token_metadata(pool: &PgPool, mint: &str, metadata: &TokenMetadata) -> Result<bool> {
+    instrument::observe("update_token_metadata", || format!("mint={}", mint), async {
+        let result = sqlx::query(
+            "UPDATE tokens SET name = $2, symbol = $3, uri = $4, decimals = $5, updated_at = NOW() WHERE mint_address = $1"
+        )
+        .bind(mint)
+        .bind(&metadata.name)
+        .bind(&metadata.symbol)
+        .bind(&metadata.uri)
+        .bind(metadata.decimals as i16)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }).await
+}
+
+/// Slot up to which live transactions were processed, as last saved; 0
+/// before the first save.
+pub async fn fetch_checkpoint_slot(pool: &PgPool) -> Result<u64> {
+    instrument::observe("fetch_checkpoint_slot", String::new, async {
+        let slot: Option<i64> = sqlx::query_scalar("SELECT last_processed_slot FROM indexer_stats WHERE id = 1")
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+
+        Ok(slot.unwrap_or(0).max(0) as u64)
+    }).await
+}
+
+pub async fn save_checkpoint_slot(pool: &PgPool, slot: u64) -> Result<()> {
+    instrument::observe("save_checkpoint_slot", || format!("slot={}", slot), async {
+        sqlx::query(
+            "UPDATE indexer_stats
+             SET last_processed_slot = GREATEST(last_processed_slot, $1), last_updated = NOW()
+             WHERE id = 1"
+        )
+        .bind(to_bigint(slot, "slot")?)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }).await
+}
+
+pub async fn load_sol_price_history(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<(DateTime<Utc>, f64)>> {
+    instrument::observe("load_sol_price_history", || format!("from={} to={}", from, to), async {
+        let points = sqlx::query_as(
+            "SELECT timestamp, price_usd FROM sol_price_history
+             WHERE timestamp BETWEEN $1 AND $2
+             ORDER BY timestamp"
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(points)
+    }).await
+}
+
+pub async fn save_sol_price_history(pool: &PgPool, points: &[(DateTime<Utc>, f64)]) -> Result<()> {
+    instrument::observe("save_sol_price_history", || format!("points={}", points.len()), async {
+        let (timestamps, prices): (Vec<DateTime<Utc>>, Vec<f64>) = points.iter().copied().unzip();
+        sqlx::query(
+            "INSERT INTO sol_price_history (timestamp, price_usd)
+             SELECT * FROM UNNEST($1::TIMESTAMPTZ[], $2::FLOAT8[])
+             ON CONFLICT (timestamp) DO UPDATE SET price_usd = EXCLUDED.price_usd"
+        )
+        .bind(&timestamps)
+        .bind(&prices)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }).await
+}
+
+/// Records an event in the `processed_events` ledger. Returns false when it
+/// was already there, meaning the event has been applied before. Callers
+/// claim in the transaction that stores the event, so a claim never
+/// outlives a failed or interrupted write, and a concurrent claim of the
+/// same event waits for that transaction to settle.
+pub async fn claim_event(conn: &mut PgConnection, signature: &str, index: usize, kind: &str) -> Result<bool> {
+    instrument::observe("claim_event", || format!("signature={} index={}", signature, index), async {
+        let result = sqlx::query(
+            "INSERT INTO processed_events (signature, event_index, kind)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (signature, event_index) DO NOTHING"
+        )
+        .bind(signature)
+        .bind(index as i16)
+        .bind(kind)
+        .execute(conn)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }).await
+}
+
+/// Keeps an event that failed processing for the dead-letter sweeper. A
+/// repeat failure bumps `attempts` and doubles the wait before the next
+/// retry, up to six hours.
+pub async fn save_dead_letter(
+    pool: &PgPool,
+    signature: &str,
+    index: usize,
+    kind: &str,
+    slot: u64,
+    payload: &str,
+    error: &str,
+) -> Result<()> {
+    instrument::observe("save_dead_letter", || format!("signature={} index={}", signature, index), async {
+        sqlx::query(
+            "INSERT INTO dead_letter_events (signature, event_index, kind, slot, payload, error, next_retry_at)
+             VALUES ($1, $2, $3, $4, $5::JSONB, $6, NOW() + INTERVAL '1 minute')
+             ON CONFLICT (signature, event_index) DO UPDATE SET
+                error = EXCLUDED.error,
+                attempts = dead_letter_events.attempts + 1,
+                last_failed_at = NOW(),
+                next_retry_at = NOW() + LEAST(POWER(2, dead_letter_events.attempts), 360) * INTERVAL '1 minute'"
+        )
+        .bind(signature)
+        .bind(index as i16)
+        .bind(kind)
+        .bind(to_bigint(slot, "slot")?)
+        .bind(payload)
+        .bind(error)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }).await
+}
+
+/// Dead letters whose retry is due, oldest first. Events that already failed
+/// `max_attempts` times stay in the table for inspection but aren't retried.
+pub async fn fetch_due_dead_letters(pool: &PgPool, max_attempts: i32, limit: i64) -> Result<Vec<model::DeadLetter>> {
+    instrument::observe("fetch_due_dead_letters", || format!("limit={}", limit), async {
+        let rows = sqlx::query_as::<_, model::DeadLetter>(
+            "SELECT signature, event_index, kind, slot, payload::TEXT AS payload, attempts
+             FROM dead_letter_events
+             WHERE next_retry_at <= NOW() AND attempts < $1
+             ORDER BY next_retry_at
+             LIMIT $2"
+        )
+        .bind(max_attempts)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }).await
+}
+
+pub async fn delete_dead_letter(pool: &PgPool, signature: &str, index: i16) -> Result<()> {
+    instrument::observe("delete_dead_letter", || format!("signature={} index={}", signature, index), async {
+        sqlx::query("DELETE FROM dead_letter_events WHERE signature = $1 AND event_index = $2")
+            .bind(signature)
+            .bind(index)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }).await
+}
+
+pub async fn count_dead_letters(pool: &PgPool) -> Result<i64> {
+    instrument::observe("count_dead_letters", String::new, async {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM dead_letter_events")
+            .fetch_one(pool)
+            .await?;
+
+        Ok(count)
+    }).await
+}
+
+/// A session that hasn't been seen for this long without ending is reported
+/// as interrupted.
+const SESSION_STALE_SECS: i64 = 300;
+
+/// Opens an `indexer_sessions` row for this run and returns its id.
+pub async fn start_session(pool: &PgPool, mode: &str, start_slot: Option<u64>) -> Result<i64> {
+    instrument::observe("start_session", || format!("mode={}", mode), async {
+        let id = sqlx::query_scalar(
+            "INSERT INTO indexer_sessions (version, mode, start_slot) VALUES ($1, $2, $3) RETURNING id"
+        )
+        .bind(env!("CARGO_PKG_VERSION"))
+        .bind(mode)
+        .bind(start_slot.map(|slot| to_bigint(slot, "start_slot")).transpose()?)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(id)
+    }).await
+}
+
+/// Records a session's progress and marks it alive; `ended` closes it.
+pub async fn update_session(
+    pool: &PgPool,
+    id: i64,
+    end_slot: Option<u64>,
+    counts: &SessionCounts,
+    ended: bool,
+) -> Result<()> {
+    instrument::observe("update_session", || format!("id={}", id), async {
+        sqlx::query(
+            "UPDATE indexer_sessions
+             SET last_seen_at = NOW(),
+                 ended_at = CASE WHEN $2 THEN NOW() END,
+                 end_slot = COALESCE($3, end_slot),
+                 transactions_processed = $4,
+                 tokens_created = $5,
+                 trades_processed = $6,
+                 tokens_graduated = $7
+             WHERE id = $1"
+        )
+        .bind(id)
+        .bind(ended)
+        .bind(end_slot.map(|slot| to_bigint(slot, "end_slot")).transpose()?)
+        .bind(to_bigint(counts.transactions, "transactions_processed")?)
+        .bind(to_bigint(counts.tokens_created, "tokens_created")?)
+        .bind(to_bigint(counts.trades, "trades_processed")?)
+        .bind(to_bigint(counts.tokens_graduated, "tokens_graduated")?)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }).await
+}
+
+/// Marks a session alive without touching its counts.
+pub async fn touch_session(pool: &PgPool, id: i64) -> Result<()> {
+    sqlx::query("UPDATE indexer_sessions SET last_seen_at = NOW() WHERE id = $1 AND ended_at IS NULL")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Most recent sessions first.
+pub async fn fetch_sessions(pool: &PgPool, limit: i64) -> Result<Vec<IndexerSession>> {
+    instrument::observe("fetch_sessions", || format!("limit={}", limit), async {
+        let sessions = sqlx::query_as::<_, IndexerSession>(
+            "SELECT id, version, mode,
+                    CASE
+                        WHEN ended_at IS NOT NULL THEN 'stopped'
+                        WHEN last_seen_at < NOW() - $2 * INTERVAL '1 second' THEN 'interrupted'
+                        ELSE 'running'
+                    END AS status,
+                    started_at, last_seen_at, ended_at,
+                    EXTRACT(EPOCH FROM COALESCE(ended_at, last_seen_at) - started_at)::BIGINT AS uptime_secs,
+                    start_slot, end_slot,
+                    transactions_processed, tokens_created, trades_processed, tokens_graduated
+             FROM indexer_sessions
+             ORDER BY started_at DESC, id DESC
+             LIMIT $1"
+        )
+        .bind(limit)
+        .bind(SESSION_STALE_SECS as f64)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(sessions)
+    }).await
+}
+
+pub async fn save_creator_fee_claims(pool: &PgPool, signature: &str, claims: &[CreatorFeeClaim]) -> Result<()> {
+    instrument::observe("save_creator_fee_claims", || format!("signature={} claims={}", signature, claims.len()), async {
+        for (index, claim) in claims.iter().enumerate() {
+            let claimed_at = Utc.timestamp_opt(claim.timestamp, 0)
+                .single()
+                .unwrap_or_else(Utc::now);
+
+            sqlx::query(
+                "INSERT INTO creator_fee_claims (signature, event_index, creator, lamports, claimed_at)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (signature, event_index) DO NOTHING"
+            )
+            .bind(signature)
+            .bind(index as i16)
+            .bind(&claim.creator)
+            .bind(to_bigint(claim.creator_fee, "creator_fee")?)
+            .bind(claimed_at)
+            .execute(pool)
+            .await?;
+        }
+
+        Ok(())
+    }).await
+}
+
+/// Appends a `token_events` row. Returns false when the kind happens once
+/// per token and the token already has it.
+pub async fn save_token_event(
+    pool: &PgPool,
+    mint: &str,
+    kind: &str,
+    at: DateTime<Utc>,
+    signature: Option<&str>,
+    details: &serde_json::Value,
+) -> Result<bool> {
+    instrument::observe("save_token_event", || format!("mint={} kind={}", mint, kind), async {
+        let inserted = sqlx::query(
+            "INSERT INTO token_events (token_mint, kind, occurred_at, signature, details)
+             VALUES ($1, $2, $3, $4, $5::JSONB)
+             ON CONFLICT DO NOTHING"
+        )
+        .bind(mint)
+        .bind(kind)
+        .bind(at)
+        .bind(signature)
+        .bind(details.to_string())
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        Ok(inserted > 0)
+    }).await
+}
+
+/// Moves the token's recorded ATH to `price_sol` when it is at least `step`
+/// times the previous one, or there is none yet. Returns whether it moved.
+pub async fn raise_ath(pool: &PgPool, mint: &str, price_sol: f64, at: DateTime<Utc>, step: f64) -> Result<bool> {
+    instrument::observe("raise_ath", || format!("mint={} price_sol={}", mint, price_sol), async {
+        let raised = sqlx::query(
+            "UPDATE tokens SET ath_price_sol = $2, ath_at = $3
+             WHERE mint_address = $1 AND (ath_price_sol IS NULL OR $2 >= ath_price_sol * $4)"
+        )
+        .bind(mint)
+        .bind(price_sol)
+        .bind(at)
+        .bind(step)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        Ok(raised > 0)
+    }).await
+}
+
+pub async fn fetch_tracked_wallets(pool: &PgPool) -> Result<Vec<TrackedWallet>> {
+    instrument::observe("fetch_tracked_wallets", String::new, async {
+        let wallets = sqlx::query_as::<_, TrackedWallet>(
+            "SELECT wallet, label, added_at FROM tracked_wallets ORDER BY added_at"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(wallets)
+    }).await
+}
+
+/// Adds a wallet, or updates its label if already tracked.
+pub async fn save_tracked_wallet(pool: &PgPool, wallet: &str, label: Option<&str>) -> Result<TrackedWallet> {
+    instrument::observe("save_tracked_wallet", || format!("wallet={}", wallet), async {
+        let wallet = sqlx::query_as::<_, TrackedWallet>(
+            "INSERT INTO tracked_wallets (wallet, label) VALUES ($1, $2)
+             ON CONFLICT (wallet) DO UPDATE SET label = EXCLUDED.label
+             RETURNING wallet, label, added_at"
+        )
+        .bind(wallet)
+        .bind(label)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(wallet)
+    }).await
+}
+
+pub async fn delete_tracked_wallet(pool: &PgPool, wallet: &str) -> Result<bool> {
+    instrument::observe("delete_tracked_wallet", || format!("wallet={}", wallet), async {
+        let result = sqlx::query("DELETE FROM tracked_wallets WHERE wallet = $1")
+            .bind(wallet)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }).await
+}
+
+pub async fn fetch_creator_lists(pool: &PgPool) -> Result<Vec<CreatorListEntry>> {
+    instrument::observe("fetch_creator_lists", String::new, async {
+        let entries = sqlx::query_as::<_, CreatorListEntry>(
+            "SELECT creator, list, reason, added_at FROM creator_lists ORDER BY added_at"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entries)
+    }).await
+}
+
+/// Puts a creator on `list`, moving it off the other list if it was there.
+pub async fn save_creator_list_entry(
+    pool: &PgPool,
+    creator: &str,
+    list: &str,
+    reason: Option<&str>,
+) -> Result<CreatorListEntry> {
+    instrument::observe("save_creator_list_entry", || format!("creator={} list={}", creator, list), async {
+        let entry = sqlx::query_as::<_, CreatorListEntry>(
+            "INSERT INTO creator_lists (creator, list, reason) VALUES ($1, $2, $3)
+             ON CONFLICT (creator) DO UPDATE SET list = EXCLUDED.list, reason = EXCLUDED.reason
+             RETURNING creator, list, reason, added_at"
+        )
+        .bind(creator)
+        .bind(list)
+        .bind(reason)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(entry)
+    }).await
+}
+
+pub async fn delete_creator_list_entry(pool: &PgPool, creator: &str) -> Result<bool> {
+    instrument::observe("delete_creator_list_entry", || format!("creator={}", creator), async {
+        let result = sqlx::query("DELETE FROM creator_lists WHERE creator = $1")
+            .bind(creator)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }).await
+}
+
+pub async fn fetch_position_totals(pool: &PgPool, wallet: &str, mint: &str) -> Result<PositionTotals> {
+    instrument::observe("fetch_position_totals", || format!("wallet={} mint={}", wallet, mint), async {
+        let totals = sqlx::query_as::<_, PositionTotals>(
+            "SELECT COUNT(*) AS trades,
+                    COALESCE(SUM(token_amount) FILTER (WHERE is_buy), 0)::BIGINT AS tokens_bought,
+                    COALESCE(SUM(token_amount) FILTER (WHERE NOT is_buy), 0)::BIGINT AS tokens_sold,
+                    COALESCE(SUM(sol_amount) FILTER (WHERE is_buy), 0)::BIGINT AS lamports_spent,
+                    COALESCE(SUM(sol_amount) FILTER (WHERE NOT is_buy), 0)::BIGINT AS lamports_received
+             FROM trades
+             WHERE token_mint = $1 AND user_wallet = $2"
+        )
+        .bind(mint)
+        .bind(wallet)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(totals)
+    }).await
+}
+
+/// Most recent reigns first; the current king, if any, is the first row.
+pub async fn fetch_koth_reigns(pool: &PgPool, limit: i64) -> Result<Vec<KothReign>> {
+    instrument::observe("fetch_koth_reigns", || format!("limit={}", limit), async {
+        let reigns = sqlx::query_as::<_, KothReign>(
+            "SELECT r.mint_address, t.name, t.symbol, r.crowned_at, r.market_cap_sol, r.market_cap_usd,
+                    r.dethroned_at, r.dethroned_by
+             FROM koth_reigns r
+             JOIN tokens t USING (mint_address)
+             ORDER BY r.dethroned_at IS NULL DESC, r.crowned_at DESC, r.id DESC
+             LIMIT $1"
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(reigns)
+    }).await
+}
+
+/// Ends the current reign, if any, and starts `mint`'s.
+pub async fn crown_koth(
+    pool: &PgPool,
+    mint: &str,
+    at: DateTime<Utc>,
+    market_cap_sol: f64,
+    market_cap_usd: f64,
+    reason: &str,
+) -> Result<()> {
+    instrument::observe("crown_koth", || format!("mint={}", mint), async {
+        let mut tx = pool.begin().await?;
+        end_koth_reign(&mut tx, at, reason).await?;
+
+        sqlx::query(
+            "INSERT INTO koth_reigns (mint_address, crowned_at, market_cap_sol, market_cap_usd)
+             VALUES ($1, $2, $3, $4)"
+        )
+        .bind(mint)
+        .bind(at)
+        .bind(market_cap_sol)
+        .bind(market_cap_usd)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }).await
+}
+
+/// Ends the current reign, if any, leaving the crown vacant.
+pub async fn vacate_koth(pool: &PgPool, at: DateTime<Utc>, reason: &str) -> Result<()> {
+    instrument::observe("vacate_koth", || format!("reason={}", reason), async {
+        let mut conn = pool.acquire().await?;
+        end_koth_reign(&mut conn, at, reason).await
+    }).await
+}
+
+async fn end_koth_reign(conn: &mut PgConnection, at: DateTime<Utc>, reason: &str) -> Result<()> {
+    sqlx::query(
+        "UPDATE koth_reigns SET dethroned_at = GREATEST($1, crowned_at), dethroned_by = $2
+         WHERE dethroned_at IS NULL"
     )
-    .fetch_one(pool)
+    .bind(at)
+    .bind(reason)
+    .execute(conn)
     .await?;
 
-    Ok(model::IndexerStats {
-        id: stats.id,
-        total_transactions: stats.total_transactions,
-        total_tokens_created: stats.total_tokens_created,
-        total_trades: stats.total_trades,
-        total_volume_sol: stats.total_volume_sol,
-        last_processed_slot: stats.last_processed_slot,
-        last_updated: stats.last_updated.unwrap_or_else(|| Utc::now()),
-    })
-}
\ No newline at end of file
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_event_ledger(pool: PgPool) {
+        let mut conn = pool.acquire().await.unwrap();
+        assert!(claim_event(&mut conn, "sig", 0, "trade").await.unwrap());
+        assert!(!claim_event(&mut conn, "sig", 0, "trade").await.unwrap());
+        assert!(claim_event(&mut conn, "sig", 1, "complete").await.unwrap());
+
+        // A claim rolled back with its transaction leaves the event unclaimed.
+        let mut tx = pool.begin().await.unwrap();
+        assert!(claim_event(&mut tx, "sig", 2, "trade").await.unwrap());
+        tx.rollback().await.unwrap();
+        assert!(claim_event(&mut conn, "sig", 2, "trade").await.unwrap());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_missing_indexes(pool: PgPool) {
+        assert!(missing_indexes(&pool).await.unwrap().is_empty());
+
+        sqlx::query("DROP INDEX idx_trades_user_timestamp").execute(&pool).await.unwrap();
+        assert_eq!(missing_indexes(&pool).await.unwrap(), [("trades", "idx_trades_user_timestamp")]);
+    }
+
+    #[test]
+    fn test_sample_transaction() {
+        assert!(sample_transaction("sig", 1.0));
+        assert!(!sample_transaction("sig", 0.0));
+
+        let kept = (0..10_000).filter(|i| sample_transaction(&format!("sig{}", i), 0.25)).count();
+        assert!((2_000..3_000).contains(&kept));
+        assert_eq!(sample_transaction("sig7", 0.5), sample_transaction("sig7", 0.5));
+        // Pinned, so a change of hash shows up here rather than in which
+        // transactions a replay keeps.
+        assert!(sample_transaction("sig7", 0.44) && !sample_transaction("sig7", 0.43));
+    }
+
+    #[test]
+    fn test_to_bigint() {
+        assert_eq!(to_bigint(1_073_000_000_000_000, "virtual_token_reserves").unwrap(), 1_073_000_000_000_000);
+        assert_eq!(to_bigint(i64::MAX as u64, "fee").unwrap(), i64::MAX);
+        assert!(to_bigint(u64::MAX, "sol_amount").is_err());
+    }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_trade_activity_counters(pool: PgPool) {
+        sqlx::query(
+            "INSERT INTO tokens (mint_address, name, symbol, uri, creator_wallet, bonding_curve_address, created_at)
+             VALUES ('mint', 'mint', 'mint', '', 'creator', 'curve', NOW())"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let at = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        record_trade_activity(&mut conn, "mint", "alice", at).await.unwrap();
+        record_trade_activity(&mut conn, "mint", "bob", at + chrono::Duration::seconds(30)).await.unwrap();
+        // A late trade from a repeat trader leaves last_trade_at alone.
+        record_trade_activity(&mut conn, "mint", "alice", at - chrono::Duration::seconds(30)).await.unwrap();
+
+        let row: (Option<DateTime<Utc>>, i64, i64) = sqlx::query_as(
+            "SELECT last_trade_at, trade_count, unique_traders FROM tokens WHERE mint_address = 'mint'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(row, (Some(at + chrono::Duration::seconds(30)), 3, 2));
+    }
+}
@@ -20,6 +20,8 @@ pub struct Token {
   pub bonding_curve_progress: Option<BigDecimal>,
 
   pub complete: bool,
+  pub graduated_at: Option<DateTime<Utc>>,
+  pub amm_pool: Option<String>,
   pub created_at: DateTime<Utc>,
   pub updated_at: DateTime<Utc>,
 }
@@ -98,8 +100,110 @@ pub struct IndexerStats {
   pub last_updated: DateTime<Utc>,
 }
 
+/// A token's identity and latest curve reserves, used to rebuild its live
+/// state.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TokenSnapshot {
+  pub name: String,
+  pub symbol: String,
+  pub creator_wallet: String,
+  pub virtual_sol_reserves: i64,
+  pub virtual_token_reserves: i64,
+  pub real_sol_reserves: i64,
+  pub real_token_reserves: i64,
+  pub token_total_supply: i64,
+  pub decimals: i16,
+  pub complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct TrackedWallet {
+  pub wallet: String,
+  pub label: Option<String>,
+  pub added_at: DateTime<Utc>,
+}
+
+/// A creator on an operator-maintained list.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct CreatorListEntry {
+  pub creator: String,
+  /// `blacklist` or `watchlist`
+  pub list: String,
+  pub reason: Option<String>,
+  pub added_at: DateTime<Utc>,
+}
+
+/// A wallet's trade totals in one token, in lamports and raw token units.
+#[derive(Debug, Clone, Default, sqlx::FromRow)]
+pub struct PositionTotals {
+  pub trades: i64,
+  pub tokens_bought: i64,
+  pub tokens_sold: i64,
+  pub lamports_spent: i64,
+  pub lamports_received: i64,
+}
+
+/// One king-of-the-hill reign. `market_cap_*` is as of the crowning.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct KothReign {
+  pub mint_address: String,
+  pub name: String,
+  pub symbol: String,
+  pub crowned_at: DateTime<Utc>,
+  pub market_cap_sol: f64,
+  pub market_cap_usd: f64,
+  /// Null for the current king
+  pub dethroned_at: Option<DateTime<Utc>>,
+  /// `overtaken`, `graduated` or `fell`
+  pub dethroned_by: Option<String>,
+}
+
+/// One indexer or backfill run, as recorded in `indexer_sessions`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct IndexerSession {
+  pub id: i64,
+  pub version: String,
+  /// `live`, or `backfill`, `backfill_tokens` or `backfill_trades`
+  pub mode: String,
+  /// `running`, `stopped`, or `interrupted` when the run went quiet without
+  /// shutting down
+  pub status: String,
+  pub started_at: DateTime<Utc>,
+  pub last_seen_at: DateTime<Utc>,
+  pub ended_at: Option<DateTime<Utc>>,
+  /// Up to `ended_at`, or `last_seen_at` for runs without one
+  pub uptime_secs: i64,
+  pub start_slot: Option<i64>,
+  pub end_slot: Option<i64>,
+  pub transactions_processed: i64,
+  pub tokens_created: i64,
+  pub trades_processed: i64,
+  pub tokens_graduated: i64,
+}
+
+/// What a run has processed so far.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SessionCounts {
+  pub transactions: u64,
+  pub tokens_created: u64,
+  pub trades: u64,
+  pub tokens_graduated: u64,
+}
+
+/// An event whose processing failed, due for another attempt. `payload` is
+/// the serialized event; see `PumpEvent::from_parts`.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DeadLetter {
+  pub signature: String,
+  pub event_index: i16,
+  pub kind: String,
+  pub slot: i64,
+  pub payload: String,
+  pub attempts: i32,
+}
+
 /// CreateEvent
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateEvent {
   pub name: String,
   pub symbol: String,
@@ -116,7 +220,7 @@ pub struct CreateEvent {
 }
 
 /// TradeEvent
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeEventData {
   pub mint: String,
   pub sol_amount: u64,
@@ -143,11 +247,27 @@ pub struct TradeEventData {
   pub signature: String,
 }
 
+impl TradeEventData {
+  /// A swap on the token's PumpSwap pool after graduation rather than a
+  /// bonding curve trade.
+  pub fn is_pool_swap(&self) -> bool {
+    self.ix_name.starts_with("pump_amm_")
+  }
+}
+
 /// CompleteEvent
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompleteEvent {
   pub user: String,
   pub mint: String,
   pub bonding_curve: String,
   pub timestamp: i64,
+}
+
+/// CollectCreatorFeeEvent: a creator withdrawing accrued fees from their vault
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatorFeeClaim {
+  pub creator: String,
+  pub creator_fee: u64,
+  pub timestamp: i64,
 }
\ No newline at end of file
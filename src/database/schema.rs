@@ -0,0 +1,105 @@
+use std::fmt;
+use sqlx::PgPool;
+use crate::error::Result;
+use super::instrument;
+
+/// Tables and columns the indexer can't run without, as (table, columns).
+/// Most writes go through plain `sqlx::query`, which isn't checked against
+/// the schema at build time, so a database behind on migrations (or a
+/// column renamed in one) would otherwise only show up as failed queries
+/// once events arrive.
+pub const EXPECTED_COLUMNS: &[(&str, &[&str])] = &[
+    ("tokens", &[
+        "mint_address", "name", "symbol", "creator_wallet", "bonding_curve_address",
+        "virtual_sol_reserves", "virtual_token_reserves", "real_token_reserves", "token_total_supply",
+        "decimals", "complete", "market_cap_usd", "bonding_curve_progress", "updated_at",
+        "trade_count", "unique_traders", "last_trade_at", "ath_price_sol",
+    ]),
+    ("trades", &[
+        "signature", "token_mint", "user_wallet", "is_buy", "sol_amount", "token_amount", "timestamp",
+        "price_usd", "sol_price_usd", "price_before_sol", "price_after_sol",
+    ]),
+    ("transactions", &["signature", "slot", "block_time", "signer", "accounts_involved"]),
+    ("token_traders", &["token_mint", "user_wallet", "first_trade_at"]),
+    ("token_events", &["token_mint", "kind", "occurred_at", "signature", "details"]),
+    ("processed_events", &["signature", "event_index", "kind"]),
+    ("dead_letter_events", &["signature", "event_index", "kind", "payload", "attempts", "next_retry_at"]),
+    ("creator_fee_claims", &["signature", "event_index", "creator", "lamports"]),
+    ("indexer_stats", &["id", "last_processed_slot", "last_updated"]),
+    ("sol_price_history", &["timestamp", "price_usd"]),
+    ("indexer_sessions", &["id", "version", "mode", "started_at", "last_seen_at", "ended_at", "end_slot"]),
+    ("archive_manifest", &["kind", "object_key", "period_start", "period_end", "row_count", "pruned_at", "schema_version"]),
+    ("cold_trade_candles", &["token_mint", "bucket", "close_sol", "close_tokens", "volume_lamports", "trades"]),
+    ("cold_trade_sides", &[
+        "token_mint", "bucket", "is_buy", "trades", "volume_lamports", "fee_lamports", "creator_fee_lamports",
+        "fees_usd", "slippage_trades", "slippage_volume_lamports", "price_impact_pct_sum", "slippage_pct_sum",
+        "slippage_pct_volume_sum", "max_slippage_pct",
+    ]),
+    ("cold_fee_recipients", &["fee_recipient", "bucket", "fee_lamports", "trades"]),
+    ("cold_creator_fees", &["creator", "token_mint", "creator_fee_lamports", "trades"]),
+    ("cold_wallet_balances", &["token_mint", "user_wallet", "balance", "trades", "sells", "sold_lamports", "first_sell_at"]),
+];
+
+/// A difference between the live schema and `EXPECTED_COLUMNS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaDrift {
+    MissingTable(&'static str),
+    MissingColumn(&'static str, &'static str),
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaDrift::MissingTable(table) => write!(f, "table {} is missing", table),
+            SchemaDrift::MissingColumn(table, column) => write!(f, "column {}.{} is missing", table, column),
+        }
+    }
+}
+
+/// Compares the live schema with `EXPECTED_COLUMNS`. A missing table is
+/// reported once rather than once per column.
+pub async fn check(pool: &PgPool) -> Result<Vec<SchemaDrift>> {
+    instrument::observe("check_schema", String::new, async {
+        let present: Vec<(String, String)> = sqlx::query_as(
+            "SELECT table_name::TEXT, column_name::TEXT
+             FROM information_schema.columns
+             WHERE table_schema = current_schema()"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut drift = Vec::new();
+        for (table, columns) in EXPECTED_COLUMNS {
+            if !present.iter().any(|(t, _)| t == table) {
+                drift.push(SchemaDrift::MissingTable(table));
+                continue;
+            }
+            for column in *columns {
+                if !present.iter().any(|(t, c)| t == table && c == column) {
+                    drift.push(SchemaDrift::MissingColumn(table, column));
+                }
+            }
+        }
+        Ok(drift)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_check(pool: PgPool) {
+        assert!(check(&pool).await.unwrap().is_empty());
+
+        sqlx::query("ALTER TABLE tokens RENAME COLUMN market_cap_usd TO market_cap_sol").execute(&pool).await.unwrap();
+        sqlx::query("DROP TABLE dead_letter_events").execute(&pool).await.unwrap();
+        assert_eq!(
+            check(&pool).await.unwrap(),
+            [
+                SchemaDrift::MissingColumn("tokens", "market_cap_usd"),
+                SchemaDrift::MissingTable("dead_letter_events"),
+            ]
+        );
+    }
+}
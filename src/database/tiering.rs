@@ -0,0 +1,395 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use crate::error::Result;
+use super::instrument;
+
+/// `archive_manifest.schema_version` of trade files holding every `trades`
+/// column. Days archived with an older layout stay in Postgres, as deleting
+/// their rows would lose the columns the file left out.
+pub const TRADES_ARCHIVE_VERSION: i32 = 2;
+
+/// A day of trades uploaded by the archiver.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ArchivedPeriod {
+    pub object_key: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub row_count: i64,
+}
+
+/// Archived trade days ending at or before `before`, still in Postgres,
+/// whose files hold every column, oldest first.
+pub async fn fetch_prunable_periods(pool: &PgPool, before: DateTime<Utc>) -> Result<Vec<ArchivedPeriod>> {
+    instrument::observe("fetch_prunable_periods", || format!("before={}", before), async {
+        let periods = sqlx::query_as::<_, ArchivedPeriod>(
+            "SELECT object_key, period_start, period_end, row_count
+             FROM archive_manifest
+             WHERE kind = 'trades' AND pruned_at IS NULL AND period_end <= $1
+               AND schema_version >= $2
+             ORDER BY period_start"
+        )
+        .bind(before)
+        .bind(TRADES_ARCHIVE_VERSION)
+        .fetch_all(pool)
+        .await?;
+        Ok(periods)
+    }).await
+}
+
+/// What the fee, slippage, creator and holder reads need from the trades
+/// in `$1..$2`. Per-minute rows can't collide across periods; the all-time
+/// ones add up.
+const COLD_AGGREGATES: [&str; 4] = [
+    "INSERT INTO cold_trade_sides (
+         token_mint, bucket, is_buy, trades, volume_lamports, fee_lamports, creator_fee_lamports, fees_usd,
+         slippage_trades, slippage_volume_lamports, price_impact_pct_sum, slippage_pct_sum,
+         slippage_pct_volume_sum, max_slippage_pct
+     )
+     SELECT token_mint,
+            date_bin(INTERVAL '1 minute', timestamp, TIMESTAMPTZ '2000-01-01'),
+            is_buy,
+            COUNT(*),
+            SUM(sol_amount)::BIGINT,
+            SUM(fee)::BIGINT,
+            SUM(creator_fee)::BIGINT,
+            SUM(fee / 1e9 * sol_price_usd)::FLOAT8,
+            COUNT(slippage_pct),
+            COALESCE(SUM(sol_amount) FILTER (WHERE slippage_pct IS NOT NULL), 0)::BIGINT,
+            COALESCE(SUM(price_impact_pct) FILTER (WHERE slippage_pct IS NOT NULL), 0),
+            COALESCE(SUM(slippage_pct), 0),
+            COALESCE(SUM(slippage_pct * sol_amount), 0),
+            MAX(slippage_pct)
+     FROM trades
+     WHERE timestamp >= $1 AND timestamp < $2
+     GROUP BY 1, 2, 3
+     ON CONFLICT (token_mint, bucket, is_buy) DO NOTHING",
+    "INSERT INTO cold_fee_recipients (fee_recipient, bucket, fee_lamports, trades)
+     SELECT fee_recipient, date_bin(INTERVAL '1 minute', timestamp, TIMESTAMPTZ '2000-01-01'), SUM(fee)::BIGINT, COUNT(*)
+     FROM trades
+     WHERE timestamp >= $1 AND timestamp < $2
+     GROUP BY 1, 2
+     ON CONFLICT (fee_recipient, bucket) DO NOTHING",
+    "INSERT INTO cold_creator_fees (creator, token_mint, creator_fee_lamports, trades)
+     SELECT creator, token_mint, SUM(creator_fee)::BIGINT, COUNT(*)
+     FROM trades
+     WHERE timestamp >= $1 AND timestamp < $2
+     GROUP BY 1, 2
+     ON CONFLICT (creator, token_mint) DO UPDATE SET
+         creator_fee_lamports = cold_creator_fees.creator_fee_lamports + EXCLUDED.creator_fee_lamports,
+         trades = cold_creator_fees.trades + EXCLUDED.trades",
+    "INSERT INTO cold_wallet_balances (token_mint, user_wallet, balance, trades, sells, sold_lamports, first_sell_at)
+     SELECT token_mint, user_wallet,
+            SUM(CASE WHEN is_buy THEN token_amount ELSE -token_amount END),
+            COUNT(*),
+            COUNT(*) FILTER (WHERE NOT is_buy),
+            COALESCE(SUM(sol_amount) FILTER (WHERE NOT is_buy), 0),
+            MIN(timestamp) FILTER (WHERE NOT is_buy)
+     FROM trades
+     WHERE timestamp >= $1 AND timestamp < $2
+     GROUP BY 1, 2
+     ON CONFLICT (token_mint, user_wallet) DO UPDATE SET
+         balance = cold_wallet_balances.balance + EXCLUDED.balance,
+         trades = cold_wallet_balances.trades + EXCLUDED.trades,
+         sells = cold_wallet_balances.sells + EXCLUDED.sells,
+         sold_lamports = cold_wallet_balances.sold_lamports + EXCLUDED.sold_lamports,
+         first_sell_at = LEAST(cold_wallet_balances.first_sell_at, EXCLUDED.first_sell_at)",
+];
+
+/// Replaces a period's trades with one-minute `cold_trade_candles` and the
+/// `COLD_AGGREGATES`, and marks it pruned, in one transaction. Returns the trades removed, or
+/// `None` without touching anything when Postgres now holds a different
+/// number of rows than were archived (e.g. a backfill added some since),
+/// as the Parquet file would be missing them.
+pub async fn move_to_cold(pool: &PgPool, period: &ArchivedPeriod) -> Result<Option<u64>> {
+    instrument::observe("move_trades_to_cold", || format!("object={}", period.object_key), async {
+        let mut tx = pool.begin().await?;
+
+        let hot: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM trades WHERE timestamp >= $1 AND timestamp < $2")
+            .bind(period.period_start)
+            .bind(period.period_end)
+            .fetch_one(&mut *tx)
+            .await?;
+        if hot != period.row_count {
+            return Ok(None);
+        }
+
+        // Periods are whole days, so no minute straddles two of them.
+        sqlx::query(
+            "INSERT INTO cold_trade_candles (
+                 token_mint, bucket, open_sol, open_tokens, high_sol, high_tokens,
+                 low_sol, low_tokens, close_sol, close_tokens, volume_lamports, trades
+             )
+             SELECT token_mint,
+                    date_bin(INTERVAL '1 minute', timestamp, TIMESTAMPTZ '2000-01-01'),
+                    (array_agg(virtual_sol_reserves ORDER BY timestamp ASC))[1],
+                    (array_agg(virtual_token_reserves ORDER BY timestamp ASC))[1],
+                    (array_agg(virtual_sol_reserves ORDER BY virtual_sol_reserves::FLOAT8 / NULLIF(virtual_token_reserves, 0) DESC NULLS LAST))[1],
+                    (array_agg(virtual_token_reserves ORDER BY virtual_sol_reserves::FLOAT8 / NULLIF(virtual_token_reserves, 0) DESC NULLS LAST))[1],
+                    (array_agg(virtual_sol_reserves ORDER BY virtual_sol_reserves::FLOAT8 / NULLIF(virtual_token_reserves, 0) ASC NULLS LAST))[1],
+                    (array_agg(virtual_token_reserves ORDER BY virtual_sol_reserves::FLOAT8 / NULLIF(virtual_token_reserves, 0) ASC NULLS LAST))[1],
+                    (array_agg(virtual_sol_reserves ORDER BY timestamp DESC))[1],
+                    (array_agg(virtual_token_reserves ORDER BY timestamp DESC))[1],
+                    SUM(sol_amount)::BIGINT,
+                    COUNT(*)
+             FROM trades
+             WHERE timestamp >= $1 AND timestamp < $2
+             GROUP BY 1, 2
+             ON CONFLICT (token_mint, bucket) DO NOTHING"
+        )
+        .bind(period.period_start)
+        .bind(period.period_end)
+        .execute(&mut *tx)
+        .await?;
+
+        for aggregate in COLD_AGGREGATES {
+            sqlx::query(aggregate)
+                .bind(period.period_start)
+                .bind(period.period_end)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let removed = sqlx::query("DELETE FROM trades WHERE timestamp >= $1 AND timestamp < $2")
+            .bind(period.period_start)
+            .bind(period.period_end)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        sqlx::query("UPDATE archive_manifest SET pruned_at = NOW() WHERE object_key = $1")
+            .bind(&period.object_key)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(Some(removed))
+    }).await
+}
+
+/// Archived trade files for days no longer in Postgres that overlap
+/// `from..to`, either bound open when `None`, oldest first. With a `mint`,
+/// only days holding its trades; its `cold_trade_candles` tell which those
+/// are without opening the files.
+pub async fn fetch_cold_objects(
+    pool: &PgPool,
+    mint: Option<&str>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Vec<String>> {
+    instrument::observe("fetch_cold_objects", || format!("mint={:?} from={:?} to={:?}", mint, from, to), async {
+        let objects = sqlx::query_scalar(
+            "SELECT object_key
+             FROM archive_manifest m
+             WHERE kind = 'trades' AND pruned_at IS NOT NULL
+               AND ($2::TIMESTAMPTZ IS NULL OR period_end > $2)
+               AND ($3::TIMESTAMPTZ IS NULL OR period_start < $3)
+               AND ($1::VARCHAR IS NULL OR EXISTS (
+                   SELECT 1 FROM cold_trade_candles c
+                   WHERE c.token_mint = $1
+                     AND c.bucket >= m.period_start AND c.bucket < m.period_end
+                     AND ($2::TIMESTAMPTZ IS NULL OR c.bucket + INTERVAL '1 minute' > $2)
+                     AND ($3::TIMESTAMPTZ IS NULL OR c.bucket < $3)
+               ))
+             ORDER BY period_start"
+        )
+        .bind(mint)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+        Ok(objects)
+    }).await
+}
+
+/// Archived trade days, no longer in Postgres, that any of `times` falls
+/// in, oldest first.
+pub async fn fetch_cold_periods_at(pool: &PgPool, times: &[DateTime<Utc>]) -> Result<Vec<ArchivedPeriod>> {
+    instrument::observe("fetch_cold_periods_at", || format!("times={}", times.len()), async {
+        let periods = sqlx::query_as::<_, ArchivedPeriod>(
+            "SELECT object_key, period_start, period_end, row_count
+             FROM archive_manifest
+             WHERE kind = 'trades' AND pruned_at IS NOT NULL
+               AND EXISTS (SELECT 1 FROM UNNEST($1::TIMESTAMPTZ[]) AS t(at) WHERE at >= period_start AND at < period_end)
+             ORDER BY period_start"
+        )
+        .bind(times)
+        .fetch_all(pool)
+        .await?;
+        Ok(periods)
+    }).await
+}
+
+/// Trades tiered out of Postgres within `from..to`, of one `mint` or all
+/// and one side or both, counted from their one-minute sums: a minute
+/// straddling either bound counts whole.
+pub async fn count_cold_trades(
+    pool: &PgPool,
+    mint: Option<&str>,
+    is_buy: Option<bool>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<i64> {
+    instrument::observe("count_cold_trades", || format!("mint={:?} is_buy={:?} from={:?} to={:?}", mint, is_buy, from, to), async {
+        let trades = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(trades), 0)::BIGINT
+             FROM cold_trade_sides
+             WHERE ($1::VARCHAR IS NULL OR token_mint = $1)
+               AND ($2::BOOLEAN IS NULL OR is_buy = $2)
+               AND ($3::TIMESTAMPTZ IS NULL OR bucket + INTERVAL '1 minute' > $3)
+               AND ($4::TIMESTAMPTZ IS NULL OR bucket < $4)"
+        )
+        .bind(mint)
+        .bind(is_buy)
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
+        .await?;
+        Ok(trades)
+    }).await
+}
+
+/// Trades of `wallet` tiered out of Postgres, in one `mint` or all.
+pub async fn count_cold_wallet_trades(pool: &PgPool, mint: Option<&str>, wallet: &str) -> Result<i64> {
+    instrument::observe("count_cold_wallet_trades", || format!("mint={:?} wallet={}", mint, wallet), async {
+        let trades = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(trades), 0)::BIGINT
+             FROM cold_wallet_balances
+             WHERE ($1::VARCHAR IS NULL OR token_mint = $1) AND user_wallet = $2"
+        )
+        .bind(mint)
+        .bind(wallet)
+        .fetch_one(pool)
+        .await?;
+        Ok(trades)
+    }).await
+}
+
+/// Curve reserves after the last trade of a tiered-out minute.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ColdClose {
+    pub bucket: DateTime<Utc>,
+    pub close_sol: i64,
+    pub close_tokens: i64,
+}
+
+/// The last tiered-out minute of `mint` starting at or before `at`.
+pub async fn fetch_cold_close(pool: &PgPool, mint: &str, at: DateTime<Utc>) -> Result<Option<ColdClose>> {
+    instrument::observe("fetch_cold_close", || format!("mint={} at={}", mint, at), async {
+        let close = sqlx::query_as::<_, ColdClose>(
+            "SELECT bucket, close_sol, close_tokens
+             FROM cold_trade_candles
+             WHERE token_mint = $1 AND bucket <= $2
+             ORDER BY bucket DESC
+             LIMIT 1"
+        )
+        .bind(mint)
+        .bind(at)
+        .fetch_optional(pool)
+        .await?;
+        Ok(close)
+    }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_move_to_cold(pool: PgPool) {
+        sqlx::query(
+            "INSERT INTO tokens (mint_address, name, symbol, uri, creator_wallet, bonding_curve_address, created_at)
+             VALUES ('mint', 'mint', 'mint', '', 'creator', 'curve', NOW())"
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (signature, seconds, sol_reserves) in [("a", 0, 31_000_000_000i64), ("b", 30, 32_000_000_000), ("c", 300, 30_500_000_000)] {
+            sqlx::query(
+                "INSERT INTO trades (signature, token_mint, user_wallet, is_buy, sol_amount, token_amount, timestamp,
+                                     virtual_sol_reserves, virtual_token_reserves, real_sol_reserves, real_token_reserves,
+                                     fee_recipient, fee_basis_points, fee, creator, creator_fee_basis_points, creator_fee,
+                                     track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume,
+                                     last_update_timestamp, ix_name)
+                 VALUES ($1, 'mint', 'alice', true, 1000000000, 1, TIMESTAMPTZ '2025-11-03 07:00:00+00' + $2 * INTERVAL '1 second',
+                         $3, 1000000000000000, 0, 0, 'fees', 95, 0, 'creator', 5, 0, false, 0, 0, 0, NOW(), 'buy')"
+            )
+            .bind(signature)
+            .bind(seconds)
+            .bind(sol_reserves)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let start = Utc.with_ymd_and_hms(2025, 11, 3, 0, 0, 0).unwrap();
+        sqlx::query(
+            "INSERT INTO archive_manifest (kind, object_key, period_start, period_end, row_count, bytes)
+             VALUES ('trades', 'trades/date=2025-11-03/trades.parquet', $1, $1 + INTERVAL '1 day', 3, 100)"
+        )
+        .bind(start)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Written before the file held every column.
+        assert!(fetch_prunable_periods(&pool, Utc::now()).await.unwrap().is_empty());
+        sqlx::query("UPDATE archive_manifest SET schema_version = $1")
+            .bind(TRADES_ARCHIVE_VERSION)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let periods = fetch_prunable_periods(&pool, Utc::now()).await.unwrap();
+        assert_eq!(periods.len(), 1);
+        assert_eq!(move_to_cold(&pool, &periods[0]).await.unwrap(), Some(3));
+
+        let candles: Vec<(i64, i64, i32)> = sqlx::query_as(
+            "SELECT open_sol, high_sol, trades FROM cold_trade_candles ORDER BY bucket"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(candles, [(31_000_000_000, 32_000_000_000, 2), (30_500_000_000, 30_500_000_000, 1)]);
+        assert!(fetch_prunable_periods(&pool, Utc::now()).await.unwrap().is_empty());
+
+        let sides: (i64, i64) = sqlx::query_as("SELECT SUM(trades)::BIGINT, SUM(volume_lamports)::BIGINT FROM cold_trade_sides")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(sides, (3, 3_000_000_000));
+        let recipients: i64 = sqlx::query_scalar("SELECT SUM(trades)::BIGINT FROM cold_fee_recipients WHERE fee_recipient = 'fees'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(recipients, 3);
+        let creator: i64 = sqlx::query_scalar("SELECT trades FROM cold_creator_fees WHERE creator = 'creator'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(creator, 3);
+        let balance: (i64, i64) = sqlx::query_as(
+            "SELECT balance::BIGINT, sells FROM cold_wallet_balances WHERE token_mint = 'mint' AND user_wallet = 'alice'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(balance, (3, 0));
+
+        let day = start + chrono::Duration::days(1);
+        assert_eq!(fetch_cold_objects(&pool, Some("mint"), Some(start), Some(day)).await.unwrap().len(), 1);
+        assert_eq!(fetch_cold_objects(&pool, None, None, None).await.unwrap().len(), 1);
+        assert!(fetch_cold_objects(&pool, Some("other"), Some(start), Some(day)).await.unwrap().is_empty());
+        let after = start + chrono::Duration::hours(8);
+        assert!(fetch_cold_objects(&pool, Some("mint"), Some(after), None).await.unwrap().is_empty());
+        assert_eq!(fetch_cold_periods_at(&pool, &[after, day]).await.unwrap().len(), 1);
+        assert!(fetch_cold_periods_at(&pool, &[day]).await.unwrap().is_empty());
+
+        assert_eq!(count_cold_trades(&pool, Some("mint"), None, None, None).await.unwrap(), 3);
+        assert_eq!(count_cold_trades(&pool, None, None, Some(start + chrono::Duration::minutes(421)), None).await.unwrap(), 1);
+        assert_eq!(count_cold_trades(&pool, Some("mint"), Some(false), None, None).await.unwrap(), 0);
+        assert_eq!(count_cold_wallet_trades(&pool, Some("mint"), "alice").await.unwrap(), 3);
+        let close = fetch_cold_close(&pool, "mint", after).await.unwrap().unwrap();
+        assert_eq!(close.close_sol, 30_500_000_000);
+        assert!(fetch_cold_close(&pool, "mint", start).await.unwrap().is_none());
+    }
+}
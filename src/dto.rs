@@ -0,0 +1,303 @@
+//! Canonical shapes of what the indexer serves and publishes: REST bodies,
+//! and the messages sent over Redis and WebSockets. Handlers, sinks and the
+//! `client` feature all go through these, so each shape is defined once.
+//!
+//! `KothMessage` and `TrackedTradeMessage` live next to the logic that
+//! builds them, in `processor::koth` and `processor::tracked`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use crate::processor::creator_lists::CreatorList;
+use crate::processor::momentum::Momentum;
+use crate::processor::state::TokenState;
+
+/// Published on `pump:trades` and `pump:trades:{mint}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeMessage {
+    pub signature: String,
+    pub mint: String,
+    pub is_buy: bool,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub user_wallet: String,
+    pub timestamp: DateTime<Utc>,
+    pub market_cap_usd: f64,
+    pub price_sol: f64,
+    /// Position in the global trade sequence, used by clients to resume
+    /// after a reconnect. Assigned by the Redis sink; absent if Redis could
+    /// not allocate one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+}
+
+/// Published on `pump:tokens:new`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewTokenMessage {
+    pub mint: String,
+    pub name: String,
+    pub symbol: String,
+    pub creator: String,
+    pub timestamp: i64,
+    pub market_cap_sol: f64,
+    /// Set by the live sinks when the creator is on an operator list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator_list: Option<CreatorList>,
+}
+
+/// Published on `pump:completions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraduationMessage {
+    pub mint: String,
+    pub user: String,
+    pub timestamp: i64,
+}
+
+/// A read response as served: the handler's body with the `meta` that
+/// `api::freshness` attaches to it.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WithMeta<T> {
+    #[serde(flatten)]
+    pub body: T,
+    pub meta: ResponseMeta,
+}
+
+/// Data freshness, attached to every read response as `meta` by
+/// `api::freshness`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResponseMeta {
+    pub server_time: DateTime<Utc>,
+    pub as_of: AsOf,
+    /// Seconds between `server_time` and `as_of.block_time`.
+    pub lag_seconds: Option<i64>,
+    /// Slots seen in logs notifications but not yet processed.
+    pub lag_slots: u64,
+    /// True when nothing has been processed yet, ingestion is paused, or the
+    /// lag exceeds two minutes.
+    pub stale: bool,
+}
+
+/// The latest transaction reflected in the data.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AsOf {
+    pub slot: u64,
+    pub block_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Pagination {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// A token in `/api/tokens` listings.
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TokenResponse {
+    pub mint_address: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub creator_wallet: String,
+    #[schema(value_type = Option<String>)]
+    pub market_cap_usd: Option<bigdecimal::BigDecimal>,
+    #[schema(value_type = Option<String>)]
+    pub bonding_curve_progress: Option<bigdecimal::BigDecimal>,
+    pub complete: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_trade_at: Option<DateTime<Utc>>,
+    pub trade_count: i64,
+    pub unique_traders: i64,
+    /// Listings only, as of the last `token_summaries` refresh
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_24h_sol: Option<f64>,
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_24h_usd: Option<f64>,
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub holder_count: Option<i64>,
+    /// Present while the token is tracked in live state
+    #[sqlx(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub momentum: Option<Momentum>,
+    /// Set when the creator is on an operator list
+    #[sqlx(skip)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator_list: Option<CreatorList>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TokenListResponse {
+    pub tokens: Vec<TokenResponse>,
+    pub pagination: Pagination,
+}
+
+/// A single token, from live state when it is tracked in memory and from
+/// the database otherwise. Both carry every field; the ones a source
+/// doesn't know are null.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TokenDetail {
+    pub mint_address: String,
+    pub name: String,
+    pub symbol: String,
+    pub creator: String,
+    /// Live state only
+    pub current_price_sol: Option<f64>,
+    /// Live state only
+    pub market_cap_sol: Option<f64>,
+    pub market_cap_usd: Option<f64>,
+    pub bonding_curve_progress: Option<f64>,
+    pub complete: bool,
+    /// Database only
+    pub created_at: Option<DateTime<Utc>>,
+    /// Database only
+    pub last_trade_at: Option<DateTime<Utc>>,
+    /// Live state only
+    pub last_updated: Option<DateTime<Utc>>,
+    /// Database only
+    pub trade_count: Option<i64>,
+    /// Database only
+    pub unique_traders: Option<i64>,
+    /// Live state only
+    pub momentum: Option<Momentum>,
+    /// Set when the creator is on an operator list
+    pub creator_list: Option<CreatorList>,
+    /// `in_memory` or `database`
+    pub source: String,
+}
+
+impl From<TokenState> for TokenDetail {
+    fn from(token: TokenState) -> Self {
+        Self {
+            mint_address: token.mint,
+            name: token.name,
+            symbol: token.symbol,
+            creator: token.creator,
+            current_price_sol: Some(token.current_price_sol),
+            market_cap_sol: Some(token.market_cap_sol),
+            market_cap_usd: Some(token.market_cap_usd),
+            bonding_curve_progress: Some(token.bonding_curve_progress),
+            complete: token.complete,
+            created_at: None,
+            last_trade_at: None,
+            last_updated: Some(token.last_updated),
+            trade_count: None,
+            unique_traders: None,
+            momentum: Some(token.momentum),
+            creator_list: None,
+            source: "in_memory".to_string(),
+        }
+    }
+}
+
+impl From<TokenResponse> for TokenDetail {
+    fn from(token: TokenResponse) -> Self {
+        use bigdecimal::ToPrimitive;
+        Self {
+            mint_address: token.mint_address,
+            name: token.name,
+            symbol: token.symbol,
+            creator: token.creator_wallet,
+            current_price_sol: None,
+            market_cap_sol: None,
+            market_cap_usd: token.market_cap_usd.and_then(|v| v.to_f64()),
+            bonding_curve_progress: token.bonding_curve_progress.and_then(|v| v.to_f64()),
+            complete: token.complete,
+            created_at: Some(token.created_at),
+            last_trade_at: token.last_trade_at,
+            last_updated: None,
+            trade_count: Some(token.trade_count),
+            unique_traders: Some(token.unique_traders),
+            momentum: None,
+            creator_list: token.creator_list,
+            source: "database".to_string(),
+        }
+    }
+}
+
+/// A stored trade, as listed by the trade endpoints.
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct TradeResponse {
+    pub signature: String,
+    pub token_mint: String,
+    pub user_wallet: String,
+    pub is_buy: bool,
+    pub sol_amount: i64,
+    pub token_amount: i64,
+    pub timestamp: DateTime<Utc>,
+    /// Spot price in SOL per token right after the trade
+    pub price_sol: f64,
+    /// `price_sol` at the SOL/USD price when the trade was indexed
+    pub price_usd: Option<f64>,
+    /// `sol_amount` in USD at the same SOL/USD price
+    pub value_usd: Option<f64>,
+    /// Spot price right before the trade
+    pub price_before_sol: Option<f64>,
+    /// Percent change from `price_before_sol` to `price_sol`
+    pub price_impact_pct: Option<f64>,
+    /// Percent distance of the execution price from `price_before_sol`
+    pub slippage_pct: Option<f64>,
+    pub virtual_sol_reserves: i64,
+    pub virtual_token_reserves: i64,
+    pub real_sol_reserves: i64,
+    pub real_token_reserves: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TradeListResponse {
+    pub trades: Vec<TradeResponse>,
+    /// `total` includes trades tiered out of Postgres, counted from their
+    /// one-minute sums, except under a `min_sol` filter or a `wallet` one
+    /// combined with others.
+    pub pagination: Pagination,
+    /// Archive objects (Parquet) holding this token's trades in the range
+    /// that were moved out of Postgres. Pages list only the trades still in
+    /// Postgres; the rest are in these files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processor::state;
+
+    #[tokio::test]
+    async fn test_token_detail_sources_share_a_shape() {
+        let state_map = state::create_state_map();
+        state::init_token_state(
+            &state_map, "mint".to_string(), "Token".to_string(), "TKN".to_string(), "creator".to_string(),
+            30_000_000_000, 1_073_000_000_000_000, 793_100_000_000_000, 1_000_000_000_000_000, 6, 150.0,
+        ).await;
+        let live = TokenDetail::from(state::get_token_state(&state_map, "mint").await.unwrap());
+        let stored = TokenDetail::from(TokenResponse {
+            mint_address: "mint".to_string(),
+            name: "Token".to_string(),
+            symbol: "TKN".to_string(),
+            uri: String::new(),
+            creator_wallet: "creator".to_string(),
+            market_cap_usd: Some("4500.25".parse().unwrap()),
+            bonding_curve_progress: None,
+            complete: false,
+            created_at: Utc::now(),
+            last_trade_at: None,
+            trade_count: 3,
+            unique_traders: 2,
+            volume_24h_sol: None,
+            volume_24h_usd: None,
+            holder_count: None,
+            momentum: None,
+            creator_list: None,
+        });
+
+        let keys = |detail: &TokenDetail| {
+            let value = serde_json::to_value(detail).unwrap();
+            value.as_object().unwrap().keys().cloned().collect::<Vec<_>>()
+        };
+        assert_eq!(keys(&live), keys(&stored));
+        assert_eq!(serde_json::to_value(&stored).unwrap()["market_cap_usd"], 4500.25);
+    }
+}
@@ -0,0 +1,73 @@
+use sqlx::error::ErrorKind;
+
+pub type Result<T, E = IndexerError> = std::result::Result<T, E>;
+
+/// Failures from the ingestion path (`helius`, `processor`, `database`),
+/// typed so callers can tell retryable problems from fatal ones and
+/// recognise expected database conflicts without matching on messages.
+#[derive(Debug, thiserror::Error)]
+pub enum IndexerError {
+    /// Helius RPC or WebSocket failure.
+    #[error("RPC error: {0}")]
+    Rpc(String),
+    /// A transaction or event that could not be decoded.
+    #[error("Parse error: {0}")]
+    Parse(String),
+    #[error("Database error: {0}")]
+    Db(#[from] sqlx::Error),
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+    #[error("Configuration error: {0}")]
+    Config(String),
+}
+
+impl IndexerError {
+    /// Whether retrying the same operation later may succeed: network,
+    /// pool and connection failures are, bad data and constraint
+    /// violations are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            IndexerError::Rpc(_) => true,
+            IndexerError::Db(e) => matches!(
+                e,
+                sqlx::Error::Io(_)
+                    | sqlx::Error::Tls(_)
+                    | sqlx::Error::PoolTimedOut
+                    | sqlx::Error::WorkerCrashed
+            ),
+            IndexerError::Redis(e) => e.is_io_error() || e.is_timeout() || e.is_connection_dropped(),
+            IndexerError::Parse(_) | IndexerError::Config(_) => false,
+        }
+    }
+
+    /// A row that already exists, e.g. a transaction replayed by a backfill.
+    pub fn is_unique_violation(&self) -> bool {
+        self.db_error_kind() == Some(ErrorKind::UniqueViolation)
+    }
+
+    /// A reference to a missing row, e.g. a trade for a token not yet stored.
+    pub fn is_foreign_key_violation(&self) -> bool {
+        self.db_error_kind() == Some(ErrorKind::ForeignKeyViolation)
+    }
+
+    fn db_error_kind(&self) -> Option<ErrorKind> {
+        match self {
+            IndexerError::Db(sqlx::Error::Database(e)) => Some(e.kind()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable() {
+        assert!(IndexerError::Rpc("timeout".to_string()).is_retryable());
+        assert!(IndexerError::Db(sqlx::Error::PoolTimedOut).is_retryable());
+        assert!(!IndexerError::Db(sqlx::Error::RowNotFound).is_retryable());
+        assert!(!IndexerError::Parse("bad discriminator".to_string()).is_retryable());
+        assert!(!IndexerError::Db(sqlx::Error::PoolTimedOut).is_unique_violation());
+    }
+}
@@ -0,0 +1,111 @@
+use sentry::protocol::{Context, Event};
+use sentry::ClientInitGuard;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use crate::config::SentryConfig;
+
+/// A log callsite that keeps failing is reported at most once per window;
+/// the log itself still has every occurrence.
+const REPORT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Starts the Sentry client. Panics are captured by the default panic
+/// integration; `error!` events reach it through [`layer`]. Keep the guard
+/// alive for the life of the process so queued events are flushed on exit.
+pub fn init(config: &SentryConfig) -> ClientInitGuard {
+    let throttle = Arc::new(Throttle::default());
+
+    sentry::init((
+        config.dsn.as_str(),
+        sentry::ClientOptions {
+            release: sentry::release_name!(),
+            environment: Some(Cow::Owned(config.environment.clone())),
+            attach_stacktrace: true,
+            before_send: Some(Arc::new(move |mut event| {
+                // Group by where the error was logged rather than by its
+                // message, which embeds signatures and error details.
+                if let Some(callsite) = callsite(&event) {
+                    if !throttle.allow(&callsite, Instant::now()) {
+                        return None;
+                    }
+                    event.fingerprint = vec![Cow::Owned(callsite)].into();
+                }
+                Some(event)
+            })),
+            ..Default::default()
+        },
+    ))
+}
+
+/// Turns `error!` events into Sentry events and lower levels into
+/// breadcrumbs. Does nothing until [`init`] has been called.
+pub fn layer<S>() -> sentry::integrations::tracing::SentryLayer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    sentry::integrations::tracing::layer()
+}
+
+/// Tags events reported while handling one transaction, so they can be
+/// found by signature/slot. Wrap the handling future with
+/// `sentry::SentryFutureExt::bind_hub`.
+pub fn transaction_hub(signature: &str, slot: u64) -> Arc<sentry::Hub> {
+    let hub = Arc::new(sentry::Hub::new_from_top(sentry::Hub::current()));
+    hub.configure_scope(|scope| {
+        scope.set_tag("signature", signature);
+        scope.set_tag("slot", slot);
+    });
+    hub
+}
+
+/// Tags events with the mint currently being processed, on the current hub.
+pub fn set_mint(mint: &str) {
+    sentry::configure_scope(|scope| scope.set_tag("mint", mint));
+}
+
+/// `module:line` of the `tracing` event a Sentry event came from.
+fn callsite(event: &Event) -> Option<String> {
+    let Some(Context::Other(location)) = event.contexts.get("Rust Tracing Location") else {
+        return None;
+    };
+    let module = location.get("module_path")?.as_str()?;
+    let line = location.get("line")?;
+    Some(format!("{}:{}", module, line))
+}
+
+#[derive(Default)]
+struct Throttle {
+    last_reported: Mutex<HashMap<String, Instant>>,
+}
+
+impl Throttle {
+    fn allow(&self, key: &str, now: Instant) -> bool {
+        let mut last_reported = self.last_reported.lock().unwrap();
+        match last_reported.get(key) {
+            Some(at) if now.duration_since(*at) < REPORT_COOLDOWN => false,
+            _ => {
+                last_reported.insert(key.to_string(), now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_per_callsite() {
+        let throttle = Throttle::default();
+        let start = Instant::now();
+
+        assert!(throttle.allow("processor:10", start));
+        assert!(!throttle.allow("processor:10", start + Duration::from_secs(5)));
+        assert!(throttle.allow("helius:20", start + Duration::from_secs(5)));
+        assert!(throttle.allow("processor:10", start + REPORT_COOLDOWN));
+    }
+}
@@ -0,0 +1,155 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use crate::error::{IndexerError, Result};
+use super::PUMP_PROGRAM_ID;
+
+const METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+/// SPL mint layout: mint authority option (36), supply (8), then decimals.
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// 8-byte discriminator, five `u64` reserves/supply fields and `complete`.
+const BONDING_CURVE_MIN_LEN: usize = 8 + 5 * 8 + 1;
+
+/// On-chain state of a pump.fun bonding curve account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondingCurveAccount {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    pub complete: bool,
+    /// Only present on curves created after the creator-fee upgrade.
+    pub creator: Option<String>,
+}
+
+/// Name, symbol and URI from a mint's Metaplex metadata account, and
+/// decimals from the mint itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub decimals: u8,
+}
+
+pub fn bonding_curve_address(mint: &str) -> Result<Pubkey> {
+    let mint = parse_pubkey(mint)?;
+    let program = Pubkey::from_str(PUMP_PROGRAM_ID).expect("valid program id");
+    Ok(Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program).0)
+}
+
+fn metadata_address(mint: &str) -> Result<Pubkey> {
+    let mint = parse_pubkey(mint)?;
+    let program = Pubkey::from_str(METADATA_PROGRAM_ID).expect("valid program id");
+    Ok(Pubkey::find_program_address(&[b"metadata", program.as_ref(), mint.as_ref()], &program).0)
+}
+
+fn parse_pubkey(address: &str) -> Result<Pubkey> {
+    Pubkey::from_str(address).map_err(|e| IndexerError::Parse(format!("invalid address {}: {}", address, e)))
+}
+
+async fn fetch_account_data(rpc: &RpcClient, address: &Pubkey) -> Result<Vec<u8>> {
+    rpc.get_account_data(address)
+        .await
+        .map_err(|e| IndexerError::Rpc(format!("Failed to fetch account {}: {}", address, e)))
+}
+
+pub async fn fetch_bonding_curve(rpc: &RpcClient, mint: &str) -> Result<BondingCurveAccount> {
+    let data = fetch_account_data(rpc, &bonding_curve_address(mint)?).await?;
+    decode_bonding_curve(&data)
+}
+
+pub async fn fetch_token_metadata(rpc: &RpcClient, mint: &str) -> Result<TokenMetadata> {
+    let data = fetch_account_data(rpc, &metadata_address(mint)?).await?;
+    let mint_data = fetch_account_data(rpc, &parse_pubkey(mint)?).await?;
+    decode_metadata(&data, decode_mint_decimals(&mint_data)?)
+}
+
+fn decode_bonding_curve(data: &[u8]) -> Result<BondingCurveAccount> {
+    if data.len() < BONDING_CURVE_MIN_LEN {
+        return Err(IndexerError::Parse(format!("bonding curve account too short ({} bytes)", data.len())));
+    }
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let creator = data
+        .get(BONDING_CURVE_MIN_LEN..BONDING_CURVE_MIN_LEN + 32)
+        .map(|bytes| Pubkey::try_from(bytes).unwrap().to_string());
+
+    Ok(BondingCurveAccount {
+        virtual_token_reserves: u64_at(8),
+        virtual_sol_reserves: u64_at(16),
+        real_token_reserves: u64_at(24),
+        real_sol_reserves: u64_at(32),
+        token_total_supply: u64_at(40),
+        complete: data[48] != 0,
+        creator,
+    })
+}
+
+/// Metaplex layout: key (1), update authority (32), mint (32), then
+/// Borsh strings padded with NULs.
+fn decode_metadata(data: &[u8], decimals: u8) -> Result<TokenMetadata> {
+    let mut offset = 1 + 32 + 32;
+    let mut read_string = || -> Result<String> {
+        let len_bytes = data
+            .get(offset..offset + 4)
+            .ok_or_else(|| IndexerError::Parse("metadata account truncated".to_string()))?;
+        let len = u32::from_le_bytes(len_bytes.try_into().unwrap()) as usize;
+        let bytes = data
+            .get(offset + 4..offset + 4 + len)
+            .ok_or_else(|| IndexerError::Parse("metadata account truncated".to_string()))?;
+        offset += 4 + len;
+        Ok(String::from_utf8_lossy(bytes).trim_end_matches('\0').to_string())
+    };
+
+    Ok(TokenMetadata {
+        name: read_string()?,
+        symbol: read_string()?,
+        uri: read_string()?,
+        decimals,
+    })
+}
+
+fn decode_mint_decimals(data: &[u8]) -> Result<u8> {
+    data.get(MINT_DECIMALS_OFFSET)
+        .copied()
+        .ok_or_else(|| IndexerError::Parse(format!("mint account too short ({} bytes)", data.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_accounts() {
+        let mut curve = vec![0u8; 8];
+        for value in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 0, 1_000_000_000_000_000] {
+            curve.extend_from_slice(&value.to_le_bytes());
+        }
+        curve.push(1);
+        let decoded = decode_bonding_curve(&curve).unwrap();
+        assert_eq!(decoded.virtual_sol_reserves, 30_000_000_000);
+        assert!(decoded.complete);
+        assert_eq!(decoded.creator, None);
+        assert!(decode_bonding_curve(&curve[..40]).is_err());
+
+        let mut metadata = vec![4u8; 65];
+        for (value, padded) in [("Pepe", 32), ("PEPE", 10), ("https://ipfs.io/x", 200)] {
+            let mut bytes = value.as_bytes().to_vec();
+            bytes.resize(padded, 0);
+            metadata.extend_from_slice(&(padded as u32).to_le_bytes());
+            metadata.extend_from_slice(&bytes);
+        }
+        let decoded = decode_metadata(&metadata, 6).unwrap();
+        assert_eq!(decoded.name, "Pepe");
+        assert_eq!(decoded.symbol, "PEPE");
+        assert_eq!(decoded.uri, "https://ipfs.io/x");
+
+        let mut mint = vec![0u8; 82];
+        mint[MINT_DECIMALS_OFFSET] = 9;
+        assert_eq!(decode_mint_decimals(&mint).unwrap(), 9);
+        assert!(decode_mint_decimals(&mint[..40]).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use crate::database::model::GeneralTransaction;
 use solana_transaction_status::{EncodedTransactionWithStatusMeta, UiMessage};
 use chrono::{DateTime, Utc};
-use anyhow::Result;
+use crate::database::to_bigint;
+use crate::error::Result;
 
 pub fn extract_transaction_metadata(
     signature: &str,
@@ -12,7 +13,7 @@ pub fn extract_transaction_metadata(
 
     let block_time_dt = if let Some(time) = block_time {
         DateTime::from_timestamp(time, 0)
-            .unwrap_or_else(|| Utc::now())
+            .unwrap_or_else(Utc::now)
     } else {
         Utc::now()
     };
@@ -53,9 +54,7 @@ pub fn extract_transaction_metadata(
                 }
                 UiMessage::Raw(raw_msg) => {
                     
-                    let account_keys: Vec<String> = raw_msg.account_keys.iter()
-                        .map(|key| key.clone())
-                        .collect();
+                    let account_keys: Vec<String> = raw_msg.account_keys.to_vec();
                     
                     let signer_str = if !account_keys.is_empty() {
                         account_keys[0].clone()
@@ -96,8 +95,8 @@ pub fn extract_transaction_metadata(
 
     
     let (pre_balances, post_balances) = if let Some(meta) = &transaction.meta {
-        let pre = meta.pre_balances.iter().map(|&b| b as i64).collect();
-        let post = meta.post_balances.iter().map(|&b| b as i64).collect();
+        let pre = meta.pre_balances.iter().map(|&b| to_bigint(b, "pre_balance")).collect::<Result<_>>()?;
+        let post = meta.post_balances.iter().map(|&b| to_bigint(b, "post_balance")).collect::<Result<_>>()?;
         (pre, post)
     } else {
         (vec![], vec![])
@@ -107,7 +106,7 @@ pub fn extract_transaction_metadata(
     let compute_units = if let Some(meta) = &transaction.meta {
         match &meta.compute_units_consumed {
             solana_transaction_status::option_serializer::OptionSerializer::Some(units) => {
-                Some(*units as i64)
+                Some(to_bigint(*units, "compute_units_consumed")?)
             }
             _ => None
         }
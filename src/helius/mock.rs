@@ -0,0 +1,530 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use base64::Engine;
+use borsh::BorshSerialize;
+use chrono::Utc;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::EncodedTransactionWithStatusMeta;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+use crate::config::MockConfig;
+use crate::error::{IndexerError, Result};
+use crate::processor::metrics::Metrics;
+use super::parser::{self, COMPLETE_EVENT_DISCRIMINATOR, CREATE_EVENT_DISCRIMINATOR, TRADE_EVENT_DISCRIMINATOR};
+use super::{ListenerStatus, RawTransaction, NOTIFICATION_SEQ, PUMP_PROGRAM_ID};
+
+// A fresh bonding curve, as pump.fun creates them.
+const INITIAL_VIRTUAL_SOL: u64 = 30_000_000_000;
+const INITIAL_VIRTUAL_TOKENS: u64 = 1_073_000_000_000_000;
+const INITIAL_REAL_TOKENS: u64 = 793_100_000_000_000;
+const TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+const FEE_BASIS_POINTS: u64 = 95;
+const CREATOR_FEE_BASIS_POINTS: u64 = 5;
+/// The least traded token stops trading once this many are live.
+const MAX_LIVE_TOKENS: usize = 40;
+const CREATE_CHANCE: f64 = 0.08;
+/// Traders are drawn from this many wallets, so they show up across tokens.
+const WALLETS: usize = 2_000;
+/// Wallets per token that sells are drawn from.
+const MAX_TRACKED_HOLDERS: usize = 64;
+/// For the coldest tokens; hotter ones are bought more.
+const BUY_CHANCE: f64 = 0.5;
+/// Slots are ~400ms on mainnet.
+const SLOT_MILLIS: i64 = 400;
+
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+const NAME_WORDS: &[&str] = &[
+    "Based", "Moon", "Frog", "Cat", "Doge", "Pepe", "Giga", "Chad", "Tiny", "Rocket", "Turbo", "Wif", "Hat",
+    "Bonk", "Sol", "Degen", "Ape", "Laser", "Pixel", "Goblin", "Banana", "Shiba", "Panda", "Alpha",
+];
+
+#[derive(BorshSerialize)]
+struct CreateEventRaw {
+    name: String,
+    symbol: String,
+    uri: String,
+    mint: [u8; 32],
+    bonding_curve: [u8; 32],
+    user: [u8; 32],
+    creator: [u8; 32],
+    timestamp: i64,
+    virtual_token_reserves: u64,
+    virtual_sol_reserves: u64,
+    real_token_reserves: u64,
+    token_total_supply: u64,
+}
+
+#[derive(BorshSerialize)]
+struct TradeEventRaw {
+    mint: [u8; 32],
+    sol_amount: u64,
+    token_amount: u64,
+    is_buy: bool,
+    user: [u8; 32],
+    timestamp: i64,
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    real_sol_reserves: u64,
+    real_token_reserves: u64,
+    fee_recipient: [u8; 32],
+    fee_basis_points: u64,
+    fee: u64,
+    creator: [u8; 32],
+    creator_fee_basis_points: u64,
+    creator_fee: u64,
+    track_volume: bool,
+    total_unclaimed_tokens: u64,
+    total_claimed_tokens: u64,
+    current_sol_volume: u64,
+    last_update_timestamp: i64,
+    ix_name: String,
+}
+
+#[derive(BorshSerialize)]
+struct CompleteEventRaw {
+    user: [u8; 32],
+    mint: [u8; 32],
+    bonding_curve: [u8; 32],
+    timestamp: i64,
+}
+
+/// splitmix64; plenty for made-up market activity.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn key(&mut self) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        for chunk in key.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+        key
+    }
+
+    /// Log-uniform between `min` and `max` SOL, in lamports: mostly small
+    /// trades with the odd large one.
+    fn lamports(&mut self, min: f64, max: f64) -> u64 {
+        let sol = min * (max / min).powf(self.unit());
+        (sol * 1_000_000_000.0) as u64
+    }
+}
+
+struct MockToken {
+    mint: [u8; 32],
+    bonding_curve: [u8; 32],
+    creator: [u8; 32],
+    /// Relative chance of being traded; a few tokens take most of the flow.
+    heat: f64,
+    virtual_sol: u64,
+    virtual_tokens: u64,
+    real_sol: u64,
+    real_tokens: u64,
+    /// Recent buyers; sells come from them.
+    holders: Vec<[u8; 32]>,
+}
+
+/// A made-up pump.fun market: tokens launch, trade along their bonding
+/// curves and graduate once every curve token is sold. Used by the mock
+/// source and the `seed` binary.
+pub struct MockMarket {
+    rng: Rng,
+    tokens: Vec<MockToken>,
+    wallets: Vec<[u8; 32]>,
+    fee_recipient: [u8; 32],
+    create_chance: f64,
+}
+
+impl MockMarket {
+    /// The same seed produces the same market.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Rng(seed);
+        let fee_recipient = rng.key();
+        let wallets = (0..WALLETS).map(|_| rng.key()).collect();
+        Self { rng, tokens: Vec::new(), wallets, fee_recipient, create_chance: CREATE_CHANCE }
+    }
+
+    /// Share of transactions that launch a token (0.08 by default).
+    pub fn with_create_chance(mut self, create_chance: f64) -> Self {
+        self.create_chance = create_chance;
+        self
+    }
+
+    /// The next transaction at `timestamp`, with its signature, in the
+    /// format the RPC returns them.
+    pub fn next(&mut self, timestamp: i64) -> (String, EncodedTransactionWithStatusMeta) {
+        let (logs, accounts) = self.next_transaction(timestamp);
+        let mut signature = self.rng.key().to_vec();
+        signature.extend_from_slice(&self.rng.key());
+        let signature = bs58::encode(signature).into_string();
+        let transaction = build_transaction(&signature, accounts, logs);
+        (signature, transaction)
+    }
+
+    /// Log lines of the next transaction, with its accounts.
+    fn next_transaction(&mut self, timestamp: i64) -> (Vec<String>, Vec<String>) {
+        let mut user = self.wallet();
+        let mut logs = vec![format!("Program {} invoke [1]", PUMP_PROGRAM_ID)];
+
+        let index = if self.tokens.is_empty() || self.rng.unit() < self.create_chance {
+            // Creators launch from fresh wallets.
+            user = self.rng.key();
+            logs.push("Program log: Instruction: Create".to_string());
+            logs.push(program_data(CREATE_EVENT_DISCRIMINATOR, &self.launch(user, timestamp)));
+            logs.push(format!("Program {} success", PUMP_PROGRAM_ID));
+            logs.push(format!("Program {} invoke [1]", PUMP_PROGRAM_ID));
+            // The creator's dev buy.
+            let index = self.tokens.len() - 1;
+            let amount = self.rng.lamports(0.1, 3.0);
+            logs.push("Program log: Instruction: Buy".to_string());
+            logs.push(program_data(TRADE_EVENT_DISCRIMINATOR, &self.buy(index, user, amount, timestamp)));
+            index
+        } else {
+            let index = self.pick();
+            let token = &self.tokens[index];
+            // Hot tokens trend up towards graduation, cold ones drift.
+            let buy_chance = (BUY_CHANCE + token.heat * 0.04).min(0.85);
+            if token.real_sol == 0 || self.rng.unit() < buy_chance {
+                let amount = self.rng.lamports(0.01, 5.0);
+                logs.push("Program log: Instruction: Buy".to_string());
+                logs.push(program_data(TRADE_EVENT_DISCRIMINATOR, &self.buy(index, user, amount, timestamp)));
+            } else {
+                let holders = &self.tokens[index].holders;
+                if !holders.is_empty() {
+                    user = holders[self.rng.below(holders.len())];
+                }
+                let amount = self.rng.lamports(0.01, 3.0);
+                logs.push("Program log: Instruction: Sell".to_string());
+                logs.push(program_data(TRADE_EVENT_DISCRIMINATOR, &self.sell(index, user, amount, timestamp)));
+            }
+            index
+        };
+
+        let mint = self.tokens[index].mint;
+        if self.tokens[index].real_tokens == 0 {
+            let token = self.tokens.remove(index);
+            logs.push(program_data(COMPLETE_EVENT_DISCRIMINATOR, &CompleteEventRaw {
+                user,
+                mint: token.mint,
+                bonding_curve: token.bonding_curve,
+                timestamp,
+            }));
+        }
+        logs.push(format!("Program {} success", PUMP_PROGRAM_ID));
+
+        let accounts = [user, mint]
+            .iter()
+            .map(|key| Pubkey::new_from_array(*key).to_string())
+            .chain([PUMP_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID].map(String::from))
+            .collect();
+        (logs, accounts)
+    }
+
+    fn launch(&mut self, creator: [u8; 32], timestamp: i64) -> CreateEventRaw {
+        if self.tokens.len() >= MAX_LIVE_TOKENS
+            && let Some(coldest) = (0..self.tokens.len()).min_by(|&a, &b| self.tokens[a].heat.total_cmp(&self.tokens[b].heat))
+        {
+            self.tokens.remove(coldest);
+        }
+        let name = format!(
+            "{} {}",
+            NAME_WORDS[self.rng.below(NAME_WORDS.len())],
+            NAME_WORDS[self.rng.below(NAME_WORDS.len())]
+        );
+        let symbol: String = name.split(' ').flat_map(|word| word.chars().take(3)).collect::<String>().to_uppercase();
+        let token = MockToken {
+            mint: self.rng.key(),
+            bonding_curve: self.rng.key(),
+            creator,
+            heat: self.rng.unit().powi(4) * 10.0 + 0.05,
+            virtual_sol: INITIAL_VIRTUAL_SOL,
+            virtual_tokens: INITIAL_VIRTUAL_TOKENS,
+            real_sol: 0,
+            real_tokens: INITIAL_REAL_TOKENS,
+            holders: Vec::new(),
+        };
+        let event = CreateEventRaw {
+            name,
+            symbol,
+            uri: format!("https://ipfs.io/ipfs/Qm{}", bs58::encode(self.rng.key()).into_string()),
+            mint: token.mint,
+            bonding_curve: token.bonding_curve,
+            user: creator,
+            creator,
+            timestamp,
+            virtual_token_reserves: token.virtual_tokens,
+            virtual_sol_reserves: token.virtual_sol,
+            real_token_reserves: token.real_tokens,
+            token_total_supply: TOTAL_SUPPLY,
+        };
+        self.tokens.push(token);
+        event
+    }
+
+    /// Skewed so a few wallets trade a lot and most only occasionally.
+    fn wallet(&mut self) -> [u8; 32] {
+        let index = (self.rng.unit().powi(3) * self.wallets.len() as f64) as usize;
+        self.wallets[index.min(self.wallets.len() - 1)]
+    }
+
+    fn pick(&mut self) -> usize {
+        let total: f64 = self.tokens.iter().map(|t| t.heat).sum();
+        let mut target = self.rng.unit() * total;
+        for (index, token) in self.tokens.iter().enumerate() {
+            target -= token.heat;
+            if target <= 0.0 {
+                return index;
+            }
+        }
+        self.tokens.len() - 1
+    }
+
+    /// Constant-product buy; the last buyer gets whatever the curve has left.
+    fn buy(&mut self, index: usize, user: [u8; 32], sol_amount: u64, timestamp: i64) -> TradeEventRaw {
+        let token = &mut self.tokens[index];
+        let k = token.virtual_sol as u128 * token.virtual_tokens as u128;
+        let out = token.virtual_tokens - (k / (token.virtual_sol + sol_amount) as u128) as u64;
+        let tokens = out.min(token.real_tokens);
+        let sol = if tokens < out {
+            (k / (token.virtual_tokens - tokens) as u128) as u64 - token.virtual_sol
+        } else {
+            sol_amount
+        };
+        token.virtual_sol += sol;
+        token.virtual_tokens -= tokens;
+        token.real_sol += sol;
+        token.real_tokens -= tokens;
+        if !token.holders.contains(&user) {
+            if token.holders.len() == MAX_TRACKED_HOLDERS {
+                token.holders.remove(0);
+            }
+            token.holders.push(user);
+        }
+        self.trade_event(index, user, true, sol, tokens, timestamp)
+    }
+
+    /// Sells about `sol_amount` worth, limited to what buyers hold.
+    fn sell(&mut self, index: usize, user: [u8; 32], sol_amount: u64, timestamp: i64) -> TradeEventRaw {
+        let token = &mut self.tokens[index];
+        let k = token.virtual_sol as u128 * token.virtual_tokens as u128;
+        let sol = sol_amount.min(token.real_sol);
+        let tokens = (k / (token.virtual_sol - sol) as u128) as u64 - token.virtual_tokens;
+        let tokens = tokens.min(INITIAL_REAL_TOKENS - token.real_tokens);
+        token.virtual_sol -= sol;
+        token.virtual_tokens += tokens;
+        token.real_sol -= sol;
+        token.real_tokens += tokens;
+        self.trade_event(index, user, false, sol, tokens, timestamp)
+    }
+
+    fn trade_event(&self, index: usize, user: [u8; 32], is_buy: bool, sol: u64, tokens: u64, timestamp: i64) -> TradeEventRaw {
+        let token = &self.tokens[index];
+        TradeEventRaw {
+            mint: token.mint,
+            sol_amount: sol,
+            token_amount: tokens,
+            is_buy,
+            user,
+            timestamp,
+            virtual_sol_reserves: token.virtual_sol,
+            virtual_token_reserves: token.virtual_tokens,
+            real_sol_reserves: token.real_sol,
+            real_token_reserves: token.real_tokens,
+            fee_recipient: self.fee_recipient,
+            fee_basis_points: FEE_BASIS_POINTS,
+            fee: sol * FEE_BASIS_POINTS / 10_000,
+            creator: token.creator,
+            creator_fee_basis_points: CREATOR_FEE_BASIS_POINTS,
+            creator_fee: sol * CREATOR_FEE_BASIS_POINTS / 10_000,
+            track_volume: false,
+            total_unclaimed_tokens: 0,
+            total_claimed_tokens: 0,
+            current_sol_volume: 0,
+            last_update_timestamp: timestamp,
+            ix_name: if is_buy { "buy" } else { "sell" }.to_string(),
+        }
+    }
+}
+
+fn program_data(discriminator: [u8; 8], event: &impl BorshSerialize) -> String {
+    let mut data = discriminator.to_vec();
+    event.serialize(&mut data).expect("writing to a Vec can't fail");
+    format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+fn build_transaction(signature: &str, accounts: Vec<String>, logs: Vec<String>) -> EncodedTransactionWithStatusMeta {
+    serde_json::from_value(json!({
+        "transaction": {
+            "signatures": [signature],
+            "message": {
+                "header": { "numRequiredSignatures": 1, "numReadonlySignedAccounts": 0, "numReadonlyUnsignedAccounts": 3 },
+                "accountKeys": accounts,
+                "recentBlockhash": SYSTEM_PROGRAM_ID,
+                "instructions": [],
+            },
+        },
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "logMessages": logs,
+            "computeUnitsConsumed": 60000,
+        },
+        "version": "legacy",
+    }))
+    .expect("mock transaction matches the RPC format")
+}
+
+fn current_slot(previous: u64) -> u64 {
+    ((Utc::now().timestamp_millis() / SLOT_MILLIS) as u64).max(previous)
+}
+
+/// False once the processor is gone.
+fn send(
+    tx_sender: &mpsc::UnboundedSender<RawTransaction>,
+    status: &ListenerStatus,
+    metrics: &Metrics,
+    signature: String,
+    slot: u64,
+    transaction: EncodedTransactionWithStatusMeta,
+) -> bool {
+    status.touch();
+    metrics.record_seen_slot(slot);
+    let raw_tx = RawTransaction {
+        signature,
+        slot,
+        block_time: Some(Utc::now().timestamp()),
+        seq: NOTIFICATION_SEQ.fetch_add(1, Ordering::Relaxed) + 1,
+        transaction,
+    };
+    if let Err(e) = tx_sender.send(raw_tx) {
+        error!("❌ Failed to send transaction: {}", e);
+        return false;
+    }
+    metrics.channel_depth.fetch_add(1, Ordering::Relaxed);
+    true
+}
+
+/// Sends each `*.json` transaction in `dir`, in file name order. Already
+/// processed ones are skipped downstream, so a restart replays nothing new.
+async fn replay(
+    dir: &Path,
+    tx_sender: &mpsc::UnboundedSender<RawTransaction>,
+    status: &ListenerStatus,
+    metrics: &Metrics,
+    interval: Duration,
+    slot: &mut u64,
+) -> Result<()> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| IndexerError::Config(format!("Can't read mock replay directory {}: {}", dir.display(), e)))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    for path in &paths {
+        let transaction: EncodedTransactionWithStatusMeta = match std::fs::read(path)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| serde_json::from_slice(&raw).map_err(|e| e.to_string()))
+        {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                warn!("⚠️  Skipping mock fixture {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let signature = parser::transaction_signature(&transaction)
+            .map(String::from)
+            .unwrap_or_else(|| path.display().to_string());
+        *slot = current_slot(*slot);
+        if !send(tx_sender, status, metrics, signature, *slot, transaction) {
+            return Ok(());
+        }
+        tokio::time::sleep(interval).await;
+    }
+    info!("🎭 Replayed {} mock fixtures from {}", paths.len(), dir.display());
+    Ok(())
+}
+
+/// Stands in for [`super::start_listener`]: feeds the processor synthetic
+/// pump.fun transactions, after replaying `replay_dir` if set.
+pub async fn start_mock_source(
+    config: MockConfig,
+    tx_sender: mpsc::UnboundedSender<RawTransaction>,
+    status: Arc<ListenerStatus>,
+    metrics: Metrics,
+) -> Result<()> {
+    let interval = Duration::from_secs_f64(1.0 / config.transactions_per_second);
+    let mut slot = current_slot(0);
+    status.subscribed.store(true, Ordering::Relaxed);
+    info!("🎭 Mock source: {} transactions/s of synthetic pump.fun activity", config.transactions_per_second);
+
+    if let Some(dir) = &config.replay_dir {
+        replay(dir, &tx_sender, &status, &metrics, interval, &mut slot).await?;
+    }
+
+    // Seeded from the clock so restarts don't reuse mints and signatures.
+    let mut market = MockMarket::new(Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64);
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let (signature, transaction) = market.next(Utc::now().timestamp());
+        slot = current_slot(slot);
+        if !send(&tx_sender, &status, &metrics, signature, slot, transaction) {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helius::parser::PumpEvent;
+
+    #[test]
+    fn test_market_transactions_parse() {
+        let mut market = MockMarket::new(7);
+        let (mut creates, mut trades, mut completes) = (0, 0, 0);
+
+        for second in 0..5_000 {
+            let (signature, transaction) = market.next(1_700_000_000 + second);
+            let events = parser::parse_transaction(&signature, &transaction).unwrap();
+            assert!(!events.is_empty());
+            for event in events {
+                match event {
+                    PumpEvent::Create(_) => creates += 1,
+                    PumpEvent::Trade(trade) => {
+                        assert!(trade.real_token_reserves <= INITIAL_REAL_TOKENS);
+                        trades += 1;
+                    }
+                    PumpEvent::Complete(_) => completes += 1,
+                }
+            }
+        }
+
+        assert!(creates > 100 && trades == 5_000);
+        assert!(completes > 0, "no token graduated");
+    }
+}
@@ -1,9 +1,13 @@
 pub mod parser;
 pub mod extractor;
+pub mod accounts;
+pub mod raydium;
+pub mod pump_swap;
+pub mod mock;
 
 use tokio::sync::Semaphore;
 use std::sync::Arc;
-use anyhow::{Result, anyhow};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
 use futures_util::{StreamExt, SinkExt};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -15,20 +19,61 @@ use tracing::{debug, error, info, warn};
 use std::str::FromStr;
 use std::time::Duration;
 use crate::database::model::GeneralTransaction;
-use chrono::{DateTime, Utc, TimeZone};
+use crate::database::to_bigint;
+use crate::error::{IndexerError, Result};
+use crate::processor::metrics::Metrics;
+use chrono::{Utc, TimeZone};
 
 const PUMP_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+/// PumpSwap, where tokens trade after graduating.
+const PUMP_AMM_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Numbers notifications across reconnects, for `RawTransaction::seq`.
+static NOTIFICATION_SEQ: AtomicU64 = AtomicU64::new(0);
+
+pub fn rpc_url(api_key: &str) -> String {
+    format!("https://mainnet.helius-rpc.com/?api-key={}", api_key)
+}
+
+/// Connection state of the logs subscription, shared with the health checks.
+#[derive(Debug, Default)]
+pub struct ListenerStatus {
+    subscribed: AtomicBool,
+    last_message_at: AtomicI64,
+}
+
+impl ListenerStatus {
+    pub fn is_subscribed(&self) -> bool {
+        self.subscribed.load(Ordering::Relaxed)
+    }
+
+    /// Unix timestamp of the last frame received from Helius, if any.
+    pub fn last_message_at(&self) -> Option<i64> {
+        match self.last_message_at.load(Ordering::Relaxed) {
+            0 => None,
+            ts => Some(ts),
+        }
+    }
+
+    fn touch(&self) {
+        self.last_message_at.store(Utc::now().timestamp(), Ordering::Relaxed);
+    }
+}
 
 #[derive(Debug)]
 pub struct RawTransaction {
     pub signature: String,
     pub slot: u64,
     pub block_time: Option<i64>, // 🔥 NEW: Store block_time separately
+    /// Order the logs notification arrived in; orders transactions within
+    /// a slot. Zero for transactions fetched outside the listener.
+    pub seq: u64,
     pub transaction: EncodedTransactionWithStatusMeta,
 }
 
 impl RawTransaction {
-    pub fn to_general_transaction(&self) -> GeneralTransaction {
+    pub fn to_general_transaction(&self) -> Result<GeneralTransaction> {
         let meta = self.transaction.meta.as_ref();
         
         let success = meta
@@ -41,9 +86,10 @@ impl RawTransaction {
         
         let compute_units = meta
             .and_then(|m| match m.compute_units_consumed {
-                solana_transaction_status::option_serializer::OptionSerializer::Some(units) => Some(units as i64),
+                solana_transaction_status::option_serializer::OptionSerializer::Some(units) => Some(to_bigint(units, "compute_units_consumed")),
                 _ => None,
-            });
+            })
+            .transpose()?;
         
         let log_messages = meta
             .and_then(|m| match &m.log_messages {
@@ -64,9 +110,7 @@ impl RawTransaction {
                             .collect()
                     }
                     solana_transaction_status::UiMessage::Raw(raw_msg) => {
-                        raw_msg.account_keys.iter()
-                            .map(|k| k.clone())
-                            .collect()
+                        raw_msg.account_keys.to_vec()
                     }
                 }
             }
@@ -76,11 +120,12 @@ impl RawTransaction {
         let signer = accounts_involved.first().cloned().unwrap_or_default();
         
         let (pre_balances, post_balances) = meta
-            .map(|m| {
-                let pre = m.pre_balances.iter().map(|b| *b as i64).collect();
-                let post = m.post_balances.iter().map(|b| *b as i64).collect();
-                (pre, post)
+            .map(|m| -> Result<_> {
+                let pre = m.pre_balances.iter().map(|&b| to_bigint(b, "pre_balance")).collect::<Result<_>>()?;
+                let post = m.post_balances.iter().map(|&b| to_bigint(b, "post_balance")).collect::<Result<_>>()?;
+                Ok((pre, post))
             })
+            .transpose()?
             .unwrap_or_default();
         let error_message = meta
             .and_then(|m| m.err.as_ref())
@@ -88,9 +133,9 @@ impl RawTransaction {
         
         let block_time = self.block_time
             .map(|ts| Utc.timestamp_opt(ts, 0).unwrap())
-            .unwrap_or_else(|| Utc::now());
+            .unwrap_or_else(Utc::now);
         
-        GeneralTransaction {
+        Ok(GeneralTransaction {
             signature: self.signature.clone(),
             slot: self.slot,
             block_time,
@@ -105,7 +150,7 @@ impl RawTransaction {
             post_balances,
             compute_units_consumed: compute_units,
             error_message,
-        }
+        })
     }
 }
 
@@ -119,7 +164,6 @@ struct RpcRequest {
 
 #[derive(Debug, Deserialize)]
 struct RpcResponse {
-    jsonrpc: String,
     #[serde(default)]
     id: u64,
     #[serde(default)]
@@ -134,7 +178,6 @@ struct RpcResponse {
 
 #[derive(Debug, Deserialize)]
 struct LogsNotification {
-    subscription: u64,
     result: LogsNotificationResult,
 }
 
@@ -160,6 +203,8 @@ struct LogsValue {
 pub async fn start_listener(
     api_key: String,
     tx_sender: mpsc::UnboundedSender<RawTransaction>,
+    status: Arc<ListenerStatus>,
+    metrics: Metrics,
 ) -> Result<()> {
     info!("Connecting to Helius WebSocket...");
     
@@ -169,7 +214,7 @@ pub async fn start_listener(
     let (ws_stream, response) = connect_async(&ws_url).await
         .map_err(|e| {
             error!("Failed to connect to WebSocket: {}", e);
-            anyhow!("WebSocket connection failed: {}", e)
+            IndexerError::Rpc(format!("WebSocket connection failed: {}", e))
         })?;
 
     info!("WebSocket connected!");
@@ -177,36 +222,44 @@ pub async fn start_listener(
 
     let (mut write, mut read) = ws_stream.split();
 
-    let subscribe_request = RpcRequest {
-        jsonrpc: "2.0".to_string(),
-        id: 1,
-        method: "logsSubscribe".to_string(),
-        params: json!([
-            {
-                "mentions": [PUMP_PROGRAM_ID]
-            },
-            {
-                "commitment": "confirmed"
-            }
-        ]),
-    };
+    // `mentions` takes a single address, so each parsed program gets its
+    // own subscription, numbered by request id.
+    for (id, program) in (1..).zip(parser::PARSERS.iter().map(|p| p.program_id())) {
+        let subscribe_request = RpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id,
+            method: "logsSubscribe".to_string(),
+            params: json!([
+                {
+                    "mentions": [program]
+                },
+                {
+                    "commitment": "confirmed"
+                }
+            ]),
+        };
 
-    let subscribe_msg = serde_json::to_string(&subscribe_request)?;
-    
-    info!("📡 Subscribing to pump.fun program logs: {}", PUMP_PROGRAM_ID);
-    
-    write.send(Message::Text(subscribe_msg.into())).await
-        .map_err(|e| anyhow!("Failed to send subscription: {}", e))?;
+        let subscribe_msg = serde_json::to_string(&subscribe_request)
+            .map_err(|e| IndexerError::Rpc(format!("Failed to encode subscription: {}", e)))?;
+
+        info!("📡 Subscribing to program logs: {}", program);
+
+        write.send(Message::Text(subscribe_msg.into())).await
+            .map_err(|e| IndexerError::Rpc(format!("Failed to send subscription: {}", e)))?;
+    }
 
     info!("Subscription request sent");
     info!("Listening for transactions...");
 
-    let mut subscription_id: Option<u64> = None;
     let mut tx_count = 0;
 
     let semaphore = Arc::new(Semaphore::new(5));
 
     while let Some(msg) = read.next().await {
+        if msg.is_ok() {
+            status.touch();
+        }
+
         match msg {
             Ok(Message::Text(text)) => {
                 match serde_json::from_str::<RpcResponse>(&text) {
@@ -216,112 +269,124 @@ pub async fn start_listener(
                             continue;
                         }
 
-                        if response.id == 1 && response.result.is_some() {
-                            subscription_id = response.result.as_ref()
+                        if (1..=parser::PARSERS.len() as u64).contains(&response.id) && response.result.is_some() {
+                            let subscription_id = response.result.as_ref()
                                 .and_then(|v| v.as_u64());
                             
+                            status.subscribed.store(true, Ordering::Relaxed);
                             info!("✅ Logs subscription confirmed!");
                             info!("   Subscription ID: {:?}", subscription_id);
                             info!("   Waiting for pump.fun events...");
                             continue;
                         }
                        
-                        if response.method.as_deref() == Some("logsNotification") {
-                            if let Some(params) = response.params {
-                                match serde_json::from_value::<LogsNotification>(params) {
-                                    Ok(notification) => {
-                                        if notification.result.value.err.is_some() {
-                                            continue;
-                                        }
+                        if response.method.as_deref() == Some("logsNotification")
+                            && let Some(params) = response.params
+                        {
+                            match serde_json::from_value::<LogsNotification>(params) {
+                                Ok(notification) => {
+                                    if notification.result.value.err.is_some() {
+                                        continue;
+                                    }
 
-                                        let signature = notification.result.value.signature.clone();
-                                        
-                                        let has_pump_event = notification.result.value.logs.iter().any(|log| {
-                                            log.contains("Program data:")
-                                        });
+                                    metrics.record_seen_slot(notification.result.context.slot);
 
-                                        if !has_pump_event {
-                                            continue;
-                                        }
+                                    let signature = notification.result.value.signature.clone();
+                                    
+                                    // PumpSwap may carry its events in CPI data
+                                    // instead of logs, so swaps are kept by name.
+                                    let has_pump_event = notification.result.value.logs.iter().any(|log| {
+                                        log.contains("Program data:")
+                                            || log == "Program log: Instruction: Buy"
+                                            || log == "Program log: Instruction: Sell"
+                                    });
 
-                                        tx_count += 1;
-                                        
-                                        if tx_count == 1 {
-                                            info!("🎉 First pump.fun event detected!");
-                                        }
+                                    if !has_pump_event {
+                                        continue;
+                                    }
+
+                                    tx_count += 1;
+                                    
+                                    if tx_count == 1 {
+                                        info!("🎉 First pump.fun event detected!");
+                                    }
+                                    
+                                    if tx_count % 10 == 0 {
+                                        info!("📊 Progress: {} detected (slot {})", tx_count, notification.result.context.slot);
+                                    }
+                                    
+                                    let fetch_signature = signature.clone();
+                                    let fetch_rpc_url = rpc_url(&api_key);
+                                    let fetch_sender = tx_sender.clone();
+                                    let fetch_tx_count = tx_count;
+                                    let fetch_seq = NOTIFICATION_SEQ.fetch_add(1, Ordering::Relaxed) + 1;
+                                    let permit = semaphore.clone();
+                                    let fetch_metrics = metrics.clone();
+                                    
+                                    tokio::spawn(async move {
+                                        let _permit = permit.acquire().await.unwrap();
                                         
-                                        if tx_count % 10 == 0 {
-                                            info!("📊 Progress: {} detected", tx_count);
-                                        }
+                                        let fetch_rpc = solana_client::rpc_client::RpcClient::new_with_commitment(
+                                            fetch_rpc_url,
+                                            CommitmentConfig::confirmed(),
+                                        );
                                         
-                                        let fetch_signature = signature.clone();
-                                        let fetch_rpc_url = format!("https://mainnet.helius-rpc.com/?api-key={}", api_key);
-                                        let fetch_sender = tx_sender.clone();
-                                        let fetch_tx_count = tx_count;
-                                        let permit = semaphore.clone();
+                                        tokio::time::sleep(Duration::from_secs(2)).await;
                                         
-                                        tokio::spawn(async move {
-                                            let _permit = permit.acquire().await.unwrap();
-                                            
-                                            let fetch_rpc = solana_client::rpc_client::RpcClient::new_with_commitment(
-                                                fetch_rpc_url,
-                                                CommitmentConfig::confirmed(),
-                                            );
-                                            
-                                            tokio::time::sleep(Duration::from_secs(2)).await;
-                                            
-                                            for attempt in 1..=3 {
-                                                match Signature::from_str(&fetch_signature) {
-                                                    Ok(sig) => {
-                                                        let config = solana_client::rpc_config::RpcTransactionConfig {
-                                                            encoding: Some(solana_transaction_status::UiTransactionEncoding::JsonParsed),
-                                                            commitment: Some(CommitmentConfig::confirmed()),
-                                                            max_supported_transaction_version: Some(0), 
-                                                        };
+                                        for attempt in 1..=3 {
+                                            match Signature::from_str(&fetch_signature) {
+                                                Ok(sig) => {
+                                                    let config = solana_client::rpc_config::RpcTransactionConfig {
+                                                        encoding: Some(solana_transaction_status::UiTransactionEncoding::JsonParsed),
+                                                        commitment: Some(CommitmentConfig::confirmed()),
+                                                        max_supported_transaction_version: Some(0), 
+                                                    };
+                                                    
+                                                    match fetch_rpc.get_transaction_with_config(&sig, config) {
+                                                        Ok(tx_response) => {
                                                         
-                                                        match fetch_rpc.get_transaction_with_config(&sig, config) {
-                                                            Ok(tx_response) => {
-                                                            
-                                                                let raw_tx = RawTransaction {
-                                                                    signature: fetch_signature.clone(),
-                                                                    slot: tx_response.slot,
-                                                                    block_time: tx_response.block_time,
-                                                                    transaction: tx_response.transaction,
-                                                                };
-
-                                                                if let Err(e) = fetch_sender.send(raw_tx) {
-                                                                    error!("❌ Failed to send transaction: {}", e);
-                                                                } else {
-                                                                    info!("✅ TX #{}: {} (attempt {})", 
-                                                                        fetch_tx_count, 
-                                                                        &fetch_signature[..8], 
-                                                                        attempt);
-                                                                }
-                                                                break;
+                                                            let raw_tx = RawTransaction {
+                                                                signature: fetch_signature.clone(),
+                                                                slot: tx_response.slot,
+                                                                block_time: tx_response.block_time,
+                                                                seq: fetch_seq,
+                                                                transaction: tx_response.transaction,
+                                                            };
+
+                                                            if let Err(e) = fetch_sender.send(raw_tx) {
+                                                                error!("❌ Failed to send transaction: {}", e);
+                                                            } else {
+                                                                fetch_metrics.channel_depth.fetch_add(1, Ordering::Relaxed);
+                                                                info!("✅ TX #{}: {} (attempt {})", 
+                                                                    fetch_tx_count, 
+                                                                    &fetch_signature[..8], 
+                                                                    attempt);
                                                             }
-                                                            Err(e) => {
-                                                                if attempt < 3 {
-                                                                    debug!("Retry {}/3 for {}...: {}", 
-                                                                        attempt, &fetch_signature[..8], e);
-                                                                    tokio::time::sleep(Duration::from_secs(2)).await;
-                                                                } else {
-                                                                    warn!("⚠️ Skipped {}... after 3 attempts", 
-                                                                        &fetch_signature[..8]);
-                                                                }
+                                                            break;
+                                                        }
+                                                        Err(e) => {
+                                                            fetch_metrics.increment_rpc_errors();
+                                                            if attempt < 3 {
+                                                                debug!("Retry {}/3 for {}...: {}", 
+                                                                    attempt, &fetch_signature[..8], e);
+                                                                tokio::time::sleep(Duration::from_secs(2)).await;
+                                                            } else {
+                                                                warn!("⚠️ Skipped {}... after 3 attempts", 
+                                                                    &fetch_signature[..8]);
                                                             }
                                                         }
                                                     }
-                                                    Err(e) => {
-                                                        warn!("⚠️ Invalid signature: {}", e);
-                                                        break;
-                                                    }
+                                                }
+                                                Err(e) => {
+                                                    warn!("⚠️ Invalid signature: {}", e);
+                                                    break;
                                                 }
                                             }
-                                        });
-                                    }
-                                    Err(e) => {
-                                        debug!("Failed to parse notification: {}", e);
-                                    }
+                                        }
+                                    });
+                                }
+                                Err(e) => {
+                                    debug!("Failed to parse notification: {}", e);
                                 }
                             }
                         }
@@ -347,6 +412,7 @@ pub async fn start_listener(
         }
     }
 
+    status.subscribed.store(false, Ordering::Relaxed);
     warn!("⚠️ WebSocket stream ended");
     Ok(())
 }
\ No newline at end of file
@@ -1,111 +1,307 @@
-use crate::database::model::{CreateEvent, TradeEventData, CompleteEvent};
-use anyhow::{Result, anyhow};
+use crate::database::model::{CreateEvent, TradeEventData, CompleteEvent, CreatorFeeClaim};
+use crate::error::{IndexerError, Result};
+use base64::Engine;
 use borsh::BorshDeserialize;
 use solana_sdk::pubkey::Pubkey;
 use solana_transaction_status::{
+  EncodedTransaction,
   EncodedTransactionWithStatusMeta,
+  UiInstruction,
+  UiMessage,
+  UiParsedInstruction,
   option_serializer::OptionSerializer,
 };
 use tracing::{debug, warn, info, error};
+use super::PUMP_PROGRAM_ID;
+use super::pump_swap::PumpSwapParser;
 
 
-const CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
-const TRADE_EVENT_DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
-const COMPLETE_EVENT_DISCRIMINATOR: [u8; 8] = [95, 114, 97, 156, 212, 46, 152, 8];
+pub(super) const CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+pub(super) const TRADE_EVENT_DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+pub(super) const COMPLETE_EVENT_DISCRIMINATOR: [u8; 8] = [95, 114, 97, 156, 212, 46, 152, 8];
+const COLLECT_CREATOR_FEE_EVENT_DISCRIMINATOR: [u8; 8] = [122, 2, 127, 1, 14, 191, 12, 175];
+/// Prefix of the self-CPI instructions Anchor's `emit_cpi!` carries events in.
+const EVENT_IX_TAG: [u8; 8] = [228, 69, 165, 46, 81, 203, 154, 29];
 
-#[derive(Debug, Clone)]
+/// Serializes as the bare inner event; consumers tell kinds apart by `kind()`.
+/// Every [`ProgramParser`] decodes into these, whichever launchpad or AMM
+/// the event came from.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged)]
 pub enum PumpEvent {
   Create(CreateEvent),
   Trade(TradeEventData),
   Complete(CompleteEvent),
 }
 
+impl PumpEvent {
+  pub fn mint(&self) -> &str {
+    match self {
+      PumpEvent::Create(e) => &e.mint,
+      PumpEvent::Trade(e) => &e.mint,
+      PumpEvent::Complete(e) => &e.mint,
+    }
+  }
+
+  pub fn kind(&self) -> &'static str {
+    match self {
+      PumpEvent::Create(_) => "create",
+      PumpEvent::Trade(_) => "trade",
+      PumpEvent::Complete(_) => "complete",
+    }
+  }
+
+  /// Rebuilds an event from its `kind()` and serialized form, since the
+  /// untagged serialization alone doesn't say which variant it was.
+  pub fn from_parts(kind: &str, payload: &str) -> serde_json::Result<Self> {
+    Ok(match kind {
+      "create" => PumpEvent::Create(serde_json::from_str(payload)?),
+      "trade" => PumpEvent::Trade(serde_json::from_str(payload)?),
+      "complete" => PumpEvent::Complete(serde_json::from_str(payload)?),
+      other => return Err(serde::de::Error::custom(format!("unknown event kind {}", other))),
+    })
+  }
+}
+
+/// The transaction an event payload was found in, for parsers that need
+/// more than the payload (e.g. accounts of the instruction that emitted it).
+pub struct ParseContext<'a> {
+  pub signature: &'a str,
+  pub transaction: &'a EncodedTransactionWithStatusMeta,
+}
+
+/// Decodes one program's Anchor events into [`PumpEvent`]s. Supporting
+/// another launchpad means adding an implementation to [`PARSERS`]: the
+/// listener subscribes to every parser's program and [`parse_transaction`]
+/// runs them all.
+pub trait ProgramParser: Send + Sync {
+  /// Short name for logs.
+  fn name(&self) -> &'static str;
+  fn program_id(&self) -> &'static str;
+  /// Discriminators of the events `decode` handles.
+  fn discriminators(&self) -> &'static [[u8; 8]];
+  /// Decodes an event payload, discriminator included. Only called with
+  /// payloads whose discriminator is one of `discriminators()`.
+  fn decode(&self, data: &[u8], context: &ParseContext) -> Option<PumpEvent>;
+}
+
+/// Every program indexed, in the order their events are numbered within a
+/// transaction.
+pub static PARSERS: &[&dyn ProgramParser] = &[&PumpFunParser, &PumpSwapParser];
+
 pub fn parse_transaction(
   signature: &str,
   transaction: &EncodedTransactionWithStatusMeta,
 ) -> Result<Vec<PumpEvent>> {
-  let mut events = Vec::new();
-
   debug!("🔍 Parsing transaction: {}", signature);
 
   let meta = transaction.meta.as_ref()
     .ok_or_else(|| {
       error!("❌ Transaction {} has no meta", signature);
-      anyhow!("Transaction has no meta")
+      IndexerError::Parse("Transaction has no meta".to_string())
     })?;
 
-  debug!("✅ Transaction meta found");
-
   match &meta.log_messages {
-    OptionSerializer::Some(log_messages) => {
-      debug!("📋 Found {} log messages", log_messages.len());
-      
-      let mut program_data_count = 0;
-      let mut pump_event_count = 0;
-      
-      for (idx, log) in log_messages.iter().enumerate() {
-        debug!("  Log[{}]: {}", idx, log);
-        
-        if log.starts_with("Program data: ") {
-          program_data_count += 1;
-          debug!("🎯 Found 'Program data:' at log index {}", idx);
-          
-          if let Some(event) = parse_event_from_log(log, signature) {
-            pump_event_count += 1;
-            info!("✨ Extracted pump.fun event #{} from log index {}", pump_event_count, idx);
-            events.push(event);
-          } else {
-            debug!("⚠️  'Program data:' at index {} is not a pump.fun event", idx);
-          }
+    OptionSerializer::Some(log_messages) => debug!("📋 Found {} log messages", log_messages.len()),
+    OptionSerializer::None => warn!("⚠️  Transaction {} has no log messages", signature),
+    OptionSerializer::Skip => warn!("⚠️  Transaction {} log messages were skipped", signature),
+  }
+
+  let context = ParseContext { signature, transaction };
+  let mut events = Vec::new();
+
+  for parser in PARSERS {
+    let handles = |data: &Vec<u8>| {
+      data.len() > 8 && parser.discriminators().iter().any(|d| data[..8] == *d)
+    };
+
+    // Depending on the program version events are logged or only carried
+    // in self-CPI instructions; never count both.
+    let mut payloads: Vec<Vec<u8>> = program_payloads(transaction, parser.program_id())
+      .into_iter()
+      .filter(handles)
+      .collect();
+    if payloads.is_empty() {
+      payloads = cpi_payloads(transaction, parser.program_id())
+        .into_iter()
+        .filter(handles)
+        .collect();
+    }
+
+    for data in &payloads {
+      match parser.decode(data, &context) {
+        Some(event) => {
+          info!("✨ Extracted {} {} event for {}", parser.name(), event.kind(), event.mint());
+          events.push(event);
         }
-      }
-      
-      if program_data_count == 0 {
-        debug!("ℹ️  No 'Program data:' logs found in transaction {}", signature);
-      } else {
-        info!("📊 TX {}: Found {} 'Program data:' logs, {} pump.fun events", 
-              signature, program_data_count, pump_event_count);
+        None => debug!("⚠️  {} event payload in {} was not decoded", parser.name(), signature),
       }
     }
-    OptionSerializer::None => {
-      warn!("⚠️  Transaction {} has no log messages", signature);
-    }
-    OptionSerializer::Skip => {
-      warn!("⚠️  Transaction {} log messages were skipped", signature);
-    }
   }
 
   if events.is_empty() {
-    debug!("ℹ️  No pump.fun events found in transaction {}", signature);
+    debug!("ℹ️  No events found in transaction {}", signature);
   } else {
-    info!("✅ TX {}: Successfully extracted {} pump.fun events", signature, events.len());
+    info!("✅ TX {}: Successfully extracted {} events", signature, events.len());
   }
 
   Ok(events)
 }
 
-fn parse_event_from_log(log: &str, signature: &str) -> Option<PumpEvent> {
-  debug!("🔎 Attempting to parse event from log");
+/// Whether the transaction emitted a pump.fun Create event, without
+/// decoding the rest of its events.
+pub fn contains_create_event(transaction: &EncodedTransactionWithStatusMeta) -> bool {
+  program_payloads(transaction, PUMP_PROGRAM_ID)
+    .iter()
+    .any(|data| data.len() > 8 && data[..8] == CREATE_EVENT_DISCRIMINATOR)
+}
+
+/// Creator fee claims aren't tied to a mint, so they're extracted
+/// separately from [`PumpEvent`]s.
+pub fn parse_creator_fee_claims(transaction: &EncodedTransactionWithStatusMeta) -> Vec<CreatorFeeClaim> {
+  #[derive(BorshDeserialize)]
+  struct CollectCreatorFeeEventRaw {
+    timestamp: i64,
+    creator: [u8; 32],
+    creator_fee: u64,
+  }
+
+  program_payloads(transaction, PUMP_PROGRAM_ID)
+    .iter()
+    .filter(|data| data.len() > 8 && data[..8] == COLLECT_CREATOR_FEE_EVENT_DISCRIMINATOR)
+    .filter_map(|data| CollectCreatorFeeEventRaw::deserialize(&mut &data[8..]).ok())
+    .map(|raw| CreatorFeeClaim {
+      creator: Pubkey::new_from_array(raw.creator).to_string(),
+      creator_fee: raw.creator_fee,
+      timestamp: raw.timestamp,
+    })
+    .collect()
+}
+
+/// Decoded `Program data:` logs, in log order, with the program that
+/// logged them. Events are attributed by the invoke stack rather than by
+/// discriminator alone, since programs can share event names.
+fn logged_payloads(transaction: &EncodedTransactionWithStatusMeta) -> Vec<(&str, Vec<u8>)> {
+  match transaction.meta.as_ref().map(|m| &m.log_messages) {
+    Some(OptionSerializer::Some(log_messages)) => attribute_program_data(log_messages),
+    _ => Vec::new(),
+  }
+}
+
+fn attribute_program_data(log_messages: &[String]) -> Vec<(&str, Vec<u8>)> {
+  let mut stack: Vec<&str> = Vec::new();
+  let mut payloads = Vec::new();
+  for log in log_messages {
+    if let Some(data) = log.strip_prefix("Program data: ") {
+      if let (Some(program), Some(bytes)) = (stack.last(), decode_program_data(data)) {
+        payloads.push((*program, bytes));
+      }
+    } else if let Some(rest) = log.strip_prefix("Program ") {
+      let mut words = rest.split(' ');
+      match (words.next(), words.next()) {
+        (Some(program), Some("invoke")) => stack.push(program),
+        (Some(_), Some("success" | "failed:")) => {
+          stack.pop();
+        }
+        _ => {}
+      }
+    }
+  }
+  payloads
+}
+
+/// Whether any indexed program ran in the transaction, judged from its logs
+/// so whole blocks can be filtered before parsing.
+pub fn invokes_indexed_program(transaction: &EncodedTransactionWithStatusMeta) -> bool {
+  match transaction.meta.as_ref().map(|m| &m.log_messages) {
+    Some(OptionSerializer::Some(log_messages)) => logs_invoke_indexed_program(log_messages),
+    _ => false,
+  }
+}
+
+fn logs_invoke_indexed_program(log_messages: &[String]) -> bool {
+  log_messages.iter().any(|log| {
+    log.strip_prefix("Program ")
+      .and_then(|rest| rest.split_once(" invoke ["))
+      .is_some_and(|(program, _)| PARSERS.iter().any(|p| p.program_id() == program))
+  })
+}
+
+/// The transaction's first signature, which identifies it.
+pub fn transaction_signature(transaction: &EncodedTransactionWithStatusMeta) -> Option<&str> {
+  match &transaction.transaction {
+    EncodedTransaction::Json(ui_tx) => ui_tx.signatures.first().map(String::as_str),
+    _ => None,
+  }
+}
+
+/// Logged event payloads of one program.
+fn program_payloads(transaction: &EncodedTransactionWithStatusMeta, program_id: &str) -> Vec<Vec<u8>> {
+  logged_payloads(transaction)
+    .into_iter()
+    .filter(|(program, _)| *program == program_id)
+    .map(|(_, data)| data)
+    .collect()
+}
+
+/// Event payloads a program emitted through self-CPI instructions.
+fn cpi_payloads(transaction: &EncodedTransactionWithStatusMeta, program_id: &str) -> Vec<Vec<u8>> {
+  program_instructions(transaction, program_id)
+    .into_iter()
+    .filter_map(|(_, data)| data.strip_prefix(EVENT_IX_TAG.as_slice()).map(<[u8]>::to_vec))
+    .collect()
+}
 
-  let data_str = log.strip_prefix("Program data: ")?;
-  debug!("📦 Encoded data: {} (length: {} chars)", 
-         if data_str.len() > 50 { 
-           format!("{}...", &data_str[..50]) 
-         } else { 
-           data_str.to_string() 
-         }, 
-         data_str.len());
+/// Accounts and data of every instruction of `program_id` in the
+/// transaction, top-level or inner.
+pub(super) fn program_instructions(
+  transaction: &EncodedTransactionWithStatusMeta,
+  program_id: &str,
+) -> Vec<(Vec<String>, Vec<u8>)> {
+  let EncodedTransaction::Json(ui_tx) = &transaction.transaction else {
+    return Vec::new();
+  };
+  let (account_keys, top_level): (Vec<String>, &[UiInstruction]) = match &ui_tx.message {
+    UiMessage::Parsed(message) => (message.account_keys.iter().map(|k| k.pubkey.clone()).collect(), &message.instructions),
+    UiMessage::Raw(message) => (message.account_keys.clone(), &[]),
+  };
+  let inner = match transaction.meta.as_ref().map(|m| &m.inner_instructions) {
+    Some(OptionSerializer::Some(inner)) => inner.iter().flat_map(|i| i.instructions.iter()).collect(),
+    _ => Vec::new(),
+  };
 
-  let event_data = if data_str.contains('/') || data_str.contains('+') || data_str.contains('=') {
+  top_level
+    .iter()
+    .chain(inner)
+    .filter_map(|instruction| {
+      let (program, accounts, data) = match instruction {
+        UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(ix)) => (ix.program_id.clone(), ix.accounts.clone(), &ix.data),
+        UiInstruction::Compiled(ix) => (
+          account_keys.get(ix.program_id_index as usize)?.clone(),
+          ix.accounts.iter().filter_map(|&i| account_keys.get(i as usize).cloned()).collect(),
+          &ix.data,
+        ),
+        UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => return None,
+      };
+      if program != program_id {
+        return None;
+      }
+      Some((accounts, bs58::decode(data).into_vec().ok()?))
+    })
+    .collect()
+}
+
+fn decode_program_data(data_str: &str) -> Option<Vec<u8>> {
+  if data_str.contains('/') || data_str.contains('+') || data_str.contains('=') {
     debug!("🔧 Detected base64 encoding");
-    match base64::decode(data_str) {
+    match base64::engine::general_purpose::STANDARD.decode(data_str) {
       Ok(bytes) => {
         debug!("✅ Decoded {} bytes from base64", bytes.len());
-        bytes
+        Some(bytes)
       }
       Err(e) => {
         error!("❌ Failed to decode base64: {}", e);
-        return None;
+        None
       }
     }
   } else {
@@ -113,70 +309,81 @@ fn parse_event_from_log(log: &str, signature: &str) -> Option<PumpEvent> {
     match bs58::decode(data_str).into_vec() {
       Ok(bytes) => {
         debug!("✅ Decoded {} bytes from base58", bytes.len());
-        bytes
+        Some(bytes)
       }
       Err(e) => {
         error!("❌ Failed to decode base58: {}", e);
-        return None;
+        None
       }
     }
-  };
+  }
+}
+
+/// The pump.fun bonding curve program.
+pub struct PumpFunParser;
 
-  if event_data.len() < 8 {
-    warn!("⚠️  Event data too short: {} bytes (need at least 8)", event_data.len());
-    return None;
+impl ProgramParser for PumpFunParser {
+  fn name(&self) -> &'static str {
+    "pump.fun"
   }
 
-  let discriminator: [u8; 8] = event_data[0..8].try_into().ok()?;
-  debug!("🔑 Discriminator: {:?}", discriminator);
+  fn program_id(&self) -> &'static str {
+    PUMP_PROGRAM_ID
+  }
 
-  match discriminator {
-    CREATE_EVENT_DISCRIMINATOR => {
-      info!("🎉 CREATE event discriminator matched!");
-      match parse_create_event(&event_data[8..]) {
-        Some(event) => {
-          info!("✅ Successfully parsed CREATE event: token={}, symbol={}", 
-                event.mint, event.symbol);
-          Some(PumpEvent::Create(event))
-        }
-        None => {
-          error!("❌ Failed to deserialize CREATE event data");
-          None
+  fn discriminators(&self) -> &'static [[u8; 8]] {
+    &[CREATE_EVENT_DISCRIMINATOR, TRADE_EVENT_DISCRIMINATOR, COMPLETE_EVENT_DISCRIMINATOR]
+  }
+
+  fn decode(&self, data: &[u8], context: &ParseContext) -> Option<PumpEvent> {
+    let discriminator: [u8; 8] = data.get(..8)?.try_into().ok()?;
+    debug!("🔑 Discriminator: {:?}", discriminator);
+
+    match discriminator {
+      CREATE_EVENT_DISCRIMINATOR => {
+        info!("🎉 CREATE event discriminator matched!");
+        match parse_create_event(&data[8..]) {
+          Some(event) => {
+            info!("✅ Successfully parsed CREATE event: token={}, symbol={}", 
+                  event.mint, event.symbol);
+            Some(PumpEvent::Create(event))
+          }
+          None => {
+            error!("❌ Failed to deserialize CREATE event data");
+            None
+          }
         }
       }
-    }
-    TRADE_EVENT_DISCRIMINATOR => {
-      info!("💰 TRADE event discriminator matched!");
-      match parse_trade_event(&event_data[8..], signature) {
-        Some(event) => {
-          info!("✅ Successfully parsed TRADE event: {} {} tokens for {} SOL", 
-                if event.is_buy { "BUY" } else { "SELL" },
-                event.token_amount as f64 / 1_000_000.0,
-                event.sol_amount as f64 / 1_000_000_000.0);
-          Some(PumpEvent::Trade(event))
-        }
-        None => {
-          error!("❌ Failed to deserialize TRADE event data");
-          None
+      TRADE_EVENT_DISCRIMINATOR => {
+        info!("💰 TRADE event discriminator matched!");
+        match parse_trade_event(&data[8..], context.signature) {
+          Some(event) => {
+            info!("✅ Successfully parsed TRADE event: {} {} tokens for {} SOL", 
+                  if event.is_buy { "BUY" } else { "SELL" },
+                  event.token_amount as f64 / 1_000_000.0,
+                  event.sol_amount as f64 / 1_000_000_000.0);
+            Some(PumpEvent::Trade(event))
+          }
+          None => {
+            error!("❌ Failed to deserialize TRADE event data");
+            None
+          }
         }
       }
-    }
-    COMPLETE_EVENT_DISCRIMINATOR => {
-      info!("🏁 COMPLETE event discriminator matched!");
-      match parse_complete_event(&event_data[8..]) {
-        Some(event) => {
-          info!("✅ Successfully parsed COMPLETE event: token={}", event.mint);
-          Some(PumpEvent::Complete(event))
-        }
-        None => {
-          error!("❌ Failed to deserialize COMPLETE event data");
-          None
+      COMPLETE_EVENT_DISCRIMINATOR => {
+        info!("🏁 COMPLETE event discriminator matched!");
+        match parse_complete_event(&data[8..]) {
+          Some(event) => {
+            info!("✅ Successfully parsed COMPLETE event: token={}", event.mint);
+            Some(PumpEvent::Complete(event))
+          }
+          None => {
+            error!("❌ Failed to deserialize COMPLETE event data");
+            None
+          }
         }
       }
-    }
-    _ => {
-      debug!("❓ Unknown discriminator: {:?} (not a pump.fun event)", discriminator);
-      None
+      _ => None,
     }
   }
 }
@@ -345,4 +552,62 @@ fn parse_complete_event(data: &[u8]) -> Option<CompleteEvent> {
     bonding_curve: Pubkey::new_from_array(raw.bonding_curve).to_string(),
     timestamp: raw.timestamp,
   })
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_attribute_program_data() {
+    let logs: Vec<String> = [
+      "Program pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA invoke [1]",
+      "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [2]",
+      "Program data: 2",
+      "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P consumed 1000 of 200000 compute units",
+      "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P success",
+      "Program data: 3",
+      "Program pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA success",
+      "Program data: 4",
+    ].map(String::from).to_vec();
+
+    // A payload outside any invocation can't be attributed and is dropped.
+    assert_eq!(
+      attribute_program_data(&logs),
+      [(PUMP_PROGRAM_ID, vec![1]), ("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA", vec![2])],
+    );
+  }
+
+  #[test]
+  fn test_truncated_payloads_dont_panic() {
+    let transaction: EncodedTransactionWithStatusMeta = serde_json::from_value(serde_json::json!({
+      "transaction": ["", "base64"],
+      "meta": null,
+    })).unwrap();
+    let context = ParseContext { signature: "sig", transaction: &transaction };
+
+    for parser in PARSERS {
+      for discriminator in parser.discriminators() {
+        // All-ones bytes also make every length prefix huge and every amount u64::MAX.
+        let mut payload = discriminator.to_vec();
+        payload.resize(600, 0xff);
+        for len in 0..=payload.len() {
+          parser.decode(&payload[..len], &context);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_logs_invoke_indexed_program() {
+    let logs = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+    assert!(logs_invoke_indexed_program(&logs(&[
+      "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+      "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [2]",
+    ])));
+    assert!(!logs_invoke_indexed_program(&logs(&[
+      "Program 11111111111111111111111111111111 invoke [1]",
+      "Program log: 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]",
+    ])));
+  }
+}
@@ -0,0 +1,182 @@
+//! PumpSwap, the AMM pump.fun tokens migrate to when they graduate.
+
+use crate::database::model::TradeEventData;
+use borsh::BorshDeserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use tracing::{debug, error};
+use super::parser::{self, ParseContext, ProgramParser, PumpEvent};
+use super::{PUMP_AMM_PROGRAM_ID, WSOL_MINT};
+
+const AMM_BUY_EVENT_DISCRIMINATOR: [u8; 8] = [103, 244, 82, 31, 44, 245, 119, 119];
+const AMM_SELL_EVENT_DISCRIMINATOR: [u8; 8] = [62, 47, 55, 10, 165, 3, 220, 42];
+/// Positions in the buy/sell instruction accounts.
+const AMM_POOL_ACCOUNT: usize = 0;
+const AMM_BASE_MINT_ACCOUNT: usize = 3;
+const AMM_QUOTE_MINT_ACCOUNT: usize = 4;
+
+/// Swaps on PumpSwap SOL pools, as trades whose reserves are the pool's
+/// instead of a bonding curve's. The pool's mint comes from the swap
+/// instruction, so a swap whose instruction can't be found is dropped.
+pub struct PumpSwapParser;
+
+impl ProgramParser for PumpSwapParser {
+  fn name(&self) -> &'static str {
+    "pumpswap"
+  }
+
+  fn program_id(&self) -> &'static str {
+    PUMP_AMM_PROGRAM_ID
+  }
+
+  fn discriminators(&self) -> &'static [[u8; 8]] {
+    &[AMM_BUY_EVENT_DISCRIMINATOR, AMM_SELL_EVENT_DISCRIMINATOR]
+  }
+
+  fn decode(&self, data: &[u8], context: &ParseContext) -> Option<PumpEvent> {
+    let pool_mints = pool_mints(context);
+    decode_swap(data, &pool_mints, context.signature).map(PumpEvent::Trade)
+  }
+}
+
+/// The base mint of every SOL pool swapped on in the transaction.
+fn pool_mints(context: &ParseContext) -> HashMap<String, String> {
+  parser::program_instructions(context.transaction, PUMP_AMM_PROGRAM_ID)
+    .into_iter()
+    .filter(|(accounts, _)| accounts.get(AMM_QUOTE_MINT_ACCOUNT).is_some_and(|quote| quote == WSOL_MINT))
+    .filter_map(|(mut accounts, _)| {
+      let mint = accounts.get(AMM_BASE_MINT_ACCOUNT)?.clone();
+      Some((accounts.swap_remove(AMM_POOL_ACCOUNT), mint))
+    })
+    .collect()
+}
+
+/// Buy and sell events share a layout up to the accounts; the amounts are
+/// named from the user's side (`base_amount_out`/`base_amount_in`, ...).
+fn decode_swap(data: &[u8], pool_mints: &HashMap<String, String>, signature: &str) -> Option<TradeEventData> {
+  #[derive(BorshDeserialize)]
+  struct SwapEventRaw {
+    timestamp: i64,
+    base_amount: u64,
+    _limit_quote_amount: u64,
+    _user_base_token_reserves: u64,
+    _user_quote_token_reserves: u64,
+    pool_base_token_reserves: u64,
+    pool_quote_token_reserves: u64,
+    quote_amount: u64,
+    lp_fee_basis_points: u64,
+    lp_fee: u64,
+    protocol_fee_basis_points: u64,
+    protocol_fee: u64,
+    _quote_amount_with_lp_fee: u64,
+    _user_quote_amount: u64,
+    pool: [u8; 32],
+    user: [u8; 32],
+    _user_base_token_account: [u8; 32],
+    _user_quote_token_account: [u8; 32],
+    protocol_fee_recipient: [u8; 32],
+    _protocol_fee_recipient_token_account: [u8; 32],
+  }
+
+  /// Only on events from after the coin creator fee upgrade.
+  #[derive(BorshDeserialize)]
+  struct CoinCreatorRaw {
+    coin_creator: [u8; 32],
+    coin_creator_fee_basis_points: u64,
+    coin_creator_fee: u64,
+  }
+
+  let is_buy = data.get(..8)? == AMM_BUY_EVENT_DISCRIMINATOR;
+  let mut rest = &data[8..];
+  let raw = match SwapEventRaw::deserialize(&mut rest) {
+    Ok(r) => r,
+    Err(e) => {
+      error!("❌ Failed to deserialize PumpSwap {} event: {}", if is_buy { "buy" } else { "sell" }, e);
+      return None;
+    }
+  };
+  let creator = CoinCreatorRaw::deserialize(&mut rest).ok();
+
+  let pool = Pubkey::new_from_array(raw.pool).to_string();
+  let Some(mint) = pool_mints.get(pool.as_str()) else {
+    debug!("❓ PumpSwap swap on pool {} without a SOL swap instruction", pool);
+    return None;
+  };
+
+  // The event carries the pool's reserves from before the swap.
+  let (base_reserves, quote_reserves) = if is_buy {
+    (raw.pool_base_token_reserves.saturating_sub(raw.base_amount), raw.pool_quote_token_reserves.saturating_add(raw.quote_amount))
+  } else {
+    (raw.pool_base_token_reserves.saturating_add(raw.base_amount), raw.pool_quote_token_reserves.saturating_sub(raw.quote_amount))
+  };
+
+  Some(TradeEventData {
+    mint: mint.to_string(),
+    sol_amount: raw.quote_amount,
+    token_amount: raw.base_amount,
+    is_buy,
+    user: Pubkey::new_from_array(raw.user).to_string(),
+    timestamp: raw.timestamp,
+    virtual_sol_reserves: quote_reserves,
+    virtual_token_reserves: base_reserves,
+    real_sol_reserves: quote_reserves,
+    real_token_reserves: base_reserves,
+    fee_recipient: Pubkey::new_from_array(raw.protocol_fee_recipient).to_string(),
+    fee_basis_points: raw.lp_fee_basis_points.saturating_add(raw.protocol_fee_basis_points),
+    fee: raw.lp_fee.saturating_add(raw.protocol_fee),
+    creator: creator.as_ref().map(|c| Pubkey::new_from_array(c.coin_creator)).unwrap_or_default().to_string(),
+    creator_fee_basis_points: creator.as_ref().map_or(0, |c| c.coin_creator_fee_basis_points),
+    creator_fee: creator.as_ref().map_or(0, |c| c.coin_creator_fee),
+    track_volume: false,
+    total_unclaimed_tokens: 0,
+    total_claimed_tokens: 0,
+    current_sol_volume: 0,
+    last_update_timestamp: raw.timestamp,
+    ix_name: if is_buy { "pump_amm_buy" } else { "pump_amm_sell" }.to_string(),
+    signature: signature.to_string(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_decode_swap() {
+    let pool = Pubkey::new_unique();
+    let mint = Pubkey::new_unique().to_string();
+    let mut data = AMM_SELL_EVENT_DISCRIMINATOR.to_vec();
+    // timestamp, base in, min quote out, user reserves (2), pool reserves (2), quote out, fees (6)
+    for value in [1_700_000_000u64, 1_000_000, 0, 0, 0, 200_000_000_000_000, 80_000_000_000, 400_000_000, 20, 800_000, 5, 200_000, 0, 0] {
+      data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(pool.as_ref());
+    data.extend_from_slice(&[7u8; 32 * 5]);
+
+    let pool_mints = HashMap::from([(pool.to_string(), mint.clone())]);
+    let trade = decode_swap(&data, &pool_mints, "sig").unwrap();
+    assert_eq!(trade.mint, mint);
+    assert!(!trade.is_buy && trade.is_pool_swap());
+    assert_eq!(trade.virtual_token_reserves, 200_000_001_000_000);
+    assert_eq!(trade.virtual_sol_reserves, 79_600_000_000);
+    assert_eq!(trade.fee, 1_000_000);
+    // No coin creator on events from before the upgrade.
+    assert_eq!(trade.creator, Pubkey::default().to_string());
+
+    assert!(decode_swap(&data, &HashMap::new(), "sig").is_none());
+  }
+
+  #[test]
+  fn test_decode_swap_saturates_fees() {
+    let pool = Pubkey::new_unique();
+    let mut data = AMM_BUY_EVENT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&[0xff; 8 * 14]);
+    data.extend_from_slice(pool.as_ref());
+    data.extend_from_slice(&[0xff; 32 * 5]);
+
+    let pool_mints = HashMap::from([(pool.to_string(), "mint".to_string())]);
+    let trade = decode_swap(&data, &pool_mints, "sig").unwrap();
+    assert_eq!(trade.fee, u64::MAX);
+    assert!(decode_swap(&data[..4], &pool_mints, "sig").is_none());
+  }
+}
@@ -0,0 +1,143 @@
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::RpcKeyedAccount;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use crate::error::{IndexerError, Result};
+use super::WSOL_MINT;
+
+/// Raydium AMM v4, where pump.fun tokens migrated before PumpSwap.
+const RAYDIUM_AMM_V4_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+
+/// Size of an AMM v4 `LiquidityStateV4` account and the offsets read from it.
+const POOL_LEN: usize = 752;
+const BASE_NEED_TAKE_PNL_OFFSET: usize = 192;
+const QUOTE_NEED_TAKE_PNL_OFFSET: usize = 200;
+const BASE_VAULT_OFFSET: usize = 336;
+const QUOTE_VAULT_OFFSET: usize = 368;
+const BASE_MINT_OFFSET: usize = 400;
+const QUOTE_MINT_OFFSET: usize = 432;
+/// SPL token account `amount`, after the mint and owner.
+const TOKEN_AMOUNT_OFFSET: usize = 64;
+
+/// The parts of an AMM v4 pool account needed to price it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaydiumPool {
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    /// Fees the vaults still hold for the protocol, not part of the reserves.
+    pub base_need_take_pnl: u64,
+    pub quote_need_take_pnl: u64,
+}
+
+/// Pool reserves in raw units: the token (6 decimals) and SOL (lamports).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaydiumReserves {
+    pub base: u64,
+    pub quote: u64,
+}
+
+/// Finds the token's SOL pool by scanning the AMM program, which is slow;
+/// callers should store the result.
+pub async fn find_pool(rpc: &RpcClient, mint: &str) -> Result<Option<Pubkey>> {
+    let accounts: Vec<RpcKeyedAccount> = rpc
+        .send(
+            RpcRequest::GetProgramAccounts,
+            json!([
+                RAYDIUM_AMM_V4_PROGRAM_ID,
+                {
+                    "encoding": "base64",
+                    // Only the addresses are needed; the pool is read on its own.
+                    "dataSlice": { "offset": 0, "length": 0 },
+                    "filters": [
+                        { "dataSize": POOL_LEN },
+                        { "memcmp": { "offset": BASE_MINT_OFFSET, "bytes": mint } },
+                        { "memcmp": { "offset": QUOTE_MINT_OFFSET, "bytes": WSOL_MINT } },
+                    ],
+                },
+            ]),
+        )
+        .await
+        .map_err(|e| IndexerError::Rpc(format!("Failed to look up the Raydium pool of {}: {}", mint, e)))?;
+
+    accounts
+        .first()
+        .map(|account| {
+            Pubkey::from_str(&account.pubkey)
+                .map_err(|e| IndexerError::Parse(format!("invalid pool address {}: {}", account.pubkey, e)))
+        })
+        .transpose()
+}
+
+pub async fn fetch_reserves(rpc: &RpcClient, pool: &Pubkey) -> Result<RaydiumReserves> {
+    let data = rpc
+        .get_account_data(pool)
+        .await
+        .map_err(|e| IndexerError::Rpc(format!("Failed to fetch account {}: {}", pool, e)))?;
+    let decoded = decode_pool(&data)?;
+
+    let vaults = rpc
+        .get_multiple_accounts(&[decoded.base_vault, decoded.quote_vault])
+        .await
+        .map_err(|e| IndexerError::Rpc(format!("Failed to fetch the vaults of {}: {}", pool, e)))?;
+    let amount = |index: usize| -> Result<u64> {
+        let vault = vaults
+            .get(index)
+            .and_then(Option::as_ref)
+            .ok_or_else(|| IndexerError::Rpc(format!("vault of pool {} not found", pool)))?;
+        decode_token_amount(&vault.data)
+    };
+
+    Ok(RaydiumReserves {
+        base: amount(0)?.saturating_sub(decoded.base_need_take_pnl),
+        quote: amount(1)?.saturating_sub(decoded.quote_need_take_pnl),
+    })
+}
+
+fn decode_pool(data: &[u8]) -> Result<RaydiumPool> {
+    if data.len() < POOL_LEN {
+        return Err(IndexerError::Parse(format!("Raydium pool account too short ({} bytes)", data.len())));
+    }
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let pubkey_at = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+
+    Ok(RaydiumPool {
+        base_vault: pubkey_at(BASE_VAULT_OFFSET),
+        quote_vault: pubkey_at(QUOTE_VAULT_OFFSET),
+        base_need_take_pnl: u64_at(BASE_NEED_TAKE_PNL_OFFSET),
+        quote_need_take_pnl: u64_at(QUOTE_NEED_TAKE_PNL_OFFSET),
+    })
+}
+
+fn decode_token_amount(data: &[u8]) -> Result<u64> {
+    data.get(TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| IndexerError::Parse(format!("token account too short ({} bytes)", data.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_pool() {
+        let (base_vault, quote_vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut pool = vec![0u8; POOL_LEN];
+        pool[BASE_NEED_TAKE_PNL_OFFSET..BASE_NEED_TAKE_PNL_OFFSET + 8].copy_from_slice(&5u64.to_le_bytes());
+        pool[BASE_VAULT_OFFSET..BASE_VAULT_OFFSET + 32].copy_from_slice(base_vault.as_ref());
+        pool[QUOTE_VAULT_OFFSET..QUOTE_VAULT_OFFSET + 32].copy_from_slice(quote_vault.as_ref());
+
+        let decoded = decode_pool(&pool).unwrap();
+        assert_eq!(decoded.base_vault, base_vault);
+        assert_eq!(decoded.quote_vault, quote_vault);
+        assert_eq!(decoded.base_need_take_pnl, 5);
+        assert_eq!(decoded.quote_need_take_pnl, 0);
+        assert!(decode_pool(&pool[..POOL_LEN - 1]).is_err());
+
+        let mut vault = vec![0u8; 165];
+        vault[TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8].copy_from_slice(&79_000_000_000u64.to_le_bytes());
+        assert_eq!(decode_token_amount(&vault).unwrap(), 79_000_000_000);
+        assert!(decode_token_amount(&vault[..70]).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! Embeddable ingestion pipeline: the Helius listener and pump.fun parser
+//! without the HTTP API, Redis or event sinks.
+//!
+//! ```no_run
+//! use futures::StreamExt;
+//! use pumpfun_indexer::{PumpEvent, PumpIndexer};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let mut events = PumpIndexer::builder()
+//!     .helius_api_key(std::env::var("HELIUS_API_KEY")?)
+//!     .build()?;
+//!
+//! while let Some(event) = events.next().await {
+//!     if let PumpEvent::Create(token) = event {
+//!         println!("new token {} ({})", token.name, token.mint);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Context, Result};
+use futures::Stream;
+use sqlx::PgPool;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Duration};
+use tracing::{error, warn};
+use crate::helius::{self, parser::PumpEvent, ListenerStatus, RawTransaction};
+use crate::processor::{self, metrics::Metrics, reorder::SlotSequencer, state::TokenStateMap};
+use crate::{background, database};
+
+/// Events buffered for a slow consumer before the pipeline waits on it.
+const DEFAULT_BUFFER: usize = 1024;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Entry point for embedding the indexer; see [`PumpIndexer::builder`].
+pub struct PumpIndexer;
+
+impl PumpIndexer {
+    pub fn builder() -> PumpIndexerBuilder {
+        PumpIndexerBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct PumpIndexerBuilder {
+    helius_api_key: Option<String>,
+    persistence: Option<PgPool>,
+    sol_price: Option<Arc<RwLock<f64>>>,
+    metrics: Option<Metrics>,
+    buffer: Option<usize>,
+    transaction_sample_rate: Option<f64>,
+}
+
+impl PumpIndexerBuilder {
+    pub fn helius_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.helius_api_key = Some(api_key.into());
+        self
+    }
+
+    /// Also save transactions, tokens and trades to this database, exactly
+    /// as the indexer binary does. The migrations must have been run.
+    pub fn persist_to(mut self, pool: PgPool) -> Self {
+        self.persistence = Some(pool);
+        self
+    }
+
+    /// SOL/USD price used for market caps when persisting. Without one, a
+    /// background updater is started.
+    pub fn sol_price(mut self, sol_price: Arc<RwLock<f64>>) -> Self {
+        self.sol_price = Some(sol_price);
+        self
+    }
+
+    /// Fraction of raw transactions written to `transactions` when
+    /// persisting, from 0 to 1 (the default). Tokens and trades are always
+    /// stored.
+    pub fn transaction_sample_rate(mut self, rate: f64) -> Self {
+        self.transaction_sample_rate = Some(rate);
+        self
+    }
+
+    pub fn metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Events buffered before the pipeline waits for the consumer.
+    pub fn buffer(mut self, events: usize) -> Self {
+        self.buffer = Some(events);
+        self
+    }
+
+    /// Starts the listener and returns its events. Must be called inside a
+    /// Tokio runtime; dropping the stream stops the pipeline.
+    pub fn build(self) -> Result<PumpEventStream> {
+        let api_key = self.helius_api_key.context("helius_api_key is required")?;
+        let metrics = self.metrics.unwrap_or_default();
+        let (raw_tx, raw_rx) = mpsc::unbounded_channel();
+        let (events_tx, events_rx) = mpsc::channel(self.buffer.unwrap_or(DEFAULT_BUFFER).max(1));
+
+        let persistence = self.persistence.map(|pool| {
+            let sol_price = self.sol_price.unwrap_or_else(|| {
+                let sol_price = Arc::new(RwLock::new(150.0));
+                tokio::spawn(background::start_sol_price_updater(sol_price.clone(), None));
+                sol_price
+            });
+            Persistence {
+                pool,
+                sol_price,
+                state: processor::state::create_state_map(),
+                transaction_sample_rate: self.transaction_sample_rate.unwrap_or(1.0),
+            }
+        });
+
+        let listener = tokio::spawn(listen(api_key, raw_tx, metrics.clone()));
+        let pipeline = tokio::spawn(pipeline(raw_rx, events_tx, persistence, metrics));
+
+        Ok(PumpEventStream { events: events_rx, tasks: vec![listener, pipeline] })
+    }
+}
+
+/// Parsed pump.fun events in slot order, as far as the reorder buffer in
+/// `processor::reorder` can restore it.
+pub struct PumpEventStream {
+    events: mpsc::Receiver<PumpEvent>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Stream for PumpEventStream {
+    type Item = PumpEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<PumpEvent>> {
+        self.events.poll_recv(cx)
+    }
+}
+
+impl Drop for PumpEventStream {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+struct Persistence {
+    pool: PgPool,
+    sol_price: Arc<RwLock<f64>>,
+    state: TokenStateMap,
+    transaction_sample_rate: f64,
+}
+
+/// Keeps the Helius subscription up, reconnecting after it drops.
+async fn listen(api_key: String, raw_tx: mpsc::UnboundedSender<RawTransaction>, metrics: Metrics) {
+    let status = Arc::new(ListenerStatus::default());
+
+    while !raw_tx.is_closed() {
+        if let Err(e) = helius::start_listener(api_key.clone(), raw_tx.clone(), status.clone(), metrics.clone()).await {
+            error!("Helius listener error: {}", e);
+        }
+        warn!("Helius listener stopped, reconnecting in {:?}", RECONNECT_DELAY);
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn pipeline(
+    raw_rx: mpsc::UnboundedReceiver<RawTransaction>,
+    events_tx: mpsc::Sender<PumpEvent>,
+    persistence: Option<Persistence>,
+    metrics: Metrics,
+) {
+    let mut sequencer = SlotSequencer::new(raw_rx, metrics.clone());
+    while let Some(raw) = sequencer.next().await {
+        if let Some(persistence) = &persistence
+            && database::sample_transaction(&raw.signature, persistence.transaction_sample_rate)
+            && let Err(e) = match raw.to_general_transaction() {
+                Ok(tx) => database::save_general_transaction(&persistence.pool, &tx).await,
+                Err(e) => Err(e),
+            }
+        {
+            error!("Failed to save transaction {}: {}", raw.signature, e);
+        }
+
+        if let Some(persistence) = &persistence
+            && let Err(e) = processor::process_creator_fee_claims(&persistence.pool, &raw.signature, &raw.transaction).await
+        {
+            error!("Failed to save creator fee claims for {}: {}", raw.signature, e);
+        }
+
+        let events = match helius::parser::parse_transaction(&raw.signature, &raw.transaction) {
+            Ok(events) => events,
+            Err(e) => {
+                error!("Failed to parse transaction {}: {}", raw.signature, e);
+                continue;
+            }
+        };
+
+        for (index, event) in events.into_iter().enumerate() {
+            if let Some(persistence) = &persistence {
+                let sol_price = *persistence.sol_price.read().await;
+                if let Err(e) = processor::spill::retry_transient(|| processor::process_event(
+                    &persistence.pool,
+                    &raw.signature,
+                    index,
+                    &event,
+                    raw.slot,
+                    None,
+                    &persistence.state,
+                    None,
+                    sol_price,
+                    &metrics,
+                )).await {
+                    error!("Failed to process event: {}", e);
+                    processor::dead_letter(&persistence.pool, &raw.signature, index, &event, raw.slot, &e, &metrics).await;
+                }
+            }
+
+            if events_tx.send(event).await.is_err() {
+                return;
+            }
+        }
+
+        metrics.record_processed_slot(raw.slot, raw.block_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_build_requires_api_key() {
+        assert!(PumpIndexer::builder().build().is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::database;
+use crate::error::Result;
+use crate::helius::parser::PumpEvent;
+use crate::processor::timeline;
+use chrono::Utc;
+use sqlx::{PgConnection, PgPool};
+use tracing::{error, info, warn};
+
+/// Stores one parsed event: its row, the token's counters and its timeline
+/// entry. Every per-event path goes through here (the live processor, catch-up
+/// and token replays via `processor::process_event`, and the backfill binary
+/// without bulk loading) so they can't drift apart. Live state, caches and
+/// sinks are the caller's business.
+///
+/// `database::bulk` is the set-based equivalent for large backfills; keep
+/// the two in step.
+///
+/// Trades of tokens not in the database get a placeholder token first.
+/// Trades are valued in USD at `sol_price_usd`, or left without USD values
+/// when it is unknown.
+///
+/// `(signature, index)` is claimed in the `processed_events` ledger in the
+/// same transaction as the event's rows. Returns false, writing nothing,
+/// when it was claimed before.
+pub async fn persist_event(
+    pool: &PgPool,
+    signature: &str,
+    index: usize,
+    event: &PumpEvent,
+    token_decimals: u8,
+    sol_price_usd: Option<f64>,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    if !database::claim_event(&mut tx, signature, index, event.kind()).await? {
+        return Ok(false);
+    }
+
+    match event {
+        PumpEvent::Create(create) => {
+            if let Err(e) = database::save_token_creation(&mut tx, create).await {
+                error!("Failed to save token creation: {}", e);
+                return Err(e);
+            }
+        }
+
+        PumpEvent::Trade(trade) => {
+            if let Err(e) = ensure_token_exists(&mut tx, &trade.mint).await {
+                error!("Failed to ensure token exists: {}", e);
+                return Err(e);
+            }
+            if let Err(e) = database::save_trade(&mut tx, trade, token_decimals, sol_price_usd).await {
+                error!("Failed to save trade: {}", e);
+                return Err(e);
+            }
+        }
+
+        PumpEvent::Complete(complete) => {
+            if let Err(e) = database::mark_token_complete(&mut tx, &complete.mint, complete.timestamp).await {
+                error!("Failed to mark token complete: {}", e);
+                return Err(e);
+            }
+        }
+    }
+    tx.commit().await?;
+
+    // Timeline entries are best effort and never fail the event.
+    match event {
+        PumpEvent::Create(create) => {
+            if let Err(e) = timeline::record_creation(pool, signature, create).await {
+                error!("Failed to record creation of {} on its timeline: {}", create.mint, e);
+            }
+        }
+        PumpEvent::Trade(trade) => {
+            if let Err(e) = timeline::record_trade(pool, trade, token_decimals).await {
+                error!("Failed to record trade {} on its token's timeline: {}", trade.signature, e);
+            }
+        }
+        PumpEvent::Complete(complete) => {
+            if let Err(e) = timeline::record_graduation(pool, signature, complete).await {
+                error!("Failed to record graduation of {} on its timeline: {}", complete.mint, e);
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+async fn ensure_token_exists(conn: &mut PgConnection, mint: &str) -> Result<()> {
+    let exists: Option<(String,)> = sqlx::query_as(
+        "SELECT mint_address FROM tokens WHERE mint_address = $1"
+    )
+    .bind(mint)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if exists.is_some() {
+        return Ok(());
+    }
+
+    warn!("⚠️  Token {} not found in DB, creating placeholder", mint);
+
+    sqlx::query(
+        "INSERT INTO tokens (
+            mint_address,
+            name,
+            symbol,
+            uri,
+            creator_wallet,
+            bonding_curve_address,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_token_reserves,
+            token_total_supply,
+            complete,
+            created_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT (mint_address) DO NOTHING"
+    )
+    .bind(mint)
+    .bind(format!("Unknown Token {}", &mint[..8])) // Placeholder name
+    .bind("UNKNOWN") // Placeholder symbol
+    .bind("") // Empty URI
+    .bind("11111111111111111111111111111111") // System program as placeholder
+    .bind("11111111111111111111111111111111") // Placeholder bonding curve ADDRESS
+    .bind(0i64) // Default reserves
+    .bind(0i64)
+    .bind(0i64)
+    .bind(0i64)
+    .bind(false)
+    .bind(Utc::now())
+    .execute(conn)
+    .await?;
+
+    info!("✅ Created placeholder token entry for {}", mint);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::model::TradeEventData;
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn test_trade_without_sol_price(pool: PgPool) {
+        let trade = TradeEventData {
+            mint: "MintWithoutCreate1111111111111111111111111".to_string(),
+            sol_amount: 1_000_000_000,
+            token_amount: 35_000_000_000_000,
+            is_buy: true,
+            user: "alice".to_string(),
+            timestamp: 1_700_000_000,
+            virtual_sol_reserves: 31_000_000_000,
+            virtual_token_reserves: 1_038_000_000_000_000,
+            real_sol_reserves: 1_000_000_000,
+            real_token_reserves: 758_100_000_000_000,
+            fee_recipient: "fees".to_string(),
+            fee_basis_points: 95,
+            fee: 9_500_000,
+            creator: "creator".to_string(),
+            creator_fee_basis_points: 5,
+            creator_fee: 500_000,
+            track_volume: true,
+            total_unclaimed_tokens: 0,
+            total_claimed_tokens: 0,
+            current_sol_volume: 0,
+            last_update_timestamp: 1_700_000_000,
+            ix_name: "buy".to_string(),
+            signature: "sig1".to_string(),
+        };
+
+        // A write that fails takes its ledger claim with it, so a retry applies
+        // the event instead of skipping it as already processed.
+        let unstorable = TradeEventData { sol_amount: u64::MAX, ..trade.clone() };
+        assert!(persist_event(&pool, "sig1", 0, &PumpEvent::Trade(unstorable), 6, None).await.is_err());
+
+        assert!(persist_event(&pool, "sig1", 0, &PumpEvent::Trade(trade.clone()), 6, None).await.unwrap());
+        assert!(!persist_event(&pool, "sig1", 0, &PumpEvent::Trade(trade), 6, None).await.unwrap());
+
+        let (symbol, price_usd, trade_count): (String, Option<f64>, i64) = sqlx::query_as(
+            "SELECT t.symbol, tr.price_usd::FLOAT8, t.trade_count
+             FROM trades tr JOIN tokens t ON t.mint_address = tr.token_mint
+             WHERE tr.signature = 'sig1'"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((symbol.as_str(), price_usd, trade_count), ("UNKNOWN", None, 1));
+    }
+}
@@ -1,3 +1,19 @@
 pub mod config;
 pub mod database;
-pub mod helius;
\ No newline at end of file
+pub mod dto;
+pub mod helius;
+pub mod processor;
+pub mod ingest;
+pub mod storage;
+pub mod api;
+pub mod background;
+pub mod sinks;
+pub mod indexer;
+pub mod error_reporting;
+pub mod logging;
+pub mod error;
+#[cfg(feature = "client")]
+pub mod client;
+
+pub use helius::parser::PumpEvent;
+pub use indexer::{PumpEventStream, PumpIndexer, PumpIndexerBuilder};
@@ -0,0 +1,58 @@
+use std::sync::{Arc, RwLock};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Used when neither `RUST_LOG` nor `logging.filter` is set. The parser
+/// logs every transaction it decodes, which drowns everything else at
+/// production volume, so it only reports problems by default.
+pub const DEFAULT_FILTER: &str = "info,pumpfun_indexer::helius::parser=warn,sqlx=warn";
+
+/// `RUST_LOG`-style directives, e.g. `info,pumpfun_indexer::processor=debug`.
+pub fn parse(directives: &str) -> Result<EnvFilter, String> {
+    EnvFilter::builder().parse(directives).map_err(|e| e.to_string())
+}
+
+/// Handle to the installed log filter, for changing it while the indexer
+/// runs (`PUT /api/admin/log-filter`).
+#[derive(Clone)]
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    current: Arc<RwLock<String>>,
+}
+
+impl LogFilter {
+    /// The filter layer to install first on the registry, starting from
+    /// `directives`, and the handle that controls it.
+    pub fn layer(directives: &str) -> Result<(reload::Layer<EnvFilter, Registry>, Self), String> {
+        let (layer, handle) = reload::Layer::new(parse(directives)?);
+        let filter = LogFilter { handle, current: Arc::new(RwLock::new(directives.to_string())) };
+        Ok((layer, filter))
+    }
+
+    pub fn current(&self) -> String {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Swaps in new directives; the old ones stay active if these don't parse.
+    pub fn set(&self, directives: &str) -> Result<(), String> {
+        let filter = parse(directives)?;
+        self.handle.reload(filter).map_err(|e| e.to_string())?;
+        *self.current.write().unwrap() = directives.to_string();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_keeps_valid_filter() {
+        let (_layer, filter) = LogFilter::layer(DEFAULT_FILTER).unwrap();
+
+        filter.set("warn,pumpfun_indexer::processor=debug").unwrap();
+        assert_eq!(filter.current(), "warn,pumpfun_indexer::processor=debug");
+
+        assert!(filter.set("pumpfun_indexer=loud").is_err());
+        assert_eq!(filter.current(), "warn,pumpfun_indexer::processor=debug");
+    }
+}
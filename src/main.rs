@@ -1,43 +1,84 @@
-mod config;
-mod database;
-mod helius;
-mod processor;
-mod storage;
-mod api;
-mod background;
+use pumpfun_indexer::{api, background, config, database, error_reporting, helius, logging, processor, sinks, storage};
 
 use anyhow::Result;
-use tracing::{info, error};
+use clap::Parser;
+use sentry::SentryFutureExt;
+use tracing::{info, warn, error, info_span, Instrument};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{RwLock, mpsc};
+use tracing_subscriber::prelude::*;
 
 #[tokio::main]
 async fn main() -> Result<()> {
 
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_file(false)
-        .init();
+    let sentry_config = config::SentryConfig::from_env();
+    let _sentry = sentry_config.as_ref().map(error_reporting::init);
+    let log_filter = init_tracing(config::LogFormat::from_env());
 
     info!("🚀 Starting Pump.fun Indexer...");
+    if let Some(sentry) = &sentry_config {
+        info!("   Error reporting: Sentry ({})", sentry.environment);
+    }
 
-    let config = config::Config::from_env()?;
+    let args = config::ConfigArgs::parse();
+    let config = config::Config::load(&args)?;
     info!("✅ Configuration loaded");
-    info!("   Database: {}", mask_db_url(&config.database_url));
-    info!("   Redis: {}", config.redis_url);
-    info!("   API Port: {}", config.api_port);
+    if config.log_filter != log_filter.current() {
+        log_filter.set(&config.log_filter).map_err(anyhow::Error::msg)?;
+    }
+    info!("   Log filter: {}", config.log_filter);
+    if config.dry_run {
+        warn!("🧪 DRY RUN: parsing live traffic without writing to the database or publishing events");
+    }
+    info!("   Database: {}", mask_db_url(&config.database.url));
+    if config.transport.uses_redis() {
+        info!("   Redis: {}", config.redis.url);
+    } else {
+        info!("   Redis: disabled (live events via Postgres LISTEN/NOTIFY)");
+    }
+    info!("   API Port: {}", config.api.port);
+    if config.api.rate_limit.enabled {
+        info!(
+            "   Rate limits: {}/{}/{} per min (read/heavy/stream)",
+            config.api.rate_limit.read_per_minute,
+            config.api.rate_limit.heavy_per_minute,
+            config.api.rate_limit.stream_per_minute,
+        );
+    }
     
-    if config.coingecko_api_key.is_some() {
+    if config.analytics.coingecko_api_key.is_some() {
         info!("   CoinGecko: Pro API enabled");
     } else {
         info!("   CoinGecko: Free tier (may have rate limits)");
     }
 
-    let pool = database::create_pool(&config.database_url).await?;
+    database::instrument::set_slow_query_threshold(Duration::from_millis(config.database.slow_query_ms));
+    let pool = database::create_pool(&config.database.url).await?;
 
+    match database::missing_indexes(&pool).await {
+        Ok(missing) => {
+            for (table, index) in missing {
+                warn!("⚠️  Missing index {} on {}; apply the latest migrations", index, table);
+            }
+        }
+        Err(e) => warn!("⚠️  Could not check database indexes: {}", e),
+    }
+
+    let drift = database::schema::check(&pool).await?;
+    if !drift.is_empty() {
+        for problem in &drift {
+            error!("❌ Schema drift: {}", problem);
+        }
+        anyhow::bail!("database schema doesn't match this build ({} problems); apply the latest migrations", drift.len());
+    }
 
-    let redis_client = storage::create_redis_client(&config.redis_url).await?;
+
+    let redis_client = if config.transport.uses_redis() {
+        Some(storage::create_redis_client(&config.redis.url).await?)
+    } else {
+        None
+    };
 
     let sol_price = Arc::new(RwLock::new(150.0));
 
@@ -45,78 +86,297 @@ async fn main() -> Result<()> {
     info!("✅ In-memory state initialized");
 
 
-    tokio::spawn(background::start_sol_price_updater(
-        sol_price.clone(),
-        config.coingecko_api_key.clone(),
-    ));
+    let supervisor = background::Supervisor::new();
+
+    let (price, api_key) = (sol_price.clone(), config.analytics.coingecko_api_key.clone());
+    supervisor.spawn("sol_price", move || background::start_sol_price_updater(price.clone(), api_key.clone()));
     
-    tokio::spawn(background::start_state_backup(pool.clone(), token_state_map.clone()));
+    // Without Redis the leaderboard endpoints compute boards on request.
+    if let Some(redis) = &redis_client {
+        let (db, redis) = (pool.clone(), redis.clone());
+        supervisor.spawn("leaderboards", move || background::start_leaderboard_updater(db.clone(), redis.clone()));
+    }
+
+    // These write to the database, so a dry run leaves them off.
+    if !config.dry_run {
+        let db = pool.clone();
+        supervisor.spawn("token_summaries", move || background::start_token_summary_refresher(db.clone()));
+
+        let (db, state) = (pool.clone(), token_state_map.clone());
+        supervisor.spawn("state_backup", move || background::start_state_backup(db.clone(), state.clone()));
+
+        let db = pool.clone();
+        supervisor.spawn("market_stats", move || background::start_market_stats_aggregator(db.clone()));
+
+        let db = pool.clone();
+        supervisor.spawn("creator_earnings", move || background::start_creator_earnings_aggregator(db.clone()));
+
+        let db = pool.clone();
+        supervisor.spawn("funnel", move || background::start_funnel_aggregator(db.clone()));
+
+        let db = pool.clone();
+        supervisor.spawn("wallet_linker", move || background::start_wallet_linker(db.clone()));
+
+        let db = pool.clone();
+        supervisor.spawn("holder_snapshots", move || background::start_holder_snapshotter(db.clone()));
+
+        if let Some(archive) = &config.archive {
+            let archiver = Arc::new(background::Archiver::new(archive)?);
+            let db = pool.clone();
+            supervisor.spawn("archiver", move || {
+                let (archiver, db) = (archiver.clone(), db.clone());
+                async move { archiver.run(db).await }
+            });
+
+            if archive.hot_days > 0 {
+                let (db, hot_days) = (pool.clone(), archive.hot_days);
+                supervisor.spawn("trade_tiering", move || background::start_trade_tiering(db.clone(), hot_days));
+            }
+        }
+    }
+
+    let settings = config::SharedSettings::new(config::RuntimeSettings::from_config(&config));
+    tokio::spawn(config::reload_on_sighup(args.clone(), settings.clone()));
+
+    let listener_status = Arc::new(helius::ListenerStatus::default());
+    let metrics = processor::metrics::Metrics::new();
+    if config.dry_run {
+        metrics.set_dry_run();
+    }
+
+    let m = metrics.clone();
+    supervisor.spawn("runtime_monitor", move || background::start_runtime_monitor(m.clone()));
+
+    if let Some(redis) = &redis_client {
+        let (redis, streams, m) = (redis.clone(), config.transport.streams().cloned(), metrics.clone());
+        supervisor.spawn("redis_health", move || {
+            storage::start_redis_health_monitor(redis.clone(), streams.clone(), m.clone())
+        });
+    }
+
+    if let Some(path) = &args.restore {
+        let started = std::time::Instant::now();
+        let snapshot = processor::snapshot::read_file(path).await?;
+        let taken_at = snapshot.taken_at;
+        let tokens = processor::snapshot::restore(&token_state_map, &metrics, snapshot).await;
+        let refreshed = processor::snapshot::refresh_since(&pool, &token_state_map, taken_at, *sol_price.read().await).await?;
+        info!(
+            "♻️  Restored {} tokens from {} (taken {}), refreshed {} from the database in {:?}",
+            tokens, path.display(), taken_at, refreshed, started.elapsed()
+        );
+    }
+
+    let ingestion = processor::control::IngestionControl::new();
+    let tracked_wallets = processor::tracked::TrackedWallets::load(&pool).await?;
+    let creator_lists = processor::creator_lists::CreatorLists::load(&pool).await?;
+    let koth = processor::koth::Koth::load(&pool).await?;
+    let recent_trades = processor::recent_trades::RecentTrades::new();
+    let rpc = Arc::new(solana_client::nonblocking::rpc_client::RpcClient::new_with_commitment(
+        helius::rpc_url(&config.rpc.helius_api_key),
+        solana_sdk::commitment_config::CommitmentConfig::confirmed(),
+    ));
+
+    // Events left from an outage before a restart are applied first.
+    let spill = if config.dry_run {
+        None
+    } else {
+        let queue = processor::spill::SpillQueue::open(&config.database.spill_dir).await?;
+        if !queue.is_empty() {
+            warn!("💾 {} events spilled during a database outage are waiting in {}", queue.len(), config.database.spill_dir);
+        }
+        Some(queue)
+    };
+
+    // Recorded before catch-up moves the checkpoint, so `start_slot` is where
+    // this run picked up. A dry run stores nothing, its session included.
+    let session = if config.dry_run {
+        None
+    } else {
+        let start_slot = Some(database::fetch_checkpoint_slot(&pool).await?).filter(|slot| *slot > 0);
+        let id = database::start_session(&pool, "live", start_slot).await?;
+        let (db, m) = (pool.clone(), metrics.clone());
+        supervisor.spawn("session_heartbeat", move || background::start_session_heartbeat(db.clone(), id, m.clone()));
+        Some(id)
+    };
+
+    if !config.dry_run {
+        // Mock mode has no RPC key: nothing to price graduated pools against
+        // and no real history to catch up on.
+        if !config.source.is_mock() {
+            let (db, client, state, price) = (pool.clone(), rpc.clone(), token_state_map.clone(), sol_price.clone());
+            supervisor.spawn("raydium_prices", move || {
+                background::start_raydium_pricer(db.clone(), client.clone(), state.clone(), price.clone())
+            });
+        }
+
+        let (db, state, price, m) = (pool.clone(), token_state_map.clone(), sol_price.clone(), metrics.clone());
+        supervisor.spawn("external_prices", move || {
+            background::start_external_price_checker(db.clone(), state.clone(), price.clone(), m.clone())
+        });
+
+        if !config.source.is_mock() {
+            let (db, client, state, price, m) = (pool.clone(), rpc.clone(), token_state_map.clone(), sol_price.clone(), metrics.clone());
+            let limit = config.catch_up_limit;
+            supervisor.spawn("catch_up", move || {
+                background::start_catch_up(db.clone(), client.clone(), state.clone(), price.clone(), m.clone(), limit)
+            });
+        }
+
+        let (db, state, price, m) = (pool.clone(), token_state_map.clone(), sol_price.clone(), metrics.clone());
+        supervisor.spawn("dead_letter", move || {
+            background::start_dead_letter_sweeper(db.clone(), state.clone(), price.clone(), m.clone())
+        });
+
+        if let Some(queue) = &spill {
+            let (db, queue, state, price, m) = (pool.clone(), queue.clone(), token_state_map.clone(), sol_price.clone(), metrics.clone());
+            supervisor.spawn("spill_drainer", move || {
+                background::start_spill_drainer(db.clone(), queue.clone(), state.clone(), price.clone(), m.clone())
+            });
+        }
+    }
+
+    let mut pubsub = storage::PubSubHub::new(&config.redis.url, &config.transport, &pool);
+    if let Some(redis) = &redis_client {
+        pubsub = pubsub.with_reconnect_signal(redis.health.reconnects());
+    }
 
     let api_state = api::AppState {
         db: pool.clone(),
         redis: redis_client.clone(),
+        pubsub: Arc::new(pubsub),
         token_state: token_state_map.clone(),
         sol_price: sol_price.clone(),
+        rate_limiter: Arc::new(api::rate_limit::RateLimiter::new(settings.clone())),
+        listener: listener_status.clone(),
+        metrics: metrics.clone(),
+        ws_limits: config.api.ws,
+        settings: settings.clone(),
+        admin_token: config.api.admin_token.as_deref().map(Arc::from),
+        supervisor: supervisor.clone(),
+        rpc,
+        ingestion: ingestion.clone(),
+        tracked_wallets: tracked_wallets.clone(),
+        creator_lists: creator_lists.clone(),
+        koth: koth.clone(),
+        recent_trades: recent_trades.clone(),
+        log_filter,
     };
     
-    let router = api::create_router(api_state);
-    let addr = format!("0.0.0.0:{}", config.api_port);
-    
-    info!("🌐 Starting API server on {}", addr);
-    
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    // Fail startup rather than silently skip a requested gRPC server.
+    if config.api.grpc_port.is_some() && !cfg!(feature = "grpc") {
+        anyhow::bail!("GRPC_PORT is set but the indexer was built without the `grpc` feature");
+    }
+    let grpc_server = match config.api.grpc_port {
+        Some(port) => {
+            let server = api::grpc::bind(api_state.clone(), port).await?;
+            Some(tokio::spawn(async move {
+                if let Err(e) = server.await {
+                    error!("gRPC server error: {:#}", e);
+                }
+            }))
+        }
+        None => None,
+    };
+
+    let router = api::create_router(api_state, &config.api.cors);
+    let api_server = api::bind(router, &config.api).await?;
     let api_server = tokio::spawn(async move {
-        axum::serve(listener, router).await.unwrap();
+        if let Err(e) = api_server.await {
+            error!("API server error: {}", e);
+        }
     });
 
+    let (http, ws) = if config.api.tls.is_some() { ("https", "wss") } else { ("http", "ws") };
     info!("✨ Indexer is running!");
-    info!("   API: http://localhost:{}", config.api_port);
-    info!("   WebSocket: ws://localhost:{}/ws/trades", config.api_port);
+    info!("   API: {}://localhost:{}", http, config.api.port);
+    info!("   WebSocket: {}://localhost:{}/ws/trades", ws, config.api.port);
     info!("Press Ctrl+C to shutdown");
 
-    let (tx_sender, mut tx_receiver) = mpsc::unbounded_channel();
+    let (tx_sender, tx_receiver) = mpsc::unbounded_channel();
     
-    let helius_key = config.helius_api_key.clone();
-    let helius_task = tokio::spawn(async move {
-        if let Err(e) = helius::start_listener(helius_key, tx_sender).await {
-            error!("Helius listener error: {}", e);
+    let listener_metrics = metrics.clone();
+    match &config.source {
+        config::IngestSource::Helius => {
+            let helius_key = config.rpc.helius_api_key.clone();
+            supervisor.spawn("helius_listener", move || {
+                helius::start_listener(helius_key.clone(), tx_sender.clone(), listener_status.clone(), listener_metrics.clone())
+            });
         }
-    });
+        config::IngestSource::Mock(mock) => {
+            info!("🎭 Ingesting synthetic events, not mainnet (--source mock)");
+            let mock = mock.clone();
+            supervisor.spawn("mock_source", move || {
+                helius::mock::start_mock_source(mock.clone(), tx_sender.clone(), listener_status.clone(), listener_metrics.clone())
+            });
+        }
+    }
 
+    let event_sinks = if config.dry_run {
+        Vec::new()
+    } else {
+        sinks::from_config(&config, &pool, redis_client.as_ref(), &metrics, &settings).await?
+    };
+    for sink in &event_sinks {
+        info!("📤 Event sink enabled: {}", sink.name());
+    }
+
+    // Shared so a restarted processor picks up where the last one stopped,
+    // including transactions still held for reordering.
+    let sequencer = Arc::new(tokio::sync::Mutex::new(
+        processor::reorder::SlotSequencer::new(tx_receiver, metrics.clone()),
+    ));
+    let event_sinks = Arc::new(event_sinks);
     let pool_clone = pool.clone();
-    let mut redis_clone = redis_client.clone();
+    let redis_clone = redis_client.clone();
     let state_clone = token_state_map.clone();
     let sol_price_clone = sol_price.clone();
+    let metrics_clone = metrics.clone();
+    let spill_clone = spill.clone();
+    let dry_run = config.dry_run;
+    let transaction_sample_rate = config.database.transaction_sample_rate;
     
-    tokio::spawn(async move {
-        while let Some(raw_tx) = tx_receiver.recv().await {
-            let signature = raw_tx.signature.clone();
-            
-            let general_tx = raw_tx.to_general_transaction();
-            
-            if let Err(e) = database::save_general_transaction(&pool_clone, &general_tx).await {
-                error!("Failed to save transaction {}: {}", signature, e);
-                continue;
-            }
-            
-            match helius::parser::parse_transaction(&signature, &raw_tx.transaction) {
-                Ok(events) => {
-                    let sol_price_value = *sol_price_clone.read().await;
-                    for event in events {
-                        if let Err(e) = processor::process_event(
-                            &pool_clone,
-                            event,
-                            &mut redis_clone,
-                            &state_clone,
-                            sol_price_value,
-                        ).await {
-                            error!("Failed to process event: {}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to parse transaction {}: {}", signature, e);
+    supervisor.spawn("processor", move || {
+        let sequencer = sequencer.clone();
+        let event_sinks = event_sinks.clone();
+        let pool = pool_clone.clone();
+        let redis = redis_clone.clone();
+        let state = state_clone.clone();
+        let sol_price = sol_price_clone.clone();
+        let metrics = metrics_clone.clone();
+        let spill = spill_clone.clone();
+        let ingestion = ingestion.clone();
+        let tracked_wallets = tracked_wallets.clone();
+        let creator_lists = creator_lists.clone();
+        let koth = koth.clone();
+        let recent_trades = recent_trades.clone();
+
+        async move {
+            let mut sequencer = sequencer.lock().await;
+            loop {
+                ingestion.wait_until_resumed().await;
+                let Some(raw_tx) = sequencer.next().await else { break };
+
+                let span = info_span!("tx", signature = %raw_tx.signature, slot = raw_tx.slot);
+                if dry_run {
+                    span.in_scope(|| observe_raw_transaction(&raw_tx, &metrics));
+                    continue;
                 }
+                let hub = error_reporting::transaction_hub(&raw_tx.signature, raw_tx.slot);
+                handle_raw_transaction(
+                    raw_tx,
+                    &pool,
+                    redis.as_ref(),
+                    &state,
+                    &sol_price,
+                    &metrics,
+                    &tracked_wallets,
+                    &creator_lists,
+                    &koth,
+                    &recent_trades,
+                    &event_sinks,
+                    spill.as_ref(),
+                    transaction_sample_rate,
+                ).bind_hub(hub).instrument(span).await;
             }
         }
     });
@@ -124,19 +384,212 @@ async fn main() -> Result<()> {
     tokio::signal::ctrl_c().await?;
     info!("👋 Shutting down gracefully...");
 
-    helius_task.abort();
+    supervisor.shutdown();
+    if let Some(id) = session {
+        let counts = background::sessions::counts(&metrics);
+        if let Err(e) = database::update_session(&pool, id, background::sessions::end_slot(&metrics), &counts, true).await {
+            error!("Failed to close session #{}: {}", id, e);
+        }
+    }
     api_server.abort();
+    if let Some(grpc_server) = grpc_server {
+        grpc_server.abort();
+    }
 
     Ok(())
 }
 
-fn mask_db_url(url: &str) -> String {
-    if let Some(at_pos) = url.rfind('@') {
-        if let Some(colon_pos) = url[..at_pos].rfind(':') {
-            let mut masked = url.to_string();
-            masked.replace_range(colon_pos + 1..at_pos, "****");
-            return masked;
+#[allow(clippy::too_many_arguments)]
+async fn handle_raw_transaction(
+    raw_tx: helius::RawTransaction,
+    pool: &sqlx::PgPool,
+    redis: Option<&storage::RedisClient>,
+    token_state: &processor::state::TokenStateMap,
+    sol_price: &RwLock<f64>,
+    metrics: &processor::metrics::Metrics,
+    tracked_wallets: &processor::tracked::TrackedWallets,
+    creator_lists: &processor::creator_lists::CreatorLists,
+    koth: &processor::koth::Koth,
+    recent_trades: &processor::recent_trades::RecentTrades,
+    event_sinks: &[Box<dyn sinks::EventSink>],
+    spill: Option<&processor::spill::SpillQueue>,
+    transaction_sample_rate: f64,
+) {
+    let signature = &raw_tx.signature;
+
+    // Only the raw record; events are processed whether or not it is kept.
+    if database::sample_transaction(signature, transaction_sample_rate) {
+        let started = Instant::now();
+        let saved = match raw_tx.to_general_transaction() {
+            Ok(tx) => database::save_general_transaction(pool, &tx).await,
+            Err(e) => Err(e),
+        };
+        metrics.transaction_write_latency.observe(started.elapsed());
+
+        if let Err(e) = saved {
+            error!("Failed to save transaction {}: {}", signature, e);
+        }
+    }
+
+    if let Err(e) = processor::process_creator_fee_claims(pool, signature, &raw_tx.transaction).await {
+        error!("Failed to save creator fee claims for {}: {}", signature, e);
+    }
+
+    match helius::parser::parse_transaction(signature, &raw_tx.transaction) {
+        Ok(events) => {
+            let sol_price_value = *sol_price.read().await;
+            for (index, event) in events.into_iter().enumerate() {
+                // Behind a spill backlog, so it reaches the database in order.
+                if let Some(queue) = spill
+                    && !queue.is_empty()
+                {
+                    processor::spill::spill(queue, signature, index, &event, raw_tx.slot, None, metrics).await;
+                    continue;
+                }
+
+                let span = info_span!("event", kind = event.kind(), mint = %event.mint());
+                error_reporting::set_mint(event.mint());
+                let started = Instant::now();
+                let result = processor::spill::retry_transient(|| processor::process_event(
+                    pool,
+                    signature,
+                    index,
+                    &event,
+                    raw_tx.slot,
+                    redis,
+                    token_state,
+                    Some(recent_trades),
+                    sol_price_value,
+                    metrics,
+                )).instrument(span).await;
+                metrics.event_write_latency.observe(started.elapsed());
+
+                match result {
+                    Ok(update) => {
+                        let tracked = match &update {
+                            Some(processor::EventUpdate::Trade(trade)) => {
+                                processor::tracked::signal(pool, tracked_wallets, token_state, trade)
+                                    .await
+                                    .unwrap_or_else(|e| {
+                                        error!("Failed to build tracked wallet signal: {}", e);
+                                        None
+                                    })
+                            }
+                            _ => None,
+                        };
+                        let koth = match &update {
+                            Some(update) => koth.observe(pool, token_state, update).await.unwrap_or_else(|e| {
+                                error!("Failed to update king of the hill: {}", e);
+                                None
+                            }),
+                            None => None,
+                        };
+                        let creator_list = match &event {
+                            helius::parser::PumpEvent::Create(create) => creator_lists.list_of(&create.creator).await,
+                            helius::parser::PumpEvent::Trade(trade) => creator_lists.list_of(&trade.creator).await,
+                            helius::parser::PumpEvent::Complete(_) => None,
+                        };
+                        let record = sinks::EventRecord {
+                            signature,
+                            slot: raw_tx.slot,
+                            index,
+                            kind: event.kind(),
+                            event: &event,
+                            update: update.as_ref(),
+                            tracked: tracked.as_ref(),
+                            koth: koth.as_ref(),
+                            creator_list,
+                        };
+                        for sink in event_sinks {
+                            sink.publish(&record).await;
+                        }
+                    }
+                    Err(e) if e.is_retryable() && let Some(queue) = spill => {
+                        processor::spill::spill(queue, signature, index, &event, raw_tx.slot, Some(&e), metrics).await;
+                    }
+                    Err(e) => {
+                        error!("Failed to process event: {}", e);
+                        processor::dead_letter(pool, signature, index, &event, raw_tx.slot, &e, metrics).await;
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            metrics.increment_parse_errors();
+            error!("Failed to parse transaction {}: {}", signature, e);
+        }
+    }
+
+    metrics.record_processed_slot(raw_tx.slot, raw_tx.block_time);
+}
+
+/// Dry-run counterpart of `handle_raw_transaction`: parses the transaction
+/// and logs and counts what would be stored, without touching the database,
+/// the in-memory state or any sink.
+fn observe_raw_transaction(raw_tx: &helius::RawTransaction, metrics: &processor::metrics::Metrics) {
+    match helius::parser::parse_transaction(&raw_tx.signature, &raw_tx.transaction) {
+        Ok(events) => {
+            for event in &events {
+                info!("🧪 Would store {} event for {}", event.kind(), event.mint());
+                match event {
+                    helius::parser::PumpEvent::Create(_) => metrics.increment_tokens_created(),
+                    helius::parser::PumpEvent::Trade(_) => metrics.increment_trades_processed(),
+                    helius::parser::PumpEvent::Complete(_) => metrics.increment_tokens_graduated(),
+                }
+            }
         }
+        Err(e) => {
+            metrics.increment_parse_errors();
+            error!("Failed to parse transaction {}: {}", raw_tx.signature, e);
+        }
+    }
+
+    metrics.record_processed_slot(raw_tx.slot, raw_tx.block_time);
+}
+
+/// Starts from `RUST_LOG` or the default filter; a `logging.filter` from the
+/// config file is applied through the returned handle once it is loaded. An
+/// invalid `RUST_LOG` falls back to the default with a warning, logged once
+/// the subscriber is installed.
+fn init_tracing(format: config::LogFormat) -> logging::LogFilter {
+    let (directives, rejected) = match std::env::var("RUST_LOG") {
+        Ok(directives) => match logging::parse(&directives) {
+            Ok(_) => (directives, None),
+            Err(e) => (logging::DEFAULT_FILTER.to_string(), Some((directives, e))),
+        },
+        Err(_) => (logging::DEFAULT_FILTER.to_string(), None),
+    };
+    let (filter_layer, log_filter) = logging::LogFilter::layer(&directives).expect("log filter was validated");
+
+    let layer = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_thread_ids(false)
+        .with_file(false);
+    let registry = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(error_reporting::layer());
+
+    match format {
+        // The span list keeps the outer `tx` span's `signature` on events
+        // logged from the per-event spans nested inside it.
+        config::LogFormat::Json => registry
+            .with(layer.json().with_current_span(true).with_span_list(true))
+            .init(),
+        config::LogFormat::Pretty => registry.with(layer).init(),
+    }
+    if let Some((directives, e)) = rejected {
+        warn!("⚠️  Ignoring invalid RUST_LOG `{}` ({}), using `{}`", directives, e, logging::DEFAULT_FILTER);
+    }
+    log_filter
+}
+
+fn mask_db_url(url: &str) -> String {
+    if let Some(at_pos) = url.rfind('@')
+        && let Some(colon_pos) = url[..at_pos].rfind(':')
+    {
+        let mut masked = url.to_string();
+        masked.replace_range(colon_pos + 1..at_pos, "****");
+        return masked;
     }
     url.to_string()
 }
\ No newline at end of file
@@ -1,14 +1,25 @@
 
+pub const SOL_DECIMALS: u8 = 9;
+/// Every pump.fun mint has 6 decimals; tokens from elsewhere store their own.
+pub const DEFAULT_TOKEN_DECIMALS: u8 = 6;
+
+/// Converts a raw amount to whole units, e.g. lamports to SOL.
+pub fn to_ui_amount(raw: u64, decimals: u8) -> f64 {
+    raw as f64 / 10f64.powi(decimals as i32)
+}
+
+/// SOL per whole token.
 pub fn calculate_price_sol(
     virtual_sol_reserves: u64,
     virtual_token_reserves: u64,
+    token_decimals: u8,
 ) -> f64 {
     if virtual_token_reserves == 0 {
         return 0.0;
     }
     
-    let sol = virtual_sol_reserves as f64 / 1_000_000_000.0;
-    let tokens = virtual_token_reserves as f64 / 1_000_000.0;
+    let sol = to_ui_amount(virtual_sol_reserves, SOL_DECIMALS);
+    let tokens = to_ui_amount(virtual_token_reserves, token_decimals);
     
     sol / tokens
 }
@@ -16,29 +27,66 @@ pub fn calculate_price_sol(
 pub fn calculate_market_cap_sol(
     price_sol: f64,
     total_supply: u64,
+    token_decimals: u8,
 ) -> f64 {
-    let supply = total_supply as f64 / 1_000_000.0;
+    let supply = to_ui_amount(total_supply, token_decimals);
     price_sol * supply
 }
 
 pub fn calculate_bonding_curve_progress(virtual_sol_reserves: u64) -> f64 {
     const TARGET_SOL: f64 = 85.0; // SOL needed to complete curve
-    let sol_in_curve = virtual_sol_reserves as f64 / 1_000_000_000.0;
-    ((sol_in_curve / TARGET_SOL) * 100.0).min(100.0).max(0.0)
+    let sol_in_curve = to_ui_amount(virtual_sol_reserves, SOL_DECIMALS);
+    ((sol_in_curve / TARGET_SOL) * 100.0).clamp(0.0, 100.0)
 }
 
-pub fn calculate_price_impact(
-    trade_sol_amount: u64,
-    virtual_sol_reserves: u64,
-) -> f64 {
-    if virtual_sol_reserves == 0 {
+/// Percent change from `price_before` to `price_after`; positive for buys.
+pub fn calculate_price_impact(price_before: f64, price_after: f64) -> f64 {
+    if price_before <= 0.0 {
         return 0.0;
     }
-    
-    let trade_sol = trade_sol_amount as f64 / 1_000_000_000.0;
-    let reserves_sol = virtual_sol_reserves as f64 / 1_000_000_000.0;
-    
-    (trade_sol / reserves_sol) * 100.0
+
+    (price_after - price_before) / price_before * 100.0
+}
+
+/// Spot prices around a trade, in SOL per token, and how far the execution
+/// price was from the price before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TradeImpact {
+    pub price_before_sol: f64,
+    pub price_after_sol: f64,
+    pub price_impact_pct: f64,
+    pub slippage_pct: f64,
+}
+
+/// Trade events carry post-trade reserves; the pre-trade reserves are
+/// recovered by undoing the trade.
+pub fn calculate_trade_impact(
+    sol_amount: u64,
+    token_amount: u64,
+    is_buy: bool,
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    token_decimals: u8,
+) -> TradeImpact {
+    let (sol_before, tokens_before) = if is_buy {
+        (virtual_sol_reserves.saturating_sub(sol_amount), virtual_token_reserves.saturating_add(token_amount))
+    } else {
+        (virtual_sol_reserves.saturating_add(sol_amount), virtual_token_reserves.saturating_sub(token_amount))
+    };
+    let price_before_sol = calculate_price_sol(sol_before, tokens_before, token_decimals);
+    let price_after_sol = calculate_price_sol(virtual_sol_reserves, virtual_token_reserves, token_decimals);
+    let execution = calculate_price_sol(sol_amount, token_amount, token_decimals);
+
+    TradeImpact {
+        price_before_sol,
+        price_after_sol,
+        price_impact_pct: calculate_price_impact(price_before_sol, price_after_sol),
+        slippage_pct: if price_before_sol > 0.0 {
+            (execution - price_before_sol).abs() / price_before_sol * 100.0
+        } else {
+            0.0
+        },
+    }
 }
 
 #[cfg(test)]
@@ -47,8 +95,11 @@ mod tests {
     
     #[test]
     fn test_price_calculation() {
-        let price = calculate_price_sol(10_000_000_000, 1_000_000_000_000);
+        let price = calculate_price_sol(10_000_000_000, 1_000_000_000_000, DEFAULT_TOKEN_DECIMALS);
         assert!((price - 0.00001).abs() < 0.000001);
+        // The same raw reserves of a 9-decimal mint are a thousandth as many tokens.
+        let price = calculate_price_sol(10_000_000_000, 1_000_000_000_000, 9);
+        assert!((price - 0.01).abs() < 1e-9);
     }
     
     #[test]
@@ -56,4 +107,22 @@ mod tests {
         let progress = calculate_bonding_curve_progress(42_500_000_000);
         assert!((progress - 50.0).abs() < 0.1);
     }
+
+    #[test]
+    fn test_trade_impact() {
+        // 1 SOL into a 30 SOL / 1.073B token curve, constant product.
+        let (sol, tokens) = (30_000_000_000u64, 1_073_000_000_000_000u64);
+        let bought = tokens - (sol as u128 * tokens as u128 / (sol + 1_000_000_000) as u128) as u64;
+        let buy = calculate_trade_impact(1_000_000_000, bought, true, sol + 1_000_000_000, tokens - bought, DEFAULT_TOKEN_DECIMALS);
+
+        assert!((buy.price_before_sol - calculate_price_sol(sol, tokens, DEFAULT_TOKEN_DECIMALS)).abs() < 1e-15);
+        // Price rises by (31/30)^2 on a constant product curve.
+        assert!((buy.price_impact_pct - ((31.0f64 / 30.0).powi(2) - 1.0) * 100.0).abs() < 1e-6);
+        // Execution lands between the two spot prices.
+        assert!((buy.slippage_pct - 100.0 / 30.0).abs() < 1e-6);
+
+        let sell = calculate_trade_impact(1_000_000_000, bought, false, sol, tokens, DEFAULT_TOKEN_DECIMALS);
+        assert!(sell.price_impact_pct < 0.0);
+        assert_eq!(calculate_price_impact(0.0, 1.0), 0.0);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Pause switch for event processing. While paused the listener keeps
+/// queueing transactions, so nothing is lost; they are processed in order
+/// on resume.
+#[derive(Debug, Clone)]
+pub struct IngestionControl {
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl Default for IngestionControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IngestionControl {
+    pub fn new() -> Self {
+        Self { paused: Arc::new(watch::Sender::new(false)) }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Returns whether the state changed.
+    pub fn set_paused(&self, paused: bool) -> bool {
+        self.paused.send_if_modified(|current| std::mem::replace(current, paused) != paused)
+    }
+
+    pub async fn wait_until_resumed(&self) {
+        let mut paused = self.paused.subscribe();
+        let _ = paused.wait_for(|paused| !paused).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pause_and_resume() {
+        let control = IngestionControl::new();
+        assert!(control.set_paused(true));
+        assert!(!control.set_paused(true));
+
+        let waiter = control.clone();
+        let resumed = tokio::spawn(async move { waiter.wait_until_resumed().await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!resumed.is_finished());
+
+        assert!(control.set_paused(false));
+        tokio::time::timeout(Duration::from_secs(1), resumed).await.unwrap().unwrap();
+        assert!(!control.is_paused());
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use utoipa::ToSchema;
+use crate::database::{self, model::CreatorListEntry};
+use crate::error::Result;
+
+/// The operator lists a creator can be on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CreatorList {
+    /// Known scammers: their tokens are flagged and left out of trending.
+    Blacklist,
+    /// Creators whose launches trigger notifications.
+    Watchlist,
+}
+
+impl CreatorList {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Blacklist => "blacklist",
+            Self::Watchlist => "watchlist",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "blacklist" => Some(Self::Blacklist),
+            "watchlist" => Some(Self::Watchlist),
+            _ => None,
+        }
+    }
+}
+
+/// In-memory copy of the `creator_lists` table, checked by the API and on
+/// every event. Loaded at startup and kept in sync by the admin routes.
+#[derive(Debug, Clone, Default)]
+pub struct CreatorLists {
+    entries: Arc<RwLock<HashMap<String, CreatorListEntry>>>,
+}
+
+impl CreatorLists {
+    pub async fn load(pool: &PgPool) -> Result<Self> {
+        let entries = database::fetch_creator_lists(pool).await?;
+        Ok(Self {
+            entries: Arc::new(RwLock::new(
+                entries.into_iter().map(|e| (e.creator.clone(), e)).collect(),
+            )),
+        })
+    }
+
+    /// The list the creator is on, if any.
+    pub async fn list_of(&self, creator: &str) -> Option<CreatorList> {
+        self.entries.read().await.get(creator).and_then(|e| CreatorList::parse(&e.list))
+    }
+
+    /// Every listed creator, for checking many tokens under one lock.
+    pub async fn all(&self) -> HashMap<String, CreatorList> {
+        self.entries
+            .read()
+            .await
+            .values()
+            .filter_map(|e| Some((e.creator.clone(), CreatorList::parse(&e.list)?)))
+            .collect()
+    }
+
+    pub async fn entries(&self) -> Vec<CreatorListEntry> {
+        let mut entries: Vec<_> = self.entries.read().await.values().cloned().collect();
+        entries.sort_by_key(|e| e.added_at);
+        entries
+    }
+
+    pub async fn insert(&self, entry: CreatorListEntry) {
+        self.entries.write().await.insert(entry.creator.clone(), entry);
+    }
+
+    pub async fn remove(&self, creator: &str) -> bool {
+        self.entries.write().await.remove(creator).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_lookup() {
+        let lists = CreatorLists::default();
+        let entry = |creator: &str, list: &str| CreatorListEntry {
+            creator: creator.to_string(),
+            list: list.to_string(),
+            reason: None,
+            added_at: chrono::Utc::now(),
+        };
+        lists.insert(entry("scammer", "blacklist")).await;
+        lists.insert(entry("builder", "watchlist")).await;
+
+        assert_eq!(lists.list_of("scammer").await, Some(CreatorList::Blacklist));
+        assert_eq!(lists.list_of("nobody").await, None);
+
+        // Moving a creator to the other list replaces its entry.
+        lists.insert(entry("scammer", "watchlist")).await;
+        assert_eq!(lists.all().await.get("scammer"), Some(&CreatorList::Watchlist));
+        assert!(lists.remove("builder").await);
+        assert_eq!(lists.entries().await.len(), 1);
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+use utoipa::ToSchema;
+use crate::database;
+use crate::error::Result;
+use super::EventUpdate;
+use super::state::{TokenState, TokenStateMap};
+
+/// SOL market cap a token must reach to contend for the crown, roughly
+/// halfway along the bonding curve.
+pub const KOTH_THRESHOLD_SOL: f64 = 200.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct King {
+    pub mint: String,
+    pub name: String,
+    pub symbol: String,
+    /// As of the king's last trade
+    pub market_cap_sol: f64,
+    pub market_cap_usd: f64,
+    pub crowned_at: DateTime<Utc>,
+}
+
+impl King {
+    fn from_state(state: &TokenState, crowned_at: DateTime<Utc>) -> Self {
+        Self {
+            mint: state.mint.clone(),
+            name: state.name.clone(),
+            symbol: state.symbol.clone(),
+            market_cap_sol: state.market_cap_sol,
+            market_cap_usd: state.market_cap_usd,
+            crowned_at,
+        }
+    }
+}
+
+/// Why a reign ended, as stored in `koth_reigns.dethroned_by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dethroned {
+    /// Another token traded above the king's market cap.
+    Overtaken,
+    Graduated,
+    /// The king's market cap dropped below the threshold.
+    Fell,
+}
+
+impl Dethroned {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Dethroned::Overtaken => "overtaken",
+            Dethroned::Graduated => "graduated",
+            Dethroned::Fell => "fell",
+        }
+    }
+}
+
+/// Published on `pump:koth` whenever the crown changes hands or is left
+/// vacant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KothMessage {
+    /// The new king; absent when no live token is above the threshold.
+    pub king: Option<King>,
+    pub previous: Option<String>,
+    pub reason: Option<Dethroned>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl KothMessage {
+    /// The mint the message is keyed by on ordered transports.
+    pub fn mint(&self) -> &str {
+        self.king.as_ref().map(|k| k.mint.as_str()).or(self.previous.as_deref()).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Verdict {
+    Keep,
+    Crown,
+    Vacate(Dethroned),
+}
+
+/// Decides what a token's new market cap means for the crown.
+fn judge(king: Option<&King>, mint: &str, market_cap_sol: f64, complete: bool) -> Verdict {
+    match king {
+        Some(king) if king.mint == mint => {
+            if complete {
+                Verdict::Vacate(Dethroned::Graduated)
+            } else if market_cap_sol < KOTH_THRESHOLD_SOL {
+                Verdict::Vacate(Dethroned::Fell)
+            } else {
+                Verdict::Keep
+            }
+        }
+        _ if complete || market_cap_sol < KOTH_THRESHOLD_SOL => Verdict::Keep,
+        Some(king) if market_cap_sol <= king.market_cap_sol => Verdict::Keep,
+        _ => Verdict::Crown,
+    }
+}
+
+/// The current king of the hill: the highest market cap among live,
+/// non-graduated tokens above `KOTH_THRESHOLD_SOL`. Challengers are only
+/// compared on their own trades, so the crown changes when a token trades
+/// above the king.
+#[derive(Debug, Clone, Default)]
+pub struct Koth {
+    king: Arc<RwLock<Option<King>>>,
+}
+
+impl Koth {
+    pub async fn load(pool: &PgPool) -> Result<Self> {
+        let king = database::fetch_koth_reigns(pool, 1)
+            .await?
+            .into_iter()
+            .find(|reign| reign.dethroned_at.is_none())
+            .map(|reign| King {
+                mint: reign.mint_address,
+                name: reign.name,
+                symbol: reign.symbol,
+                market_cap_sol: reign.market_cap_sol,
+                market_cap_usd: reign.market_cap_usd,
+                crowned_at: reign.crowned_at,
+            });
+
+        Ok(Self { king: Arc::new(RwLock::new(king)) })
+    }
+
+    pub async fn current(&self) -> Option<King> {
+        self.king.read().await.clone()
+    }
+
+    /// Checks a processed event against the crown, recording any change.
+    /// Returns the message to publish when the crown changed.
+    pub async fn observe(
+        &self,
+        pool: &PgPool,
+        state_map: &TokenStateMap,
+        update: &EventUpdate,
+    ) -> Result<Option<KothMessage>> {
+        let (mint, at) = match update {
+            EventUpdate::Trade(trade) => (&trade.mint, trade.timestamp),
+            EventUpdate::Graduated(graduation) => (
+                &graduation.mint,
+                Utc.timestamp_opt(graduation.timestamp, 0).single().unwrap_or_else(Utc::now),
+            ),
+            EventUpdate::TokenCreated(_) => return Ok(None),
+        };
+
+        let mut king = self.king.write().await;
+        let Some(token) = state_map.read().await.get(mint).cloned() else {
+            return Ok(None);
+        };
+
+        let (new_king, reason) = match judge(king.as_ref(), mint, token.market_cap_sol, token.complete) {
+            Verdict::Keep => {
+                if let Some(king) = king.as_mut().filter(|k| k.mint == *mint) {
+                    king.market_cap_sol = token.market_cap_sol;
+                    king.market_cap_usd = token.market_cap_usd;
+                }
+                return Ok(None);
+            }
+            Verdict::Crown => (Some(King::from_state(&token, at)), Dethroned::Overtaken),
+            Verdict::Vacate(reason) => (successor(state_map, mint, at).await, reason),
+        };
+
+        match &new_king {
+            Some(new) => {
+                database::crown_koth(pool, &new.mint, at, new.market_cap_sol, new.market_cap_usd, reason.as_str()).await?;
+                info!("👑 {} ({}) is king of the hill at {:.1} SOL", new.symbol, new.mint, new.market_cap_sol);
+            }
+            None => {
+                database::vacate_koth(pool, at, reason.as_str()).await?;
+                info!("👑 King of the hill vacated ({})", reason.as_str());
+            }
+        }
+
+        let previous = king.take();
+        *king = new_king.clone();
+        Ok(Some(KothMessage {
+            king: new_king,
+            reason: previous.is_some().then_some(reason),
+            previous: previous.map(|k| k.mint),
+            timestamp: at,
+        }))
+    }
+}
+
+/// The best contender in live state other than `excluded`, for when the
+/// king steps down.
+async fn successor(state_map: &TokenStateMap, excluded: &str, at: DateTime<Utc>) -> Option<King> {
+    state_map
+        .read()
+        .await
+        .values()
+        .filter(|t| t.mint != excluded && !t.complete && t.market_cap_sol >= KOTH_THRESHOLD_SOL)
+        .max_by(|a, b| a.market_cap_sol.total_cmp(&b.market_cap_sol))
+        .map(|t| King::from_state(t, at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_judge() {
+        let king = King {
+            mint: "king".into(),
+            name: "King".into(),
+            symbol: "K".into(),
+            market_cap_sol: 300.0,
+            market_cap_usd: 45_000.0,
+            crowned_at: Utc::now(),
+        };
+
+        assert_eq!(judge(None, "a", 250.0, false), Verdict::Crown);
+        assert_eq!(judge(None, "a", 150.0, false), Verdict::Keep);
+        assert_eq!(judge(None, "a", 500.0, true), Verdict::Keep);
+        assert_eq!(judge(Some(&king), "a", 300.0, false), Verdict::Keep);
+        assert_eq!(judge(Some(&king), "a", 301.0, false), Verdict::Crown);
+        assert_eq!(judge(Some(&king), "king", 210.0, false), Verdict::Keep);
+        assert_eq!(judge(Some(&king), "king", 190.0, false), Verdict::Vacate(Dethroned::Fell));
+        assert_eq!(judge(Some(&king), "king", 410.0, true), Verdict::Vacate(Dethroned::Graduated));
+    }
+}
@@ -1,12 +1,76 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Upper bounds (seconds) for the DB write latency histogram.
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
 
 #[derive(Clone)]
 pub struct Metrics {
     pub tokens_created: Arc<AtomicU64>,
     pub trades_processed: Arc<AtomicU64>,
     pub tokens_graduated: Arc<AtomicU64>,
+    pub transactions_processed: Arc<AtomicU64>,
     pub redis_publish_errors: Arc<AtomicU64>,
+    /// Publishes held for Redis to come back or for another try.
+    pub redis_pending_publishes: Arc<AtomicI64>,
+    /// Publishes given up on: pushed out of a full queue, or out of attempts.
+    pub redis_publishes_dropped: Arc<AtomicU64>,
+    pub rpc_errors: Arc<AtomicU64>,
+    pub kafka_errors: Arc<AtomicU64>,
+    pub nats_errors: Arc<AtomicU64>,
+    pub webhook_errors: Arc<AtomicU64>,
+    pub notify_errors: Arc<AtomicU64>,
+    /// Transactions the parser rejected.
+    pub parse_errors: Arc<AtomicU64>,
+    /// Events skipped because the ledger showed them already processed.
+    pub duplicate_events: Arc<AtomicU64>,
+    /// Events that failed processing and went to `dead_letter_events`,
+    /// retries included.
+    pub dead_lettered_events: Arc<AtomicU64>,
+    /// Rows in `dead_letter_events`, as of the sweeper's last pass.
+    pub dead_letter_events: Arc<AtomicI64>,
+    /// Events written to the on-disk spill queue while the database was down.
+    pub spilled_events: Arc<AtomicU64>,
+    /// Events waiting in the spill queue.
+    pub spill_queue_events: Arc<AtomicI64>,
+    /// API responses served from the Redis response cache.
+    pub cache_hits: Arc<AtomicU64>,
+    /// API responses computed because the cache had no entry.
+    pub cache_misses: Arc<AtomicU64>,
+    /// 1 when running with `--dry-run`, so dashboards can tell the counters
+    /// above describe what would have been stored.
+    pub dry_run: Arc<AtomicI64>,
+    /// Create transactions moved ahead of a processing backlog.
+    pub prioritized_creates: Arc<AtomicU64>,
+    /// Transactions fetched by the listener but not yet picked up by the processor.
+    pub channel_depth: Arc<AtomicI64>,
+    /// Graduated tokens whose price was off Jupiter's in the last cross-check.
+    pub diverging_prices: Arc<AtomicI64>,
+    pub ws_clients: Arc<AtomicI64>,
+    pub ws_messages_sent: Arc<AtomicU64>,
+    /// Messages dropped because a client's send queue was full.
+    pub ws_messages_dropped: Arc<AtomicU64>,
+    /// Connections refused by the `WS_MAX_CONNECTIONS` cap.
+    pub ws_connections_rejected: Arc<AtomicU64>,
+    pub sse_clients: Arc<AtomicI64>,
+    /// Latest slot seen in a logs notification.
+    pub last_seen_slot: Arc<AtomicU64>,
+    /// Slot of the latest transaction the processor finished.
+    pub last_processed_slot: Arc<AtomicU64>,
+    /// Block time (unix seconds) of the latest processed transaction, 0 until one is.
+    pub last_processed_block_time: Arc<AtomicI64>,
+    /// Runtime workers stuck in a single poll at the monitor's last sample.
+    pub runtime_blocked_workers: Arc<AtomicI64>,
+    pub transaction_write_latency: Arc<Histogram>,
+    pub event_write_latency: Arc<Histogram>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Metrics {
@@ -15,7 +79,38 @@ impl Metrics {
             tokens_created: Arc::new(AtomicU64::new(0)),
             trades_processed: Arc::new(AtomicU64::new(0)),
             tokens_graduated: Arc::new(AtomicU64::new(0)),
+            transactions_processed: Arc::new(AtomicU64::new(0)),
             redis_publish_errors: Arc::new(AtomicU64::new(0)),
+            redis_pending_publishes: Arc::new(AtomicI64::new(0)),
+            redis_publishes_dropped: Arc::new(AtomicU64::new(0)),
+            rpc_errors: Arc::new(AtomicU64::new(0)),
+            kafka_errors: Arc::new(AtomicU64::new(0)),
+            nats_errors: Arc::new(AtomicU64::new(0)),
+            webhook_errors: Arc::new(AtomicU64::new(0)),
+            notify_errors: Arc::new(AtomicU64::new(0)),
+            parse_errors: Arc::new(AtomicU64::new(0)),
+            duplicate_events: Arc::new(AtomicU64::new(0)),
+            dead_lettered_events: Arc::new(AtomicU64::new(0)),
+            dead_letter_events: Arc::new(AtomicI64::new(0)),
+            spilled_events: Arc::new(AtomicU64::new(0)),
+            spill_queue_events: Arc::new(AtomicI64::new(0)),
+            cache_hits: Arc::new(AtomicU64::new(0)),
+            cache_misses: Arc::new(AtomicU64::new(0)),
+            dry_run: Arc::new(AtomicI64::new(0)),
+            prioritized_creates: Arc::new(AtomicU64::new(0)),
+            channel_depth: Arc::new(AtomicI64::new(0)),
+            diverging_prices: Arc::new(AtomicI64::new(0)),
+            ws_clients: Arc::new(AtomicI64::new(0)),
+            ws_messages_sent: Arc::new(AtomicU64::new(0)),
+            ws_messages_dropped: Arc::new(AtomicU64::new(0)),
+            ws_connections_rejected: Arc::new(AtomicU64::new(0)),
+            sse_clients: Arc::new(AtomicI64::new(0)),
+            last_seen_slot: Arc::new(AtomicU64::new(0)),
+            last_processed_slot: Arc::new(AtomicU64::new(0)),
+            last_processed_block_time: Arc::new(AtomicI64::new(0)),
+            runtime_blocked_workers: Arc::new(AtomicI64::new(0)),
+            transaction_write_latency: Arc::new(Histogram::new(LATENCY_BUCKETS)),
+            event_write_latency: Arc::new(Histogram::new(LATENCY_BUCKETS)),
         }
     }
 
@@ -35,6 +130,90 @@ impl Metrics {
         self.redis_publish_errors.fetch_add(1, Ordering::Relaxed);
     }
 
+    pub fn increment_rpc_errors(&self) {
+        self.rpc_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_kafka_errors(&self) {
+        self.kafka_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_nats_errors(&self) {
+        self.nats_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_webhook_errors(&self) {
+        self.webhook_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_notify_errors(&self) {
+        self.notify_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_parse_errors(&self) {
+        self.parse_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_duplicate_events(&self) {
+        self.duplicate_events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_dead_lettered_events(&self) {
+        self.dead_lettered_events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_spilled_events(&self) {
+        self.spilled_events.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn increment_prioritized_creates(&self) {
+        self.prioritized_creates.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn set_dry_run(&self) {
+        self.dry_run.store(1, Ordering::Relaxed);
+    }
+
+    pub fn record_seen_slot(&self, slot: u64) {
+        self.last_seen_slot.fetch_max(slot, Ordering::Relaxed);
+    }
+
+    /// Called once per transaction the processor has finished with.
+    pub fn record_processed_slot(&self, slot: u64, block_time: Option<i64>) {
+        self.transactions_processed.fetch_add(1, Ordering::Relaxed);
+        self.last_processed_slot.fetch_max(slot, Ordering::Relaxed);
+        if let Some(block_time) = block_time {
+            self.last_processed_block_time.fetch_max(block_time, Ordering::Relaxed);
+        }
+    }
+
+    pub fn last_processed_block_time(&self) -> Option<i64> {
+        match self.last_processed_block_time.load(Ordering::Relaxed) {
+            0 => None,
+            ts => Some(ts),
+        }
+    }
+
+    /// Counts a connected WebSocket client until the guard is dropped.
+    pub fn track_ws_client(&self) -> GaugeGuard {
+        GaugeGuard::new(self.ws_clients.clone())
+    }
+
+    /// Like `track_ws_client`, but refuses the client once `max` are
+    /// already connected.
+    pub fn try_track_ws_client(&self, max: i64) -> Option<GaugeGuard> {
+        if self.ws_clients.fetch_add(1, Ordering::Relaxed) >= max {
+            self.ws_clients.fetch_sub(1, Ordering::Relaxed);
+            self.ws_connections_rejected.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+        Some(GaugeGuard(self.ws_clients.clone()))
+    }
+
+    pub fn track_sse_client(&self) -> GaugeGuard {
+        GaugeGuard::new(self.sse_clients.clone())
+    }
+
     pub fn get_stats(&self) -> MetricsSnapshot {
         MetricsSnapshot {
             tokens_created: self.tokens_created.load(Ordering::Relaxed),
@@ -43,6 +222,131 @@ impl Metrics {
             redis_publish_errors: self.redis_publish_errors.load(Ordering::Relaxed),
         }
     }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        let counters = [
+            ("pump_tokens_created_total", "Token creations processed", &self.tokens_created),
+            ("pump_trades_processed_total", "Trades processed", &self.trades_processed),
+            ("pump_tokens_graduated_total", "Bonding curves completed", &self.tokens_graduated),
+            ("pump_transactions_processed_total", "Transactions the processor finished with", &self.transactions_processed),
+            ("pump_redis_publish_errors_total", "Failed Redis publishes", &self.redis_publish_errors),
+            ("pump_redis_publishes_dropped_total", "Redis publishes given up on", &self.redis_publishes_dropped),
+            ("pump_rpc_errors_total", "Failed getTransaction calls", &self.rpc_errors),
+            ("pump_kafka_publish_errors_total", "Events Kafka did not acknowledge", &self.kafka_errors),
+            ("pump_nats_publish_errors_total", "Events JetStream did not acknowledge", &self.nats_errors),
+            ("pump_webhook_delivery_errors_total", "Events the webhook never accepted", &self.webhook_errors),
+            ("pump_pg_notify_errors_total", "Failed pg_notify calls", &self.notify_errors),
+            ("pump_parse_errors_total", "Transactions the parser rejected", &self.parse_errors),
+            ("pump_duplicate_events_total", "Events skipped as already processed", &self.duplicate_events),
+            ("pump_dead_lettered_events_total", "Events that failed processing and were dead-lettered", &self.dead_lettered_events),
+            ("pump_spilled_events_total", "Events spilled to disk while the database was down", &self.spilled_events),
+            ("pump_prioritized_creates_total", "Create transactions processed ahead of a backlog", &self.prioritized_creates),
+            ("pump_response_cache_hits_total", "API responses served from the Redis cache", &self.cache_hits),
+            ("pump_response_cache_misses_total", "API responses computed on a cache miss", &self.cache_misses),
+            ("pump_ws_messages_sent_total", "Messages written to WebSocket clients", &self.ws_messages_sent),
+            ("pump_ws_messages_dropped_total", "Messages dropped for slow WebSocket clients", &self.ws_messages_dropped),
+            ("pump_ws_connections_rejected_total", "WebSocket connections refused by the connection cap", &self.ws_connections_rejected),
+        ];
+        for (name, help, value) in counters {
+            write_metric(&mut out, name, help, "counter", value.load(Ordering::Relaxed));
+        }
+
+        let gauges = [
+            ("pump_pipeline_channel_depth", "Fetched transactions waiting to be processed", &self.channel_depth),
+            ("pump_diverging_prices", "Graduated tokens priced off Jupiter by more than the threshold", &self.diverging_prices),
+            ("pump_dead_letter_events", "Failed events waiting in the dead-letter table", &self.dead_letter_events),
+            ("pump_spill_queue_events", "Events waiting in the on-disk spill queue", &self.spill_queue_events),
+            ("pump_redis_pending_publishes", "Redis publishes waiting to be replayed or retried", &self.redis_pending_publishes),
+            ("pump_ws_clients", "Connected WebSocket clients", &self.ws_clients),
+            ("pump_sse_clients", "Connected Server-Sent Events clients", &self.sse_clients),
+            ("pump_dry_run", "1 when nothing is being stored (--dry-run)", &self.dry_run),
+            ("pump_last_processed_block_time", "Block time of the latest processed transaction", &self.last_processed_block_time),
+            ("pump_tokio_blocked_workers", "Runtime workers stuck in one poll for a whole sample interval", &self.runtime_blocked_workers),
+        ];
+        for (name, help, value) in gauges {
+            write_metric(&mut out, name, help, "gauge", value.load(Ordering::Relaxed));
+        }
+
+        let seen = self.last_seen_slot.load(Ordering::Relaxed);
+        let processed = self.last_processed_slot.load(Ordering::Relaxed);
+        write_metric(&mut out, "pump_last_seen_slot", "Latest slot seen in a logs notification", "gauge", seen);
+        write_metric(&mut out, "pump_last_processed_slot", "Slot of the latest processed transaction", "gauge", processed);
+        write_metric(&mut out, "pump_slot_lag", "Slots between the latest notification and the latest processed transaction", "gauge", seen.saturating_sub(processed));
+
+        let _ = writeln!(out, "# HELP pump_db_write_duration_seconds Time spent persisting pipeline output");
+        let _ = writeln!(out, "# TYPE pump_db_write_duration_seconds histogram");
+        self.transaction_write_latency.render(&mut out, "pump_db_write_duration_seconds", "op=\"transaction\"");
+        self.event_write_latency.render(&mut out, "pump_db_write_duration_seconds", "op=\"event\"");
+        crate::database::instrument::render(&mut out);
+        crate::background::runtime_monitor::render(&mut out);
+
+        out
+    }
+}
+
+pub(crate) fn write_metric(out: &mut String, name: &str, help: &str, kind: &str, value: impl std::fmt::Display) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+pub struct GaugeGuard(Arc<AtomicI64>);
+
+impl GaugeGuard {
+    fn new(gauge: Arc<AtomicI64>) -> Self {
+        gauge.fetch_add(1, Ordering::Relaxed);
+        Self(gauge)
+    }
+}
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Fixed-bucket histogram; bucket counts are stored non-cumulatively and
+/// summed at render time.
+pub struct Histogram {
+    bounds: &'static [f64],
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        if let Some(i) = self.bounds.iter().position(|bound| secs <= *bound) {
+            self.buckets[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut cumulative = 0;
+        for (bound, bucket) in self.bounds.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{}_bucket{{{},le=\"{}\"}} {}", name, labels, bound, cumulative);
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{{},le=\"+Inf\"}} {}", name, labels, count);
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, count);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -51,4 +355,47 @@ pub struct MetricsSnapshot {
     pub trades_processed: u64,
     pub tokens_graduated: u64,
     pub redis_publish_errors: u64,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let histogram = Histogram::new(&[0.01, 0.1]);
+        histogram.observe(Duration::from_millis(5));
+        histogram.observe(Duration::from_millis(50));
+        histogram.observe(Duration::from_secs(1));
+
+        let mut out = String::new();
+        histogram.render(&mut out, "h", "op=\"x\"");
+
+        assert!(out.contains("h_bucket{op=\"x\",le=\"0.01\"} 1"));
+        assert!(out.contains("h_bucket{op=\"x\",le=\"0.1\"} 2"));
+        assert!(out.contains("h_bucket{op=\"x\",le=\"+Inf\"} 3"));
+        assert!(out.contains("h_count{op=\"x\"} 3"));
+    }
+
+    #[test]
+    fn test_gauge_guard_decrements_on_drop() {
+        let metrics = Metrics::new();
+        let guard = metrics.track_ws_client();
+        assert_eq!(metrics.ws_clients.load(Ordering::Relaxed), 1);
+        drop(guard);
+        assert_eq!(metrics.ws_clients.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_ws_connection_cap() {
+        let metrics = Metrics::new();
+        let first = metrics.try_track_ws_client(1);
+        assert!(first.is_some());
+        assert!(metrics.try_track_ws_client(1).is_none());
+        assert_eq!(metrics.ws_clients.load(Ordering::Relaxed), 1);
+        assert_eq!(metrics.ws_connections_rejected.load(Ordering::Relaxed), 1);
+
+        drop(first);
+        assert!(metrics.try_track_ws_client(1).is_some());
+    }
+}
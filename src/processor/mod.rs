@@ -1,101 +1,164 @@
 pub mod calculator;
 pub mod state;
 pub mod metrics;
+pub mod control;
+pub mod tracked;
+pub mod momentum;
+pub mod koth;
+pub mod reorder;
+pub mod recent_trades;
+pub mod creator_lists;
+pub mod timeline;
+pub mod spill;
+pub mod snapshot;
 
 use crate::database;
+use crate::dto::{GraduationMessage, NewTokenMessage, TradeMessage};
 use crate::helius::parser::PumpEvent;
+use crate::ingest;
 use crate::storage::RedisClient;
+use crate::storage::response_cache::{self, CacheScope};
 use sqlx::PgPool;
-use anyhow::Result;
-use tracing::{info, error, debug, warn};
-use serde::{Serialize, Deserialize};
-use chrono::{TimeZone, Utc};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TradeMessage {
-    pub signature: String,
-    pub mint: String,
-    pub is_buy: bool,
-    pub sol_amount: u64,
-    pub token_amount: u64,
-    pub user_wallet: String,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
-    pub market_cap_usd: f64,
-    pub price_sol: f64,
+use crate::error::{IndexerError, Result};
+use tracing::{info, warn, error, debug};
+use serde::Serialize;
+use chrono::TimeZone;
+
+/// What processing an event changed, in the shape live consumers see.
+/// Handed to every `EventSink` alongside the raw event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum EventUpdate {
+    TokenCreated(NewTokenMessage),
+    Trade(TradeMessage),
+    Graduated(GraduationMessage),
 }
 
-async fn safe_publish<T: serde::Serialize>(
-    redis: &mut RedisClient,
-    channel: &str,
-    message: &T,
-) {
-    if let Err(e) = redis.publish(channel, message).await {
-        error!("⚠️ Redis publish failed (channel: {}): {}", channel, e);
-        error!("   Event will still be saved to database");
-    } else {
-        debug!("✅ Published to Redis channel: {}", channel);
+/// Stores the creator fee claims in a transaction; returns how many there were.
+pub async fn process_creator_fee_claims(
+    pool: &PgPool,
+    signature: &str,
+    transaction: &solana_transaction_status::EncodedTransactionWithStatusMeta,
+) -> Result<usize> {
+    let claims = crate::helius::parser::parse_creator_fee_claims(transaction);
+    if !claims.is_empty() {
+        database::save_creator_fee_claims(pool, signature, &claims).await?;
+        for claim in &claims {
+            info!("💸 Creator {} claimed {:.4} SOL", claim.creator, calculator::to_ui_amount(claim.creator_fee, calculator::SOL_DECIMALS));
+        }
     }
+    Ok(claims.len())
 }
 
-async fn ensure_token_exists(pool: &PgPool, mint: &str) -> Result<()> {
-    let exists: Option<(String,)> = sqlx::query_as(
-        "SELECT mint_address FROM tokens WHERE mint_address = $1"
-    )
-    .bind(mint)
-    .fetch_optional(pool)
-    .await?;
-    
-    if exists.is_some() {
-        return Ok(());
+/// Whether the token graduated from a curve we index, rebuilding its live
+/// state from the database when it isn't held, e.g. for tokens that
+/// graduated before a restart. Post-graduation prices only apply to these.
+pub async fn load_graduated_state(
+    pool: &PgPool,
+    state_map: &state::TokenStateMap,
+    mint: &str,
+    sol_price_usd: f64,
+) -> Result<bool> {
+    if let Some(complete) = state_map.read().await.get(mint).map(|s| s.complete) {
+        return Ok(complete);
+    }
+
+    match database::fetch_token_snapshot(pool, mint).await? {
+        Some(snapshot) if snapshot.complete => {
+            state::restore_token_state(state_map, mint, snapshot, sol_price_usd).await;
+            Ok(true)
+        }
+        _ => Ok(false),
     }
-    
-    warn!("⚠️  Token {} not found in DB, creating placeholder", mint);
-    
-    sqlx::query(
-        "INSERT INTO tokens (
-            mint_address, 
-            name, 
-            symbol, 
-            uri, 
-            creator_wallet,
-            bonding_curve_address,
-            virtual_sol_reserves,
-            virtual_token_reserves,
-            real_token_reserves,
-            token_total_supply,
-            complete,
-            created_at
-        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-        ON CONFLICT (mint_address) DO NOTHING"
-    )
-    .bind(mint)
-    .bind(format!("Unknown Token {}", &mint[..8])) // Placeholder name
-    .bind("UNKNOWN") // Placeholder symbol
-    .bind("") // Empty URI
-    .bind("11111111111111111111111111111111") // System program as placeholder
-    .bind("11111111111111111111111111111111") // Placeholder bonding curve ADDRESS
-    .bind(0i64) // Default reserves
-    .bind(0i64)
-    .bind(0i64)
-    .bind(0i64)
-    .bind(false)
-    .bind(Utc::now())
-    .execute(pool)
-    .await?;
-    
-    info!("✅ Created placeholder token entry for {}", mint);
-    
-    Ok(())
 }
 
+/// Persists one event and updates live state. Returns the update to hand to
+/// the event sinks, or `None` when there is nothing to publish (a trade for
+/// a token missing from the in-memory state, or a PumpSwap trade on a token
+/// that didn't graduate from an indexed curve). `slot` is the event's
+/// transaction slot, which keeps live reserves from moving backwards.
+///
+/// Each event is applied at most once: `(signature, index)` is claimed in
+/// the `processed_events` ledger in the transaction that stores it, and an
+/// event claimed before is skipped with `None`, so replays and overlapping
+/// ingestion paths can't count it twice. A failed or interrupted write
+/// leaves no claim behind, so a retry isn't skipped.
+#[allow(clippy::too_many_arguments)]
 pub async fn process_event(
     pool: &PgPool,
-    event: PumpEvent,
-    redis: &mut RedisClient,
+    signature: &str,
+    index: usize,
+    event: &PumpEvent,
+    slot: u64,
+    redis: Option<&RedisClient>,
     state_map: &state::TokenStateMap,
+    recent_trades: Option<&recent_trades::RecentTrades>,
     sol_price_usd: f64,
-) -> Result<()> {
-    match event {
+    metrics: &metrics::Metrics,
+) -> Result<Option<EventUpdate>> {
+    if let PumpEvent::Trade(trade) = event
+        && trade.is_pool_swap()
+        && !load_graduated_state(pool, state_map, &trade.mint, sol_price_usd).await?
+    {
+        debug!("⏭️  Skipping PumpSwap trade {} on {}, not a graduated token", signature, trade.mint);
+        return Ok(None);
+    }
+
+    let (decimals, price) = match event {
+        PumpEvent::Create(_) => (calculator::DEFAULT_TOKEN_DECIMALS, Some(sol_price_usd)),
+        PumpEvent::Trade(trade) => (state::token_decimals(state_map, &trade.mint).await, Some(sol_price_usd)),
+        PumpEvent::Complete(_) => (calculator::DEFAULT_TOKEN_DECIMALS, None),
+    };
+    if !ingest::persist_event(pool, signature, index, event, decimals, price).await? {
+        debug!("⏭️  Skipping {} event {}#{}, already processed", event.kind(), signature, index);
+        metrics.increment_duplicate_events();
+        return Ok(None);
+    }
+
+    apply_event(pool, event, decimals, slot, redis, state_map, recent_trades, sol_price_usd, metrics).await
+}
+
+/// Records an event `process_event` failed on in `dead_letter_events`, where
+/// `background::dead_letter` retries it. If the database is what failed this
+/// may fail too, in which case the event is only logged.
+pub async fn dead_letter(
+    pool: &PgPool,
+    signature: &str,
+    index: usize,
+    event: &PumpEvent,
+    slot: u64,
+    failure: &IndexerError,
+    metrics: &metrics::Metrics,
+) {
+    metrics.increment_dead_lettered_events();
+
+    let saved = match serde_json::to_string(event) {
+        Ok(payload) => database::save_dead_letter(
+            pool, signature, index, event.kind(), slot, &payload, &failure.to_string(),
+        ).await,
+        Err(e) => Err(IndexerError::Parse(e.to_string())),
+    };
+    match saved {
+        Ok(()) => warn!("📮 Dead-lettered {} event {}#{}: {}", event.kind(), signature, index, failure),
+        Err(e) => error!("Failed to dead-letter {} event {}#{} ({}): {}", event.kind(), signature, index, failure, e),
+    }
+}
+
+/// Updates live state, caches and metrics for an event `persist_event` has
+/// just stored.
+#[allow(clippy::too_many_arguments)]
+async fn apply_event(
+    pool: &PgPool,
+    event: &PumpEvent,
+    decimals: u8,
+    slot: u64,
+    redis: Option<&RedisClient>,
+    state_map: &state::TokenStateMap,
+    recent_trades: Option<&recent_trades::RecentTrades>,
+    sol_price_usd: f64,
+    metrics: &metrics::Metrics,
+) -> Result<Option<EventUpdate>> {
+    let update = match event {
         PumpEvent::Create(create) => {
             info!(
                 "🆕 New token: {} ({}) - Mint: {}",
@@ -104,11 +167,6 @@ pub async fn process_event(
                 create.mint
             );
 
-            if let Err(e) = database::save_token_creation(pool, &create).await {
-                error!("Failed to save token creation: {}", e);
-                return Err(e);
-            }
-
             state::init_token_state(
                 state_map,
                 create.mint.clone(),
@@ -119,66 +177,67 @@ pub async fn process_event(
                 create.virtual_token_reserves,
                 create.real_token_reserves,
                 create.token_total_supply,
+                calculator::DEFAULT_TOKEN_DECIMALS,
                 sol_price_usd,
             ).await;
 
-            let creation_msg = serde_json::json!({
-                "mint": create.mint,
-                "name": create.name,
-                "symbol": create.symbol,
-                "creator": create.user,
-                "timestamp": create.timestamp,
-                "market_cap_sol": 0.0,
-            });
+            let creation_msg = NewTokenMessage {
+                mint: create.mint.clone(),
+                name: create.name.clone(),
+                symbol: create.symbol.clone(),
+                creator: create.user.clone(),
+                timestamp: create.timestamp,
+                market_cap_sol: 0.0,
+                creator_list: None,
+            };
 
-            safe_publish(redis, "pump:tokens:new", &creation_msg).await;
+            response_cache::invalidate(redis, CacheScope::Tokens).await;
+            if let Some(recent_trades) = recent_trades {
+                recent_trades.record_token(&create.mint).await;
+            }
 
+            metrics.increment_tokens_created();
             info!("✅ Token saved to database and state initialized");
+
+            Some(EventUpdate::TokenCreated(creation_msg))
         }
 
         PumpEvent::Trade(trade) => {
             let action = if trade.is_buy { "BUY" } else { "SELL" };
-            let token_amt = trade.token_amount as f64 / 1_000_000.0;
-            let sol_amt = trade.sol_amount as f64 / 1_000_000_000.0;
+            let token_amt = calculator::to_ui_amount(trade.token_amount, decimals);
+            let sol_amt = calculator::to_ui_amount(trade.sol_amount, calculator::SOL_DECIMALS);
 
             info!(
                 "💰 {} {:.2} tokens for {:.4} SOL - Mint: {}",
                 action, token_amt, sol_amt, trade.mint
             );
 
-            if let Err(e) = ensure_token_exists(pool, &trade.mint).await {
-                error!("Failed to ensure token exists: {}", e);
-                return Err(e);
-            }
-
-            if let Err(e) = database::save_trade(pool, &trade).await {
-                error!("Failed to save trade: {}", e);
-                return Err(e);
+            response_cache::invalidate_key(redis, CacheScope::TokenDetail, &trade.mint).await;
+            if let Some(recent_trades) = recent_trades {
+                match recent_trades::RecentTrade::from_event(trade, decimals, sol_price_usd) {
+                    Ok(recent) => recent_trades.record(recent).await,
+                    Err(e) => error!("Failed to buffer trade {}: {}", trade.signature, e),
+                }
             }
 
-            let updated_state = state::update_token_state(
-                state_map,
-                &trade.mint,
-                trade.virtual_sol_reserves,
-                trade.virtual_token_reserves,
-                trade.real_sol_reserves,
-                trade.real_token_reserves,
-                sol_price_usd,
-            ).await;
+            let updated_state = state::apply_trade(state_map, trade, slot, sol_price_usd).await;
 
-            if let Some(state) = &updated_state {
-                if let Err(e) = database::update_token_metrics(
+            if let Some(state) = &updated_state
+                && let Err(e) = database::update_token_metrics(
                     pool,
                     &trade.mint,
-                    state.market_cap_sol,
+                    state.market_cap_usd,
                     state.bonding_curve_progress,
-                ).await {
-                    error!("Failed to update token metrics: {}", e);
-                }
+                ).await
+            {
+                error!("Failed to update token metrics: {}", e);
             }
 
-            if let Some(state) = updated_state {
-                let trade_msg = TradeMessage {
+            metrics.increment_trades_processed();
+            debug!("✅ Trade processed");
+
+            updated_state.map(|state| {
+                EventUpdate::Trade(TradeMessage {
                     signature: trade.signature.clone(),
                     mint: trade.mint.clone(),
                     is_buy: trade.is_buy,
@@ -188,38 +247,31 @@ pub async fn process_event(
                     timestamp: chrono::Utc.timestamp_opt(trade.timestamp, 0).unwrap(),
                     market_cap_usd: state.market_cap_usd,
                     price_sol: state.current_price_sol,
-                };
-
-                safe_publish(redis, "pump:trades", &trade_msg).await;
-
-                let token_channel = format!("pump:trades:{}", trade.mint);
-                safe_publish(redis, &token_channel, &trade_msg).await;
-            }
-
-            debug!("✅ Trade processed");
+                    seq: None,
+                })
+            })
         }
 
         PumpEvent::Complete(complete) => {
             info!("🎓 Token graduated to Raydium: {}", complete.mint);
 
-            if let Err(e) = database::mark_token_complete(pool, &complete.mint).await {
-                error!("Failed to mark token complete: {}", e);
-                return Err(e);
-            }
-
             state::mark_token_complete(state_map, &complete.mint).await;
+            response_cache::invalidate_key(redis, CacheScope::TokenDetail, &complete.mint).await;
 
-            let completion_msg = serde_json::json!({
-                "mint": complete.mint,
-                "user": complete.user,
-                "timestamp": complete.timestamp,
-            });
+            let completion_msg = GraduationMessage {
+                mint: complete.mint.clone(),
+                user: complete.user.clone(),
+                timestamp: complete.timestamp,
+            };
 
-            safe_publish(redis, "pump:completions", &completion_msg).await;
+            response_cache::invalidate(redis, CacheScope::Tokens).await;
 
+            metrics.increment_tokens_graduated();
             info!("✅ Token marked as complete");
+
+            Some(EventUpdate::Graduated(completion_msg))
         }
-    }
+    };
 
-    Ok(())
+    Ok(update)
 }
\ No newline at end of file
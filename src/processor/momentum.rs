@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use utoipa::ToSchema;
+
+/// Width of one bucket of the rolling window.
+const BUCKET_SECS: i64 = 10;
+/// Longest window reported; older buckets are dropped.
+const WINDOW_SECS: i64 = 15 * 60;
+/// Window `trades_per_minute` is averaged over.
+const RATE_WINDOW_SECS: i64 = 5 * 60;
+
+/// Trading activity over rolling windows ending now. Net buy pressure is
+/// buy volume minus sell volume, in SOL.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Momentum {
+    /// Average over the last 5 minutes
+    pub trades_per_minute: f64,
+    pub net_buy_sol_1m: f64,
+    pub net_buy_sol_5m: f64,
+    pub net_buy_sol_15m: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Bucket {
+    start: i64,
+    trades: u32,
+    /// Buy minus sell lamports.
+    net_lamports: i64,
+}
+
+/// Trades bucketed by block time over the last 15 minutes, updated as each
+/// trade is processed so momentum never needs a query.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeWindow {
+    buckets: VecDeque<Bucket>,
+}
+
+impl TradeWindow {
+    pub fn record(&mut self, timestamp: i64, is_buy: bool, sol_amount: u64) {
+        let start = timestamp - timestamp.rem_euclid(BUCKET_SECS);
+        let sol_amount = i64::try_from(sol_amount).unwrap_or(i64::MAX);
+        let net = if is_buy { sol_amount } else { -sol_amount };
+
+        match self.buckets.iter_mut().rev().find(|b| b.start == start) {
+            Some(bucket) => {
+                bucket.trades += 1;
+                bucket.net_lamports = bucket.net_lamports.saturating_add(net);
+            }
+            None => {
+                // Trades arrive roughly in order, so this is nearly always a push.
+                let at = self.buckets.iter().rposition(|b| b.start < start).map_or(0, |i| i + 1);
+                self.buckets.insert(at, Bucket { start, trades: 1, net_lamports: net });
+            }
+        }
+
+        if let Some(newest) = self.buckets.back().map(|b| b.start) {
+            self.expire(newest);
+        }
+    }
+
+    fn expire(&mut self, now: i64) {
+        while self.buckets.front().is_some_and(|b| b.start <= now - WINDOW_SECS) {
+            self.buckets.pop_front();
+        }
+    }
+
+    pub fn momentum(&self, now: i64) -> Momentum {
+        let sum = |secs: i64| {
+            self.buckets
+                .iter()
+                .filter(|b| b.start > now - secs && b.start <= now)
+                .fold((0u32, 0i64), |(trades, net), b| (trades + b.trades, net.saturating_add(b.net_lamports)))
+        };
+        let sol = |lamports: i64| lamports as f64 / 1_000_000_000.0;
+
+        let (trades_5m, net_5m) = sum(RATE_WINDOW_SECS);
+        Momentum {
+            trades_per_minute: trades_5m as f64 / (RATE_WINDOW_SECS as f64 / 60.0),
+            net_buy_sol_1m: sol(sum(60).1),
+            net_buy_sol_5m: sol(net_5m),
+            net_buy_sol_15m: sol(sum(WINDOW_SECS).1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_windows() {
+        let now = 1_700_000_000;
+        let mut window = TradeWindow::default();
+        window.record(now - 600, true, 4_000_000_000);
+        window.record(now - 120, true, 2_000_000_000);
+        window.record(now - 30, false, 500_000_000);
+        window.record(now - 20, true, 1_000_000_000);
+        // Arrives late but still lands in its own bucket.
+        window.record(now - 125, false, 1_000_000_000);
+
+        let momentum = window.momentum(now);
+        assert_eq!(momentum.trades_per_minute, 4.0 / 5.0);
+        assert_eq!(momentum.net_buy_sol_1m, 0.5);
+        assert_eq!(momentum.net_buy_sol_5m, 1.5);
+        assert_eq!(momentum.net_buy_sol_15m, 5.5);
+
+        // Everything ages out once the window has passed.
+        window.record(now + WINDOW_SECS, true, 1);
+        assert_eq!(window.buckets.len(), 1);
+        assert_eq!(window.momentum(now + 2 * WINDOW_SECS), Momentum::default());
+    }
+}
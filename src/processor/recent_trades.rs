@@ -0,0 +1,266 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use crate::database::{model::TradeEventData, to_bigint};
+use crate::error::Result;
+use super::calculator;
+
+/// Trades kept across all tokens.
+pub const GLOBAL_CAPACITY: usize = 1_000;
+/// Trades kept per token.
+pub const TOKEN_CAPACITY: usize = 100;
+/// Tokens with their own buffer; past this the least recently traded one is
+/// dropped.
+const MAX_TOKENS: usize = 10_000;
+
+/// A trade shaped like a `trades` row as the API returns it, built from the
+/// event so it never has to be read back.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecentTrade {
+    pub signature: String,
+    pub token_mint: String,
+    pub user_wallet: String,
+    pub is_buy: bool,
+    pub sol_amount: i64,
+    pub token_amount: i64,
+    pub timestamp: DateTime<Utc>,
+    pub price_sol: f64,
+    pub price_usd: Option<f64>,
+    pub value_usd: Option<f64>,
+    pub price_before_sol: Option<f64>,
+    pub price_impact_pct: Option<f64>,
+    pub slippage_pct: Option<f64>,
+    pub virtual_sol_reserves: i64,
+    pub virtual_token_reserves: i64,
+    pub real_sol_reserves: i64,
+    pub real_token_reserves: i64,
+}
+
+impl RecentTrade {
+    pub fn from_event(trade: &TradeEventData, token_decimals: u8, sol_price_usd: f64) -> Result<Self> {
+        let impact = calculator::calculate_trade_impact(
+            trade.sol_amount,
+            trade.token_amount,
+            trade.is_buy,
+            trade.virtual_sol_reserves,
+            trade.virtual_token_reserves,
+            token_decimals,
+        );
+
+        Ok(Self {
+            signature: trade.signature.clone(),
+            token_mint: trade.mint.clone(),
+            user_wallet: trade.user.clone(),
+            is_buy: trade.is_buy,
+            sol_amount: to_bigint(trade.sol_amount, "sol_amount")?,
+            token_amount: to_bigint(trade.token_amount, "token_amount")?,
+            timestamp: Utc.timestamp_opt(trade.timestamp, 0).single().unwrap_or_else(Utc::now),
+            price_sol: impact.price_after_sol,
+            price_usd: Some(impact.price_after_sol * sol_price_usd),
+            value_usd: Some(calculator::to_ui_amount(trade.sol_amount, calculator::SOL_DECIMALS) * sol_price_usd),
+            price_before_sol: Some(impact.price_before_sol),
+            price_impact_pct: Some(impact.price_impact_pct),
+            slippage_pct: Some(impact.slippage_pct),
+            virtual_sol_reserves: to_bigint(trade.virtual_sol_reserves, "virtual_sol_reserves")?,
+            virtual_token_reserves: to_bigint(trade.virtual_token_reserves, "virtual_token_reserves")?,
+            real_sol_reserves: to_bigint(trade.real_sol_reserves, "real_sol_reserves")?,
+            real_token_reserves: to_bigint(trade.real_token_reserves, "real_token_reserves")?,
+        })
+    }
+}
+
+/// The newest trades of one token or of all of them, newest first. The
+/// buffer always holds every trade from its oldest entry on, so the first
+/// `len` trades can be served from it as if read from `trades`.
+#[derive(Debug, Default)]
+struct TradeWindow {
+    trades: VecDeque<RecentTrade>,
+    /// Set when the buffer holds the token's whole history, i.e. it was
+    /// created with the token and has not evicted anything since.
+    complete: bool,
+}
+
+impl TradeWindow {
+    fn insert(&mut self, trade: RecentTrade, capacity: usize) {
+        if self.trades.iter().any(|t| t.signature == trade.signature) {
+            return;
+        }
+
+        let key = (trade.timestamp, &trade.signature);
+        let at = self
+            .trades
+            .iter()
+            .position(|t| (t.timestamp, &t.signature) < key)
+            .unwrap_or(self.trades.len());
+        // A trade older than everything held would leave a gap before it,
+        // unless nothing older exists.
+        if at == self.trades.len() && !self.trades.is_empty() && !self.complete {
+            return;
+        }
+
+        self.trades.insert(at, trade);
+        if self.trades.len() > capacity {
+            self.trades.pop_back();
+            self.complete = false;
+        }
+    }
+
+    /// The requested page, or `None` when it reaches past what is held.
+    fn page(&self, offset: usize, limit: usize) -> Option<Vec<RecentTrade>> {
+        (self.complete || offset + limit <= self.trades.len())
+            .then(|| self.trades.iter().skip(offset).take(limit).cloned().collect())
+    }
+
+    fn newest(&self) -> Option<DateTime<Utc>> {
+        self.trades.front().map(|t| t.timestamp)
+    }
+}
+
+#[derive(Debug)]
+struct Buffers {
+    global: TradeWindow,
+    tokens: HashMap<String, TradeWindow>,
+}
+
+/// The latest trades overall and per token, filled by the processor so the
+/// first pages of trade listings skip Postgres. Starts empty; until enough
+/// trades arrive after startup, requests fall through to the database.
+#[derive(Debug, Clone)]
+pub struct RecentTrades {
+    buffers: Arc<RwLock<Buffers>>,
+}
+
+impl Default for RecentTrades {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A page of one token's trades from memory.
+pub struct TokenPage {
+    pub trades: Vec<RecentTrade>,
+    /// The token's trade count, when the buffer holds all of them.
+    pub total: Option<i64>,
+}
+
+impl RecentTrades {
+    pub fn new() -> Self {
+        Self {
+            buffers: Arc::new(RwLock::new(Buffers { global: TradeWindow::default(), tokens: HashMap::new() })),
+        }
+    }
+
+    /// Starts a complete buffer for a token seen being created.
+    pub async fn record_token(&self, mint: &str) {
+        let mut buffers = self.buffers.write().await;
+        if !buffers.tokens.contains_key(mint) {
+            make_room(&mut buffers.tokens);
+        }
+        buffers
+            .tokens
+            .entry(mint.to_string())
+            .or_insert_with(|| TradeWindow { trades: VecDeque::new(), complete: true });
+    }
+
+    pub async fn record(&self, trade: RecentTrade) {
+        let mut buffers = self.buffers.write().await;
+        if !buffers.tokens.contains_key(&trade.token_mint) {
+            make_room(&mut buffers.tokens);
+        }
+        buffers
+            .tokens
+            .entry(trade.token_mint.clone())
+            .or_default()
+            .insert(trade.clone(), TOKEN_CAPACITY);
+        buffers.global.insert(trade, GLOBAL_CAPACITY);
+    }
+
+    /// Drops a token's buffer and the global one, for when trades were
+    /// stored without passing through here and may fall inside them.
+    pub async fn forget(&self, mint: &str) {
+        let mut buffers = self.buffers.write().await;
+        buffers.tokens.remove(mint);
+        buffers.global = TradeWindow::default();
+    }
+
+    pub async fn latest(&self, offset: usize, limit: usize) -> Option<Vec<RecentTrade>> {
+        self.buffers.read().await.global.page(offset, limit)
+    }
+
+    pub async fn latest_for(&self, mint: &str, offset: usize, limit: usize) -> Option<TokenPage> {
+        let buffers = self.buffers.read().await;
+        let window = buffers.tokens.get(mint)?;
+        let trades = window.page(offset, limit)?;
+        Some(TokenPage { trades, total: window.complete.then_some(window.trades.len() as i64) })
+    }
+}
+
+fn make_room(tokens: &mut HashMap<String, TradeWindow>) {
+    if tokens.len() < MAX_TOKENS {
+        return;
+    }
+    let stalest = tokens
+        .iter()
+        .min_by_key(|(_, window)| window.newest())
+        .map(|(mint, _)| mint.clone());
+    if let Some(mint) = stalest {
+        tokens.remove(&mint);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(signature: &str, secs: i64) -> RecentTrade {
+        RecentTrade {
+            signature: signature.to_string(),
+            token_mint: "mint".to_string(),
+            user_wallet: "wallet".to_string(),
+            is_buy: true,
+            sol_amount: 1,
+            token_amount: 1,
+            timestamp: Utc.timestamp_opt(secs, 0).unwrap(),
+            price_sol: 0.0,
+            price_usd: None,
+            value_usd: None,
+            price_before_sol: None,
+            price_impact_pct: None,
+            slippage_pct: None,
+            virtual_sol_reserves: 0,
+            virtual_token_reserves: 0,
+            real_sol_reserves: 0,
+            real_token_reserves: 0,
+        }
+    }
+
+    fn signatures(trades: Vec<RecentTrade>) -> Vec<String> {
+        trades.into_iter().map(|t| t.signature).collect()
+    }
+
+    #[test]
+    fn test_trade_window() {
+        let mut window = TradeWindow::default();
+        window.insert(trade("b", 20), 3);
+        window.insert(trade("c", 30), 3);
+        window.insert(trade("b", 20), 3);
+        // Older than anything held, and the window may not hold all history.
+        window.insert(trade("a", 10), 3);
+
+        assert_eq!(signatures(window.page(0, 2).unwrap()), ["c", "b"]);
+        assert!(window.page(0, 3).is_none());
+
+        let mut window = TradeWindow { complete: true, ..Default::default() };
+        window.insert(trade("b", 20), 3);
+        window.insert(trade("a", 10), 3);
+        assert_eq!(signatures(window.page(0, 50).unwrap()), ["b", "a"]);
+
+        window.insert(trade("d", 40), 3);
+        window.insert(trade("c", 30), 3);
+        assert!(!window.complete);
+        assert_eq!(signatures(window.page(1, 2).unwrap()), ["c", "b"]);
+        assert!(window.page(0, 4).is_none());
+    }
+}
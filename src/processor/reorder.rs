@@ -0,0 +1,197 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use crate::helius::{parser, RawTransaction};
+use super::metrics::Metrics;
+
+/// How long a fetched transaction waits for earlier ones whose fetches are
+/// still in flight. Fetches all sleep the same 2s, so this only has to
+/// cover RPC latency jitter.
+pub const REORDER_HOLD: Duration = Duration::from_secs(2);
+/// Past this many buffered transactions the oldest is released early.
+const MAX_BUFFERED: usize = 1_000;
+
+/// Holds items for `hold` after they arrive and releases them in
+/// `(slot, seq)` order. Once an item's hold expires, it and every item
+/// ordered before it are released, so a late arrival can only overtake
+/// items that were still waiting. Urgent items skip both and go out first.
+#[derive(Debug)]
+pub struct ReorderBuffer<T> {
+    hold: Duration,
+    /// Keyed by `(slot, seq, arrival)`; the arrival counter keeps repeated
+    /// `(slot, seq)` pairs apart.
+    entries: BTreeMap<(u64, u64, u64), (Instant, T)>,
+    arrivals: u64,
+    urgent: VecDeque<T>,
+}
+
+impl<T> ReorderBuffer<T> {
+    pub fn new(hold: Duration) -> Self {
+        Self { hold, entries: BTreeMap::new(), arrivals: 0, urgent: VecDeque::new() }
+    }
+
+    /// Queues an item ahead of everything held, in arrival order.
+    pub fn push_urgent(&mut self, item: T) {
+        self.urgent.push_back(item);
+    }
+
+    /// Whether an item is ready but not yet taken, i.e. the consumer is
+    /// falling behind.
+    pub fn is_backlogged(&self, now: Instant) -> bool {
+        self.next_release().is_some_and(|at| at <= now)
+    }
+
+    pub fn push(&mut self, slot: u64, seq: u64, item: T, now: Instant) {
+        self.arrivals += 1;
+        self.entries.insert((slot, seq, self.arrivals), (now, item));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.urgent.is_empty()
+    }
+
+    /// The next item in order, if its turn has come.
+    pub fn pop_ready(&mut self, now: Instant) -> Option<T> {
+        if let Some(item) = self.urgent.pop_front() {
+            return Some(item);
+        }
+
+        let released_up_to = self
+            .entries
+            .iter()
+            .filter(|(_, (arrived, _))| now.duration_since(*arrived) >= self.hold)
+            .map(|(key, _)| *key)
+            .next_back();
+
+        let first = *self.entries.keys().next()?;
+        if released_up_to.is_some_and(|key| first <= key) || self.entries.len() > MAX_BUFFERED {
+            return self.entries.remove(&first).map(|(_, item)| item);
+        }
+        None
+    }
+
+    /// When the earliest-arrived item's hold expires.
+    pub fn next_release(&self) -> Option<Instant> {
+        self.entries.values().map(|(arrived, _)| *arrived + self.hold).min()
+    }
+
+    /// The next item in order, without waiting for its hold.
+    pub fn pop_first(&mut self) -> Option<T> {
+        if let Some(item) = self.urgent.pop_front() {
+            return Some(item);
+        }
+        self.entries.pop_first().map(|(_, (_, item))| item)
+    }
+}
+
+/// The processor's side of the listener channel: fetched transactions come
+/// out in slot order rather than in the order their fetches finished.
+pub struct SlotSequencer {
+    receiver: mpsc::UnboundedReceiver<RawTransaction>,
+    buffer: ReorderBuffer<RawTransaction>,
+    metrics: Metrics,
+}
+
+impl SlotSequencer {
+    pub fn new(receiver: mpsc::UnboundedReceiver<RawTransaction>, metrics: Metrics) -> Self {
+        Self { receiver, buffer: ReorderBuffer::new(REORDER_HOLD), metrics }
+    }
+
+    /// The next transaction in slot order; `None` once the listener is gone
+    /// and the buffer is empty.
+    pub async fn next(&mut self) -> Option<RawTransaction> {
+        loop {
+            // Take in everything already fetched, so a Create queued behind
+            // a backlog is seen and can go first.
+            let now = Instant::now();
+            while let Ok(raw_tx) = self.receiver.try_recv() {
+                self.accept(raw_tx, now);
+            }
+
+            if let Some(raw_tx) = self.buffer.pop_ready(now) {
+                return Some(self.released(raw_tx));
+            }
+
+            let received = match self.buffer.next_release() {
+                Some(at) => tokio::time::timeout_at(at.into(), self.receiver.recv()).await.ok(),
+                None => Some(self.receiver.recv().await),
+            };
+            match received {
+                Some(Some(raw_tx)) => self.accept(raw_tx, Instant::now()),
+                Some(None) => return self.buffer.pop_first().map(|raw_tx| self.released(raw_tx)),
+                // A hold expired; loop round to release it.
+                None => {}
+            }
+        }
+    }
+
+    /// While the processor is behind, transactions creating a token jump
+    /// the queue: every later trade of that token would otherwise find no
+    /// token row and take the placeholder path. Nothing of the token can be
+    /// ordered before its Create, so this never reorders its own events.
+    fn accept(&mut self, raw_tx: RawTransaction, now: Instant) {
+        if self.buffer.is_backlogged(now) && parser::contains_create_event(&raw_tx.transaction) {
+            self.metrics.increment_prioritized_creates();
+            self.buffer.push_urgent(raw_tx);
+        } else {
+            self.buffer.push(raw_tx.slot, raw_tx.seq, raw_tx, now);
+        }
+    }
+
+    fn released(&self, raw_tx: RawTransaction) -> RawTransaction {
+        self.metrics.channel_depth.fetch_sub(1, Ordering::Relaxed);
+        raw_tx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_releases_in_slot_order() {
+        let hold = Duration::from_secs(2);
+        let start = Instant::now();
+        let mut buffer = ReorderBuffer::new(hold);
+
+        buffer.push(12, 3, "c", start);
+        buffer.push(10, 1, "a", start + Duration::from_millis(300));
+        buffer.push(11, 2, "b", start + Duration::from_millis(500));
+        assert_eq!(buffer.pop_ready(start + Duration::from_secs(1)), None);
+        assert_eq!(buffer.next_release(), Some(start + hold));
+
+        // "c" has waited long enough, so everything before it goes too.
+        let now = start + hold;
+        assert_eq!(buffer.pop_ready(now), Some("a"));
+        assert_eq!(buffer.pop_ready(now), Some("b"));
+        assert_eq!(buffer.pop_ready(now), Some("c"));
+        assert!(buffer.is_empty());
+
+        // Same slot: listener order decides.
+        buffer.push(13, 5, "e", now);
+        buffer.push(13, 4, "d", now);
+        assert_eq!(buffer.pop_first(), Some("d"));
+        assert_eq!(buffer.pop_first(), Some("e"));
+    }
+
+    #[test]
+    fn test_urgent_items_skip_the_queue() {
+        let hold = Duration::from_secs(2);
+        let start = Instant::now();
+        let mut buffer = ReorderBuffer::new(hold);
+
+        buffer.push(10, 1, "trade", start);
+        assert!(!buffer.is_backlogged(start + Duration::from_secs(1)));
+        let now = start + hold;
+        assert!(buffer.is_backlogged(now));
+
+        buffer.push(11, 2, "later trade", now);
+        buffer.push_urgent("create");
+        assert_eq!(buffer.pop_ready(now), Some("create"));
+        assert_eq!(buffer.pop_ready(now), Some("trade"));
+        assert_eq!(buffer.pop_ready(now), None);
+        assert_eq!(buffer.pop_first(), Some("later trade"));
+        assert!(buffer.is_empty());
+    }
+}
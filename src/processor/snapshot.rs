@@ -0,0 +1,159 @@
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use anyhow::{bail, Context};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use crate::database;
+use crate::error::Result;
+use super::metrics::Metrics;
+use super::momentum::TradeWindow;
+use super::state::{self, TokenState, TokenStateMap};
+
+/// Bumped whenever the layout changes; older snapshots are refused.
+pub const SNAPSHOT_VERSION: u32 = 1;
+/// Trades are stamped with block time, which trails the wall clock a little;
+/// tokens active this close to `taken_at` are refreshed too.
+const CLOCK_MARGIN_SECS: i64 = 60;
+
+/// The complete in-memory token state, as served by
+/// `GET /api/admin/snapshot` and loaded by `--restore`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub version: u32,
+    pub taken_at: DateTime<Utc>,
+    pub last_seen_slot: u64,
+    pub last_processed_slot: u64,
+    pub last_processed_block_time: i64,
+    pub tokens: Vec<SnapshotToken>,
+}
+
+/// A token's state plus the parts the API never shows: its momentum
+/// window and the slot its reserves are from.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotToken {
+    #[serde(flatten)]
+    pub state: TokenState,
+    pub trades: TradeWindow,
+    pub reserves_slot: u64,
+}
+
+pub async fn take(state_map: &TokenStateMap, metrics: &Metrics) -> StateSnapshot {
+    let map = state_map.read().await;
+    StateSnapshot {
+        version: SNAPSHOT_VERSION,
+        taken_at: Utc::now(),
+        last_seen_slot: metrics.last_seen_slot.load(Ordering::Relaxed),
+        last_processed_slot: metrics.last_processed_slot.load(Ordering::Relaxed),
+        last_processed_block_time: metrics.last_processed_block_time.load(Ordering::Relaxed),
+        tokens: map
+            .values()
+            .map(|state| SnapshotToken {
+                trades: state.trades.clone(),
+                reserves_slot: state.reserves_slot,
+                state: state.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Replaces the state map's contents with the snapshot's. Returns how many
+/// tokens were loaded.
+pub async fn restore(state_map: &TokenStateMap, metrics: &Metrics, snapshot: StateSnapshot) -> usize {
+    let mut map = state_map.write().await;
+    map.clear();
+    for token in snapshot.tokens {
+        let mut state = token.state;
+        state.trades = token.trades;
+        state.reserves_slot = token.reserves_slot;
+        map.insert(state.mint.clone(), state);
+    }
+
+    metrics.last_seen_slot.fetch_max(snapshot.last_seen_slot, Ordering::Relaxed);
+    metrics.last_processed_slot.fetch_max(snapshot.last_processed_slot, Ordering::Relaxed);
+    metrics.last_processed_block_time.fetch_max(snapshot.last_processed_block_time, Ordering::Relaxed);
+    map.len()
+}
+
+pub async fn read_file(path: &Path) -> anyhow::Result<StateSnapshot> {
+    let contents = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+    let snapshot: StateSnapshot = serde_json::from_slice(&contents)
+        .with_context(|| format!("{} is not a state snapshot", path.display()))?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        bail!("snapshot {} has version {}, this build reads {}", path.display(), snapshot.version, SNAPSHOT_VERSION);
+    }
+    Ok(snapshot)
+}
+
+/// Brings tokens that changed after the snapshot was taken up to date from
+/// the database: reserves are reloaded (their momentum window is kept) and
+/// tokens created since are added. Returns how many were refreshed.
+pub async fn refresh_since(
+    pool: &PgPool,
+    state_map: &TokenStateMap,
+    taken_at: DateTime<Utc>,
+    sol_price_usd: f64,
+) -> Result<usize> {
+    let mints = database::fetch_mints_active_since(pool, taken_at - Duration::seconds(CLOCK_MARGIN_SECS)).await?;
+
+    for mint in &mints {
+        let Some(snapshot) = database::fetch_token_snapshot(pool, mint).await? else {
+            continue;
+        };
+        if state_map.read().await.contains_key(mint) {
+            let complete = snapshot.complete;
+            state::update_token_state(
+                state_map,
+                mint,
+                snapshot.virtual_sol_reserves as u64,
+                snapshot.virtual_token_reserves as u64,
+                snapshot.real_sol_reserves as u64,
+                snapshot.real_token_reserves as u64,
+                sol_price_usd,
+            ).await;
+            if complete {
+                state::mark_token_complete(state_map, mint).await;
+            }
+        } else {
+            state::restore_token_state(state_map, mint, snapshot, sol_price_usd).await;
+        }
+    }
+    Ok(mints.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_round_trip_keeps_momentum() {
+        let state_map = state::create_state_map();
+        state::init_token_state(
+            &state_map, "mint".to_string(), "Token".to_string(), "TKN".to_string(), "creator".to_string(),
+            30_000_000_000, 1_073_000_000_000_000, 793_100_000_000_000, 1_000_000_000_000_000, 6, 150.0,
+        ).await;
+        let now = Utc::now().timestamp();
+        {
+            let mut map = state_map.write().await;
+            let token = map.get_mut("mint").unwrap();
+            token.trades.record(now - 30, true, 2_000_000_000);
+            token.reserves_slot = 42;
+        }
+        let metrics = Metrics::new();
+        metrics.last_processed_slot.store(42, Ordering::Relaxed);
+
+        let json = serde_json::to_vec(&take(&state_map, &metrics).await).unwrap();
+        let restored_map = state::create_state_map();
+        let restored_metrics = Metrics::new();
+        let count = restore(&restored_map, &restored_metrics, serde_json::from_slice(&json).unwrap()).await;
+
+        assert_eq!(count, 1);
+        assert_eq!(restored_metrics.last_processed_slot.load(Ordering::Relaxed), 42);
+        let token = state::get_token_state(&restored_map, "mint").await.unwrap();
+        assert_eq!(token.reserves_slot, 42);
+        assert_eq!(token.momentum.net_buy_sol_1m, 2.0);
+        assert_eq!(token.virtual_sol_reserves, 30_000_000_000);
+    }
+}
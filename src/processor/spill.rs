@@ -0,0 +1,220 @@
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Duration};
+use tracing::{error, warn};
+use crate::error::{IndexerError, Result};
+use crate::helius::parser::PumpEvent;
+use super::metrics::Metrics;
+
+const FILE_NAME: &str = "events.ndjson";
+/// Attempts at an operation before a retryable failure is given up on.
+pub const RETRY_ATTEMPTS: u32 = 4;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// Runs `operation` again after a retryable failure (see
+/// `IndexerError::is_retryable`), backing off 250ms, 500ms, 1s between
+/// attempts. The last error is returned once `RETRY_ATTEMPTS` are used up.
+pub async fn retry_transient<T, F, Fut>(mut operation: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(e) if e.is_retryable() && attempt < RETRY_ATTEMPTS => {
+                let delay = RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+                warn!("Attempt {} failed, retrying in {:?}: {}", attempt, delay, e);
+                sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// An event waiting in the spill queue, serialized like a dead letter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpilledEvent {
+    pub signature: String,
+    pub index: usize,
+    pub slot: u64,
+    pub kind: String,
+    pub payload: String,
+}
+
+impl SpilledEvent {
+    pub fn new(signature: &str, index: usize, slot: u64, event: &PumpEvent) -> serde_json::Result<Self> {
+        Ok(Self {
+            signature: signature.to_string(),
+            index,
+            slot,
+            kind: event.kind().to_string(),
+            payload: serde_json::to_string(event)?,
+        })
+    }
+
+    pub fn event(&self) -> serde_json::Result<PumpEvent> {
+        PumpEvent::from_parts(&self.kind, &self.payload)
+    }
+}
+
+/// Events that couldn't be stored because the database was unreachable,
+/// kept on local disk (one JSON line each, oldest first) until
+/// `background::spill` applies them.
+///
+/// While the queue holds anything, new events are appended behind it
+/// rather than stored directly, so they still reach the database in order:
+/// a trade can't land before the create it depends on.
+#[derive(Clone)]
+pub struct SpillQueue {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    path: PathBuf,
+    /// Serializes appends and rewrites of the file.
+    file: Mutex<()>,
+    len: AtomicUsize,
+}
+
+impl SpillQueue {
+    /// Opens the queue in `dir`, picking up events left by a previous run.
+    pub async fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref()).await?;
+        let path = dir.as_ref().join(FILE_NAME);
+        let len = match fs::read_to_string(&path).await {
+            Ok(contents) => contents.lines().filter(|line| !line.is_empty()).count(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            inner: Arc::new(Inner { path, file: Mutex::new(()), len: AtomicUsize::new(len) }),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub async fn push(&self, entry: &SpilledEvent) -> io::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let _guard = self.inner.file.lock().await;
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&self.inner.path).await?;
+        file.write_all(line.as_bytes()).await?;
+        file.sync_data().await?;
+        self.inner.len.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Up to `limit` of the oldest lines, parsed. A line that doesn't parse
+    /// comes back as an error so the caller can count and skip it.
+    pub async fn peek(&self, limit: usize) -> io::Result<Vec<serde_json::Result<SpilledEvent>>> {
+        let _guard = self.inner.file.lock().await;
+        let contents = match fs::read_to_string(&self.inner.path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        Ok(contents
+            .lines()
+            .filter(|line| !line.is_empty())
+            .take(limit)
+            .map(serde_json::from_str)
+            .collect())
+    }
+
+    /// Drops the `count` oldest events, once they've been dealt with.
+    /// Events appended since `peek` are kept.
+    pub async fn remove_front(&self, count: usize) -> io::Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        let _guard = self.inner.file.lock().await;
+        let contents = fs::read_to_string(&self.inner.path).await?;
+        let rest: Vec<&str> = contents.lines().filter(|line| !line.is_empty()).skip(count).collect();
+
+        let tmp = self.inner.path.with_extension("ndjson.tmp");
+        let mut rewritten = rest.join("\n");
+        if !rewritten.is_empty() {
+            rewritten.push('\n');
+        }
+        fs::write(&tmp, rewritten).await?;
+        fs::rename(&tmp, &self.inner.path).await?;
+        self.inner.len.store(rest.len(), Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Appends an event to the spill queue after `failure` suggested the
+/// database is down. If the disk fails too the event is only logged.
+pub async fn spill(
+    queue: &SpillQueue,
+    signature: &str,
+    index: usize,
+    event: &PumpEvent,
+    slot: u64,
+    failure: Option<&IndexerError>,
+    metrics: &Metrics,
+) {
+    let saved = match SpilledEvent::new(signature, index, slot, event) {
+        Ok(entry) => queue.push(&entry).await,
+        Err(e) => Err(e.into()),
+    };
+    match saved {
+        Ok(()) => {
+            metrics.increment_spilled_events();
+            metrics.spill_queue_events.store(queue.len() as i64, Ordering::Relaxed);
+            match failure {
+                Some(failure) => warn!("💾 Spilled {} event {}#{} to disk: {}", event.kind(), signature, index, failure),
+                None => warn!("💾 Spilled {} event {}#{} behind {} queued", event.kind(), signature, index, queue.len() - 1),
+            }
+        }
+        Err(e) => error!("Failed to spill {} event {}#{}: {}", event.kind(), signature, index, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::model::CompleteEvent;
+
+    #[tokio::test]
+    async fn test_queue_keeps_order_across_reopen() {
+        let dir = std::env::temp_dir().join(format!("pump-spill-{}", std::process::id()));
+        let event = PumpEvent::Complete(CompleteEvent {
+            user: "alice".to_string(),
+            mint: "mint".to_string(),
+            bonding_curve: "curve".to_string(),
+            timestamp: 1_700_000_000,
+        });
+
+        let queue = SpillQueue::open(&dir).await.unwrap();
+        for index in 0..3 {
+            queue.push(&SpilledEvent::new("sig", index, 1, &event).unwrap()).await.unwrap();
+        }
+        queue.remove_front(1).await.unwrap();
+
+        let reopened = SpillQueue::open(&dir).await.unwrap();
+        assert_eq!(reopened.len(), 2);
+        let entries = reopened.peek(10).await.unwrap();
+        let indexes: Vec<usize> = entries.into_iter().map(|entry| entry.unwrap().index).collect();
+        assert_eq!(indexes, [1, 2]);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -2,9 +2,15 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use utoipa::ToSchema;
+use crate::database::model::{TokenSnapshot, TradeEventData};
+use super::calculator::{self, DEFAULT_TOKEN_DECIMALS};
+use super::momentum::{Momentum, TradeWindow};
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TokenState {
     pub mint: String,
     pub name: String,
@@ -24,8 +30,26 @@ pub struct TokenState {
     
     
     pub total_supply: u64,
+    /// Decimals of the mint; reserves and supply are in its raw units.
+    pub decimals: u8,
     pub complete: bool,
     pub last_updated: DateTime<Utc>,
+
+    /// As of the last trade; read the state through `get_token_state` or
+    /// `get_all_tokens` to get it as of now.
+    pub momentum: Momentum,
+    #[serde(skip)]
+    pub trades: TradeWindow,
+    /// Slot of the trade the reserves come from; 0 until one is applied.
+    #[serde(skip)]
+    pub reserves_slot: u64,
+}
+
+impl TokenState {
+    /// Recomputes `momentum` for windows ending at `now` (unix seconds).
+    pub fn refresh_momentum(&mut self, now: i64) {
+        self.momentum = self.trades.momentum(now);
+    }
 }
 
 
@@ -35,6 +59,7 @@ pub fn create_state_map() -> TokenStateMap {
     Arc::new(RwLock::new(HashMap::new()))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn init_token_state(
     state_map: &TokenStateMap,
     mint: String,
@@ -45,24 +70,15 @@ pub async fn init_token_state(
     virtual_token_reserves: u64,
     real_token_reserves: u64,
     total_supply: u64,
+    decimals: u8,
     sol_price_usd: f64,
 ) {
     let mut map = state_map.write().await;
     
-    let price_sol = if virtual_token_reserves > 0 {
-        (virtual_sol_reserves as f64 / 1_000_000_000.0) / 
-        (virtual_token_reserves as f64 / 1_000_000.0)
-    } else {
-        0.0
-    };
-    
-    let market_cap_sol = price_sol * (total_supply as f64 / 1_000_000.0);
+    let price_sol = calculator::calculate_price_sol(virtual_sol_reserves, virtual_token_reserves, decimals);
+    let market_cap_sol = calculator::calculate_market_cap_sol(price_sol, total_supply, decimals);
     let market_cap_usd = market_cap_sol * sol_price_usd;
-    
-
-    const TARGET_SOL: f64 = 85.0;
-    let sol_in_curve = virtual_sol_reserves as f64 / 1_000_000_000.0;
-    let progress = ((sol_in_curve / TARGET_SOL) * 100.0).min(100.0).max(0.0);
+    let progress = calculator::calculate_bonding_curve_progress(virtual_sol_reserves);
     
     let token_state = TokenState {
         mint: mint.clone(),
@@ -78,8 +94,12 @@ pub async fn init_token_state(
         market_cap_usd,
         bonding_curve_progress: progress,
         total_supply,
+        decimals,
         complete: false,
         last_updated: Utc::now(),
+        momentum: Momentum::default(),
+        trades: TradeWindow::default(),
+        reserves_slot: 0,
     };
     
     map.insert(mint, token_state);
@@ -97,35 +117,76 @@ pub async fn update_token_state(
     let mut map = state_map.write().await;
     
     if let Some(state) = map.get_mut(mint) {
-        state.virtual_sol_reserves = virtual_sol_reserves;
-        state.virtual_token_reserves = virtual_token_reserves;
-        state.real_sol_reserves = real_sol_reserves;
-        state.real_token_reserves = real_token_reserves;
-        
-
-        state.current_price_sol = if virtual_token_reserves > 0 {
-            (virtual_sol_reserves as f64 / 1_000_000_000.0) / 
-            (virtual_token_reserves as f64 / 1_000_000.0)
-        } else {
-            0.0
-        };
-        
-        state.market_cap_sol = state.current_price_sol * (state.total_supply as f64 / 1_000_000.0);
-        state.market_cap_usd = state.market_cap_sol * sol_price_usd;
-        
-
-        const TARGET_SOL: f64 = 85.0;
-        let sol_in_curve = virtual_sol_reserves as f64 / 1_000_000_000.0;
-        state.bonding_curve_progress = ((sol_in_curve / TARGET_SOL) * 100.0).min(100.0).max(0.0);
-        
-        state.last_updated = Utc::now();
-        
+        apply_reserves(
+            state,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_sol_reserves,
+            real_token_reserves,
+            sol_price_usd,
+        );
         Some(state.clone())
     } else {
         None
     }
 }
 
+/// Applies a trade's reserves and adds it to the token's momentum window.
+/// A trade from an earlier slot than the current reserves still counts
+/// towards momentum but leaves the reserves alone, so they never go back.
+pub async fn apply_trade(
+    state_map: &TokenStateMap,
+    trade: &TradeEventData,
+    slot: u64,
+    sol_price_usd: f64,
+) -> Option<TokenState> {
+    let mut map = state_map.write().await;
+
+    let state = map.get_mut(&trade.mint)?;
+    if slot >= state.reserves_slot {
+        apply_reserves(
+            state,
+            trade.virtual_sol_reserves,
+            trade.virtual_token_reserves,
+            trade.real_sol_reserves,
+            trade.real_token_reserves,
+            sol_price_usd,
+        );
+        state.reserves_slot = slot;
+    } else {
+        debug!("⏪ Kept reserves of {} at slot {} over a trade from slot {}", trade.mint, state.reserves_slot, slot);
+    }
+    state.trades.record(trade.timestamp, trade.is_buy, trade.sol_amount);
+    state.refresh_momentum(trade.timestamp.max(Utc::now().timestamp()));
+    Some(state.clone())
+}
+
+fn apply_reserves(
+    state: &mut TokenState,
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    real_sol_reserves: u64,
+    real_token_reserves: u64,
+    sol_price_usd: f64,
+) {
+    state.virtual_sol_reserves = virtual_sol_reserves;
+    state.virtual_token_reserves = virtual_token_reserves;
+    state.real_sol_reserves = real_sol_reserves;
+    state.real_token_reserves = real_token_reserves;
+    
+
+    state.current_price_sol = calculator::calculate_price_sol(virtual_sol_reserves, virtual_token_reserves, state.decimals);
+    state.market_cap_sol = calculator::calculate_market_cap_sol(state.current_price_sol, state.total_supply, state.decimals);
+    state.market_cap_usd = state.market_cap_sol * sol_price_usd;
+
+    // After graduation the reserves are the PumpSwap pool's.
+    if !state.complete {
+        state.bonding_curve_progress = calculator::calculate_bonding_curve_progress(virtual_sol_reserves);
+    }
+    
+    state.last_updated = Utc::now();
+}
+
 pub async fn mark_token_complete(state_map: &TokenStateMap, mint: &str) {
     let mut map = state_map.write().await;
     if let Some(state) = map.get_mut(mint) {
@@ -135,12 +196,66 @@ pub async fn mark_token_complete(state_map: &TokenStateMap, mint: &str) {
     }
 }
 
+/// Replaces a token's entry with one rebuilt from a stored or on-chain
+/// snapshot.
+pub async fn restore_token_state(
+    state_map: &TokenStateMap,
+    mint: &str,
+    snapshot: TokenSnapshot,
+    sol_price_usd: f64,
+) -> Option<TokenState> {
+    init_token_state(
+        state_map,
+        mint.to_string(),
+        snapshot.name,
+        snapshot.symbol,
+        snapshot.creator_wallet,
+        snapshot.virtual_sol_reserves as u64,
+        snapshot.virtual_token_reserves as u64,
+        snapshot.real_token_reserves as u64,
+        snapshot.token_total_supply as u64,
+        snapshot.decimals as u8,
+        sol_price_usd,
+    ).await;
+    update_token_state(
+        state_map,
+        mint,
+        snapshot.virtual_sol_reserves as u64,
+        snapshot.virtual_token_reserves as u64,
+        snapshot.real_sol_reserves as u64,
+        snapshot.real_token_reserves as u64,
+        sol_price_usd,
+    ).await;
+    if snapshot.complete {
+        mark_token_complete(state_map, mint).await;
+    }
+    get_token_state(state_map, mint).await
+}
+
+/// The token's decimals, or pump.fun's for a token not held in state.
+pub async fn token_decimals(state_map: &TokenStateMap, mint: &str) -> u8 {
+    state_map.read().await.get(mint).map_or(DEFAULT_TOKEN_DECIMALS, |s| s.decimals)
+}
+
+pub async fn remove_token_state(state_map: &TokenStateMap, mint: &str) -> bool {
+    state_map.write().await.remove(mint).is_some()
+}
+
 pub async fn get_token_state(state_map: &TokenStateMap, mint: &str) -> Option<TokenState> {
     let map = state_map.read().await;
-    map.get(mint).cloned()
+    let mut state = map.get(mint).cloned()?;
+    state.refresh_momentum(Utc::now().timestamp());
+    Some(state)
 }
 
 pub async fn get_all_tokens(state_map: &TokenStateMap) -> Vec<TokenState> {
     let map = state_map.read().await;
-    map.values().cloned().collect()
+    let now = Utc::now().timestamp();
+    map.values()
+        .cloned()
+        .map(|mut state| {
+            state.refresh_momentum(now);
+            state
+        })
+        .collect()
 }
\ No newline at end of file
@@ -0,0 +1,107 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use tracing::info;
+use crate::database::{self, model::{CompleteEvent, CreateEvent, TradeEventData}};
+use crate::error::Result;
+use super::calculator;
+
+/// Bonding curve progress (percent) marking each progress milestone.
+const PROGRESS_MILESTONES: [(f64, &str); 3] = [(25.0, "progress_25"), (50.0, "progress_50"), (75.0, "progress_75")];
+/// How far a new high has to beat the last recorded ATH to be put on the
+/// timeline, so a run-up doesn't add an entry per buy.
+const ATH_STEP: f64 = 1.10;
+
+fn timestamp(unix: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(unix, 0).single().unwrap_or_else(Utc::now)
+}
+
+/// The progress milestones passed when progress went from `before` to
+/// `after`; a sell never passes one.
+fn crossed_milestones(before: f64, after: f64) -> impl Iterator<Item = &'static str> {
+    PROGRESS_MILESTONES
+        .into_iter()
+        .filter(move |(threshold, _)| before < *threshold && after >= *threshold)
+        .map(|(_, kind)| kind)
+}
+
+/// Records the creation along with the launch reserves, which point-in-time
+/// state falls back to before the first trade.
+pub async fn record_creation(pool: &PgPool, signature: &str, create: &CreateEvent) -> Result<()> {
+    let details = json!({
+        "name": create.name,
+        "symbol": create.symbol,
+        "creator": create.user,
+        "virtual_sol_reserves": create.virtual_sol_reserves,
+        "virtual_token_reserves": create.virtual_token_reserves,
+        "real_token_reserves": create.real_token_reserves,
+        "token_total_supply": create.token_total_supply,
+    });
+    database::save_token_event(pool, &create.mint, "created", timestamp(create.timestamp), Some(signature), &details).await?;
+    Ok(())
+}
+
+/// Records what a stored trade did to its token: its first trade, progress
+/// milestones passed, a new ATH, or the creator selling.
+pub async fn record_trade(pool: &PgPool, trade: &TradeEventData, token_decimals: u8) -> Result<()> {
+    let at = timestamp(trade.timestamp);
+    let signature = Some(trade.signature.as_str());
+
+    let first = database::save_token_event(
+        pool,
+        &trade.mint,
+        "first_trade",
+        at,
+        signature,
+        &json!({ "wallet": trade.user, "is_buy": trade.is_buy, "sol_amount": trade.sol_amount }),
+    ).await?;
+
+    // The event carries the reserves after the trade; the SOL side moved by
+    // exactly `sol_amount`.
+    let sol_before = if trade.is_buy {
+        trade.virtual_sol_reserves.saturating_sub(trade.sol_amount)
+    } else {
+        trade.virtual_sol_reserves.saturating_add(trade.sol_amount)
+    };
+    let before = calculator::calculate_bonding_curve_progress(sol_before);
+    let after = calculator::calculate_bonding_curve_progress(trade.virtual_sol_reserves);
+    for kind in crossed_milestones(before, after) {
+        database::save_token_event(pool, &trade.mint, kind, at, signature, &json!({ "progress": after })).await?;
+    }
+
+    if trade.is_buy {
+        let price_sol = calculator::calculate_price_sol(trade.virtual_sol_reserves, trade.virtual_token_reserves, token_decimals);
+        // The first trade only sets the baseline.
+        if database::raise_ath(pool, &trade.mint, price_sol, at, ATH_STEP).await? && !first {
+            database::save_token_event(pool, &trade.mint, "ath", at, signature, &json!({ "price_sol": price_sol })).await?;
+        }
+    }
+
+    if !trade.is_buy && trade.user == trade.creator {
+        info!("🚨 Creator {} sold {} of {}", trade.creator, trade.token_amount, trade.mint);
+        let details = json!({ "wallet": trade.user, "token_amount": trade.token_amount, "sol_amount": trade.sol_amount });
+        database::save_token_event(pool, &trade.mint, "creator_sell", at, signature, &details).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn record_graduation(pool: &PgPool, signature: &str, complete: &CompleteEvent) -> Result<()> {
+    let details = json!({ "user": complete.user });
+    database::save_token_event(pool, &complete.mint, "graduated", timestamp(complete.timestamp), Some(signature), &details).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossed_milestones() {
+        assert_eq!(crossed_milestones(10.0, 20.0).count(), 0);
+        assert_eq!(crossed_milestones(20.0, 25.0).collect::<Vec<_>>(), ["progress_25"]);
+        assert_eq!(crossed_milestones(24.0, 80.0).collect::<Vec<_>>(), ["progress_25", "progress_50", "progress_75"]);
+        // Selling back through a milestone doesn't record it again.
+        assert_eq!(crossed_milestones(60.0, 40.0).count(), 0);
+    }
+}
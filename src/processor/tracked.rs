@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use crate::database::{self, model::{PositionTotals, TrackedWallet}};
+use crate::error::Result;
+use crate::dto::TradeMessage;
+use super::calculator;
+use super::state::{self, TokenStateMap};
+
+/// In-memory copy of the `tracked_wallets` table, checked on every trade.
+/// Loaded at startup and kept in sync by the admin routes.
+#[derive(Debug, Clone, Default)]
+pub struct TrackedWallets {
+    wallets: Arc<RwLock<HashMap<String, TrackedWallet>>>,
+}
+
+impl TrackedWallets {
+    pub async fn load(pool: &PgPool) -> Result<Self> {
+        let wallets = database::fetch_tracked_wallets(pool).await?;
+        Ok(Self {
+            wallets: Arc::new(RwLock::new(
+                wallets.into_iter().map(|w| (w.wallet.clone(), w)).collect(),
+            )),
+        })
+    }
+
+    pub async fn get(&self, wallet: &str) -> Option<TrackedWallet> {
+        self.wallets.read().await.get(wallet).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<TrackedWallet> {
+        let mut wallets: Vec<_> = self.wallets.read().await.values().cloned().collect();
+        wallets.sort_by_key(|w| w.added_at);
+        wallets
+    }
+
+    pub async fn insert(&self, wallet: TrackedWallet) {
+        self.wallets.write().await.insert(wallet.wallet.clone(), wallet);
+    }
+
+    pub async fn remove(&self, wallet: &str) -> bool {
+        self.wallets.write().await.remove(wallet).is_some()
+    }
+}
+
+/// A tracked wallet's holding in the traded token after the trade. Cost is
+/// averaged over every buy the indexer has seen; PnL is in SOL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Position {
+    pub trades: i64,
+    pub tokens_held: f64,
+    pub avg_cost_sol: f64,
+    pub value_sol: f64,
+    pub realized_pnl_sol: f64,
+    pub unrealized_pnl_sol: f64,
+}
+
+impl Position {
+    /// `price_sol` is the token's current price per whole token.
+    pub fn from_totals(totals: &PositionTotals, token_decimals: u8, price_sol: f64) -> Self {
+        let tokens = |raw: i64| calculator::to_ui_amount(raw as u64, token_decimals);
+        let sol = |lamports: i64| calculator::to_ui_amount(lamports as u64, calculator::SOL_DECIMALS);
+        let bought = tokens(totals.tokens_bought);
+        let sold = tokens(totals.tokens_sold);
+        let spent = sol(totals.lamports_spent);
+        let received = sol(totals.lamports_received);
+
+        // Sells of tokens bought before indexing started count at zero cost.
+        let avg_cost_sol = if bought > 0.0 { spent / bought } else { 0.0 };
+        let tokens_held = (bought - sold).max(0.0);
+        let value_sol = tokens_held * price_sol;
+
+        Self {
+            trades: totals.trades,
+            tokens_held,
+            avg_cost_sol,
+            value_sol,
+            realized_pnl_sol: received - avg_cost_sol * sold.min(bought),
+            unrealized_pnl_sol: value_sol - avg_cost_sol * tokens_held,
+        }
+    }
+}
+
+/// Published on `pump:tracked`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedTradeMessage {
+    pub wallet: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub mint: String,
+    pub trade: TradeMessage,
+    pub position: Position,
+}
+
+/// Builds the copy-trade signal for a trade, or `None` when its wallet is
+/// not tracked.
+pub async fn signal(
+    pool: &PgPool,
+    tracked: &TrackedWallets,
+    state_map: &TokenStateMap,
+    trade: &TradeMessage,
+) -> Result<Option<TrackedTradeMessage>> {
+    let Some(wallet) = tracked.get(&trade.user_wallet).await else {
+        return Ok(None);
+    };
+
+    let totals = database::fetch_position_totals(pool, &trade.user_wallet, &trade.mint).await?;
+    let decimals = state::token_decimals(state_map, &trade.mint).await;
+    Ok(Some(TrackedTradeMessage {
+        wallet: wallet.wallet,
+        label: wallet.label,
+        mint: trade.mint.clone(),
+        trade: trade.clone(),
+        position: Position::from_totals(&totals, decimals, trade.price_sol),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_pnl() {
+        // Bought 1000 tokens for 2 SOL, sold 400 for 1.2 SOL.
+        let totals = PositionTotals {
+            trades: 3,
+            tokens_bought: 1_000_000_000,
+            tokens_sold: 400_000_000,
+            lamports_spent: 2_000_000_000,
+            lamports_received: 1_200_000_000,
+        };
+        let position = Position::from_totals(&totals, calculator::DEFAULT_TOKEN_DECIMALS, 0.003);
+
+        assert!((position.avg_cost_sol - 0.002).abs() < 1e-12);
+        assert!((position.tokens_held - 600.0).abs() < 1e-9);
+        assert!((position.value_sol - 1.8).abs() < 1e-9);
+        assert!((position.realized_pnl_sol - 0.4).abs() < 1e-9);
+        assert!((position.unrealized_pnl_sol - 0.6).abs() < 1e-9);
+
+        let unseen_buys = PositionTotals { trades: 1, tokens_sold: 10_000_000, lamports_received: 50_000_000, ..Default::default() };
+        let position = Position::from_totals(&unseen_buys, calculator::DEFAULT_TOKEN_DECIMALS, 0.003);
+        assert_eq!(position.tokens_held, 0.0);
+        assert!((position.realized_pnl_sol - 0.05).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(feature = "kafka")]
+mod producer {
+    use anyhow::{Context, Result};
+    use async_trait::async_trait;
+    use rdkafka::config::ClientConfig;
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+    use std::time::Duration;
+    use tracing::{error, info};
+    use crate::config::KafkaConfig;
+    use crate::helius::parser::PumpEvent;
+    use crate::processor::metrics::Metrics;
+    use crate::sinks::{EventRecord, EventSink};
+
+    /// How long librdkafka keeps retrying a message before reporting it failed.
+    const DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Publishes every indexed event to a per-kind topic, keyed by mint so a
+    /// token's events stay ordered within one partition.
+    ///
+    /// Delivery is at-least-once: the producer is idempotent with `acks=all`,
+    /// and `publish` waits for the broker acknowledgement before returning.
+    pub struct KafkaSink {
+        producer: FutureProducer,
+        config: KafkaConfig,
+        metrics: Metrics,
+    }
+
+    impl KafkaSink {
+        pub fn new(config: &KafkaConfig, metrics: Metrics) -> Result<Self> {
+            let producer: FutureProducer = ClientConfig::new()
+                .set("bootstrap.servers", &config.brokers)
+                .set("client.id", &config.client_id)
+                .set("acks", "all")
+                .set("enable.idempotence", "true")
+                // Same key hashing as the Java client, so other producers
+                // keyed by mint land on the same partitions.
+                .set("partitioner", "murmur2_random")
+                .set("compression.type", "lz4")
+                .set("message.timeout.ms", DELIVERY_TIMEOUT.as_millis().to_string())
+                .create()
+                .context("Failed to create Kafka producer")?;
+
+            info!("✅ Kafka producer ready: {}", config.brokers);
+
+            Ok(Self { producer, config: config.clone(), metrics })
+        }
+
+        fn topic(&self, event: &PumpEvent) -> &str {
+            match event {
+                PumpEvent::Create(_) => &self.config.creates_topic,
+                PumpEvent::Trade(_) => &self.config.trades_topic,
+                PumpEvent::Complete(_) => &self.config.graduations_topic,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for KafkaSink {
+        fn name(&self) -> &'static str {
+            "kafka"
+        }
+
+        async fn publish(&self, record: &EventRecord<'_>) {
+            let payload = match serde_json::to_vec(record) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    error!("Failed to serialize Kafka record: {}", e);
+                    return;
+                }
+            };
+
+            let topic = self.topic(record.event);
+            let message = FutureRecord::to(topic).key(record.event.mint()).payload(&payload);
+
+            if let Err((e, _)) = self.producer.send(message, DELIVERY_TIMEOUT).await {
+                self.metrics.increment_kafka_errors();
+                error!("⚠️ Kafka delivery failed (topic: {}, signature: {}): {}", topic, record.signature, e);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "kafka"))]
+mod producer {
+    use anyhow::{bail, Result};
+    use crate::config::KafkaConfig;
+    use crate::processor::metrics::Metrics;
+    use crate::sinks::{EventRecord, EventSink};
+
+    /// Stand-in when the crate is built without the `kafka` feature.
+    pub struct KafkaSink;
+
+    impl KafkaSink {
+        pub fn new(_config: &KafkaConfig, _metrics: Metrics) -> Result<Self> {
+            bail!("KAFKA_BROKERS is set but the indexer was built without the `kafka` feature")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EventSink for KafkaSink {
+        fn name(&self) -> &'static str {
+            "kafka"
+        }
+
+        async fn publish(&self, _record: &EventRecord<'_>) {}
+    }
+}
+
+pub use producer::KafkaSink;
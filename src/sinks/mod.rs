@@ -0,0 +1,188 @@
+pub mod kafka;
+pub mod nats;
+pub mod notifier;
+pub mod postgres;
+pub mod redis;
+pub mod webhook;
+
+pub use kafka::KafkaSink;
+pub use nats::NatsSink;
+pub use notifier::NotifierSink;
+pub use postgres::PgNotifySink;
+pub use redis::RedisSink;
+pub use webhook::WebhookSink;
+
+use std::borrow::Cow;
+use async_trait::async_trait;
+use serde::Serialize;
+use crate::config::{Config, SharedSettings, SinkKind};
+use crate::helius::parser::PumpEvent;
+use crate::processor::metrics::Metrics;
+use crate::processor::EventUpdate;
+use crate::processor::creator_lists::CreatorList;
+use crate::processor::koth::KothMessage;
+use crate::processor::tracked::TrackedTradeMessage;
+use crate::storage::RedisClient;
+use sqlx::PgPool;
+
+/// An indexed event plus where it came from, as handed to every sink.
+#[derive(Debug, Serialize)]
+pub struct EventRecord<'a> {
+    pub signature: &'a str,
+    pub slot: u64,
+    /// Position of the event within its transaction; with the signature it
+    /// uniquely identifies the event, so consumers can drop redeliveries.
+    pub index: usize,
+    pub kind: &'static str,
+    pub event: &'a PumpEvent,
+    /// What the processor derived from the event (bonding curve state, USD
+    /// market cap). Absent when the event could not be priced.
+    #[serde(rename = "data", skip_serializing_if = "Option::is_none")]
+    pub update: Option<&'a EventUpdate>,
+    /// Set when the trade was made by a tracked wallet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracked: Option<&'a TrackedTradeMessage>,
+    /// Set when the event changed the king of the hill.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub koth: Option<&'a KothMessage>,
+    /// The operator list the token's creator is on, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator_list: Option<CreatorList>,
+}
+
+impl<'a> EventRecord<'a> {
+    pub fn id(&self) -> String {
+        format!("{}:{}", self.signature, self.index)
+    }
+
+    /// `update` as live subscribers receive it, whichever transport carries
+    /// it: new tokens are tagged with their creator's operator list.
+    pub fn live_update(&self) -> Option<Cow<'a, EventUpdate>> {
+        match self.update? {
+            EventUpdate::TokenCreated(message) if self.creator_list.is_some() => {
+                let mut message = message.clone();
+                message.creator_list = self.creator_list;
+                Some(Cow::Owned(EventUpdate::TokenCreated(message)))
+            }
+            update => Some(Cow::Borrowed(update)),
+        }
+    }
+}
+
+/// A delivery target for indexed events. `publish` returns once the target
+/// has accepted the record; failures are logged and counted by the sink.
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn publish(&self, record: &EventRecord<'_>);
+}
+
+/// Accepts and discards every record.
+pub struct NoopSink;
+
+#[async_trait]
+impl EventSink for NoopSink {
+    fn name(&self) -> &'static str {
+        "noop"
+    }
+
+    async fn publish(&self, _record: &EventRecord<'_>) {}
+}
+
+/// Builds the sinks listed in `config.sinks`, in order.
+pub async fn from_config(
+    config: &Config,
+    pool: &PgPool,
+    redis: Option<&RedisClient>,
+    metrics: &Metrics,
+    settings: &SharedSettings,
+) -> anyhow::Result<Vec<Box<dyn EventSink>>> {
+    let mut sinks: Vec<Box<dyn EventSink>> = Vec::new();
+    for kind in &config.sinks {
+        let sink: Box<dyn EventSink> = match kind {
+            SinkKind::Redis => match redis {
+                Some(redis) => Box::new(RedisSink::new(redis.clone(), config.transport.streams().cloned(), metrics.clone())),
+                None => anyhow::bail!("Redis sink requires EVENT_TRANSPORT=pubsub or streams"),
+            },
+            SinkKind::Postgres => Box::new(PgNotifySink::new(pool.clone(), metrics.clone())),
+            SinkKind::Kafka => match &config.kafka {
+                Some(kafka) => Box::new(KafkaSink::new(kafka, metrics.clone())?),
+                None => anyhow::bail!("Kafka sink requires KAFKA_BROKERS"),
+            },
+            SinkKind::Nats => match &config.nats {
+                Some(nats) => Box::new(NatsSink::new(nats, metrics.clone()).await?),
+                None => anyhow::bail!("NATS sink requires NATS_URL"),
+            },
+            SinkKind::Webhook => match &config.webhook {
+                Some(webhook) => Box::new(WebhookSink::new(webhook, metrics.clone())?),
+                None => anyhow::bail!("Webhook sink requires WEBHOOK_URL"),
+            },
+            SinkKind::Notifier => match &config.notifier {
+                Some(notifier) => Box::new(NotifierSink::spawn(notifier, settings.clone())?),
+                None => anyhow::bail!("Notifier requires NOTIFY_DISCORD_WEBHOOKS or NOTIFY_TELEGRAM_BOT_TOKEN"),
+            },
+            SinkKind::Noop => Box::new(NoopSink),
+        };
+        sinks.push(sink);
+    }
+    Ok(sinks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::model::CompleteEvent;
+    use crate::dto::NewTokenMessage;
+
+    #[test]
+    fn test_record_shape() {
+        let event = PumpEvent::Complete(CompleteEvent {
+            user: "User".to_string(),
+            mint: "Mint".to_string(),
+            bonding_curve: "Curve".to_string(),
+            timestamp: 1,
+        });
+        let record = EventRecord { signature: "sig", slot: 9, index: 0, kind: event.kind(), event: &event, update: None, tracked: None, koth: None, creator_list: None };
+
+        assert_eq!(record.id(), "sig:0");
+        assert_eq!(
+            serde_json::to_value(&record).unwrap(),
+            serde_json::json!({
+                "signature": "sig",
+                "slot": 9,
+                "index": 0,
+                "kind": "complete",
+                "event": {"user": "User", "mint": "Mint", "bonding_curve": "Curve", "timestamp": 1}
+            })
+        );
+    }
+
+    #[test]
+    fn test_live_update_tags_creator_list() {
+        let event = PumpEvent::Complete(CompleteEvent {
+            user: "User".to_string(),
+            mint: "Mint".to_string(),
+            bonding_curve: "Curve".to_string(),
+            timestamp: 1,
+        });
+        let update = EventUpdate::TokenCreated(NewTokenMessage {
+            mint: "Mint".to_string(),
+            name: "Name".to_string(),
+            symbol: "SYM".to_string(),
+            creator: "Creator".to_string(),
+            timestamp: 1,
+            market_cap_sol: 30.0,
+            creator_list: None,
+        });
+        let mut record = EventRecord { signature: "sig", slot: 9, index: 0, kind: event.kind(), event: &event, update: Some(&update), tracked: None, koth: None, creator_list: None };
+        assert!(matches!(record.live_update(), Some(Cow::Borrowed(_))));
+
+        record.creator_list = Some(CreatorList::Watchlist);
+        match record.live_update().as_deref() {
+            Some(EventUpdate::TokenCreated(message)) => assert_eq!(message.creator_list, Some(CreatorList::Watchlist)),
+            other => panic!("unexpected update: {:?}", other),
+        }
+        assert!(record.update.is_some_and(|update| matches!(update, EventUpdate::TokenCreated(message) if message.creator_list.is_none())));
+    }
+}
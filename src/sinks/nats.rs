@@ -0,0 +1,117 @@
+#[cfg(feature = "nats")]
+mod publisher {
+    use anyhow::{Context, Result};
+    use async_nats::jetstream::{self, context::Publish, stream};
+    use async_trait::async_trait;
+    use std::time::Duration;
+    use tracing::{error, info};
+    use crate::config::NatsConfig;
+    use crate::processor::metrics::Metrics;
+    use crate::sinks::{EventRecord, EventSink};
+
+    /// Publishes every indexed event to JetStream under
+    /// `{prefix}.{creates|trades|graduations}.{mint}`, so durable consumers
+    /// can filter by kind or by token.
+    ///
+    /// Each message carries `Nats-Msg-Id: {signature}:{index}`, letting the
+    /// stream drop duplicates from retries within its duplicate window.
+    pub struct NatsSink {
+        jetstream: jetstream::Context,
+        subject_prefix: String,
+        metrics: Metrics,
+    }
+
+    impl NatsSink {
+        pub async fn new(config: &NatsConfig, metrics: Metrics) -> Result<Self> {
+            let client = async_nats::connect(&config.url)
+                .await
+                .context("Failed to connect to NATS")?;
+            let jetstream = jetstream::new(client);
+
+            jetstream
+                .get_or_create_stream(stream::Config {
+                    name: config.stream.clone(),
+                    subjects: vec![format!("{}.>", config.subject_prefix)],
+                    max_age: Duration::from_secs(config.max_age_hours * 3600),
+                    ..Default::default()
+                })
+                .await
+                .context("Failed to create JetStream stream")?;
+
+            info!("✅ NATS JetStream ready: {} (stream {})", config.url, config.stream);
+
+            Ok(Self {
+                jetstream,
+                subject_prefix: config.subject_prefix.clone(),
+                metrics,
+            })
+        }
+
+        fn subject(&self, record: &EventRecord<'_>) -> String {
+            let kind = match record.kind {
+                "create" => "creates",
+                "trade" => "trades",
+                _ => "graduations",
+            };
+            format!("{}.{}.{}", self.subject_prefix, kind, record.event.mint())
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for NatsSink {
+        fn name(&self) -> &'static str {
+            "nats"
+        }
+
+        async fn publish(&self, record: &EventRecord<'_>) {
+            let payload = match serde_json::to_vec(record) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    error!("Failed to serialize NATS record: {}", e);
+                    return;
+                }
+            };
+
+            let subject = self.subject(record);
+            let message = Publish::build().payload(payload.into()).message_id(record.id());
+
+            let acked = match self.jetstream.send_publish(subject.clone(), message).await {
+                Ok(ack) => ack.await.map(|_| ()),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = acked {
+                self.metrics.increment_nats_errors();
+                error!("⚠️ NATS publish failed (subject: {}): {}", subject, e);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "nats"))]
+mod publisher {
+    use anyhow::{bail, Result};
+    use crate::config::NatsConfig;
+    use crate::processor::metrics::Metrics;
+    use crate::sinks::{EventRecord, EventSink};
+
+    /// Stand-in when the crate is built without the `nats` feature.
+    pub struct NatsSink;
+
+    impl NatsSink {
+        pub async fn new(_config: &NatsConfig, _metrics: Metrics) -> Result<Self> {
+            bail!("NATS_URL is set but the indexer was built without the `nats` feature")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EventSink for NatsSink {
+        fn name(&self) -> &'static str {
+            "nats"
+        }
+
+        async fn publish(&self, _record: &EventRecord<'_>) {}
+    }
+}
+
+pub use publisher::NatsSink;
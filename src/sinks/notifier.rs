@@ -0,0 +1,179 @@
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{info, warn};
+use crate::config::{NotifierConfig, NotifyRules, SharedSettings, TelegramConfig};
+use crate::helius::parser::PumpEvent;
+use crate::processor::EventUpdate;
+use crate::processor::creator_lists::CreatorList;
+use super::{EventRecord, EventSink};
+
+/// Alerts waiting to be posted; once full, new alerts are dropped rather
+/// than slowing the pipeline down.
+const QUEUE_CAPACITY: usize = 100;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A notable event worth telling humans about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Alert {
+    TrackedCreatorLaunch { creator: String, mint: String, name: String, symbol: String },
+    WhaleBuy { mint: String, user: String, sol: f64, market_cap_usd: Option<f64>, signature: String },
+    Graduation { mint: String },
+}
+
+impl Alert {
+    /// Plain text, so it renders the same on Discord and Telegram without
+    /// escaping.
+    pub fn text(&self) -> String {
+        match self {
+            Alert::TrackedCreatorLaunch { creator, mint, name, symbol } => format!(
+                "🚀 New token by tracked creator {}\n{} ({})\nhttps://pump.fun/coin/{}",
+                creator, name, symbol, mint
+            ),
+            Alert::WhaleBuy { mint, user, sol, market_cap_usd, signature } => {
+                let market_cap = market_cap_usd
+                    .map(|mc| format!(" at ${:.0} market cap", mc))
+                    .unwrap_or_default();
+                format!(
+                    "🐋 {} bought {:.2} SOL of {}{}\nhttps://solscan.io/tx/{}",
+                    user, sol, mint, market_cap, signature
+                )
+            }
+            Alert::Graduation { mint } => format!(
+                "🎓 {} completed its bonding curve\nhttps://pump.fun/coin/{}",
+                mint, mint
+            ),
+        }
+    }
+}
+
+/// Checks a record against the configured rules.
+pub fn alert_for(rules: &NotifyRules, record: &EventRecord<'_>) -> Option<Alert> {
+    match record.event {
+        PumpEvent::Create(create)
+            if rules.tracked_creators.contains(&create.creator) || record.creator_list == Some(CreatorList::Watchlist) =>
+        {
+            Some(Alert::TrackedCreatorLaunch {
+                creator: create.creator.clone(),
+                mint: create.mint.clone(),
+                name: create.name.clone(),
+                symbol: create.symbol.clone(),
+            })
+        }
+        PumpEvent::Trade(trade) if trade.is_buy => {
+            let sol = trade.sol_amount as f64 / 1_000_000_000.0;
+            if !rules.whale_buy_sol.is_some_and(|threshold| sol >= threshold) {
+                return None;
+            }
+            let market_cap_usd = match record.update {
+                Some(EventUpdate::Trade(message)) => Some(message.market_cap_usd),
+                _ => None,
+            };
+            Some(Alert::WhaleBuy {
+                mint: trade.mint.clone(),
+                user: trade.user.clone(),
+                sol,
+                market_cap_usd,
+                signature: record.signature.to_string(),
+            })
+        }
+        PumpEvent::Complete(complete) if rules.graduations => {
+            Some(Alert::Graduation { mint: complete.mint.clone() })
+        }
+        _ => None,
+    }
+}
+
+/// Posts alerts for notable events to Discord webhooks and Telegram chats.
+/// Matching happens inline against the current runtime rules; delivery runs
+/// on a background task.
+pub struct NotifierSink {
+    settings: SharedSettings,
+    tx: mpsc::Sender<Alert>,
+}
+
+impl NotifierSink {
+    pub fn spawn(config: &NotifierConfig, settings: SharedSettings) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+
+        info!(
+            "🔔 Notifier ready: {} Discord webhook(s), {} Telegram chat(s)",
+            config.discord_webhooks.len(),
+            config.telegram.as_ref().map_or(0, |telegram| telegram.chat_ids.len())
+        );
+        tokio::spawn(deliver(client, config.clone(), rx));
+
+        Ok(Self { settings, tx })
+    }
+}
+
+#[async_trait]
+impl EventSink for NotifierSink {
+    fn name(&self) -> &'static str {
+        "notifier"
+    }
+
+    async fn publish(&self, record: &EventRecord<'_>) {
+        let Some(alert) = alert_for(&self.settings.read().notify, record) else {
+            return;
+        };
+        if let Err(TrySendError::Full(_)) = self.tx.try_send(alert) {
+            warn!("Notifier queue full, dropping alert for {}", record.id());
+        }
+    }
+}
+
+async fn deliver(client: reqwest::Client, config: NotifierConfig, mut rx: mpsc::Receiver<Alert>) {
+    while let Some(alert) = rx.recv().await {
+        let text = alert.text();
+
+        for url in &config.discord_webhooks {
+            let body = serde_json::json!({ "content": text });
+            report("Discord", client.post(url).json(&body).send().await);
+        }
+
+        if let Some(TelegramConfig { bot_token, chat_ids }) = &config.telegram {
+            let url = format!("https://api.telegram.org/bot{}/sendMessage", bot_token);
+            for chat_id in chat_ids {
+                let body = serde_json::json!({
+                    "chat_id": chat_id,
+                    "text": text,
+                    "disable_web_page_preview": true,
+                });
+                report("Telegram", client.post(&url).json(&body).send().await);
+            }
+        }
+    }
+}
+
+fn report(target: &str, result: reqwest::Result<reqwest::Response>) {
+    match result {
+        Ok(response) if response.status().is_success() => {}
+        Ok(response) => warn!("{} notification rejected: {}", target, response.status()),
+        Err(e) => warn!("{} notification failed: {}", target, e.without_url()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::model::CompleteEvent;
+
+    #[test]
+    fn test_graduation_rule() {
+        let event = PumpEvent::Complete(CompleteEvent {
+            user: "User".to_string(),
+            mint: "Mint".to_string(),
+            bonding_curve: "Curve".to_string(),
+            timestamp: 1,
+        });
+        let record = EventRecord { signature: "sig", slot: 1, index: 0, kind: event.kind(), event: &event, update: None, tracked: None, koth: None, creator_list: None };
+
+        let mut rules = NotifyRules { graduations: true, ..Default::default() };
+        assert_eq!(alert_for(&rules, &record), Some(Alert::Graduation { mint: "Mint".to_string() }));
+
+        rules.graduations = false;
+        assert_eq!(alert_for(&rules, &record), None);
+    }
+}
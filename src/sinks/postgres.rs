@@ -0,0 +1,67 @@
+use async_trait::async_trait;
+use sqlx::PgPool;
+use tracing::error;
+use crate::processor::metrics::Metrics;
+use crate::processor::EventUpdate;
+use super::{EventRecord, EventSink};
+
+/// Delivers live updates with `pg_notify` on the `pump:*` channels, for
+/// deployments without Redis. Trades go out once on `pump:trades`; the API
+/// routes them to per-token subscribers itself.
+pub struct PgNotifySink {
+    pool: PgPool,
+    metrics: Metrics,
+}
+
+impl PgNotifySink {
+    pub fn new(pool: PgPool, metrics: Metrics) -> Self {
+        Self { pool, metrics }
+    }
+
+    async fn notify<T: serde::Serialize>(&self, channel: &str, message: &T) {
+        let payload = match serde_json::to_string(message) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Failed to serialize notification: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = sqlx::query("SELECT pg_notify($1, $2)")
+            .bind(channel)
+            .bind(&payload)
+            .execute(&self.pool)
+            .await
+        {
+            self.metrics.increment_notify_errors();
+            error!("⚠️ pg_notify failed (channel: {}): {}", channel, e);
+        }
+    }
+}
+
+#[async_trait]
+impl EventSink for PgNotifySink {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn publish(&self, record: &EventRecord<'_>) {
+        if let Some(tracked) = record.tracked {
+            self.notify("pump:tracked", tracked).await;
+        }
+        if let Some(koth) = record.koth {
+            self.notify("pump:koth", koth).await;
+        }
+
+        let Some(update) = record.live_update() else {
+            return;
+        };
+
+        let channel = match &*update {
+            EventUpdate::TokenCreated(_) => "pump:tokens:new",
+            EventUpdate::Trade(_) => "pump:trades",
+            EventUpdate::Graduated(_) => "pump:completions",
+        };
+        self.notify(channel, &*update).await;
+    }
+}
@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use tracing::{debug, error, warn};
+use crate::config::StreamsConfig;
+use crate::processor::metrics::Metrics;
+use crate::processor::EventUpdate;
+use crate::storage::redis_health::{is_connection_error, is_transient_error, publish_payload, PendingPublish};
+use crate::storage::{replay_buffer, RedisClient};
+use super::{EventRecord, EventSink};
+
+/// Feeds the API's live streams: publishes updates on the `pump:*` pub/sub
+/// channels (or appends them to Redis Streams when configured) and keeps
+/// the trade replay buffer.
+pub struct RedisSink {
+    redis: RedisClient,
+    streams: Option<StreamsConfig>,
+    metrics: Metrics,
+}
+
+impl RedisSink {
+    pub fn new(redis: RedisClient, streams: Option<StreamsConfig>, metrics: Metrics) -> Self {
+        Self { redis, streams, metrics }
+    }
+
+    /// Publishes on `channel`, or with streams enabled appends to the
+    /// stream backing it. `mint` lets stream readers route per-token.
+    /// While Redis is down the message is deferred for the health monitor
+    /// to replay instead.
+    async fn safe_publish<T: serde::Serialize>(&self, channel: &str, mint: &str, message: &T) {
+        let payload = match serde_json::to_string(message) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("⚠️ Failed to serialize message for {}: {}", channel, e);
+                return;
+            }
+        };
+        let publish = PendingPublish::new(channel, mint, payload);
+
+        let health = &self.redis.health;
+        if health.defer_if_down(publish.clone()) {
+            debug!("Deferred publish to {} until Redis is back", channel);
+            return;
+        }
+
+        let mut redis = self.redis.clone();
+        if let Err(e) = publish_payload(&mut redis, self.streams.as_ref(), &publish).await {
+            self.metrics.increment_redis_errors();
+            if is_connection_error(&e) {
+                health.mark_down();
+                health.defer_if_down(publish);
+                warn!("⚠️ Redis unreachable (channel: {}), publish deferred: {}", channel, e);
+                return;
+            }
+            if is_transient_error(&e) {
+                health.retry_later(publish);
+                warn!("⚠️ Redis publish failed (channel: {}), will retry: {}", channel, e);
+                return;
+            }
+            error!("⚠️ Redis publish failed (channel: {}): {}", channel, e);
+            error!("   Event will still be saved to database");
+        } else {
+            debug!("✅ Published to Redis channel: {}", channel);
+        }
+    }
+}
+
+#[async_trait]
+impl EventSink for RedisSink {
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn publish(&self, record: &EventRecord<'_>) {
+        if let Some(tracked) = record.tracked {
+            self.safe_publish("pump:tracked", &tracked.mint, tracked).await;
+        }
+        if let Some(koth) = record.koth {
+            self.safe_publish("pump:koth", koth.mint(), koth).await;
+        }
+
+        let Some(update) = record.live_update() else {
+            return;
+        };
+
+        match &*update {
+            EventUpdate::TokenCreated(message) => {
+                self.safe_publish("pump:tokens:new", &message.mint, message).await;
+            }
+            EventUpdate::Trade(message) => {
+                let mut redis = self.redis.clone();
+                let mut message = message.clone();
+
+                message.seq = match replay_buffer::next_seq(&mut redis).await {
+                    Ok(seq) => Some(seq),
+                    Err(e) => {
+                        self.metrics.increment_redis_errors();
+                        warn!("Failed to allocate trade sequence number: {}", e);
+                        None
+                    }
+                };
+
+                if let Some(seq) = message.seq
+                    && let Ok(payload) = serde_json::to_string(&message)
+                    && let Err(e) = replay_buffer::record(&mut redis, seq, &payload).await
+                {
+                    self.metrics.increment_redis_errors();
+                    warn!("Failed to buffer trade {} for replay: {}", seq, e);
+                }
+
+                self.safe_publish("pump:trades", &message.mint, &message).await;
+                // Stream readers fan trades out per mint themselves.
+                if self.streams.is_none() {
+                    self.safe_publish(&format!("pump:trades:{}", message.mint), &message.mint, &message).await;
+                }
+            }
+            EventUpdate::Graduated(message) => {
+                self.safe_publish("pump:completions", &message.mint, message).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::{error, warn};
+use crate::config::WebhookConfig;
+use crate::processor::metrics::Metrics;
+use super::{EventRecord, EventSink};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_ATTEMPTS: u32 = 3;
+
+/// POSTs each record as JSON to a configured URL, retrying server errors
+/// and timeouts with a short backoff.
+pub struct WebhookSink {
+    client: reqwest::Client,
+    config: WebhookConfig,
+    metrics: Metrics,
+}
+
+impl WebhookSink {
+    pub fn new(config: &WebhookConfig, metrics: Metrics) -> anyhow::Result<Self> {
+        let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build()?;
+        Ok(Self { client, config: config.clone(), metrics })
+    }
+}
+
+#[async_trait]
+impl EventSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn publish(&self, record: &EventRecord<'_>) {
+        for attempt in 1..=MAX_ATTEMPTS {
+            let mut request = self.client
+                .post(&self.config.url)
+                .header("x-event-id", record.id())
+                .json(record);
+            if let Some(token) = &self.config.bearer_token {
+                request = request.bearer_auth(token);
+            }
+
+            match request.send().await {
+                Ok(response) if response.status().is_success() => return,
+                // The receiver rejected the payload; retrying won't help.
+                Ok(response) if response.status().is_client_error() => {
+                    self.metrics.increment_webhook_errors();
+                    error!("⚠️ Webhook rejected {} with {}", record.id(), response.status());
+                    return;
+                }
+                Ok(response) => warn!("Webhook attempt {} for {} returned {}", attempt, record.id(), response.status()),
+                Err(e) => warn!("Webhook attempt {} for {} failed: {}", attempt, record.id(), e),
+            }
+
+            if attempt < MAX_ATTEMPTS {
+                tokio::time::sleep(Duration::from_millis(250 * 2u64.pow(attempt))).await;
+            }
+        }
+
+        self.metrics.increment_webhook_errors();
+        error!("⚠️ Webhook delivery failed for {} after {} attempts", record.id(), MAX_ATTEMPTS);
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::{Context, Result};
+use redis::aio::MultiplexedConnection;
+use redis::streams::{
+    StreamId, StreamInfoConsumersReply, StreamInfoGroupsReply, StreamMaxlen, StreamReadOptions, StreamReadReply,
+};
+use redis::AsyncCommands;
+use crate::config::StreamsConfig;
+use super::RedisClient;
+
+pub const TRADES_STREAM: &str = "pump:stream:trades";
+pub const TOKENS_STREAM: &str = "pump:stream:tokens:new";
+pub const COMPLETIONS_STREAM: &str = "pump:stream:completions";
+pub const TRACKED_STREAM: &str = "pump:stream:tracked";
+pub const KOTH_STREAM: &str = "pump:stream:koth";
+
+/// Each API replica reads through a single consumer in its own group.
+const CONSUMER: &str = "hub";
+const READ_COUNT: usize = 100;
+/// How long one `XREADGROUP` waits for new entries.
+pub const READ_BLOCK_MS: usize = 5_000;
+/// A group whose consumers haven't read for this long belongs to a replica
+/// that is gone. Live readers block for `READ_BLOCK_MS` at a time.
+const STALE_GROUP_IDLE_MS: usize = 60 * 60 * 1000;
+
+/// The stream backing a pub/sub channel name. Per-mint trade channels all
+/// read from the trades stream.
+pub fn stream_for(channel: &str) -> &'static str {
+    match channel {
+        "pump:tokens:new" => TOKENS_STREAM,
+        "pump:completions" => COMPLETIONS_STREAM,
+        "pump:tracked" => TRACKED_STREAM,
+        "pump:koth" => KOTH_STREAM,
+        _ => TRADES_STREAM,
+    }
+}
+
+/// The channel a stream's entries are broadcast on.
+pub fn channel_for(stream: &str) -> &'static str {
+    match stream {
+        TOKENS_STREAM => "pump:tokens:new",
+        COMPLETIONS_STREAM => "pump:completions",
+        TRACKED_STREAM => "pump:tracked",
+        KOTH_STREAM => "pump:koth",
+        _ => "pump:trades",
+    }
+}
+
+/// Appends an event, trimming the stream to roughly `config.maxlen` entries.
+pub async fn append(
+    redis: &mut RedisClient,
+    config: &StreamsConfig,
+    stream: &str,
+    mint: &str,
+    payload: &str,
+) -> Result<()> {
+    redis
+        .connection
+        .xadd_maxlen::<_, _, _, _, ()>(
+            stream,
+            StreamMaxlen::Approx(config.maxlen),
+            "*",
+            &[("mint", mint), ("payload", payload)],
+        )
+        .await
+        .with_context(|| format!("Failed to append to {}", stream))
+}
+
+/// This replica's consumer group: `prefix` plus its host name. It stays the
+/// same across restarts, so a restarted replica resumes where it left off.
+/// Processes sharing a group would split its entries between them, so
+/// replicas on one host need their own `prefix`.
+pub fn replica_group(prefix: &str) -> String {
+    let host = std::env::var("HOSTNAME").ok().filter(|host| !host.is_empty());
+    format!("{}:{}", prefix, host.as_deref().unwrap_or("api"))
+}
+
+/// Creates the consumer group (and the stream) if missing. New groups start
+/// at the end of the stream; an existing one keeps its last-delivered ID.
+pub async fn ensure_group(conn: &mut MultiplexedConnection, stream: &str, group: &str) -> Result<()> {
+    match conn.xgroup_create_mkstream::<_, _, _, ()>(stream, group, "$").await {
+        Ok(()) => Ok(()),
+        Err(e) if e.code() == Some("BUSYGROUP") => Ok(()),
+        Err(e) => Err(e).with_context(|| format!("Failed to create consumer group on {}", stream)),
+    }
+}
+
+/// Removes the groups under `prefix` other than `own` whose consumers have
+/// all been idle for `STALE_GROUP_IDLE_MS`: those of replicas that were
+/// renamed, scaled away or crashed. Returns the removed names.
+pub async fn reap_stale_groups(
+    conn: &mut MultiplexedConnection,
+    stream: &str,
+    prefix: &str,
+    own: &str,
+) -> Result<Vec<String>> {
+    let groups: StreamInfoGroupsReply = conn
+        .xinfo_groups(stream)
+        .await
+        .with_context(|| format!("Failed to list consumer groups on {}", stream))?;
+
+    let mut reaped = Vec::new();
+    for group in groups.groups {
+        if !is_sibling_group(&group.name, prefix, own) {
+            continue;
+        }
+        let consumers: StreamInfoConsumersReply = conn
+            .xinfo_consumers(stream, &group.name)
+            .await
+            .with_context(|| format!("Failed to list consumers of {} on {}", group.name, stream))?;
+        let idle: Vec<usize> = consumers.consumers.iter().map(|consumer| consumer.idle).collect();
+        if is_stale(&idle) {
+            destroy_group(conn, stream, &group.name).await?;
+            reaped.push(group.name);
+        }
+    }
+    Ok(reaped)
+}
+
+/// Another replica's group under the same prefix.
+fn is_sibling_group(group: &str, prefix: &str, own: &str) -> bool {
+    group != own && group.strip_prefix(prefix).is_some_and(|rest| rest.starts_with(':'))
+}
+
+/// A group with no consumer that read recently, or none at all.
+fn is_stale(consumer_idle_ms: &[usize]) -> bool {
+    consumer_idle_ms.iter().all(|idle| *idle >= STALE_GROUP_IDLE_MS)
+}
+
+/// Reads the next batch for this replica's consumer. `cursor` is `"0"` for
+/// entries delivered earlier but never acknowledged, `">"` for new ones.
+pub async fn read_group(
+    conn: &mut MultiplexedConnection,
+    stream: &str,
+    group: &str,
+    cursor: &str,
+) -> Result<Vec<StreamId>> {
+    let options = StreamReadOptions::default()
+        .group(group, CONSUMER)
+        .count(READ_COUNT)
+        .block(READ_BLOCK_MS);
+
+    let reply: Option<StreamReadReply> = conn
+        .xread_options(&[stream], &[cursor], &options)
+        .await
+        .with_context(|| format!("Failed to read {}", stream))?;
+
+    Ok(reply
+        .into_iter()
+        .flat_map(|reply| reply.keys)
+        .flat_map(|key| key.ids)
+        .collect())
+}
+
+pub async fn ack(conn: &mut MultiplexedConnection, stream: &str, group: &str, ids: &[String]) -> Result<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    conn.xack::<_, _, _, ()>(stream, group, ids)
+        .await
+        .with_context(|| format!("Failed to acknowledge entries on {}", stream))
+}
+
+/// Removes a group whose reader has stopped.
+pub async fn destroy_group(conn: &mut MultiplexedConnection, stream: &str, group: &str) -> Result<()> {
+    conn.xgroup_destroy::<_, _, ()>(stream, group)
+        .await
+        .with_context(|| format!("Failed to remove consumer group {} on {}", group, stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_stream_mapping() {
+        assert_eq!(stream_for("pump:trades"), TRADES_STREAM);
+        assert_eq!(stream_for("pump:trades:Mint"), TRADES_STREAM);
+        assert_eq!(stream_for("pump:tokens:new"), TOKENS_STREAM);
+        assert_eq!(channel_for(stream_for("pump:completions")), "pump:completions");
+        assert_eq!(channel_for(stream_for("pump:tracked")), "pump:tracked");
+        assert_eq!(channel_for(stream_for("pump:koth")), "pump:koth");
+    }
+
+    #[test]
+    fn test_replica_group_survives_restarts() {
+        let group = replica_group("pump-api");
+        assert!(group.starts_with("pump-api:"));
+        assert_eq!(group, replica_group("pump-api"));
+    }
+
+    #[test]
+    fn test_stale_groups() {
+        let own = "pump-api:web-1";
+        assert!(is_sibling_group("pump-api:web-2", "pump-api", own));
+        // Groups named by older versions, with a pid.
+        assert!(is_sibling_group("pump-api:web-1:4242", "pump-api", own));
+        assert!(!is_sibling_group(own, "pump-api", own));
+        assert!(!is_sibling_group("pump-api-staging:web-1", "pump-api", own));
+        assert!(!is_sibling_group("analytics", "pump-api", own));
+
+        assert!(is_stale(&[]));
+        assert!(is_stale(&[STALE_GROUP_IDLE_MS]));
+        assert!(!is_stale(&[STALE_GROUP_IDLE_MS, 1_000]));
+    }
+}
@@ -1,3 +1,10 @@
 pub mod redis_client;
+pub mod redis_health;
+pub mod response_cache;
+pub mod pubsub_hub;
+pub mod replay_buffer;
+pub mod event_streams;
 
-pub use redis_client::{RedisClient, create_redis_client};
\ No newline at end of file
+pub use redis_client::{RedisClient, create_redis_client};
+pub use pubsub_hub::PubSubHub;
+pub use redis_health::{RedisHealth, start_redis_health_monitor};
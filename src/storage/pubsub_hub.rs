@@ -0,0 +1,495 @@
+use futures::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, watch};
+use tokio::time::{interval, sleep, Duration, Instant};
+use tracing::{debug, error, info, warn};
+use crate::config::EventTransport;
+use super::event_streams;
+
+/// Messages buffered per channel before slow receivers start lagging.
+const CHANNEL_CAPACITY: usize = 1024;
+/// How often an idle subscriber checks whether anyone is still listening.
+const IDLE_CHECK: Duration = Duration::from_secs(30);
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+type Channels = Arc<Mutex<HashMap<String, broadcast::Sender<Arc<str>>>>>;
+
+/// Shares one Redis subscription per channel across every API client.
+///
+/// The first client to ask for a channel starts a subscriber task that
+/// forwards payloads into a broadcast channel; later clients just attach a
+/// receiver. The task shuts down once the last receiver is gone.
+///
+/// With Redis Streams or Postgres `LISTEN/NOTIFY` configured, one reader
+/// per base channel (`pump:trades`, `pump:tokens:new`, `pump:completions`)
+/// feeds every channel derived from it, routing trades to per-mint channels
+/// itself.
+///
+/// Redis-backed readers also resubscribe whenever the health monitor sees
+/// Redis come back, since a connection that went half-open during an
+/// outage can stay silent without ever ending.
+pub struct PubSubHub {
+    source: Source,
+    channels: Channels,
+    reconnects: Option<watch::Receiver<u64>>,
+}
+
+enum Source {
+    PubSub { redis_url: String },
+    /// Entries are acknowledged to `group`, this replica's own consumer
+    /// group, once broadcast. `prefix` is shared by every replica's group.
+    Streams { redis_url: String, prefix: String, group: String },
+    Postgres { pool: PgPool },
+}
+
+impl PubSubHub {
+    pub fn new(redis_url: &str, transport: &EventTransport, pool: &PgPool) -> Self {
+        let source = match transport {
+            EventTransport::PubSub => Source::PubSub { redis_url: redis_url.to_string() },
+            EventTransport::Streams(config) => Source::Streams {
+                redis_url: redis_url.to_string(),
+                prefix: config.consumer_group.clone(),
+                group: event_streams::replica_group(&config.consumer_group),
+            },
+            EventTransport::Postgres => Source::Postgres { pool: pool.clone() },
+        };
+
+        Self {
+            source,
+            channels: Arc::new(Mutex::new(HashMap::new())),
+            reconnects: None,
+        }
+    }
+
+    /// Resubscribe Redis readers each time `reconnects` changes; see
+    /// `RedisHealth::reconnects`.
+    pub fn with_reconnect_signal(mut self, reconnects: watch::Receiver<u64>) -> Self {
+        self.reconnects = Some(reconnects);
+        self
+    }
+
+    pub fn subscribe(&self, channel: &str) -> broadcast::Receiver<Arc<str>> {
+        let mut channels = self.channels.lock().unwrap();
+
+        if let Some(tx) = channels.get(channel) {
+            return tx.subscribe();
+        }
+
+        let (tx, rx) = broadcast::channel(CHANNEL_CAPACITY);
+
+        // Shared readers run exactly while some channel derived from their
+        // base channel is registered.
+        let base = base_channel(channel);
+        let reader_running = channels.keys().any(|other| base_channel(other) == base);
+
+        match &self.source {
+            Source::PubSub { redis_url } => {
+                tokio::spawn(run_subscriber(
+                    redis_url.clone(),
+                    channel.to_string(),
+                    tx.clone(),
+                    self.channels.clone(),
+                    self.reconnects.clone(),
+                ));
+            }
+            Source::Streams { redis_url, prefix, group } if !reader_running => {
+                tokio::spawn(run_stream_reader(
+                    redis_url.clone(),
+                    event_streams::stream_for(channel),
+                    prefix.clone(),
+                    group.clone(),
+                    self.channels.clone(),
+                    self.reconnects.clone(),
+                ));
+            }
+            Source::Postgres { pool } if !reader_running => {
+                tokio::spawn(run_notify_listener(pool.clone(), base.to_string(), self.channels.clone()));
+            }
+            _ => {}
+        }
+
+        channels.insert(channel.to_string(), tx);
+        rx
+    }
+
+    /// Like `subscribe`, as a stream. Messages a lagging receiver missed are
+    /// skipped rather than ending the stream.
+    pub fn subscribe_stream(&self, channel: &str) -> impl Stream<Item = Arc<str>> + use<> {
+        stream::unfold(self.subscribe(channel), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(payload) => return Some((payload, rx)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Receiver lagged, skipped {} messages", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Channels with a live subscriber task.
+    pub fn active_channels(&self) -> usize {
+        self.channels.lock().unwrap().len()
+    }
+
+    /// Connected receivers (WebSocket subscriptions and SSE clients) per channel.
+    pub fn subscriber_counts(&self) -> Vec<(String, usize)> {
+        self.channels
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(channel, tx)| (channel.clone(), tx.receiver_count()))
+            .collect()
+    }
+}
+
+/// Removes the channel if nobody is listening. Done under the map lock so a
+/// concurrent `subscribe` either sees the entry gone or keeps it alive.
+fn release_if_idle(channels: &Channels, channel: &str, tx: &broadcast::Sender<Arc<str>>) -> bool {
+    let mut channels = channels.lock().unwrap();
+    if tx.receiver_count() == 0 {
+        channels.remove(channel);
+        true
+    } else {
+        false
+    }
+}
+
+/// The channel a shared reader listens on for `channel`: per-mint trade
+/// channels derive from `pump:trades`.
+fn base_channel(channel: &str) -> &str {
+    if channel.starts_with("pump:trades:") { "pump:trades" } else { channel }
+}
+
+/// Drops every channel derived from `base` that has no receivers left.
+/// Returns true once none remain, meaning the reader should stop.
+fn release_idle_source(channels: &Channels, base: &str) -> bool {
+    let mut channels = channels.lock().unwrap();
+    channels.retain(|channel, tx| base_channel(channel) != base || tx.receiver_count() > 0);
+    !channels.keys().any(|channel| base_channel(channel) == base)
+}
+
+/// Broadcasts a payload on its base channel and, for trades, on the
+/// token's own channel.
+fn dispatch(channels: &Channels, base: &str, mint: Option<&str>, payload: Arc<str>) {
+    let channels = channels.lock().unwrap();
+    if let Some(tx) = channels.get(base) {
+        let _ = tx.send(payload.clone());
+    }
+    if base == "pump:trades"
+        && let Some(mint) = mint
+        && let Some(tx) = channels.get(&format!("pump:trades:{}", mint))
+    {
+        let _ = tx.send(payload);
+    }
+}
+
+/// Every live message carries its token's mint.
+#[derive(Deserialize)]
+struct Routed {
+    mint: String,
+}
+
+async fn run_notify_listener(pool: PgPool, channel: String, channels: Channels) {
+    info!("✅ Shared Postgres listener started: {}", channel);
+
+    let mut idle_check = interval(IDLE_CHECK);
+
+    'connect: loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to open Postgres listener for {}: {}", channel, e);
+                if release_idle_source(&channels, &channel) {
+                    break 'connect;
+                }
+                sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = listener.listen(&channel).await {
+            error!("Failed to LISTEN on {}: {}", channel, e);
+            if release_idle_source(&channels, &channel) {
+                break 'connect;
+            }
+            sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        loop {
+            tokio::select! {
+                notification = listener.recv() => {
+                    let notification = match notification {
+                        Ok(notification) => notification,
+                        Err(e) => {
+                            warn!("Postgres listener for {} failed, reconnecting: {}", channel, e);
+                            break;
+                        }
+                    };
+
+                    let payload = notification.payload();
+                    let mint = serde_json::from_str::<Routed>(payload).ok().map(|routed| routed.mint);
+                    dispatch(&channels, &channel, mint.as_deref(), Arc::from(payload));
+                }
+
+                _ = idle_check.tick() => {
+                    if release_idle_source(&channels, &channel) {
+                        break 'connect;
+                    }
+                }
+            }
+        }
+
+        sleep(RECONNECT_DELAY).await;
+    }
+
+    info!("Shared Postgres listener stopped: {} (no clients)", channel);
+}
+
+/// Forgets reconnects that happened before the current connection.
+fn mark_reconnect_seen(reconnects: &mut Option<watch::Receiver<u64>>) {
+    if let Some(rx) = reconnects {
+        rx.borrow_and_update();
+    }
+}
+
+fn reconnect_signalled(reconnects: &mut Option<watch::Receiver<u64>>) -> bool {
+    reconnects.as_mut().is_some_and(|rx| rx.has_changed().unwrap_or(false))
+}
+
+/// Resolves on the next reconnect; never without a signal (or once the
+/// monitor is gone).
+async fn reconnected(reconnects: &mut Option<watch::Receiver<u64>>) {
+    if let Some(rx) = reconnects
+        && rx.changed().await.is_ok()
+    {
+        return;
+    }
+    std::future::pending().await
+}
+
+async fn run_stream_reader(
+    redis_url: String,
+    stream: &'static str,
+    prefix: String,
+    group: String,
+    channels: Channels,
+    mut reconnects: Option<watch::Receiver<u64>>,
+) {
+    info!("✅ Shared stream reader started: {} (group {})", stream, group);
+
+    let base = event_streams::channel_for(stream);
+    let mut reaped = false;
+
+    'connect: loop {
+        let connection = match redis::Client::open(redis_url.as_str()) {
+            Ok(client) => client.get_multiplexed_async_connection().await,
+            Err(e) => Err(e),
+        };
+
+        let mut connection = match connection {
+            Ok(connection) => connection,
+            Err(e) => {
+                error!("Failed to connect to Redis for {}: {}", stream, e);
+                if release_idle_source(&channels, base) {
+                    break 'connect;
+                }
+                sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = event_streams::ensure_group(&mut connection, stream, &group).await {
+            error!("{:#}", e);
+            sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        if !reaped {
+            match event_streams::reap_stale_groups(&mut connection, stream, &prefix, &group).await {
+                Ok(groups) if !groups.is_empty() => info!("🧹 Removed stale consumer groups on {}: {}", stream, groups.join(", ")),
+                Ok(_) => {}
+                Err(e) => warn!("{:#}", e),
+            }
+            reaped = true;
+        }
+
+        // Entries delivered before a restart or disconnect but never
+        // acknowledged are re-read first, then the group carries on from
+        // its last-delivered ID.
+        let mut cursor = "0";
+        let mut last_idle_check = Instant::now();
+        mark_reconnect_seen(&mut reconnects);
+
+        loop {
+            if reconnect_signalled(&mut reconnects) {
+                info!("Redis reconnected, reopening stream reader for {}", stream);
+                continue 'connect;
+            }
+
+            if last_idle_check.elapsed() >= IDLE_CHECK {
+                if release_idle_source(&channels, base) {
+                    // Nothing else reads this replica's group, and with no
+                    // clients there is nothing to resume for.
+                    if let Err(e) = event_streams::destroy_group(&mut connection, stream, &group).await {
+                        warn!("{:#}", e);
+                    }
+                    break 'connect;
+                }
+                last_idle_check = Instant::now();
+            }
+
+            let entries = match event_streams::read_group(&mut connection, stream, &group, cursor).await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("{:#}, reconnecting", e);
+                    break;
+                }
+            };
+
+            if entries.is_empty() {
+                cursor = ">";
+                continue;
+            }
+
+            for entry in &entries {
+                let Some(payload) = entry.get::<String>("payload") else {
+                    warn!("Skipping stream entry {} without payload", entry.id);
+                    continue;
+                };
+                let mint = entry.get::<String>("mint");
+                dispatch(&channels, base, mint.as_deref(), Arc::from(payload));
+            }
+
+            let ids: Vec<String> = entries.into_iter().map(|entry| entry.id).collect();
+            if let Err(e) = event_streams::ack(&mut connection, stream, &group, &ids).await {
+                warn!("{:#}", e);
+            }
+        }
+
+        sleep(RECONNECT_DELAY).await;
+    }
+
+    info!("Shared stream reader stopped: {} (no clients)", stream);
+}
+
+async fn run_subscriber(
+    redis_url: String,
+    channel: String,
+    tx: broadcast::Sender<Arc<str>>,
+    channels: Channels,
+    mut reconnects: Option<watch::Receiver<u64>>,
+) {
+    info!("✅ Shared subscriber started: {}", channel);
+
+    let mut idle_check = interval(IDLE_CHECK);
+
+    'connect: loop {
+        let pubsub = match redis::Client::open(redis_url.as_str()) {
+            Ok(client) => client.get_async_pubsub().await,
+            Err(e) => Err(e),
+        };
+
+        let mut pubsub = match pubsub {
+            Ok(pubsub) => pubsub,
+            Err(e) => {
+                error!("Failed to get pubsub connection for {}: {}", channel, e);
+                if release_if_idle(&channels, &channel, &tx) {
+                    break 'connect;
+                }
+                sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        if let Err(e) = pubsub.subscribe(&channel).await {
+            error!("Failed to subscribe to Redis channel {}: {}", channel, e);
+            if release_if_idle(&channels, &channel, &tx) {
+                break 'connect;
+            }
+            sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        let mut messages = pubsub.on_message();
+        mark_reconnect_seen(&mut reconnects);
+
+        loop {
+            tokio::select! {
+                msg = messages.next() => {
+                    let Some(msg) = msg else {
+                        warn!("Redis pubsub stream ended for {}, reconnecting", channel);
+                        break;
+                    };
+
+                    if let Ok(payload) = msg.get_payload::<String>() {
+                        // Only errors when there are no receivers; the idle
+                        // check below handles that case.
+                        let _ = tx.send(Arc::from(payload));
+                    }
+                }
+
+                _ = idle_check.tick() => {
+                    if release_if_idle(&channels, &channel, &tx) {
+                        break 'connect;
+                    }
+                }
+
+                _ = reconnected(&mut reconnects) => {
+                    info!("Redis reconnected, resubscribing to {}", channel);
+                    continue 'connect;
+                }
+            }
+        }
+
+        sleep(RECONNECT_DELAY).await;
+    }
+
+    info!("Shared subscriber stopped: {} (no clients)", channel);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_only_when_no_receivers() {
+        let channels: Channels = Arc::new(Mutex::new(HashMap::new()));
+        let (tx, rx) = broadcast::channel(4);
+        channels.lock().unwrap().insert("pump:trades".to_string(), tx.clone());
+
+        assert!(!release_if_idle(&channels, "pump:trades", &tx));
+        assert_eq!(channels.lock().unwrap().len(), 1);
+
+        drop(rx);
+        assert!(release_if_idle(&channels, "pump:trades", &tx));
+        assert!(channels.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_release_idle_source_keeps_other_channels() {
+        let channels: Channels = Arc::new(Mutex::new(HashMap::new()));
+        let (trades, _) = broadcast::channel(4);
+        let (mint, mint_rx) = broadcast::channel(4);
+        let (tokens, _) = broadcast::channel(4);
+        {
+            let mut channels = channels.lock().unwrap();
+            channels.insert("pump:trades".to_string(), trades);
+            channels.insert("pump:trades:Mint".to_string(), mint);
+            channels.insert("pump:tokens:new".to_string(), tokens);
+        }
+
+        assert!(!release_idle_source(&channels, "pump:trades"));
+        assert_eq!(channels.lock().unwrap().len(), 2);
+
+        drop(mint_rx);
+        assert!(release_idle_source(&channels, "pump:trades"));
+        assert!(channels.lock().unwrap().contains_key("pump:tokens:new"));
+    }
+}
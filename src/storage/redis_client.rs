@@ -2,11 +2,13 @@ use redis::aio::ConnectionManager;
 use redis::{Client, AsyncCommands, RedisResult};
 use anyhow::{Result, Context};
 use serde_json;
-use tracing::{info, error, warn};
+use tracing::{info, warn};
+use super::redis_health::RedisHealth;
 
 #[derive(Clone)]
 pub struct RedisClient {
     pub connection: ConnectionManager,
+    pub health: RedisHealth,
 }
 
 impl RedisClient {
@@ -22,7 +24,7 @@ impl RedisClient {
 
         info!("  Redis connected successfully");
 
-        Ok(Self { connection })
+        Ok(Self { connection, health: RedisHealth::new() })
     }
 
     pub async fn publish<T: serde::Serialize>(
@@ -33,14 +35,20 @@ impl RedisClient {
         let json = serde_json::to_string(message)
             .context("Failed to serialize message")?;
 
-        match self.connection.publish::<_, _, ()>(channel, json.clone()).await {
+        self.publish_raw(channel, &json).await
+    }
+
+    /// Publishes an already serialized message.
+    pub async fn publish_raw(&mut self, channel: &str, json: &str) -> Result<()> {
+        match self.connection.publish::<_, _, ()>(channel, json).await {
             Ok(_) => Ok(()),
             Err(e) => {
                 warn!("  Redis publish error: {}", e);
                 
                 if e.is_connection_dropped() || e.is_io_error() {
-                    warn!("  Redis connection lost, attempting reconnect...");
-                
+                    // The health monitor reconnects and replays what is
+                    // deferred in the meantime.
+                    self.health.mark_down();
                 }
                 
                 Err(e.into())
@@ -121,12 +129,12 @@ pub async fn create_redis_client(redis_url: &str) -> Result<RedisClient> {
 }
 
 fn mask_redis_url(url: &str) -> String {
-    if let Some(at_pos) = url.rfind('@') {
-        if let Some(colon_pos) = url[..at_pos].rfind(':') {
-            let mut masked = url.to_string();
-            masked.replace_range(colon_pos + 1..at_pos, "****");
-            return masked;
-        }
+    if let Some(at_pos) = url.rfind('@')
+        && let Some(colon_pos) = url[..at_pos].rfind(':')
+    {
+        let mut masked = url.to_string();
+        masked.replace_range(colon_pos + 1..at_pos, "****");
+        return masked;
     }
     url.to_string()
 }
@@ -0,0 +1,275 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use anyhow::Result;
+use tokio::sync::watch;
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+use crate::config::StreamsConfig;
+use crate::processor::metrics::Metrics;
+use super::{event_streams, RedisClient};
+
+/// Publishes kept while Redis is unreachable; older ones are dropped first.
+pub const PENDING_LIMIT: usize = 1_000;
+/// Times a publish that failed with a transient error is tried before it
+/// is dropped.
+pub const MAX_PUBLISH_ATTEMPTS: u32 = 5;
+const CHECK_INTERVAL_SECS: u64 = 5;
+
+/// A live event that couldn't be delivered, in the form the Redis sink
+/// sends it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingPublish {
+    pub channel: String,
+    pub mint: String,
+    pub payload: String,
+    /// Failed attempts so far, not counting ones made while Redis was down.
+    pub attempts: u32,
+}
+
+impl PendingPublish {
+    pub fn new(channel: &str, mint: &str, payload: String) -> Self {
+        Self { channel: channel.to_string(), mint: mint.to_string(), payload, attempts: 0 }
+    }
+}
+
+/// Whether Redis is reachable, shared by every clone of a [`RedisClient`].
+///
+/// While it is down the Redis sink defers publishes here instead of
+/// failing each one; [`start_redis_health_monitor`] replays them in order
+/// once Redis answers again and then signals [`RedisHealth::reconnects`],
+/// which makes the API's shared subscribers resubscribe. Publishes that
+/// hit a transient error while Redis is up wait in the same queue for the
+/// monitor's next pass.
+#[derive(Clone)]
+pub struct RedisHealth {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    healthy: AtomicBool,
+    pending: Mutex<VecDeque<PendingPublish>>,
+    dropped: AtomicU64,
+    reconnects: watch::Sender<u64>,
+}
+
+impl Default for RedisHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RedisHealth {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                healthy: AtomicBool::new(true),
+                pending: Mutex::new(VecDeque::new()),
+                dropped: AtomicU64::new(0),
+                reconnects: watch::Sender::new(0),
+            }),
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.inner.healthy.load(Ordering::Relaxed)
+    }
+
+    pub fn mark_down(&self) {
+        if self.inner.healthy.swap(false, Ordering::Relaxed) {
+            warn!("🔌 Redis unreachable, deferring live publishes (up to {})", PENDING_LIMIT);
+        }
+    }
+
+    /// Queues the publish if Redis is down, returning false (and keeping
+    /// nothing) when it is up and the caller should publish itself.
+    pub fn defer_if_down(&self, publish: PendingPublish) -> bool {
+        let mut pending = self.inner.pending.lock().unwrap();
+        if self.is_healthy() {
+            return false;
+        }
+        self.push(&mut pending, publish);
+        true
+    }
+
+    /// Queues a publish that just failed for another try, unless it has
+    /// used up its attempts.
+    pub fn retry_later(&self, mut publish: PendingPublish) {
+        publish.attempts += 1;
+        if publish.attempts >= MAX_PUBLISH_ATTEMPTS {
+            warn!("Dropping publish to {} after {} attempts", publish.channel, publish.attempts);
+            self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let mut pending = self.inner.pending.lock().unwrap();
+        self.push(&mut pending, publish);
+    }
+
+    fn push(&self, pending: &mut VecDeque<PendingPublish>, publish: PendingPublish) {
+        if pending.len() == PENDING_LIMIT {
+            pending.pop_front();
+            self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        pending.push_back(publish);
+    }
+
+    pub fn pending(&self) -> usize {
+        self.inner.pending.lock().unwrap().len()
+    }
+
+    /// Ticks every time Redis comes back after an outage.
+    pub fn reconnects(&self) -> watch::Receiver<u64> {
+        self.inner.reconnects.subscribe()
+    }
+
+    /// Publishes given up on since the last call.
+    pub fn take_dropped(&self) -> u64 {
+        self.inner.dropped.swap(0, Ordering::Relaxed)
+    }
+
+    fn next_pending(&self) -> Option<PendingPublish> {
+        self.inner.pending.lock().unwrap().pop_front()
+    }
+
+    fn requeue(&self, publish: PendingPublish) {
+        self.inner.pending.lock().unwrap().push_front(publish);
+    }
+
+    /// Marks Redis healthy again, returning whether it was down.
+    fn mark_up(&self) -> bool {
+        !self.inner.healthy.swap(true, Ordering::Relaxed)
+    }
+}
+
+/// Publishes a serialized event on `channel`, or appends it to the stream
+/// backing the channel when streams are configured.
+pub async fn publish_payload(
+    redis: &mut RedisClient,
+    streams: Option<&StreamsConfig>,
+    publish: &PendingPublish,
+) -> Result<()> {
+    match streams {
+        None => redis.publish_raw(&publish.channel, &publish.payload).await,
+        Some(config) => {
+            let stream = event_streams::stream_for(&publish.channel);
+            event_streams::append(redis, config, stream, &publish.mint, &publish.payload).await
+        }
+    }
+}
+
+/// Whether a failed Redis call means the connection is gone, as opposed to
+/// a problem with the command itself.
+pub fn is_connection_error(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<redis::RedisError>()
+        .is_some_and(|e| e.is_io_error() || e.is_connection_dropped() || e.is_connection_refusal() || e.is_timeout())
+}
+
+/// Whether the same call could succeed if tried again, e.g. while Redis is
+/// still loading its dataset after a restart.
+pub fn is_transient_error(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<redis::RedisError>()
+        .is_some_and(|e| !matches!(e.retry_method(), redis::RetryMethod::NoRetry))
+}
+
+/// Pings Redis every few seconds. A failed ping marks it down; the
+/// `ConnectionManager` reconnects on the next command, so pinging is also
+/// what brings the connection back. Once a ping succeeds the queued
+/// publishes are replayed, oldest first, before live ones go out directly
+/// again.
+pub async fn start_redis_health_monitor(redis: RedisClient, streams: Option<StreamsConfig>, metrics: Metrics) {
+    let mut interval = interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+    let health = redis.health.clone();
+
+    info!("🩺 Starting Redis health monitor ({}s interval)", CHECK_INTERVAL_SECS);
+
+    loop {
+        interval.tick().await;
+        report(&health, &metrics);
+
+        let mut client = redis.clone();
+        if let Err(e) = client.ping().await {
+            if health.is_healthy() {
+                warn!("Redis health check failed: {:#}", e);
+            }
+            health.mark_down();
+            continue;
+        }
+
+        // Only what is queued now; a publish that fails again goes to the
+        // back and waits for the next pass.
+        let mut replayed = 0;
+        let mut reachable = true;
+        for _ in 0..health.pending() {
+            let Some(publish) = health.next_pending() else { break };
+            match publish_payload(&mut client, streams.as_ref(), &publish).await {
+                Ok(()) => replayed += 1,
+                Err(e) if is_connection_error(&e) => {
+                    warn!("Replaying publish on {} failed: {:#}", publish.channel, e);
+                    health.requeue(publish);
+                    reachable = false;
+                    break;
+                }
+                Err(e) => {
+                    metrics.increment_redis_errors();
+                    warn!("Retrying publish on {} failed: {:#}", publish.channel, e);
+                    health.retry_later(publish);
+                }
+            }
+        }
+
+        if reachable && health.mark_up() {
+            info!("🔌 Redis reachable again: replayed {} deferred publishes", replayed);
+            health.inner.reconnects.send_modify(|epoch| *epoch += 1);
+        } else if replayed > 0 {
+            info!("🔁 Retried {} Redis publishes", replayed);
+        }
+        report(&health, &metrics);
+    }
+}
+
+fn report(health: &RedisHealth, metrics: &Metrics) {
+    metrics.redis_pending_publishes.store(health.pending() as i64, Ordering::Relaxed);
+    metrics.redis_publishes_dropped.fetch_add(health.take_dropped(), Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn publish(n: usize) -> PendingPublish {
+        PendingPublish::new("pump:trades", "mint", n.to_string())
+    }
+
+    #[test]
+    fn test_defers_only_while_down() {
+        let health = RedisHealth::new();
+        assert!(!health.defer_if_down(publish(0)));
+
+        health.mark_down();
+        for n in 0..PENDING_LIMIT + 2 {
+            assert!(health.defer_if_down(publish(n)));
+        }
+        assert_eq!(health.pending(), PENDING_LIMIT);
+        assert_eq!(health.take_dropped(), 2);
+        assert_eq!(health.next_pending(), Some(publish(2)));
+
+        assert!(health.mark_up());
+        assert!(!health.defer_if_down(publish(0)));
+    }
+
+    #[test]
+    fn test_retry_later_gives_up() {
+        let health = RedisHealth::new();
+        let mut failed = publish(0);
+        for attempt in 1..MAX_PUBLISH_ATTEMPTS {
+            health.retry_later(failed);
+            failed = health.next_pending().unwrap();
+            assert_eq!(failed.attempts, attempt);
+        }
+        health.retry_later(failed);
+        assert_eq!(health.pending(), 0);
+        assert_eq!(health.take_dropped(), 1);
+    }
+}
@@ -0,0 +1,59 @@
+use anyhow::{Context, Result};
+use redis::AsyncCommands;
+use super::RedisClient;
+
+const SEQ_KEY: &str = "pump:trades:seq";
+const BUFFER_KEY: &str = "pump:trades:replay";
+/// Most recent trade messages kept for reconnecting clients.
+pub const BUFFER_SIZE: isize = 5_000;
+
+/// Allocates the next trade sequence number. Sequence numbers are global
+/// across all mints and only ever increase.
+pub async fn next_seq(redis: &mut RedisClient) -> Result<u64> {
+    let seq: i64 = redis.increment(SEQ_KEY).await?;
+    Ok(seq as u64)
+}
+
+/// Appends a published trade to the replay buffer, trimming the oldest
+/// entries beyond `BUFFER_SIZE`.
+pub async fn record(redis: &mut RedisClient, seq: u64, payload: &str) -> Result<()> {
+    redis::pipe()
+        .atomic()
+        .zadd(BUFFER_KEY, payload, seq)
+        .ignore()
+        .zremrangebyrank(BUFFER_KEY, 0, -(BUFFER_SIZE + 1))
+        .ignore()
+        .query_async::<()>(&mut redis.connection)
+        .await
+        .context("Failed to record trade for replay")
+}
+
+/// Buffered trades with a sequence number greater than `seq`, oldest first.
+pub async fn since(redis: &mut RedisClient, seq: u64) -> Result<Vec<(u64, String)>> {
+    let entries: Vec<(String, u64)> = redis
+        .connection
+        .zrangebyscore_withscores(BUFFER_KEY, format!("({}", seq), "+inf")
+        .await
+        .context("Failed to read replay buffer")?;
+
+    Ok(entries.into_iter().map(|(payload, seq)| (seq, payload)).collect())
+}
+
+/// Whether replaying from `since_seq` skips trades that already fell out of
+/// the buffer.
+pub fn has_gap(since_seq: u64, replayed: &[(u64, String)]) -> bool {
+    replayed.first().is_some_and(|(first, _)| *first > since_seq + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_gap() {
+        let entries = vec![(11, String::new()), (12, String::new())];
+        assert!(!has_gap(10, &entries));
+        assert!(has_gap(5, &entries));
+        assert!(!has_gap(12, &[]));
+    }
+}
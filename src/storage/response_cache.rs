@@ -0,0 +1,120 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::atomic::Ordering;
+use tracing::warn;
+use crate::processor::metrics::Metrics;
+use super::RedisClient;
+
+/// Groups of cached API responses that are invalidated together. Each scope
+/// has a generation counter baked into its keys; bumping it orphans every
+/// cached entry in the scope, which then ages out on its TTL.
+#[derive(Debug, Clone, Copy)]
+pub enum CacheScope {
+    /// `/api/tokens` listings. Invalidated on token creation and graduation.
+    Tokens,
+    /// `/api/stats`. Every trade changes it, so it relies on the TTL alone.
+    Stats,
+    /// `/api/tokens/{mint}` for tokens outside live state, keyed by mint.
+    /// Each entry is dropped on a trade or graduation of its token.
+    TokenDetail,
+}
+
+impl CacheScope {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Tokens => "tokens",
+            Self::Stats => "stats",
+            Self::TokenDetail => "token",
+        }
+    }
+
+    pub fn ttl_secs(&self) -> usize {
+        match self {
+            Self::Tokens => 5,
+            Self::Stats => 5,
+            Self::TokenDetail => 60,
+        }
+    }
+
+    fn generation_key(&self) -> String {
+        format!("pump:cache:{}:generation", self.name())
+    }
+
+    fn entry_key(&self, generation: i64, key: &str) -> String {
+        format!("pump:cache:{}:{}:{}", self.name(), generation, key)
+    }
+}
+
+/// Returns the cached response for `key` or computes and stores it. Redis
+/// failures are logged and fall through to `compute`, so the cache can only
+/// make a request faster, never fail it. Without Redis nothing is cached.
+pub async fn get_or_compute<T, E, F, Fut>(
+    redis: Option<&RedisClient>,
+    metrics: &Metrics,
+    scope: CacheScope,
+    key: &str,
+    compute: F,
+) -> Result<T, E>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    let Some(redis) = redis else {
+        return compute().await;
+    };
+    let mut redis = redis.clone();
+
+    let generation = match redis.get::<i64>(&scope.generation_key()).await {
+        Ok(generation) => generation.unwrap_or(0),
+        Err(e) => {
+            warn!("Response cache unavailable: {}", e);
+            return compute().await;
+        }
+    };
+
+    let cache_key = scope.entry_key(generation, key);
+
+    match redis.get::<T>(&cache_key).await {
+        Ok(Some(value)) => {
+            metrics.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value);
+        }
+        Ok(None) => {
+            metrics.cache_misses.fetch_add(1, Ordering::Relaxed);
+        }
+        Err(e) => warn!("Response cache read failed ({}): {}", cache_key, e),
+    }
+
+    let value = compute().await?;
+
+    if let Err(e) = redis.set(&cache_key, &value, Some(scope.ttl_secs())).await {
+        warn!("Response cache write failed ({}): {}", cache_key, e);
+    }
+
+    Ok(value)
+}
+
+pub async fn invalidate(redis: Option<&RedisClient>, scope: CacheScope) {
+    let Some(redis) = redis else {
+        return;
+    };
+    if let Err(e) = redis.clone().increment(&scope.generation_key()).await {
+        warn!("Failed to invalidate {} response cache: {}", scope.name(), e);
+    }
+}
+
+/// Drops the cached response for one `key` in `scope`.
+pub async fn invalidate_key(redis: Option<&RedisClient>, scope: CacheScope, key: &str) {
+    let Some(redis) = redis else {
+        return;
+    };
+    let mut redis = redis.clone();
+
+    let result = match redis.get::<i64>(&scope.generation_key()).await {
+        Ok(generation) => redis.delete(&scope.entry_key(generation.unwrap_or(0), key)).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        warn!("Failed to invalidate {} response cache for {}: {}", scope.name(), key, e);
+    }
+}